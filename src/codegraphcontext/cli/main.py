@@ -7,6 +7,23 @@ Commands:
 - setup: Runs an interactive wizard to configure the Neo4j database connection.
 - start: Launches the main MCP server.
 - tool: A placeholder for directly calling server tools (for debugging).
+- dead-code: Reports unreachable Rust code in the indexed graph, optionally as JSON for CI.
+- health: Lists functions exceeding size, parameter, nesting and complexity limits.
+- diff: Compares the graphs of two git revisions of a repository.
+- export: Exports the subgraph rooted at a function, module or crate, or the whole graph, as JSON, a Mermaid diagram, GraphML or DOT.
+- semver: Classifies the API changes between two git revisions as major, minor or patch.
+- hotspots: Ranks functions or files by git churn times complexity.
+- check-layers: Checks the module dependencies against the layering rules in `.cgc-layers.json`.
+- dsm: Prints the module dependency structure matrix of a crate as JSON or CSV.
+- bulk-export: Writes a repository's graph as CSV files for `neo4j-admin database import`, offline.
+- bulk-link: Links a repository loaded from a bulk export, once the database is back up.
+- dump: Writes the graph, or one repository's part of it, as JSON Lines with stable keys.
+- load: Loads a graph written by `dump`.
+- scip: Writes a SCIP index of a repository's definitions, references and implementations.
+- lsif: Writes an LSIF dump of a repository's definitions, references, hovers and implementations.
+- ignore: Dry-runs the `.cgcignore` and CGC_IGNORE rules of a repository, listing what is indexed.
+- bench: Benchmarks parsing, resolution and writing over the sample projects against a stored baseline.
+- doc-coverage: Reports the share of public Rust items with doc comments, with a CI threshold.
 - help: Displays help information.
 - version: Show the installed version.
 """
@@ -18,9 +35,16 @@ import logging
 import json
 import os
 from pathlib import Path
+from typing import List, Optional
 from dotenv import load_dotenv, find_dotenv
 from importlib.metadata import version as pkg_version, PackageNotFoundError
 from codegraphcontext.server import MCPServer
+from codegraphcontext.tools import benchmarks, lsif, scip
+from codegraphcontext.tools.bulk_import import export_repository
+from codegraphcontext.tools.dsm import render as render_dsm
+from codegraphcontext.tools.graph_dump import load_graph, read_dump, write_dump
+from codegraphcontext.tools.graph_export import module_graph, render as render_subgraph
+from codegraphcontext.tools.ignore import IgnoreRules
 from .setup_wizard import run_setup_wizard
 
 # Set the log level for the noisy neo4j logger to WARNING to keep the output clean.
@@ -174,6 +198,602 @@ def list_tools():
         console.print(f"[bold red]An unexpected error occurred:[/bold red] {e}")
 
 
+@app.command("dead-code")
+def dead_code(
+    json_output: bool = typer.Option(False, "--json", help="Print the report as JSON on stdout."),
+    include_pub: bool = typer.Option(False, "--include-pub", help="Report unreachable `pub` items too instead of treating the public API as entry points."),
+    fail_on_findings: bool = typer.Option(False, "--fail-on-findings", help="Exit with status 1 when unreachable code is found."),
+):
+    """
+    Reports Rust functions, types and enum variants unreachable from the crate's entry points.
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        report = server.code_finder.find_unreachable_code(pub_is_entry_point=not include_pub)
+        server.shutdown()
+    except ValueError as e:
+        console.print(f"[bold red]Configuration Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    sections = [("unreachable_functions", "Unreachable functions"), ("unreachable_types", "Unused types"),
+                ("unconstructed_variants", "Never constructed variants")]
+    findings = sum(len(report[key]) for key, _ in sections)
+    if json_output:
+        typer.echo(json.dumps(report, indent=2))
+    else:
+        for key, title in sections:
+            if not report[key]:
+                continue
+            table = Table(title=title, show_header=True, header_style="bold magenta")
+            table.add_column("Name")
+            table.add_column("Location", style="dim")
+            for item in report[key]:
+                name = f"{item['enum']}::{item['name']}" if key == "unconstructed_variants" else item['name']
+                table.add_row(name, f"{item['file_path']}:{item['line_number']}")
+            console.print(table)
+        console.print(f"Found [bold]{findings}[/bold] unreachable items (entry points: {report['entry_points']}).")
+
+    if fail_on_findings and findings:
+        raise typer.Exit(code=1)
+
+
+@app.command("health")
+def health(
+    max_lines: int = typer.Option(60, "--max-lines", help="Maximum number of lines per function."),
+    max_params: int = typer.Option(5, "--max-params", help="Maximum number of parameters per function."),
+    max_nesting: int = typer.Option(4, "--max-nesting", help="Maximum control flow nesting depth."),
+    max_complexity: int = typer.Option(10, "--max-complexity", help="Maximum cyclomatic complexity."),
+    json_output: bool = typer.Option(False, "--json", help="Print the report as JSON on stdout."),
+    fail_on_findings: bool = typer.Option(False, "--fail-on-findings", help="Exit with status 1 when a function exceeds a limit."),
+):
+    """
+    Lists the functions exceeding the given limits and records the violations in the graph.
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        report = server.code_finder.get_code_health_report(max_lines, max_params, max_nesting, max_complexity)
+        server.shutdown()
+    except ValueError as e:
+        console.print(f"[bold red]Configuration Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if json_output:
+        typer.echo(json.dumps(report, indent=2))
+    else:
+        if report["functions"]:
+            table = Table(title="Functions exceeding limits", show_header=True, header_style="bold magenta")
+            table.add_column("Name")
+            table.add_column("Violations")
+            table.add_column("Location", style="dim")
+            for item in report["functions"]:
+                details = ", ".join(f"{metric} {item['metrics'][metric]}" for metric in item["violations"])
+                table.add_row(item["name"], details, f"{item['file_path']}:{item['line_number']}")
+            console.print(table)
+        console.print(f"Found [bold]{len(report['functions'])}[/bold] functions exceeding limits {report['thresholds']}.")
+
+    if fail_on_findings and report["functions"]:
+        raise typer.Exit(code=1)
+
+
+@app.command("diff")
+def diff(
+    base: str = typer.Argument(..., help="The base revision (branch, tag or commit)."),
+    head: str = typer.Argument("HEAD", help="The revision to compare against the base."),
+    repo: Path = typer.Option(Path("."), "--repo", help="Path to the git repository."),
+    json_output: bool = typer.Option(False, "--json", help="Print the diff as JSON on stdout."),
+):
+    """
+    Indexes two revisions of a repository side by side and prints what changed between them.
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        report = server.diff_revisions(str(repo.resolve()), base, head)
+        server.shutdown()
+    except ValueError as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if json_output:
+        typer.echo(json.dumps(report, indent=2))
+        return
+
+    table = Table(title=f"Changes from {base} to {head}", show_header=True, header_style="bold magenta")
+    table.add_column("Section")
+    table.add_column("Change")
+    table.add_column("Item")
+    table.add_column("Location", style="dim")
+    for change, items in report["functions"].items():
+        for item in items:
+            table.add_row("function", change, item["name"], f"{item['file']}:{item['line_number']}")
+    for change, items in report["calls"].items():
+        for item in items:
+            table.add_row("call", change, f"{item['caller']} -> {item['callee']}", item["caller_file"])
+    for change, items in report["public_api"].items():
+        for item in items:
+            table.add_row("public api", change, item["path"], item["signature"] or "")
+    console.print(table)
+    console.print(", ".join(f"{key.replace('_', ' ')}: {count}" for key, count in report["summary"].items()))
+
+
+@app.command("export")
+def export(
+    root: Optional[str] = typer.Argument(None, help="The function (`name` or `Type::method`), module or crate to start from; the whole graph if omitted."),
+    kind: str = typer.Option("function", "--kind", help="What ROOT names: function, module or crate."),
+    edges: str = typer.Option(None, "--edges", help="Comma-separated relationship types to follow (CALLS by default) or, without ROOT, to export (all by default)."),
+    depth: int = typer.Option(2, "--depth", help="Maximum number of hops from the root."),
+    direction: str = typer.Option("out", "--direction", help="Follow edges out of the root, into it, or both."),
+    include_external: bool = typer.Option(False, "--include-external", help="Let module and crate exports leave the module or crate."),
+    repo: Path = typer.Option(None, "--repo", help="Without ROOT: only export the nodes under this repository."),
+    labels: str = typer.Option(None, "--labels", help="Without ROOT: comma-separated node labels to export, e.g. Function,Class (all by default)."),
+    max_nodes: int = typer.Option(None, "--max-nodes", help="Maximum number of nodes to export (500 from a ROOT, unlimited without)."),
+    min_samples: int = typer.Option(None, "--min-samples", help="Only follow call edges with at least this many profile samples."),
+    export_format: str = typer.Option("json", "--format", help="Output format: json, mermaid, graphml (for Gephi or yEd) or dot (for Graphviz)."),
+    modules: bool = typer.Option(False, "--modules", help="Collapse the nodes to the files they are in, for a module graph."),
+    cluster: bool = typer.Option(False, "--cluster", help="DOT output: draw the nodes of each file inside a box."),
+    output: Path = typer.Option(None, "--output", "-o", help="Write to this file instead of stdout."),
+):
+    """
+    Exports the subgraph reachable from a function, module or crate, for focused diagrams, or
+    without ROOT the whole (optionally filtered) graph with its properties.
+    """
+    _load_credentials()
+    edge_types = [e for e in edges.split(",") if e.strip()] if edges else None
+    try:
+        server = MCPServer()
+        if root:
+            max_nodes = max_nodes or 500
+            subgraph = server.code_finder.export_subgraph(
+                root, root_kind=kind, edge_types=edge_types, depth=depth,
+                direction=direction, include_external=include_external, max_nodes=max_nodes, min_samples=min_samples,
+            )
+        else:
+            subgraph = server.code_finder.export_graph(
+                repo_path=str(repo) if repo else None, labels=[label for label in labels.split(",") if label.strip()] if labels else None,
+                edge_types=edge_types, max_nodes=max_nodes,
+            )
+        server.shutdown()
+        if modules:
+            subgraph = module_graph(subgraph)
+        rendered = render_subgraph(subgraph, export_format, cluster)
+    except ValueError as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if output:
+        output.write_text(rendered, encoding="utf-8")
+        console.print(f"Exported {len(subgraph['nodes'])} nodes and {len(subgraph['edges'])} edges to {output}.")
+    else:
+        typer.echo(rendered)
+    if subgraph["truncated"]:
+        console.print(f"[yellow]Stopped at {max_nodes} nodes; narrow the export or raise --max-nodes.[/yellow]")
+
+
+@app.command("semver")
+def semver(
+    base: str = typer.Argument(..., help="The released revision (branch, tag or commit)."),
+    head: str = typer.Argument("HEAD", help="The revision to compare against the base."),
+    repo: Path = typer.Option(Path("."), "--repo", help="Path to the git repository."),
+    json_output: bool = typer.Option(False, "--json", help="Print the report as JSON on stdout."),
+    fail_on_major: bool = typer.Option(False, "--fail-on-major", help="Exit with status 1 when a breaking change is found."),
+):
+    """
+    Classifies the API changes between two revisions of a Rust crate by the semver bump they need.
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        report = server.check_semver(str(repo.resolve()), base, head)
+        server.shutdown()
+    except ValueError as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if json_output:
+        typer.echo(json.dumps(report, indent=2))
+    else:
+        table = Table(title=f"API changes from {base} to {head}", show_header=True, header_style="bold magenta")
+        table.add_column("Level")
+        table.add_column("Change")
+        table.add_column("Path")
+        table.add_column("Detail", style="dim")
+        for change in report["changes"]:
+            table.add_row(change["level"], change["change"], change["path"], change["detail"])
+        console.print(table)
+        console.print(f"Required version bump: [bold]{report['level']}[/bold] "
+                      f"({', '.join(f'{level}: {count}' for level, count in report['summary'].items())})")
+
+    if fail_on_major and report["level"] == "major":
+        raise typer.Exit(code=1)
+
+
+@app.command("hotspots")
+def hotspots(
+    since_days: int = typer.Option(90, "--since-days", help="Start of the time window, in days ago."),
+    until_days: int = typer.Option(0, "--until-days", help="End of the time window, in days ago."),
+    files: bool = typer.Option(False, "--files", help="Rank files instead of functions."),
+    limit: int = typer.Option(20, "--limit", help="Maximum number of hotspots to show."),
+    json_output: bool = typer.Option(False, "--json", help="Print the ranking as JSON on stdout."),
+):
+    """
+    Ranks the indexed code by maintenance risk (churn in the window times complexity).
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        ranking = server.code_finder.find_hotspots(since_days, until_days, "file" if files else "function", limit)
+        server.shutdown()
+    except ValueError as e:
+        console.print(f"[bold red]Configuration Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if json_output:
+        typer.echo(json.dumps(ranking, indent=2))
+        return
+
+    table = Table(title=f"Hotspots over the last {since_days} days", show_header=True, header_style="bold magenta")
+    table.add_column("Score", justify="right")
+    table.add_column("Commits", justify="right")
+    table.add_column("Complexity", justify="right")
+    table.add_column("Name" if not files else "File")
+    if not files:
+        table.add_column("Location", style="dim")
+    for item in ranking:
+        row = [str(item["score"]), str(item["commits"]), str(item["complexity"])]
+        row += [item["file_path"]] if files else [item["name"], f"{item['file_path']}:{item['line_number']}"]
+        table.add_row(*row)
+    console.print(table)
+
+
+@app.command("check-layers")
+def check_layers(
+    path: Path = typer.Argument(Path("."), help="Path of the indexed repository containing `.cgc-layers.json`."),
+    json_output: bool = typer.Option(False, "--json", help="Print the report as JSON on stdout."),
+):
+    """
+    Checks the indexed module dependencies against the repository's layering rules.
+    Exits with status 1 when a rule is violated, so it can gate CI.
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        report = server.code_finder.check_layering(str(path.resolve()))
+        server.shutdown()
+    except ValueError as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if json_output:
+        typer.echo(json.dumps(report, indent=2))
+    else:
+        for violation in report["violations"]:
+            table = Table(title=f"{violation['source_module']} -> {violation['target_module']} ({violation['rule']})",
+                          show_header=True, header_style="bold magenta")
+            table.add_column("Relation")
+            table.add_column("Edge")
+            table.add_column("Location", style="dim")
+            for edge in violation["edges"]:
+                location = f"{edge['source_file']}:{edge['line_number']}" if edge["line_number"] else edge["source_file"]
+                table.add_row(edge["relation"], f"{edge['source']} -> {edge['target']}", location)
+            console.print(table)
+        console.print(f"Checked {len(report['rules'])} rules against {report['module_dependencies']} module dependencies: "
+                      f"[bold]{len(report['violations'])}[/bold] violations.")
+
+    if report["violations"]:
+        raise typer.Exit(code=1)
+
+
+@app.command("bench")
+def bench(
+    projects: List[Path] = typer.Option(None, "--project", "-p", help="A project to benchmark (repeatable); the bundled sample projects by default."),
+    samples: int = typer.Option(benchmarks.DEFAULT_SAMPLES, "--samples", help="Timed iterations of each benchmark."),
+    baseline: Path = typer.Option(Path(benchmarks.BASELINE_FILE), "--baseline", help="The baseline file to compare against."),
+    save: bool = typer.Option(False, "--save-baseline", help="Store this run as the new baseline."),
+    threshold: float = typer.Option(benchmarks.DEFAULT_THRESHOLD * 100, "--threshold", help="Slowdown of the mean, in percent, counted as a regression."),
+    json_output: bool = typer.Option(False, "--json", help="Print the results as JSON on stdout."),
+):
+    """
+    Runs the parse, resolve and write benchmarks over the sample projects, compares them with the
+    stored baseline and exits with status 1 when one regressed beyond the threshold.
+    """
+    from codegraphcontext.tools.graph_builder import create_parsers
+
+    if not projects:
+        tests_dir = Path(__file__).resolve().parents[3] / "tests"
+        projects = sorted(path for path in tests_dir.glob("sample_project*") if path.is_dir())
+        if not projects:
+            console.print("[bold red]Error:[/bold red] the sample projects were not found, pass --project.")
+            raise typer.Exit(code=2)
+    try:
+        results = benchmarks.run_suite(projects, create_parsers(), samples,
+                                       on_benchmark=lambda benchmark_id: console.print(f"[dim]Benchmarking {benchmark_id}...[/dim]"))
+    except ValueError as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+    benchmarks.compare(results, benchmarks.load_baseline(baseline), threshold / 100)
+    if save:
+        benchmarks.save_baseline(baseline, results)
+
+    if json_output:
+        typer.echo(json.dumps(results, indent=2))
+    else:
+        table = Table(title="Benchmarks", show_header=True, header_style="bold magenta")
+        table.add_column("Benchmark")
+        table.add_column("Mean", justify="right")
+        table.add_column("Std dev", justify="right")
+        table.add_column("Throughput", justify="right")
+        table.add_column("Change", justify="right")
+        for result in results:
+            style = {"regressed": "red", "improved": "green"}.get(result["verdict"])
+            change = f"{result['change'] * 100:+.1f}% ({result['verdict']})" if result["change"] is not None else "no baseline"
+            table.add_row(result["id"], f"{result['mean_ns'] / 1e6:.2f} ms", f"{result['std_dev_ns'] / 1e6:.2f} ms",
+                          f"{result['throughput']} {result['throughput_unit']}", change, style=style)
+        console.print(table)
+        if save:
+            console.print(f"Saved the baseline to {baseline}.")
+
+    regressed = [result["id"] for result in results if result["verdict"] == "regressed"]
+    if regressed:
+        console.print(f"[bold red]{len(regressed)} benchmarks regressed by more than {threshold:g}%:[/bold red] {', '.join(regressed)}")
+        raise typer.Exit(code=1)
+
+
+@app.command("doc-coverage")
+def doc_coverage(
+    crate: str = typer.Option(None, "--crate", help="The crate to report on; all indexed crates by default."),
+    min_coverage: float = typer.Option(0.0, "--min-coverage", help="Exit with status 1 when a crate's coverage (in percent) is below this."),
+    json_output: bool = typer.Option(False, "--json", help="Print the report as JSON on stdout."),
+):
+    """
+    Reports the documentation coverage of the public API per crate and module, listing undocumented items.
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        report = server.code_finder.get_doc_coverage(crate_name=crate, min_coverage=min_coverage)
+        server.shutdown()
+    except ValueError as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if json_output:
+        typer.echo(json.dumps(report, indent=2))
+    else:
+        table = Table(title="Documentation coverage", show_header=True, header_style="bold magenta")
+        table.add_column("Crate")
+        table.add_column("Module")
+        table.add_column("Documented", justify="right")
+        table.add_column("Coverage", justify="right")
+        for module in report["modules"]:
+            style = "red" if module["below_threshold"] else None
+            table.add_row(module["crate"], module["module"] or "", f"{module['documented']}/{module['total']}",
+                          f"{module['coverage']}%", style=style)
+        console.print(table)
+        for module in report["modules"]:
+            for item in module["missing"]:
+                console.print(f"[dim]{item['file_path']}:{item['line_number']}[/dim] {item['kind']} {item['path']}")
+        console.print(f"Documented [bold]{report['documented']}[/bold] of {report['total']} public items ({report['coverage']}%).")
+
+    if not report["passed"]:
+        raise typer.Exit(code=1)
+
+
+@app.command("dsm")
+def dsm(
+    crate: str = typer.Option(None, "--crate", help="The crate to analyze; all indexed crates by default."),
+    depth: int = typer.Option(None, "--depth", help="Collapse modules to this many path segments."),
+    export_format: str = typer.Option("json", "--format", help="Output format: json or csv."),
+    output: Path = typer.Option(None, "--output", "-o", help="Write to this file instead of stdout."),
+):
+    """
+    Prints the module dependency structure matrix of a crate, providers first.
+    """
+    _load_credentials()
+    try:
+        server = MCPServer()
+        matrix = server.code_finder.module_dependency_matrix(crate_name=crate, depth=depth)
+        server.shutdown()
+        rendered = render_dsm(matrix, export_format)
+    except ValueError as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+
+    if output:
+        output.write_text(rendered, encoding="utf-8")
+        console.print(f"Wrote the matrix of {len(matrix['modules'])} modules to {output}.")
+    else:
+        typer.echo(rendered)
+    if matrix["feedback"]:
+        console.print(f"[yellow]{len(matrix['feedback'])} dependencies point against the layering (above the diagonal).[/yellow]")
+
+
+@app.command("ignore")
+def ignore(
+    path: Path = typer.Argument(..., help="The repository root holding the `.cgcignore` file."),
+    limit: int = typer.Option(50, "--limit", help="The maximum number of included and of excluded files listed."),
+    json_output: bool = typer.Option(False, "--json", help="Print the dry-run as JSON on stdout."),
+):
+    """
+    Dry-runs the ignore rules of a repository without a database: prints the effective rules with
+    where each comes from, and which supported files would be indexed or excluded (and by which rule).
+    """
+    from codegraphcontext.tools.graph_builder import create_parsers
+
+    if not path.is_dir():
+        console.print(f"[bold red]Error:[/bold red] {path} is not a directory.")
+        raise typer.Exit(code=2)
+    report = IgnoreRules.load(path).dry_run(create_parsers().keys(), limit)
+
+    if json_output:
+        typer.echo(json.dumps(report, indent=2))
+        return
+    table = Table(title=f"Ignore rules of {report['root']}", show_header=True, header_style="bold magenta")
+    table.add_column("Pattern")
+    table.add_column("Source")
+    for rule in report["rules"]:
+        table.add_row(rule["pattern"], rule["source"])
+    console.print(table)
+    for entry in report["excluded"]:
+        console.print(f"[red]-[/red] {entry['path']} [dim]({entry['pattern']}, {entry['source']})[/dim]")
+    for included in report["included"]:
+        console.print(f"[green]+[/green] {included}")
+    console.print(f"Indexing [bold]{report['included_count']}[/bold] files, excluding {report['excluded_count']}.")
+
+
+@app.command("bulk-export")
+def bulk_export(
+    path: Path = typer.Argument(..., help="The repository to export."),
+    output: Path = typer.Option(..., "--output", "-o", help="The directory to write the CSV files and import script to."),
+    is_dependency: bool = typer.Option(False, "--dependency", help="Mark the code as a dependency."),
+):
+    """
+    Parses a repository without a database and writes its nodes and relationships as CSV files for
+    `neo4j-admin database import full`, much faster than transactional writes for initial ingestion
+    of very large codebases. Run `cgc bulk-link` on the repository once the database is loaded.
+    """
+    from codegraphcontext.tools.graph_builder import create_parsers
+
+    path = path.resolve()
+    if not path.is_dir():
+        console.print(f"[bold red]Error:[/bold red] {path} is not a directory.")
+        raise typer.Exit(code=2)
+    parsers = create_parsers()
+    summary = export_repository(
+        path, output, lambda file: parsers[file.suffix].parse(file, is_dependency), parsers.keys(), is_dependency,
+    )
+    for error in summary["errors"]:
+        console.print(f"[yellow]Skipped {error}[/yellow]")
+    nodes = sum(summary["counts"]["nodes"].values())
+    relationships = sum(summary["counts"]["relationships"].values())
+    console.print(f"Exported {summary['files']} files as {nodes} nodes and {relationships} relationships to {summary['output_dir']}.")
+    console.print("Stop the database and run the import (add --overwrite-destination to replace an existing database):")
+    typer.echo(summary["script"])
+    console.print("Then start the database and run `cgc bulk-link` on the repository to create cross-file references.")
+
+
+@app.command("bulk-link")
+def bulk_link(
+    path: Path = typer.Argument(..., help="The repository loaded from a bulk export."),
+):
+    """
+    Completes a repository imported from `cgc bulk-export`: creates the language-specific nodes
+    and the cross-file relationships (calls, inheritance, ...) the export leaves out.
+    """
+    _load_credentials()
+    server = MCPServer()
+    result = asyncio.run(server.graph_builder.complete_bulk_import_async(path.resolve()))
+    server.shutdown()
+    if result is None:
+        console.print("[bold red]Error:[/bold red] linking failed, see the log above.")
+        raise typer.Exit(code=1)
+    for error in result["errors"]:
+        console.print(f"[yellow]Skipped {error}[/yellow]")
+    console.print(f"Linked {result['files']} files of {path.resolve()}.")
+
+
+@app.command("dump")
+def dump(
+    path: Optional[Path] = typer.Argument(None, help="The indexed repository to dump; the whole graph if omitted."),
+    output: Path = typer.Option(..., "--output", "-o", help="The JSON Lines file to write (gzip-compressed if it ends in .gz)."),
+):
+    """
+    Writes the nodes and relationships of the graph, or of one repository, as JSON Lines with
+    stable keys, to archive a graph, ship it to another machine or read it with other tools.
+    """
+    _load_credentials()
+    server = MCPServer()
+    try:
+        with server.db_manager.get_driver().session() as session:
+            header = write_dump(session, output, str(path.resolve()) if path else None)
+    finally:
+        server.shutdown()
+    if not header["nodes"]:
+        console.print(f"[yellow]Nothing indexed{f' under {path.resolve()}' if path else ''}; the dump is empty.[/yellow]")
+    console.print(f"Dumped {header['nodes']} nodes and {header['relationships']} relationships to {output}.")
+
+
+@app.command("load")
+def load(
+    dump_file: Path = typer.Argument(..., help="A dump written by `cgc dump`."),
+    replace: bool = typer.Option(False, "--replace", help="Delete the repositories of the dump from the graph first."),
+):
+    """
+    Loads a dump written by `cgc dump` into the graph. Loading the same dump again updates the
+    graph in place; repositories indexed since the dump was taken need --replace.
+    """
+    try:
+        header, graph = read_dump(dump_file)
+    except (OSError, ValueError) as e:
+        console.print(f"[bold red]Error:[/bold red] {e}")
+        raise typer.Exit(code=2)
+    _load_credentials()
+    server = MCPServer()
+    try:
+        if replace:
+            for node in graph["nodes"].values():
+                if node["owned"] and "Repository" in node["labels"]:
+                    server.graph_builder.delete_repository_from_graph(node["props"]["path"])
+        with server.db_manager.get_driver().session() as session:
+            counts = load_graph(session, graph, header.get("repository"))
+    finally:
+        server.shutdown()
+    console.print(f"Loaded {counts['nodes']} nodes and {counts['relationships']} relationships from {dump_file}.")
+
+
+@app.command("scip")
+def scip_index(
+    path: Path = typer.Argument(..., help="The indexed repository."),
+    output: Path = typer.Option(Path("index.scip"), "--output", "-o", help="The SCIP index to write."),
+    package: str = typer.Option(None, "--package", help="The package name of the symbols (the repository's directory name by default)."),
+    package_version: str = typer.Option(None, "--package-version", help="The package version of the symbols, e.g. a release or commit."),
+):
+    """
+    Writes a SCIP index of the definitions, references and implementations the graph resolved
+    in a repository, for Sourcegraph (`src code-intel upload`) and other SCIP consumers.
+    """
+    root = path.resolve()
+    _load_credentials()
+    server = MCPServer()
+    try:
+        graph = server.code_finder.symbol_graph(str(root))
+    finally:
+        server.shutdown()
+    if not graph["files"]:
+        console.print(f"[bold red]Error:[/bold red] {root} is not indexed.")
+        raise typer.Exit(code=2)
+    documents = scip.build_documents(graph, package or root.name, package_version)
+    output.write_bytes(scip.encode_index(documents, str(root), get_version()))
+    occurrences = sum(len(document["occurrences"]) for document in documents)
+    symbols = sum(len(document["symbols"]) for document in documents)
+    console.print(f"Wrote {len(documents)} documents, {symbols} symbols and {occurrences} occurrences to {output}.")
+
+
+@app.command("lsif")
+def lsif_dump(
+    path: Path = typer.Argument(..., help="The indexed repository."),
+    output: Path = typer.Option(Path("dump.lsif"), "--output", "-o", help="The LSIF dump to write (JSON Lines)."),
+):
+    """
+    Writes an LSIF dump of the definitions, references, hovers (from doc comments) and
+    implementations the graph resolved in a repository, for editors and code hosts reading LSIF.
+    """
+    root = path.resolve()
+    _load_credentials()
+    server = MCPServer()
+    try:
+        graph = server.code_finder.symbol_graph(str(root))
+    finally:
+        server.shutdown()
+    if not graph["files"]:
+        console.print(f"[bold red]Error:[/bold red] {root} is not indexed.")
+        raise typer.Exit(code=2)
+    documents = scip.build_documents(graph, root.name)
+    count = lsif.write_dump(documents, str(root), output, get_version())
+    console.print(f"Wrote {count} vertices and edges for {len(documents)} documents to {output}.")
+
+
 @app.command()
 def help(ctx: typer.Context):
     """Show the main help message and exit."""
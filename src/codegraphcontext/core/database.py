@@ -1,16 +1,44 @@
 # src/codegraphcontext/core/database.py
 """
 This module provides a thread-safe singleton manager for the Neo4j database connection.
+
+The driver pools its connections (CGC_DB_POOL_SIZE of them at most), checks that pooled
+connections idle for a while are still alive before handing them out, and replaces dead ones, so
+the server reconnects on its own once a restarted database is back. Units of work that must
+survive the restart in between (a file's writes, a linking pass) run through `run_with_retry`,
+which retries them on transient errors with exponential backoff.
 """
 import os
 import logging
+import random
 import threading
-from typing import Optional
+import time
+from typing import Callable, Iterator, Optional, TypeVar
 
 from neo4j import GraphDatabase, Driver
+from neo4j.exceptions import ServiceUnavailable, SessionExpired, TransientError
 
 logger = logging.getLogger(__name__)
 
+T = TypeVar("T")
+
+# Errors after which the same work can succeed when tried again: the database is unreachable or
+# restarting, the connection was lost, or the transaction hit a deadlock or lock timeout.
+TRANSIENT_ERRORS = (ServiceUnavailable, SessionExpired, TransientError)
+DEFAULT_POOL_SIZE = 50
+DEFAULT_MAX_RETRIES = 8
+DEFAULT_RETRY_DELAY = 0.5
+MAX_RETRY_DELAY = 30.0
+# Pooled connections idle for longer than this (seconds) are checked before being reused.
+LIVENESS_CHECK_TIMEOUT = 30.0
+
+
+def backoff_delays(retries: int, initial: float, maximum: float = MAX_RETRY_DELAY) -> Iterator[float]:
+    """The waits before each of `retries` retries: doubling from `initial` up to `maximum`, less up to 25% jitter."""
+    for attempt in range(retries):
+        yield min(maximum, initial * 2 ** attempt) * random.uniform(0.75, 1.0)
+
+
 class DatabaseManager:
     """
     Manages the Neo4j database driver as a singleton to ensure only one
@@ -21,6 +49,7 @@ class DatabaseManager:
     """
     _instance = None
     _driver: Optional[Driver] = None
+    _remote_driver: Optional[Driver] = None
     _lock = threading.Lock() # Lock to ensure thread-safe initialization. 
 
     def __new__(cls):
@@ -43,6 +72,15 @@ class DatabaseManager:
         self.neo4j_uri = os.getenv('NEO4J_URI')
         self.neo4j_username = os.getenv('NEO4J_USERNAME', 'neo4j')
         self.neo4j_password = os.getenv('NEO4J_PASSWORD')
+        # A remote (shared) database the graphs are mirrored to, see `tools.delta_sync`.
+        self.remote_uri = os.getenv('CGC_REMOTE_NEO4J_URI')
+        self.remote_username = os.getenv('CGC_REMOTE_NEO4J_USERNAME', 'neo4j')
+        self.remote_password = os.getenv('CGC_REMOTE_NEO4J_PASSWORD')
+        self.pool_size = max(1, int(os.getenv('CGC_DB_POOL_SIZE', DEFAULT_POOL_SIZE)))
+        self.max_retries = max(0, int(os.getenv('CGC_DB_MAX_RETRIES', DEFAULT_MAX_RETRIES)))
+        self.retry_delay = max(0.0, float(os.getenv('CGC_DB_RETRY_DELAY', DEFAULT_RETRY_DELAY)))
+        self.retries = 0
+        self.last_transient_error: Optional[str] = None
         self._initialized = True
 
     def get_driver(self) -> Driver:
@@ -71,7 +109,9 @@ class DatabaseManager:
                     logger.info(f"Creating Neo4j driver connection to {self.neo4j_uri}")
                     self._driver = GraphDatabase.driver(
                         self.neo4j_uri,
-                        auth=(self.neo4j_username, self.neo4j_password)
+                        auth=(self.neo4j_username, self.neo4j_password),
+                        max_connection_pool_size=self.pool_size,
+                        liveness_check_timeout=LIVENESS_CHECK_TIMEOUT,
                     )
                     # Test the connection immediately to fail fast if credentials are wrong.
                     try:
@@ -86,14 +126,46 @@ class DatabaseManager:
                         raise
         return self._driver
 
-    def close_driver(self):
-        """Closes the Neo4j driver connection if it exists."""
-        if self._driver is not None:
+    def remote_configured(self) -> bool:
+        """Whether a remote database to sync graphs to is set up (CGC_REMOTE_NEO4J_URI and password)."""
+        return bool(self.remote_uri and self.remote_password)
+
+    def get_remote_driver(self) -> Driver:
+        """
+        Gets the driver of the remote database graphs are synced to, creating it if it doesn't exist.
+
+        Raises:
+            ValueError: If the remote database is not configured.
+        """
+        if self._remote_driver is None:
             with self._lock:
-                if self._driver is not None:
-                    logger.info("Closing Neo4j driver")
-                    self._driver.close()
-                    self._driver = None
+                if self._remote_driver is None:
+                    if not self.remote_configured():
+                        raise ValueError(
+                            "The remote Neo4j must be set via environment variables:\n"
+                            "- CGC_REMOTE_NEO4J_URI\n"
+                            "- CGC_REMOTE_NEO4J_USERNAME\n"
+                            "- CGC_REMOTE_NEO4J_PASSWORD"
+                        )
+                    logger.info(f"Creating Neo4j driver connection to remote {self.remote_uri}")
+                    self._remote_driver = GraphDatabase.driver(
+                        self.remote_uri,
+                        auth=(self.remote_username, self.remote_password),
+                        max_connection_pool_size=self.pool_size,
+                        liveness_check_timeout=LIVENESS_CHECK_TIMEOUT,
+                    )
+        return self._remote_driver
+
+    def close_driver(self):
+        """Closes the Neo4j driver connections if they exist."""
+        with self._lock:
+            if self._driver is not None:
+                logger.info("Closing Neo4j driver")
+                self._driver.close()
+                self._driver = None
+            if self._remote_driver is not None:
+                self._remote_driver.close()
+                self._remote_driver = None
 
     def is_connected(self) -> bool:
         """Checks if the database connection is currently active."""
@@ -105,3 +177,33 @@ class DatabaseManager:
             return True
         except Exception:
             return False
+
+    def run_with_retry(self, work: Callable[[], T], description: str = "Database work") -> T:
+        """
+        Runs `work` and returns its result, running it again after a backoff (see `backoff_delays`)
+        whenever it fails with a transient error, up to CGC_DB_MAX_RETRIES times. `work` must be
+        safe to repeat after failing halfway, as MERGE-based writes are; other errors propagate at once.
+        """
+        delays = backoff_delays(self.max_retries, self.retry_delay)
+        while True:
+            try:
+                return work()
+            except TRANSIENT_ERRORS as e:
+                delay = next(delays, None)
+                self.last_transient_error = f"{type(e).__name__}: {e}"
+                if delay is None:
+                    logger.error(f"{description} failed after {self.max_retries} retries: {e}")
+                    raise
+                self.retries += 1
+                logger.warning(f"{description} failed ({type(e).__name__}: {e}), retrying in {delay:.1f}s")
+                time.sleep(delay)
+
+    def pool_status(self) -> dict:
+        """The pool settings and how many transient failures were retried since the server started."""
+        return {
+            "pool_size": self.pool_size,
+            "max_retries": self.max_retries,
+            "retry_delay": self.retry_delay,
+            "retries": self.retries,
+            "last_transient_error": self.last_transient_error,
+        }
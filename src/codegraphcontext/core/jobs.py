@@ -3,14 +3,21 @@
 This module defines the data structures and manager for handling long-running,
 background jobs, such as code indexing.
 """
+import logging
 import uuid
 import threading
+from collections import deque
 from datetime import datetime, timedelta
 from dataclasses import dataclass, asdict
 from enum import Enum
-from typing import Any, Dict, List, Optional
+from typing import Any, Callable, Dict, List, Optional
 from pathlib import Path
 
+logger = logging.getLogger(__name__)
+
+# How many progress events of each job are kept for `check_job_status`.
+MAX_PROGRESS_EVENTS = 200
+
 
 class JobStatus(Enum):
     """Enumeration for the possible statuses of a background job."""
@@ -39,6 +46,10 @@ class JobInfo:
     result: Optional[Dict[str, Any]] = None
     path: Optional[str] = None
     is_dependency: bool = False
+    phase: Optional[str] = None # scanning, indexing, linking, annotating, syncing, then completed or failed
+    nodes_written: int = 0
+    shards: Optional[List[Dict[str, Any]]] = None # The status of each shard of a sharded build.
+    queues: Optional[Dict[str, Any]] = None # The parse and write queue depths of a build's pipeline.
 
     def __post_init__(self):
         """Ensures the errors list is initialized after the object is created."""
@@ -62,6 +73,26 @@ class JobInfo:
         remaining_files = self.total_files - self.processed_files
         return remaining_files * avg_time_per_file
 
+    def progress_event(self, sequence: int) -> Dict[str, Any]:
+        """A machine-readable snapshot of the job's progress, as sent to progress listeners."""
+        # Only file indexing has a known amount of work left; linking passes report no ETA.
+        remaining = self.estimated_time_remaining if self.phase in (None, "indexing") else None
+        return {
+            "job_id": self.job_id,
+            "sequence": sequence,
+            "time": datetime.now().isoformat(timespec="seconds"),
+            "status": self.status.value,
+            "phase": self.phase,
+            "processed_files": self.processed_files,
+            "total_files": self.total_files,
+            "progress_percentage": round(self.progress_percentage, 1),
+            "nodes_written": self.nodes_written,
+            "current_file": self.current_file,
+            "eta_seconds": None if remaining is None else round(remaining, 1),
+            "shards": self.shards,
+            "queues": self.queues,
+        }
+
 class JobManager:
     """
     A thread-safe manager for creating, updating, and retrieving information
@@ -70,6 +101,8 @@ class JobManager:
     def __init__(self):
         self.jobs: Dict[str, JobInfo] = {}
         self.lock = threading.Lock() # A lock to ensure thread-safe access to the jobs dictionary.
+        self.progress: Dict[str, deque] = {} # The latest progress events of each job.
+        self.listeners: List[Callable[[Dict[str, Any]], None]] = []
 
     def create_job(self, path: str, is_dependency: bool = False) -> str:
         """Creates a new job, assigns it a unique ID, and stores it."""
@@ -93,6 +126,44 @@ class JobManager:
                     if hasattr(job, key):
                         setattr(job, key, value)
 
+    def report_progress(self, job_id: str, **kwargs):
+        """
+        Updates a job like `update_job` and emits a progress event (see `JobInfo.progress_event`) to
+        every listener. Listeners run on the reporting thread and must not block.
+        """
+        with self.lock:
+            job = self.jobs.get(job_id)
+            if job is None:
+                return
+            for key, value in kwargs.items():
+                if hasattr(job, key):
+                    setattr(job, key, value)
+            events = self.progress.setdefault(job_id, deque(maxlen=MAX_PROGRESS_EVENTS))
+            event = job.progress_event(events[-1]["sequence"] + 1 if events else 1)
+            events.append(event)
+            listeners = list(self.listeners)
+        for listener in listeners:
+            try:
+                listener(event)
+            except Exception as e:
+                logger.warning(f"Progress listener failed for job {job_id}: {e}")
+
+    def add_listener(self, listener: Callable[[Dict[str, Any]], None]):
+        """Registers a callback receiving the progress events of every job."""
+        with self.lock:
+            self.listeners.append(listener)
+
+    def remove_listener(self, listener: Callable[[Dict[str, Any]], None]):
+        """Unregisters a callback added with `add_listener`."""
+        with self.lock:
+            if listener in self.listeners:
+                self.listeners.remove(listener)
+
+    def get_progress_events(self, job_id: str, since: int = 0) -> List[Dict[str, Any]]:
+        """The kept progress events of a job with a sequence number above `since`, oldest first."""
+        with self.lock:
+            return [event for event in self.progress.get(job_id, ()) if event["sequence"] > since]
+
     def get_job(self, job_id: str) -> Optional[JobInfo]:
         """Retrieves the information for a single job."""
         with self.lock:
@@ -130,3 +201,4 @@ class JobManager:
             ]
             for job_id in jobs_to_remove:
                 del self.jobs[job_id]
+                self.progress.pop(job_id, None)
@@ -3,13 +3,19 @@
 This module implements the live file-watching functionality using the `watchdog` library.
 It observes directories for changes and triggers updates to the code graph.
 """
+import asyncio
 import logging
 import threading
+import time
+from collections import deque
+from datetime import datetime
 from pathlib import Path
 import typing
 from watchdog.observers import Observer
 from watchdog.events import FileSystemEventHandler
 
+from codegraphcontext.tools.ignore import IGNORE_FILE, IgnoreRules
+
 if typing.TYPE_CHECKING:
     from codegraphcontext.tools.graph_builder import GraphBuilder
     from codegraphcontext.core.jobs import JobManager
@@ -20,122 +26,109 @@ logger = logging.getLogger(__name__)
 class RepositoryEventHandler(FileSystemEventHandler):
     """
     A dedicated event handler for a single repository being watched.
-    
-    This handler is stateful. It performs an initial scan of the repository
-    to build a baseline and then uses this cached state to perform efficient
-    updates when files are changed, created, or deleted.
+
+    File events are coalesced: each one restarts a single debounce timer for the whole repository,
+    so a burst of them (save-all, branch switch, formatter run) triggers one incremental re-index
+    (`GraphBuilder.reindex_path_async`) of the touched files and their dependents once the
+    repository has been quiet for the debounce window; a change to the ignore file re-checks it all.
+    Every re-index is recorded as an event describing what changed in the graph.
     """
-    def __init__(self, graph_builder: "GraphBuilder", repo_path: Path, debounce_interval=2.0, perform_initial_scan: bool = True):
+    def __init__(self, graph_builder: "GraphBuilder", repo_path: Path, debounce_interval=2.0, max_events: int = 100):
         """
         Initializes the event handler.
 
         Args:
             graph_builder: An instance of the GraphBuilder to perform graph operations.
             repo_path: The absolute path to the repository directory to watch.
-            debounce_interval: The time in seconds to wait for more changes before processing an event.
-            perform_initial_scan: Whether to perform an initial scan of the repository.
+            debounce_interval: The time in seconds to wait for more changes before re-indexing.
+            max_events: How many of the latest change events to keep.
         """
         super().__init__()
         self.graph_builder = graph_builder
         self.repo_path = repo_path
         self.debounce_interval = debounce_interval
-        self.timers = {} # A dictionary to manage debounce timers for file paths.
-        
-        # Caches for the repository's state.
-        self.all_file_data = []
-        self.imports_map = {}
-        
-        # Perform the initial scan and linking when the watcher is created.
-        if perform_initial_scan:
-            self._initial_scan()
-
-    def _initial_scan(self):
-        """Scans the entire repository, parses all files, and builds the initial graph."""
-        logger.info(f"Performing initial scan for watcher: {self.repo_path}")
-        all_files = list(self.repo_path.rglob("*.py"))
-        
-        # 1. Pre-scan all files to get a global map of where every symbol is defined.
-        self.imports_map = self.graph_builder._pre_scan_for_imports(all_files)
-        
-        # 2. Parse all files in detail and cache the parsed data.
-        for f in all_files:
-            parsed_data = self.graph_builder.parse_python_file(self.repo_path, f, self.imports_map)
-            if "error" not in parsed_data:
-                self.all_file_data.append(parsed_data)
-        
-        # 3. After all files are parsed, create the relationships (e.g., function calls) between them.
-        self.graph_builder._create_all_function_calls(self.all_file_data, self.imports_map)
-        logger.info(f"Initial scan and graph linking complete for: {self.repo_path}")
-
-    def _debounce(self, event_path, action):
-        """
-        Schedules an action to run after a debounce interval.
-        This prevents the handler from firing on every single file save event in rapid
-        succession, which is common in IDEs. It waits for a quiet period before processing.
+        self.pending = set() # Paths touched since the last re-index.
+        self.timer = None
+        self.lock = threading.Lock()
+        self.reindex_lock = threading.Lock() # Re-indexes of one repository never overlap.
+        self.events = deque(maxlen=max_events)
+        self.rules = IgnoreRules.load(repo_path) # Events for excluded files (e.g. under target/) are dropped.
+        self.sequence = 0
+
+    def _debounce(self, event_path: str):
         """
-        # If a timer already exists for this path, cancel it.
-        if event_path in self.timers:
-            self.timers[event_path].cancel()
-        # Create and start a new timer.
-        timer = threading.Timer(self.debounce_interval, action)
-        timer.start()
-        self.timers[event_path] = timer
-
-    def _handle_modification(self, event_path_str: str):
+        Records a touched path and (re)starts the repository's timer, so the re-index waits for a
+        quiet period instead of firing on every single file save event in rapid succession.
         """
-        Orchestrates the complete update cycle for a modified or created file.
-        This involves re-scanning the entire repo to update cross-file relationships.
-        """
-        logger.info(f"File change detected, starting full repository refresh for: {event_path_str}")
-        modified_path = Path(event_path_str)
-
-        # 1. Get all supported files in the repository.
-        supported_extensions = self.graph_builder.parsers.keys()
-        all_files = [f for f in self.repo_path.rglob("*") if f.is_file() and f.suffix in supported_extensions]
-
-        # 2. Re-scan all files to get a fresh, global map of all symbols.
-        self.imports_map = self.graph_builder._pre_scan_for_imports(all_files)
-        logger.info("Refreshed global imports map.")
-
-        # 3. Update the specific file that changed in the graph.
-        # This deletes old nodes and adds new ones for the single file.
-        self.graph_builder.update_file_in_graph(
-            modified_path, self.repo_path, self.imports_map
-        )
-
-        # 4. Re-parse all files to have a complete, in-memory representation for the linking pass.
-        # This is necessary because a change in one file can affect relationships in others.
-        self.all_file_data = []
-        for f in all_files:
-            parsed_data = self.graph_builder.parse_file(self.repo_path, f)
-            if "error" not in parsed_data:
-                self.all_file_data.append(parsed_data)
-        logger.info("Refreshed in-memory cache of all file data.")
-
-        # 5. CRITICAL: Re-link the entire graph using the fully updated cache and imports map.
-        logger.info("Re-linking the entire graph for calls and inheritance...")
-        self.graph_builder._create_all_function_calls(self.all_file_data, self.imports_map)
-        self.graph_builder._create_all_inheritance_links(self.all_file_data, self.imports_map)
-        logger.info(f"Graph refresh for change in {event_path_str} complete! ✅")
+        if Path(event_path).name == IGNORE_FILE:
+            # New rules may add or drop files; the re-index applies them.
+            self.rules = IgnoreRules.load(self.repo_path)
+        elif Path(event_path).suffix not in self.graph_builder.parsers or self.rules.is_ignored(Path(event_path)):
+            return
+        with self.lock:
+            self.pending.add(str(Path(event_path).resolve()))
+            if self.timer is not None:
+                self.timer.cancel()
+            self.timer = threading.Timer(self.debounce_interval, self._flush)
+            self.timer.daemon = True
+            self.timer.start()
+
+    def _flush(self):
+        """Re-indexes the repository for the coalesced burst of events and records what changed."""
+        with self.lock:
+            paths, self.pending, self.timer = sorted(self.pending), set(), None
+        if not paths:
+            return
+        with self.reindex_lock:
+            logger.info(f"{len(paths)} changed file(s) in {self.repo_path}, re-indexing incrementally")
+            started = time.time()
+            # New ignore rules may drop or bring back any file, so they re-check the whole repository.
+            full = any(Path(path).name == IGNORE_FILE for path in paths)
+            result = asyncio.run(self.graph_builder.reindex_path_async(self.repo_path, paths=None if full else paths))
+            with self.lock:
+                self.sequence += 1
+                event = {
+                    "sequence": self.sequence,
+                    "repo_path": str(self.repo_path),
+                    "time": datetime.now().isoformat(timespec="seconds"),
+                    "paths": paths,
+                    "duration_seconds": round(time.time() - started, 3),
+                }
+                event.update(result if result is not None else {"error": "Re-index failed, see the server log"})
+                self.events.append(event)
+            logger.info(f"Graph refresh for {self.repo_path} complete: {len(event.get('added', []))} added, "
+                        f"{len(event.get('changed', []))} changed, {len(event.get('removed', []))} removed ✅")
+
+    def get_events(self, since: int = 0) -> list:
+        """The recorded change events with a sequence number above `since`, oldest first."""
+        with self.lock:
+            return [event for event in self.events if event["sequence"] > since]
+
+    def cancel(self):
+        """Drops pending changes without re-indexing."""
+        with self.lock:
+            if self.timer is not None:
+                self.timer.cancel()
+            self.pending, self.timer = set(), None
 
     # The following methods are called by the watchdog observer when a file event occurs.
     def on_created(self, event):
-        if not event.is_directory and event.src_path.endswith('.py'):
-            self._debounce(event.src_path, lambda: self._handle_modification(event.src_path))
+        if not event.is_directory:
+            self._debounce(event.src_path)
 
     def on_modified(self, event):
-        if not event.is_directory and event.src_path.endswith('.py'):
-            self._debounce(event.src_path, lambda: self._handle_modification(event.src_path))
+        if not event.is_directory:
+            self._debounce(event.src_path)
 
     def on_deleted(self, event):
-        if not event.is_directory and event.src_path.endswith('.py'):
-            self._debounce(event.src_path, lambda: self._handle_modification(event.src_path))
+        if not event.is_directory:
+            self._debounce(event.src_path)
 
     def on_moved(self, event):
-        if not event.is_directory and event.src_path.endswith('.py'):
-            # A move is treated as a deletion at the old path and a creation at the new path.
-            self._debounce(event.src_path, lambda: self._handle_modification(event.src_path))
-            self._debounce(event.dest_path, lambda: self._handle_modification(event.dest_path))
+        if not event.is_directory:
+            # A move is a deletion at the old path and a creation at the new path.
+            self._debounce(event.src_path)
+            self._debounce(event.dest_path)
 
 
 class CodeWatcher:
@@ -148,9 +141,10 @@ class CodeWatcher:
         self.observer = Observer()
         self.watched_paths = set() # Keep track of paths already being watched.
         self.watches = {} # Store watch objects to allow unscheduling
+        self.handlers = {} # The event handler of each watched path, holding its change events
 
-    def watch_directory(self, path: str, perform_initial_scan: bool = True):
-        """Schedules a directory to be watched for changes."""
+    def watch_directory(self, path: str, debounce_interval: float = 2.0):
+        """Schedules a directory to be watched for changes, re-indexing after `debounce_interval` quiet seconds."""
         path_obj = Path(path).resolve()
         path_str = str(path_obj)
 
@@ -159,14 +153,16 @@ class CodeWatcher:
             return {"message": f"Path already being watched: {path_str}"}
         
         # Create a new, dedicated event handler for this specific repository path.
-        event_handler = RepositoryEventHandler(self.graph_builder, path_obj, perform_initial_scan=perform_initial_scan)
+        event_handler = RepositoryEventHandler(self.graph_builder, path_obj, debounce_interval=debounce_interval)
         
         watch = self.observer.schedule(event_handler, path_str, recursive=True)
         self.watches[path_str] = watch
+        self.handlers[path_str] = event_handler
         self.watched_paths.add(path_str)
         logger.info(f"Started watching for code changes in: {path_str}")
         
         return {"message": f"Started watching {path_str}."}
+
     def unwatch_directory(self, path: str):
         """Stops watching a directory for changes."""
         path_obj = Path(path).resolve()
@@ -179,6 +175,9 @@ class CodeWatcher:
         watch = self.watches.pop(path_str, None)
         if watch:
             self.observer.unschedule(watch)
+        handler = self.handlers.pop(path_str, None)
+        if handler:
+            handler.cancel()
         
         self.watched_paths.discard(path_str)
         logger.info(f"Stopped watching for code changes in: {path_str}")
@@ -188,6 +187,15 @@ class CodeWatcher:
         """Returns a list of all currently watched directory paths."""
         return list(self.watched_paths)
 
+    def get_events(self, path: str = None, since: int = 0) -> list:
+        """The change events of one watched path (or of all of them) newer than sequence number `since`."""
+        if path is not None:
+            handler = self.handlers.get(str(Path(path).resolve()))
+            if handler is None:
+                raise ValueError(f"Path not currently being watched: {path}")
+            return handler.get_events(since)
+        return [event for handler in self.handlers.values() for event in handler.get_events(since)]
+
     def start(self):
         """Starts the observer thread."""
         if not self.observer.is_alive():
@@ -6,6 +6,7 @@ import logging
 import importlib
 import stdlibs
 import sys
+import threading
 import traceback
 import os
 import re
@@ -22,11 +23,40 @@ from .core.jobs import JobManager, JobStatus
 from .core.watcher import CodeWatcher
 from .tools.graph_builder import GraphBuilder
 from .tools.code_finder import CodeFinder
+from .tools.dsm import render as render_dsm
+from .tools.graph_export import module_graph, render as render_subgraph
+from .tools.ignore import IgnoreRules
 from .tools.import_extractor import ImportExtractor
+from .tools.query_cache import DEFAULT_QUERY_CACHE_SIZE, QueryCache, cache_key
+from .tools.snippets import expand_tree
 from .utils.debug_log import debug_log
 
 logger = logging.getLogger(__name__)
 
+# Read-only tools answered from the graph alone; their results are cached until the graph changes.
+CACHED_TOOLS = {
+    "find_dead_code", "find_recursive_functions", "get_crate_info", "find_thread_crossing_types",
+    "find_await_holding_lock", "find_deadlock_candidates", "find_blocking_in_async", "find_interior_mutability",
+    "find_builders", "find_newtypes", "find_types_satisfying", "find_implementations", "find_transitive_callees",
+    "find_unreachable_code", "find_signature_impact", "find_unresolved_references", "function_history",
+    "call_history", "module_as_of", "find_hot_functions", "find_benchmarks", "find_untested_code",
+    "find_clippy_findings", "find_allocation_sites", "find_literal", "list_tasks", "get_doc_coverage",
+    "module_dependency_matrix", "type_hierarchy", "call_hierarchy", "find_similar_functions",
+    "suggest_module_boundaries", "find_central_functions", "find_method_shadowing", "find_glob_imports",
+    "get_canonical_paths", "items_under_feature", "estimate_monomorphization", "find_god_objects",
+    "find_taint_flows", "export_subgraph", "why_depends", "search_by_signature", "find_hotspots",
+    "find_duplicates", "find_unused_pub_items", "find_unhandled_results", "find_generic_instantiations",
+    "get_trait_impl_coverage", "get_public_api", "find_circular_module_dependencies", "find_code",
+    "analyze_code_relationships", "execute_cypher_query", "list_shards", "calculate_cyclomatic_complexity",
+    "find_most_complex_functions",
+}
+# Tools that neither write to the graph nor get cached. Any other tool moves the graph to a new generation.
+UNCACHED_READ_TOOLS = {
+    "check_job_status", "list_jobs", "list_indexed_repositories", "list_watched_paths", "get_watch_events",
+    "get_query_cache_stats", "preview_ignore_rules", "get_snippet", "list_imports", "visualize_graph_query", "semantic_search",
+    "ask_code_graph", "find_recently_changed_functions", "diff_revisions", "check_semver", "sync_to_remote",
+}
+
 class MCPServer:
     """
     The main MCP Server class.
@@ -57,6 +87,11 @@ class MCPServer:
 
         # Initialize managers for jobs and file watching.
         self.job_manager = JobManager()
+        # Clients asking for progress (a `progressToken` in a tool call's `_meta`) get the progress
+        # events of the job the call started as `notifications/progress` messages.
+        self.progress_tokens: Dict[str, Any] = {}
+        self.stdout_lock = threading.Lock()
+        self.job_manager.add_listener(self._notify_progress)
         
         # Get the current event loop to pass to thread-sensitive components like the graph builder.
         if loop is None:
@@ -72,10 +107,36 @@ class MCPServer:
         self.code_finder = CodeFinder(self.db_manager)
         self.import_extractor = ImportExtractor()
         self.code_watcher = CodeWatcher(self.graph_builder, self.job_manager)
+        self.query_cache = QueryCache(lambda: self.graph_builder.generation,
+                                      int(os.getenv("CGC_QUERY_CACHE_SIZE", DEFAULT_QUERY_CACHE_SIZE)))
         
         # Define the tool manifest that will be exposed to the AI assistant.
         self._init_tools()
 
+    def _send(self, message: Dict[str, Any]):
+        """Writes a JSON-RPC message to stdout; job threads and the request loop share it."""
+        with self.stdout_lock:
+            print(json.dumps(message), flush=True)
+
+    def _notify_progress(self, event: Dict[str, Any]):
+        """Forwards a job progress event to the client that asked for it, as an MCP progress notification."""
+        token = self.progress_tokens.get(event["job_id"])
+        if token is None:
+            return
+        if event["status"] in (JobStatus.COMPLETED.value, JobStatus.FAILED.value, JobStatus.CANCELLED.value):
+            self.progress_tokens.pop(event["job_id"], None)
+        detail = f" ({event['current_file']})" if event.get("current_file") else ""
+        self._send({
+            "jsonrpc": "2.0", "method": "notifications/progress",
+            "params": {
+                "progressToken": token,
+                "progress": event["processed_files"],
+                "total": event["total_files"],
+                "message": f"{event['phase']}: {event['processed_files']}/{event['total_files']} files{detail}",
+                "event": event,
+            }
+        })
+
     def _init_tools(self):
         """
         Defines the complete tool manifest for the LLM.
@@ -90,17 +151,24 @@ class MCPServer:
                     "type": "object",
                     "properties": {
                         "path": {"type": "string", "description": "Path to the directory or file to add."},
-                        "is_dependency": {"type": "boolean", "description": "Whether this code is a dependency.", "default": False}
+                        "is_dependency": {"type": "boolean", "description": "Whether this code is a dependency.", "default": False},
+                        "incremental": {"type": "boolean", "description": "Re-index an already indexed repository incrementally: only files whose content hash changed are parsed again and, within them, only added, removed or modified items are rewritten; removed files are dropped and the cross-file edges touching them are repaired. The job result lists the added, changed, removed and re-linked files.", "default": False},
+                        "sharded": {"type": "boolean", "description": "Index a multi-crate workspace as one shard per Cargo package, built in parallel, with references between crates stitched in a final pass. The job reports the status of each shard; see `list_shards` and `reindex_shard`.", "default": False},
+                        "max_workers": {"type": "integer", "description": "How many shards a sharded build indexes at once (defaults to CGC_SHARD_WORKERS, else 4)."},
+                        "dedup": {"type": "boolean", "description": "Record files byte-identical to one already indexed (vendored copies, duplicated generated code; 1 KiB or more) as File nodes with `alias_of` and File-[:ALIAS_OF]->File instead of parsing them again.", "default": True}
                     },
                     "required": ["path"]
                 }
             },
             "check_job_status": {
                 "name": "check_job_status",
-                "description": "Check the status and progress of a background job.",
+                "description": "Check the status and progress of a background job. `progress` is the latest structured progress event: `phase` (scanning, indexing, linking, annotating, syncing, completed or failed), `processed_files`/`total_files`, `nodes_written`, `current_file`, `eta_seconds` and, while files are indexed, `queues`: the depths of the `parse_queue` (files waiting for a parse worker) and `write_queue` (parsed files waiting for the graph writer) out of their `capacity`, and `backpressure_seconds` the parse workers spent waiting on a full write queue. Clients passing a `progressToken` in the `_meta` of the call starting a job also receive these events as `notifications/progress`.",
                 "inputSchema": {
                     "type": "object",
-                    "properties": { "job_id": {"type": "string", "description": "Job ID from a previous tool call"} },
+                    "properties": {
+                        "job_id": {"type": "string", "description": "Job ID from a previous tool call"},
+                        "since_event": {"type": "integer", "description": "Also list the kept progress events with a higher sequence number (0 for all of them)."}
+                    },
                     "required": ["job_id"]
                 }
             },
@@ -120,11 +188,11 @@ class MCPServer:
             },
             "analyze_code_relationships": {
                 "name": "analyze_code_relationships",
-                "description": "Analyze code relationships like 'who calls this function' or 'class hierarchy'. Supported query types include: find_callers, find_callees, find_all_callers, find_all_callees, find_importers, who_modifies, class_hierarchy, overrides, dead_code, call_chain, module_deps, variable_scope, find_complexity, find_functions_by_argument, find_functions_by_decorator.",
+                "description": "Analyze code relationships like 'who calls this function' or 'class hierarchy'. Supported query types include: find_callers, find_callees, find_all_callers, find_all_callees, find_importers, who_modifies, class_hierarchy, overrides, dead_code, call_chain, module_deps, variable_scope, find_complexity, find_functions_by_argument, find_functions_by_decorator, find_lock_users, find_variant_usages, find_field_accesses, find_type_usages (constructions, field accesses, trait impls, parameters/returns and generic instantiations of a struct or enum), find_by_trait_bound, find_closure_captures (closures capturing a local or 'Type.field', and whether by ref, mutable ref or move).",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "query_type": {"type": "string", "description": "Type of relationship query to run.", "enum": ["find_callers", "find_callees", "find_all_callers", "find_all_callees", "find_importers", "who_modifies", "class_hierarchy", "overrides", "dead_code", "call_chain", "module_deps", "variable_scope", "find_complexity", "find_functions_by_argument", "find_functions_by_decorator"]},
+                        "query_type": {"type": "string", "description": "Type of relationship query to run.", "enum": ["find_callers", "find_callees", "find_all_callers", "find_all_callees", "find_importers", "who_modifies", "class_hierarchy", "overrides", "dead_code", "call_chain", "module_deps", "variable_scope", "find_complexity", "find_functions_by_argument", "find_functions_by_decorator", "find_lock_users", "find_variant_usages", "find_field_accesses", "find_type_usages", "find_by_trait_bound", "find_closure_captures"]},
                         "target": {"type": "string", "description": "The function, class, or module to analyze."},
                         "context": {"type": "string", "description": "Optional: specific file path for precise results."} 
                     },
@@ -133,16 +201,19 @@ class MCPServer:
             },
             "watch_directory": {
                 "name": "watch_directory",
-                "description": "Performs an initial scan of a directory and then continuously monitors it for changes, automatically keeping the graph up-to-date. Ideal for projects under active development. Returns a job ID for the initial scan.",
+                "description": "Performs an initial scan of a directory and then continuously monitors it for changes, automatically keeping the graph up-to-date. Bursts of file events (save-all, branch switches) are coalesced into one incremental re-index once the directory has been quiet for `debounce_seconds`; use `get_watch_events` to see what each re-index changed. Ideal for projects under active development. Returns a job ID for the initial scan.",
                 "inputSchema": {
                     "type": "object",
-                    "properties": { "path": {"type": "string", "description": "Path to directory to watch"} },
+                    "properties": {
+                        "path": {"type": "string", "description": "Path to directory to watch"},
+                        "debounce_seconds": {"type": "number", "description": "How long the directory must be quiet before changes are re-indexed.", "default": 2.0}
+                    },
                     "required": ["path"]
                 }
             },
             "execute_cypher_query": {
                 "name": "execute_cypher_query",
-                "description": "Fallback tool to run a direct, read-only Cypher query against the code graph. Use this for complex questions not covered by other tools. The graph contains nodes representing code structures and relationships between them. **Schema Overview:**\n- **Nodes:** `Repository`, `File`, `Module`, `Class`, `Function`.\n- **Properties:** Nodes have properties like `name`, `path`, `cyclomatic_complexity` (on Function nodes), and `code`.\n- **Relationships:** `CONTAINS` (e.g., File-[:CONTAINS]->Function), `CALLS` (Function-[:CALLS]->Function or File-[:CALLS]->Function), `IMPORTS` (File-[:IMPORTS]->Module), `INHERITS` (Class-[:INHERITS]->Class).",
+                "description": "Fallback tool to run a direct, read-only Cypher query against the code graph. Use this for complex questions not covered by other tools. The graph contains nodes representing code structures and relationships between them. **Schema Overview:**\n- **Nodes:** `Repository`, `File`, `Module`, `Class`, `Function`, `Channel` (Rust channels), `Lock` (Mutex/RwLock/atomic fields and statics), `Variant` (Rust enum variants), `Field` (struct fields), `Closure` (Rust closures), `Impl` (Rust impl blocks with their generics and where clause), `AssociatedType` (trait associated types, including generic ones, and their impl bindings), `Macro` (Rust `macro_rules!` definitions), `Crate` (Cargo packages with `edition`, `declared_rust_version` and `estimated_msrv`), `Task` (`TODO`/`FIXME`/`HACK` comments with `kind`, `text`, `assignee` and git blame metadata), `Literal` (notable string, `url` and `number` literals by `value` and `kind`), `Diagnostic` (`cargo clippy` findings with `lint`, `level`, `message` and lines, from `run_clippy`), `Benchmark` (Criterion results with `mean_ns`, `median_ns`, `std_dev_ns`, `change` and `verdict`, from `import_benchmarks`), `Revision` and `VersionedItem` (the history recorded by `record_history`: revisions by `ordinal`, `label` and `commit`, and functions and types by `key` with `valid_from`/`valid_to` lists of revision ordinals, -1 for still valid, and `changed_in`), `UnresolvedReference` (placeholder target of calls no indexed function matched: `kind` call or method_call, `name`, receiver type `owner` or ''), `Shard` (a Cargo package of a repository indexed with `sharded: true`, with `status`, `file_count`, `nodes_written`, `duration_seconds` and `indexed_at`), `BlockingCall` (blocking APIs such as `std::thread::sleep`, by `category`). Revision snapshots indexed by `diff_revisions`, `check_semver` and `record_history` carry the same labels prefixed with `Snapshot` (`SnapshotFunction`, `SnapshotRepository`, ...) and a `revision` commit property.\n- **Properties:** Nodes have properties like `name`, `path`, `cyclomatic_complexity`, `nesting_depth`, `health_violations` (set by the code health report), `layering_violations` (on Repository nodes, from `.cgc-layers.json` rules), `body_hash` and `shape_minhash` (structural fingerprints), `docstring` (Rust `///`, `/** */` and `#[doc]` documentation of items; on File nodes, the module's `//!` docs preceded by those on its `mod` declaration), coverage from `import_coverage` (`covered_lines`, `coverable_lines`, `line_coverage`, `uncovered_lines` and `covered_by_tests` on Function nodes, the first three also on File nodes), CPU profile samples from `import_profile` (`total_samples`, `self_samples` and `sample_share` on Function nodes, `samples` on CALLS and MAY_CALL edges), `call_count` and `call_sites` (on CALLS edges: how many times the caller calls that callee and the `line:column-line:column` spans of those calls; each edge is one call site with its own span), spans `start_line`, `start_column`, `end_line`, `end_column`, `start_byte`, `end_byte` and the name's `column` (on symbol nodes; lines 1-based, columns 0-based bytes), `uid` and `content_hash` (on File, Function, Class and Variable nodes; a stable id derived from the repository, file, owner and name that survives re-indexing, and a fingerprint of the source), `crate_summary` (on Rust File nodes; the JSON of what crate-wide indexing passes read of the file, so re-indexing need not parse it again), `deferred` (on CALLS edges patched from a placeholder after their caller was indexed), `alias_of` (on File nodes recorded as aliases of a byte-identical indexed file, which holds their items), `source_compressed` (the compressed bytes of the `source` of items written with `CGC_COMPRESS_SNIPPETS` set; returned decompressed as `source`), `embedding` and `embedding_model` (used by semantic search), git blame metadata `last_commit`, `last_author`, `last_modified` (epoch seconds), `age_days` and `owners` (also on File nodes) and `receiver` (on Function nodes; `&self`, `&mut self`, `self` or `associated` for Rust methods), `kind` and `repr` (on Rust Class nodes; kind is struct/enum/trait/union, repr lists `#[repr(...)]` hints such as `C` or `packed`, `is_send`/`is_sync` hold the Send/Sync analysis, `is_builder` marks builder types, `newtype_kind` and `wraps_type` describe newtypes and wrappers, `derives` lists `#[derive]` traits, `required_methods`/`provided_methods` and `required_consts`/`provided_consts` split trait items by whether they have a default), `signature` and `public_paths` (on Rust Function and Class nodes; the declaration without its body and the paths the crate exposes the item under), `is_async` and `is_async_entry_point` (on Function nodes), `pagerank` and `betweenness` (on Function nodes; call graph centrality), `shadows_traits` (on inherent Rust methods named like a method of a trait their type implements), `call_names` and `call_lines` (on Function nodes; every call made, including to code outside the graph), `cfg` and `features` (on Rust Function and Class nodes; the `#[cfg]` conditions they are compiled under and the Cargo features those require; files of gated modules carry `module_cfg` and `module_features`), `interior_mutability` (on Field and Variable nodes; the Cell/RefCell/UnsafeCell/OnceCell/Atomic kind), and `code`.\n- **Relationships:** `CONTAINS` (e.g., File-[:CONTAINS]->Function), `CALLS` (Function-[:CALLS]->Function or File-[:CALLS]->Function; `discarded` tells how an ignored Rust return value was dropped; `shadowing` and `ambiguous_with` mark calls where an inherent and a trait method of the same name both apply), `IMPORTS` (File-[:IMPORTS {line_number, is_reexport}]->Module; Rust glob imports also carry `glob_target`, `glob_provided` and `glob_used`), `INCLUDES` (File-[:INCLUDES]->File for Rust `include!`), `INHERITS` (Class-[:INHERITS]->Class), `SENDS_TO`/`RECEIVES_FROM` (Function-[:SENDS_TO]->Channel), `LOCKS` (Function-[:LOCKS]->Lock), `GUARDS` (Lock-[:GUARDS]->Class|Variable), `WRAPPED_IN` (Class-[:WRAPPED_IN {wrappers}]->Class for types stored behind Box/Rc/Arc/RefCell fields), `VARIANT_OF` (Variant-[:VARIANT_OF]->Class), `CONSTRUCTS`/`MATCHES` (Function-[:MATCHES]->Variant), `HAS_FIELD` (Class-[:HAS_FIELD]->Field), `READS_FIELD`/`WRITES_FIELD` (Function-[:WRITES_FIELD]->Field), `HAS_IMPL` (Class-[:HAS_IMPL]->Impl-[:CONTAINS]->Function for Rust methods), `IMPLEMENTS` (Impl-[:IMPLEMENTS]->Class for the implemented trait), `DECLARES_TYPE`/`BINDS_TYPE`/`BINDS` (Class-[:DECLARES_TYPE]->AssociatedType<-[:BINDS]-AssociatedType<-[:BINDS_TYPE]-Impl), `MAY_CALL` (Function-[:MAY_CALL {via_param}]->Closure|Function for closures passed to callable parameters, closures have their own CALLS edges; Function-[:MAY_CALL {dispatch, via_traits}]->Function for trait-dispatched calls to each implementation), `GENERATED_BY` (Function|Class-[:GENERATED_BY]->Macro for items expanded from a `macro_rules!` invocation in the same file), `PART_OF` (File-[:PART_OF]->Crate), `HAS_TASK` (Function-[:HAS_TASK]->Task; files CONTAIN their tasks), `HAS_DIAGNOSTIC` (Function|File-[:HAS_DIAGNOSTIC]->Diagnostic; functions also list their `clippy_lints`), `CONTAINS_LITERAL` (Function|File-[:CONTAINS_LITERAL {line_number, in_macro, constant}]->Literal), `COVERS` (Function-[:COVERS {covered_lines, line_coverage}]->Function from a test to the functions its run executed), `BENCHMARKS` (Benchmark-[:BENCHMARKS]->Function), `CALLED_DURING` (VersionedItem-[:CALLED_DURING {valid_from, valid_to}]->VersionedItem), `REFERENCES_UNRESOLVED` (caller-[:REFERENCES_UNRESOLVED {line_number, column, full_call_name, args}]->UnresolvedReference, turned into CALLS once the target is indexed), `HAS_SHARD` (Repository-[:HAS_SHARD]->Shard), `ALIAS_OF` (File-[:ALIAS_OF]->File from a byte-identical copy to the file indexed in its place), `USES` (File-[:USES {imports}]->File between Rust modules of a crate; files carry their `module_path`), `MOVES_INTO_THREAD` (Closure-[:MOVES_INTO_THREAD {variable, type, is_send}]->Class for values moved into spawned threads), `HOLDS_LOCK_ACROSS_AWAIT` (Function-[:HOLDS_LOCK_ACROSS_AWAIT {lock_line, await_line}]->Lock), `ACQUIRED_BEFORE` (Lock-[:ACQUIRED_BEFORE {function, line_number, via}]->Lock when the second lock is taken while the first is held), `BLOCKS_ON` (Function-[:BLOCKS_ON {line_number, in_async}]->BlockingCall), `BORROWS` (Function-[:BORROWS {method, mode, mutable}]->Field|Variable for Cell/RefCell/atomic accesses), `BUILDS` (Class-[:BUILDS {build_method, setters, entry_points}]->Class from a builder to the type it builds), `WRAPS` (Class-[:WRAPS {field}]->Class from a newtype to its inner type), `REFERENCES_TYPE` (Function|Class|File-[:REFERENCES_TYPE {role, type_text, generic_in, line_number}]->Class for parameter, return, field, variable, construction and expression mentions of a type), `INSTANTIATES` (Function|File-[:INSTANTIATES {instantiation, type_arguments, inferred, line_number}]->Class|Function for concrete instantiations of generic items; these carry `generic_params`), `CAPTURES` (Closure-[:CAPTURES {place, mode, reason}]->Variable|Field with mode ref, mut_ref or move; closures also list `captured_places` and `capture_modes`).",
                 "inputSchema": {
                     "type": "object",
                     "properties": { "cypher_query": {"type": "string", "description": "The read-only Cypher query to execute."} },
@@ -184,209 +255,2106 @@ class MCPServer:
                     }
                 }
             },
-            "calculate_cyclomatic_complexity": {
-                "name": "calculate_cyclomatic_complexity",
-                "description": "Calculate the cyclomatic complexity of a specific function to measure its complexity.",
+            "preview_ignore_rules": {
+                "name": "preview_ignore_rules",
+                "description": "Dry-runs the ignore rules of a repository without indexing: the effective rules in order with their `source` (`default`, the `CGC_IGNORE` environment variable or `.cgcignore`; the last matching rule wins and `!pattern` includes again), how many supported files are indexed and excluded, and examples of each with the rule excluding them.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "function_name": {"type": "string", "description": "The name of the function to analyze."},
-                        "file_path": {"type": "string", "description": "Optional: The full path to the file containing the function for a more specific query."} 
+                        "path": {"type": "string", "description": "The repository root holding the `.cgcignore` file."},
+                        "limit": {"type": "integer", "description": "The maximum number of included and of excluded files listed.", "default": 50}
                     },
-                    "required": ["function_name"]
+                    "required": ["path"]
                 }
             },
-            "find_most_complex_functions": {
-                "name": "find_most_complex_functions",
-                "description": "Find the most complex functions in the codebase based on cyclomatic complexity.",
+            "sync_to_remote": {
+                "name": "sync_to_remote",
+                "description": "Mirrors an indexed repository to the remote (shared) Neo4j set by `CGC_REMOTE_NEO4J_URI`, `CGC_REMOTE_NEO4J_USERNAME` and `CGC_REMOTE_NEO4J_PASSWORD`, shipping only the nodes and relationships that changed since its last sync. Re-indexes of a repository sync it on their own when a remote is set. Returns the counts of the delta: `upsert_nodes`, `delete_nodes`, `create_relationships`, `delete_relationships`, `unchanged_nodes` and `unchanged_relationships`.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "limit": {"type": "integer", "description": "The maximum number of complex functions to return.", "default": 10}
+                        "repo_path": {"type": "string", "description": "The indexed repository to sync."},
+                        "full": {"type": "boolean", "description": "Remove the repository from the remote and ship all of it again, e.g. after the remote was reset.", "default": False},
+                        "dry_run": {"type": "boolean", "description": "Only compute the delta, without writing to the remote.", "default": False}
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            "list_shards": {
+                "name": "list_shards",
+                "description": "Lists the shards of repositories indexed with `sharded: true` (one per Cargo package, plus a root shard for files outside any package) with their `status`, `file_count`, `nodes_written`, `duration_seconds`, `errors` and `indexed_at` (epoch seconds).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {"type": "string", "description": "An indexed repository; all repositories if omitted."}
                     }
                 }
             },
-            "list_indexed_repositories": {
-                "name": "list_indexed_repositories",
-                "description": "List all indexed repositories.",
+            "reindex_shard": {
+                "name": "reindex_shard",
+                "description": "Re-indexes one shard (Cargo package) of a sharded repository incrementally, re-linking the files of other shards that reference it. Returns a job ID; its result lists the added, changed, removed and re-linked files.",
                 "inputSchema": {
                     "type": "object",
-                    "properties": {}
+                    "properties": {
+                        "repo_path": {"type": "string", "description": "The indexed repository."},
+                        "shard": {"type": "string", "description": "The shard's package name or root directory."}
+                    },
+                    "required": ["repo_path", "shard"]
                 }
             },
-            "delete_repository": {
-                "name": "delete_repository",
-                "description": "Delete an indexed repository from the graph.",
+            "get_query_cache_stats": {
+                "name": "get_query_cache_stats",
+                "description": "Reports the cache of read-only query results: `hits`, `misses`, cached `entries`, and the graph `generation` they were computed from. Cached results are dropped whenever the graph is written (indexing, incremental updates, imported reports). The cache size is set with CGC_QUERY_CACHE_SIZE (0 disables it).",
+                "inputSchema": {"type": "object", "properties": {}}
+            },
+            "get_watch_events": {
+                "name": "get_watch_events",
+                "description": "Lists the re-indexes a watched directory went through, oldest first: when each ran, the file paths whose events it coalesced, the files `added`, `changed` and `removed`, the dependent files `relinked`, item counts (`items`) and errors. Pass the last seen `sequence` as `since` to get only newer events.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "repo_path": {"type": "string", "description": "The path of the repository to delete."} 
+                        "path": {"type": "string", "description": "A watched directory; all watched directories if omitted."},
+                        "since": {"type": "integer", "description": "Only return events with a higher sequence number.", "default": 0}
+                    }
+                }
+            },
+            "find_unresolved_references": {
+                "name": "find_unresolved_references",
+                "description": "List the calls that resolve to no indexed function, grouped by placeholder (`UnresolvedReference` nodes typed by `kind`: `call` or `method_call`, with the receiver type as `owner`), most referenced first with example call sites. These are calls into code outside the graph (std, unindexed crates) or into code not indexed yet; placeholders are patched into CALLS edges (marked `deferred`) as soon as a matching function is indexed.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "description": "Optional: only placeholders with this name or receiver type."},
+                        "kind": {"type": "string", "description": "Optional: `call` or `method_call`."},
+                        "file_path": {"type": "string", "description": "Optional: only references from files ending with this path."},
+                        "limit": {"type": "integer", "description": "Maximum number of placeholders.", "default": 50}
+                    }
+                }
+            },
+            "get_snippet": {
+                "name": "get_snippet",
+                "description": "Get the exact current source of a symbol (function, type, variable, field, variant, impl, macro or closure) from its recorded byte span, with its `path:line:column` location for jumping to the definition in an editor and its full line/column/byte span. Flags snippets whose code changed since indexing as `stale`.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "description": "The symbol (`name` or `Type::name`)."},
+                        "uid": {"type": "string", "description": "Optional: the stable uid of a node from an earlier result, instead of a name."},
+                        "file_path": {"type": "string", "description": "Optional: only symbols in files ending with this path."},
+                        "context_lines": {"type": "integer", "description": "Whole lines of context to add around the snippet.", "default": 0}
+                    }
+                }
+            },
+            "record_history": {
+                "name": "record_history",
+                "description": "Record the history of a git repository's code graph over a sequence of revisions (e.g. release tags), replacing the history recorded before: each revision is indexed like in `diff_revisions`, and every function, type and call edge gets the ranges of revisions it existed in (VersionedItem nodes and CALLED_DURING edges with `valid_from`/`valid_to` revision ordinals). Enables `function_history`, `call_history` and `module_as_of`. Indexing many revisions takes a while.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {"type": "string", "description": "Path to the git repository."},
+                        "revisions": {"type": "array", "items": {"type": "string"}, "description": "Revisions (tags, branches or commits) oldest first, e.g. ['v1.0', 'v1.1', 'v1.2', 'HEAD'], or a single range like ['v1.0..HEAD'] to take its first-parent commits (the 20 latest)."}
                     },
-                    "required": ["repo_path"]
+                    "required": ["repo_path", "revisions"]
                 }
             },
-            "visualize_graph_query": {
-                "name": "visualize_graph_query",
-                "description": "Generates a URL to visualize the results of a Cypher query in the Neo4j Browser. The user can open this URL in their web browser to see the graph visualization.",
+            "function_history": {
+                "name": "function_history",
+                "description": "Show when a function or type appeared, disappeared and changed across the history recorded with `record_history`.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "cypher_query": {"type": "string", "description": "The Cypher query to visualize."}
+                        "name": {"type": "string", "description": "The function or type (`name` or `Type::method`)."},
+                        "repo_path": {"type": "string", "description": "Optional: only the history of this repository."}
                     },
-                    "required": ["cypher_query"]
+                    "required": ["name"]
                 }
             },
-            "list_watched_paths": {
-                "name": "list_watched_paths",
-                "description": "Lists all directories currently being watched for live file changes.",
-                "inputSchema": {"type": "object", "properties": {}}
+            "call_history": {
+                "name": "call_history",
+                "description": "Answer 'when did this function start calling that one': the revision ranges, from the history recorded with `record_history`, over which a function called a given callee, or each of its callees.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "caller": {"type": "string", "description": "The calling function (`name` or `Type::method`)."},
+                        "callee": {"type": "string", "description": "Optional: the called function; all callees when omitted."},
+                        "repo_path": {"type": "string", "description": "Optional: only the history of this repository."}
+                    },
+                    "required": ["caller"]
+                }
             },
-            "unwatch_directory": {
-                "name": "unwatch_directory",
-                "description": "Stops watching a directory for live file changes.",
+            "module_as_of": {
+                "name": "module_as_of",
+                "description": "Show a file or directory of a repository as of a revision recorded with `record_history` (e.g. 'show me this module as of tag v1.2'): its functions and types and their calls at that revision, plus the path of the revision's indexed snapshot for further queries.",
                 "inputSchema": {
                     "type": "object",
                     "properties": {
-                        "path": {"type": "string", "description": "The absolute path of the directory to stop watching."}
+                        "repo_path": {"type": "string", "description": "Path to the git repository."},
+                        "path": {"type": "string", "description": "File or directory relative to the repository root, e.g. 'src/net' or 'src/lib.rs'."},
+                        "revision": {"type": "string", "description": "The recorded revision, by the name it was recorded with or a commit prefix."}
+                    },
+                    "required": ["repo_path", "path", "revision"]
+                }
+            },
+            "import_profile": {
+                "name": "import_profile",
+                "description": "Import a CPU profile in collapsed stack format (from `cargo flamegraph`, `inferno-collapse-perf` or `stackcollapse-perf.pl` run on `perf script` output), replacing the previous one. Frames are matched to indexed functions, which get `total_samples`, `self_samples` and `sample_share`, and the call edges between them get `samples`. Use `export_subgraph` with `min_samples` for hot-path-only call graphs.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "profile_path": {"type": "string", "description": "Path to the collapsed stack file (`frame;frame;... count` per line)."}
+                    },
+                    "required": ["profile_path"]
+                }
+            },
+            "find_hot_functions": {
+                "name": "find_hot_functions",
+                "description": "List the hottest functions of the imported CPU profile by share of samples, with their self samples and hottest calls, to prioritize optimization work.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "limit": {"type": "integer", "description": "Maximum number of functions.", "default": 20},
+                        "min_share": {"type": "number", "description": "Only functions on at least this fraction of all samples (0-1).", "default": 0.0}
+                    }
+                }
+            },
+            "import_benchmarks": {
+                "name": "import_benchmarks",
+                "description": "Import Criterion benchmark results (`target/criterion`) into the graph: each benchmark becomes a Benchmark node with its latest mean, median and standard deviation (in nanoseconds) and, when Criterion compared it with a previous run, its relative `change` and `verdict` (regressed, improved or unchanged). Benchmarks are linked with BENCHMARKS to the functions named in their id, preferring those called by the function registering them.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string", "description": "A Criterion output directory, or the crate or workspace whose target/criterion holds the results."}
+                    },
+                    "required": ["path"]
+                }
+            },
+            "find_benchmarks": {
+                "name": "find_benchmarks",
+                "description": "List imported Criterion benchmarks with their latest timings, change against the previous run and the functions they measure, largest slowdowns first. Use it to see how fast a function is or which code regressed.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "function_name": {"type": "string", "description": "Optional: only benchmarks measuring this function."},
+                        "verdict": {"type": "string", "enum": ["regressed", "improved", "unchanged"], "description": "Optional: only benchmarks with this verdict."},
+                        "limit": {"type": "integer", "description": "Maximum number of benchmarks.", "default": 50}
+                    }
+                }
+            },
+            "import_coverage": {
+                "name": "import_coverage",
+                "description": "Import a test coverage report of indexed Rust code, in LCOV format (`cargo llvm-cov --lcov`, `grcov -t lcov`) or llvm-cov JSON (`cargo llvm-cov --json`). Sets `covered_lines`, `coverable_lines`, `line_coverage`, `uncovered_lines` and `covered_by_tests` on the functions and files it covers. With `test_name`, the report is taken to come from running that single test, which gets a COVERS edge to every function it executed.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "report_path": {"type": "string", "description": "Path to the LCOV (.info/.lcov) or llvm-cov JSON report."},
+                        "source_root": {"type": "string", "description": "Optional: directory relative source paths of the report are resolved against, by default the report's directory."},
+                        "test_name": {"type": "string", "description": "Optional: the #[test] function whose run produced the report."}
+                    },
+                    "required": ["report_path"]
+                }
+            },
+            "find_untested_code": {
+                "name": "find_untested_code",
+                "description": "Find the functions reachable from an entry point (by default `main`) through the call graph that no test executes, according to coverage imported with `import_coverage`, each with its call path. Reachable functions in files without coverage data are listed separately.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "entry_point": {"type": "string", "description": "The function to start from.", "default": "main"},
+                        "file_path": {"type": "string", "description": "Optional: the file of the entry point, when several functions share its name."},
+                        "max_depth": {"type": "integer", "description": "Maximum call depth to follow.", "default": 10}
+                    }
+                }
+            },
+            "run_clippy": {
+                "name": "run_clippy",
+                "description": "Run `cargo clippy --message-format=json` on an indexed Rust crate or workspace and attach its diagnostics to the graph, replacing those of a previous run: each finding becomes a Diagnostic node (lint, level, message, lines) linked with HAS_DIAGNOSTIC from the innermost function containing it, or from its file. Needs cargo with clippy installed; builds the crate.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string", "description": "Path to the crate or workspace (the directory holding Cargo.toml)."}
                     },
                     "required": ["path"]
                 }
+            },
+            "find_clippy_findings": {
+                "name": "find_clippy_findings",
+                "description": "List the functions with clippy (and rustc lint) diagnostics attached by `run_clippy`, most complex first, e.g. complex functions that also have clippy warnings. Filter by lint or minimum cyclomatic complexity.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "lint": {"type": "string", "description": "Optional: only this lint, e.g. 'needless_return' or 'clippy::needless_return'."},
+                        "min_complexity": {"type": "integer", "description": "Optional: only functions with at least this cyclomatic complexity."},
+                        "file_path": {"type": "string", "description": "Optional: only functions of this file."},
+                        "limit": {"type": "integer", "description": "Maximum number of functions.", "default": 50}
+                    }
+                }
+            },
+            "find_allocation_sites": {
+                "name": "find_allocation_sites",
+                "description": "List the heap allocation sites reachable from a hot function: calls such as `Box::new`, `Vec::new`/`with_capacity`, `String::from`, `to_string`, `Rc::new` and `Arc::new` in the function and in everything it calls transitively (trait-dispatched calls and closures included), each with its call path and depth, plus counts per kind of allocation.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "function_name": {"type": "string", "description": "The hot function to start from."},
+                        "file_path": {"type": "string", "description": "Optional: the file of the function, to disambiguate."},
+                        "max_depth": {"type": "integer", "description": "Maximum call depth to follow.", "default": 5},
+                        "expand_dispatch": {"type": "boolean", "description": "Follow trait-dispatched calls to every implementation and closures passed as arguments.", "default": True}
+                    },
+                    "required": ["function_name"]
+                }
+            },
+            "find_literal": {
+                "name": "find_literal",
+                "description": "Find which functions contain a literal: an error message such as \"Division by zero\", a URL or a magic number. Notable string, URL and number literals (not 0, 1, 2, 10 or 100) in function bodies, macro arguments and `const`/`static` values are indexed as Literal nodes linked with CONTAINS_LITERAL.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "text": {"type": "string", "description": "The literal or part of it, e.g. 'division by zero' or '10_000'."},
+                        "kind": {"type": "string", "description": "Optional: only literals of this kind.", "enum": ["string", "url", "number"]},
+                        "exact": {"type": "boolean", "description": "Match the whole literal exactly instead of a case-insensitive substring.", "default": False},
+                        "limit": {"type": "integer", "description": "Maximum number of results.", "default": 50}
+                    },
+                    "required": ["text"]
+                }
+            },
+            "list_tasks": {
+                "name": "list_tasks",
+                "description": "List the open `TODO`, `FIXME` and `HACK` comments indexed as Task nodes, with the function and module they sit in, the assignee of `TODO(name)` and the git author and age of the line. Filter by subsystem (a module path or file name), kind or author; the oldest tasks come first.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "subsystem": {"type": "string", "description": "Optional: a module path such as 'crate::net' or 'net' (submodules included), or a file name."},
+                        "kinds": {"type": "array", "items": {"type": "string", "enum": ["TODO", "FIXME", "HACK"]}, "description": "Optional: only these kinds of tasks."},
+                        "author": {"type": "string", "description": "Optional: only tasks last touched by this git author (name or email)."}
+                    }
+                }
+            },
+            "get_doc_coverage": {
+                "name": "get_doc_coverage",
+                "description": "Report the documentation coverage of the public API of Rust crates: the percentage of public functions, types, `pub` methods and fields, enum variants, trait methods and exported macros with doc comments, per crate and per module, listing every undocumented item. With `min_coverage`, tells whether each crate reaches the threshold and flags the modules below it.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "crate_name": {"type": "string", "description": "Optional: the crate to report on; all indexed crates by default."},
+                        "min_coverage": {"type": "number", "description": "Required coverage in percent (0-100).", "default": 0}
+                    }
+                }
+            },
+            "module_dependency_matrix": {
+                "name": "module_dependency_matrix",
+                "description": "Return the dependency structure matrix (DSM) of the Rust modules of a crate: for each pair of modules, how many `use` statements, calls, type usages, field and variant accesses and trait impls the row module has on the column module. Modules are ordered providers first, so a layered crate gives a lower-triangular matrix; counts above the diagonal are listed as `feedback` and modules depending on each other as `cycles`. Returns JSON, or the bare matrix as CSV with `format: csv`.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "crate_name": {"type": "string", "description": "Optional: the crate to analyze; all indexed crates by default."},
+                        "depth": {"type": "integer", "description": "Optional: collapse modules to this many path segments, e.g. 2 turns `crate::net::tcp` into `crate::net`."},
+                        "format": {"type": "string", "description": "Output format.", "enum": ["json", "csv"], "default": "json"}
+                    }
+                }
+            },
+            "type_hierarchy": {
+                "name": "type_hierarchy",
+                "description": "Return the type hierarchy of a Rust trait or type as a tree: upward, the supertrait chain of a trait (e.g. `Shape: Area + Perimeter + Display`) or the traits a type implements and derives; downward, every implementor of a trait (handwritten, derived and blanket impls) and its subtraits. Each tree also comes rendered as indented text.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "description": "The trait or type name or path, e.g. 'Shape' or 'traits::Shape'; traits outside the graph such as 'Clone' work too."},
+                        "depth": {"type": "integer", "description": "Number of levels to expand (1-10).", "default": 5}
+                    },
+                    "required": ["name"]
+                }
+            },
+            "call_hierarchy": {
+                "name": "call_hierarchy",
+                "description": "Return the call hierarchy of a function in the shape of LSP `callHierarchy/incomingCalls` and `outgoingCalls`: nested callers or callees as CallHierarchyItems (name, kind, detail, uri, zero-based range and selectionRange) with the `fromRanges` of each call site, `depth` levels deep. Recursive calls are marked and not expanded.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "symbol": {"type": "string", "description": "The function, as 'name' or 'Type::method'."},
+                        "direction": {"type": "string", "enum": ["incoming", "outgoing"], "description": "Callers (incoming) or callees (outgoing).", "default": "incoming"},
+                        "depth": {"type": "integer", "description": "Number of levels to expand (1-10).", "default": 3},
+                        "file_path": {"type": "string", "description": "Optional: the file defining the function, to disambiguate."}
+                    },
+                    "required": ["symbol"]
+                }
+            },
+            "find_similar_functions": {
+                "name": "find_similar_functions",
+                "description": "Find functions elsewhere in the codebase that are structurally or semantically similar to a given function, combining normalized AST shape (exact body hash or MinHash of token shingles) with embedding similarity. Useful for 'has someone already written this helper?' checks before writing new code.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "function_name": {"type": "string", "description": "The name of the function to compare against."},
+                        "file_path": {"type": "string", "description": "Optional: the file defining the function, to disambiguate."},
+                        "limit": {"type": "integer", "description": "Maximum number of similar functions to return.", "default": 10},
+                        "min_score": {"type": "number", "description": "Minimum combined similarity, from 0 to 1.", "default": 0.5}
+                    },
+                    "required": ["function_name"]
+                }
+            },
+            "suggest_module_boundaries": {
+                "name": "suggest_module_boundaries",
+                "description": "Cluster functions and types by call and usage density (Louvain community detection, methods stay with their type) and compare the clusters with the actual module structure. Returns the modularity of the modules versus the detected communities, communities that span several modules, and items that are linked more to another module than to their own and would sit better there.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "resolution": {"type": "number", "description": "Louvain resolution; higher values give more, smaller communities.", "default": 1.0},
+                        "min_weight": {"type": "integer", "description": "Minimum number of calls and uses linking an item to the suggested module.", "default": 2},
+                        "limit": {"type": "integer", "description": "Maximum number of communities and suggestions to return.", "default": 50}
+                    }
+                }
+            },
+            "find_central_functions": {
+                "name": "find_central_functions",
+                "description": "Rank functions by call graph centrality computed at indexing time: `pagerank` surfaces core functions that many important functions depend on, `betweenness` surfaces glue code that many call chains pass through. A good default for which code to read or show first when getting to know a codebase.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "metric": {"type": "string", "enum": ["pagerank", "betweenness"], "description": "The centrality measure to rank by.", "default": "pagerank"},
+                        "limit": {"type": "integer", "description": "Maximum number of functions to return.", "default": 20},
+                        "file_path": {"type": "string", "description": "Optional: only rank functions defined in this file."}
+                    }
+                }
+            },
+            "find_method_shadowing": {
+                "name": "find_method_shadowing",
+                "description": "Find Rust types with an inherent method and a trait method of the same name that both apply (e.g. a type with its own `add` plus `impl Add`), with every ambiguous call site and which one wins: the inherent method for `value.add(..)` and `Type::add(..)`, the trait method only when the call names the trait.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "type_name": {"type": "string", "description": "Optional: only check this type."}
+                    }
+                }
+            },
+            "find_glob_imports": {
+                "name": "find_glob_imports",
+                "description": "Report Rust glob imports (`use foo::*`, e.g. a `prelude` module) resolved against the module tree: the names each glob brings into scope, the ones the importing module actually uses (for `pub use` globs, also through the re-export) and the explicit import to replace it with. Globs that pull in the most unused names come first.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "file_path": {"type": "string", "description": "Optional: only report globs imported in this file."}
+                    }
+                }
+            },
+            "get_canonical_paths": {
+                "name": "get_canonical_paths",
+                "description": "For a Rust function, type or method, return its definition path, every public path it is reachable under (e.g. both `my_crate::Person` and `my_crate::structs_enums::Person`), following `pub mod` chains and `pub use` re-exports across modules and indexed crates, and the recommended import: the shortest public path, plus its `crate::` form for use inside the defining crate.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "symbol": {"type": "string", "description": "The item name, optionally qualified to disambiguate: 'Person', 'structs_enums::Person', 'crate::Person' or 'Person::new'."}
+                    },
+                    "required": ["symbol"]
+                }
+            },
+            "items_under_feature": {
+                "name": "items_under_feature",
+                "description": "List everything compiled only with a given Cargo feature (items under `#[cfg(feature = ...)]` directly, through an enclosing impl or module, or through a gated `mod` declaration), plus the always-compiled code that calls, mentions or instantiates those items. The latter catches 'works only with feature X' surprises.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "feature": {"type": "string", "description": "The Cargo feature name, e.g. 'serde'."}
+                    },
+                    "required": ["feature"]
+                }
+            },
+            "estimate_monomorphization": {
+                "name": "estimate_monomorphization",
+                "description": "Estimate monomorphization bloat for binary-size-sensitive Rust code: how many copies the compiler generates of each generic function and method of a generic type, from the generic instantiations in the graph (followed through generic callers), ranked by copies times lines. Each entry lists the concrete instantiations; `_` marks type arguments that could not be inferred.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "limit": {"type": "integer", "description": "Maximum number of functions to return.", "default": 20},
+                        "file_path": {"type": "string", "description": "Optional: only rank functions defined in this file."}
+                    }
+                }
+            },
+            "find_god_objects": {
+                "name": "find_god_objects",
+                "description": "Find 'god objects': types with an excessive number of inherent methods, trait impls or fields, with their fan-in (outside code calling their methods or mentioning them) and fan-out (code their methods call or mention). Useful to spot types that should be split. Thresholds are configurable.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "max_methods": {"type": "integer", "description": "Maximum number of inherent methods.", "default": 20},
+                        "max_trait_impls": {"type": "integer", "description": "Maximum number of implemented traits.", "default": 10},
+                        "max_fields": {"type": "integer", "description": "Maximum number of fields.", "default": 15},
+                        "limit": {"type": "integer", "description": "Maximum number of types to report.", "default": 50}
+                    }
+                }
+            },
+            "find_taint_flows": {
+                "name": "find_taint_flows",
+                "description": "Taint analysis over the call graph: find chains along which data read by source calls (user input, files, network; e.g. `read_file_contents`, `std::env::args`) can reach sink calls (e.g. `Command::new`, SQL execution). Data is followed up through return values to callers and down through arguments to callees. Each finding lists the source call, the sink call and the intermediate functions. Sources and sinks default to common Rust APIs.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "sources": {"type": "array", "items": {"type": "string"}, "description": "Optional: source call patterns, e.g. ['read_file_contents', 'std::env::var']. Matched by trailing path segments."},
+                        "sinks": {"type": "array", "items": {"type": "string"}, "description": "Optional: sink call patterns, e.g. ['Command::new', 'Connection::execute']."},
+                        "max_depth": {"type": "integer", "description": "Maximum number of calls to follow up and down from a source.", "default": 4},
+                        "limit": {"type": "integer", "description": "Maximum number of findings.", "default": 50}
+                    }
+                }
+            },
+            "export_subgraph": {
+                "name": "export_subgraph",
+                "description": "Export a focused subgraph for diagrams instead of the whole graph: everything reachable from a function, a module or a crate over the chosen edge types (CALLS by default) up to a depth, following edges outwards, inwards or both. Module and crate exports stay inside that module or crate unless `include_external` is set. Relationships of one type between the same two nodes are merged into one edge with a `weight` (e.g. its number of call sites). Returns the nodes and edges as JSON, a Mermaid flowchart with `format: mermaid`, a GraphML document with typed attributes (for Gephi or yEd) with `format: graphml`, or a Graphviz DOT digraph with nodes colored by kind (fn, method, struct, enum, trait, module) with `format: dot`. `modules: true` collapses the nodes to the files they are in for a module graph; `cluster_by_module: true` draws the DOT nodes of each file inside a box.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "root": {"type": "string", "description": "The function (`name` or `Type::method`), module path (`crate::net` or `net`) or crate name to start from."},
+                        "root_kind": {"type": "string", "description": "What `root` names.", "enum": ["function", "module", "crate"], "default": "function"},
+                        "edge_types": {"type": "array", "items": {"type": "string"}, "description": "Relationship types to follow, e.g. ['CALLS', 'MAY_CALL', 'REFERENCES_TYPE'].", "default": ["CALLS"]},
+                        "depth": {"type": "integer", "description": "Maximum number of hops from the root (1-10).", "default": 2},
+                        "direction": {"type": "string", "description": "Follow edges out of the root (callees), into it (callers) or both.", "enum": ["out", "in", "both"], "default": "out"},
+                        "file_path": {"type": "string", "description": "Optional: the file of the root function, to disambiguate."},
+                        "include_external": {"type": "boolean", "description": "Let module and crate exports leave the module or crate.", "default": False},
+                        "max_nodes": {"type": "integer", "description": "Maximum number of nodes to export.", "default": 500},
+                        "min_samples": {"type": "integer", "description": "Optional: only follow call edges with at least this many samples of the imported profile (hot paths)."},
+                        "format": {"type": "string", "description": "Output format.", "enum": ["json", "mermaid", "graphml", "dot"], "default": "json"},
+                        "modules": {"type": "boolean", "description": "Collapse the nodes to the files (modules) they are in.", "default": False},
+                        "cluster_by_module": {"type": "boolean", "description": "DOT output: group the nodes of each file in a cluster.", "default": False}
+                    },
+                    "required": ["root"]
+                }
+            },
+            "check_semver": {
+                "name": "check_semver",
+                "description": "Classify the changes between two git revisions of a Rust crate by semver level: major for removed public items, changed signatures, removed trait impls, new required trait methods and variants added to exhaustive enums; minor for additions; patch for changed function bodies. Reports each change with its level and the overall version bump required. Revisions are indexed like in `diff_revisions`.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {"type": "string", "description": "Path to the git repository."},
+                        "base": {"type": "string", "description": "The released revision (branch, tag or commit)."},
+                        "head": {"type": "string", "description": "The revision to compare against the base.", "default": "HEAD"}
+                    },
+                    "required": ["repo_path", "base"]
+                }
+            },
+            "check_layering": {
+                "name": "check_layering",
+                "description": "Check the architecture layering rules declared in a repository's `.cgc-layers.json` (e.g. `domain` must not depend on `io`, or `ui` may only depend on `domain`) against the indexed module dependencies, and report each violation with the offending imports, calls and type usages. Rules use module globs; see the config format in the docs.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {"type": "string", "description": "Path of the indexed repository containing `.cgc-layers.json`."}
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            "why_depends": {
+                "name": "why_depends",
+                "description": "Explain why one module depends on another with concrete evidence: the `use` statements, calls, type usages, generic instantiations, field/variant accesses and trait impls that go from the first module's code to the second's. Answers questions like 'why does concurrency depend on smart_pointers'.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "module_a": {"type": "string", "description": "The depending module, e.g. 'concurrency' or 'crate::net::http' (submodules included)."},
+                        "module_b": {"type": "string", "description": "The module depended upon."}
+                    },
+                    "required": ["module_a", "module_b"]
+                }
+            },
+            "search_by_signature": {
+                "name": "search_by_signature",
+                "description": "Hoogle-style search of Rust functions by type signature, e.g. 'fn(&[i32]) -> Option<i32>', 'Fn(i32) -> i32' or just a return type like 'Option<i32>'. Matching tolerates references, `&mut` vs `&`, slices vs Vec, generic parameters and parameter order, each at a small cost; single capital letters in the pattern are wildcards. Functions returning a matching closure are included too. Results are ordered by cost, exact matches first.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "pattern": {"type": "string", "description": "The signature pattern to search for."},
+                        "limit": {"type": "integer", "description": "Maximum number of functions to return.", "default": 20}
+                    },
+                    "required": ["pattern"]
+                }
+            },
+            "semantic_search": {
+                "name": "semantic_search",
+                "description": "Find the functions closest in meaning to a free-text description (e.g. 'shared counter protected by a mutex'), using embeddings of each function's name, doc comment, signature and body computed at indexing time. Returns functions with their similarity score, best first.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "query": {"type": "string", "description": "Description of the code to find."},
+                        "limit": {"type": "integer", "description": "Maximum number of functions to return.", "default": 10},
+                        "file_path": {"type": "string", "description": "Optional: Only search functions of files ending with this path."}
+                    },
+                    "required": ["query"]
+                }
+            },
+            "ask_code_graph": {
+                "name": "ask_code_graph",
+                "description": "Answer a natural-language question about the code, such as 'who calls BankAccount::withdraw outside tests?', 'what does main call?', 'where is Person defined?', 'who implements Greetable?', 'what methods does Person have?', 'who uses type Person?', 'functions with complexity over 5' or 'what is in file lib.rs?'. Questions may add 'outside tests' or 'in file <name>'. The question is translated into a parameterized read-only Cypher query over the graph schema, run with a row limit and a timeout, and returned with its results.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "question": {"type": "string", "description": "The question to answer."},
+                        "limit": {"type": "integer", "description": "Maximum number of results (at most 1000).", "default": 100}
+                    },
+                    "required": ["question"]
+                }
+            },
+            "find_hotspots": {
+                "name": "find_hotspots",
+                "description": "Rank functions or files by maintenance risk: git churn (commits touching the code) within a time window multiplied by cyclomatic complexity. Useful to find complex code that keeps changing.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "since_days": {"type": "integer", "description": "Start of the time window, in days ago.", "default": 90},
+                        "until_days": {"type": "integer", "description": "End of the time window, in days ago.", "default": 0},
+                        "level": {"type": "string", "description": "Rank functions or whole files.", "enum": ["function", "file"], "default": "function"},
+                        "limit": {"type": "integer", "description": "Maximum number of hotspots to return.", "default": 20}
+                    }
+                }
+            },
+            "find_recently_changed_functions": {
+                "name": "find_recently_changed_functions",
+                "description": "List functions last changed in git within a number of days, newest first, with the last commit, its author and the owners of the function's lines (from git blame at indexing time). Optionally keep only the functions that call a given function, or those owned by a given author.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "days": {"type": "integer", "description": "How many days back to look.", "default": 30},
+                        "calls": {"type": "string", "description": "Optional: Only functions calling the function with this name."},
+                        "author": {"type": "string", "description": "Optional: Only functions this author owns lines of."}
+                    }
+                }
+            },
+            "diff_revisions": {
+                "name": "diff_revisions",
+                "description": "Compute a structured diff between two git revisions of a repository: added, removed and changed functions, added and removed call edges, and changes to the Rust public API. Each revision is exported and indexed as a separate snapshot (reused on later calls), kept apart from the indexed repositories so other tools never see it, so the first call on a revision can take a while.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {"type": "string", "description": "Path to the git repository."},
+                        "base": {"type": "string", "description": "The base revision (branch, tag or commit)."},
+                        "head": {"type": "string", "description": "The revision to compare against the base.", "default": "HEAD"}
+                    },
+                    "required": ["repo_path", "base"]
+                }
+            },
+            "find_duplicates": {
+                "name": "find_duplicates",
+                "description": "Cluster near-identical functions across files. Functions are compared by a structural hash of their signature and body computed at indexing time, with identifiers and literals normalized, so copies that only rename things or change constants end up in the same cluster.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "min_size": {"type": "integer", "description": "Ignore functions with fewer syntax tokens than this.", "default": 20},
+                        "include_same_file": {"type": "boolean", "description": "Also report clusters whose functions all live in one file.", "default": False}
+                    }
+                }
+            },
+            "get_code_health_report": {
+                "name": "get_code_health_report",
+                "description": "List the functions exceeding configurable limits on line count, parameter count (`self` excluded), control flow nesting depth and cyclomatic complexity, with their metrics. The violated metrics are also stored on the Function nodes as `health_violations`, so they can be queried later with Cypher.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "max_lines": {"type": "integer", "description": "Maximum number of lines per function.", "default": 60},
+                        "max_params": {"type": "integer", "description": "Maximum number of parameters per function.", "default": 5},
+                        "max_nesting": {"type": "integer", "description": "Maximum nesting depth of control flow.", "default": 4},
+                        "max_complexity": {"type": "integer", "description": "Maximum cyclomatic complexity.", "default": 10},
+                        "file_path": {"type": "string", "description": "Optional: Only check the functions of this file."}
+                    }
+                }
+            },
+            "find_unused_pub_items": {
+                "name": "find_unused_pub_items",
+                "description": "Report Rust `pub` items that are never used outside their defining module (scope 'module', the default) or outside their crate (scope 'crate'), based on calls, trait dispatch, type mentions, instantiations, field/variant accesses and trait impls. Each finding suggests a narrower visibility (`pub(crate)` or private) and lists the public paths the item is exported under, to help library authors shrink their API.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "scope": {"type": "string", "description": "'module' to find items unused outside their module, 'crate' for `pub` items unused outside their crate.", "enum": ["module", "crate"], "default": "module"},
+                        "file_path": {"type": "string", "description": "Optional: Only report items defined in this file."}
+                    }
+                }
+            },
+            "find_unhandled_results": {
+                "name": "find_unhandled_results",
+                "description": "Flag Rust call sites where the value of a `Result`-returning function is discarded: as a bare statement (`f();`), with `let _ = f();` or with `f().ok();`. Each finding lists the calling function, the line, the discarding pattern and the callee's return type.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "file_path": {"type": "string", "description": "Optional: Only report call sites in this file."}
+                    }
+                }
+            },
+            "find_generic_instantiations": {
+                "name": "find_generic_instantiations",
+                "description": "List all concrete instantiations of a generic Rust struct, enum or function, e.g. `Point<i32>` from `Point::new(1, 2)`, `Point<f64>` from an annotation or `largest::<u8>` from a turbofish. Type arguments are taken from written types or inferred from literal and typed arguments at call and construction sites; each instantiation lists its sites. Useful to estimate which monomorphizations exist.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "item_name": {"type": "string", "description": "The generic type or function, e.g. 'Point'."},
+                        "file_path": {"type": "string", "description": "Optional: The file the item is defined in."}
+                    },
+                    "required": ["item_name"]
+                }
+            },
+            "get_trait_impl_coverage": {
+                "name": "get_trait_impl_coverage",
+                "description": "Report, for each impl of a Rust trait, which required methods it implements, which default methods it overrides and which it inherits, how it binds the trait's associated types and which associated consts it specifies. For example, shows that `Student` relies on the default `Greetable::greet` while `Teacher` overrides it.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "trait_name": {"type": "string", "description": "The name of the trait, e.g. 'Greetable'."},
+                        "file_path": {"type": "string", "description": "Optional: The file the trait is defined in, to disambiguate traits with the same name."}
+                    },
+                    "required": ["trait_name"]
+                }
+            },
+            "get_public_api": {
+                "name": "get_public_api",
+                "description": "Dump the effective public API of indexed Rust crates: every function and type reachable from the crate root through `pub mod` chains and `pub use` re-exports (including globs), one entry per public path such as `my_crate::Person`, sorted by path. Entries carry the item's signature and location; types also list their `pub` inherent methods and trait impls. The output is stable JSON, suitable for diffing between versions.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "crate_name": {"type": "string", "description": "Optional: Only report this crate's API."}
+                    }
+                }
+            },
+            "find_circular_module_dependencies": {
+                "name": "find_circular_module_dependencies",
+                "description": "Find circular dependencies between Rust modules: strongly connected components of more than one module in the module-level `USES` graph built from `use` statements. Each component lists its modules, one concrete cycle, and the `use` statements forming every edge, largest first, to help prioritize architecture cleanups.",
+                "inputSchema": {"type": "object", "properties": {}}
+            },
+            "find_signature_impact": {
+                "name": "find_signature_impact",
+                "description": "Impact analysis for changing a function's or method's signature. Returns, grouped by file, everything that would need to change with it: direct and trait-dispatched callers, the trait declaration and other impls of the same trait method, `pub use` re-export sites, and the tests that call it (directly or within three calls).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "function_name": {"type": "string", "description": "The function or method whose signature would change."},
+                        "file_path": {"type": "string", "description": "Optional: The file the function is defined in."},
+                        "owner": {"type": "string", "description": "Optional: The type or trait the method belongs to."}
+                    },
+                    "required": ["function_name"]
+                }
+            },
+            "find_unreachable_code": {
+                "name": "find_unreachable_code",
+                "description": "Reachability-based dead code detection for Rust. Starting from entry points (`main`, tests, benches, exported functions and, by default, the `pub` API), follows the call graph and reports unreachable functions, types never used by reachable code, and enum variants that are never constructed.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "pub_is_entry_point": {"type": "boolean", "description": "Treat `pub` items as entry points (library crates). Set to false to also report unused public API.", "default": True},
+                        "file_path": {"type": "string", "description": "Optional: Only report items in this file."}
+                    }
+                }
+            },
+            "find_transitive_callees": {
+                "name": "find_transitive_callees",
+                "description": "Follow calls transitively from a function. Optionally expands virtual calls: trait-dispatched method calls (on `dyn Trait`, `impl Trait` or generic `T: Trait` values) fan out to every implementation, and closures passed to callable parameters are included. Each callee has a confidence (1.0 for direct calls, 1/n for one of n dispatch candidates) so certain callees can be told apart from possible ones.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "function_name": {"type": "string", "description": "The function to start from."},
+                        "file_path": {"type": "string", "description": "Optional: The file the function is defined in."},
+                        "expand_dispatch": {"type": "boolean", "description": "Follow trait-dispatch and higher-order MAY_CALL edges.", "default": True},
+                        "max_depth": {"type": "integer", "description": "Maximum call depth to follow.", "default": 5}
+                    },
+                    "required": ["function_name"]
+                }
+            },
+            "find_implementations": {
+                "name": "find_implementations",
+                "description": "Find all implementations of a Rust trait, given by name or path (e.g. `traits::Area` or `Display`). Returns each implementing type with the impl span, its generic constraints and where clause, and whether the impl is handwritten, blanket (`impl<T: Bound> Trait for T`) or derived (`#[derive(Trait)]`).",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "trait_path": {"type": "string", "description": "The trait name or path, e.g. 'traits::Area'."}
+                    },
+                    "required": ["trait_path"]
+                }
+            },
+            "find_types_satisfying": {
+                "name": "find_types_satisfying",
+                "description": "Find the Rust types that satisfy trait bounds such as 'Display + Clone', through `#[derive]`, direct impls, blanket impls (`impl<T: Display> MyTrait for T`) or the Send/Sync analysis. Answers questions like 'what can I pass to a function taking `T: Display + Clone`'.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "bounds": {"type": "string", "description": "The required bounds, e.g. 'Display + Clone', 'T: Debug + Send' or 'Display, Clone'."}
+                    },
+                    "required": ["bounds"]
+                }
+            },
+            "find_newtypes": {
+                "name": "find_newtypes",
+                "description": "Find Rust newtypes (single-field tuple structs such as `struct Meters(f64)`) and generic wrappers (such as `struct Wrapper<T> { value: T }`). With `inner_type`, returns the newtypes that wrap that type, directly or through other newtypes, so type usages can be traced through them.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "inner_type": {"type": "string", "description": "Optional: Only return the newtypes wrapping this type."}
+                    }
+                }
+            },
+            "find_builders": {
+                "name": "find_builders",
+                "description": "Find Rust builder types: types with chainable `fn foo(mut self, ..) -> Self` setters and a consuming `build()` method. Returns each builder with the type it builds, its setters, and the functions that create it (e.g. `Config::builder()`), which are the construction entry points for code generation.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "type_name": {"type": "string", "description": "Optional: Only return the builders of this type, or the builder with this name."}
+                    }
+                }
+            },
+            "find_interior_mutability": {
+                "name": "find_interior_mutability",
+                "description": "Report every Rust field and static using interior mutability (`Cell`, `RefCell`, `UnsafeCell`, `OnceCell`, atomics), grouped by cell kind, with the functions that borrow or mutate each one (`borrow_mut`, `set`, `fetch_add`, ...). Useful for finding runtime-borrow hotspots.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "kind": {"type": "string", "description": "Optional: Only report one cell kind.", "enum": ["Cell", "RefCell", "UnsafeCell", "OnceCell", "Atomic"]}
+                    }
+                }
+            },
+            "find_blocking_in_async": {
+                "name": "find_blocking_in_async",
+                "description": "Find blocking calls in Rust async code: `std::thread::sleep`, blocking std IO and networking, and `.lock()` on std mutexes made inside `async fn` bodies or async blocks, or in sync functions those call. Reports the async function, the call path to the blocking call, and the async entry point (e.g. `#[tokio::main]`) it is reachable from.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "require_entry_point": {"type": "boolean", "description": "Only report findings reachable from an async entry point.", "default": False},
+                        "file_path": {"type": "string", "description": "Optional: Only report blocking calls in this file."}
+                    }
+                }
+            },
+            "find_deadlock_candidates": {
+                "name": "find_deadlock_candidates",
+                "description": "Analyze the order in which Rust locks (Mutex/RwLock fields and statics) are acquired, including locks taken by functions called while a guard is held, and report cycles in that order as potential deadlocks. Each hop of a cycle lists the function, line and call path that acquire the locks in that order; a lock acquired before itself is a self-deadlock.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "max_cycle_length": {"type": "integer", "description": "Maximum number of locks in a reported cycle (1-8).", "default": 4}
+                    }
+                }
+            },
+            "find_await_holding_lock": {
+                "name": "find_await_holding_lock",
+                "description": "Find Rust async functions and blocks where a synchronous `MutexGuard`/`RwLock` guard is still alive across an `.await`, a common cause of deadlocks and non-Send futures. Reports the function, the lock (resolved to its Lock node when it is a known field or static), the guard and the span from the lock to the await.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "file_path": {"type": "string", "description": "Optional: Only report functions in this file."}
+                    }
+                }
+            },
+            "find_thread_crossing_types": {
+                "name": "find_thread_crossing_types",
+                "description": "List the Rust types moved into `thread::spawn` (or scoped/builder spawn) closures together with a Send/Sync analysis derived from their fields (Rc vs Arc, RefCell vs Mutex, raw pointers, manual `unsafe impl Send`). Also reports spawned closures that capture values which are not Send.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "only_non_send": {"type": "boolean", "description": "Only report thread crossings of types that are not Send.", "default": False}
+                    }
+                }
+            },
+            "get_crate_info": {
+                "name": "get_crate_info",
+                "description": "Get the indexed Rust crates with their Cargo.toml metadata (name, edition, declared rust-version) and an estimated minimum supported Rust version (MSRV) derived from the syntax and standard library features they use, e.g. let-else, GATs, const generics or scoped threads. Reports which features determine the estimate and whether the declared rust-version is lower.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "crate_name": {"type": "string", "description": "Optional: The package name of a single crate."}
+                    }
+                }
+            },
+            "find_recursive_functions": {
+                "name": "find_recursive_functions",
+                "description": "Find directly recursive functions and mutually recursive cycles (A calls B calls A) in the indexed code, with the cycle members and an estimated recursion depth bound where it can be derived from the recursive call arguments.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "max_cycle_length": {"type": "integer", "description": "Maximum number of functions in a mutual recursion cycle (2-8).", "default": 4},
+                        "file_path": {"type": "string", "description": "Optional: Only report recursion starting in this file."}
+                    }
+                }
+            },
+            "calculate_cyclomatic_complexity": {
+                "name": "calculate_cyclomatic_complexity",
+                "description": "Calculate the cyclomatic complexity of a specific function to measure its complexity.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "function_name": {"type": "string", "description": "The name of the function to analyze."},
+                        "file_path": {"type": "string", "description": "Optional: The full path to the file containing the function for a more specific query."} 
+                    },
+                    "required": ["function_name"]
+                }
+            },
+            "find_most_complex_functions": {
+                "name": "find_most_complex_functions",
+                "description": "Find the most complex functions in the codebase based on cyclomatic complexity.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "limit": {"type": "integer", "description": "The maximum number of complex functions to return.", "default": 10}
+                    }
+                }
+            },
+            "list_indexed_repositories": {
+                "name": "list_indexed_repositories",
+                "description": "List all indexed repositories.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {}
+                }
+            },
+            "delete_repository": {
+                "name": "delete_repository",
+                "description": "Delete an indexed repository from the graph.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "repo_path": {"type": "string", "description": "The path of the repository to delete."} 
+                    },
+                    "required": ["repo_path"]
+                }
+            },
+            "visualize_graph_query": {
+                "name": "visualize_graph_query",
+                "description": "Generates a URL to visualize the results of a Cypher query in the Neo4j Browser. The user can open this URL in their web browser to see the graph visualization.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "cypher_query": {"type": "string", "description": "The Cypher query to visualize."}
+                    },
+                    "required": ["cypher_query"]
+                }
+            },
+            "list_watched_paths": {
+                "name": "list_watched_paths",
+                "description": "Lists all directories currently being watched for live file changes.",
+                "inputSchema": {"type": "object", "properties": {}}
+            },
+            "unwatch_directory": {
+                "name": "unwatch_directory",
+                "description": "Stops watching a directory for live file changes.",
+                "inputSchema": {
+                    "type": "object",
+                    "properties": {
+                        "path": {"type": "string", "description": "The absolute path of the directory to stop watching."}
+                    },
+                    "required": ["path"]
+                }
+            }
+        }    
+
+    def get_database_status(self) -> dict:
+        """Returns the current connection status of the Neo4j database, with its pool and retry counters."""
+        return {"connected": self.db_manager.is_connected(), "remote_uri": self.db_manager.remote_uri,
+                **self.db_manager.pool_status()}
+        
+    def get_local_package_path(self, package_name: str) -> Optional[str]:
+        """
+        Finds the local installation path of a Python package.
+
+        This method uses `importlib` to locate a package and determines its root
+        directory, handling both regular packages (directories with __init__.py)
+        and single-file modules.
+
+        Args:
+            package_name: The name of the package to locate (e.g., "requests").
+
+        Returns:
+            The absolute path to the package's directory as a string, or None if not found.
+        """
+        try:
+            debug_log(f"Getting local path for package: {package_name}")
+            
+            module = importlib.import_module(package_name)
+            
+            if hasattr(module, '__file__') and module.__file__:
+                module_file = Path(module.__file__)
+                debug_log(f"Module file: {module_file}")
+
+                if module_file.name == '__init__.py':
+                    # For a package, the path is the parent directory of __init__.py
+                    package_path = str(module_file.parent)
+                elif package_name in stdlibs.module_names:
+                    # For a standard library single file module, the path is the file itself
+                    package_path = str(module_file)
+                else:
+                    # For other single-file modules, assume the parent directory is the container
+                    package_path = str(module_file.parent)
+
+                debug_log(f"Determined package path: {package_path}")
+                return package_path
+
+            elif hasattr(module, '__path__'):
+                # This handles namespace packages which may not have an __init__.py
+                if isinstance(module.__path__, list) and module.__path__:
+                    package_path = str(Path(module.__path__[0]))
+                    debug_log(f"Package path from __path__: {package_path}")
+                    return package_path
+                else:
+                    # Fallback for other __path__ formats
+                    package_path = str(Path(str(module.__path__)))
+                    debug_log(f"Package path from __path__ (str): {package_path}")
+                    return package_path
+            
+            debug_log(f"Could not determine path for {package_name}")
+            return None
+            
+        except ImportError as e:
+            debug_log(f"Could not import {package_name}: {e}")
+            return None
+        except Exception as e:
+            debug_log(f"Error getting local path for {package_name}: {e}")
+            return None
+        
+    def execute_cypher_query_tool(self, **args) -> Dict[str, Any]:
+        """
+        Tool implementation for executing a read-only Cypher query.
+        
+        Important: Includes a safety check to prevent any database modification
+        by disallowing keywords like CREATE, MERGE, DELETE, etc.
+        """
+        cypher_query = args.get("cypher_query")
+        if not cypher_query:
+            return {"error": "Cypher query cannot be empty."}
+
+        # Safety Check: Prevent any write operations to the database.
+        # This check first removes all string literals and then checks for forbidden keywords.
+        forbidden_keywords = ['CREATE', 'MERGE', 'DELETE', 'SET', 'REMOVE', 'DROP', 'CALL apoc']
+        
+        # Regex to match single or double quoted strings, handling escaped quotes.
+        string_literal_pattern = r'"(?:\\.|[^"\\])*"|\'(?:\\.|[^\'\\])*\''
+        
+        # Remove all string literals from the query.
+        query_without_strings = re.sub(string_literal_pattern, '', cypher_query)
+        
+        # Now, check for forbidden keywords in the query without strings.
+        for keyword in forbidden_keywords:
+            if re.search(r'\b' + keyword + r'\b', query_without_strings, re.IGNORECASE):
+                return {
+                    "error": "This tool only supports read-only queries. Prohibited keywords like CREATE, MERGE, DELETE, SET, etc., are not allowed."
+                }
+
+        try:
+            debug_log(f"Executing Cypher query: {cypher_query}")
+            with self.db_manager.get_driver().session() as session:
+                result = session.run(cypher_query)
+                # Convert results to a list of dictionaries for clean JSON serialization.
+                records = [expand_tree(record.data()) for record in result]
+                
+                return {
+                    "success": True,
+                    "query": cypher_query,
+                    "record_count": len(records),
+                    "results": records
+                }
+        
+        except CypherSyntaxError as e:
+            debug_log(f"Cypher syntax error: {str(e)}")
+            return {
+                "error": "Cypher syntax error.",
+                "details": str(e),
+                "query": cypher_query
+            }
+        except Exception as e:
+            debug_log(f"Error executing Cypher query: {str(e)}")
+            return {
+                "error": "An unexpected error occurred while executing the query.",
+                "details": str(e)
+            }
+    
+    def find_dead_code_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find potentially dead code across the entire project."""
+        exclude_decorated_with = args.get("exclude_decorated_with", [])
+        try:
+            debug_log("Finding dead code.")
+            results = self.code_finder.find_dead_code(exclude_decorated_with=exclude_decorated_with)
+            
+            return {
+                "success": True,
+                "query_type": "dead_code",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding dead code: {str(e)}")
+            return {"error": f"Failed to find dead code: {str(e)}"}
+
+    def check_layering_tool(self, **args) -> Dict[str, Any]:
+        """Tool to check a repository against its layering rules."""
+        try:
+            debug_log(f"Checking layering rules of {args.get('repo_path')}")
+            results = self.code_finder.check_layering(args["repo_path"])
+            return {
+                "success": True,
+                "query_type": "layering",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error checking layering rules: {str(e)}")
+            return {"error": f"Failed to check layering rules: {str(e)}"}
+
+    def why_depends_tool(self, **args) -> Dict[str, Any]:
+        """Tool to explain the dependency between two modules."""
+        try:
+            debug_log(f"Explaining dependency of {args.get('module_a')} on {args.get('module_b')}")
+            results = self.code_finder.why_depends(args["module_a"], args["module_b"])
+            return {
+                "success": True,
+                "query_type": "why_depends",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error explaining dependency: {str(e)}")
+            return {"error": f"Failed to explain dependency: {str(e)}"}
+
+    def search_by_signature_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find functions matching a type signature pattern."""
+        try:
+            debug_log(f"Searching by signature: {args.get('pattern')}")
+            results = self.code_finder.search_by_signature(args["pattern"], limit=int(args.get("limit", 20)))
+            return {
+                "success": True,
+                "query_type": "signature_search",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error searching by signature: {str(e)}")
+            return {"error": f"Failed to search by signature: {str(e)}"}
+
+    def semantic_search_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find functions by meaning using stored embeddings."""
+        try:
+            debug_log(f"Semantic search for: {args.get('query')}")
+            results = self.code_finder.semantic_search(
+                args["query"], limit=int(args.get("limit", 10)), file_path=args.get("file_path")
+            )
+            return {
+                "success": True,
+                "query_type": "semantic_search",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error in semantic search: {str(e)}")
+            return {"error": f"Failed to run semantic search: {str(e)}"}
+
+    def ask_code_graph_tool(self, **args) -> Dict[str, Any]:
+        """Tool to answer a natural-language question with a generated Cypher query."""
+        try:
+            debug_log(f"Answering question: {args.get('question')}")
+            results = self.code_finder.answer_question(args["question"], limit=args.get("limit", 100))
+            return {
+                "success": True,
+                "query_type": "natural_language",
+                "results": results
+            }
+        except ValueError as e:
+            return {"error": str(e)}
+        except Exception as e:
+            debug_log(f"Error answering question: {str(e)}")
+            return {"error": f"Failed to answer question: {str(e)}"}
+
+    def find_hotspots_tool(self, **args) -> Dict[str, Any]:
+        """Tool to rank code by churn times complexity."""
+        try:
+            debug_log(f"Finding hotspots with {args}")
+            results = self.code_finder.find_hotspots(
+                since_days=int(args.get("since_days", 90)),
+                until_days=int(args.get("until_days", 0)),
+                level=args.get("level", "function"),
+                limit=int(args.get("limit", 20)),
+            )
+            return {
+                "success": True,
+                "query_type": "hotspots",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding hotspots: {str(e)}")
+            return {"error": f"Failed to find hotspots: {str(e)}"}
+
+    def find_recently_changed_functions_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find functions changed recently according to git blame."""
+        try:
+            debug_log(f"Finding recently changed functions with {args}")
+            results = self.code_finder.find_recently_changed_functions(
+                days=int(args.get("days", 30)), calls=args.get("calls"), author=args.get("author")
+            )
+            return {
+                "success": True,
+                "query_type": "recently_changed_functions",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding recently changed functions: {str(e)}")
+            return {"error": f"Failed to find recently changed functions: {str(e)}"}
+
+    def diff_revisions(self, repo_path: str, base: str, head: str = "HEAD") -> Dict[str, Any]:
+        """Indexes both revisions of `repo_path` (if needed) and diffs their graphs."""
+        roots = {label: self.graph_builder.index_revision(Path(repo_path), revision)
+                 for label, revision in (("base", base), ("head", head))}
+        diff = self.code_finder.diff_indexed_trees(str(roots["base"]), str(roots["head"]))
+        diff["base"] = {"revision": base, "path": str(roots["base"])}
+        diff["head"] = {"revision": head, "path": str(roots["head"])}
+        return diff
+
+    def preview_ignore_rules_tool(self, **args) -> Dict[str, Any]:
+        """Tool to dry-run the ignore rules of a repository"""
+        path = args.get("path")
+        limit = args.get("limit", 50)
+        try:
+            debug_log(f"Previewing ignore rules of {path}")
+            if not path or not Path(path).is_dir():
+                return {"error": f"Invalid path: {path} is not a directory"}
+            results = IgnoreRules.load(Path(path)).dry_run(self.graph_builder.parsers.keys(), limit)
+            return {"success": True, "query_type": "preview_ignore_rules", "results": results}
+        except Exception as e:
+            debug_log(f"Error previewing ignore rules: {str(e)}")
+            return {"error": f"Failed to preview ignore rules: {str(e)}"}
+
+    def sync_to_remote_tool(self, **args) -> Dict[str, Any]:
+        """Tool to mirror a repository's changed subgraph to the remote database"""
+        repo_path = args.get("repo_path")
+        try:
+            debug_log(f"Syncing {repo_path} to the remote database")
+            results = self.graph_builder.sync_to_remote(Path(repo_path), args.get("full", False), args.get("dry_run", False))
+            return {"success": True, "query_type": "sync_to_remote", "results": results}
+        except Exception as e:
+            debug_log(f"Error syncing to the remote database: {str(e)}")
+            return {"error": f"Failed to sync to the remote database: {str(e)}"}
+
+    def list_shards_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the shards of sharded repositories"""
+        repo_path = args.get("repo_path")
+        try:
+            debug_log(f"Listing shards of {repo_path or 'all repositories'}")
+            results = self.code_finder.list_shards(str(Path(repo_path).resolve()) if repo_path else None)
+            return {"success": True, "query_type": "list_shards", "results": results}
+        except Exception as e:
+            debug_log(f"Error listing shards: {str(e)}")
+            return {"error": f"Failed to list shards: {str(e)}"}
+
+    def reindex_shard_tool(self, **args) -> Dict[str, Any]:
+        """Tool to re-index one shard of a sharded repository"""
+        repo_path = args.get("repo_path")
+        shard = args.get("shard")
+        if not repo_path or not shard:
+            return {"error": "Both 'repo_path' and 'shard' are required."}
+        try:
+            path_obj = Path(repo_path).resolve()
+            shards = self.graph_builder.plan_shards(path_obj)
+            match = next((s for s in shards if s["name"] == shard or Path(s["path"]) == (path_obj / shard).resolve()), None)
+            if match is None:
+                return {"error": f"Failed to re-index shard: '{shard}' is not a shard of {path_obj}",
+                        "shards": [s["name"] for s in shards]}
+            job_id = self.job_manager.create_job(str(path_obj))
+            coro = self.graph_builder.reindex_shard_async(path_obj, Path(match["path"]), job_id)
+            asyncio.run_coroutine_threadsafe(coro, self.loop)
+            debug_log(f"Started shard re-index job {job_id} for {match['path']}")
+            return {
+                "success": True, "job_id": job_id, "shard": {"name": match["name"], "path": match["path"]},
+                "message": f"Incremental re-index of shard '{match['name']}' started",
+                "instructions": f"Use 'check_job_status' with job_id '{job_id}' to monitor progress; its result lists what changed"
+            }
+        except Exception as e:
+            debug_log(f"Error re-indexing shard: {str(e)}")
+            return {"error": f"Failed to re-index shard: {str(e)}"}
+
+    def get_query_cache_stats_tool(self, **args) -> Dict[str, Any]:
+        """Tool to report the query result cache statistics"""
+        try:
+            return {"success": True, "query_type": "query_cache_stats", "results": self.query_cache.stats()}
+        except Exception as e:
+            debug_log(f"Error reading query cache stats: {str(e)}")
+            return {"error": f"Failed to read query cache stats: {str(e)}"}
+
+    def get_watch_events_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the change events of watched directories"""
+        path = args.get("path")
+        since = int(args.get("since", 0))
+        try:
+            debug_log(f"Listing watch events for {path or 'all watched paths'} since {since}")
+            events = self.code_watcher.get_events(path, since)
+            return {"success": True, "query_type": "watch_events", "results": events}
+        except Exception as e:
+            debug_log(f"Error listing watch events: {str(e)}")
+            return {"error": f"Failed to list watch events: {str(e)}"}
+
+    def find_unresolved_references_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the placeholders of unresolved calls."""
+        try:
+            debug_log(f"Finding unresolved references with {args}")
+            results = self.code_finder.find_unresolved_references(
+                name=args.get("name"), kind=args.get("kind"), file_path=args.get("file_path"),
+                limit=int(args.get("limit", 50)))
+            return {
+                "success": True,
+                "query_type": "find_unresolved_references",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding unresolved references: {str(e)}")
+            return {"error": f"Failed to find unresolved references: {str(e)}"}
+
+    def get_snippet_tool(self, **args) -> Dict[str, Any]:
+        """Tool to read the exact source of a symbol from its recorded span."""
+        try:
+            debug_log(f"Getting snippet with {args}")
+            results = self.code_finder.get_snippet(
+                name=args.get("name"), file_path=args.get("file_path"), uid=args.get("uid"),
+                context_lines=int(args.get("context_lines", 0)))
+            return {
+                "success": True,
+                "query_type": "get_snippet",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error getting snippet: {str(e)}")
+            return {"error": f"Failed to get snippet: {str(e)}"}
+
+    def record_history_tool(self, **args) -> Dict[str, Any]:
+        """Tool to record the history of a repository over a sequence of revisions."""
+        try:
+            debug_log(f"Recording history with {args}")
+            results = self.graph_builder.record_history(Path(args["repo_path"]), args["revisions"])
+            return {
+                "success": True,
+                "query_type": "record_history",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error recording history: {str(e)}")
+            return {"error": f"Failed to record history: {str(e)}"}
+
+    def function_history_tool(self, **args) -> Dict[str, Any]:
+        """Tool to show the recorded history of a function or type."""
+        try:
+            debug_log(f"Finding function history with {args}")
+            results = self.code_finder.function_history(args["name"], repo_path=args.get("repo_path"))
+            return {
+                "success": True,
+                "query_type": "function_history",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding function history: {str(e)}")
+            return {"error": f"Failed to find function history: {str(e)}"}
+
+    def call_history_tool(self, **args) -> Dict[str, Any]:
+        """Tool to show when a function called another over the recorded history."""
+        try:
+            debug_log(f"Finding call history with {args}")
+            results = self.code_finder.call_history(args["caller"], callee=args.get("callee"), repo_path=args.get("repo_path"))
+            return {
+                "success": True,
+                "query_type": "call_history",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding call history: {str(e)}")
+            return {"error": f"Failed to find call history: {str(e)}"}
+
+    def module_as_of_tool(self, **args) -> Dict[str, Any]:
+        """Tool to show a file or directory as of a recorded revision."""
+        try:
+            debug_log(f"Finding module as of revision with {args}")
+            results = self.code_finder.module_as_of(args["repo_path"], args["path"], args["revision"])
+            return {
+                "success": True,
+                "query_type": "module_as_of",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding module as of revision: {str(e)}")
+            return {"error": f"Failed to find module as of revision: {str(e)}"}
+
+    def import_profile_tool(self, **args) -> Dict[str, Any]:
+        """Tool to import a collapsed stack CPU profile onto the call graph."""
+        try:
+            debug_log(f"Importing profile with {args}")
+            results = self.graph_builder.import_profile(Path(args["profile_path"]))
+            return {
+                "success": True,
+                "query_type": "import_profile",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error importing profile: {str(e)}")
+            return {"error": f"Failed to import profile: {str(e)}"}
+
+    def find_hot_functions_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the hottest functions of the imported profile."""
+        try:
+            debug_log(f"Finding hot functions with {args}")
+            results = self.code_finder.find_hot_functions(
+                limit=int(args.get("limit", 20)), min_share=float(args.get("min_share", 0.0)))
+            return {
+                "success": True,
+                "query_type": "find_hot_functions",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding hot functions: {str(e)}")
+            return {"error": f"Failed to find hot functions: {str(e)}"}
+
+    def import_benchmarks_tool(self, **args) -> Dict[str, Any]:
+        """Tool to import Criterion benchmark results onto the graph."""
+        try:
+            debug_log(f"Importing benchmarks with {args}")
+            results = self.graph_builder.import_benchmarks(Path(args["path"]))
+            return {
+                "success": True,
+                "query_type": "import_benchmarks",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error importing benchmarks: {str(e)}")
+            return {"error": f"Failed to import benchmarks: {str(e)}"}
+
+    def find_benchmarks_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list imported benchmarks and the functions they measure."""
+        try:
+            debug_log(f"Finding benchmarks with {args}")
+            results = self.code_finder.find_benchmarks(
+                function_name=args.get("function_name"), verdict=args.get("verdict"), limit=int(args.get("limit", 50)))
+            return {
+                "success": True,
+                "query_type": "find_benchmarks",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding benchmarks: {str(e)}")
+            return {"error": f"Failed to find benchmarks: {str(e)}"}
+
+    def import_coverage_tool(self, **args) -> Dict[str, Any]:
+        """Tool to import a coverage report onto the graph."""
+        try:
+            debug_log(f"Importing coverage with {args}")
+            source_root = args.get("source_root")
+            results = self.graph_builder.import_coverage(
+                Path(args["report_path"]), source_root=Path(source_root) if source_root else None,
+                test_name=args.get("test_name"))
+            return {
+                "success": True,
+                "query_type": "import_coverage",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error importing coverage: {str(e)}")
+            return {"error": f"Failed to import coverage: {str(e)}"}
+
+    def find_untested_code_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find untested functions reachable from an entry point."""
+        try:
+            debug_log(f"Finding untested code with {args}")
+            results = self.code_finder.find_untested_code(
+                entry_point=args.get("entry_point", "main"), file_path=args.get("file_path"),
+                max_depth=int(args.get("max_depth", 10)))
+            return {
+                "success": True,
+                "query_type": "find_untested_code",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding untested code: {str(e)}")
+            return {"error": f"Failed to find untested code: {str(e)}"}
+
+    def run_clippy_tool(self, **args) -> Dict[str, Any]:
+        """Tool to run clippy on a crate and attach its diagnostics to the graph."""
+        try:
+            debug_log(f"Running clippy with {args}")
+            results = self.graph_builder.annotate_clippy(Path(args["path"]))
+            return {
+                "success": True,
+                "query_type": "run_clippy",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error running clippy: {str(e)}")
+            return {"error": f"Failed to run clippy: {str(e)}"}
+
+    def find_clippy_findings_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the functions with clippy diagnostics."""
+        try:
+            debug_log(f"Finding clippy findings with {args}")
+            results = self.code_finder.find_clippy_findings(
+                lint=args.get("lint"), min_complexity=args.get("min_complexity"), file_path=args.get("file_path"),
+                limit=int(args.get("limit", 50)))
+            return {
+                "success": True,
+                "query_type": "find_clippy_findings",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding clippy findings: {str(e)}")
+            return {"error": f"Failed to find clippy findings: {str(e)}"}
+
+    def find_allocation_sites_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the heap allocations reachable from a function."""
+        try:
+            debug_log(f"Finding allocation sites with {args}")
+            results = self.code_finder.find_allocation_sites(
+                args["function_name"], file_path=args.get("file_path"), max_depth=int(args.get("max_depth", 5)),
+                expand_dispatch=args.get("expand_dispatch", True))
+            return {
+                "success": True,
+                "query_type": "find_allocation_sites",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding allocation sites: {str(e)}")
+            return {"error": f"Failed to find allocation sites: {str(e)}"}
+
+    def find_literal_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find the functions containing a literal."""
+        try:
+            debug_log(f"Finding literal with {args}")
+            results = self.code_finder.find_literal(
+                args["text"], kind=args.get("kind"), exact=args.get("exact", False), limit=int(args.get("limit", 50)))
+            return {
+                "success": True,
+                "query_type": "find_literal",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding literal: {str(e)}")
+            return {"error": f"Failed to find literal: {str(e)}"}
+
+    def list_tasks_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the TODO/FIXME/HACK comments of a subsystem."""
+        try:
+            debug_log(f"Listing tasks with {args}")
+            results = self.code_finder.find_tasks(
+                subsystem=args.get("subsystem"), kinds=args.get("kinds"), author=args.get("author"))
+            return {
+                "success": True,
+                "query_type": "list_tasks",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error listing tasks: {str(e)}")
+            return {"error": f"Failed to list tasks: {str(e)}"}
+
+    def get_doc_coverage_tool(self, **args) -> Dict[str, Any]:
+        """Tool to report the documentation coverage of public Rust items."""
+        try:
+            debug_log(f"Computing documentation coverage with {args}")
+            results = self.code_finder.get_doc_coverage(
+                crate_name=args.get("crate_name"), min_coverage=float(args.get("min_coverage", 0)))
+            return {
+                "success": True,
+                "query_type": "doc_coverage",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error computing documentation coverage: {str(e)}")
+            return {"error": f"Failed to compute documentation coverage: {str(e)}"}
+
+    def module_dependency_matrix_tool(self, **args) -> Dict[str, Any]:
+        """Tool to build the module dependency structure matrix of a crate."""
+        try:
+            debug_log(f"Building module dependency matrix with {args}")
+            dsm = self.code_finder.module_dependency_matrix(
+                crate_name=args.get("crate_name"), depth=int(args["depth"]) if args.get("depth") else None)
+            export_format = args.get("format", "json")
+            return {
+                "success": True,
+                "query_type": "module_dependency_matrix",
+                "results": dsm if export_format == "json" else render_dsm(dsm, export_format)
+            }
+        except Exception as e:
+            debug_log(f"Error building module dependency matrix: {str(e)}")
+            return {"error": f"Failed to build module dependency matrix: {str(e)}"}
+
+    def type_hierarchy_tool(self, **args) -> Dict[str, Any]:
+        """Tool to build the supertrait and implementor tree of a trait or type."""
+        try:
+            debug_log(f"Building type hierarchy with {args}")
+            results = self.code_finder.type_hierarchy(args["name"], depth=int(args.get("depth", 5)))
+            return {
+                "success": True,
+                "query_type": "type_hierarchy",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error building type hierarchy: {str(e)}")
+            return {"error": f"Failed to build type hierarchy: {str(e)}"}
+
+    def call_hierarchy_tool(self, **args) -> Dict[str, Any]:
+        """Tool to build an LSP-style call hierarchy of a function."""
+        try:
+            debug_log(f"Building call hierarchy with {args}")
+            results = self.code_finder.call_hierarchy(
+                args["symbol"], direction=args.get("direction", "incoming"), depth=int(args.get("depth", 3)),
+                file_path=args.get("file_path"))
+            return {
+                "success": True,
+                "query_type": "call_hierarchy",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error building call hierarchy: {str(e)}")
+            return {"error": f"Failed to build call hierarchy: {str(e)}"}
+
+    def find_similar_functions_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find functions similar to a given one."""
+        try:
+            debug_log(f"Finding functions similar to {args.get('function_name')}")
+            results = self.code_finder.find_similar_functions(
+                args["function_name"], file_path=args.get("file_path"), limit=int(args.get("limit", 10)),
+                min_score=float(args.get("min_score", 0.5)))
+            return {
+                "success": True,
+                "query_type": "similar_functions",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding similar functions: {str(e)}")
+            return {"error": f"Failed to find similar functions: {str(e)}"}
+
+    def suggest_module_boundaries_tool(self, **args) -> Dict[str, Any]:
+        """Tool to compare call graph communities with the module structure."""
+        try:
+            debug_log(f"Suggesting module boundaries with {args}")
+            results = self.code_finder.suggest_module_boundaries(
+                resolution=float(args.get("resolution", 1.0)), min_weight=int(args.get("min_weight", 2)),
+                limit=int(args.get("limit", 50)))
+            return {
+                "success": True,
+                "query_type": "module_boundaries",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error suggesting module boundaries: {str(e)}")
+            return {"error": f"Failed to suggest module boundaries: {str(e)}"}
+
+    def find_central_functions_tool(self, **args) -> Dict[str, Any]:
+        """Tool to rank functions by call graph centrality."""
+        try:
+            debug_log(f"Finding central functions with {args}")
+            results = self.code_finder.find_central_functions(
+                metric=args.get("metric", "pagerank"), limit=int(args.get("limit", 20)), file_path=args.get("file_path"))
+            return {
+                "success": True,
+                "query_type": "central_functions",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding central functions: {str(e)}")
+            return {"error": f"Failed to find central functions: {str(e)}"}
+
+    def find_method_shadowing_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find inherent methods shadowing trait methods and their ambiguous call sites."""
+        try:
+            debug_log(f"Finding method shadowing with {args}")
+            results = self.code_finder.find_method_shadowing(type_name=args.get("type_name"))
+            return {
+                "success": True,
+                "query_type": "method_shadowing",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding method shadowing: {str(e)}")
+            return {"error": f"Failed to find method shadowing: {str(e)}"}
+
+    def find_glob_imports_tool(self, **args) -> Dict[str, Any]:
+        """Tool to report glob imports and the names actually used from them."""
+        try:
+            debug_log(f"Finding glob imports with {args}")
+            results = self.code_finder.find_glob_imports(file_path=args.get("file_path"))
+            return {
+                "success": True,
+                "query_type": "glob_imports",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding glob imports: {str(e)}")
+            return {"error": f"Failed to find glob imports: {str(e)}"}
+
+    def get_canonical_paths_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the definition and public re-export paths of a Rust item."""
+        try:
+            debug_log(f"Resolving canonical paths of {args.get('symbol')}")
+            results = self.code_finder.get_canonical_paths(args["symbol"])
+            return {
+                "success": True,
+                "query_type": "canonical_paths",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error resolving canonical paths: {str(e)}")
+            return {"error": f"Failed to resolve canonical paths: {str(e)}"}
+
+    def items_under_feature_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the items gated behind a Cargo feature and their ungated users."""
+        try:
+            debug_log(f"Finding items under feature {args.get('feature')}")
+            results = self.code_finder.items_under_feature(args["feature"])
+            return {
+                "success": True,
+                "query_type": "feature_items",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding items under feature: {str(e)}")
+            return {"error": f"Failed to find items under feature: {str(e)}"}
+
+    def estimate_monomorphization_tool(self, **args) -> Dict[str, Any]:
+        """Tool to rank generic functions by their estimated monomorphized copies."""
+        try:
+            debug_log(f"Estimating monomorphization with {args}")
+            results = self.code_finder.estimate_monomorphization(limit=int(args.get("limit", 20)), file_path=args.get("file_path"))
+            return {
+                "success": True,
+                "query_type": "monomorphization",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error estimating monomorphization: {str(e)}")
+            return {"error": f"Failed to estimate monomorphization: {str(e)}"}
+
+    def find_god_objects_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find types with too many methods, trait impls or fields."""
+        try:
+            debug_log(f"Finding god objects with {args}")
+            results = self.code_finder.find_god_objects(
+                max_methods=int(args.get("max_methods", 20)),
+                max_trait_impls=int(args.get("max_trait_impls", 10)),
+                max_fields=int(args.get("max_fields", 15)),
+                limit=int(args.get("limit", 50)),
+            )
+            return {
+                "success": True,
+                "query_type": "god_objects",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding god objects: {str(e)}")
+            return {"error": f"Failed to find god objects: {str(e)}"}
+
+    def find_taint_flows_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find source-to-sink data paths through the call graph."""
+        try:
+            debug_log(f"Finding taint flows with {args}")
+            results = self.code_finder.find_taint_flows(
+                sources=args.get("sources"),
+                sinks=args.get("sinks"),
+                max_depth=int(args.get("max_depth", 4)),
+                limit=int(args.get("limit", 50)),
+            )
+            return {
+                "success": True,
+                "query_type": "taint_flows",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding taint flows: {str(e)}")
+            return {"error": f"Failed to find taint flows: {str(e)}"}
+
+    def export_subgraph_tool(self, **args) -> Dict[str, Any]:
+        """Tool to export the subgraph rooted at a function, module or crate."""
+        try:
+            debug_log(f"Exporting subgraph with {args}")
+            subgraph = self.code_finder.export_subgraph(
+                args["root"],
+                root_kind=args.get("root_kind", "function"),
+                edge_types=args.get("edge_types"),
+                depth=int(args.get("depth", 2)),
+                direction=args.get("direction", "out"),
+                file_path=args.get("file_path"),
+                include_external=args.get("include_external", False),
+                max_nodes=int(args.get("max_nodes", 500)),
+                min_samples=args.get("min_samples"),
+            )
+            if args.get("modules"):
+                subgraph = module_graph(subgraph)
+            export_format = args.get("format", "json")
+            return {
+                "success": True,
+                "query_type": "subgraph_export",
+                "results": subgraph if export_format == "json" else
+                           render_subgraph(subgraph, export_format, args.get("cluster_by_module", False))
+            }
+        except Exception as e:
+            debug_log(f"Error exporting subgraph: {str(e)}")
+            return {"error": f"Failed to export subgraph: {str(e)}"}
+
+    def check_semver(self, repo_path: str, base: str, head: str = "HEAD") -> Dict[str, Any]:
+        """Indexes both revisions of `repo_path` (if needed) and classifies their API changes by semver level."""
+        roots = {label: self.graph_builder.index_revision(Path(repo_path), revision)
+                 for label, revision in (("base", base), ("head", head))}
+        report = self.code_finder.classify_semver_changes(str(roots["base"]), str(roots["head"]))
+        report["base"] = {"revision": base, "path": str(roots["base"])}
+        report["head"] = {"revision": head, "path": str(roots["head"])}
+        return report
+
+    def check_semver_tool(self, **args) -> Dict[str, Any]:
+        """Tool to classify the changes between two revisions by semver level."""
+        try:
+            debug_log(f"Checking semver changes with {args}")
+            results = self.check_semver(args["repo_path"], args["base"], args.get("head", "HEAD"))
+            return {
+                "success": True,
+                "query_type": "semver",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error checking semver changes: {str(e)}")
+            return {"error": f"Failed to check semver changes: {str(e)}"}
+
+    def diff_revisions_tool(self, **args) -> Dict[str, Any]:
+        """Tool to diff the graphs of two revisions of a repository."""
+        try:
+            debug_log(f"Diffing revisions with {args}")
+            results = self.diff_revisions(args["repo_path"], args["base"], args.get("head", "HEAD"))
+            return {
+                "success": True,
+                "query_type": "revision_diff",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error diffing revisions: {str(e)}")
+            return {"error": f"Failed to diff revisions: {str(e)}"}
+
+    def find_duplicates_tool(self, **args) -> Dict[str, Any]:
+        """Tool to cluster structurally duplicated functions."""
+        try:
+            debug_log(f"Finding duplicate functions with {args}")
+            results = self.code_finder.find_duplicates(
+                min_size=int(args.get("min_size", 20)),
+                include_same_file=args.get("include_same_file", False),
+            )
+            return {
+                "success": True,
+                "query_type": "duplicates",
+                "results": results
             }
-        }    
+        except Exception as e:
+            debug_log(f"Error finding duplicate functions: {str(e)}")
+            return {"error": f"Failed to find duplicate functions: {str(e)}"}
 
-    def get_database_status(self) -> dict:
-        """Returns the current connection status of the Neo4j database."""
-        return {"connected": self.db_manager.is_connected()}
-        
-    def get_local_package_path(self, package_name: str) -> Optional[str]:
-        """
-        Finds the local installation path of a Python package.
+    def get_code_health_report_tool(self, **args) -> Dict[str, Any]:
+        """Tool to report functions exceeding code health thresholds."""
+        try:
+            debug_log(f"Building code health report with {args}")
+            results = self.code_finder.get_code_health_report(
+                max_lines=int(args.get("max_lines", 60)),
+                max_params=int(args.get("max_params", 5)),
+                max_nesting=int(args.get("max_nesting", 4)),
+                max_complexity=int(args.get("max_complexity", 10)),
+                file_path=args.get("file_path"),
+            )
+            return {
+                "success": True,
+                "query_type": "code_health_report",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error building code health report: {str(e)}")
+            return {"error": f"Failed to build code health report: {str(e)}"}
 
-        This method uses `importlib` to locate a package and determines its root
-        directory, handling both regular packages (directories with __init__.py)
-        and single-file modules.
+    def find_unused_pub_items_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find pub items unused outside their module or crate."""
+        scope = args.get("scope", "module")
+        file_path = args.get("file_path")
+        try:
+            debug_log(f"Finding unused pub items, scope: {scope}, file: {file_path}")
+            results = self.code_finder.find_unused_pub_items(scope, file_path)
+            return {
+                "success": True,
+                "query_type": "unused_pub_items",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding unused pub items: {str(e)}")
+            return {"error": f"Failed to find unused pub items: {str(e)}"}
 
-        Args:
-            package_name: The name of the package to locate (e.g., "requests").
+    def find_unhandled_results_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find discarded Result values."""
+        file_path = args.get("file_path")
+        try:
+            debug_log(f"Finding unhandled Results, file: {file_path}")
+            results = self.code_finder.find_unhandled_results(file_path)
+            return {
+                "success": True,
+                "query_type": "unhandled_results",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding unhandled Results: {str(e)}")
+            return {"error": f"Failed to find unhandled Results: {str(e)}"}
 
-        Returns:
-            The absolute path to the package's directory as a string, or None if not found.
-        """
+    def find_generic_instantiations_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list the instantiations of a generic item."""
+        item_name = args.get("item_name")
+        file_path = args.get("file_path")
         try:
-            debug_log(f"Getting local path for package: {package_name}")
-            
-            module = importlib.import_module(package_name)
-            
-            if hasattr(module, '__file__') and module.__file__:
-                module_file = Path(module.__file__)
-                debug_log(f"Module file: {module_file}")
+            debug_log(f"Finding instantiations of generic item {item_name}")
+            results = self.code_finder.find_generic_instantiations(item_name, file_path)
+            return {
+                "success": True,
+                "query_type": "generic_instantiations",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding generic instantiations: {str(e)}")
+            return {"error": f"Failed to find generic instantiations: {str(e)}"}
 
-                if module_file.name == '__init__.py':
-                    # For a package, the path is the parent directory of __init__.py
-                    package_path = str(module_file.parent)
-                elif package_name in stdlibs.module_names:
-                    # For a standard library single file module, the path is the file itself
-                    package_path = str(module_file)
-                else:
-                    # For other single-file modules, assume the parent directory is the container
-                    package_path = str(module_file.parent)
+    def get_trait_impl_coverage_tool(self, **args) -> Dict[str, Any]:
+        """Tool to report how the impls of a trait cover its items."""
+        trait_name = args.get("trait_name")
+        file_path = args.get("file_path")
+        try:
+            debug_log(f"Reporting impl coverage of trait {trait_name}")
+            results = self.code_finder.get_trait_impl_coverage(trait_name, file_path)
+            return {
+                "success": True,
+                "query_type": "trait_impl_coverage",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error reporting trait impl coverage: {str(e)}")
+            return {"error": f"Failed to report trait impl coverage: {str(e)}"}
 
-                debug_log(f"Determined package path: {package_path}")
-                return package_path
+    def get_public_api_tool(self, **args) -> Dict[str, Any]:
+        """Tool to extract the public API of Rust crates."""
+        crate_name = args.get("crate_name")
+        try:
+            debug_log(f"Extracting public API, crate: {crate_name}")
+            results = self.code_finder.get_public_api(crate_name)
+            return {
+                "success": True,
+                "query_type": "public_api",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error extracting public API: {str(e)}")
+            return {"error": f"Failed to extract public API: {str(e)}"}
 
-            elif hasattr(module, '__path__'):
-                # This handles namespace packages which may not have an __init__.py
-                if isinstance(module.__path__, list) and module.__path__:
-                    package_path = str(Path(module.__path__[0]))
-                    debug_log(f"Package path from __path__: {package_path}")
-                    return package_path
-                else:
-                    # Fallback for other __path__ formats
-                    package_path = str(Path(str(module.__path__)))
-                    debug_log(f"Package path from __path__ (str): {package_path}")
-                    return package_path
-            
-            debug_log(f"Could not determine path for {package_name}")
-            return None
-            
-        except ImportError as e:
-            debug_log(f"Could not import {package_name}: {e}")
-            return None
+    def find_circular_module_dependencies_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find circular module dependencies."""
+        try:
+            debug_log("Finding circular module dependencies.")
+            results = self.code_finder.find_circular_module_dependencies()
+            return {
+                "success": True,
+                "query_type": "circular_module_dependencies",
+                "results": results
+            }
         except Exception as e:
-            debug_log(f"Error getting local path for {package_name}: {e}")
-            return None
-        
-    def execute_cypher_query_tool(self, **args) -> Dict[str, Any]:
-        """
-        Tool implementation for executing a read-only Cypher query.
-        
-        Important: Includes a safety check to prevent any database modification
-        by disallowing keywords like CREATE, MERGE, DELETE, etc.
-        """
-        cypher_query = args.get("cypher_query")
-        if not cypher_query:
-            return {"error": "Cypher query cannot be empty."}
+            debug_log(f"Error finding circular module dependencies: {str(e)}")
+            return {"error": f"Failed to find circular module dependencies: {str(e)}"}
 
-        # Safety Check: Prevent any write operations to the database.
-        # This check first removes all string literals and then checks for forbidden keywords.
-        forbidden_keywords = ['CREATE', 'MERGE', 'DELETE', 'SET', 'REMOVE', 'DROP', 'CALL apoc']
-        
-        # Regex to match single or double quoted strings, handling escaped quotes.
-        string_literal_pattern = r'"(?:\\.|[^"\\])*"|\'(?:\\.|[^\'\\])*\''
-        
-        # Remove all string literals from the query.
-        query_without_strings = re.sub(string_literal_pattern, '', cypher_query)
-        
-        # Now, check for forbidden keywords in the query without strings.
-        for keyword in forbidden_keywords:
-            if re.search(r'\b' + keyword + r'\b', query_without_strings, re.IGNORECASE):
-                return {
-                    "error": "This tool only supports read-only queries. Prohibited keywords like CREATE, MERGE, DELETE, SET, etc., are not allowed."
-                }
+    def find_signature_impact_tool(self, **args) -> Dict[str, Any]:
+        """Tool to analyze the impact of a signature change."""
+        function_name = args.get("function_name")
+        file_path = args.get("file_path")
+        owner = args.get("owner")
+        try:
+            debug_log(f"Analyzing signature change impact for {function_name}")
+            results = self.code_finder.find_signature_impact(function_name, file_path, owner)
+            return {
+                "success": True,
+                "query_type": "signature_impact",
+                "function_name": function_name,
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error analyzing signature change impact: {str(e)}")
+            return {"error": f"Failed to analyze signature change impact: {str(e)}"}
 
+    def find_unreachable_code_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find code unreachable from the entry points."""
+        pub_is_entry_point = args.get("pub_is_entry_point", True)
+        file_path = args.get("file_path")
         try:
-            debug_log(f"Executing Cypher query: {cypher_query}")
-            with self.db_manager.get_driver().session() as session:
-                result = session.run(cypher_query)
-                # Convert results to a list of dictionaries for clean JSON serialization.
-                records = [record.data() for record in result]
-                
-                return {
-                    "success": True,
-                    "query": cypher_query,
-                    "record_count": len(records),
-                    "results": records
-                }
-        
-        except CypherSyntaxError as e:
-            debug_log(f"Cypher syntax error: {str(e)}")
+            debug_log(f"Finding unreachable code, pub_is_entry_point: {pub_is_entry_point}")
+            results = self.code_finder.find_unreachable_code(pub_is_entry_point, file_path)
             return {
-                "error": "Cypher syntax error.",
-                "details": str(e),
-                "query": cypher_query
+                "success": True,
+                "query_type": "unreachable_code",
+                "results": results
             }
         except Exception as e:
-            debug_log(f"Error executing Cypher query: {str(e)}")
+            debug_log(f"Error finding unreachable code: {str(e)}")
+            return {"error": f"Failed to find unreachable code: {str(e)}"}
+
+    def find_transitive_callees_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find transitive callees, optionally through virtual calls."""
+        function_name = args.get("function_name")
+        file_path = args.get("file_path")
+        expand_dispatch = args.get("expand_dispatch", True)
+        max_depth = args.get("max_depth", 5)
+        try:
+            debug_log(f"Finding transitive callees of {function_name}, expand_dispatch: {expand_dispatch}")
+            results = self.code_finder.find_transitive_callees(function_name, file_path, expand_dispatch, max_depth)
             return {
-                "error": "An unexpected error occurred while executing the query.",
-                "details": str(e)
+                "success": True,
+                "query_type": "transitive_callees",
+                "function_name": function_name,
+                "results": results
             }
-    
-    def find_dead_code_tool(self, **args) -> Dict[str, Any]:
-        """Tool to find potentially dead code across the entire project."""
-        exclude_decorated_with = args.get("exclude_decorated_with", [])
+        except Exception as e:
+            debug_log(f"Error finding transitive callees: {str(e)}")
+            return {"error": f"Failed to find transitive callees: {str(e)}"}
+
+    def find_implementations_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find the implementations of a trait."""
+        trait_path = args.get("trait_path")
         try:
-            debug_log("Finding dead code.")
-            results = self.code_finder.find_dead_code(exclude_decorated_with=exclude_decorated_with)
-            
+            debug_log(f"Finding implementations of trait: {trait_path}")
+            results = self.code_finder.find_implementations(trait_path)
             return {
                 "success": True,
-                "query_type": "dead_code",
+                "query_type": "implementations",
+                "trait": trait_path,
                 "results": results
             }
         except Exception as e:
-            debug_log(f"Error finding dead code: {str(e)}")
-            return {"error": f"Failed to find dead code: {str(e)}"}
+            debug_log(f"Error finding implementations: {str(e)}")
+            return {"error": f"Failed to find implementations: {str(e)}"}
+
+    def find_types_satisfying_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find the types satisfying trait bounds."""
+        bounds = args.get("bounds")
+        try:
+            debug_log(f"Finding types satisfying: {bounds}")
+            results = self.code_finder.find_types_satisfying(bounds)
+            return {
+                "success": True,
+                "query_type": "types_satisfying",
+                "bounds": bounds,
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding types satisfying bounds: {str(e)}")
+            return {"error": f"Failed to find types satisfying bounds: {str(e)}"}
+
+    def find_newtypes_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find newtypes and wrapper types."""
+        inner_type = args.get("inner_type")
+        try:
+            debug_log(f"Finding newtypes, inner type: {inner_type}")
+            results = self.code_finder.find_newtypes(inner_type)
+            return {
+                "success": True,
+                "query_type": "newtypes",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding newtypes: {str(e)}")
+            return {"error": f"Failed to find newtypes: {str(e)}"}
+
+    def find_builders_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find builder types."""
+        type_name = args.get("type_name")
+        try:
+            debug_log(f"Finding builders, type: {type_name}")
+            results = self.code_finder.find_builders(type_name)
+            return {
+                "success": True,
+                "query_type": "builders",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding builders: {str(e)}")
+            return {"error": f"Failed to find builders: {str(e)}"}
+
+    def find_interior_mutability_tool(self, **args) -> Dict[str, Any]:
+        """Tool to report interior mutability usage."""
+        kind = args.get("kind")
+        try:
+            debug_log(f"Finding interior mutability usage, kind: {kind}")
+            results = self.code_finder.find_interior_mutability(kind)
+            return {
+                "success": True,
+                "query_type": "interior_mutability",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding interior mutability usage: {str(e)}")
+            return {"error": f"Failed to find interior mutability usage: {str(e)}"}
+
+    def find_blocking_in_async_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find blocking calls in async contexts."""
+        require_entry_point = args.get("require_entry_point", False)
+        file_path = args.get("file_path")
+        try:
+            debug_log("Finding blocking calls in async code.")
+            results = self.code_finder.find_blocking_in_async(require_entry_point, file_path)
+            return {
+                "success": True,
+                "query_type": "blocking_in_async",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding blocking calls in async code: {str(e)}")
+            return {"error": f"Failed to find blocking calls in async code: {str(e)}"}
+
+    def find_deadlock_candidates_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find lock ordering cycles."""
+        max_cycle_length = args.get("max_cycle_length", 4)
+        try:
+            debug_log("Finding deadlock candidates.")
+            results = self.code_finder.find_deadlock_candidates(max_cycle_length)
+            return {
+                "success": True,
+                "query_type": "deadlock_candidates",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding deadlock candidates: {str(e)}")
+            return {"error": f"Failed to find deadlock candidates: {str(e)}"}
+
+    def find_await_holding_lock_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find lock guards held across await points."""
+        file_path = args.get("file_path")
+        try:
+            debug_log("Finding locks held across await points.")
+            results = self.code_finder.find_await_holding_lock(file_path)
+            return {
+                "success": True,
+                "query_type": "await_holding_lock",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding locks held across await points: {str(e)}")
+            return {"error": f"Failed to find locks held across await points: {str(e)}"}
+
+    def find_thread_crossing_types_tool(self, **args) -> Dict[str, Any]:
+        """Tool to list types crossing thread boundaries and flag non-Send captures."""
+        only_non_send = args.get("only_non_send", False)
+        try:
+            debug_log("Finding thread-crossing types.")
+            results = self.code_finder.find_thread_crossing_types(only_non_send)
+            return {
+                "success": True,
+                "query_type": "thread_crossing_types",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding thread-crossing types: {str(e)}")
+            return {"error": f"Failed to find thread-crossing types: {str(e)}"}
+
+    def get_crate_info_tool(self, **args) -> Dict[str, Any]:
+        """Tool to get Rust crate metadata and estimated MSRV."""
+        crate_name = args.get("crate_name")
+        try:
+            debug_log(f"Getting crate info for: {crate_name or 'all crates'}")
+            results = self.code_finder.get_crate_info(crate_name)
+            return {
+                "success": True,
+                "crate_name": crate_name,
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error getting crate info: {str(e)}")
+            return {"error": f"Failed to get crate info: {str(e)}"}
+
+    def find_recursive_functions_tool(self, **args) -> Dict[str, Any]:
+        """Tool to find recursive and mutually recursive functions."""
+        max_cycle_length = args.get("max_cycle_length", 4)
+        file_path = args.get("file_path")
+        try:
+            debug_log("Finding recursive functions.")
+            results = self.code_finder.find_recursive_functions(max_cycle_length, file_path)
+            return {
+                "success": True,
+                "query_type": "recursion",
+                "results": results
+            }
+        except Exception as e:
+            debug_log(f"Error finding recursive functions: {str(e)}")
+            return {"error": f"Failed to find recursive functions: {str(e)}"}
 
     def calculate_cyclomatic_complexity_tool(self, **args) -> Dict[str, Any]:
         """Tool to calculate cyclomatic complexity for a given function."""
@@ -496,6 +2464,7 @@ class MCPServer:
         path = args.get("path")
         if not path:
             return {"error": "Path is a required argument."}
+        debounce_seconds = float(args.get("debounce_seconds", 2.0))
 
         path_obj = Path(path).resolve()
         path_str = str(path_obj)
@@ -519,7 +2488,7 @@ class MCPServer:
             # 3. Decide whether to perform an initial scan
             if is_already_indexed:
                 # If already indexed, just start the watcher without a scan
-                self.code_watcher.watch_directory(path_str, perform_initial_scan=False)
+                self.code_watcher.watch_directory(path_str, debounce_interval=debounce_seconds)
                 return {
                     "success": True,
                     "message": f"Path '{path_str}' is already indexed. Now watching for live changes."
@@ -530,7 +2499,7 @@ class MCPServer:
                 if "error" in scan_job_result:
                     return scan_job_result
                 
-                self.code_watcher.watch_directory(path_str, perform_initial_scan=True)
+                self.code_watcher.watch_directory(path_str, debounce_interval=debounce_seconds)
                 
                 return {
                     "success": True,
@@ -599,6 +2568,8 @@ class MCPServer:
         """
         path = args.get("path")
         is_dependency = args.get("is_dependency", False)
+        incremental = args.get("incremental", False)
+        sharded = args.get("sharded", False)
         
         try:
             path_obj = Path(path).resolve()
@@ -610,13 +2581,23 @@ class MCPServer:
                     "message": f"Path '{path}' does not exist."
                 }
 
-            # Prevent re-indexing the same repository.
+            # Prevent re-indexing the same repository, unless only its changes are wanted.
             indexed_repos = self.list_indexed_repositories_tool().get("repositories", [])
             for repo in indexed_repos:
                 if Path(repo["path"]).resolve() == path_obj:
+                    if incremental and path_obj.is_dir():
+                        job_id = self.job_manager.create_job(str(path_obj), is_dependency)
+                        coro = self.graph_builder.reindex_path_async(path_obj, job_id)
+                        asyncio.run_coroutine_threadsafe(coro, self.loop)
+                        debug_log(f"Started incremental re-index job {job_id} for path: {str(path_obj)}")
+                        return {
+                            "success": True, "job_id": job_id,
+                            "message": f"Incremental re-index started for {str(path_obj)}",
+                            "instructions": f"Use 'check_job_status' with job_id '{job_id}' to monitor progress; its result lists what changed"
+                        }
                     return {
                         "success": False,
-                        "message": f"Repository '{path}' is already indexed."
+                        "message": f"Repository '{path}' is already indexed. Pass `incremental: true` to re-index its changes."
                     }
             
             # Estimate time and create a job for the user to track.
@@ -625,9 +2606,13 @@ class MCPServer:
             self.job_manager.update_job(job_id, total_files=total_files, estimated_duration=estimated_time)
             
             # Create the coroutine for the background task and schedule it on the main event loop.
-            coro = self.graph_builder.build_graph_from_path_async(
-                path_obj, is_dependency, job_id
-            )
+            if sharded and path_obj.is_dir():
+                coro = self.graph_builder.build_sharded_async(path_obj, is_dependency, job_id, args.get("max_workers"),
+                                                              args.get("dedup", True))
+            else:
+                coro = self.graph_builder.build_graph_from_path_async(
+                    path_obj, is_dependency, job_id, args.get("dedup", True)
+                )
             asyncio.run_coroutine_threadsafe(coro, self.loop)
             
             debug_log(f"Started background job {job_id} for path: {str(path_obj)}, is_dependency: {is_dependency}")
@@ -742,6 +2727,10 @@ class MCPServer:
                 job_dict["end_time"] = job.end_time.strftime("%Y-%m-%d %H:%M:%S")
             
             job_dict["status"] = job.status.value
+            events = self.job_manager.get_progress_events(job_id)
+            job_dict["progress"] = events[-1] if events else None
+            if args.get("since_event") is not None:
+                job_dict["progress_events"] = self.job_manager.get_progress_events(job_id, int(args["since_event"]))
             
             return {"success": True, "job": job_dict}
         
@@ -830,6 +2819,75 @@ class MCPServer:
             "list_imports": self.list_imports_tool,
             "add_package_to_graph": self.add_package_to_graph_tool,
             "find_dead_code": self.find_dead_code_tool,
+            "find_recursive_functions": self.find_recursive_functions_tool,
+            "get_crate_info": self.get_crate_info_tool,
+            "find_thread_crossing_types": self.find_thread_crossing_types_tool,
+            "find_await_holding_lock": self.find_await_holding_lock_tool,
+            "find_deadlock_candidates": self.find_deadlock_candidates_tool,
+            "find_blocking_in_async": self.find_blocking_in_async_tool,
+            "find_interior_mutability": self.find_interior_mutability_tool,
+            "find_builders": self.find_builders_tool,
+            "find_newtypes": self.find_newtypes_tool,
+            "find_types_satisfying": self.find_types_satisfying_tool,
+            "find_implementations": self.find_implementations_tool,
+            "find_transitive_callees": self.find_transitive_callees_tool,
+            "find_unreachable_code": self.find_unreachable_code_tool,
+            "find_signature_impact": self.find_signature_impact_tool,
+            "preview_ignore_rules": self.preview_ignore_rules_tool,
+            "sync_to_remote": self.sync_to_remote_tool,
+            "list_shards": self.list_shards_tool,
+            "reindex_shard": self.reindex_shard_tool,
+            "get_query_cache_stats": self.get_query_cache_stats_tool,
+            "get_watch_events": self.get_watch_events_tool,
+            "find_unresolved_references": self.find_unresolved_references_tool,
+            "get_snippet": self.get_snippet_tool,
+            "record_history": self.record_history_tool,
+            "function_history": self.function_history_tool,
+            "call_history": self.call_history_tool,
+            "module_as_of": self.module_as_of_tool,
+            "import_profile": self.import_profile_tool,
+            "find_hot_functions": self.find_hot_functions_tool,
+            "import_benchmarks": self.import_benchmarks_tool,
+            "find_benchmarks": self.find_benchmarks_tool,
+            "import_coverage": self.import_coverage_tool,
+            "find_untested_code": self.find_untested_code_tool,
+            "run_clippy": self.run_clippy_tool,
+            "find_clippy_findings": self.find_clippy_findings_tool,
+            "find_allocation_sites": self.find_allocation_sites_tool,
+            "find_literal": self.find_literal_tool,
+            "list_tasks": self.list_tasks_tool,
+            "get_doc_coverage": self.get_doc_coverage_tool,
+            "module_dependency_matrix": self.module_dependency_matrix_tool,
+            "type_hierarchy": self.type_hierarchy_tool,
+            "call_hierarchy": self.call_hierarchy_tool,
+            "find_similar_functions": self.find_similar_functions_tool,
+            "suggest_module_boundaries": self.suggest_module_boundaries_tool,
+            "find_central_functions": self.find_central_functions_tool,
+            "find_method_shadowing": self.find_method_shadowing_tool,
+            "find_glob_imports": self.find_glob_imports_tool,
+            "get_canonical_paths": self.get_canonical_paths_tool,
+            "items_under_feature": self.items_under_feature_tool,
+            "estimate_monomorphization": self.estimate_monomorphization_tool,
+            "find_god_objects": self.find_god_objects_tool,
+            "find_taint_flows": self.find_taint_flows_tool,
+            "export_subgraph": self.export_subgraph_tool,
+            "check_semver": self.check_semver_tool,
+            "check_layering": self.check_layering_tool,
+            "why_depends": self.why_depends_tool,
+            "search_by_signature": self.search_by_signature_tool,
+            "semantic_search": self.semantic_search_tool,
+            "ask_code_graph": self.ask_code_graph_tool,
+            "find_hotspots": self.find_hotspots_tool,
+            "find_recently_changed_functions": self.find_recently_changed_functions_tool,
+            "diff_revisions": self.diff_revisions_tool,
+            "find_duplicates": self.find_duplicates_tool,
+            "get_code_health_report": self.get_code_health_report_tool,
+            "find_unused_pub_items": self.find_unused_pub_items_tool,
+            "find_unhandled_results": self.find_unhandled_results_tool,
+            "find_generic_instantiations": self.find_generic_instantiations_tool,
+            "get_trait_impl_coverage": self.get_trait_impl_coverage_tool,
+            "get_public_api": self.get_public_api_tool,
+            "find_circular_module_dependencies": self.find_circular_module_dependencies_tool,
             "find_code": self.find_code_tool,
             "analyze_code_relationships": self.analyze_code_relationships_tool,
             "watch_directory": self.watch_directory_tool,
@@ -849,7 +2907,14 @@ class MCPServer:
         if handler:
             # Run the synchronous tool function in a separate thread to avoid
             # blocking the main asyncio event loop.
-            return await asyncio.to_thread(handler, **args)
+            if tool_name in CACHED_TOOLS:
+                return await asyncio.to_thread(self.query_cache.get_or_compute, cache_key(tool_name, args),
+                                               lambda: handler(**args))
+            result = await asyncio.to_thread(handler, **args)
+            if tool_name not in UNCACHED_READ_TOOLS:
+                # Tools writing to the graph (imported reports, history, deletions) invalidate cached results.
+                self.graph_builder.mark_graph_changed()
+            return result
         else:
             return {"error": f"Unknown tool: {tool_name}"}
 
@@ -903,6 +2968,11 @@ class MCPServer:
                     tool_name = params.get('name')
                     args = params.get('arguments', {})
                     result = await self.handle_tool_call(tool_name, args)
+                    progress_token = (params.get('_meta') or {}).get('progressToken')
+                    if progress_token is not None and result.get("job_id"):
+                        job = self.job_manager.get_job(result["job_id"])
+                        if job and job.status in (JobStatus.PENDING, JobStatus.RUNNING):
+                            self.progress_tokens[result["job_id"]] = progress_token
                     
                     if "error" in result:
                         response = {
@@ -927,7 +2997,7 @@ class MCPServer:
                 
                 # Send the response to standard output if it's not a notification.
                 if request_id is not None and response:
-                    print(json.dumps(response), flush=True)
+                    self._send(response)
 
             except Exception as e:
                 logger.error(f"Error processing request: {e}\n{traceback.format_exc()}")
@@ -939,7 +3009,7 @@ class MCPServer:
                     "jsonrpc": "2.0", "id": request_id,
                     "error": {"code": -32603, "message": f"Internal error: {str(e)}", "data": traceback.format_exc()}
                 }
-                print(json.dumps(error_response), flush=True)
+                self._send(error_response)
 
     def shutdown(self):
         """Gracefully shuts down the server and its components."""
@@ -0,0 +1,31 @@
+# src/codegraphcontext/tools/allocations.py
+"""
+Heap allocation sites, recognized from the calls recorded on each function (`call_names`).
+
+Patterns match calls by their last path segments like taint sources and sinks do, so `Box::new`
+matches `std::boxed::Box::new` and `to_string` matches `name.to_string`. `Vec::new` and
+`String::new` do not allocate until the first push but usually precede one, so they count too.
+"""
+from typing import Any, Dict, List
+
+from .taint import call_matches
+
+# Call pattern -> what gets allocated.
+ALLOCATION_CALLS = {
+    "Box::new": "Box", "Box::pin": "Box", "Rc::new": "Rc", "Arc::new": "Arc",
+    "Vec::new": "Vec", "Vec::with_capacity": "Vec", "to_vec": "Vec",
+    "String::new": "String", "String::from": "String", "String::with_capacity": "String",
+    "to_string": "String", "to_owned": "String",
+    "HashMap::new": "HashMap", "HashMap::with_capacity": "HashMap", "BTreeMap::new": "BTreeMap",
+    "HashSet::new": "HashSet", "VecDeque::new": "VecDeque", "VecDeque::with_capacity": "VecDeque",
+}
+
+
+def allocation_calls(call_names: List[str], call_lines: List[int]) -> List[Dict[str, Any]]:
+    """The calls among `call_names` that allocate, with their line and what they allocate."""
+    sites = []
+    for name, line in zip(call_names or [], call_lines or []):
+        pattern = next((p for p in ALLOCATION_CALLS if call_matches(p, name)), None)
+        if pattern:
+            sites.append({"call": name, "allocation": ALLOCATION_CALLS[pattern], "line_number": line})
+    return sites
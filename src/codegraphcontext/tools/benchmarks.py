@@ -0,0 +1,139 @@
+# src/codegraphcontext/tools/benchmarks.py
+"""
+The indexer's own benchmark suite, run by `cgc bench` over the bundled sample projects.
+
+Per project it times three stages, without a database:
+- `parse/<project>`: parsing every supported file into its intermediate representation;
+- `resolve/<project>`: the pre-scan building the name-to-file map references resolve against;
+- `write/<project>`: turning the parsed files into nodes and relationships and writing them out,
+  through the CSV writer of `bulk_import` (the same rows indexing sends to Neo4j).
+
+Like Criterion, each benchmark runs a warm-up iteration and then `samples` timed ones, and
+reports the `mean_ns`, `median_ns` and `std_dev_ns` of an iteration, with its throughput in files
+(or nodes) per second. Results are compared against a stored baseline (a JSON file written with
+`save_baseline`): a benchmark regressed when its mean is more than `threshold` slower, improved
+when it is more than `threshold` faster, and is unchanged otherwise.
+"""
+import json
+import statistics
+import tempfile
+import time
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+
+from .bulk_import import CsvGraphWriter, export_file
+from .ignore import IgnoreRules
+
+DEFAULT_SAMPLES = 10
+DEFAULT_THRESHOLD = 0.10
+BASELINE_FILE = ".cgc-bench.json"
+
+
+def measure(work: Callable[[], Any], samples: int = DEFAULT_SAMPLES) -> Dict[str, float]:
+    """Times `samples` runs of `work` after a warm-up run; statistics of an iteration in nanoseconds."""
+    work()
+    timings = []
+    for _ in range(max(1, samples)):
+        started = time.perf_counter_ns()
+        work()
+        timings.append(time.perf_counter_ns() - started)
+    return {
+        "mean_ns": statistics.mean(timings),
+        "median_ns": statistics.median(timings),
+        "std_dev_ns": statistics.stdev(timings) if len(timings) > 1 else 0.0,
+        "samples": len(timings),
+    }
+
+
+def pre_scan(files: List[Path], parsers: Dict[str, Any]) -> dict:
+    """The imports map of `files`, built like `GraphBuilder._pre_scan_for_imports`."""
+    from .languages import javascript, python, rust
+
+    imports_map = {}
+    scanners = {'.py': python.pre_scan_python, '.js': javascript.pre_scan_javascript, '.rs': rust.pre_scan_rust}
+    for suffix, scan in scanners.items():
+        matching = [file for file in files if file.suffix == suffix]
+        if matching and suffix in parsers:
+            imports_map.update(scan(matching, parsers[suffix]))
+    return imports_map
+
+
+def _result(benchmark_id: str, timing: Dict[str, float], units: int, unit: str) -> Dict[str, Any]:
+    group, _, function = benchmark_id.partition("/")
+    return {
+        "id": benchmark_id,
+        "group": group,
+        "function": function,
+        **timing,
+        "throughput": round(units / (timing["mean_ns"] / 1e9), 1) if timing["mean_ns"] else None,
+        "throughput_unit": f"{unit}/s",
+        "change": None,
+        "verdict": None,
+    }
+
+
+def run_suite(projects: List[Path], parsers: Dict[str, Any], samples: int = DEFAULT_SAMPLES,
+              on_benchmark: Optional[Callable[[str], None]] = None) -> List[Dict[str, Any]]:
+    """Runs the parse, resolve and write benchmarks of every project; see the module docstring."""
+    results = []
+    for project in projects:
+        project = Path(project).resolve()
+        files = list(IgnoreRules.load(project).walk(parsers))
+        if not files:
+            continue
+
+        def parse_all():
+            return [parsers[file.suffix].parse(file) for file in files]
+
+        parsed = parse_all()
+        nodes = sum(1 + sum(len(file_data.get(key, [])) for key in ('functions', 'classes', 'variables'))
+                    for file_data in parsed)
+
+        def write_all():
+            writer = CsvGraphWriter()
+            writer.node("Repository", f"Repository:{project}", path=str(project), name=project.name)
+            for file_data in parsed:
+                export_file(writer, file_data, project)
+            with tempfile.TemporaryDirectory(prefix="cgc-bench-") as output:
+                writer.write(Path(output))
+
+        for name, work, units, unit in [
+            ("parse", parse_all, len(files), "files"),
+            ("resolve", lambda: pre_scan(files, parsers), len(files), "files"),
+            ("write", write_all, nodes, "nodes"),
+        ]:
+            benchmark_id = f"{name}/{project.name}"
+            if on_benchmark:
+                on_benchmark(benchmark_id)
+            results.append(_result(benchmark_id, measure(work, samples), units, unit))
+    if not results:
+        raise ValueError("No supported source files in the benchmarked projects")
+    return results
+
+
+def compare(results: List[Dict[str, Any]], baseline: Dict[str, Dict[str, Any]],
+            threshold: float = DEFAULT_THRESHOLD) -> List[Dict[str, Any]]:
+    """Sets the `change` of each result's mean against the baseline's and its `verdict`."""
+    for result in results:
+        previous = baseline.get(result["id"])
+        if not previous or not previous.get("mean_ns"):
+            continue
+        change = result["mean_ns"] / previous["mean_ns"] - 1
+        result["change"] = round(change, 4)
+        result["verdict"] = "regressed" if change > threshold else "improved" if change < -threshold else "unchanged"
+    return results
+
+
+def load_baseline(path: Path) -> Dict[str, Dict[str, Any]]:
+    """The results of a saved baseline by benchmark id; empty when there is none yet."""
+    try:
+        data = json.loads(Path(path).read_text(encoding="utf-8"))
+    except FileNotFoundError:
+        return {}
+    return {result["id"]: result for result in data.get("benchmarks", [])}
+
+
+def save_baseline(path: Path, results: List[Dict[str, Any]]):
+    """Stores results as the baseline later runs are compared against."""
+    benchmarks = [{key: value for key, value in result.items() if key not in ("change", "verdict")} for result in results]
+    Path(path).write_text(json.dumps({"saved_at": int(time.time()), "benchmarks": benchmarks}, indent=2) + "\n", encoding="utf-8")
@@ -0,0 +1,226 @@
+# src/codegraphcontext/tools/bulk_import.py
+"""
+Writes the graph of a repository as CSV files for `neo4j-admin database import full`, the offline
+bulk loader, for initial ingestion of codebases too large for transactional writes.
+
+The export holds what indexing writes per file: the Repository, Directory, File, Function, Class,
+Variable, Parameter and Module nodes, their CONTAINS and HAS_PARAMETER relationships and IMPORTS,
+with the same properties and stable ids (`uid`, see `node_ids`). References resolved across files
+(CALLS, INHERITS, ...) and the language-specific nodes (Rust impls, fields, channels, ...) need
+the graph, so they are created once the import is loaded (`GraphBuilder.complete_bulk_import_async`).
+
+Each label and relationship type gets its own file with a typed header; arrays are separated by
+ARRAY_DELIMITER and values that Neo4j cannot store (maps, nested lists) are JSON-encoded.
+"""
+import csv
+import json
+import shlex
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterable, List, Optional, Tuple
+
+from .ignore import IgnoreRules
+from .incremental import file_hash, fingerprint
+from .node_ids import assign_ids, stable_id
+
+ARRAY_DELIMITER = "\x1f"
+IMPORT_SCRIPT = "neo4j-admin-import.sh"
+
+
+def _storable(value: Any) -> Any:
+    """`value` as Neo4j stores it: primitives and lists of one primitive type as is, the rest as JSON."""
+    if value is None or isinstance(value, (bool, int, float, str)):
+        return value
+    if isinstance(value, (list, tuple)) and value and all(isinstance(v, str) for v in value):
+        return list(value)
+    if isinstance(value, (list, tuple)) and value and all(isinstance(v, (int, float)) and not isinstance(v, bool) for v in value):
+        return list(value)
+    if isinstance(value, (list, tuple)) and not value:
+        return None
+    return json.dumps(value, sort_keys=True, default=str)
+
+
+def column_type(values: Iterable[Any]) -> str:
+    """The neo4j-admin header type of a column holding `values` (None values are absent properties)."""
+    kinds = set()
+    for value in values:
+        if value is None:
+            continue
+        if isinstance(value, bool):
+            kinds.add("boolean")
+        elif isinstance(value, int):
+            kinds.add("long")
+        elif isinstance(value, float):
+            kinds.add("double")
+        elif isinstance(value, list):
+            kinds.add("double[]" if any(isinstance(v, float) for v in value) else
+                      "long[]" if isinstance(value[0], int) else "string[]")
+        else:
+            kinds.add("string")
+    if kinds <= {"long", "double"} and kinds:
+        return "double" if "double" in kinds else "long"
+    if kinds <= {"long[]", "double[]"} and kinds:
+        return "double[]" if "double[]" in kinds else "long[]"
+    return kinds.pop() if len(kinds) == 1 else "string"
+
+
+def _cell(value: Any, kind: str) -> str:
+    if value is None:
+        return ""
+    if kind.endswith("[]"):
+        if not isinstance(value, list):
+            value = [value]
+        return ARRAY_DELIMITER.join(str(v).lower() if isinstance(v, bool) else str(v) for v in value)
+    if kind == "boolean":
+        return "true" if value else "false"
+    if kind == "string" and not isinstance(value, str):
+        return json.dumps(value, sort_keys=True, default=str)
+    return str(value)
+
+
+class CsvGraphWriter:
+    """Collects nodes and relationships, merging repeats like MERGE would, and writes them as CSV."""
+
+    def __init__(self):
+        self.nodes: Dict[str, Dict[str, Dict[str, Any]]] = {}
+        self.relationships: Dict[str, Dict[Tuple[str, str], Dict[str, Any]]] = {}
+
+    def node(self, label: str, node_id: str, **props):
+        """Adds a node, or sets more properties on it; None values leave a property unchanged."""
+        stored = self.nodes.setdefault(label, {}).setdefault(node_id, {})
+        stored.update({key: _storable(value) for key, value in props.items() if value is not None})
+
+    def relationship(self, rel_type: str, start: str, end: str, **props):
+        """Adds a relationship between two nodes; one per type and pair, like MERGE."""
+        stored = self.relationships.setdefault(rel_type, {}).setdefault((start, end), {})
+        stored.update({key: _storable(value) for key, value in props.items() if value is not None})
+
+    def write(self, output_dir: Path, database: str = "neo4j") -> Dict[str, Any]:
+        """
+        Writes `nodes_<Label>.csv` and `rels_<TYPE>.csv` files and the import script into
+        `output_dir`, and returns the file names, counts and the `neo4j-admin` command.
+        """
+        output_dir = Path(output_dir)
+        output_dir.mkdir(parents=True, exist_ok=True)
+        args = ["neo4j-admin", "database", "import", "full", "--multiline-fields=true",
+                "--array-delimiter=U+001F"]
+        counts = {"nodes": {}, "relationships": {}}
+
+        for label, nodes in sorted(self.nodes.items()):
+            keys = sorted({key for props in nodes.values() for key in props})
+            types = {key: column_type(props.get(key) for props in nodes.values()) for key in keys}
+            name = f"nodes_{label}.csv"
+            with open(output_dir / name, "w", newline="", encoding="utf-8") as f:
+                writer = csv.writer(f)
+                writer.writerow([":ID"] + [f"{key}:{types[key]}" for key in keys])
+                for node_id, props in nodes.items():
+                    writer.writerow([node_id] + [_cell(props.get(key), types[key]) for key in keys])
+            args.append(f"--nodes={label}={name}")
+            counts["nodes"][label] = len(nodes)
+
+        for rel_type, relationships in sorted(self.relationships.items()):
+            keys = sorted({key for props in relationships.values() for key in props})
+            types = {key: column_type(props.get(key) for props in relationships.values()) for key in keys}
+            name = f"rels_{rel_type}.csv"
+            with open(output_dir / name, "w", newline="", encoding="utf-8") as f:
+                writer = csv.writer(f)
+                writer.writerow([":START_ID", ":END_ID"] + [f"{key}:{types[key]}" for key in keys])
+                for (start, end), props in relationships.items():
+                    writer.writerow([start, end] + [_cell(props.get(key), types[key]) for key in keys])
+            args.append(f"--relationships={rel_type}={name}")
+            counts["relationships"][rel_type] = len(relationships)
+
+        args.append(database)
+        command = " ".join(shlex.quote(arg) for arg in args)
+        script = output_dir / IMPORT_SCRIPT
+        script.write_text(f'#!/bin/sh\n# Run with the database stopped; it must not exist yet or is overwritten with --overwrite-destination.\n'
+                          f'cd "$(dirname "$0")" && exec {command} "$@"\n', encoding="utf-8")
+        script.chmod(0o755)
+        return {"output_dir": str(output_dir), "counts": counts, "command": command, "script": str(script)}
+
+
+def export_file(writer: CsvGraphWriter, file_data: Dict, repo_root: Path):
+    """Adds what `GraphBuilder.add_file_to_graph` writes for one parsed file, before the linking passes."""
+    file_path = Path(file_data['file_path']).resolve()
+    file_path_str = str(file_path)
+    try:
+        directories = list(reversed(file_path.relative_to(repo_root).parents))[1:]
+        relative_path = str(file_path.relative_to(repo_root))
+    except ValueError:
+        directories, relative_path = [], file_path.name
+    root = str(repo_root)
+    file_id = f"File:{file_path_str}"
+    writer.node("File", file_id, path=file_path_str, name=file_path.name, relative_path=relative_path,
+                is_dependency=file_data.get('is_dependency', False), docstring=file_data.get('docstring'),
+                uid=stable_id(f"{root}:File:{relative_path}"), content_hash=file_hash(file_path))
+
+    parent = f"Repository:{root}"
+    for directory in directories:
+        directory_path = str(repo_root / directory)
+        writer.node("Directory", f"Directory:{directory_path}", path=directory_path, name=directory.name)
+        writer.relationship("CONTAINS", parent, f"Directory:{directory_path}")
+        parent = f"Directory:{directory_path}"
+    writer.relationship("CONTAINS", parent, file_id)
+
+    ids = {}
+    for items, label in [(file_data['functions'], 'Function'), (file_data['classes'], 'Class'), (file_data['variables'], 'Variable')]:
+        assign_ids(root, label, relative_path, items)
+        for item in items:
+            if label == 'Function':
+                item.setdefault('cyclomatic_complexity', 1)
+            item['fingerprint'] = fingerprint(item)
+            writer.node(label, item['uid'], **{**item, "file_path": file_path_str})
+            writer.relationship("CONTAINS", file_id, item['uid'])
+            ids.setdefault((label, item['name']), []).append(item)
+
+    for func in file_data['functions']:
+        for arg_name in func.get('args', []):
+            parameter_id = f"Parameter:{file_path_str}:{func['line_number']}:{arg_name}"
+            writer.node("Parameter", parameter_id, name=arg_name, file_path=file_path_str, function_line_number=func['line_number'])
+            writer.relationship("HAS_PARAMETER", func['uid'], parameter_id)
+        if func.get("context_type") in ("function_definition", "function_item"):
+            for outer in ids.get(('Function', func.get('context')), []):
+                writer.relationship("CONTAINS", outer['uid'], func['uid'])
+        if func.get('class_context') and not func.get('impl_line'):
+            for cls in ids.get(('Class', func['class_context']), []):
+                writer.relationship("CONTAINS", cls['uid'], func['uid'])
+
+    for imp in file_data.get('imports', []):
+        if file_data.get('lang') == 'javascript':
+            if not imp.get('source'):
+                continue
+            writer.node("Module", f"Module:{imp['source']}", name=imp['source'])
+            writer.relationship("IMPORTS", file_id, f"Module:{imp['source']}",
+                                imported_name=imp.get('name', '*'), alias=imp.get('alias'))
+        else:
+            writer.node("Module", f"Module:{imp['name']}", name=imp['name'], alias=imp.get('alias'),
+                        full_import_name=imp.get('full_import_name'))
+            writer.relationship("IMPORTS", file_id, f"Module:{imp['name']}",
+                                **{key: imp[key] for key in ('line_number', 'full_import_name', 'is_reexport', 'alias') if key in imp})
+
+
+def export_repository(repo_path: Path, output_dir: Path, parse: Callable[[Path], Dict], suffixes: Iterable[str],
+                      is_dependency: bool = False, on_file: Optional[Callable[[Path], None]] = None) -> Dict[str, Any]:
+    """
+    Parses every supported file of a repository that its ignore rules keep (see `ignore`) with
+    `parse` and writes the import files; returns what `CsvGraphWriter.write` does plus the files
+    that failed to parse.
+    """
+    repo_root = Path(repo_path).resolve()
+    writer = CsvGraphWriter()
+    writer.node("Repository", f"Repository:{repo_root}", path=str(repo_root), name=repo_root.name, is_dependency=is_dependency)
+    errors: List[str] = []
+    files = list(IgnoreRules.load(repo_root).walk(suffixes))
+    for file in files:
+        if on_file:
+            on_file(file)
+        try:
+            file_data = parse(file)
+        except Exception as e:
+            file_data = {"error": str(e)}
+        if "error" in file_data:
+            errors.append(f"{file}: {file_data['error']}")
+            continue
+        export_file(writer, file_data, repo_root)
+    summary = writer.write(output_dir)
+    summary.update({"files": len(files) - len(errors), "errors": errors})
+    return summary
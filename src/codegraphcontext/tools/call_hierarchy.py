@@ -0,0 +1,72 @@
+# src/codegraphcontext/tools/call_hierarchy.py
+"""
+Shapes call graph neighbourhoods like LSP call hierarchies (`callHierarchy/incomingCalls` and
+`outgoingCalls`), so editor plugins can render them directly.
+
+Items are `CallHierarchyItem`s: `name`, a numeric LSP `kind` (function, method or constructor),
+`detail` (the signature, or the owning type), `uri`, `range` spanning the whole function and
+`selectionRange` on its first line. Lines are zero-based as in LSP; the graph has no columns, so
+every `character` is 0. Incoming entries hold the caller in `from`, outgoing entries the callee in
+`to`, and both list the call sites as `fromRanges`, which are always in the calling function.
+Each entry nests the next level in `calls`; an entry calling back into a function already on
+its path is marked `recursive` and not expanded.
+"""
+from pathlib import Path
+from typing import Any, Dict, List, Tuple
+
+SYMBOL_KIND_FUNCTION = 12
+SYMBOL_KIND_METHOD = 6
+SYMBOL_KIND_CONSTRUCTOR = 9
+CONSTRUCTOR_NAMES = {"new", "__init__", "constructor", "default"}
+
+
+def lsp_range(start_line: int, end_line: int = None) -> Dict[str, Dict[str, int]]:
+    """A range from the start of `start_line` to the start of `end_line` (1-based graph lines)."""
+    start = max((start_line or 1) - 1, 0)
+    end = max((end_line or start_line or 1) - 1, start)
+    return {"start": {"line": start, "character": 0}, "end": {"line": end, "character": 0}}
+
+
+def hierarchy_item(node: Dict[str, Any]) -> Dict[str, Any]:
+    """A `CallHierarchyItem` for a function node `{id, name, owner, file_path, line_number, end_line, signature}`."""
+    if node.get('owner'):
+        kind = SYMBOL_KIND_CONSTRUCTOR if node['name'] in CONSTRUCTOR_NAMES else SYMBOL_KIND_METHOD
+    else:
+        kind = SYMBOL_KIND_FUNCTION
+    return {
+        "name": f"{node['owner']}::{node['name']}" if node.get('owner') else node['name'],
+        "kind": kind,
+        "detail": node.get('signature') or node.get('owner') or "",
+        "uri": Path(node['file_path']).as_uri() if node.get('file_path') else None,
+        "range": lsp_range(node.get('line_number'), node.get('end_line')),
+        "selectionRange": lsp_range(node.get('line_number')),
+        "data": {"id": node['id']},
+    }
+
+
+def build_tree(root: str, nodes: Dict[str, Dict], calls: Dict[Tuple[str, str], List[int]],
+               direction: str, depth: int) -> Dict[str, Any]:
+    """
+    The hierarchy of `root` to `depth` levels, from `nodes` by id and `calls` mapping
+    `(caller_id, callee_id)` to the call lines. `direction` is `incoming` or `outgoing`.
+    """
+    neighbours = {}
+    for (caller, callee), lines in calls.items():
+        if direction == 'incoming':
+            neighbours.setdefault(callee, []).append((caller, lines))
+        else:
+            neighbours.setdefault(caller, []).append((callee, lines))
+    side = "from" if direction == 'incoming' else "to"
+
+    def expand(node_id, path, level):
+        entries = []
+        for other, lines in sorted(neighbours.get(node_id, []), key=lambda n: (nodes[n[0]]['file_path'] or '', nodes[n[0]]['line_number'] or 0)):
+            entry = {side: hierarchy_item(nodes[other]), "fromRanges": [lsp_range(line) for line in sorted(set(lines) - {None})]}
+            if other in path:
+                entry["recursive"] = True
+            elif level < depth:
+                entry["calls"] = expand(other, path | {other}, level + 1)
+            entries.append(entry)
+        return entries
+
+    return {"item": hierarchy_item(nodes[root]), "direction": direction, "calls": expand(root, {root}, 1)}
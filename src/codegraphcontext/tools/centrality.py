@@ -0,0 +1,90 @@
+# src/codegraphcontext/tools/centrality.py
+"""
+Centrality scores over the call graph, to tell the functions everything else revolves around.
+
+PageRank ranks a function highly when it is called by many functions that are themselves called
+a lot, so utilities and core abstractions come first. Betweenness counts how many shortest call
+chains between two functions pass through a function, which surfaces the glue code and
+bottlenecks connecting otherwise separate parts. Both are normalized: PageRank scores sum to 1 and
+betweenness is divided by the number of ordered pairs of other functions.
+"""
+from collections import deque
+from typing import Dict, Hashable, Iterable, List, Optional, Tuple
+
+# Betweenness is exact up to this many functions and estimated from as many sources above it.
+BETWEENNESS_MAX_SOURCES = 1000
+
+
+def pagerank(nodes: Iterable[Hashable], edges: Iterable[Tuple[Hashable, Hashable]], damping: float = 0.85,
+             iterations: int = 100, tolerance: float = 1e-9) -> Dict[Hashable, float]:
+    """PageRank by power iteration; functions calling nothing spread their score over every function."""
+    nodes = list(nodes)
+    if not nodes:
+        return {}
+    callees = {node: set() for node in nodes}
+    for caller, callee in edges:
+        if caller in callees and callee in callees and caller != callee:
+            callees[caller].add(callee)
+
+    count = len(nodes)
+    rank = {node: 1.0 / count for node in nodes}
+    for _ in range(iterations):
+        dangling = sum(rank[node] for node in nodes if not callees[node])
+        new = {node: (1 - damping) / count + damping * dangling / count for node in nodes}
+        for caller, targets in callees.items():
+            if targets:
+                share = damping * rank[caller] / len(targets)
+                for callee in targets:
+                    new[callee] += share
+        converged = sum(abs(new[node] - rank[node]) for node in nodes) < tolerance
+        rank = new
+        if converged:
+            break
+    return rank
+
+
+def betweenness(nodes: Iterable[Hashable], edges: Iterable[Tuple[Hashable, Hashable]],
+                max_sources: Optional[int] = None) -> Dict[Hashable, float]:
+    """
+    Brandes' betweenness over the directed call graph. With `max_sources`, only that many evenly
+    spread functions start the shortest path searches and the result is scaled up, which keeps
+    large graphs fast at the cost of an estimate.
+    """
+    nodes = list(nodes)
+    callees = {node: [] for node in nodes}
+    for caller, callee in set(edges):
+        if caller in callees and callee in callees and caller != callee:
+            callees[caller].append(callee)
+
+    sources: List[Hashable] = nodes
+    if max_sources and len(nodes) > max_sources:
+        step = len(nodes) / max_sources
+        sources = [nodes[int(i * step)] for i in range(max_sources)]
+
+    score = {node: 0.0 for node in nodes}
+    for source in sources:
+        order, predecessors = [], {node: [] for node in nodes}
+        paths = dict.fromkeys(nodes, 0)
+        distance = dict.fromkeys(nodes, -1)
+        paths[source], distance[source] = 1, 0
+        queue = deque([source])
+        while queue:
+            node = queue.popleft()
+            order.append(node)
+            for callee in callees[node]:
+                if distance[callee] < 0:
+                    distance[callee] = distance[node] + 1
+                    queue.append(callee)
+                if distance[callee] == distance[node] + 1:
+                    paths[callee] += paths[node]
+                    predecessors[callee].append(node)
+        dependency = dict.fromkeys(nodes, 0.0)
+        for node in reversed(order):
+            for predecessor in predecessors[node]:
+                dependency[predecessor] += paths[predecessor] / paths[node] * (1 + dependency[node])
+            if node != source:
+                score[node] += dependency[node]
+
+    count = len(nodes)
+    scale = (count / len(sources) if sources else 1) / ((count - 1) * (count - 2) if count > 2 else 1)
+    return {node: value * scale for node, value in score.items()}
@@ -0,0 +1,73 @@
+# src/codegraphcontext/tools/clippy.py
+"""
+Runs `cargo clippy --message-format=json` on a crate and turns its output into diagnostics that can
+be attached to graph nodes.
+
+Cargo prints one JSON object per line; lints are the `compiler-message` objects with a lint `code`
+(`clippy::needless_return`, or a rustc lint such as `unused_variables`). Their primary span gives the
+file, relative to the workspace root, and the lines the diagnostic covers. The same diagnostic is
+reported once per target (lib, tests, examples) and is kept once.
+"""
+import json
+import logging
+import subprocess
+from pathlib import Path
+from typing import Any, Dict, Iterable, List
+
+logger = logging.getLogger(__name__)
+
+
+def parse_diagnostics(lines: Iterable[str], workspace_root: Path) -> List[Dict[str, Any]]:
+    """The lint diagnostics in cargo's JSON output, with absolute file paths, in file and line order."""
+    diagnostics = {}
+    for line in lines:
+        try:
+            entry = json.loads(line)
+        except json.JSONDecodeError:
+            continue
+        message = entry.get("message") if entry.get("reason") == "compiler-message" else None
+        if not message or not message.get("code"):
+            continue
+        span = next((span for span in message.get("spans", []) if span.get("is_primary")), None)
+        if span is None:
+            continue
+        file_path = (Path(workspace_root) / span["file_name"]).resolve()
+        lint = message["code"]["code"]
+        key = (lint, str(file_path), span["line_start"], span["column_start"])
+        diagnostics.setdefault(key, {
+            "lint": lint,
+            "source": "clippy" if lint.startswith("clippy::") else "rustc",
+            "level": message.get("level"),
+            "message": message.get("message"),
+            "file_path": str(file_path),
+            "line_number": span["line_start"],
+            "end_line": span["line_end"],
+            "column": span["column_start"],
+            "suggestion": span.get("suggested_replacement"),
+        })
+    return sorted(diagnostics.values(), key=lambda d: (d["file_path"], d["line_number"], d["column"], d["lint"]))
+
+
+def run_clippy(crate_path: Path, extra_args: List[str] = None) -> List[Dict[str, Any]]:
+    """
+    Runs clippy on every target of the crate (or workspace) at `crate_path` and returns its lint
+    diagnostics. Raises ValueError when cargo or clippy is missing or the crate does not build.
+    """
+    crate_path = Path(crate_path).resolve()
+    try:
+        metadata = subprocess.run(["cargo", "metadata", "--no-deps", "--format-version", "1"], cwd=crate_path,
+                                  capture_output=True, check=True)
+        process = subprocess.run(["cargo", "clippy", "--all-targets", "--message-format=json", *(extra_args or [])],
+                                 cwd=crate_path, capture_output=True)
+    except FileNotFoundError:
+        raise ValueError("cargo is not installed or not on PATH")
+    except subprocess.CalledProcessError as e:
+        raise ValueError(f"cargo metadata failed in {crate_path}: {e.stderr.decode(errors='replace').strip()}")
+
+    stdout = process.stdout.decode(errors="replace")
+    if process.returncode != 0 and '"reason":"build-finished"' not in stdout:
+        raise ValueError(f"cargo clippy failed in {crate_path}: {process.stderr.decode(errors='replace').strip()[-2000:]}")
+    workspace_root = Path(json.loads(metadata.stdout)["workspace_root"])
+    diagnostics = parse_diagnostics(stdout.splitlines(), workspace_root)
+    logger.info(f"cargo clippy reported {len(diagnostics)} diagnostics in {crate_path}")
+    return diagnostics
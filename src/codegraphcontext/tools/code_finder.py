@@ -1,13 +1,41 @@
 # src/codegraphcontext/tools/code_finder.py
 import logging
+import os
 import re
+import time
 from typing import Any, Dict, List
 from pathlib import Path
 
+from neo4j import READ_ACCESS, Query
+
 from ..core.database import DatabaseManager
+from .allocations import allocation_calls
+from .call_hierarchy import build_tree
+from .communities import louvain, modularity, suggest_moves
+from .dsm import build_matrix
+from .embeddings import get_embedder
+from .git_history import blame_file, file_churn, open_repository
+from .graph_export import EXCLUDED_PROPERTIES
+from .layering import LAYERS_CONFIG_FILE, evaluate_rules, load_rules
+from .monomorphization import estimate_copies
+from .nl_query import translate_question
+from .semver_check import classify_changes
+from .signature_search import match_function, parse_signature
+from .similarity import shape_similarity
+from .snippets import expand_tree, source_text
+from .taint import DEFAULT_SINKS, DEFAULT_SOURCES, find_flows
+from .temporal import describe_ranges, valid_at
+from .type_hierarchy import render_tree
 
 logger = logging.getLogger(__name__)
 
+# Relationships from one module's code to another's that make the first depend on the second.
+DEPENDENCY_EVIDENCE = {
+    'CALLS': 'calls', 'MAY_CALL': 'calls', 'REFERENCES_TYPE': 'type_usages', 'INSTANTIATES': 'type_usages',
+    'READS_FIELD': 'field_accesses', 'WRITES_FIELD': 'field_accesses',
+    'CONSTRUCTS': 'variant_usages', 'MATCHES': 'variant_usages', 'IMPLEMENTS': 'trait_impls',
+}
+
 class CodeFinder:
     """Module for finding relevant code snippets and analyzing relationships."""
 
@@ -23,11 +51,12 @@ class CodeFinder:
                 WITH node, score
                 WHERE node:Function AND node.name CONTAINS $search_term
                 RETURN node.name as name, node.file_path as file_path, node.line_number as line_number,
-                       node.source as source, node.docstring as docstring, node.is_dependency as is_dependency
+                       node.column as column, node.end_line as end_line,
+                       coalesce(node.source, node.source_compressed) as source, node.docstring as docstring, node.is_dependency as is_dependency
                 ORDER BY score DESC
                 LIMIT 20
             """, search_term=search_term)
-            return [dict(record) for record in result]
+            return [expand_tree(dict(record)) for record in result]
     
     def find_by_class_name(self, search_term: str) -> List[Dict]:
         """Find classes by name matching using the full-text index."""
@@ -37,11 +66,12 @@ class CodeFinder:
                 WITH node, score
                 WHERE node:Class AND node.name CONTAINS $search_term
                 RETURN node.name as name, node.file_path as file_path, node.line_number as line_number,
-                       node.source as source, node.docstring as docstring, node.is_dependency as is_dependency
+                       node.column as column, node.end_line as end_line,
+                       coalesce(node.source, node.source_compressed) as source, node.docstring as docstring, node.is_dependency as is_dependency
                 ORDER BY score DESC
                 LIMIT 20
             """, search_term=search_term)
-            return [dict(record) for record in result]
+            return [expand_tree(dict(record)) for record in result]
 
     def find_by_variable_name(self, search_term: str) -> List[Dict]:
         """Find variables by name matching"""
@@ -50,7 +80,7 @@ class CodeFinder:
                 MATCH (v:Variable)
                 WHERE v.name CONTAINS $search_term OR v.name =~ $regex_pattern
                 RETURN v.name as name, v.file_path as file_path, v.line_number as line_number,
-                       v.value as value, v.context as context, v.is_dependency as is_dependency
+                       v.value as value, v.context as context, v.docstring as docstring, v.is_dependency as is_dependency
                 ORDER BY v.is_dependency ASC, v.name
                 LIMIT 20
             """, search_term=search_term, regex_pattern=f"(?i).*{re.escape(search_term)}.*")
@@ -71,13 +101,34 @@ class CodeFinder:
                         ELSE 'variable' 
                     END as type,
                     node.name as name, node.file_path as file_path,
-                    node.line_number as line_number, node.source as source,
+                    node.line_number as line_number, coalesce(node.source, node.source_compressed) as source,
                     node.docstring as docstring, node.is_dependency as is_dependency
                 ORDER BY score DESC
                 LIMIT 20
             """, search_term=search_term)
-            return [dict(record) for record in result]
+            return [expand_tree(dict(record)) for record in result]
     
+    def find_literal(self, text: str, kind: str = None, exact: bool = False, limit: int = 50) -> List[Dict]:
+        """
+        Find the functions (and constants) containing a string, URL or number literal, e.g. which
+        function produces the error message "Division by zero". Matches literals containing `text`,
+        case-insensitively, or equal to it when `exact`.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (holder)-[r:CONTAINS_LITERAL]->(l:Literal)
+                WHERE holder.revision IS NULL AND ($kind IS NULL OR l.kind = $kind)
+                  AND (CASE WHEN $exact THEN l.value = $text ELSE toLower(l.value) CONTAINS toLower($text) END)
+                RETURN l.value as value, l.kind as kind,
+                       CASE WHEN holder:Function THEN holder.name END as function,
+                       CASE WHEN holder:Function THEN holder.class_context END as owner, r.constant as constant,
+                       coalesce(holder.file_path, holder.path) as file_path, r.line_number as line_number,
+                       r.in_macro as in_macro
+                ORDER BY size(l.value), file_path, line_number
+                LIMIT $limit
+            """, text=text, kind=kind, exact=exact, limit=limit)
+            return [dict(record) for record in result]
+
     def find_related_code(self, user_query: str) -> Dict[str, Any]:
         """Find code related to a query using multiple search strategies"""
         results = {
@@ -184,6 +235,7 @@ class CodeFinder:
                         call.args as call_args,
                         call.full_call_name as full_call_name,
                         call.call_type as call_type,
+                        call.call_count as call_count,
                         target.file_path as target_file_path
                     ORDER BY caller.is_dependency ASC, caller.file_path, caller.line_number
                     LIMIT 20
@@ -205,6 +257,7 @@ class CodeFinder:
                             call.args as call_args,
                             call.full_call_name as full_call_name,
                             call.call_type as call_type,
+                            call.call_count as call_count,
                             target.file_path as target_file_path
                         ORDER BY caller.is_dependency ASC, caller.file_path, caller.line_number
                         LIMIT 20
@@ -225,6 +278,7 @@ class CodeFinder:
                         call.args as call_args,
                         call.full_call_name as full_call_name,
                         call.call_type as call_type,
+                        call.call_count as call_count,
                         target.file_path as target_file_path
                     ORDER BY caller.is_dependency ASC, caller.file_path, caller.line_number
                     LIMIT 20
@@ -252,7 +306,8 @@ class CodeFinder:
                         call.line_number as call_line_number,
                         call.args as call_args,
                         call.full_call_name as full_call_name,
-                        call.call_type as call_type
+                        call.call_type as call_type,
+                        call.call_count as call_count
                     ORDER BY called.is_dependency ASC, called.name
                     LIMIT 20
                 """, function_name=function_name, absolute_file_path=absolute_file_path)
@@ -270,7 +325,8 @@ class CodeFinder:
                         call.line_number as call_line_number,
                         call.args as call_args,
                         call.full_call_name as full_call_name,
-                        call.call_type as call_type
+                        call.call_type as call_type,
+                        call.call_count as call_count
                     ORDER BY called.is_dependency ASC, called.name
                     LIMIT 20
                 """, function_name=function_name)
@@ -371,7 +427,7 @@ class CodeFinder:
             
             methods_query = f"""
                 {match_clause}
-                MATCH (child)-[:CONTAINS]->(method:Function)
+                MATCH (child)-[:HAS_IMPL*0..1]->()-[:CONTAINS]->(method:Function)
                 RETURN DISTINCT
                     method.name as method_name,
                     method.file_path as method_file_path,
@@ -394,7 +450,7 @@ class CodeFinder:
         """Find all implementations of a function across different classes"""
         with self.driver.session() as session:
             result = session.run("""
-                MATCH (class:Class)-[:CONTAINS]->(func:Function {name: $function_name})
+                MATCH (class:Class)-[:HAS_IMPL*0..1]->()-[:CONTAINS]->(func:Function {name: $function_name})
                 OPTIONAL MATCH (file:File)-[:CONTAINS]->(class)
                 RETURN DISTINCT
                     class.name as class_name,
@@ -446,6 +502,243 @@ class CodeFinder:
                 "note": "These functions might be unused, but could be entry points, callbacks, or called dynamically"
             }
     
+    def find_unreachable_code(self, pub_is_entry_point: bool = True, file_path: str = None) -> Dict[str, Any]:
+        """
+        Reachability-based dead code detection for Rust. Starting from entry points (`main`, test and
+        bench functions, `#[no_mangle]`/`extern` functions and, unless disabled, the `pub` API), follows
+        CALLS and MAY_CALL edges; trait impl methods count as reachable once their type is. Reports the
+        functions never reached, the types never used by reachable code, and the enum variants of used
+        enums that reachable code never constructs.
+        """
+        with self.driver.session() as session:
+            functions = {record['id']: expand_tree(dict(record)) for record in session.run("""
+                MATCH (f:Function {lang: 'rust'})
+                WHERE coalesce(f.is_dependency, false) = false
+                RETURN elementId(f) as id, f.name as name, f.file_path as file_path, f.line_number as line_number,
+                       f.visibility as visibility, coalesce(f.decorators, []) as decorators,
+                       coalesce(f.source, f.source_compressed) as source,
+                       f.class_context as class_context, f.impl_trait as impl_trait, f.context_type as context_type
+            """)}
+            edges = {}
+            for record in session.run("""
+                MATCH (a:Function {lang: 'rust'})-[:CALLS|MAY_CALL|CONTAINS*1..2]->(b:Function)
+                WHERE a <> b AND ALL(n IN [a, b] WHERE coalesce(n.is_dependency, false) = false)
+                RETURN DISTINCT elementId(a) as source, elementId(b) as target
+            """):
+                edges.setdefault(record['source'], set()).add(record['target'])
+            types = [dict(record) for record in session.run("""
+                MATCH (c:Class {lang: 'rust'})
+                WHERE coalesce(c.is_dependency, false) = false
+                OPTIONAL MATCH (c)-[:HAS_FIELD]->(fd:Field)
+                RETURN c.name as name, c.file_path as file_path, c.line_number as line_number, c.kind as kind,
+                       c.visibility as visibility, collect(fd.type) as field_types
+            """)]
+            variants = [dict(record) for record in session.run("""
+                MATCH (v:Variant)-[:VARIANT_OF]->(e:Class)
+                OPTIONAL MATCH (fn:Function)-[:CONSTRUCTS]->(v)
+                RETURN v.name as name, e.name as enum, v.file_path as file_path, v.line_number as line_number,
+                       collect(elementId(fn)) as constructed_by
+            """)]
+
+        def is_entry_point(func):
+            attributes = ' '.join(func['decorators'])
+            return (func['name'] == 'main' and func['context_type'] is None
+                    or re.search(r'#\[\s*([\w:]*test|bench|no_mangle|export_name)\b', attributes) is not None
+                    or (func['source'] or '').lstrip().startswith(('extern ', 'pub extern '))
+                    or (pub_is_entry_point and func['visibility'] == 'pub'))
+
+        reachable = set()
+        frontier = [fid for fid, func in functions.items() if is_entry_point(func)]
+        reachable_types = {t['name'] for t in types if pub_is_entry_point and t['visibility'] == 'pub'}
+        while frontier:
+            while frontier:
+                fid = frontier.pop()
+                if fid in reachable:
+                    continue
+                reachable.add(fid)
+                func = functions.get(fid, {})
+                reachable_types.update(name for name in (func.get('class_context'), func.get('impl_trait')) if name)
+                frontier.extend(edges.get(fid, ()) - reachable)
+            # Trait impl methods are called through the trait, so they live as long as their type.
+            frontier = [fid for fid, func in functions.items()
+                        if fid not in reachable and func['impl_trait'] and func['class_context'] in reachable_types]
+
+        reachable_sources = ' '.join(functions[fid]['source'] or '' for fid in reachable if fid in functions)
+        used_words = set(re.findall(r'\b[A-Z]\w*', reachable_sources))
+        types_by_name = {}
+        for type_info in types:
+            types_by_name.setdefault(type_info['name'], []).append(type_info)
+        pending = [name for name in used_words | reachable_types if name in types_by_name]
+        reachable_types |= set(pending)
+        while pending:
+            for type_info in types_by_name.get(pending.pop(), []):
+                for field_type in type_info['field_types']:
+                    for word in re.findall(r'\b[A-Z]\w*', field_type or ''):
+                        if word in types_by_name and word not in reachable_types:
+                            reachable_types.add(word)
+                            pending.append(word)
+
+        in_scope = lambda item: file_path is None or item['file_path'] == file_path
+        unreachable_functions = [
+            {"name": func['name'], "file_path": func['file_path'], "line_number": func['line_number'],
+             "owner": func['class_context']}
+            for fid, func in functions.items() if fid not in reachable and in_scope(func)
+        ]
+        unreachable_types = [
+            {"name": t['name'], "file_path": t['file_path'], "line_number": t['line_number'], "kind": t['kind']}
+            for t in types if t['name'] not in reachable_types and in_scope(t)
+        ]
+        unconstructed_variants = [
+            {"name": v['name'], "enum": v['enum'], "file_path": v['file_path'], "line_number": v['line_number']}
+            for v in variants
+            if v['enum'] in reachable_types and in_scope(v) and not any(fid in reachable for fid in v['constructed_by'])
+        ]
+        sort_key = lambda item: (item['file_path'], item['line_number'])
+        return {
+            "unreachable_functions": sorted(unreachable_functions, key=sort_key),
+            "unreachable_types": sorted(unreachable_types, key=sort_key),
+            "unconstructed_variants": sorted(unconstructed_variants, key=sort_key),
+            "entry_points": "main, tests, benches, exported functions" + (" and the pub API" if pub_is_entry_point else ""),
+        }
+
+    def find_unused_pub_items(self, scope: str = 'module', file_path: str = None) -> List[Dict]:
+        """
+        Find Rust functions and types declared `pub` (or `pub(..)`) that nothing outside their
+        defining module uses (`scope='module'`), or `pub` items nothing outside their crate uses
+        (`scope='crate'`). Uses are calls, trait dispatch, type mentions, instantiations, variant
+        and field accesses and trait impls; modules are approximated by files. Each finding suggests
+        the narrowest visibility that keeps the current uses compiling.
+        """
+        with self.driver.session() as session:
+            items = [dict(record) for record in session.run("""
+                MATCH (n {lang: 'rust'})
+                WHERE (n:Function OR n:Class) AND n.visibility STARTS WITH 'pub'
+                  AND coalesce(n.is_dependency, false) = false AND n.impl_trait IS NULL
+                  AND ($file_path IS NULL OR n.file_path = $file_path)
+                OPTIONAL MATCH (:File {path: n.file_path})-[:PART_OF]->(cr:Crate)
+                RETURN elementId(n) as id, n.name as name, CASE WHEN n:Class THEN n.kind ELSE 'function' END as kind,
+                       n.class_context as owner, n.visibility as visibility, n.file_path as file_path,
+                       n.line_number as line_number, coalesce(n.decorators, []) as decorators,
+                       coalesce(n.public_paths, []) as public_paths, cr.path as crate
+            """, file_path=file_path)]
+            uses = [dict(record) for record in session.run("""
+                MATCH (src)-[:CALLS|MAY_CALL|REFERENCES_TYPE|INSTANTIATES]->(n)
+                WHERE n:Function OR n:Class
+                RETURN DISTINCT elementId(n) as id, coalesce(src.file_path, src.path) as file_path
+                UNION
+                MATCH (src:Function)-[:CONSTRUCTS|MATCHES]->(:Variant)-[:VARIANT_OF]->(n:Class)
+                RETURN DISTINCT elementId(n) as id, src.file_path as file_path
+                UNION
+                MATCH (src:Function)-[:READS_FIELD|WRITES_FIELD]->(:Field)<-[:HAS_FIELD]-(n:Class)
+                RETURN DISTINCT elementId(n) as id, src.file_path as file_path
+                UNION
+                MATCH (i:Impl)-[:IMPLEMENTS]->(n:Class)
+                RETURN DISTINCT elementId(n) as id, i.file_path as file_path
+            """)]
+            crates = {record['path']: record['crate'] for record in session.run("""
+                MATCH (f:File)-[:PART_OF]->(cr:Crate)
+                RETURN f.path as path, cr.path as crate
+            """)}
+
+        used_from = {}
+        for use in uses:
+            used_from.setdefault(use['id'], set()).add(use['file_path'])
+
+        findings = []
+        for item in items:
+            # Tests and entry points are used by the harness, not by other code.
+            if item['name'] == 'main' or any(re.search(r'#\[\s*([\w:]*test|bench)\b', d) for d in item['decorators']):
+                continue
+            other_files = used_from.get(item['id'], set()) - {item['file_path']}
+            other_crates = {crates.get(path) for path in other_files} - {item['crate']}
+            if scope == 'crate':
+                if item['visibility'] != 'pub' or other_crates:
+                    continue
+                suggested = 'pub(crate)' if other_files else 'private'
+            elif other_files:
+                continue
+            else:
+                suggested = 'private'
+            findings.append({
+                "name": item['name'] if not item['owner'] else f"{item['owner']}::{item['name']}",
+                "kind": item['kind'],
+                "visibility": item['visibility'],
+                "suggested_visibility": suggested,
+                "file_path": item['file_path'],
+                "line_number": item['line_number'],
+                "used_in_files": sorted(other_files),
+                "public_paths": item['public_paths'],
+            })
+        return sorted(findings, key=lambda f: (f['file_path'], f['line_number']))
+
+    def _estimate_recursion_depth(self, params: List[str], recursive_calls: List[List[str]]) -> Dict[str, Any]:
+        """
+        Approximates the recursion depth from the arguments of the recursive calls: `n - 1` is
+        linear in `n`, `n / 2` or `n >> 1` is logarithmic. Returns None bounds when no argument
+        shrinks a parameter in a recognisable way.
+        """
+        bound = None
+        for args in recursive_calls:
+            for arg in args or []:
+                arg = arg.strip()
+                linear = re.fullmatch(r'(\w+)\s*-\s*\d+', arg)
+                halving = re.fullmatch(r'(\w+)\s*(/\s*\d+|>>\s*\d+)', arg)
+                if linear and linear.group(1) in params and bound is None:
+                    bound = f"O({linear.group(1)})"
+                elif halving and halving.group(1) in params:
+                    bound = f"O(log {halving.group(1)})"
+        return {
+            "depth_bound": bound,
+            "recursive_call_sites": len(recursive_calls),
+            "branching": len(recursive_calls) > 1,
+        }
+
+    def find_recursive_functions(self, max_cycle_length: int = 4, file_path: str = None) -> Dict[str, Any]:
+        """Find directly recursive functions and mutually recursive call cycles of up to `max_cycle_length` functions."""
+        max_cycle_length = max(2, min(int(max_cycle_length), 8))
+        with self.driver.session() as session:
+            direct = session.run("""
+                MATCH (f:Function)-[call:CALLS]->(f)
+                WHERE f.is_dependency = false AND ($file_path IS NULL OR f.file_path = $file_path)
+                RETURN f.name as function_name, f.class_context as owner, f.file_path as file_path,
+                       f.line_number as line_number, f.args as params, collect(call.args) as call_args
+                ORDER BY f.file_path, f.line_number
+            """, file_path=file_path)
+
+            direct_recursion = []
+            for record in direct:
+                entry = {key: record[key] for key in ('function_name', 'owner', 'file_path', 'line_number')}
+                entry.update(self._estimate_recursion_depth(record['params'] or [], record['call_args']))
+                direct_recursion.append(entry)
+
+            # The upper bound of a variable-length pattern cannot be a parameter.
+            cycles = session.run(f"""
+                MATCH path = (f:Function)-[:CALLS*2..{max_cycle_length}]->(f)
+                WHERE f.is_dependency = false AND ($file_path IS NULL OR f.file_path = $file_path)
+                  AND ALL(n IN nodes(path)[1..-1] WHERE n <> f)
+                RETURN [n IN nodes(path)[..-1] | {{name: n.name, file_path: n.file_path, line_number: n.line_number, id: elementId(n)}}] as members
+                LIMIT 500
+            """, file_path=file_path)
+
+            seen = set()
+            mutual_recursion = []
+            for record in cycles:
+                members = record['members']
+                ids = [m['id'] for m in members]
+                if len(set(ids)) != len(ids) or frozenset(ids) in seen:
+                    continue
+                seen.add(frozenset(ids))
+                mutual_recursion.append({
+                    "members": [{k: m[k] for k in ('name', 'file_path', 'line_number')} for m in members],
+                    "cycle_length": len(members),
+                })
+
+            return {
+                "direct_recursion": direct_recursion,
+                "mutual_recursion": mutual_recursion,
+                "note": "Depth bounds are estimated from the arguments of recursive calls and are only reported when recognisable"
+            }
+
     def find_all_callers(self, function_name: str, file_path: str = None) -> List[Dict]:
         """Find all direct and indirect callers of a specific function."""
         with self.driver.session() as session:
@@ -492,6 +785,485 @@ class CodeFinder:
                 result = session.run(query, function_name=function_name)
             return [dict(record) for record in result]
 
+    def find_signature_impact(self, function_name: str, file_path: str = None, owner: str = None) -> Dict[str, Any]:
+        """
+        Everything that has to change along with a function's signature, grouped by file: direct
+        callers (including trait-dispatched call sites), the other methods bound to the same trait
+        method (the trait's default and every impl's version), `pub use` re-exports, and the tests
+        that reach the function within three calls.
+        """
+        with self.driver.session() as session:
+            targets = [dict(record) for record in session.run("""
+                MATCH (f:Function {name: $function_name})
+                WHERE ($file_path IS NULL OR f.file_path = $file_path) AND ($owner IS NULL OR f.class_context = $owner)
+                OPTIONAL MATCH (t:Class {kind: 'trait'})-[:CONTAINS]->(f)
+                RETURN elementId(f) as id, f.name as name, f.file_path as file_path, f.line_number as line_number,
+                       f.class_context as owner, coalesce(f.impl_trait, t.name) as trait
+            """, function_name=function_name, file_path=file_path, owner=owner)]
+            target_ids = [t['id'] for t in targets]
+            traits = sorted({t['trait'] for t in targets if t['trait']})
+
+            callers = [dict(record) for record in session.run("""
+                MATCH (caller)-[r:CALLS|MAY_CALL]->(f)
+                WHERE elementId(f) IN $ids AND (r:CALLS OR r.dispatch IS NOT NULL)
+                RETURN DISTINCT caller.name as name, caller.file_path as file_path,
+                       coalesce(r.line_number, r.call_site_line) as line_number, labels(caller)[0] as kind,
+                       CASE WHEN r.dispatch IS NULL THEN 'direct' ELSE r.dispatch + ' dispatch' END as call_kind
+            """, ids=target_ids)]
+
+            implementations = [dict(record) for record in session.run("""
+                MATCH (m:Function {name: $function_name})
+                WHERE NOT elementId(m) IN $ids
+                  AND (EXISTS { MATCH (i:Impl)-[:CONTAINS]->(m) WHERE i.trait IN $traits }
+                       OR EXISTS { MATCH (t:Class {kind: 'trait'})-[:CONTAINS]->(m) WHERE t.name IN $traits })
+                RETURN m.name as name, m.class_context as owner, m.file_path as file_path, m.line_number as line_number,
+                       coalesce(m.impl_trait, m.class_context) as trait
+                UNION
+                MATCH (t:Class {kind: 'trait'})
+                WHERE t.name IN $traits
+                RETURN t.name + '::' + $function_name as name, t.name as owner, t.file_path as file_path,
+                       t.line_number as line_number, t.name as trait
+            """, function_name=function_name, ids=target_ids, traits=traits)]
+
+            target_files = {t['file_path'] for t in targets}
+            reexports = []
+            for record in session.run("""
+                MATCH (file:File)-[r:IMPORTS]->(m:Module)
+                WHERE r.is_reexport = true
+                RETURN file.path as file_path, r.line_number as line_number, r.full_import_name as import_path
+            """):
+                segments = record['import_path'].split('::')
+                names = {segment for target in targets for segment in (target['name'], target['owner']) if segment}
+                if segments[-1] in names or (segments[-1] == '*' and any(
+                        len(segments) > 1 and Path(path).stem == segments[-2] for path in target_files)):
+                    reexports.append(dict(record))
+
+            tests = [dict(record) for record in session.run("""
+                MATCH path = (test:Function)-[:CALLS*1..3]->(f)
+                WHERE elementId(f) IN $ids AND ANY(d IN coalesce(test.decorators, []) WHERE d =~ $test_attribute)
+                WITH test, min(length(path)) as distance
+                RETURN test.name as name, test.file_path as file_path, test.line_number as line_number, distance
+            """, ids=target_ids, test_attribute=r'#\[\s*([\w:]*::)?test\b.*')]
+
+        files = {}
+        for group, items in (("callers", callers), ("implementations", implementations),
+                             ("reexports", reexports), ("tests", tests)):
+            for item in items:
+                entry = files.setdefault(item['file_path'], {"file_path": item['file_path'], "callers": [],
+                                                             "implementations": [], "reexports": [], "tests": []})
+                entry[group].append({key: value for key, value in item.items() if key != 'file_path'})
+        for entry in files.values():
+            for group in ("callers", "implementations", "reexports", "tests"):
+                entry[group].sort(key=lambda item: item['line_number'] or 0)
+
+        return {
+            "targets": [{key: value for key, value in t.items() if key != 'id'} for t in targets],
+            "files": sorted(files.values(), key=lambda entry: entry['file_path']),
+            "summary": {"callers": len(callers), "implementations": len(implementations),
+                        "reexports": len(reexports), "tests": len(tests), "files": len(files)},
+        }
+
+    def find_transitive_callees(self, function_name: str, file_path: str = None, expand_dispatch: bool = True,
+                                max_depth: int = 5) -> List[Dict]:
+        """
+        Follow calls transitively from a function. With `expand_dispatch`, MAY_CALL edges are followed
+        too: trait-dispatched calls fan out to every implementation and closures passed to callable
+        parameters are included. Direct CALLS edges have confidence 1.0; a dispatched call site with
+        n candidate implementations gives each 1/n, and higher-order MAY_CALL edges 0.5. A callee's
+        confidence is that of its most certain call path.
+        """
+        relationships = "CALLS|MAY_CALL" if expand_dispatch else "CALLS"
+        with self.driver.session() as session:
+            roots = session.run("""
+                MATCH (f:Function {name: $function_name})
+                WHERE $file_path IS NULL OR f.file_path = $file_path
+                RETURN elementId(f) as id
+            """, function_name=function_name, file_path=file_path)
+            best = {record['id']: {"confidence": 1.0, "depth": 0, "path": [function_name], "edges": []} for record in roots}
+            callees = {}
+
+            frontier = set(best)
+            for depth in range(1, max_depth + 1):
+                if not frontier:
+                    break
+                result = session.run(f"""
+                    MATCH (a)-[r:{relationships}]->(b)
+                    WHERE elementId(a) IN $ids AND (b:Function OR b:Closure)
+                    RETURN elementId(a) as source, elementId(b) as target, type(r) as relationship,
+                           r.dispatch as dispatch, r.call_site_line as call_site_line, r.line_number as line_number,
+                           b.name as name, b.file_path as file_path, b.line_number as target_line,
+                           b.is_dependency as is_dependency
+                """, ids=list(frontier))
+                edges = [dict(record) for record in result]
+
+                candidates_per_site, dispatched = {}, set()
+                for edge in edges:
+                    if edge['dispatch']:
+                        site = (edge['source'], edge['call_site_line'])
+                        candidates_per_site[site] = candidates_per_site.get(site, 0) + 1
+                        dispatched.add((edge['source'], edge['call_site_line'], edge['name']))
+
+                next_frontier = set()
+                for edge in edges:
+                    if edge['relationship'] == 'CALLS' and (edge['source'], edge['line_number'], edge['name']) in dispatched:
+                        # Name-based resolution of a dispatched call picks one implementation; the MAY_CALL edges cover all.
+                        continue
+                    if edge['relationship'] == 'CALLS':
+                        edge_confidence, kind = 1.0, 'call'
+                    elif edge['dispatch']:
+                        edge_confidence = 1.0 / candidates_per_site[(edge['source'], edge['call_site_line'])]
+                        kind = f"{edge['dispatch']} dispatch"
+                    else:
+                        edge_confidence, kind = 0.5, 'higher-order'
+                    source = best[edge['source']]
+                    confidence = source['confidence'] * edge_confidence
+                    current = best.get(edge['target'])
+                    if current is not None and current['confidence'] >= confidence:
+                        continue
+                    best[edge['target']] = {
+                        "confidence": confidence,
+                        "depth": depth,
+                        "path": source['path'] + [edge['name']],
+                        "edges": source['edges'] + [kind],
+                    }
+                    callees[edge['target']] = edge
+                    next_frontier.add(edge['target'])
+                frontier = next_frontier
+
+        results = []
+        for node_id, edge in callees.items():
+            info = best[node_id]
+            results.append({
+                "callee_name": edge['name'],
+                "callee_file_path": edge['file_path'],
+                "callee_line_number": edge['target_line'],
+                "callee_is_dependency": edge['is_dependency'],
+                "depth": info['depth'],
+                "confidence": round(info['confidence'], 3),
+                "certain": info['confidence'] == 1.0,
+                "call_path": info['path'],
+                "edge_kinds": info['edges'],
+            })
+        return sorted(results, key=lambda r: (-r['confidence'], r['depth'], r['callee_file_path'] or '', r['callee_line_number'] or 0))
+
+    def find_allocation_sites(self, function_name: str, file_path: str = None, max_depth: int = 5,
+                              expand_dispatch: bool = True) -> Dict[str, Any]:
+        """
+        List the heap allocation calls (see `allocations`) in a function and in everything it calls
+        transitively, up to `max_depth` hops, each with the call path from the function, plus the
+        number of sites per kind of allocation.
+        """
+        callees = self.find_transitive_callees(function_name, file_path, expand_dispatch, max_depth)
+        with self.driver.session() as session:
+            roots = [dict(record) for record in session.run("""
+                MATCH (f:Function {name: $function_name})
+                WHERE $file_path IS NULL OR f.file_path = $file_path
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       f.call_names as call_names, f.call_lines as call_lines
+            """, function_name=function_name, file_path=file_path)]
+            if not roots:
+                raise ValueError(f"No function named '{function_name}' in the graph")
+            reached = [dict(record) for record in session.run("""
+                UNWIND $callees as callee
+                MATCH (f:Function {name: callee.callee_name, file_path: callee.callee_file_path, line_number: callee.callee_line_number})
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       f.call_names as call_names, f.call_lines as call_lines, callee.depth as depth, callee.call_path as call_path
+            """, callees=callees)]
+
+        sites = []
+        for func in [{**root, "depth": 0, "call_path": [function_name]} for root in roots] + reached:
+            for site in allocation_calls(func['call_names'], func['call_lines']):
+                sites.append({**site, "function": func['name'], "owner": func['owner'], "file_path": func['file_path'],
+                              "depth": func['depth'], "call_path": func['call_path']})
+        sites.sort(key=lambda site: (site['depth'], site['file_path'], site['line_number']))
+        summary = {}
+        for site in sites:
+            summary[site['allocation']] = summary.get(site['allocation'], 0) + 1
+        return {"function": function_name, "functions_reached": len(roots) + len(reached), "summary": summary, "sites": sites}
+
+    def find_untested_code(self, entry_point: str = "main", file_path: str = None, max_depth: int = 10) -> Dict[str, Any]:
+        """
+        List the functions reachable from an entry point (itself included) that no test executes,
+        from imported coverage (see `GraphBuilder.import_coverage`): those with no covered line and
+        no incoming COVERS edge. Functions of files the reports do not cover are listed apart, as
+        their coverage is unknown.
+        """
+        callees = self.find_transitive_callees(entry_point, file_path, expand_dispatch=True, max_depth=max_depth)
+        with self.driver.session() as session:
+            roots = [dict(record) for record in session.run("""
+                MATCH (f:Function {name: $entry_point})
+                WHERE $file_path IS NULL OR f.file_path = $file_path
+                RETURN f.name as callee_name, f.file_path as callee_file_path, f.line_number as callee_line_number
+            """, entry_point=entry_point, file_path=file_path)]
+            if not roots:
+                raise ValueError(f"No function named '{entry_point}' in the graph")
+            reached = [{**root, "depth": 0, "call_path": [entry_point]} for root in roots] \
+                + [callee for callee in callees if not callee['callee_is_dependency']]
+            result = session.run("""
+                UNWIND $reached as callee
+                MATCH (f:Function {name: callee.callee_name, file_path: callee.callee_file_path, line_number: callee.callee_line_number})
+                OPTIONAL MATCH (file:File {path: f.file_path})
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       f.covered_by_tests as covered_by_tests, f.line_coverage as line_coverage,
+                       f.uncovered_lines as uncovered_lines, EXISTS { MATCH (:Function)-[:COVERS]->(f) } as has_test,
+                       file.line_coverage IS NOT NULL as file_reported,
+                       callee.depth as depth, callee.call_path as call_path
+            """, reached=reached)
+
+            untested, unknown, tested = [], [], 0
+            for record in result:
+                func = dict(record)
+                if func.pop('has_test') or func['covered_by_tests']:
+                    tested += 1
+                    continue
+                reported = func.pop('file_reported') or func['covered_by_tests'] is not None
+                func.pop('covered_by_tests')
+                (untested if reported else unknown).append(func)
+
+        key = lambda func: (func['depth'], func['file_path'], func['line_number'])
+        return {
+            "entry_point": entry_point,
+            "functions_reached": tested + len(untested) + len(unknown),
+            "tested": tested,
+            "untested": sorted(untested, key=key),
+            "no_coverage_data": sorted(unknown, key=key),
+        }
+
+    def find_benchmarks(self, function_name: str = None, verdict: str = None, limit: int = 50) -> List[Dict]:
+        """
+        List the Criterion benchmarks imported with `GraphBuilder.import_benchmarks`, with their
+        latest timings, change against the previous run and the functions they measure; the largest
+        slowdowns come first. Filter by measured function or by verdict (regressed, improved or
+        unchanged).
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (b:Benchmark)
+                WHERE $verdict IS NULL OR b.verdict = $verdict
+                OPTIONAL MATCH (b)-[:BENCHMARKS]->(fn:Function)
+                WITH b, collect(fn {.name, owner: fn.class_context, .file_path, .line_number}) as functions
+                WHERE $function_name IS NULL OR any(func IN functions WHERE func.name = $function_name)
+                RETURN b {.*} as benchmark, functions
+                ORDER BY coalesce(b.change, 0.0) DESC, b.mean_ns DESC
+                LIMIT $limit
+            """, function_name=function_name, verdict=verdict, limit=limit)
+            return [{**record['benchmark'], "functions": record['functions']} for record in result]
+
+    def find_hot_functions(self, limit: int = 20, min_share: float = 0.0) -> List[Dict]:
+        """
+        List the functions of the imported profile (see `GraphBuilder.import_profile`) by their
+        share of all samples, with their own samples and their hottest outgoing calls, to decide
+        where optimization work pays off.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (fn:Function) WHERE fn.total_samples IS NOT NULL AND fn.sample_share >= $min_share
+                OPTIONAL MATCH (fn)-[r:CALLS|MAY_CALL]->(callee) WHERE r.samples IS NOT NULL
+                WITH fn, r, callee ORDER BY r.samples DESC
+                WITH fn, [call IN collect(CASE WHEN r IS NULL THEN NULL ELSE {name: callee.name, file_path: callee.file_path,
+                                                                                    samples: r.samples} END) WHERE call IS NOT NULL] as calls
+                RETURN fn.name as name, fn.class_context as owner, fn.file_path as file_path, fn.line_number as line_number,
+                       fn.total_samples as total_samples, fn.self_samples as self_samples, fn.sample_share as sample_share,
+                       calls[..5] as hottest_calls
+                ORDER BY total_samples DESC, self_samples DESC
+                LIMIT $limit
+            """, limit=limit, min_share=min_share)
+            return [dict(record) for record in result]
+
+    def _history_revisions(self, session, repo_path: str = None) -> Dict[str, Dict[int, Dict]]:
+        """The revisions recorded by `GraphBuilder.record_history`, per repository and ordinal."""
+        repo = str(Path(repo_path).resolve()) if repo_path else None
+        revisions = {}
+        for record in session.run("""
+            MATCH (rv:Revision) WHERE $repo IS NULL OR rv.repo = $repo
+            RETURN rv.repo as repo, rv.ordinal as ordinal, rv.label as label, rv.commit as commit,
+                   rv.committed_at as committed_at, rv.path as path
+        """, repo=repo):
+            revisions.setdefault(record['repo'], {})[record['ordinal']] = {
+                key: record[key] for key in ('ordinal', 'label', 'commit', 'committed_at', 'path')}
+        if not revisions:
+            raise ValueError(f"No history recorded{f' for {repo}' if repo else ''}; run record_history first")
+        return revisions
+
+    def function_history(self, name: str, repo_path: str = None) -> List[Dict]:
+        """
+        The recorded history of the functions or types named `name` (or `Owner::name`): the
+        revision ranges they existed in and the revisions that changed their code.
+        """
+        owner, name = (name.rsplit('::', 1) if '::' in name else (None, name))
+        with self.driver.session() as session:
+            revisions = self._history_revisions(session, repo_path)
+            result = session.run("""
+                MATCH (vi:VersionedItem {name: $name})
+                WHERE vi.repo IN $repos AND ($owner IS NULL OR vi.owner = $owner)
+                RETURN vi {.*} as item
+                ORDER BY vi.repo, vi.file, vi.line_number
+            """, name=name, owner=owner, repos=list(revisions))
+            history = []
+            for record in result:
+                item, repo_revisions = record['item'], revisions[record['item']['repo']]
+                history.append({
+                    **{key: item.get(key) for key in ('repo', 'kind', 'name', 'owner', 'impl_trait', 'file', 'line_number')},
+                    "ranges": describe_ranges(item['valid_from'], item['valid_to'], repo_revisions),
+                    "changed_in": [repo_revisions[ordinal] for ordinal in item['changed_in']],
+                })
+            return history
+
+    def call_history(self, caller: str, callee: str = None, repo_path: str = None) -> List[Dict]:
+        """
+        When a function (`name` or `Owner::name`) started and stopped calling another one, or each of
+        its callees over the recorded history: the revision ranges of every call edge.
+        """
+        caller_owner, caller = (caller.rsplit('::', 1) if '::' in caller else (None, caller))
+        callee_owner, callee = (callee.rsplit('::', 1) if callee and '::' in callee else (None, callee))
+        with self.driver.session() as session:
+            revisions = self._history_revisions(session, repo_path)
+            result = session.run("""
+                MATCH (a:VersionedItem {name: $caller})-[r:CALLED_DURING]->(b:VersionedItem)
+                WHERE a.repo IN $repos AND ($caller_owner IS NULL OR a.owner = $caller_owner)
+                  AND ($callee IS NULL OR b.name = $callee) AND ($callee_owner IS NULL OR b.owner = $callee_owner)
+                RETURN a.repo as repo, a.name as caller, a.owner as caller_owner, a.file as caller_file,
+                       b.name as callee, b.owner as callee_owner, b.file as callee_file,
+                       r.valid_from as valid_from, r.valid_to as valid_to
+                ORDER BY r.valid_from[0], callee
+            """, caller=caller, caller_owner=caller_owner, callee=callee, callee_owner=callee_owner, repos=list(revisions))
+            history = []
+            for record in result:
+                edge = dict(record)
+                ranges = describe_ranges(edge.pop('valid_from'), edge.pop('valid_to'), revisions[edge['repo']])
+                history.append({**edge, "ranges": ranges, "started_in": ranges[0]["from"],
+                                "calls_in_latest": ranges[-1]["until"] is None})
+            return history
+
+    def module_as_of(self, repo_path: str, path: str, revision: str) -> Dict[str, Any]:
+        """
+        The functions and types of a file or directory (relative to the repository root) as of a
+        recorded revision, named by its label or a commit prefix, with their calls then. The
+        revision's indexed snapshot (its `path`) can be queried further with Cypher, under the
+        `Snapshot`-prefixed labels (see `GraphBuilder.index_revision`).
+        """
+        with self.driver.session() as session:
+            repo_revisions = next(iter(self._history_revisions(session, repo_path).values()))
+            matches = [rev for rev in repo_revisions.values() if rev['label'] == revision or rev['commit'].startswith(revision)]
+            if not matches:
+                raise ValueError(f"Revision '{revision}' is not part of the recorded history; recorded: "
+                                 f"{', '.join(rev['label'] for rev in repo_revisions.values())}")
+            ordinal = matches[-1]['ordinal']
+            prefix = path.strip('/')
+            items = [record['item'] for record in session.run("""
+                MATCH (vi:VersionedItem {repo: $repo})
+                WHERE vi.file = $prefix OR vi.file STARTS WITH $prefix + '/'
+                RETURN vi {.*} as item
+                ORDER BY vi.file, vi.line_number
+            """, repo=str(Path(repo_path).resolve()), prefix=prefix)]
+            items = [item for item in items if valid_at(item['valid_from'], item['valid_to'], ordinal)]
+            keys = [item['key'] for item in items]
+            calls = [dict(record) for record in session.run("""
+                MATCH (a:VersionedItem {repo: $repo})-[r:CALLED_DURING]->(b:VersionedItem)
+                WHERE a.key IN $keys
+                RETURN a.name as caller, a.owner as caller_owner, b.name as callee, b.owner as callee_owner,
+                       b.file as callee_file, r.valid_from as valid_from, r.valid_to as valid_to
+            """, repo=str(Path(repo_path).resolve()), keys=keys)]
+
+        calls = [{key: call[key] for key in ('caller', 'caller_owner', 'callee', 'callee_owner', 'callee_file')}
+                 for call in calls if valid_at(call['valid_from'], call['valid_to'], ordinal)]
+        return {
+            "revision": matches[-1],
+            "path": prefix,
+            "items": [{key: item.get(key) for key in ('kind', 'name', 'owner', 'impl_trait', 'file', 'line_number')}
+                      for item in items],
+            "calls": calls,
+        }
+
+    def get_snippet(self, name: str = None, file_path: str = None, uid: str = None, context_lines: int = 0) -> List[Dict]:
+        """
+        The exact source of the symbols named `name` (or `Owner::name`), or of the node with `uid`,
+        read from disk at their recorded byte span, with an editor `location` (`path:line:column`,
+        1-based) of their name. `stale` tells that the symbol's code on disk differs from what was
+        indexed; `context_lines` adds whole lines around the snippet.
+        """
+        if not name and not uid:
+            raise ValueError("Give a symbol name or a uid")
+        owner, name = (name.rsplit('::', 1) if name and '::' in name else (None, name))
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (n)
+                WHERE n.start_byte IS NOT NULL AND n.file_path IS NOT NULL
+                  AND CASE WHEN $uid IS NOT NULL THEN n.uid = $uid ELSE n.name = $name END
+                  AND ($owner IS NULL OR coalesce(n.class_context, n.owner, n.enum) = $owner)
+                  AND ($file_path IS NULL OR n.file_path ENDS WITH $file_path)
+                RETURN labels(n)[0] as label, n.name as name, coalesce(n.class_context, n.owner, n.enum) as owner,
+                       n.uid as uid, n.file_path as file_path, n.line_number as line_number, n.column as column,
+                       n.start_line as start_line, n.start_column as start_column, n.end_line as end_line,
+                       n.end_column as end_column, n.start_byte as start_byte, n.end_byte as end_byte,
+                       CASE WHEN n.generated_by IS NULL THEN coalesce(n.source, n.source_compressed) END as source
+                ORDER BY n.file_path, n.start_byte
+                LIMIT 20
+            """, name=name, owner=owner, uid=uid, file_path=file_path)
+            symbols = [dict(record) for record in result]
+
+        snippets = []
+        for symbol in symbols:
+            indexed_source = source_text(symbol.pop('source'))
+            try:
+                data = Path(symbol['file_path']).read_bytes()
+            except OSError as e:
+                snippets.append({**symbol, "error": f"Cannot read {symbol['file_path']}: {e}"})
+                continue
+            snippet = data[symbol['start_byte']:symbol['end_byte']].decode('utf-8', errors='replace')
+            if context_lines:
+                lines = data.splitlines(keepends=True)
+                first, last = max(symbol['start_line'] - 1 - context_lines, 0), symbol['end_line'] + context_lines
+                text = b''.join(lines[first:last]).decode('utf-8', errors='replace')
+            else:
+                text = snippet
+            snippets.append({
+                **symbol,
+                "location": f"{symbol['file_path']}:{symbol['line_number']}:{(symbol['column'] or 0) + 1}",
+                "snippet": text,
+                "stale": indexed_source is not None and indexed_source != snippet,
+            })
+        return snippets
+
+    def find_unresolved_references(self, name: str = None, kind: str = None, file_path: str = None,
+                                   limit: int = 50) -> List[Dict]:
+        """
+        The placeholders of calls no indexed function matched (`GraphBuilder._resolve_placeholders`),
+        most referenced first: their `kind` (`call` or `method_call`), receiver type (`owner`) and
+        name, how many call sites reference them and a few of those sites.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (caller)-[r:REFERENCES_UNRESOLVED]->(u:UnresolvedReference)
+                WHERE caller.revision IS NULL AND ($name IS NULL OR u.name = $name OR u.owner = $name) AND ($kind IS NULL OR u.kind = $kind)
+                  AND ($file_path IS NULL OR coalesce(caller.file_path, caller.path) ENDS WITH $file_path)
+                WITH u, r, caller ORDER BY coalesce(caller.file_path, caller.path), r.line_number
+                WITH u, count(r) as references,
+                     collect({caller: caller.name, file_path: coalesce(caller.file_path, caller.path),
+                              line_number: r.line_number, call: r.full_call_name})[..5] as examples
+                RETURN u.kind as kind, u.owner as owner, u.name as name, references, examples
+                ORDER BY references DESC, name
+                LIMIT $limit
+            """, name=name, kind=kind, file_path=file_path, limit=limit)
+            return [{**dict(record), "owner": record['owner'] or None} for record in result]
+
+    def list_shards(self, repo_path: str = None) -> List[Dict]:
+        """
+        The shards of sharded builds (`GraphBuilder.build_sharded_async`), per repository: their
+        package name, root path, status, file count, nodes written, duration, errors and when they
+        were last indexed (epoch seconds).
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (r:Repository)-[:HAS_SHARD]->(s:Shard)
+                WHERE $repo_path IS NULL OR r.path = $repo_path
+                RETURN r.path as repository, s.name as name, s.path as path, s.status as status,
+                       s.file_count as file_count, s.nodes_written as nodes_written,
+                       s.duration_seconds as duration_seconds, s.errors as errors, s.indexed_at as indexed_at
+                ORDER BY repository, path
+            """, repo_path=repo_path)
+            return [dict(record) for record in result]
+
     def find_function_call_chain(self, start_function: str, end_function: str, max_depth: int = 5) -> List[Dict]:
         """Find call chains between two functions"""
         with self.driver.session() as session:
@@ -585,6 +1357,679 @@ class CodeFinder:
                 "instances": [dict(record) for record in variable_instances]
             }
     
+    def find_lock_users(self, lock_name: str, file_path: str = None) -> List[Dict]:
+        """Find the functions that lock or atomically access a shared field or static (e.g. 'SafeCounter.count')."""
+        with self.driver.session() as session:
+            query = """
+                MATCH (fn:Function)-[access:LOCKS]->(lock:Lock)
+                WHERE (lock.name = $lock_name OR lock.field = $lock_name)
+                  AND ($file_path IS NULL OR lock.file_path = $file_path)
+                OPTIONAL MATCH (lock)-[:GUARDS]->(owner)
+                RETURN DISTINCT
+                    lock.name as lock_name,
+                    lock.kind as lock_kind,
+                    lock.guarded_type as guarded_type,
+                    lock.file_path as lock_file_path,
+                    owner.name as guarded_owner,
+                    fn.name as function_name,
+                    fn.file_path as function_file_path,
+                    fn.line_number as function_line_number,
+                    access.line_number as access_line_number,
+                    access.mode as access_mode
+                ORDER BY lock.file_path, lock.name, fn.line_number
+                LIMIT 50
+            """
+            result = session.run(query, lock_name=lock_name, file_path=file_path)
+            return [dict(record) for record in result]
+
+    def find_variant_usages(self, variant_name: str, file_path: str = None) -> List[Dict]:
+        """Find where an enum variant (e.g. 'Message::Quit' or just 'Quit') is constructed or matched on."""
+        with self.driver.session() as session:
+            query = """
+                MATCH (fn:Function)-[usage:CONSTRUCTS|MATCHES]->(v:Variant)
+                WHERE (v.qualified_name = $variant_name OR v.name = $variant_name)
+                  AND ($file_path IS NULL OR v.file_path = $file_path)
+                RETURN DISTINCT
+                    v.qualified_name as variant,
+                    v.file_path as variant_file_path,
+                    type(usage) as usage,
+                    fn.name as function_name,
+                    fn.file_path as function_file_path,
+                    fn.line_number as function_line_number,
+                    usage.line_number as usage_line_number
+                ORDER BY v.qualified_name, fn.file_path, usage.line_number
+                LIMIT 50
+            """
+            result = session.run(query, variant_name=variant_name, file_path=file_path)
+            return [dict(record) for record in result]
+
+    def find_field_accesses(self, field_name: str, file_path: str = None) -> List[Dict]:
+        """Find the functions that read or write a struct field (e.g. 'BankAccount.balance' or just 'balance')."""
+        owner, _, name = field_name.rpartition('.')
+        with self.driver.session() as session:
+            query = """
+                MATCH (fn:Function)-[access:READS_FIELD|WRITES_FIELD]->(fd:Field {name: $name})
+                WHERE ($owner = '' OR fd.owner = $owner)
+                  AND ($file_path IS NULL OR fd.file_path = $file_path)
+                RETURN DISTINCT
+                    fd.owner + '.' + fd.name as field,
+                    fd.type as field_type,
+                    fd.file_path as field_file_path,
+                    CASE type(access) WHEN 'WRITES_FIELD' THEN 'write' ELSE 'read' END as access_mode,
+                    fn.name as function_name,
+                    fn.file_path as function_file_path,
+                    fn.line_number as function_line_number,
+                    access.line_number as access_line_number
+                ORDER BY field, fn.file_path, access.line_number
+                LIMIT 50
+            """
+            result = session.run(query, name=name, owner=owner, file_path=file_path)
+            return [dict(record) for record in result]
+
+    def find_closure_captures(self, variable_name: str, file_path: str = None) -> List[Dict]:
+        """
+        Find the closures capturing a local (e.g. 'counter') or a field (e.g. 'SafeCounter.count'),
+        with the captured place and how: by reference, by mutable reference or by move. Closures
+        handed to a thread spawner carry `spawned_by`, showing where shared ownership is needed.
+        """
+        owner, _, name = variable_name.rpartition('.')
+        with self.driver.session() as session:
+            query = """
+                MATCH (cl:Closure)
+                WHERE ($file_path IS NULL OR cl.file_path ENDS WITH $file_path) AND size(coalesce(cl.captured_places, [])) > 0
+                UNWIND range(0, size(cl.captured_places) - 1) as i
+                WITH cl, cl.captured_places[i] as place, cl.capture_modes[i] as mode, cl.capture_reasons[i] as reason
+                WHERE ($owner = '' AND (place = $name OR place STARTS WITH $name + '.'))
+                   OR ((place = 'self.' + $name OR place STARTS WITH 'self.' + $name + '.') AND ($owner = '' OR cl.owner = $owner))
+                RETURN cl.name as closure, cl.context as function_name, cl.owner as owner, cl.file_path as file_path,
+                       cl.line_number as line_number, place, mode, reason, cl.is_move as is_move, cl.spawned_by as spawned_by
+                ORDER BY cl.file_path, cl.line_number, place
+            """
+            result = session.run(query, name=name, owner=owner, file_path=file_path)
+            return [dict(record) for record in result]
+
+    def find_type_usages(self, type_name: str, file_path: str = None) -> List[Dict]:
+        """
+        Find everywhere a struct, enum or trait is used: constructions (struct literals, variants and
+        associated constructors), variant matches, field reads and writes, trait impls, parameters,
+        return types, fields, annotations and generic instantiations such as `Vec<Person>`.
+        """
+        constructor_pattern = rf"(?s).*\b(Self|{re.escape(type_name)})\b.*"
+        queries = [
+            """
+                MATCH (src)-[r:REFERENCES_TYPE]->(t)
+                RETURN CASE WHEN r.generic_in IS NOT NULL THEN 'generic_instantiation' ELSE r.role END as usage,
+                       r.type_text as detail, src.name as user, coalesce(src.file_path, src.path) as file_path,
+                       r.line_number as line_number,
+                       t.file_path as type_file_path
+            """,
+            """
+                MATCH (t)-[:HAS_IMPL]->(:Impl)-[:CONTAINS]->(ctor:Function)<-[c:CALLS]-(caller:Function)
+                WHERE ctor.receiver = 'associated' AND ctor.return_type =~ $constructor_pattern
+                RETURN 'construction' as usage, t.name + '::' + ctor.name as detail, caller.name as user,
+                       caller.file_path as file_path, c.line_number as line_number,
+                       t.file_path as type_file_path
+            """,
+            """
+                MATCH (fn:Function)-[u:CONSTRUCTS|MATCHES]->(v:Variant)-[:VARIANT_OF]->(t)
+                RETURN CASE type(u) WHEN 'MATCHES' THEN 'match' ELSE 'construction' END as usage,
+                       v.qualified_name as detail, fn.name as user, fn.file_path as file_path, u.line_number as line_number,
+                       t.file_path as type_file_path
+            """,
+            """
+                MATCH (t)-[:HAS_FIELD]->(fd:Field)<-[a:READS_FIELD|WRITES_FIELD]-(fn:Function)
+                RETURN CASE type(a) WHEN 'WRITES_FIELD' THEN 'field_write' ELSE 'field_read' END as usage,
+                       fd.owner + '.' + fd.name as detail, fn.name as user, fn.file_path as file_path, a.line_number as line_number,
+                       t.file_path as type_file_path
+            """,
+            """
+                MATCH (t)-[:HAS_IMPL]->(i:Impl)
+                WHERE i.trait IS NOT NULL
+                RETURN 'trait_impl' as usage, i.name as detail, i.trait as user, i.file_path as file_path, i.line_number as line_number,
+                       t.file_path as type_file_path
+            """,
+        ]
+        usages = []
+        with self.driver.session() as session:
+            for query in queries:
+                result = session.run("""
+                    MATCH (t:Class {name: $type_name})
+                    WHERE $file_path IS NULL OR t.file_path = $file_path
+                    WITH t
+                """ + query,
+                    type_name=type_name, file_path=file_path, constructor_pattern=constructor_pattern)
+                usages.extend(dict(record) for record in result)
+
+        unique = {tuple(usage.values()): usage for usage in usages}
+        return sorted(unique.values(), key=lambda u: (u['file_path'] or '', u['line_number'] or 0, u['usage']))
+
+    def find_by_trait_bound(self, bounds: str, file_path: str = None) -> List[Dict]:
+        """
+        Find generic functions, types and impl blocks requiring the given bounds, e.g. 'T: Display + Debug'
+        or just 'Display + Debug', regardless of whether they are written inline or in a where clause.
+        """
+        match = re.match(r'^\s*([A-Za-z_]\w*)\s*:(?!:)(.*)$', bounds)
+        param, bound_text = (match.group(1), match.group(2)) if match else ('', bounds)
+        required = [b.strip() for b in bound_text.split('+') if b.strip()]
+        with self.driver.session() as session:
+            query = """
+                MATCH (n)
+                WHERE (n:Function OR n:Class OR n:Impl) AND n.constraints IS NOT NULL
+                  AND ($file_path IS NULL OR n.file_path = $file_path)
+                  AND ALL(b IN $required WHERE
+                        ($param = '' AND b IN n.bound_traits)
+                        OR ANY(c IN n.constraints WHERE c = $param + ': ' + b OR c STARTS WITH $param + ': ' + b + '<'))
+                RETURN
+                    labels(n)[0] as kind,
+                    n.name as name,
+                    n.file_path as file_path,
+                    n.line_number as line_number,
+                    n.constraints as constraints
+                ORDER BY n.file_path, n.line_number
+                LIMIT 50
+            """
+            result = session.run(query, param=param, required=required, file_path=file_path)
+            return [dict(record) for record in result]
+
+    def find_implementations(self, trait_path: str) -> List[Dict]:
+        """
+        Find all implementations of a trait given by name or path (e.g. 'traits::Area'): handwritten
+        and blanket impl blocks with their spans and generic constraints, plus `#[derive]`d impls.
+        The leading path segments, if any, must appear in the trait's file path (derive macros are matched by name).
+        """
+        parts = [p for p in trait_path.split('::') if p and p not in ('crate', 'self', 'super')]
+        trait_name = parts[-1]
+        module_path = '/'.join(parts[:-1])
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (i:Impl {trait: $trait_name})
+                OPTIONAL MATCH (i)-[:IMPLEMENTS]->(t:Class)
+                OPTIONAL MATCH (c:Class)-[:HAS_IMPL]->(i)
+                WITH i, t, c
+                WHERE $module_path = '' OR (t IS NOT NULL AND t.file_path CONTAINS $module_path)
+                      OR (t IS NULL AND i.trait_type CONTAINS $module_path)
+                RETURN coalesce(c.name, i.type) as type_name, c.file_path as type_file_path,
+                       i.name as impl, i.file_path as file_path, i.line_number as line_number, i.end_line as end_line,
+                       coalesce(i.constraints, []) as constraints, i.where_clause as where_clause,
+                       CASE WHEN i.blanket_param IS NOT NULL THEN 'blanket' ELSE 'handwritten' END as impl_kind,
+                       coalesce(i.is_unsafe, false) as is_unsafe, coalesce(i.is_negative, false) as is_negative,
+                       t.file_path as trait_file_path
+                UNION
+                MATCH (c:Class)
+                WHERE $trait_name IN coalesce(c.derives, [])
+                RETURN c.name as type_name, c.file_path as type_file_path,
+                       '#[derive(' + $trait_name + ')]' as impl, c.file_path as file_path, c.line_number as line_number,
+                       c.line_number as end_line, coalesce(c.constraints, []) as constraints, NULL as where_clause,
+                       'derived' as impl_kind, false as is_unsafe, false as is_negative, NULL as trait_file_path
+            """, trait_name=trait_name, module_path=module_path)
+            return sorted((dict(record) for record in result), key=lambda r: (r['file_path'], r['line_number']))
+
+    def find_method_shadowing(self, type_name: str = None) -> List[Dict]:
+        """
+        Find Rust types whose inherent method shares its name with a method of a trait they
+        implement (their own `add` plus `impl Add`), with the call sites where both could apply.
+        Method calls and `Type::method` calls get the inherent one (`resolves_to: inherent`);
+        only calls naming the trait get the trait's.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (c:Class)-[:HAS_IMPL]->(:Impl)-[:CONTAINS]->(inherent:Function)
+                WHERE inherent.shadows_traits IS NOT NULL AND ($type_name IS NULL OR c.name = $type_name)
+                OPTIONAL MATCH (c)-[:HAS_IMPL]->(ti:Impl)-[:CONTAINS]->(tm:Function {name: inherent.name})
+                WHERE ti.trait IN inherent.shadows_traits
+                WITH c, inherent, collect(tm) as trait_methods
+                UNWIND [inherent] + trait_methods as target
+                OPTIONAL MATCH (caller)-[r:CALLS]->(target)
+                WHERE r.shadowing IS NOT NULL
+                WITH c, inherent, [t IN trait_methods | {file_path: t.file_path, line_number: t.line_number}] as trait_methods,
+                     collect(CASE WHEN r IS NOT NULL THEN {caller: caller.name, file_path: coalesce(caller.file_path, caller.path),
+                             line_number: r.line_number, call: r.full_call_name, resolves_to: r.shadowing,
+                             ambiguous_with: r.ambiguous_with} END) as call_sites
+                RETURN c.name as type_name, inherent.name as method, inherent.file_path as file_path,
+                       inherent.line_number as line_number, inherent.shadows_traits as traits, trait_methods, call_sites
+                ORDER BY file_path, line_number
+            """, type_name=type_name)
+            shadowing = [dict(record) for record in result]
+        for entry in shadowing:
+            entry['call_sites'].sort(key=lambda site: (site['file_path'], site['line_number'] or 0))
+        return shadowing
+
+    def type_hierarchy(self, name: str, depth: int = 5) -> List[Dict]:
+        """
+        The type hierarchy of a Rust trait or type given by name or path ('traits::Shape'). Upward
+        (`supertypes`): the supertrait chain of a trait, or the traits a type implements or derives
+        with their own supertraits. Downward (`subtypes`): the implementors of a trait (handwritten,
+        derived and blanket impls) and its subtraits with their implementors. Traits outside the
+        graph (`Clone`, `fmt::Display`) are looked up by name. Each tree is also rendered as `text`.
+        """
+        parts = [p for p in name.split('::') if p and p not in ('crate', 'self', 'super')]
+        type_name, module_path = parts[-1], '/'.join(parts[:-1])
+        depth = max(1, min(int(depth), 10))
+        last_segment = lambda path: re.sub(r'<.*', '', path or '').split('::')[-1]
+        node_fields = """{v}.name as name, {v}.kind as kind, {v}.file_path as file_path, {v}.line_number as line_number,
+                         coalesce({v}.bases, []) as bases, coalesce({v}.derives, []) as derives"""
+
+        with self.driver.session() as session:
+            def find_types(type_name):
+                return [dict(record) for record in session.run(f"""
+                    MATCH (c:Class {{name: $name}})
+                    WHERE coalesce(c.lang, 'rust') = 'rust'
+                    RETURN elementId(c) as id, {node_fields.format(v='c')}
+                """, name=type_name)]
+
+            def node(record, relation, **extra):
+                return {"name": record['name'], "kind": record.get('kind'), "relation": relation,
+                        "file_path": record.get('file_path'), "line_number": record.get('line_number'), **extra, "children": []}
+
+            def supertraits(trait, seen, level):
+                children = []
+                for base in trait.get('bases', []):
+                    matches = find_types(last_segment(base)) or [{"name": base, "kind": "trait"}]
+                    record = next((m for m in matches if m.get('kind') == 'trait'), matches[0])
+                    child = node(record, "supertrait")
+                    if record['name'] in seen:
+                        child["repeated"] = True
+                    elif level < depth:
+                        child["children"] = supertraits(record, seen | {record['name']}, level + 1)
+                    children.append(child)
+                return children
+
+            def implemented_traits(type_record, seen, level):
+                children = []
+                for record in session.run("""
+                    MATCH (c:Class)-[:HAS_IMPL]->(i:Impl)
+                    WHERE elementId(c) = $id AND i.trait IS NOT NULL AND NOT coalesce(i.is_negative, false)
+                    OPTIONAL MATCH (i)-[:IMPLEMENTS]->(t:Class)
+                    RETURN DISTINCT coalesce(t.name, i.trait) as name, t.kind as kind, t.file_path as file_path,
+                           t.line_number as line_number, coalesce(t.bases, []) as bases
+                    ORDER BY name
+                """, id=type_record['id']):
+                    record = dict(record)
+                    child = node({**record, "kind": record['kind'] or 'trait'}, "implements")
+                    if level < depth:
+                        child["children"] = supertraits(record, seen | {record['name']}, level + 1)
+                    children.append(child)
+                for derived in type_record.get('derives', []):
+                    children.append(node({"name": derived, "kind": "trait"}, "derives"))
+                return children
+
+            def implementors(trait, seen, level):
+                children, trait_name = [], trait['name']
+                for record in session.run("""
+                    MATCH (i:Impl)
+                    WHERE (i.trait = $name OR i.trait ENDS WITH '::' + $name)
+                      AND ($id IS NULL OR NOT EXISTS { MATCH (i)-[:IMPLEMENTS]->(other) WHERE elementId(other) <> $id })
+                    OPTIONAL MATCH (c:Class)-[:HAS_IMPL]->(i)
+                    RETURN coalesce(c.name, i.type) as name, c.kind as kind, coalesce(c.file_path, i.file_path) as file_path,
+                           coalesce(c.line_number, i.line_number) as line_number, i.blanket_param IS NOT NULL as blanket,
+                           coalesce(i.constraints, []) as constraints, coalesce(i.is_negative, false) as is_negative
+                    ORDER BY file_path, line_number
+                """, name=trait_name, id=trait.get('id')):
+                    record = dict(record)
+                    if record['is_negative']:
+                        continue
+                    relation = "blanket" if record['blanket'] else "implementor"
+                    children.append(node(record, relation, **({"constraints": record['constraints']} if record['blanket'] else {})))
+                for record in session.run(f"""
+                    MATCH (c:Class) WHERE $name IN coalesce(c.derives, [])
+                    RETURN {node_fields.format(v='c')} ORDER BY file_path, line_number
+                """, name=trait_name):
+                    children.append(node(dict(record), "derived"))
+                for record in session.run(f"""
+                    MATCH (c:Class {{kind: 'trait'}})
+                    WHERE any(base IN coalesce(c.bases, []) WHERE base = $name OR base ENDS WITH '::' + $name)
+                    RETURN elementId(c) as id, {node_fields.format(v='c')} ORDER BY file_path, line_number
+                """, name=trait_name):
+                    record = dict(record)
+                    child = node(record, "subtrait")
+                    if record['name'] in seen:
+                        child["repeated"] = True
+                    elif level < depth:
+                        child["children"] = implementors(record, seen | {record['name']}, level + 1)
+                    children.append(child)
+                return children
+
+            roots = [r for r in find_types(type_name) if not module_path or module_path in (r['file_path'] or '')]
+            if not roots:
+                # A trait outside the graph still has implementors and derives in it.
+                roots = [{"id": None, "name": type_name, "kind": "trait", "file_path": None, "line_number": None,
+                          "bases": [], "derives": []}]
+
+            trees = []
+            for root in roots:
+                tree = node(root, None)
+                del tree["children"]
+                if root['kind'] == 'trait':
+                    tree["supertypes"] = supertraits(root, {root['name']}, 1)
+                    tree["subtypes"] = implementors(root, {root['name']}, 1)
+                else:
+                    tree["supertypes"] = implemented_traits(root, set(), 1)
+                    tree["subtypes"] = []
+                tree["text"] = render_tree(tree)
+                trees.append(tree)
+        return trees
+
+    def module_dependency_matrix(self, crate_name: str = None, depth: int = None) -> Dict[str, Any]:
+        """
+        The dependency structure matrix (see `dsm`) of the Rust modules of a crate, or of every
+        indexed crate: how many `use` statements, calls, type usages, field and variant accesses
+        and trait impls each module has on each other module. `depth` collapses modules to their
+        first path segments (`crate::net::tcp` counts as `crate::net` at depth 2). Without a crate,
+        modules are prefixed with their crate name instead of `crate`. Non-empty cells are also
+        listed with their counts per kind of dependency.
+        """
+        def collapse(module):
+            return '::'.join(module.split('::')[:max(1, int(depth))]) if depth else module
+
+        with self.driver.session() as session:
+            modules = {}
+            for record in session.run("""
+                MATCH (f:File) WHERE f.module_path IS NOT NULL
+                OPTIONAL MATCH (f)-[:PART_OF]->(cr:Crate)
+                WITH f, cr WHERE $crate_name IS NULL OR cr.name = $crate_name
+                RETURN f.path as path, f.module_path as module, cr.name as crate
+            """, crate_name=crate_name):
+                module = collapse(record['module'])
+                if crate_name is None and record['crate']:
+                    module = re.sub(r'^crate\b', record['crate'], module)
+                modules[record['path']] = module
+            if crate_name and not modules:
+                raise ValueError(f"No indexed Rust crate named '{crate_name}'")
+            files = list(modules)
+            edges = [dict(record) for record in session.run("""
+                MATCH (src)-[r]->(dst)
+                WHERE type(r) IN $types AND coalesce(src.file_path, src.path) IN $files AND dst.file_path IN $files
+                  AND coalesce(src.file_path, src.path) <> dst.file_path
+                RETURN type(r) as relation, coalesce(src.file_path, src.path) as source_file,
+                       dst.file_path as target_file, count(r) as count
+            """, types=list(DEPENDENCY_EVIDENCE), files=files)]
+            edges += [dict(record) for record in session.run("""
+                MATCH (a:File)-[r:USES]->(b:File)
+                WHERE a.path IN $files AND b.path IN $files
+                RETURN 'USES' as relation, a.path as source_file, b.path as target_file, size(r.imports) as count
+            """, files=files)]
+
+        kinds = {**DEPENDENCY_EVIDENCE, 'USES': 'imports'}
+        cells = {}
+        for edge in edges:
+            source, target = modules[edge['source_file']], modules[edge['target_file']]
+            if source != target:
+                cell = cells.setdefault((source, target), {})
+                cell[kinds[edge['relation']]] = cell.get(kinds[edge['relation']], 0) + edge['count']
+        counts = {pair: sum(cell.values()) for pair, cell in cells.items()}
+        dsm = build_matrix(sorted(set(modules.values())), counts)
+        position = {module: i for i, module in enumerate(dsm['modules'])}
+        return {
+            "crate": crate_name,
+            "depth": depth,
+            **dsm,
+            "cells": [{"source": source, "target": target, "count": counts[(source, target)], "kinds": cell}
+                      for (source, target), cell in sorted(cells.items(), key=lambda item: (position[item[0][0]], position[item[0][1]]))],
+        }
+
+    def find_unhandled_results(self, file_path: str = None) -> List[Dict]:
+        """
+        Find call sites that throw away the value of a `Result`-returning function: bare statements
+        (`f();`), `let _ = f();` and `f().ok();`. Each finding names the discarding pattern.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (caller:Function)-[c:CALLS]->(callee:Function)
+                WHERE c.discarded IS NOT NULL AND callee.return_type =~ $result_pattern
+                  AND ($file_path IS NULL OR caller.file_path = $file_path)
+                RETURN DISTINCT caller.name as function, caller.file_path as file_path, c.line_number as line_number,
+                       c.discarded as pattern, c.full_call_name as call, callee.name as callee,
+                       callee.file_path as callee_file_path, callee.return_type as return_type
+                ORDER BY file_path, line_number
+            """, file_path=file_path, result_pattern=r"(?s)\s*(\w+::)*Result\b.*")
+            return [dict(record) for record in result]
+
+    def find_generic_instantiations(self, item_name: str, file_path: str = None) -> List[Dict]:
+        """
+        List the concrete instantiations of a generic type or function, e.g. `Point<i32>` from
+        `Point::new(1, 2)` or `Point<f64>` from an annotation, with their sites. Helps estimate
+        which monomorphizations exist; `_` marks type arguments that could not be inferred.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (src)-[r:INSTANTIATES]->(n {name: $item_name})
+                WHERE (n:Class OR n:Function) AND ($file_path IS NULL OR n.file_path = $file_path)
+                RETURN n.name as item, n.file_path as item_file_path, n.generic_params as generic_params,
+                       r.instantiation as instantiation, r.type_arguments as type_arguments, r.site_kind as site_kind,
+                       r.inferred as inferred, src.name as user, coalesce(src.file_path, src.path) as file_path,
+                       r.line_number as line_number
+                ORDER BY file_path, line_number
+            """, item_name=item_name, file_path=file_path)
+            records = [dict(record) for record in result]
+
+        items = {}
+        for record in records:
+            item = items.setdefault(record['item_file_path'], {
+                "item": record['item'],
+                "file_path": record['item_file_path'],
+                "generic_params": record['generic_params'],
+                "instantiations": {},
+            })
+            instantiation = item["instantiations"].setdefault(record['instantiation'], {
+                "instantiation": record['instantiation'],
+                "type_arguments": record['type_arguments'],
+                "sites": [],
+            })
+            instantiation["sites"].append({
+                "user": record['user'],
+                "file_path": record['file_path'],
+                "line_number": record['line_number'],
+                "site_kind": record['site_kind'],
+                "inferred": record['inferred'],
+            })
+        for item in items.values():
+            item["instantiations"] = sorted(item["instantiations"].values(), key=lambda i: (-len(i['sites']), i['instantiation']))
+            item["instantiation_count"] = len(item["instantiations"])
+        return list(items.values())
+
+    def items_under_feature(self, feature: str) -> Dict[str, Any]:
+        """
+        List the Rust functions and types compiled only with a Cargo feature (through their own
+        `#[cfg]`, an enclosing impl or module, or a gated `mod` declaration), and the code compiled
+        without any `cfg` condition that calls, mentions or instantiates them, which breaks the
+        build when the feature is off.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (file:File)-[:CONTAINS]->(n)
+                WHERE (n:Function OR n:Class) AND ($feature IN coalesce(n.features, []) OR $feature IN coalesce(file.module_features, []))
+                RETURN elementId(n) as id, labels(n)[0] as label, n.name as name, n.class_context as owner,
+                       n.file_path as file_path, n.line_number as line_number,
+                       coalesce(file.module_cfg, []) + coalesce(n.cfg, []) as cfg
+                ORDER BY file_path, line_number
+            """, feature=feature)
+            items = [dict(record) for record in result]
+            result = session.run("""
+                MATCH (ufile:File)-[:CONTAINS]->(user)-[r:CALLS|MAY_CALL|REFERENCES_TYPE|INSTANTIATES|CONSTRUCTS]->(n)
+                WHERE elementId(n) IN $ids AND (user:Function OR user:Class)
+                  AND size(coalesce(user.cfg, [])) = 0 AND size(coalesce(ufile.module_cfg, [])) = 0
+                RETURN DISTINCT user.name as user, user.class_context as user_owner, user.file_path as file_path,
+                       coalesce(r.line_number, user.line_number) as line_number, type(r) as relation,
+                       n.name as target, n.class_context as target_owner
+                ORDER BY file_path, line_number
+            """, ids=[item['id'] for item in items])
+            users = [dict(record) for record in result]
+
+        for item in items:
+            del item['id']
+        return {
+            "feature": feature,
+            "items": items,
+            "ungated_users": users,
+            "summary": {"items": len(items), "ungated_users": len(users)},
+        }
+
+    def estimate_monomorphization(self, limit: int = 20, file_path: str = None) -> List[Dict]:
+        """
+        Estimate how many monomorphized copies each generic function (or method of a generic type)
+        produces from the recorded instantiations (see `monomorphization`), and rank them by the
+        code they generate: copies times lines. Useful when binary size matters.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (f:Function)
+                WHERE coalesce(f.is_dependency, false) = false
+                OPTIONAL MATCH (c:Class)-[:HAS_IMPL]->(:Impl)-[:CONTAINS]->(f)
+                WITH f, c WHERE size(coalesce(f.generic_params, [])) > 0 OR size(coalesce(c.generic_params, [])) > 0
+                RETURN elementId(f) as id, f.name as name, f.class_context as owner, f.file_path as file_path,
+                       f.line_number as line_number, f.end_line as end_line, coalesce(f.generic_params, []) as generic_params,
+                       elementId(c) as class_id, coalesce(c.generic_params, []) as class_params
+            """)
+            functions = {record['id']: dict(record) for record in result}
+            result = session.run("""
+                MATCH (src)-[r:INSTANTIATES]->(n)
+                RETURN elementId(src) as source, elementId(n) as target, r.type_arguments as type_arguments
+            """)
+            instantiations = [(record['source'], record['target'], record['type_arguments'] or []) for record in result]
+
+        class_params = {func['class_id']: func['class_params'] for func in functions.values() if func['class_id']}
+        copies = estimate_copies(functions, class_params, instantiations)
+
+        ranking = []
+        for fid, bindings in copies.items():
+            func = functions[fid]
+            if file_path and func['file_path'] != file_path:
+                continue
+            params = func['class_params'] + func['generic_params']
+            lines = func['end_line'] - func['line_number'] + 1 if func['end_line'] else 1
+            ranking.append({
+                "name": f"{func['owner']}::{func['name']}" if func['owner'] else func['name'],
+                "file_path": func['file_path'],
+                "line_number": func['line_number'],
+                "generic_params": params,
+                "copies": len(bindings),
+                "instantiations": sorted(", ".join(f"{p}={a}" for p, a in zip(params, copy)) for copy in bindings),
+                "lines": lines,
+                "estimated_lines": len(bindings) * lines,
+            })
+        ranking.sort(key=lambda item: (-item['estimated_lines'], -item['copies'], item['file_path'], item['line_number']))
+        return ranking[:limit]
+
+    def get_trait_impl_coverage(self, trait_name: str, file_path: str = None) -> List[Dict]:
+        """
+        For each impl of a trait, report which required methods it implements, which default methods
+        it overrides or inherits, and how it specifies the trait's associated types and consts.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (t:Class {name: $trait_name, kind: 'trait'})
+                WHERE $file_path IS NULL OR t.file_path = $file_path
+                OPTIONAL MATCH (t)-[:DECLARES_TYPE]->(decl:AssociatedType)
+                WITH t, collect(DISTINCT decl.name) as declared_types
+                MATCH (i:Impl)-[:IMPLEMENTS]->(t)
+                OPTIONAL MATCH (i)-[:BINDS_TYPE]->(bound:AssociatedType)
+                RETURN t.name as trait, t.file_path as trait_file_path,
+                       coalesce(t.required_methods, []) as required_methods, coalesce(t.provided_methods, []) as provided_methods,
+                       coalesce(t.required_consts, []) as required_consts, coalesce(t.provided_consts, []) as provided_consts,
+                       declared_types, i.name as impl, i.type as type_name, i.file_path as file_path, i.line_number as line_number,
+                       coalesce(i.methods, []) as methods, coalesce(i.associated_consts, []) as consts,
+                       collect(CASE WHEN bound IS NULL THEN NULL ELSE {name: bound.name, binding: bound.binding} END) as bindings
+                ORDER BY i.file_path, i.line_number
+            """, trait_name=trait_name, file_path=file_path)
+            records = [dict(record) for record in result]
+
+        traits = {}
+        for record in records:
+            trait = traits.setdefault(record['trait_file_path'], {
+                "trait": record['trait'],
+                "file_path": record['trait_file_path'],
+                "required_methods": record['required_methods'],
+                "provided_methods": record['provided_methods'],
+                "associated_types": sorted(record['declared_types']),
+                "required_consts": record['required_consts'],
+                "provided_consts": record['provided_consts'],
+                "impls": [],
+            })
+            methods, consts = set(record['methods']), set(record['consts'])
+            bindings = {binding['name']: binding['binding'] for binding in record['bindings']}
+            trait["impls"].append({
+                "impl": record['impl'],
+                "type_name": record['type_name'],
+                "file_path": record['file_path'],
+                "line_number": record['line_number'],
+                "implemented_methods": [m for m in record['required_methods'] if m in methods],
+                "missing_methods": [m for m in record['required_methods'] if m not in methods],
+                "overridden_defaults": [m for m in record['provided_methods'] if m in methods],
+                "inherited_defaults": [m for m in record['provided_methods'] if m not in methods],
+                "associated_types": bindings,
+                "missing_associated_types": [name for name in sorted(record['declared_types']) if name not in bindings],
+                "specified_consts": sorted(consts),
+                "missing_consts": [c for c in record['required_consts'] if c not in consts],
+                "inherited_consts": [c for c in record['provided_consts'] if c not in consts],
+            })
+        return list(traits.values())
+
+    def find_types_satisfying(self, bounds: str) -> List[Dict]:
+        """
+        Find the types satisfying all of the given trait bounds ('Display + Clone', 'T: Display + Clone'
+        or 'Display, Clone'), through derives, direct impls, blanket impls (`impl<T: Display> Show for T`)
+        or, for Send and Sync, the auto trait analysis. Impls with their own bounds are reported as conditional.
+        """
+        match = re.match(r'^\s*([A-Za-z_]\w*)\s*:(?!:)(.*)$', bounds)
+        bound_text = match.group(2) if match else bounds
+        required = [b.strip().split('<')[0].split('::')[-1] for b in re.split(r'[+,]', bound_text) if b.strip()]
+
+        with self.driver.session() as session:
+            types = [dict(record) for record in session.run("""
+                MATCH (c:Class)
+                WHERE c.kind IN ['struct', 'enum', 'union']
+                OPTIONAL MATCH (c)-[:HAS_IMPL]->(i:Impl)
+                WHERE i.trait IS NOT NULL
+                RETURN c.name as type_name, c.file_path as file_path, c.line_number as line_number, c.kind as kind,
+                       coalesce(c.derives, []) as derives, c.is_send as is_send, c.is_sync as is_sync,
+                       collect(CASE WHEN i IS NULL THEN NULL ELSE
+                           {trait: i.trait, negative: coalesce(i.is_negative, false),
+                            conditional: size(coalesce(i.constraints, [])) > 0} END) as impls
+            """)]
+            blanket_impls = [dict(record) for record in session.run("""
+                MATCH (i:Impl)
+                WHERE i.blanket_param IS NOT NULL
+                RETURN i.trait as trait, i.blanket_param as param, coalesce(i.constraints, []) as constraints
+            """)]
+
+        blanket_bounds = {}
+        for impl in blanket_impls:
+            prefix = impl['param'] + ': '
+            param_bounds = [c[len(prefix):].split('<')[0].split('::')[-1] for c in impl['constraints'] if c.startswith(prefix)]
+            blanket_bounds.setdefault(impl['trait'], []).append([b for b in param_bounds if not b.startswith(("'", '?'))])
+
+        def satisfies(type_info, trait, visiting):
+            """Returns how `type_info` satisfies `trait` ('derive', 'impl', 'conditional impl', 'auto', 'blanket') or None."""
+            if trait in type_info['derives']:
+                return 'derive'
+            matching = [i for i in type_info['impls'] if i['trait'] == trait]
+            if any(i['negative'] for i in matching):
+                return None
+            if matching:
+                return 'conditional impl' if all(i['conditional'] for i in matching) else 'impl'
+            if trait in ('Send', 'Sync') and type_info[f"is_{trait.lower()}"]:
+                return 'auto'
+            if trait in visiting:
+                return None
+            for param_bounds in blanket_bounds.get(trait, []):
+                if all(satisfies(type_info, bound, visiting | {trait}) for bound in param_bounds):
+                    return 'blanket'
+            return None
+
+        results = []
+        for type_info in types:
+            satisfied_by = {trait: satisfies(type_info, trait, frozenset()) for trait in required}
+            if all(satisfied_by.values()):
+                results.append({
+                    "type_name": type_info['type_name'],
+                    "file_path": type_info['file_path'],
+                    "line_number": type_info['line_number'],
+                    "kind": type_info['kind'],
+                    "satisfied_by": satisfied_by,
+                    "conditional": any(how == 'conditional impl' for how in satisfied_by.values()),
+                })
+        return sorted(results, key=lambda r: (r['conditional'], r['type_name'], r['file_path']))
+
     def analyze_code_relationships(self, query_type: str, target: str, context: str = None) -> Dict[str, Any]:
         """Main method to analyze different types of code relationships with fixed return types"""
         query_type = query_type.lower().strip()
@@ -698,6 +2143,48 @@ class CodeFinder:
                     "summary": f"Module '{target}' is imported by {len(results['imported_by_files'])} files"
                 }
             
+            elif query_type in ["find_lock_users", "who_locks", "lock_users"]:
+                results = self.find_lock_users(target, context)
+                return {
+                    "query_type": "find_lock_users", "target": target, "context": context, "results": results,
+                    "summary": f"Found {len(results)} lock/atomic accesses of '{target}'"
+                }
+
+            elif query_type in ["find_variant_usages", "variant_usages", "who_handles_variant"]:
+                results = self.find_variant_usages(target, context)
+                return {
+                    "query_type": "find_variant_usages", "target": target, "context": context, "results": results,
+                    "summary": f"Found {len(results)} constructions/matches of variant '{target}'"
+                }
+
+            elif query_type in ["find_field_accesses", "field_accesses", "who_writes_field"]:
+                results = self.find_field_accesses(target, context)
+                return {
+                    "query_type": "find_field_accesses", "target": target, "context": context, "results": results,
+                    "summary": f"Found {len(results)} reads/writes of field '{target}'"
+                }
+
+            elif query_type in ["find_closure_captures", "closure_captures", "who_captures"]:
+                results = self.find_closure_captures(target, context)
+                return {
+                    "query_type": "find_closure_captures", "target": target, "context": context, "results": results,
+                    "summary": f"Found {len(results)} closure captures of '{target}'"
+                }
+
+            elif query_type in ["find_type_usages", "type_usages", "who_uses_type"]:
+                results = self.find_type_usages(target, context)
+                return {
+                    "query_type": "find_type_usages", "target": target, "context": context, "results": results,
+                    "summary": f"Found {len(results)} usages of type '{target}'"
+                }
+
+            elif query_type in ["find_by_trait_bound", "trait_bound", "requires_bound"]:
+                results = self.find_by_trait_bound(target, context)
+                return {
+                    "query_type": "find_by_trait_bound", "target": target, "context": context, "results": results,
+                    "summary": f"Found {len(results)} items requiring '{target}'"
+                }
+
             elif query_type in ["variable_scope", "var_scope", "variable_usage_scope"]:
                 results = self.find_variable_usage_scope(target)
                 return {
@@ -711,7 +2198,9 @@ class CodeFinder:
                     "supported_types": [
                         "find_callers", "find_callees", "find_importers", "who_modifies",
                         "class_hierarchy", "overrides", "dead_code", "call_chain",
-                        "module_deps", "variable_scope", "find_complexity"
+                        "module_deps", "variable_scope", "find_complexity", "find_lock_users",
+                        "find_variant_usages", "find_field_accesses", "find_type_usages", "find_by_trait_bound",
+                        "find_closure_captures"
                     ]
                 }
         
@@ -742,6 +2231,979 @@ class CodeFinder:
             
             return [dict(record) for record in result]
 
+    def find_await_holding_lock(self, file_path: str = None) -> List[Dict]:
+        """Find async functions where a synchronous lock guard is still alive across an `.await`."""
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (f:Function)
+                WHERE f.await_holding_lock IS NOT NULL AND ($file_path IS NULL OR f.file_path = $file_path)
+                OPTIONAL MATCH (f)-[r:HOLDS_LOCK_ACROSS_AWAIT]->(l:Lock)
+                RETURN f.name as function_name, f.file_path as file_path, f.line_number as line_number,
+                       f.class_context as class_context, f.await_holding_lock as findings,
+                       collect(CASE WHEN l IS NULL THEN NULL ELSE {lock_name: l.name, lock_kind: l.kind, guard: r.guard,
+                                    lock_line: r.lock_line, await_line: r.await_line, await_span: r.await_span} END) as locks
+                ORDER BY f.file_path, f.line_number
+            """, file_path=file_path)
+            return [dict(record) for record in result]
+
+    def find_blocking_in_async(self, require_entry_point: bool = False, file_path: str = None) -> List[Dict]:
+        """
+        Find blocking calls (sleeps, std IO, std mutex locks) made in async code: directly in an
+        async function or block, or in sync functions that async functions call (up to three calls
+        deep). Each finding names the async entry point (`#[tokio::main]`, ...) it is reachable from, if any.
+        """
+        with self.driver.session() as session:
+            direct = session.run("""
+                MATCH (f:Function)-[b:BLOCKS_ON]->(op:BlockingCall)
+                WHERE b.in_async = true AND ($file_path IS NULL OR f.file_path = $file_path)
+                RETURN f.name as async_function, f.file_path as async_file_path, f.line_number as async_line_number,
+                       f.name as function_name, f.file_path as file_path, b.line_number as line_number,
+                       op.name as call, op.category as category, [f.name] as call_path
+            """, file_path=file_path)
+            indirect = session.run("""
+                MATCH path = (a:Function)-[:CALLS*1..3]->(f:Function)-[b:BLOCKS_ON]->(op:BlockingCall)
+                WHERE a.is_async = true AND b.in_async = false
+                  AND all(n IN nodes(path)[1..-1] WHERE coalesce(n.is_async, false) = false)
+                  AND ($file_path IS NULL OR f.file_path = $file_path)
+                RETURN DISTINCT a.name as async_function, a.file_path as async_file_path, a.line_number as async_line_number,
+                       f.name as function_name, f.file_path as file_path, b.line_number as line_number,
+                       op.name as call, op.category as category, [n IN nodes(path)[..-1] | n.name] as call_path
+            """, file_path=file_path)
+            findings = [dict(record) for record in direct] + [dict(record) for record in indirect]
+
+            for finding in findings:
+                entry = session.run("""
+                    MATCH path = (e:Function)-[:CALLS*0..5]->(a:Function {name: $name, file_path: $file_path, line_number: $line_number})
+                    WHERE e.is_async_entry_point = true
+                    RETURN e.name as entry_point, [n IN nodes(path) | n.name] as path
+                    ORDER BY length(path)
+                    LIMIT 1
+                """, name=finding['async_function'], file_path=finding['async_file_path'],
+                    line_number=finding['async_line_number']).single()
+                finding['entry_point'] = entry['entry_point'] if entry else None
+                finding['entry_path'] = entry['path'] if entry else None
+
+        if require_entry_point:
+            findings = [finding for finding in findings if finding['entry_point']]
+        return sorted(findings, key=lambda f: (f['file_path'], f['line_number'], f['async_function']))
+
+    def _module_files(self, session, module: str) -> List[str]:
+        """The files of a Rust module given by path (`crate::net` or `net`), submodules included; or of a file name."""
+        module = module.strip()
+        path = module if module.startswith('crate') else f"crate::{module}"
+        files = [record['path'] for record in session.run("""
+            MATCH (f:File)
+            WHERE f.module_path = $path OR f.module_path STARTS WITH $path + '::'
+            RETURN f.path as path
+        """, path=path)]
+        if not files:
+            files = [record['path'] for record in session.run(
+                "MATCH (f:File) WHERE f.path ENDS WITH '/' + $name RETURN f.path as path", name=module)]
+        return files
+
+    def find_tasks(self, subsystem: str = None, kinds: List[str] = None, author: str = None) -> List[Dict]:
+        """
+        List the open TODO, FIXME and HACK comments, optionally in a subsystem given as a Rust module
+        path (`crate::net` or `net`, submodules included) or a file name, of some `kinds` or last
+        touched by an `author` (from git blame). Each task comes with the function and module it sits
+        in; the oldest come first.
+        """
+        with self.driver.session() as session:
+            files = self._module_files(session, subsystem) if subsystem else None
+            if subsystem and not files:
+                raise ValueError(f"No module or file named '{subsystem}' in the graph")
+            result = session.run("""
+                MATCH (f:File)-[:CONTAINS]->(t:Task)
+                WHERE ($files IS NULL OR f.path IN $files) AND ($kinds IS NULL OR t.kind IN $kinds)
+                  AND ($author IS NULL OR t.last_author = $author OR t.last_author_email = $author)
+                OPTIONAL MATCH (fn:Function)-[:HAS_TASK]->(t)
+                RETURN t.kind as kind, t.text as text, t.assignee as assignee, t.file_path as file_path,
+                       t.line_number as line_number, fn.name as function, t.owner as owner,
+                       coalesce(f.module_path, f.relative_path) as module, t.last_author as author,
+                       t.last_modified as last_modified, t.age_days as age_days, t.last_commit as commit
+                ORDER BY coalesce(t.age_days, -1) DESC, t.file_path, t.line_number
+            """, files=files, kinds=[kind.upper() for kind in kinds] if kinds else None, author=author)
+            return [dict(record) for record in result]
+
+    def why_depends(self, module_a: str, module_b: str) -> Dict[str, Any]:
+        """
+        Explain why Rust module `module_a` depends on `module_b` with the concrete evidence: `use`
+        statements, calls, type mentions, generic instantiations, field and variant accesses and
+        trait impls going from A's items to B's. Modules are given by path (`crate::concurrency`
+        or `concurrency`) and include their submodules; a file name works for other languages.
+        """
+        evidence_kinds = DEPENDENCY_EVIDENCE
+        with self.driver.session() as session:
+            files_a, files_b = self._module_files(session, module_a), self._module_files(session, module_b)
+            if not files_a or not files_b:
+                missing = module_a if not files_a else module_b
+                raise ValueError(f"No indexed module or file matches '{missing}'")
+
+            imports = [record['statement'] for record in session.run("""
+                MATCH (a:File)-[r:USES]->(b:File)
+                WHERE a.path IN $files_a AND b.path IN $files_b
+                UNWIND r.imports as statement
+                RETURN DISTINCT statement
+            """, files_a=files_a, files_b=files_b)]
+
+            edges = session.run("""
+                MATCH (src)-[r]->(dst)
+                WHERE type(r) IN $types AND coalesce(src.file_path, src.path) IN $files_a AND dst.file_path IN $files_b
+                RETURN DISTINCT type(r) as relation, labels(src)[0] as source_label, src.name as source,
+                       coalesce(src.file_path, src.path) as source_file, coalesce(r.line_number, src.line_number) as line_number,
+                       labels(dst)[0] as target_label, dst.name as target, coalesce(dst.owner, dst.class_context) as target_owner,
+                       r.role as role
+                ORDER BY source_file, line_number
+            """, types=list(evidence_kinds), files_a=files_a, files_b=files_b)
+            edges = [dict(record) for record in edges]
+
+        evidence = {"imports": imports, "calls": [], "type_usages": [], "field_accesses": [], "variant_usages": [], "trait_impls": []}
+        for edge in edges:
+            target = f"{edge['target_owner']}::{edge['target']}" if edge['target_owner'] else edge['target']
+            entry = {"relation": edge['relation'], "source": edge['source'], "target": target,
+                     "file_path": edge['source_file'], "line_number": edge['line_number']}
+            if edge['role']:
+                entry["role"] = edge['role']
+            evidence[evidence_kinds[edge['relation']]].append(entry)
+
+        return {
+            "module_a": module_a,
+            "module_b": module_b,
+            "depends": any(evidence.values()),
+            "files_a": files_a,
+            "files_b": files_b,
+            "evidence": evidence,
+            "summary": {kind: len(items) for kind, items in evidence.items()},
+        }
+
+    def find_taint_flows(self, sources: List[str] = None, sinks: List[str] = None, max_depth: int = 4,
+                         limit: int = 50) -> Dict[str, Any]:
+        """
+        Find call chains along which data from source calls can reach sink calls (see `taint`).
+        Sources and sinks are call patterns such as `read_file_contents` or `Command::new`; the
+        defaults cover common Rust input APIs and command, file system and SQL sinks.
+        """
+        sources, sinks = sources or DEFAULT_SOURCES, sinks or DEFAULT_SINKS
+        max_depth = max(1, min(int(max_depth), 8))
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (f:Function) WHERE coalesce(f.is_dependency, false) = false
+                RETURN elementId(f) as id, f.name as name, f.class_context as owner, f.file_path as file_path,
+                       f.line_number as line_number, f.call_names as call_names, f.call_lines as call_lines
+            """)
+            functions = {record['id']: dict(record) for record in result}
+            result = session.run("""
+                MATCH (a:Function)-[:CALLS]->(b:Function)
+                WHERE coalesce(a.is_dependency, false) = false AND coalesce(b.is_dependency, false) = false
+                RETURN DISTINCT elementId(a) as caller, elementId(b) as callee
+            """)
+            calls = [(record['caller'], record['callee']) for record in result]
+
+        flows = find_flows(functions, calls, sources, sinks, max_depth, limit)
+        return {"sources": sources, "sinks": sinks, "findings": flows}
+
+    def export_subgraph(self, root: str, root_kind: str = 'function', edge_types: List[str] = None, depth: int = 2,
+                        direction: str = 'out', file_path: str = None, include_external: bool = False,
+                        max_nodes: int = 500, min_samples: int = None) -> Dict[str, Any]:
+        """
+        Export the subgraph reachable from a function (`name` or `Owner::name`), a module (by path
+        or file name) or a crate (by name) over the given edge types, up to `depth` hops in the
+        `direction` of the edges (`out`, `in` or `both`). Module and crate exports stay inside the
+        module or crate unless `include_external` is set. Stops growing at `max_nodes` nodes. With
+        `min_samples`, only call edges with at least that many profile samples (see
+        `GraphBuilder.import_profile`) are followed, giving the hot paths. Relationships of one type
+        between the same two nodes become one edge whose `weight` counts them (e.g. call sites).
+        """
+        if root_kind not in ('function', 'module', 'crate'):
+            raise ValueError(f"Unknown root kind '{root_kind}'; expected function, module or crate")
+        if direction not in ('out', 'in', 'both'):
+            raise ValueError(f"Unknown direction '{direction}'; expected out, in or both")
+        edge_types = [edge_type.strip().upper() for edge_type in (edge_types or ['CALLS'])]
+        depth = max(1, min(int(depth), 10))
+        node_fields = """labels({v})[0] as label, {v}.name as name, coalesce({v}.class_context, {v}.owner) as owner,
+                         coalesce({v}.file_path, {v}.path) as file_path, {v}.line_number as line_number, {v}.kind as kind"""
+
+        with self.driver.session() as session:
+            scope_files = None
+            if root_kind == 'function':
+                owner, name = (root.rsplit('::', 1) if '::' in root else (None, root))
+                roots = session.run(f"""
+                    MATCH (n:Function {{name: $name}})
+                    WHERE ($owner IS NULL OR n.class_context = $owner) AND ($file_path IS NULL OR n.file_path ENDS WITH $file_path)
+                    RETURN elementId(n) as id, {node_fields.format(v='n')}
+                """, name=name, owner=owner, file_path=file_path)
+            else:
+                if root_kind == 'module':
+                    scope_files = self._module_files(session, root)
+                else:
+                    scope_files = [record['path'] for record in session.run(
+                        "MATCH (f:File)-[:PART_OF]->(:Crate {name: $name}) RETURN f.path as path", name=root)]
+                roots = session.run(f"""
+                    MATCH (f:File)-[:CONTAINS]->(n:Function) WHERE f.path IN $files
+                    RETURN elementId(n) as id, {node_fields.format(v='n')}
+                """, files=scope_files)
+            nodes = {record['id']: {**dict(record), "root": True} for record in roots}
+            if not nodes:
+                raise ValueError(f"No indexed {root_kind} matches '{root}'")
+            if include_external:
+                scope_files = None
+
+            patterns = {'out': ["(n)-[r]->(m)"], 'in': ["(n)<-[r]-(m)"], 'both': ["(n)-[r]->(m)", "(n)<-[r]-(m)"]}[direction]
+            edges, relationships, frontier, truncated = {}, {}, list(nodes), False
+            for _ in range(depth):
+                next_frontier = []
+                for pattern in patterns:
+                    result = session.run(f"""
+                        MATCH {pattern}
+                        WHERE elementId(n) IN $ids AND type(r) IN $types
+                          AND ($files IS NULL OR coalesce(m.file_path, m.path) IN $files)
+                          AND ($min_samples IS NULL OR coalesce(r.samples, 0) >= $min_samples)
+                        RETURN elementId(startNode(r)) as source, elementId(endNode(r)) as target, type(r) as type,
+                               r.line_number as line_number, r.samples as samples, elementId(r) as relationship, elementId(m) as id,
+                               {node_fields.format(v='m')}
+                    """, ids=frontier, types=edge_types, files=scope_files, min_samples=min_samples)
+                    for record in result:
+                        record = dict(record)
+                        if record['id'] not in nodes:
+                            if len(nodes) >= max_nodes:
+                                truncated = True
+                                continue
+                            nodes[record['id']] = {key: record[key] for key in
+                                                   ('id', 'label', 'name', 'owner', 'file_path', 'line_number', 'kind')}
+                            nodes[record['id']]["root"] = False
+                            next_frontier.append(record['id'])
+                        key = (record['source'], record['target'], record['type'])
+                        relationships.setdefault(key, set()).add(record['relationship'])
+                        edge = edges.setdefault(key, {field: record[field] for field in
+                                                      ('source', 'target', 'type', 'line_number', 'samples')})
+                        edge['line_number'] = min(filter(None, (edge['line_number'], record['line_number'])), default=None)
+                        edge['weight'] = len(relationships[key])
+                frontier = next_frontier
+                if not frontier:
+                    break
+
+        return {
+            "root": root,
+            "root_kind": root_kind,
+            "edge_types": edge_types,
+            "depth": depth,
+            "direction": direction,
+            "min_samples": min_samples,
+            "nodes": list(nodes.values()),
+            "edges": list(edges.values()),
+            "truncated": truncated,
+        }
+
+    def export_graph(self, repo_path: str = None, labels: List[str] = None, edge_types: List[str] = None,
+                     max_nodes: int = None) -> Dict[str, Any]:
+        """
+        Export the whole graph, or the nodes under one repository, for offline exploration: the
+        nodes with the given labels (all by default) and the relationships of the given types (all
+        by default) between them, each with its stored properties (see `graph_export`). Stops at
+        `max_nodes` nodes when given. Unlike `export_subgraph`, relationships are not merged.
+        """
+        root = str(Path(repo_path).resolve()) if repo_path else None
+        labels = [label.strip() for label in labels] if labels else None
+        edge_types = [edge_type.strip().upper() for edge_type in edge_types] if edge_types else None
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (n)
+                WHERE ($root IS NULL OR coalesce(n.file_path, n.path) = $root
+                       OR coalesce(n.file_path, n.path) STARTS WITH $prefix)
+                  AND ($labels IS NULL OR any(label IN labels(n) WHERE label IN $labels))
+                RETURN elementId(n) as id, labels(n)[0] as label, properties(n) as props
+                ORDER BY label, coalesce(n.file_path, n.path), n.line_number
+            """ + (" LIMIT $limit" if max_nodes else ""), root=root, prefix=root and root + os.sep,
+                labels=labels, limit=(max_nodes or 0) + 1)
+            nodes = {}
+            for record in result:
+                props = {key: value for key, value in record['props'].items() if key not in EXCLUDED_PROPERTIES}
+                nodes[record['id']] = {
+                    "id": record['id'], "label": record['label'], "name": props.get('name'), "kind": props.get('kind'),
+                    "owner": props.get('class_context', props.get('owner')),
+                    "file_path": props.get('file_path', props.get('path')), "line_number": props.get('line_number'),
+                    "properties": props,
+                }
+            truncated = bool(max_nodes) and len(nodes) > max_nodes
+            if truncated:
+                nodes = dict(list(nodes.items())[:max_nodes])
+            result = session.run("""
+                MATCH (n)-[r]->(m)
+                WHERE elementId(n) IN $ids AND elementId(m) IN $ids AND ($types IS NULL OR type(r) IN $types)
+                RETURN elementId(n) as source, elementId(m) as target, type(r) as type, properties(r) as props
+            """, ids=list(nodes), types=edge_types)
+            edges = [{"source": record['source'], "target": record['target'], "type": record['type'],
+                      "line_number": record['props'].get('line_number'), "samples": record['props'].get('samples'),
+                      "weight": 1, "properties": record['props']} for record in result]
+
+        return {
+            "root": None,
+            "repo_path": root,
+            "labels": labels,
+            "edge_types": edge_types,
+            "nodes": list(nodes.values()),
+            "edges": edges,
+            "truncated": truncated,
+        }
+
+    def symbol_graph(self, repo_path: str) -> Dict[str, Any]:
+        """
+        The resolved symbols of a repository, for code navigation indexes (see `scip`): its `files`,
+        the `definitions` in them, the `references` to those definitions (calls, type mentions
+        and field accesses, in the file they are made from) and the `implementations` relating
+        types and methods to the traits, trait methods or classes they implement.
+        """
+        root = str(Path(repo_path).resolve())
+        prefix = root + os.sep
+        with self.driver.session() as session:
+            files = [dict(record) for record in session.run("""
+                MATCH (f:File) WHERE f.path STARTS WITH $prefix AND f.alias_of IS NULL
+                RETURN f.path as path, coalesce(f.relative_path, f.name) as relative_path
+                ORDER BY path
+            """, prefix=prefix)]
+            definitions = [dict(record) for record in session.run("""
+                MATCH (n) WHERE (n:Function OR n:Class OR n:Variable OR n:Field OR n:Variant OR n:Macro)
+                  AND n.file_path STARTS WITH $prefix
+                RETURN elementId(n) as id, labels(n)[0] as label, n.name as name, n.kind as kind,
+                       coalesce(n.class_context, n.owner, n.enum) as owner, n.context as context,
+                       n.inline_module as inline_module, n.file_path as file_path, n.line_number as line_number,
+                       n.column as column, n.start_line as start_line, n.start_column as start_column,
+                       n.end_line as end_line, n.end_column as end_column, n.signature as signature,
+                       n.docstring as docstring
+            """, prefix=prefix)]
+            references = [dict(record) for record in session.run("""
+                MATCH (src)-[r:CALLS|REFERENCES_TYPE|READS_FIELD|WRITES_FIELD]->(t)
+                WHERE coalesce(src.file_path, src.path) STARTS WITH $prefix AND t.file_path STARTS WITH $prefix
+                RETURN elementId(t) as target, coalesce(src.file_path, src.path) as file_path,
+                       r.line_number as line_number, r.column as column,
+                       CASE type(r) WHEN 'WRITES_FIELD' THEN 'write' WHEN 'READS_FIELD' THEN 'read' END as access
+            """, prefix=prefix)]
+            implementations = [dict(record) for record in session.run("""
+                MATCH (c:Class)-[:HAS_IMPL]->(:Impl)-[:IMPLEMENTS]->(t:Class)
+                WHERE c.file_path STARTS WITH $prefix
+                RETURN elementId(c) as source, elementId(t) as target
+                UNION
+                MATCH (t:Class)<-[:IMPLEMENTS]-(:Impl)-[:CONTAINS]->(m:Function)
+                WHERE m.file_path STARTS WITH $prefix
+                MATCH (tm:Function {name: m.name, class_context: t.name, file_path: t.file_path})
+                RETURN elementId(m) as source, elementId(tm) as target
+                UNION
+                MATCH (c:Class)-[:INHERITS]->(p:Class)
+                WHERE c.file_path STARTS WITH $prefix
+                RETURN elementId(c) as source, elementId(p) as target
+            """, prefix=prefix)]
+        return {"root": root, "files": files, "definitions": definitions, "references": references,
+                "implementations": implementations}
+
+    def call_hierarchy(self, symbol: str, direction: str = 'incoming', depth: int = 3, file_path: str = None) -> List[Dict]:
+        """
+        The callers (`incoming`) or callees (`outgoing`) of a function (`name` or `Owner::name`) as
+        nested LSP call hierarchy entries with spans and call site ranges, `depth` levels deep
+        (see `call_hierarchy`). Trait-dispatched calls count as calls. One tree per matching function.
+        """
+        if direction not in ('incoming', 'outgoing'):
+            raise ValueError(f"Unknown direction '{direction}'; expected incoming or outgoing")
+        depth = max(1, min(int(depth), 10))
+        owner, name = (symbol.rsplit('::', 1) if '::' in symbol else (None, symbol))
+        node_fields = """{v}.name as name, {v}.class_context as owner, {v}.file_path as file_path,
+                         {v}.line_number as line_number, {v}.end_line as end_line, {v}.signature as signature"""
+        pattern = "(m:Function)-[r:CALLS|MAY_CALL]->(n)" if direction == 'incoming' else "(n)-[r:CALLS|MAY_CALL]->(m:Function)"
+
+        with self.driver.session() as session:
+            roots = [dict(record) for record in session.run(f"""
+                MATCH (n:Function {{name: $name}})
+                WHERE ($owner IS NULL OR n.class_context = $owner) AND ($file_path IS NULL OR n.file_path ENDS WITH $file_path)
+                RETURN elementId(n) as id, {node_fields.format(v='n')}
+                ORDER BY file_path, line_number
+            """, name=name, owner=owner, file_path=file_path)]
+            if not roots:
+                raise ValueError(f"Function '{symbol}' not found")
+
+            nodes = {root['id']: root for root in roots}
+            calls, frontier = {}, list(nodes)
+            for _ in range(depth):
+                result = session.run(f"""
+                    MATCH {pattern}
+                    WHERE elementId(n) IN $ids
+                    RETURN elementId(startNode(r)) as caller, elementId(endNode(r)) as callee,
+                           coalesce(r.line_number, r.call_site_line) as line_number, elementId(m) as id,
+                           {node_fields.format(v='m')}
+                """, ids=frontier)
+                frontier = []
+                for record in result:
+                    record = dict(record)
+                    if record['id'] not in nodes:
+                        nodes[record['id']] = {key: record[key] for key in
+                                               ('id', 'name', 'owner', 'file_path', 'line_number', 'end_line', 'signature')}
+                        frontier.append(record['id'])
+                    calls.setdefault((record['caller'], record['callee']), []).append(record['line_number'])
+                if not frontier:
+                    break
+
+        return [build_tree(root['id'], nodes, calls, direction, depth) for root in roots]
+
+    def check_layering(self, repo_path: str) -> Dict[str, Any]:
+        """
+        Evaluate the layering rules of a repository (see `layering`) against the module
+        dependencies in the graph: `use` statements and the calls, type usages, field and variant
+        accesses and trait impls between files. The violations are also stored on the Repository
+        node as `layering_violations` so they can be queried after indexing.
+        """
+        repo_path = str(Path(repo_path).resolve())
+        rules = load_rules(Path(repo_path))
+        if rules is None:
+            raise ValueError(f"No {LAYERS_CONFIG_FILE} found in {repo_path}")
+        root = repo_path + os.sep
+        with self.driver.session() as session:
+            modules = {record['path']: record['module'] for record in session.run("""
+                MATCH (f:File) WHERE f.path STARTS WITH $root
+                RETURN f.path as path, coalesce(f.module_path, f.relative_path) as module
+            """, root=root)}
+            edges = [dict(record) for record in session.run("""
+                MATCH (src)-[r]->(dst)
+                WHERE type(r) IN $types AND coalesce(src.file_path, src.path) STARTS WITH $root AND dst.file_path STARTS WITH $root
+                  AND coalesce(src.file_path, src.path) <> dst.file_path
+                RETURN DISTINCT type(r) as relation, src.name as source, coalesce(src.file_path, src.path) as source_file,
+                       dst.name as target, dst.file_path as target_file, coalesce(r.line_number, src.line_number) as line_number
+            """, types=list(DEPENDENCY_EVIDENCE), root=root)]
+            edges += [dict(record) for record in session.run("""
+                MATCH (a:File)-[r:USES]->(b:File)
+                WHERE a.path STARTS WITH $root AND b.path STARTS WITH $root
+                UNWIND r.imports as statement
+                RETURN 'USES' as relation, statement as source, a.path as source_file, b.name as target,
+                       b.path as target_file, null as line_number
+            """, root=root)]
+
+            dependencies = {}
+            for edge in edges:
+                source, target = modules.get(edge['source_file']), modules.get(edge['target_file'])
+                if source and target:
+                    dependencies.setdefault((source, target), []).append(edge)
+            violations = evaluate_rules(rules, dependencies)
+
+            session.run("MATCH (r:Repository {path: $path}) SET r.layering_violations = $violations", path=repo_path,
+                        violations=[f"{v['source_module']} -> {v['target_module']}: {v['rule']}" for v in violations])
+
+        return {
+            "config": str(Path(repo_path) / LAYERS_CONFIG_FILE),
+            "rules": [rule['name'] for rule in rules],
+            "module_dependencies": len(dependencies),
+            "violations": violations,
+        }
+
+    def find_circular_module_dependencies(self) -> List[Dict]:
+        """
+        Find strongly connected components of more than one Rust module in the module-level USES
+        graph. Each component lists its modules, the `use` statements of every edge inside it and
+        one concrete cycle through it, largest components first.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (a:File)-[r:USES]->(b:File)
+                RETURN a.module_path as source, b.module_path as target, a.path as source_file, r.imports as imports
+            """)
+            edges = [dict(record) for record in result]
+
+        graph, files = {}, {}
+        for edge in edges:
+            graph.setdefault(edge['source'], set()).add(edge['target'])
+            graph.setdefault(edge['target'], set())
+            files[edge['source']] = edge['source_file']
+
+        # Tarjan's algorithm, iterative to stay clear of the recursion limit on large crates.
+        index, lowlink, on_stack, stack, components = {}, {}, set(), [], []
+        for root in sorted(graph):
+            if root in index:
+                continue
+            work = [(root, iter(sorted(graph[root])))]
+            index[root] = lowlink[root] = len(index)
+            stack.append(root)
+            on_stack.add(root)
+            while work:
+                node, children = work[-1]
+                child = next(children, None)
+                if child is not None:
+                    if child not in index:
+                        index[child] = lowlink[child] = len(index)
+                        stack.append(child)
+                        on_stack.add(child)
+                        work.append((child, iter(sorted(graph[child]))))
+                    elif child in on_stack:
+                        lowlink[node] = min(lowlink[node], index[child])
+                    continue
+                work.pop()
+                if work:
+                    lowlink[work[-1][0]] = min(lowlink[work[-1][0]], lowlink[node])
+                if lowlink[node] == index[node]:
+                    component = []
+                    while True:
+                        member = stack.pop()
+                        on_stack.discard(member)
+                        component.append(member)
+                        if member == node:
+                            break
+                    if len(component) > 1:
+                        components.append(sorted(component))
+
+        def find_cycle(start, members):
+            paths = [[start]]
+            while paths:
+                path = paths.pop(0)
+                for nxt in sorted(graph[path[-1]]):
+                    if nxt == start:
+                        return path + [start]
+                    if nxt in members and nxt not in path:
+                        paths.append(path + [nxt])
+            return None
+
+        report = []
+        for component in components:
+            members = set(component)
+            report.append({
+                "modules": component,
+                "size": len(component),
+                "cycle": find_cycle(component[0], members),
+                "edges": sorted(
+                    ({"from": e['source'], "to": e['target'], "file_path": e['source_file'], "imports": e['imports']}
+                     for e in edges if e['source'] in members and e['target'] in members),
+                    key=lambda e: (e['from'], e['to'])),
+            })
+        return sorted(report, key=lambda c: (-c['size'], c['modules']))
+
+    def get_public_api(self, crate_name: str = None) -> List[Dict]:
+        """
+        List the effective public API of Rust crates: every function and type reachable from the
+        crate root through `pub mod` and `pub use`, one entry per public path, sorted by path.
+        Types also carry their `pub` inherent methods and trait impls, so two snapshots of the
+        output can be diffed to review API changes.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (n)
+                WHERE (n:Function OR n:Class) AND size(coalesce(n.public_paths, [])) > 0
+                OPTIONAL MATCH (n)-[:HAS_IMPL]->(i:Impl)
+                OPTIONAL MATCH (i)-[:CONTAINS]->(m:Function)
+                WHERE i.trait IS NULL AND m.visibility = 'pub'
+                RETURN n.public_paths as paths, CASE WHEN n:Function THEN 'function' ELSE n.kind END as kind,
+                       n.name as name, n.signature as signature, n.docstring as docstring,
+                       n.file_path as file_path, n.line_number as line_number,
+                       collect(DISTINCT CASE WHEN i.trait IS NOT NULL THEN i.name END) as trait_impls,
+                       collect(DISTINCT CASE WHEN m IS NOT NULL THEN m.signature END) as methods
+            """)
+            records = [dict(record) for record in result]
+
+        api = []
+        for record in records:
+            for path in record['paths']:
+                if crate_name and path.split('::')[0] != crate_name.replace('-', '_'):
+                    continue
+                entry = {
+                    "path": path,
+                    "kind": record['kind'],
+                    "signature": record['signature'],
+                    "docstring": record['docstring'],
+                    "file_path": record['file_path'],
+                    "line_number": record['line_number'],
+                }
+                if record['kind'] != 'function':
+                    entry["methods"] = sorted(record['methods'])
+                    entry["trait_impls"] = sorted(record['trait_impls'])
+                api.append(entry)
+        return sorted(api, key=lambda item: item['path'])
+
+    def get_doc_coverage(self, crate_name: str = None, min_coverage: float = 0.0) -> Dict[str, Any]:
+        """
+        Documentation coverage of the public API of Rust crates: the share of public functions and
+        types (see `get_public_api`), of the `pub` inherent methods, `pub` fields, variants and trait
+        methods of public types and of exported macros that have doc comments, per crate and module,
+        with the undocumented items. `passed` tells whether every crate reaches `min_coverage` (a
+        percentage); modules below it are flagged too.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (n) WHERE (n:Function OR n:Class) AND size(coalesce(n.public_paths, [])) > 0
+                RETURN CASE WHEN n:Function THEN 'function' ELSE n.kind END as kind, n.public_paths[0] as path,
+                       n.docstring as docstring, n.file_path as file_path, n.line_number as line_number
+                UNION ALL
+                MATCH (c:Class)-[:HAS_IMPL]->(i:Impl)-[:CONTAINS]->(m:Function)
+                WHERE size(coalesce(c.public_paths, [])) > 0 AND i.trait IS NULL AND m.visibility = 'pub'
+                RETURN 'method' as kind, c.public_paths[0] + '::' + m.name as path,
+                       m.docstring as docstring, m.file_path as file_path, m.line_number as line_number
+                UNION ALL
+                MATCH (c:Class {kind: 'trait'})-[:CONTAINS]->(m:Function)
+                WHERE size(coalesce(c.public_paths, [])) > 0
+                RETURN 'trait_method' as kind, c.public_paths[0] + '::' + m.name as path,
+                       m.docstring as docstring, m.file_path as file_path, m.line_number as line_number
+                UNION ALL
+                MATCH (c:Class)-[:HAS_FIELD]->(fd:Field)
+                WHERE size(coalesce(c.public_paths, [])) > 0 AND fd.visibility = 'pub'
+                RETURN 'field' as kind, c.public_paths[0] + '::' + fd.name as path,
+                       fd.docstring as docstring, fd.file_path as file_path, fd.line_number as line_number
+                UNION ALL
+                MATCH (v:Variant)-[:VARIANT_OF]->(c:Class)
+                WHERE size(coalesce(c.public_paths, [])) > 0
+                RETURN 'variant' as kind, c.public_paths[0] + '::' + v.name as path,
+                       v.docstring as docstring, v.file_path as file_path, v.line_number as line_number
+                UNION ALL
+                MATCH (mc:Macro {exported: true})
+                RETURN 'macro' as kind, mc.name + '!' as path,
+                       mc.docstring as docstring, mc.file_path as file_path, mc.line_number as line_number
+            """)
+            items = [dict(record) for record in result]
+            files = {record['path']: dict(record) for record in session.run("""
+                MATCH (f:File)-[:PART_OF]->(cr:Crate)
+                RETURN f.path as path, cr.name as crate, f.module_path as module
+            """)}
+
+        def coverage(total, documented):
+            return round(100.0 * documented / total, 1) if total else 100.0
+
+        modules = {}
+        for item in items:
+            location = files.get(item['file_path'])
+            if location is None or (crate_name and location['crate'] != crate_name):
+                continue
+            module = modules.setdefault((location['crate'], location['module']), {"total": 0, "documented": 0, "missing": []})
+            module["total"] += 1
+            if (item.pop('docstring') or '').strip():
+                module["documented"] += 1
+            else:
+                module["missing"].append(item)
+        if crate_name and not modules:
+            raise ValueError(f"No public items found for crate '{crate_name}'")
+
+        crates = {}
+        for (crate, _), module in modules.items():
+            totals = crates.setdefault(crate, {"crate": crate, "total": 0, "documented": 0})
+            totals["total"] += module["total"]
+            totals["documented"] += module["documented"]
+        for totals in crates.values():
+            totals["coverage"] = coverage(totals["total"], totals["documented"])
+        total, documented = sum(c["total"] for c in crates.values()), sum(c["documented"] for c in crates.values())
+
+        return {
+            "min_coverage": min_coverage,
+            "coverage": coverage(total, documented),
+            "total": total,
+            "documented": documented,
+            "passed": all(c["coverage"] >= min_coverage for c in crates.values()),
+            "crates": sorted(crates.values(), key=lambda c: c["crate"]),
+            "modules": [
+                {"crate": crate, "module": module, "total": m["total"], "documented": m["documented"],
+                 "coverage": coverage(m["total"], m["documented"]),
+                 "below_threshold": coverage(m["total"], m["documented"]) < min_coverage,
+                 "missing": sorted(m["missing"], key=lambda item: (item['file_path'], item['line_number']))}
+                for (crate, module), m in sorted(modules.items(), key=lambda entry: (entry[0][0], entry[0][1] or ''))
+            ],
+        }
+
+    def _path_matches(self, segments: List[str], crate: str, path: str) -> bool:
+        """Whether a full path ends with the given segments; a leading `crate` stands for `crate`'s name."""
+        if segments[0] == 'crate':
+            segments = [crate] + segments[1:]
+        parts = path.split('::')
+        return parts[-len(segments):] == segments
+
+    def get_canonical_paths(self, symbol: str) -> List[Dict]:
+        """
+        For a Rust function, type or method (`Person`, `structs_enums::Person`, `Person::new`), return
+        where it is defined, every public path it is reachable under (`pub mod` chains and `pub use`
+        re-exports, also from other indexed crates re-exporting it) and the import to recommend: the
+        shortest public path for other crates and its `crate::` form inside the defining crate.
+        """
+        segments = [part for part in symbol.strip().split('::') if part]
+        if not segments:
+            raise ValueError("Empty symbol")
+        name, qualifier = segments[-1], segments[:-1]
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (file:File)-[:CONTAINS]->(n {name: $name})
+                WHERE (n:Function OR n:Class) AND n.lang = 'rust' AND coalesce(n.is_dependency, false) = false
+                OPTIONAL MATCH (owner:Class)-[:HAS_IMPL]->(:Impl)-[:CONTAINS]->(n)
+                OPTIONAL MATCH (file)-[:PART_OF]->(cr:Crate)
+                RETURN labels(n)[0] as label, n.name as name, n.kind as kind, n.file_path as file_path,
+                       n.line_number as line_number, n.inline_module as inline_module, file.module_path as module_path,
+                       coalesce(n.public_paths, []) as public_paths, n.context as context, owner.name as owner,
+                       coalesce(owner.public_paths, []) as owner_paths, owner.inline_module as owner_inline_module,
+                       cr.name as crate
+            """, name=name)
+            candidates = [dict(record) for record in result]
+
+            results = []
+            for item in candidates:
+                if item['context'] and not item['owner']:
+                    continue  # nested functions and trait declarations have no path of their own
+                crate = (item['crate'] or 'crate').replace('-', '_')
+                module = (item['module_path'] or 'crate')[len('crate'):]
+                # Methods are reached through their type, so the paths of the type are followed.
+                item_name = item['owner'] or item['name']
+                inline = item['owner_inline_module'] if item['owner'] else item['inline_module']
+                definition = f"{crate}{module}{'::' + inline if inline else ''}::{item_name}"
+                item_paths = list(item['owner_paths'] if item['owner'] else item['public_paths'])
+
+                # Follow `pub use` re-exports by other crates until no new path appears.
+                reexports, frontier = [], list(item_paths)
+                while frontier:
+                    found = session.run("""
+                        MATCH (f:File)-[r:IMPORTS]->()
+                        WHERE r.is_reexport AND (r.full_import_name IN $paths OR (r.full_import_name ENDS WITH '::*'
+                              AND any(p IN $paths WHERE p STARTS WITH left(r.full_import_name, size(r.full_import_name) - 1)
+                                      AND NOT substring(p, size(r.full_import_name) - 1) CONTAINS '::')))
+                        OPTIONAL MATCH (f)-[:PART_OF]->(cr:Crate)
+                        RETURN DISTINCT f.path as file_path, f.module_path as module_path, r.line_number as line_number,
+                               r.full_import_name as imported, r.alias as alias, cr.name as crate
+                    """, paths=frontier)
+                    frontier = []
+                    for record in found:
+                        other = (record['crate'] or '').replace('-', '_')
+                        if not other or other == crate or not record['module_path']:
+                            continue
+                        exported_as = item_name if record['imported'].endswith('*') else record['alias'] or item_name
+                        path = f"{other}{record['module_path'][len('crate'):]}::{exported_as}"
+                        if path not in item_paths:
+                            item_paths.append(path)
+                            frontier.append(path)
+                            reexports.append({"path": path, "file_path": record['file_path'], "line_number": record['line_number']})
+
+                suffix = f"::{item['name']}" if item['owner'] else ""
+                definition += suffix
+                public_paths = sorted(path + suffix for path in item_paths)
+                if qualifier and not any(self._path_matches(segments, crate, path) for path in [definition] + public_paths):
+                    continue
+                shortest = lambda paths: min(paths, key=lambda p: (p.count('::'), p)) if paths else None
+                own = shortest([p for p in public_paths if p.split('::')[0] == crate])
+                recommended = own or shortest(public_paths)
+                results.append({
+                    "name": f"{item['owner']}::{item['name']}" if item['owner'] else item['name'],
+                    "kind": 'function' if item['label'] == 'Function' else item['kind'],
+                    "file_path": item['file_path'],
+                    "line_number": item['line_number'],
+                    "crate": crate,
+                    "definition_path": definition,
+                    "public_paths": public_paths,
+                    "reexported_by": reexports,
+                    "recommended_import": f"use {recommended};" if recommended else None,
+                    "internal_import": f"use crate{(own or definition)[len(crate):]};",
+                })
+        return results
+
+    def find_glob_imports(self, file_path: str = None) -> List[Dict]:
+        """
+        Report Rust glob imports (`use foo::*`) with the names each brings into scope, those the
+        importing module actually uses (for `pub use` globs, also through the re-export) and the
+        explicit import to replace it with, most wasteful globs first. Globs that could not be
+        resolved to a module of the crate (`std::io::prelude::*`, enum variants) are listed last.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (f:File)-[r:IMPORTS]->(m:Module)
+                WHERE r.glob_provided IS NOT NULL AND ($file_path IS NULL OR f.path = $file_path)
+                RETURN f.path as file_path, r.line_number as line_number, r.glob_module as module,
+                       r.full_import_name as import_path, coalesce(r.is_reexport, false) as is_reexport,
+                       r.glob_target as target_module, r.glob_provided as provided, r.glob_used as used
+            """, file_path=file_path)
+            globs = [dict(record) for record in result]
+
+        for glob in globs:
+            base = glob['import_path'][:-len('::*')]
+            keyword = 'pub use' if glob['is_reexport'] else 'use'
+            used = glob['used']
+            glob['unused'] = len(glob['provided']) - len(used) if glob['target_module'] else None
+            if not glob['target_module']:
+                glob['suggestion'] = None
+            elif not used:
+                # A re-export may be used by other crates, which the graph cannot see.
+                glob['suggestion'] = None if glob['is_reexport'] else "remove the import, no name from it is used"
+            elif len(used) == 1:
+                glob['suggestion'] = f"{keyword} {base}::{used[0]};"
+            else:
+                glob['suggestion'] = f"{keyword} {base}::{{{', '.join(used)}}};"
+        return sorted(globs, key=lambda g: (g['target_module'] is None, -(g['unused'] or 0), g['file_path'], g['line_number'] or 0))
+
+    def find_deadlock_candidates(self, max_cycle_length: int = 4) -> Dict[str, Any]:
+        """
+        Build the lock acquisition order graph (ACQUIRED_BEFORE edges between Locks) and report its
+        cycles as potential deadlocks, each hop with the function and call path that produce it.
+        """
+        max_cycle_length = max(1, min(int(max_cycle_length), 8))
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (a:Lock)-[r:ACQUIRED_BEFORE]->(b:Lock)
+                RETURN a.name as first, a.file_path as first_file, b.name as second, b.file_path as second_file,
+                       r.function as function_name, r.file_path as file_path, r.held_since as held_since,
+                       r.line_number as line_number, r.via as via
+                ORDER BY r.file_path, r.line_number
+            """)
+            orderings = [dict(record) for record in result]
+
+        edges = {}
+        for ordering in orderings:
+            key = ((ordering['first'], ordering['first_file']), (ordering['second'], ordering['second_file']))
+            edges.setdefault(key, []).append(ordering)
+        successors = {}
+        for first, second in edges:
+            successors.setdefault(first, set()).add(second)
+
+        cycles, seen = [], set()
+
+        def visit(path):
+            for following in sorted(successors.get(path[-1], ())):
+                if following == path[0]:
+                    start = path.index(min(path))
+                    canonical = tuple(path[start:] + path[:start])
+                    if canonical not in seen:
+                        seen.add(canonical)
+                        hops = list(zip(canonical, canonical[1:] + canonical[:1]))
+                        cycles.append({
+                            "locks": [name for name, _ in canonical],
+                            "orderings": [edges[hop] for hop in hops],
+                        })
+                elif following not in path and len(path) < max_cycle_length:
+                    visit(path + [following])
+
+        for lock in sorted(successors):
+            visit([lock])
+        return {"lock_orderings": orderings, "cycles": cycles}
+
+    def find_thread_crossing_types(self, only_non_send: bool = False) -> Dict[str, Any]:
+        """
+        Find the types moved into spawned threads, with their Send/Sync analysis, and the
+        thread-spawned closures that capture values which are not Send.
+        """
+        with self.driver.session() as session:
+            crossing = session.run("""
+                MATCH (cl:Closure)-[r:MOVES_INTO_THREAD]->(c:Class)
+                WHERE NOT $only_non_send OR r.is_send = false
+                RETURN c.name as type_name, c.file_path as file_path, c.line_number as line_number,
+                       c.is_send as is_send, c.is_sync as is_sync, c.thread_safety_reason as reason,
+                       collect({function_name: cl.context, file_path: cl.file_path, line_number: cl.line_number,
+                                spawned_by: cl.spawned_by, variable: r.variable, captured_as: r.type,
+                                capture_is_send: r.is_send, capture_reason: r.reason}) as crossings
+                ORDER BY c.is_send, c.name
+            """, only_non_send=only_non_send)
+            thread_crossing_types = [dict(record) for record in crossing]
+
+            non_send = session.run("""
+                MATCH (cl:Closure)
+                WHERE cl.spawned_by IS NOT NULL AND size(coalesce(cl.non_send_captures, [])) > 0
+                RETURN cl.name as closure, cl.context as function_name, cl.file_path as file_path,
+                       cl.line_number as line_number, cl.spawned_by as spawned_by,
+                       cl.non_send_captures as non_send_captures
+                ORDER BY cl.file_path, cl.line_number
+            """)
+            return {
+                "thread_crossing_types": thread_crossing_types,
+                "non_send_captures": [dict(record) for record in non_send],
+            }
+
+    def find_newtypes(self, inner_type: str = None) -> List[Dict]:
+        """
+        Find newtypes (`struct Meters(f64)`) and generic wrappers (`struct Wrapper<T> { value: T }`).
+        With `inner_type`, only the newtypes wrapping that type are returned, following chains of
+        newtypes (`struct Manager(Staff)` over `struct Staff(Person)`) so usages can be traced through them.
+        """
+        with self.driver.session() as session:
+            if inner_type:
+                result = session.run("""
+                    MATCH path = (c:Class)-[:WRAPS*1..5]->(inner:Class {name: $inner_type})
+                    RETURN DISTINCT c.name as name, c.file_path as file_path, c.line_number as line_number,
+                           c.newtype_kind as kind, c.wraps_type as inner_type,
+                           [n IN nodes(path) | n.name] as wrap_chain
+                    ORDER BY size(wrap_chain), c.name
+                """, inner_type=inner_type)
+            else:
+                result = session.run("""
+                    MATCH (c:Class)
+                    WHERE c.newtype_kind IS NOT NULL
+                    OPTIONAL MATCH (c)-[:WRAPS]->(inner:Class)
+                    RETURN c.name as name, c.file_path as file_path, c.line_number as line_number,
+                           c.newtype_kind as kind, c.wraps_type as inner_type,
+                           inner.file_path as inner_file_path
+                    ORDER BY c.file_path, c.line_number
+                """)
+            return [dict(record) for record in result]
+
+    def find_builders(self, type_name: str = None) -> List[Dict]:
+        """
+        Find builder types (chained `fn foo(mut self, ..) -> Self` setters ending in `build()`)
+        with the type they build and their construction entry points. With `type_name`, only
+        builders of (or named) that type are returned.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (b:Class)
+                WHERE b.is_builder = true
+                OPTIONAL MATCH (b)-[:BUILDS]->(t:Class)
+                WITH b, t
+                WHERE $type_name IS NULL OR b.name = $type_name OR t.name = $type_name
+                RETURN b.name as builder, b.file_path as file_path, b.line_number as line_number,
+                       t.name as target, t.file_path as target_file_path,
+                       b.build_method as build_method, b.builder_setters as setters,
+                       b.builder_entry_points as entry_points
+                ORDER BY b.file_path, b.line_number
+            """, type_name=type_name)
+            return [dict(record) for record in result]
+
+    def find_interior_mutability(self, kind: str = None) -> List[Dict]:
+        """
+        List the fields and statics using interior mutability (`Cell`, `RefCell`, `UnsafeCell`,
+        `OnceCell`, atomics) grouped by cell kind, with the functions that borrow or mutate them.
+        Cells with the most mutable borrowers come first within each group.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (t)
+                WHERE (t:Field OR t:Variable) AND t.interior_mutability IS NOT NULL
+                  AND ($kind IS NULL OR t.interior_mutability = $kind)
+                OPTIONAL MATCH (fn:Function)-[r:BORROWS]->(t)
+                WITH t, fn, r ORDER BY r.line_number
+                RETURN t.interior_mutability as kind, coalesce(t.owner + '.', '') + t.name as name,
+                       t.type as type, t.file_path as file_path, t.line_number as line_number,
+                       [a IN collect(CASE WHEN r IS NULL THEN NULL ELSE
+                           {function_name: fn.name, line_number: r.line_number, method: r.method,
+                            mode: r.mode, mutable: r.mutable} END) WHERE a IS NOT NULL] as accesses
+            """, kind=kind)
+
+            groups = {}
+            for record in result:
+                cell = dict(record)
+                cell['mutable_borrowers'] = sorted({a['function_name'] for a in cell['accesses'] if a['mutable']})
+                groups.setdefault(cell.pop('kind'), []).append(cell)
+
+        return [
+            {
+                "kind": cell_kind,
+                "cells": sorted(cells, key=lambda c: (-len(c['mutable_borrowers']), c['file_path'], c['line_number'])),
+                "mutable_borrow_sites": sum(1 for c in cells for a in c['accesses'] if a['mutable']),
+            }
+            for cell_kind, cells in sorted(groups.items())
+        ]
+
+    def find_clippy_findings(self, lint: str = None, min_complexity: int = None, file_path: str = None,
+                             limit: int = 50) -> List[Dict]:
+        """
+        List the functions with diagnostics from the last `cargo clippy` run (see
+        `GraphBuilder.annotate_clippy`), optionally only those of a `lint` (`needless_return` or
+        `clippy::needless_return`) or with a cyclomatic complexity of at least `min_complexity`.
+        Most complex functions first.
+        """
+        if lint and '::' not in lint:
+            lint = f"clippy::{lint}"
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (fn:Function)-[:HAS_DIAGNOSTIC]->(d:Diagnostic)
+                WHERE ($lint IS NULL OR d.lint = $lint OR d.lint = substring($lint, 8))
+                  AND ($min_complexity IS NULL OR fn.cyclomatic_complexity >= $min_complexity)
+                  AND ($file_path IS NULL OR fn.file_path = $file_path)
+                WITH fn, d ORDER BY d.line_number
+                RETURN fn.name as name, fn.class_context as owner, fn.file_path as file_path, fn.line_number as line_number,
+                       fn.cyclomatic_complexity as complexity,
+                       collect({lint: d.lint, level: d.level, message: d.message, line_number: d.line_number}) as diagnostics
+                ORDER BY complexity DESC, size(diagnostics) DESC, file_path, line_number
+                LIMIT $limit
+            """, lint=lint, min_complexity=min_complexity, file_path=file_path, limit=limit)
+            return [dict(record) for record in result]
+
     def find_most_complex_functions(self, limit: int = 10) -> List[Dict]:
         """Find the most complex functions based on cyclomatic complexity."""
         with self.driver.session() as session:
@@ -755,6 +3217,568 @@ class CodeFinder:
             result = session.run(query, limit=limit)
             return [dict(record) for record in result]
 
+    def get_code_health_report(self, max_lines: int = 60, max_params: int = 5, max_nesting: int = 4,
+                               max_complexity: int = 10, file_path: str = None, annotate: bool = True) -> Dict[str, Any]:
+        """
+        List the functions exceeding any of the given limits on line count, parameter count (`self`
+        excluded), control flow nesting depth and cyclomatic complexity. Unless `annotate` is off, the
+        violated metrics are also stored on the Function nodes (`health_violations`) for later queries.
+        """
+        thresholds = {"lines": max_lines, "parameters": max_params, "nesting_depth": max_nesting, "complexity": max_complexity}
+        with self.driver.session() as session:
+            functions = [dict(record) for record in session.run("""
+                MATCH (f:Function)
+                WHERE coalesce(f.is_dependency, false) = false AND ($file_path IS NULL OR f.file_path = $file_path)
+                RETURN elementId(f) as id, f.name as name, f.class_context as owner, f.file_path as file_path,
+                       f.line_number as line_number, f.end_line as end_line, coalesce(f.args, []) as args,
+                       f.cyclomatic_complexity as complexity, f.nesting_depth as nesting_depth
+            """, file_path=file_path)]
+
+            report = []
+            for func in functions:
+                metrics = {
+                    "lines": func['end_line'] - func['line_number'] + 1 if func['end_line'] else None,
+                    "parameters": len([arg for arg in func['args'] if arg not in ('self', 'cls')]),
+                    "nesting_depth": func['nesting_depth'],
+                    "complexity": func['complexity'],
+                }
+                violations = [metric for metric, value in metrics.items()
+                              if value is not None and value > thresholds[metric]]
+                func['violations'] = violations
+                if violations:
+                    report.append({
+                        "name": func['name'] if not func['owner'] else f"{func['owner']}::{func['name']}",
+                        "file_path": func['file_path'],
+                        "line_number": func['line_number'],
+                        "metrics": metrics,
+                        "violations": violations,
+                    })
+
+            if annotate:
+                session.run("""
+                    UNWIND $functions as row
+                    MATCH (f:Function) WHERE elementId(f) = row.id
+                    SET f.health_violations = row.violations
+                """, functions=[{"id": func['id'], "violations": func['violations']} for func in functions])
+
+        report.sort(key=lambda f: (-len(f['violations']), f['file_path'], f['line_number']))
+        return {
+            "thresholds": thresholds,
+            "functions": report,
+            "summary": {metric: sum(metric in f['violations'] for f in report) for metric in thresholds},
+        }
+
+    def find_central_functions(self, metric: str = "pagerank", limit: int = 20, file_path: str = None) -> List[Dict]:
+        """
+        Rank functions by their centrality in the call graph, computed at indexing time: `pagerank`
+        (called by many important functions: core utilities and abstractions) or `betweenness` (on
+        many shortest call chains: glue code between subsystems). A good first pick of context.
+        """
+        if metric not in ("pagerank", "betweenness"):
+            raise ValueError(f"Unknown metric '{metric}'; expected 'pagerank' or 'betweenness'")
+        with self.driver.session() as session:
+            result = session.run(f"""
+                MATCH (f:Function)
+                WHERE f.{metric} IS NOT NULL AND coalesce(f.is_dependency, false) = false
+                  AND ($file_path IS NULL OR f.file_path = $file_path)
+                WITH f ORDER BY f.{metric} DESC LIMIT $limit
+                OPTIONAL MATCH (caller:Function)-[:CALLS|MAY_CALL]->(f)
+                WITH f, count(DISTINCT caller) as callers
+                OPTIONAL MATCH (f)-[:CALLS|MAY_CALL]->(callee:Function)
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       f.pagerank as pagerank, f.betweenness as betweenness, callers, count(DISTINCT callee) as callees
+                ORDER BY {metric} DESC, file_path, line_number
+            """, limit=limit, file_path=file_path)
+            return [dict(record) for record in result]
+
+    def suggest_module_boundaries(self, resolution: float = 1.0, min_weight: int = 2, limit: int = 50) -> Dict[str, Any]:
+        """
+        Cluster functions and types by how densely they call and use each other (Louvain, with
+        methods kept with their type) and compare the clusters with the module structure: Rust
+        module paths, or files for other languages. Returns the modularity of both partitions, the
+        communities spanning several modules, and items linked more to another module than to
+        their own (by at least `min_weight` calls and uses) that would sit better there.
+        """
+        with self.driver.session() as session:
+            items = [dict(record) for record in session.run("""
+                MATCH (file:File)-[:CONTAINS]->(n)
+                WHERE (n:Function OR n:Class) AND coalesce(n.is_dependency, false) = false
+                WITH file, n, head([(o:Class)-[:HAS_IMPL]->(:Impl)-[:CONTAINS]->(n) | o]
+                                   + [(o:Class)-[:CONTAINS]->(n) WHERE n:Function | o]) as owner
+                RETURN elementId(n) as id, elementId(coalesce(owner, n)) as unit, n.name as name,
+                       labels(n)[0] as label, n.file_path as file_path, n.line_number as line_number,
+                       file.module_path as module_path, coalesce(owner.inline_module, n.inline_module) as inline_module
+            """)]
+            fields = {record['field']: record['owner'] for record in session.run("""
+                MATCH (c:Class)-[:HAS_FIELD]->(fd:Field)
+                RETURN elementId(fd) as field, elementId(c) as owner
+            """)}
+            links = [dict(record) for record in session.run("""
+                MATCH (a)-[r:CALLS|MAY_CALL|REFERENCES_TYPE|INSTANTIATES|READS_FIELD|WRITES_FIELD]->(b)
+                WHERE (a:Function OR a:Class) AND (b:Function OR b:Class OR b:Field)
+                RETURN elementId(a) as source, elementId(b) as target, count(r) as weight
+            """)]
+
+        unit_of = {item['id']: item['unit'] for item in items}
+        unit_of.update({field: owner for field, owner in fields.items()})
+        units, module_of = {}, {}
+        for item in items:
+            if item['id'] != item['unit']:
+                continue
+            module = item['module_path'] or item['file_path']
+            if item['module_path'] and item['inline_module']:
+                module += f"::{item['inline_module']}"
+            module_of[item['id']] = module
+            units[item['id']] = {"name": item['name'], "kind": 'function' if item['label'] == 'Function' else 'type',
+                                 "file_path": item['file_path'], "line_number": item['line_number']}
+
+        edges = {}
+        for link in links:
+            a, b = unit_of.get(link['source']), unit_of.get(link['target'])
+            if a in units and b in units and a != b:
+                edges[(a, b)] = edges.get((a, b), 0) + link['weight']
+        nodes = sorted(units, key=lambda unit: (units[unit]['file_path'], units[unit]['line_number'] or 0))
+        partition = louvain(nodes, edges, resolution=resolution)
+
+        communities = {}
+        for unit, group in partition.items():
+            community = communities.setdefault(group, {"community": group, "size": 0, "modules": {}})
+            community["size"] += 1
+            community["modules"][module_of[unit]] = community["modules"].get(module_of[unit], 0) + 1
+        spanning = [c for c in sorted(communities.values(), key=lambda c: c["community"]) if len(c["modules"]) > 1]
+
+        suggestions = []
+        for suggestion in suggest_moves(partition, module_of, edges, min_weight=min_weight)[:limit]:
+            suggestions.append({**units[suggestion.pop("item")], **suggestion})
+        return {
+            "modularity": {"modules": modularity(module_of, edges), "communities": modularity(partition, edges)},
+            "community_count": len(communities),
+            "communities_spanning_modules": spanning[:limit],
+            "suggestions": suggestions,
+        }
+
+    def find_god_objects(self, max_methods: int = 20, max_trait_impls: int = 10, max_fields: int = 15,
+                         limit: int = 50) -> List[Dict]:
+        """
+        Report types with more inherent methods, trait impls or fields than the given limits, with
+        their fan-in (functions and types outside the type calling its methods or mentioning it) and
+        fan-out (functions and types its methods and fields call or mention), largest first.
+        """
+        thresholds = {"methods": max_methods, "trait_impls": max_trait_impls, "fields": max_fields}
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (c:Class)
+                WHERE coalesce(c.is_dependency, false) = false AND coalesce(c.kind, 'class') <> 'trait'
+                WITH c, [(c)-[:HAS_IMPL]->(i:Impl)-[:CONTAINS]->(m:Function) WHERE i.trait IS NULL | m]
+                        + [(c)-[:CONTAINS]->(m:Function) | m] as methods,
+                     [(c)-[:HAS_IMPL]->(i:Impl) WHERE i.trait IS NOT NULL | i.trait] as trait_impls,
+                     [(c)-[:HAS_FIELD]->(f:Field) | f] as fields
+                WHERE size(methods) > $max_methods OR size(trait_impls) > $max_trait_impls OR size(fields) > $max_fields
+                WITH c, methods, trait_impls, fields, [(c)-[:HAS_IMPL]->(:Impl)-[:CONTAINS]->(m:Function) | m] + methods as members
+                UNWIND [c] + members + fields as member
+                OPTIONAL MATCH (user)-[:CALLS|REFERENCES_TYPE|READS_FIELD|WRITES_FIELD]->(member)
+                WHERE NOT user IN members AND user <> c
+                WITH c, methods, trait_impls, fields, members, collect(DISTINCT user) as users
+                UNWIND [c] + members as member
+                OPTIONAL MATCH (member)-[:CALLS|REFERENCES_TYPE]->(dependency)
+                WHERE NOT dependency IN members AND dependency <> c
+                RETURN c.name as name, c.kind as kind, c.file_path as file_path, c.line_number as line_number,
+                       size(methods) as methods, size(trait_impls) as trait_impls, size(fields) as fields,
+                       size(users) as fan_in, count(DISTINCT dependency) as fan_out
+            """, max_methods=max_methods, max_trait_impls=max_trait_impls, max_fields=max_fields)
+            types = [dict(record) for record in result]
+
+        for item in types:
+            item["violations"] = [metric for metric, limit_ in thresholds.items() if item[metric] > limit_]
+        types.sort(key=lambda t: (-len(t['violations']), -(t['methods'] + t['trait_impls'] + t['fields']), t['file_path']))
+        return types[:limit]
+
+    def find_duplicates(self, min_size: int = 20, include_same_file: bool = False) -> List[Dict]:
+        """
+        Cluster functions whose normalized structural hash (`body_hash`) is equal, i.e. copies that
+        differ only in identifiers and literals. Functions under `min_size` tokens are ignored, and
+        clusters confined to a single file are skipped unless `include_same_file` is set.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (f:Function)
+                WHERE f.body_hash IS NOT NULL AND f.body_size >= $min_size AND coalesce(f.is_dependency, false) = false
+                WITH f.body_hash as hash, f.body_size as size,
+                     collect({name: f.name, owner: f.class_context, file_path: f.file_path,
+                              line_number: f.line_number, end_line: f.end_line}) as functions
+                WHERE size(functions) > 1
+                RETURN hash, size, functions
+                ORDER BY size DESC, hash
+            """, min_size=min_size)
+
+            clusters = []
+            for record in result:
+                functions = sorted(record['functions'], key=lambda f: (f['file_path'], f['line_number']))
+                files = {f['file_path'] for f in functions}
+                if len(files) < 2 and not include_same_file:
+                    continue
+                clusters.append({"hash": record['hash'], "size": record['size'], "file_count": len(files), "functions": functions})
+            return clusters
+
+    def find_similar_functions(self, function_name: str, file_path: str = None, limit: int = 10,
+                               min_score: float = 0.5) -> List[Dict]:
+        """
+        Find functions elsewhere in the codebase that look like the given one, to check whether a
+        helper already exists. Each candidate gets a `structural` score (1 for the same normalized
+        body, otherwise the MinHash estimate of shared token shingles), a `semantic` score (cosine
+        similarity of the embeddings) and their mean as `score`; candidates under `min_score` are dropped.
+        """
+        embedder = get_embedder()
+        with self.driver.session() as session:
+            targets = [dict(record) for record in session.run("""
+                MATCH (f:Function {name: $function_name})
+                WHERE $file_path IS NULL OR f.file_path = $file_path
+                RETURN elementId(f) as id, f.name as name, f.class_context as owner, f.file_path as file_path,
+                       f.line_number as line_number, f.body_hash as body_hash, f.shape_minhash as shape,
+                       CASE WHEN f.embedding_model = $model THEN f.embedding END as embedding
+                ORDER BY file_path, line_number
+            """, function_name=function_name, file_path=file_path, model=embedder.name)]
+            if not targets:
+                raise ValueError(f"Function '{function_name}' not found")
+            candidates = [dict(record) for record in session.run("""
+                MATCH (f:Function)
+                WHERE coalesce(f.is_dependency, false) = false AND NOT elementId(f) IN $ids
+                  AND (f.shape_minhash IS NOT NULL OR f.embedding_model = $model)
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       f.end_line as end_line, f.body_hash as body_hash, f.shape_minhash as shape,
+                       CASE WHEN f.embedding_model = $model THEN f.embedding END as embedding
+            """, ids=[t['id'] for t in targets], model=embedder.name)]
+
+        results = []
+        for target in targets:
+            matches = []
+            for candidate in candidates:
+                if target['body_hash'] and target['body_hash'] == candidate['body_hash']:
+                    structural = 1.0
+                else:
+                    structural = shape_similarity(target['shape'], candidate['shape'])
+                semantic = None
+                if target['embedding'] and candidate['embedding']:
+                    semantic = sum(a * b for a, b in zip(target['embedding'], candidate['embedding']))
+                scores = [score for score in (structural, semantic) if score is not None]
+                if not scores or sum(scores) / len(scores) < min_score:
+                    continue
+                matches.append({
+                    "name": candidate['name'],
+                    "owner": candidate['owner'],
+                    "file_path": candidate['file_path'],
+                    "line_number": candidate['line_number'],
+                    "end_line": candidate['end_line'],
+                    "score": sum(scores) / len(scores),
+                    "structural": structural,
+                    "semantic": semantic,
+                    "same_body": structural == 1.0 and target['body_hash'] == candidate['body_hash'],
+                })
+            matches.sort(key=lambda m: (-m['score'], m['file_path'], m['line_number']))
+            results.append({
+                "function": {key: target[key] for key in ('name', 'owner', 'file_path', 'line_number')},
+                "similar": matches[:limit],
+            })
+        return results
+
+    def find_hotspots(self, since_days: int = 90, until_days: int = 0, level: str = 'function', limit: int = 20) -> List[Dict]:
+        """
+        Rank functions (or files, with `level='file'`) by maintenance risk: git churn between
+        `since_days` and `until_days` days ago multiplied by cyclomatic complexity (summed over the
+        file's functions for files). A function's churn is the number of commits in the window that
+        last touched its current lines, according to git blame.
+        """
+        if level not in ('function', 'file'):
+            raise ValueError(f"Unknown hotspot level '{level}', expected 'function' or 'file'")
+        with self.driver.session() as session:
+            repositories = [record['path'] for record in session.run(
+                "MATCH (r:Repository) WHERE coalesce(r.is_dependency, false) = false RETURN r.path as path")]
+            functions = [dict(record) for record in session.run("""
+                MATCH (f:Function)
+                WHERE coalesce(f.is_dependency, false) = false
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       coalesce(f.end_line, f.line_number) as end_line, coalesce(f.cyclomatic_complexity, 1) as complexity
+            """)]
+
+        churn = {}
+        for repo_path in repositories:
+            try:
+                churn.update(file_churn(Path(repo_path), since_days, until_days))
+            except ValueError:
+                continue  # not a git repository
+
+        functions_by_file = {}
+        for func in functions:
+            if func['file_path'] in churn:
+                functions_by_file.setdefault(func['file_path'], []).append(func)
+
+        hotspots = []
+        if level == 'file':
+            for file_path, funcs in functions_by_file.items():
+                complexity = sum(f['complexity'] for f in funcs)
+                hotspots.append({"file_path": file_path, **churn[file_path], "complexity": complexity,
+                                 "function_count": len(funcs), "score": churn[file_path]['commits'] * complexity})
+        else:
+            now = time.time()
+            since, until = now - since_days * 86400, now - until_days * 86400
+            repositories_by_file = {}
+            for file_path, funcs in functions_by_file.items():
+                repository = repositories_by_file.setdefault(str(Path(file_path).parent), open_repository(Path(file_path).parent))
+                hunks = [h for h in blame_file(repository, Path(file_path)) if h['time'] and since <= h['time'] <= until] if repository else []
+                for func in funcs:
+                    touching = [h for h in hunks if h['start'] <= func['end_line'] and h['end'] >= func['line_number']]
+                    commits = len({h['commit'] for h in touching})
+                    if not commits:
+                        continue
+                    lines = sum(min(func['end_line'], h['end']) - max(func['line_number'], h['start']) + 1 for h in touching)
+                    hotspots.append({
+                        "name": f"{func['owner']}::{func['name']}" if func['owner'] else func['name'],
+                        "file_path": file_path, "line_number": func['line_number'],
+                        "commits": commits, "lines_changed": lines, "complexity": func['complexity'],
+                        "score": commits * func['complexity'],
+                    })
+
+        hotspots.sort(key=lambda h: (-h['score'], -h['lines_changed'], h['file_path'], h.get('line_number', 0)))
+        return hotspots[:limit]
+
+    def find_recently_changed_functions(self, days: int = 30, calls: str = None, author: str = None) -> List[Dict]:
+        """
+        List the functions whose lines were last committed within `days` (from git blame metadata
+        stored at indexing time), newest first. `calls` keeps only the functions calling the function
+        of that name, and `author` those whose owners include that author.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (f:Function)
+                WHERE f.last_modified IS NOT NULL AND f.last_modified >= timestamp() / 1000 - $days * 86400
+                  AND ($author IS NULL OR $author IN coalesce(f.owners, []))
+                  AND ($calls IS NULL OR EXISTS { MATCH (f)-[:CALLS]->(:Function {name: $calls}) })
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       f.last_commit as last_commit, f.last_author as last_author, f.last_modified as last_modified,
+                       f.owners as owners
+                ORDER BY f.last_modified DESC, f.file_path, f.line_number
+            """, days=days, calls=calls, author=author)
+            return [dict(record) for record in result]
+
+    def diff_indexed_trees(self, base_root: str, head_root: str) -> Dict[str, Any]:
+        """
+        Structurally diff two revision snapshots of a repository indexed with
+        `GraphBuilder.index_revision` (read under their `Snapshot` labels). Functions are matched by
+        file (relative to each root), owner, implemented trait and name; a function changed when its
+        source did. Call edges and the public API paths of both trees are compared as sets.
+        """
+        def snapshot(root):
+            root = str(Path(root).resolve()) + os.sep
+            with self.driver.session() as session:
+                functions = session.run("""
+                    MATCH (f:SnapshotFunction) WHERE f.file_path STARTS WITH $root
+                    RETURN substring(f.file_path, size($root)) as file, f.class_context as owner, f.impl_trait as impl_trait,
+                           f.name as name, f.signature as signature, coalesce(f.source, f.source_code, f.source_compressed) as source,
+                           f.line_number as line_number
+                """, root=root)
+                functions = {(r['file'], r['owner'], r['impl_trait'], r['name']): expand_tree(dict(r)) for r in functions}
+                calls = session.run("""
+                    MATCH (caller:SnapshotFunction)-[:CALLS]->(callee:SnapshotFunction) WHERE caller.file_path STARTS WITH $root
+                    RETURN DISTINCT substring(caller.file_path, size($root)) as caller_file, caller.class_context as caller_owner,
+                           caller.name as caller, callee.class_context as callee_owner, callee.name as callee,
+                           CASE WHEN callee.file_path STARTS WITH $root THEN substring(callee.file_path, size($root))
+                                ELSE callee.file_path END as callee_file
+                """, root=root)
+                calls = {tuple(r.values()) for r in calls}
+                api = session.run("""
+                    MATCH (n) WHERE (n:SnapshotFunction OR n:SnapshotClass) AND n.file_path STARTS WITH $root
+                    UNWIND coalesce(n.public_paths, []) as path
+                    RETURN path, CASE WHEN n:SnapshotFunction THEN 'function' ELSE n.kind END as kind, n.signature as signature
+                """, root=root)
+                api = {r['path']: dict(r) for r in api}
+            return functions, calls, api
+
+        base_functions, base_calls, base_api = snapshot(base_root)
+        head_functions, head_calls, head_api = snapshot(head_root)
+
+        def describe(key, func):
+            file, owner, _, name = key
+            return {"name": f"{owner}::{name}" if owner else name, "file": file,
+                    "line_number": func['line_number'], "signature": func['signature']}
+
+        changed_functions = []
+        for key in sorted(base_functions.keys() & head_functions.keys(), key=str):
+            old, new = base_functions[key], head_functions[key]
+            if old['source'] != new['source']:
+                entry = describe(key, new)
+                entry["signature_changed"] = old['signature'] != new['signature']
+                if entry["signature_changed"]:
+                    entry["old_signature"] = old['signature']
+                changed_functions.append(entry)
+
+        def describe_call(edge):
+            caller_file, caller_owner, caller, callee_owner, callee, callee_file = edge
+            return {"caller": f"{caller_owner}::{caller}" if caller_owner else caller, "caller_file": caller_file,
+                    "callee": f"{callee_owner}::{callee}" if callee_owner else callee, "callee_file": callee_file}
+
+        diff = {
+            "functions": {
+                "added": [describe(k, head_functions[k]) for k in sorted(head_functions.keys() - base_functions.keys(), key=str)],
+                "removed": [describe(k, base_functions[k]) for k in sorted(base_functions.keys() - head_functions.keys(), key=str)],
+                "changed": changed_functions,
+            },
+            "calls": {
+                "added": [describe_call(e) for e in sorted(head_calls - base_calls, key=str)],
+                "removed": [describe_call(e) for e in sorted(base_calls - head_calls, key=str)],
+            },
+            "public_api": {
+                "added": [head_api[p] for p in sorted(head_api.keys() - base_api.keys())],
+                "removed": [base_api[p] for p in sorted(base_api.keys() - head_api.keys())],
+                "changed": [{"path": p, "kind": head_api[p]['kind'], "old_signature": base_api[p]['signature'],
+                             "signature": head_api[p]['signature']}
+                            for p in sorted(base_api.keys() & head_api.keys())
+                            if base_api[p]['signature'] != head_api[p]['signature']],
+            },
+        }
+        diff["summary"] = {f"{section}_{change}": len(items) for section, changes in diff.items()
+                           for change, items in changes.items()}
+        return diff
+
+    def _public_api_snapshot(self, root: str) -> Dict[str, Dict]:
+        """The public API of the Rust crates under `root`, keyed by public path, in the shape `semver_check` compares."""
+        root = str(Path(root).resolve()) + os.sep
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (n) WHERE (n:SnapshotFunction OR n:SnapshotClass) AND n.file_path STARTS WITH $root AND size(coalesce(n.public_paths, [])) > 0
+                OPTIONAL MATCH (n)-[:HAS_IMPL]->(i:SnapshotImpl)
+                OPTIONAL MATCH (i)-[:CONTAINS]->(m:SnapshotFunction) WHERE i.trait IS NULL AND m.visibility = 'pub'
+                WITH n, collect(DISTINCT i.trait) as trait_impls, collect(DISTINCT [m.name, m.signature]) as methods
+                OPTIONAL MATCH (v:SnapshotVariant)-[:VARIANT_OF]->(n)
+                WITH n, trait_impls, methods, collect(DISTINCT v.name) as variants
+                OPTIONAL MATCH (n)-[:HAS_FIELD]->(fd:SnapshotField)
+                RETURN n.public_paths as paths, CASE WHEN n:SnapshotFunction THEN 'function' ELSE n.kind END as kind,
+                       n.signature as signature, trait_impls, methods, variants,
+                       n.required_methods as required_methods, n.provided_methods as provided_methods,
+                       any(d IN coalesce(n.decorators, []) WHERE d CONTAINS 'non_exhaustive') as non_exhaustive,
+                       collect(DISTINCT CASE WHEN fd.visibility = 'pub' THEN [fd.name, fd.type] END) as fields,
+                       [fd IN collect(DISTINCT fd) | coalesce(fd.visibility, '')] as field_visibilities
+            """, root=root)
+            records = [dict(record) for record in result]
+
+        api = {}
+        for record in records:
+            item = {
+                "kind": record['kind'], "signature": record['signature'],
+                "methods": {name: signature for name, signature in record['methods'] if name},
+                "trait_impls": sorted(record['trait_impls']), "variants": sorted(record['variants']),
+                "required_methods": record['required_methods'] or [], "provided_methods": record['provided_methods'] or [],
+                "fields": {name: type_text for name, type_text in record['fields']},
+                "has_private_fields": any(v != 'pub' for v in record['field_visibilities']),
+                "non_exhaustive": record['non_exhaustive'],
+            }
+            for path in record['paths']:
+                api[path] = item
+        return api
+
+    def classify_semver_changes(self, base_root: str, head_root: str) -> Dict[str, Any]:
+        """
+        Classify the changes between two indexed copies of a crate (see `diff_indexed_trees`) as
+        major, minor or patch per the semver rules in `semver_check`: removed items, changed
+        signatures and new required trait methods are breaking, additions are minor and changed
+        function bodies are patches. Paths are compared without their leading crate name.
+        """
+        def by_path(api):
+            return {path.split('::', 1)[-1] if '::' in path else path: item for path, item in api.items()}
+
+        diff = self.diff_indexed_trees(base_root, head_root)
+        return classify_changes(by_path(self._public_api_snapshot(base_root)), by_path(self._public_api_snapshot(head_root)),
+                                diff["functions"]["changed"])
+
+    def search_by_signature(self, pattern: str, limit: int = 20) -> List[Dict]:
+        """
+        Hoogle-style search of Rust functions by type signature (see `signature_search`), e.g.
+        `fn(&[i32]) -> Option<i32>`, `Fn(i32) -> i32` or just a return type. Results are ordered by
+        match cost (0 for an exact match); `match` tells whether the function's own signature
+        matched or the callable it returns.
+        """
+        parsed = parse_signature(pattern)
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (f:Function)
+                WHERE f.param_types IS NOT NULL AND coalesce(f.is_dependency, false) = false
+                RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                       f.signature as signature, f.param_types as param_types, f.return_type as return_type,
+                       coalesce(f.generic_params, []) as generic_params
+            """)
+            functions = [dict(record) for record in result]
+
+        matches = []
+        for func in functions:
+            matched = match_function(parsed, func['param_types'], func['return_type'], func['generic_params'])
+            if matched is None:
+                continue
+            cost, how = matched
+            matches.append({
+                "name": f"{func['owner']}::{func['name']}" if func['owner'] else func['name'],
+                "signature": func['signature'], "match": how, "cost": cost,
+                "file_path": func['file_path'], "line_number": func['line_number'],
+            })
+        matches.sort(key=lambda m: (m['cost'], m['match'] != 'signature', m['file_path'], m['line_number']))
+        return matches[:limit]
+
+    def semantic_search(self, description: str, limit: int = 10, file_path: str = None) -> List[Dict]:
+        """
+        Return the functions whose embeddings (see `embeddings`) are closest to a free-text
+        description, best first, with their cosine similarity. Uses the `function_embeddings` vector
+        index when the database has one, otherwise scans the stored embeddings.
+        """
+        embedder = get_embedder()
+        vector = embedder.embed_query(description)
+        returned = """
+            RETURN f.name as name, f.class_context as owner, f.file_path as file_path, f.line_number as line_number,
+                   f.docstring as docstring, score
+        """
+        with self.driver.session() as session:
+            try:
+                result = session.run("""
+                    CALL db.index.vector.queryNodes('function_embeddings', $candidates, $vector) YIELD node as f, score
+                    WHERE f.embedding_model = $model AND ($file_path IS NULL OR f.file_path ENDS WITH $file_path)
+                """ + returned + " LIMIT $limit",
+                    candidates=limit * 5, vector=vector, model=embedder.name, file_path=file_path, limit=limit)
+                return [dict(record) for record in result]
+            except Exception as e:
+                logger.debug(f"Vector index query failed, scanning embeddings instead: {e}")
+
+            result = session.run("""
+                MATCH (f:Function)
+                WHERE f.embedding_model = $model AND ($file_path IS NULL OR f.file_path ENDS WITH $file_path)
+                RETURN elementId(f) as id, f.embedding as embedding
+            """, model=embedder.name, file_path=file_path)
+            scores = sorted(((sum(a * b for a, b in zip(vector, record['embedding'])), record['id']) for record in result),
+                            reverse=True)[:limit]
+            result = session.run("""
+                UNWIND $scores as row
+                MATCH (f:Function) WHERE elementId(f) = row.id
+                WITH f, row.score as score
+            """ + returned + " ORDER BY score DESC", scores=[{"id": id_, "score": score} for score, id_ in scores])
+            return [dict(record) for record in result]
+
+    def answer_question(self, question: str, limit: int = 100, timeout: float = 10.0) -> Dict[str, Any]:
+        """
+        Translate a natural-language question into a parameterized Cypher query (see `nl_query`) and
+        run it in a read-only transaction, with at most `limit` rows (capped at 1000) and a timeout
+        in seconds. The generated query and its parameters are returned with the results.
+        """
+        limit = max(1, min(int(limit), 1000))
+        cypher, parameters, template = translate_question(question, limit)
+        with self.driver.session(default_access_mode=READ_ACCESS) as session:
+            records = session.execute_read(
+                lambda tx: [expand_tree(record.data()) for record in tx.run(Query(cypher, timeout=timeout), parameters)]
+            )
+        return {
+            "question": question,
+            "template": template,
+            "cypher": cypher,
+            "parameters": parameters,
+            "record_count": len(records),
+            "results": records,
+        }
+
     def list_indexed_repositories(self) -> List[Dict]:
         """List all indexed repositories."""
         with self.driver.session() as session:
@@ -764,3 +3788,29 @@ class CodeFinder:
                 ORDER BY r.name
             """)
             return [dict(record) for record in result]
+
+    def get_crate_info(self, crate_name: str = None) -> List[Dict]:
+        """
+        Get the indexed Rust crates with their manifest metadata and estimated minimum supported
+        Rust version. Flags crates whose declared `rust-version` is lower than the estimate.
+        """
+        with self.driver.session() as session:
+            result = session.run("""
+                MATCH (cr:Crate)
+                WHERE $crate_name IS NULL OR cr.name = $crate_name
+                OPTIONAL MATCH (f:File)-[:PART_OF]->(cr)
+                RETURN cr.name as name, cr.path as path, cr.edition as edition,
+                       cr.declared_rust_version as declared_rust_version, cr.estimated_msrv as estimated_msrv,
+                       cr.msrv_reasons as msrv_reasons, cr.syntax_features as syntax_features,
+                       count(f) as file_count
+                ORDER BY cr.name
+            """, crate_name=crate_name)
+            crates = [dict(record) for record in result]
+
+        def version_key(version):
+            return tuple(int(part) for part in re.findall(r'\d+', version or ''))
+
+        for crate in crates:
+            declared, estimated = crate['declared_rust_version'], crate['estimated_msrv']
+            crate['declared_below_estimate'] = bool(declared and estimated and version_key(declared) < version_key(estimated))
+        return crates
@@ -0,0 +1,136 @@
+# src/codegraphcontext/tools/communities.py
+"""
+Community detection over the dependency graph of functions and types, to compare the clusters
+the code actually forms with the module structure it is organized in.
+
+Items are linked by calls, type mentions, instantiations and field accesses, weighted by how
+often they occur, and treated as undirected. The Louvain method groups items into communities
+that maximize modularity: many links inside communities, few between them. An item whose links
+mostly go to another module, which its community is also centered on, is suggested to move there.
+"""
+from typing import Dict, Hashable, List, Tuple
+
+Edges = Dict[Tuple[Hashable, Hashable], float]
+
+
+def _undirected(edges: Dict[Tuple[Hashable, Hashable], float]) -> Edges:
+    merged = {}
+    for (a, b), weight in edges.items():
+        key = (a, b) if str(a) <= str(b) else (b, a)
+        merged[key] = merged.get(key, 0.0) + weight
+    return merged
+
+
+def modularity(partition: Dict[Hashable, Hashable], edges: Edges, resolution: float = 1.0) -> float:
+    """Newman modularity of `partition` (node to community) over undirected weighted `edges`."""
+    edges = _undirected(edges)
+    total = sum(edges.values())
+    if not total:
+        return 0.0
+    inside, degree = {}, {}
+    for (a, b), weight in edges.items():
+        if partition[a] == partition[b]:
+            inside[partition[a]] = inside.get(partition[a], 0.0) + weight
+        degree[partition[a]] = degree.get(partition[a], 0.0) + weight
+        degree[partition[b]] = degree.get(partition[b], 0.0) + weight
+    return sum(inside.get(c, 0.0) / total - resolution * (degree[c] / (2 * total)) ** 2 for c in degree)
+
+
+def _one_level(nodes: List[Hashable], edges: Edges, resolution: float) -> Tuple[Dict[Hashable, Hashable], bool]:
+    """Moves single nodes between communities while that increases modularity."""
+    adjacency = {node: {} for node in nodes}
+    degree = dict.fromkeys(nodes, 0.0)
+    for (a, b), weight in edges.items():
+        adjacency[a][b] = adjacency[a].get(b, 0.0) + weight
+        if a != b:
+            adjacency[b][a] = adjacency[b].get(a, 0.0) + weight
+        degree[a] += weight
+        degree[b] += weight
+    total = sum(edges.values())
+
+    community = {node: node for node in nodes}
+    community_degree = dict(degree)
+    moved, improved = False, True
+    while improved:
+        improved = False
+        for node in nodes:
+            current = community[node]
+            links = {}
+            for neighbour, weight in adjacency[node].items():
+                if neighbour != node:
+                    links[community[neighbour]] = links.get(community[neighbour], 0.0) + weight
+            community_degree[current] -= degree[node]
+            gain = lambda c: links.get(c, 0.0) - resolution * community_degree[c] * degree[node] / (2 * total)
+            best = current
+            for candidate in sorted(links, key=str):
+                if gain(candidate) > gain(best) + 1e-12:
+                    best = candidate
+            community_degree[best] += degree[node]
+            if best != current:
+                community[node] = best
+                moved = improved = True
+    return community, moved
+
+
+def louvain(nodes: List[Hashable], edges: Dict[Tuple[Hashable, Hashable], float],
+            resolution: float = 1.0) -> Dict[Hashable, int]:
+    """
+    Louvain community detection; returns each node's community as a number, the largest
+    community first. Higher `resolution` gives more, smaller communities. Deterministic for a
+    given node order.
+    """
+    edges = _undirected(edges)
+    partition = {node: node for node in nodes}
+    level_nodes, level_edges = list(nodes), edges
+    while level_edges and sum(level_edges.values()):
+        community, moved = _one_level(level_nodes, level_edges, resolution)
+        if not moved:
+            break
+        partition = {node: community[group] for node, group in partition.items()}
+        aggregated = {}
+        for (a, b), weight in level_edges.items():
+            key = (community[a], community[b])
+            aggregated[key] = aggregated.get(key, 0.0) + weight
+        level_nodes = list(dict.fromkeys(community[node] for node in level_nodes))
+        level_edges = _undirected(aggregated)
+
+    sizes = {}
+    for group in partition.values():
+        sizes[group] = sizes.get(group, 0) + 1
+    numbers = {group: i for i, group in enumerate(sorted(sizes, key=lambda g: (-sizes[g], str(g))))}
+    return {node: numbers[group] for node, group in partition.items()}
+
+
+def suggest_moves(partition: Dict[Hashable, int], module_of: Dict[Hashable, str],
+                  edges: Dict[Tuple[Hashable, Hashable], float], min_weight: float = 2.0) -> List[Dict]:
+    """
+    Items linked more strongly to another module than to their own (by at least `min_weight`
+    in total), when that module is also where most of the item's community lives.
+    """
+    edges = _undirected(edges)
+    links = {}
+    for (a, b), weight in edges.items():
+        if a == b:
+            continue
+        for item, other in ((a, b), (b, a)):
+            per_module = links.setdefault(item, {})
+            per_module[module_of[other]] = per_module.get(module_of[other], 0.0) + weight
+
+    home = {}
+    for item, group in partition.items():
+        counts = home.setdefault(group, {})
+        counts[module_of[item]] = counts.get(module_of[item], 0) + 1
+    community_module = {group: max(sorted(counts), key=counts.get) for group, counts in home.items()}
+
+    suggestions = []
+    for item, per_module in links.items():
+        own = per_module.get(module_of[item], 0.0)
+        target = max(sorted(per_module), key=per_module.get)
+        if target == module_of[item] or per_module[target] < max(min_weight, own + 1e-9):
+            continue
+        if community_module[partition[item]] != target:
+            continue
+        suggestions.append({"item": item, "module": module_of[item], "suggested_module": target,
+                            "links_to_own_module": own, "links_to_suggested_module": per_module[target],
+                            "community": partition[item]})
+    return sorted(suggestions, key=lambda s: (-(s["links_to_suggested_module"] - s["links_to_own_module"]), str(s["item"])))
@@ -0,0 +1,66 @@
+# src/codegraphcontext/tools/coverage.py
+"""
+Reads line coverage reports of Rust test runs:
+
+- LCOV tracefiles, from `cargo llvm-cov --lcov` or `grcov -t lcov` (`SF:` file, `DA:line,hits` records);
+- llvm-cov JSON exports, from `cargo llvm-cov --json`, whose region `segments` start at
+  `[line, column, count, has_count, is_region_entry, is_gap_region]`. A line gets the highest count
+  of the non-gap segments starting on it, so lines inside a region without a segment of their own
+  are not listed.
+
+Both come down to `{absolute file path: {line: hits}}`; relative paths are resolved against a
+source root.
+"""
+import json
+from pathlib import Path
+from typing import Dict, Iterable, Optional
+
+
+def parse_lcov(lines: Iterable[str]) -> Dict[str, Dict[int, int]]:
+    """The hits per line of each file (as written in the tracefile) of an LCOV tracefile."""
+    files, current = {}, None
+    for line in lines:
+        line = line.strip()
+        if line.startswith("SF:"):
+            current = files.setdefault(line[3:], {})
+        elif line.startswith("DA:") and current is not None:
+            number, hits = line[3:].split(",")[:2]
+            current[int(number)] = max(current.get(int(number), 0), int(float(hits)))
+        elif line == "end_of_record":
+            current = None
+    return files
+
+
+def parse_llvm_json(report: Dict) -> Dict[str, Dict[int, int]]:
+    """The hits per line of each file of an llvm-cov JSON export."""
+    files = {}
+    for export in report.get("data", []):
+        for file_report in export.get("files", []):
+            lines = files.setdefault(file_report["filename"], {})
+            for segment in file_report.get("segments", []):
+                line, _, count, has_count = segment[:4]
+                is_gap = len(segment) > 5 and segment[5]
+                if has_count and not is_gap:
+                    lines[line] = max(lines.get(line, 0), count)
+    return files
+
+
+def load_report(report_path: Path, source_root: Optional[Path] = None) -> Dict[str, Dict[int, int]]:
+    """
+    Reads an LCOV or llvm-cov JSON report and returns the hits per line of each file, keyed by
+    absolute path; relative paths are taken from `source_root` (by default the report's directory).
+    """
+    report_path = Path(report_path)
+    try:
+        text = report_path.read_text(encoding="utf-8")
+    except OSError as e:
+        raise ValueError(f"Cannot read coverage report {report_path}: {e}")
+    try:
+        files = parse_llvm_json(json.loads(text))
+    except json.JSONDecodeError:
+        files = parse_lcov(text.splitlines())
+    if not files:
+        raise ValueError(f"{report_path} is neither an LCOV nor an llvm-cov JSON coverage report")
+
+    root = Path(source_root or report_path.parent).resolve()
+    return {str((root / path).resolve()): lines for path, lines in files.items()}
@@ -0,0 +1,98 @@
+# src/codegraphcontext/tools/criterion.py
+"""
+Reads the results Criterion.rs keeps under `target/criterion`: one directory per benchmark holding
+`new/benchmark.json` (its `group_id`, `function_id`, `value_str` and `full_id`),
+`new/estimates.json` (point estimates and confidence intervals of the `mean`, `median` and
+`std_dev` of an iteration, in nanoseconds) and, once a previous run exists, `change/estimates.json`
+(the relative change of the mean and median against it).
+
+Like Criterion, a benchmark counts as regressed when the confidence interval of its mean change
+lies entirely above the noise threshold, and as improved when it lies entirely below its negation.
+"""
+import json
+import re
+from pathlib import Path
+from typing import Any, Dict, Iterable, List, Set
+
+NOISE_THRESHOLD = 0.01
+
+
+def _point(estimates: Dict, statistic: str):
+    return (estimates.get(statistic) or {}).get("point_estimate")
+
+
+def criterion_dir(path: Path) -> Path:
+    """`path` itself when it holds Criterion results, otherwise its `target/criterion`."""
+    nested = Path(path) / "target" / "criterion"
+    return nested if nested.is_dir() else Path(path)
+
+
+def load_results(path: Path, noise_threshold: float = NOISE_THRESHOLD) -> List[Dict[str, Any]]:
+    """
+    Reads every benchmark under a Criterion output directory (or a crate's `target/criterion`),
+    with its latest timings and, when there is a previous run, its change and verdict.
+    """
+    root = criterion_dir(path)
+    results = []
+    for benchmark_file in sorted(root.glob("**/new/benchmark.json")):
+        directory = benchmark_file.parent
+        try:
+            benchmark = json.loads(benchmark_file.read_text(encoding="utf-8"))
+            estimates = json.loads((directory / "estimates.json").read_text(encoding="utf-8"))
+        except (OSError, json.JSONDecodeError):
+            continue
+        result = {
+            "id": benchmark.get("full_id") or benchmark.get("title") or directory.parent.name,
+            "group": benchmark.get("group_id"),
+            "function": benchmark.get("function_id"),
+            "parameter": benchmark.get("value_str"),
+            "mean_ns": _point(estimates, "mean"),
+            "median_ns": _point(estimates, "median"),
+            "std_dev_ns": _point(estimates, "std_dev"),
+            "measured_at": int((directory / "estimates.json").stat().st_mtime),
+            "change": None,
+            "verdict": None,
+        }
+        try:
+            change = json.loads((directory.parent / "change" / "estimates.json").read_text(encoding="utf-8"))
+        except (OSError, json.JSONDecodeError):
+            change = None
+        if change and change.get("mean"):
+            interval = change["mean"].get("confidence_interval") or {}
+            lower, upper = interval.get("lower_bound"), interval.get("upper_bound")
+            result["change"] = _point(change, "mean")
+            if lower is not None and lower > noise_threshold:
+                result["verdict"] = "regressed"
+            elif upper is not None and upper < -noise_threshold:
+                result["verdict"] = "improved"
+            else:
+                result["verdict"] = "unchanged"
+        results.append(result)
+    if not results:
+        raise ValueError(f"No Criterion results under {root}")
+    return results
+
+
+def candidate_names(benchmark: Dict[str, Any]) -> Set[str]:
+    """
+    The lowercase function names a benchmark id may refer to: its words (`Fibonacci/recursive 20`
+    gives `fibonacci`, `recursive` and `20`) and its group and function ids with spaces as
+    underscores (`parse config` gives `parse_config`).
+    """
+    names = {word.lower() for word in re.split(r"[^\w]+", benchmark["id"] or "") if word}
+    for part in (benchmark.get("group"), benchmark.get("function")):
+        if part:
+            names.add(re.sub(r"\s+", "_", part.strip()).lower())
+    return names
+
+
+def pick_targets(benchmark: Dict[str, Any], functions: Iterable[Dict[str, Any]]) -> List[Dict[str, Any]]:
+    """
+    The functions a benchmark measures, among `functions` (`{id, name, called}`, where `called`
+    tells whether the code registering the benchmark calls it): those named after a word of the
+    benchmark id, keeping only the called ones when there are any.
+    """
+    names = candidate_names(benchmark)
+    matches = [func for func in functions if func["name"].lower() in names]
+    called = [func for func in matches if func.get("called")]
+    return called or matches
@@ -0,0 +1,214 @@
+# src/codegraphcontext/tools/delta_sync.py
+"""
+Mirrors the graph of a repository to a remote (shared) Neo4j by shipping only what changed.
+
+Indexing writes to the local database; `sync` then reads the repository's subgraph back from
+it (`snapshot`), keys every node and relationship, and diffs them (`diff`) against the manifest
+of the last sync kept on disk, so only the delta crosses the network: nodes that are new or whose
+properties changed are upserted, nodes and relationships gone locally are deleted, and new
+relationships are created. After an incremental re-index that is a few items, not whole files.
+
+Nodes are keyed by their `uid`, else their `path`, else a digest of their properties (a change
+then replaces the node); relationships by their type, end nodes and a digest of their properties.
+On the remote each synced node and relationship carries its `sync_key`, and the nodes of the
+repository (those under its path, as opposed to shared ones it references, such as modules or
+types of other repositories) its `sync_repo`. A `full` sync forgets the manifest, removes the
+repository's nodes from the remote and ships everything again, e.g. after the remote was reset.
+"""
+import hashlib
+import json
+from collections import defaultdict
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+
+SYNC_STATE_DIR = Path.home() / ".codegraphcontext" / "sync"
+# Bookkeeping set on synced (or imported, see `graph_dump`) nodes and relationships, not part of them.
+SYNC_PROPERTIES = ("sync_key", "sync_repo")
+
+
+def digest(value: Any) -> str:
+    return hashlib.sha256(json.dumps(value, sort_keys=True, default=str).encode("utf-8")).hexdigest()[:16]
+
+
+def primary_label(labels: List[str]) -> str:
+    return sorted(labels)[0] if labels else "Node"
+
+
+def node_key(labels: List[str], props: Dict[str, Any]) -> str:
+    """The identity of a node across databases: its uid, path or, failing both, its properties."""
+    label = primary_label(labels)
+    if props.get("uid"):
+        return f"{label}:uid:{props['uid']}"
+    if props.get("path"):
+        return f"{label}:path:{props['path']}"
+    return f"{label}:{digest(props)}"
+
+
+def _own_properties(props: Dict[str, Any]) -> Dict[str, Any]:
+    return {key: value for key, value in props.items() if key not in SYNC_PROPERTIES}
+
+
+def _quote(name: str) -> str:
+    return "`" + name.replace("`", "``") + "`"
+
+
+def snapshot(session, repo_path: Optional[str]) -> Dict[str, Dict]:
+    """
+    The nodes (`key: {labels, props, hash, owned}`) and relationships (`key: {type, start,
+    start_label, end, end_label, props}`) of a repository in the local graph: those of the nodes
+    under its path and the ones they point to. Without a repository, of the whole graph.
+    """
+    prefix = repo_path.rstrip("/") + "/" if repo_path else None
+    nodes, ids = {}, {}
+    for record in session.run("""
+        MATCH (n) WHERE $root IS NULL OR n.path = $root OR n.path STARTS WITH $prefix OR n.file_path STARTS WITH $prefix
+        RETURN elementId(n) as id, labels(n) as labels, properties(n) as props
+    """, root=repo_path, prefix=prefix):
+        props = _own_properties(record["props"])
+        key = node_key(record["labels"], props)
+        ids[record["id"]] = key
+        nodes[key] = {"labels": sorted(record["labels"]), "props": props, "hash": digest(props), "owned": True}
+
+    relationships, seen = {}, defaultdict(int)
+    for record in session.run("""
+        MATCH (n)-[r]->(m) WHERE elementId(n) IN $ids
+        RETURN elementId(n) as start, type(r) as type, properties(r) as props,
+               elementId(m) as end, labels(m) as end_labels, properties(m) as end_props
+    """, ids=list(ids)):
+        end = ids.get(record["end"])
+        if end is None:
+            end_props = _own_properties(record["end_props"])
+            end = node_key(record["end_labels"], end_props)
+            nodes.setdefault(end, {"labels": sorted(record["end_labels"]), "props": end_props,
+                                   "hash": digest(end_props), "owned": False})
+        start, props = ids[record["start"]], _own_properties(record["props"])
+        base = f"{record['type']}:{start}->{end}:{digest(props)}"
+        # Parallel relationships with the same properties (one per call site) stay distinct.
+        key = f"{base}#{seen[base]}"
+        seen[base] += 1
+        relationships[key] = {"type": record["type"], "start": start, "start_label": primary_label(nodes[start]["labels"]),
+                              "end": end, "end_label": primary_label(nodes[end]["labels"]), "props": props}
+    return {"nodes": nodes, "relationships": relationships}
+
+
+def manifest_of(graph: Dict[str, Dict]) -> Dict[str, Dict]:
+    """What the manifest keeps of a snapshot: node hashes and labels, relationship ends."""
+    return {
+        "nodes": {key: [node["hash"], node["owned"], primary_label(node["labels"])] for key, node in graph["nodes"].items()},
+        "relationships": {key: [rel["type"], rel["start"], rel["start_label"]] for key, rel in graph["relationships"].items()},
+    }
+
+
+def diff(manifest: Dict[str, Dict], graph: Dict[str, Dict]) -> Dict[str, List]:
+    """The changes turning the synced state (`manifest`) into `graph`, plus how much is unchanged."""
+    synced_nodes, synced_rels = manifest.get("nodes", {}), manifest.get("relationships", {})
+    upsert = [dict(node, key=key) for key, node in graph["nodes"].items()
+              if synced_nodes.get(key, [None])[0] != node["hash"]]
+    delete = [{"key": key, "label": label} for key, (_, owned, label) in synced_nodes.items()
+              if owned and key not in graph["nodes"]]
+    create = [dict(rel, key=key) for key, rel in graph["relationships"].items() if key not in synced_rels]
+    remove = [{"key": key, "type": rel_type, "start": start, "start_label": start_label}
+              for key, (rel_type, start, start_label) in synced_rels.items() if key not in graph["relationships"]]
+    return {
+        "upsert_nodes": upsert, "delete_nodes": delete, "create_relationships": create, "delete_relationships": remove,
+        "unchanged_nodes": len(graph["nodes"]) - len(upsert),
+        "unchanged_relationships": len(graph["relationships"]) - len(create),
+    }
+
+
+def summarize(delta: Dict[str, List]) -> Dict[str, int]:
+    return {key: value if isinstance(value, int) else len(value) for key, value in delta.items()}
+
+
+def _grouped(rows: List[Dict], *fields: str) -> Dict[Tuple, List[Dict]]:
+    groups = defaultdict(list)
+    for row in rows:
+        groups[tuple(row[field] for field in fields)].append(row)
+    return groups
+
+
+def apply(session, delta: Dict[str, List], repo_path: str, batch_size: int = 500):
+    """Writes a delta to the remote: deletions first, then node upserts, then new relationships."""
+    def batches(rows):
+        for start in range(0, len(rows), batch_size):
+            yield rows[start:start + batch_size]
+
+    for label in sorted({primary_label(node["labels"]) for node in delta["upsert_nodes"]} |
+                        {row["label"] for row in delta["delete_nodes"]}):
+        session.run(f"CREATE INDEX {_quote('sync_key_' + label)} IF NOT EXISTS FOR (n:{_quote(label)}) ON (n.sync_key)")
+
+    for (rel_type, start_label), rows in _grouped(delta["delete_relationships"], "type", "start_label").items():
+        for batch in batches(rows):
+            session.run(f"""
+                UNWIND $rows AS row
+                MATCH (:{_quote(start_label)} {{sync_key: row.start}})-[r:{_quote(rel_type)} {{sync_key: row.key}}]->()
+                DELETE r
+            """, rows=batch)
+    for (label,), rows in _grouped(delta["delete_nodes"], "label").items():
+        for batch in batches(rows):
+            session.run(f"""
+                UNWIND $rows AS row
+                MATCH (n:{_quote(label)} {{sync_key: row.key}}) DETACH DELETE n
+            """, rows=batch)
+
+    upserts = [dict(node, labels=tuple(node["labels"])) for node in delta["upsert_nodes"]]
+    for (labels, owned), rows in _grouped(upserts, "labels", "owned").items():
+        # Shared nodes are only merged in: another repository may own them.
+        assignment = "n = row.props, n.sync_repo = $repo" if owned else "n += row.props"
+        for batch in batches([{"key": row["key"], "props": row["props"]} for row in rows]):
+            session.run(f"""
+                UNWIND $rows AS row
+                MERGE (n:{':'.join(_quote(label) for label in labels)} {{sync_key: row.key}})
+                SET {assignment}, n.sync_key = row.key
+            """, rows=batch, repo=repo_path)
+
+    for (rel_type, start_label, end_label), rows in _grouped(delta["create_relationships"], "type", "start_label", "end_label").items():
+        for batch in batches([{"key": row["key"], "start": row["start"], "end": row["end"], "props": row["props"]} for row in rows]):
+            session.run(f"""
+                UNWIND $rows AS row
+                MATCH (a:{_quote(start_label)} {{sync_key: row.start}})
+                MATCH (b:{_quote(end_label)} {{sync_key: row.end}})
+                MERGE (a)-[r:{_quote(rel_type)} {{sync_key: row.key}}]->(b)
+                SET r += row.props
+            """, rows=batch)
+
+
+def sync(local_driver, remote_driver, repo_path: str, remote_uri: str, full: bool = False,
+         dry_run: bool = False, state_dir: Optional[Path] = None) -> Dict[str, int]:
+    """
+    Ships the changes of a repository's graph since its last sync to `remote_uri` and records the
+    new manifest; with `dry_run`, only counts them. Returns the counts of the delta.
+    """
+    manifest_path = state_file(remote_uri, repo_path, state_dir)
+    with local_driver.session() as session:
+        graph = snapshot(session, repo_path)
+    if not graph["nodes"]:
+        raise ValueError(f"Repository {repo_path} is not indexed")
+    delta = diff({} if full else load_manifest(manifest_path), graph)
+    if not dry_run:
+        with remote_driver.session() as session:
+            if full:
+                session.run("MATCH (n {sync_repo: $repo}) DETACH DELETE n", repo=repo_path)
+            apply(session, delta, repo_path)
+        save_manifest(manifest_path, manifest_of(graph))
+    return summarize(delta)
+
+
+def state_file(remote_uri: str, repo_path: str, state_dir: Optional[Path] = None) -> Path:
+    """Where the manifest of a repository's syncs to a remote is kept."""
+    return Path(state_dir or SYNC_STATE_DIR) / f"{digest([remote_uri, repo_path])}.json"
+
+
+def load_manifest(path: Path) -> Dict[str, Dict]:
+    try:
+        return json.loads(Path(path).read_text(encoding="utf-8"))
+    except (OSError, json.JSONDecodeError):
+        return {}
+
+
+def save_manifest(path: Path, manifest: Dict[str, Dict]):
+    path = Path(path)
+    path.parent.mkdir(parents=True, exist_ok=True)
+    temporary = path.with_suffix(".tmp")
+    temporary.write_text(json.dumps(manifest), encoding="utf-8")
+    temporary.replace(path)
@@ -0,0 +1,85 @@
+# src/codegraphcontext/tools/dsm.py
+"""
+Dependency structure matrices (DSM) of modules.
+
+Row `i`, column `j` holds how many dependencies module `i` has on module `j` (imports, calls, type
+usages and so on). Modules are ordered so that dependencies point to earlier modules where
+possible (providers first, their users below), which makes a layered design lower-triangular:
+every count above the diagonal is a feedback dependency, and modules in a dependency cycle
+are kept next to each other.
+"""
+import csv
+import io
+import json
+from typing import Any, Dict, List, Tuple
+
+DSM_FORMATS = ("json", "csv")
+
+
+def order_modules(modules: List[str], counts: Dict[Tuple[str, str], int]) -> Tuple[List[str], List[List[str]]]:
+    """
+    Orders modules providers first by sorting their strongly connected components topologically
+    (Tarjan's algorithm emits a component after everything it depends on). Returns the order and
+    the cycles, i.e. the components of more than one module.
+    """
+    graph = {module: sorted({target for (source, target) in counts if source == module and target != module})
+             for module in modules}
+    index, lowlink, on_stack, stack = {}, {}, set(), []
+    order, cycles = [], []
+
+    def connect(module):
+        index[module] = lowlink[module] = len(index)
+        stack.append(module)
+        on_stack.add(module)
+        for target in graph.get(module, []):
+            if target not in index:
+                connect(target)
+                lowlink[module] = min(lowlink[module], lowlink[target])
+            elif target in on_stack:
+                lowlink[module] = min(lowlink[module], index[target])
+        if lowlink[module] == index[module]:
+            component = []
+            while True:
+                member = stack.pop()
+                on_stack.discard(member)
+                component.append(member)
+                if member == module:
+                    break
+            order.extend(sorted(component))
+            if len(component) > 1:
+                cycles.append(sorted(component))
+
+    for module in sorted(modules):
+        if module not in index:
+            connect(module)
+    return order, cycles
+
+
+def build_matrix(modules: List[str], counts: Dict[Tuple[str, str], int]) -> Dict[str, Any]:
+    """The ordered DSM: `modules`, the `matrix` rows, `feedback` cells above the diagonal and `cycles`."""
+    order, cycles = order_modules(modules, counts)
+    position = {module: i for i, module in enumerate(order)}
+    matrix = [[counts.get((source, target), 0) if source != target else 0 for target in order] for source in order]
+    feedback = [{"source": source, "target": target, "count": count}
+                for (source, target), count in sorted(counts.items())
+                if source != target and position[target] > position[source]]
+    return {"modules": order, "matrix": matrix, "feedback": feedback, "cycles": cycles}
+
+
+def to_csv(dsm: Dict[str, Any]) -> str:
+    """The matrix as CSV, with module names as the header row and first column."""
+    output = io.StringIO()
+    writer = csv.writer(output, lineterminator="\n")
+    writer.writerow([""] + dsm["modules"])
+    for module, row in zip(dsm["modules"], dsm["matrix"]):
+        writer.writerow([module] + row)
+    return output.getvalue()
+
+
+def render(dsm: Dict[str, Any], export_format: str) -> str:
+    """Serializes a DSM in one of `DSM_FORMATS`."""
+    if export_format == "json":
+        return json.dumps(dsm, indent=2)
+    if export_format == "csv":
+        return to_csv(dsm)
+    raise ValueError(f"Unknown DSM format '{export_format}'; expected one of {', '.join(DSM_FORMATS)}")
@@ -0,0 +1,119 @@
+# src/codegraphcontext/tools/embeddings.py
+"""
+Embeddings of functions for semantic search.
+
+By default functions are embedded with a dependency-free hashing embedder over the words of
+their name, doc comment, parameters and body (identifiers are split on case and underscores).
+Setting `CGC_EMBEDDING_MODEL` to a sentence-transformers model name (e.g. `all-MiniLM-L6-v2`)
+uses that model instead when the package is installed. Vectors are L2-normalized, so the dot
+product of two of them is their cosine similarity.
+"""
+import hashlib
+import logging
+import math
+import os
+import re
+from typing import Dict, List
+
+logger = logging.getLogger(__name__)
+
+HASHING_DIMENSIONS = 256
+STOPWORDS = {
+    'a', 'an', 'and', 'are', 'as', 'be', 'by', 'for', 'from', 'if', 'in', 'into', 'is', 'it', 'of', 'on', 'or',
+    'that', 'the', 'this', 'to', 'with', 'fn', 'def', 'let', 'mut', 'pub', 'self', 'return', 'else', 'match',
+    'impl', 'where', 'crate', 'super', 'use', 'mod', 'struct', 'enum', 'true', 'false', 'none', 'some', 'ok',
+}
+# Weights of the parts of a function in the hashing embedding: names say the most about intent.
+PART_WEIGHTS = {"name": 3.0, "docstring": 2.0, "signature": 1.5, "body": 1.0}
+MAX_BODY_CHARS = 4000
+
+
+def words(text: str) -> List[str]:
+    """Lowercased words of `text`, with identifiers split (`parse_and_validate`, `HashMap` -> hash, map)."""
+    tokens = []
+    for identifier in re.findall(r"[A-Za-z][A-Za-z0-9]*", text or ""):
+        for word in re.findall(r"[A-Z]+(?![a-z])|[A-Z]?[a-z]+|[0-9]+", identifier):
+            word = word.lower()
+            if len(word) > 3 and word.endswith('s') and not word.endswith('ss'):
+                word = word[:-1]
+            if len(word) > 1 and word not in STOPWORDS:
+                tokens.append(word)
+    return tokens
+
+
+def function_parts(func: Dict) -> Dict[str, str]:
+    """The texts a function is embedded from, keyed by `PART_WEIGHTS` part."""
+    signature = " ".join(str(arg) for arg in func.get('args') or []) + " " + (func.get('return_type') or "")
+    return {
+        "name": f"{func.get('class_context') or ''} {func.get('name') or ''}",
+        "docstring": func.get('docstring') or "",
+        "signature": signature,
+        "body": (func.get('source') or func.get('source_code') or "")[:MAX_BODY_CHARS],
+    }
+
+
+def _normalize(vector: List[float]) -> List[float]:
+    norm = math.sqrt(sum(v * v for v in vector))
+    return [v / norm for v in vector] if norm else vector
+
+
+class HashingEmbedder:
+    """Feature-hashed bag of words with sublinear term frequency; cheap and deterministic."""
+
+    name = f"hashing-{HASHING_DIMENSIONS}"
+    dimensions = HASHING_DIMENSIONS
+
+    def _embed_weighted(self, weighted_words: List[tuple]) -> List[float]:
+        counts = {}
+        for word, weight in weighted_words:
+            counts[word] = counts.get(word, 0.0) + weight
+        vector = [0.0] * self.dimensions
+        for word, count in counts.items():
+            digest = hashlib.md5(word.encode("utf-8")).digest()
+            index = int.from_bytes(digest[:4], "little") % self.dimensions
+            sign = 1.0 if digest[4] & 1 else -1.0
+            vector[index] += sign * (1.0 + math.log(count))
+        return _normalize(vector)
+
+    def embed_function(self, func: Dict) -> List[float]:
+        return self._embed_weighted([(word, PART_WEIGHTS[part]) for part, text in function_parts(func).items()
+                                     for word in words(text)])
+
+    def embed_query(self, text: str) -> List[float]:
+        return self._embed_weighted([(word, 1.0) for word in words(text)])
+
+
+class SentenceTransformerEmbedder:
+    """Embeds with a sentence-transformers model; loaded once per process."""
+
+    def __init__(self, model_name: str):
+        from sentence_transformers import SentenceTransformer
+        self.model = SentenceTransformer(model_name)
+        self.name = model_name
+        self.dimensions = self.model.get_sentence_embedding_dimension()
+
+    def embed_function(self, func: Dict) -> List[float]:
+        parts = function_parts(func)
+        text = f"{parts['name']}\n{parts['docstring']}\n{parts['signature']}\n{parts['body'][:1000]}"
+        return self.model.encode(text, normalize_embeddings=True).tolist()
+
+    def embed_query(self, text: str) -> List[float]:
+        return self.model.encode(text, normalize_embeddings=True).tolist()
+
+
+_embedder = None
+
+
+def get_embedder():
+    """Returns the configured embedder, falling back to hashing when the model cannot be loaded."""
+    global _embedder
+    if _embedder is None:
+        model_name = os.getenv("CGC_EMBEDDING_MODEL")
+        if model_name:
+            try:
+                _embedder = SentenceTransformerEmbedder(model_name)
+            except Exception as e:
+                logger.warning(f"Could not load embedding model '{model_name}', using hashing embeddings: {e}")
+        if _embedder is None:
+            _embedder = HashingEmbedder()
+    return _embedder
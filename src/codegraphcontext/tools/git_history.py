@@ -0,0 +1,160 @@
+# src/codegraphcontext/tools/git_history.py
+import io
+import logging
+import shutil
+import subprocess
+import tarfile
+import tempfile
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+try:
+    import pygit2
+except ImportError:  # blame metadata is skipped without libgit2 bindings
+    pygit2 = None
+
+logger = logging.getLogger(__name__)
+
+REVISIONS_DIR = Path.home() / ".codegraphcontext" / "revisions"
+
+
+def run_git(repo_path: Path, *args: str) -> str:
+    """Runs a git command in `repo_path` and returns its stdout, raising ValueError when git fails."""
+    try:
+        process = subprocess.run(["git", "-C", str(repo_path), *args], capture_output=True, check=True)
+    except FileNotFoundError:
+        raise ValueError("git is not installed or not on PATH")
+    except subprocess.CalledProcessError as e:
+        raise ValueError(f"git {' '.join(args)} failed: {e.stderr.decode(errors='replace').strip()}")
+    return process.stdout.decode(errors="replace")
+
+
+def resolve_revision(repo_path: Path, revision: str) -> str:
+    """Returns the full commit hash `revision` (a branch, tag or commit) points to."""
+    return run_git(repo_path, "rev-parse", "--verify", f"{revision}^{{commit}}").strip()
+
+
+def list_revisions(repo_path: Path, revision_range: str, max_count: int = 20) -> List[str]:
+    """
+    The commits of `revision_range` (e.g. `v1.0..HEAD`) along the first-parent chain, oldest first;
+    only the `max_count` latest ones when there are more.
+    """
+    commits = run_git(repo_path, "rev-list", "--first-parent", f"--max-count={max_count}", revision_range).split()
+    return commits[::-1]
+
+
+def commit_time(repo_path: Path, commit: str) -> int:
+    """The commit time of `commit`, in epoch seconds."""
+    return int(run_git(repo_path, "show", "-s", "--format=%ct", commit).strip())
+
+
+def export_revision(repo_path: Path, revision: str) -> Path:
+    """
+    Extracts the tree of `revision` into its own directory under ~/.codegraphcontext/revisions,
+    named after the repository and commit, so each revision can be indexed as a separate
+    repository. The snapshot of a commit is extracted once and reused afterwards.
+    """
+    repo_path = Path(repo_path).resolve()
+    commit = resolve_revision(repo_path, revision)
+    snapshot = REVISIONS_DIR / f"{repo_path.name}@{commit[:12]}"
+    if snapshot.is_dir():
+        return snapshot
+
+    archive = subprocess.run(["git", "-C", str(repo_path), "archive", "--format=tar", commit], capture_output=True)
+    if archive.returncode != 0:
+        raise ValueError(f"git archive {commit} failed: {archive.stderr.decode(errors='replace').strip()}")
+
+    REVISIONS_DIR.mkdir(parents=True, exist_ok=True)
+    staging = Path(tempfile.mkdtemp(prefix=f".{snapshot.name}-", dir=REVISIONS_DIR))
+    try:
+        with tarfile.open(fileobj=io.BytesIO(archive.stdout)) as tar:
+            tar.extractall(staging)
+        staging.rename(snapshot)
+    except Exception:
+        shutil.rmtree(staging, ignore_errors=True)
+        raise
+    logger.info(f"Exported {repo_path.name} at {commit[:12]} to {snapshot}")
+    return snapshot
+
+
+def open_repository(path: Path):
+    """Returns the pygit2 repository containing `path`, or None when there is none (or no pygit2)."""
+    if pygit2 is None:
+        return None
+    discovered = pygit2.discover_repository(str(path))
+    return pygit2.Repository(discovered) if discovered else None
+
+
+def blame_file(repository, file_path: Path) -> List[Dict[str, Any]]:
+    """
+    Blames the committed content of `file_path` and returns one entry per hunk with its line range
+    and the commit, author and commit time that last touched it. Untracked files have no hunks.
+    """
+    if repository.workdir is None:
+        return []
+    try:
+        relative = Path(file_path).resolve().relative_to(Path(repository.workdir).resolve()).as_posix()
+        blame = repository.blame(relative)
+    except (ValueError, KeyError, pygit2.GitError):
+        return []
+
+    hunks = []
+    for hunk in blame:
+        signature = hunk.final_committer
+        hunks.append({
+            "start": hunk.final_start_line_number,
+            "end": hunk.final_start_line_number + hunk.lines_in_hunk - 1,
+            "commit": str(hunk.final_commit_id),
+            "author": signature.name if signature else None,
+            "email": signature.email if signature else None,
+            "time": signature.time if signature else None,
+        })
+    return hunks
+
+
+def summarize_blame(hunks: List[Dict[str, Any]], start: int, end: int, now: float) -> Optional[Dict[str, Any]]:
+    """
+    Condenses the hunks overlapping lines `start`..`end` into node properties: the most recent
+    commit, its author and time (epoch seconds), the age in days at `now`, and the authors of
+    those lines ordered by how many of them they own.
+    """
+    lines_by_author = {}
+    latest = None
+    for hunk in hunks:
+        overlap = min(end, hunk['end']) - max(start, hunk['start']) + 1
+        if overlap <= 0 or hunk['time'] is None:
+            continue
+        lines_by_author[hunk['author']] = lines_by_author.get(hunk['author'], 0) + overlap
+        if latest is None or hunk['time'] > latest['time']:
+            latest = hunk
+    if latest is None:
+        return None
+    return {
+        "last_commit": latest['commit'],
+        "last_author": latest['author'],
+        "last_author_email": latest['email'],
+        "last_modified": latest['time'],
+        "age_days": int((now - latest['time']) // 86400),
+        "owners": sorted(lines_by_author, key=lambda author: (-lines_by_author[author], author)),
+    }
+
+
+def file_churn(repo_path: Path, since_days: int, until_days: int = 0) -> Dict[str, Dict[str, int]]:
+    """
+    Counts, per file (absolute path), the commits touching it and the lines added plus deleted
+    between `since_days` and `until_days` days ago, from `git log --numstat`.
+    """
+    root = Path(run_git(repo_path, "rev-parse", "--show-toplevel").strip())
+    log = run_git(repo_path, "log", "--no-merges", "--no-renames", "--numstat", "--format=commit %H",
+                  f"--since={since_days} days ago", f"--until={until_days} days ago")
+    churn = {}
+    for line in log.splitlines():
+        parts = line.split("\t")
+        if len(parts) != 3:
+            continue
+        added, deleted, path = parts
+        stats = churn.setdefault(str((root / path).resolve()), {"commits": 0, "lines_changed": 0})
+        stats["commits"] += 1
+        if added != "-":  # binary files have no line counts
+            stats["lines_changed"] += int(added) + int(deleted)
+    return churn
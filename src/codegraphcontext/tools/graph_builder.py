@@ -1,16 +1,42 @@
 
 # src/codegraphcontext/tools/graph_builder.py
 import asyncio
+import functools
+import json
 import logging
 import os
+import re
+import threading
+import time
 from pathlib import Path
-from typing import Any, Coroutine, Dict, Optional, Tuple
+from concurrent.futures import ThreadPoolExecutor
+from typing import Any, Callable, Coroutine, Dict, Optional, Tuple
 from datetime import datetime
 import ast
 
 from ..core.database import DatabaseManager
 from ..core.jobs import JobManager, JobStatus
 from ..utils.debug_log import debug_log
+from .centrality import BETWEENNESS_MAX_SOURCES, betweenness, pagerank
+from .clippy import run_clippy
+from .coverage import load_report
+from .criterion import candidate_names, load_results as load_benchmarks, pick_targets
+from . import delta_sync
+from .embeddings import get_embedder
+from .git_history import (REVISIONS_DIR, blame_file, commit_time, export_revision, list_revisions, open_repository,
+                          resolve_revision, summarize_blame)
+from .incremental import crate_summary, diff_items, file_hash, fingerprint, plan_reindex
+from .interning import intern_tree
+from .ignore import IgnoreRules
+from .layering import LAYERS_CONFIG_FILE
+from .shards import plan_shards
+from .snippets import codec_from_env, source_text, stored_properties
+from .sources import ContentIndex
+from .spill import SpillList, memory_limit_bytes
+from .node_ids import assign_ids, stable_id
+from .pipeline import DEFAULT_PARSE_WORKERS, DEFAULT_PIPELINE_CAPACITY, BoundedPipeline
+from .profile import aggregate, load_profile, normalize_frame
+from .temporal import build_history
 
 # New imports for tree-sitter
 from tree_sitter import Language, Parser
@@ -18,6 +44,20 @@ from tree_sitter_languages import get_language
 
 logger = logging.getLogger(__name__)
 
+# Relationships attached from outside indexing (imported reports), kept when a file is re-indexed.
+ANNOTATION_RELATIONSHIPS = ['HAS_DIAGNOSTIC', 'COVERS']
+# Span properties of a call expression, stored on its CALLS edge.
+CALL_SPAN_KEYS = ('start_line', 'start_column', 'end_line', 'end_column', 'start_byte', 'end_byte')
+
+# Indexed revision snapshots keep their nodes under these prefixed labels (`SnapshotFunction`), out
+# of the labels every other query matches; see `index_revision`.
+SNAPSHOT_LABEL_PREFIX = 'Snapshot'
+
+# Rows written per UNWIND statement and transaction; override with CGC_WRITE_BATCH_SIZE.
+DEFAULT_WRITE_BATCH_SIZE = 500
+# Shards indexed at the same time by sharded builds; override with CGC_SHARD_WORKERS.
+DEFAULT_SHARD_WORKERS = 4
+
 # This is for developers and testers only. It enables detailed debug logging to a file.
 # Set to 1 to enable, 0 to disable.
 debug_mode = 0
@@ -39,14 +79,32 @@ class TreeSitterParser:
         elif self.language_name == 'javascript':
             from .languages.javascript import JavascriptTreeSitterParser
             self.language_specific_parser = JavascriptTreeSitterParser(self)
+        elif self.language_name == 'rust':
+            from .languages.rust import RustTreeSitterParser
+            self.language_specific_parser = RustTreeSitterParser(self)
 
     def parse(self, file_path: Path, is_dependency: bool = False) -> Dict:
-        """Dispatches parsing to the language-specific parser."""
+        """Dispatches parsing to the language-specific parser; the result's strings are interned (see `interning`)."""
         if self.language_specific_parser:
-            return self.language_specific_parser.parse(file_path, is_dependency)
+            return intern_tree(self.language_specific_parser.parse(file_path, is_dependency))
         else:
             raise NotImplementedError(f"No language-specific parser implemented for {self.language_name}")
 
+def create_parsers() -> Dict[str, TreeSitterParser]:
+    """A parser per supported file extension."""
+    return {
+        '.py': TreeSitterParser('python'),
+        '.js': TreeSitterParser('javascript'), # Added JavaScript parser
+        '.rs': TreeSitterParser('rust'),
+    }
+
+def retried(method):
+    """Runs a GraphBuilder method through `DatabaseManager.run_with_retry`; it must be safe to repeat."""
+    @functools.wraps(method)
+    def wrapper(self, *args, **kwargs):
+        return self.db_manager.run_with_retry(lambda: method(self, *args, **kwargs), method.__name__)
+    return wrapper
+
 class GraphBuilder:
     """Module for building and managing the Neo4j code graph."""
 
@@ -55,10 +113,15 @@ class GraphBuilder:
         self.job_manager = job_manager
         self.loop = loop
         self.driver = self.db_manager.get_driver()
-        self.parsers = {
-            '.py': TreeSitterParser('python'),
-            '.js': TreeSitterParser('javascript'), # Added JavaScript parser
-        }
+        self.batch_size = max(1, int(os.getenv("CGC_WRITE_BATCH_SIZE", DEFAULT_WRITE_BATCH_SIZE)))
+        self.snippet_codec = codec_from_env()
+        # Bumped after writes to the graph; cached query results of older generations are stale.
+        self.generation = 0
+        self.generation_lock = threading.Lock()
+        self.parsers = create_parsers()
+        # tree-sitter parsers are not thread-safe: threads other than the first get their own (see `parse_file`).
+        self.local = threading.local()
+        self.local.parsers = self.parsers
         self.create_schema()
 
     # A general schema creation based on common features across languages
@@ -70,9 +133,29 @@ class GraphBuilder:
                 session.run("CREATE CONSTRAINT file_path IF NOT EXISTS FOR (f:File) REQUIRE f.path IS UNIQUE")
                 session.run("CREATE CONSTRAINT directory_path IF NOT EXISTS FOR (d:Directory) REQUIRE d.path IS UNIQUE")
                 session.run("CREATE CONSTRAINT function_unique IF NOT EXISTS FOR (f:Function) REQUIRE (f.name, f.file_path, f.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT function_uid IF NOT EXISTS FOR (f:Function) REQUIRE f.uid IS UNIQUE")
+                session.run("CREATE CONSTRAINT class_uid IF NOT EXISTS FOR (c:Class) REQUIRE c.uid IS UNIQUE")
+                session.run("CREATE CONSTRAINT variable_uid IF NOT EXISTS FOR (v:Variable) REQUIRE v.uid IS UNIQUE")
                 session.run("CREATE CONSTRAINT class_unique IF NOT EXISTS FOR (c:Class) REQUIRE (c.name, c.file_path, c.line_number) IS UNIQUE")
                 session.run("CREATE CONSTRAINT variable_unique IF NOT EXISTS FOR (v:Variable) REQUIRE (v.name, v.file_path, v.line_number) IS UNIQUE")
                 session.run("CREATE CONSTRAINT module_name IF NOT EXISTS FOR (m:Module) REQUIRE m.name IS UNIQUE")
+                session.run("CREATE CONSTRAINT channel_unique IF NOT EXISTS FOR (ch:Channel) REQUIRE (ch.name, ch.file_path, ch.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT lock_unique IF NOT EXISTS FOR (l:Lock) REQUIRE (l.name, l.file_path, l.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT field_unique IF NOT EXISTS FOR (fd:Field) REQUIRE (fd.name, fd.owner, fd.file_path, fd.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT closure_unique IF NOT EXISTS FOR (cl:Closure) REQUIRE (cl.name, cl.file_path, cl.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT impl_unique IF NOT EXISTS FOR (i:Impl) REQUIRE (i.name, i.file_path, i.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT associated_type_unique IF NOT EXISTS FOR (at:AssociatedType) REQUIRE (at.name, at.owner, at.file_path, at.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT crate_path IF NOT EXISTS FOR (cr:Crate) REQUIRE cr.path IS UNIQUE")
+                session.run("CREATE CONSTRAINT blocking_call_name IF NOT EXISTS FOR (b:BlockingCall) REQUIRE b.name IS UNIQUE")
+                session.run("CREATE CONSTRAINT macro_unique IF NOT EXISTS FOR (mc:Macro) REQUIRE (mc.name, mc.file_path, mc.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT variant_unique IF NOT EXISTS FOR (v:Variant) REQUIRE (v.name, v.file_path, v.line_number) IS UNIQUE")
+                session.run("CREATE CONSTRAINT literal_unique IF NOT EXISTS FOR (l:Literal) REQUIRE (l.value, l.kind) IS UNIQUE")
+                session.run("CREATE CONSTRAINT benchmark_id IF NOT EXISTS FOR (b:Benchmark) REQUIRE b.id IS UNIQUE")
+                session.run("CREATE CONSTRAINT revision_unique IF NOT EXISTS FOR (rv:Revision) REQUIRE (rv.repo, rv.ordinal) IS UNIQUE")
+                session.run("CREATE CONSTRAINT versioned_item_unique IF NOT EXISTS FOR (vi:VersionedItem) REQUIRE (vi.repo, vi.key) IS UNIQUE")
+                session.run("CREATE CONSTRAINT shard_path IF NOT EXISTS FOR (s:Shard) REQUIRE s.path IS UNIQUE")
+                session.run("CREATE CONSTRAINT unresolved_reference_unique IF NOT EXISTS FOR (u:UnresolvedReference) REQUIRE (u.kind, u.name, u.owner) IS UNIQUE")
+                session.run("CREATE CONSTRAINT task_unique IF NOT EXISTS FOR (t:Task) REQUIRE (t.file_path, t.line_number) IS UNIQUE")
 
                 # Indexes for language attribute
                 session.run("CREATE INDEX function_lang IF NOT EXISTS FOR (f:Function) ON (f.lang)")
@@ -88,6 +171,16 @@ class GraphBuilder:
             except Exception as e:
                 logger.warning(f"Schema creation warning: {e}")
 
+            # Vector indexes need Neo4j 5.11+; semantic search scans the embeddings without one.
+            try:
+                session.run(f"""
+                    CREATE VECTOR INDEX function_embeddings IF NOT EXISTS
+                    FOR (f:Function) ON (f.embedding)
+                    OPTIONS {{indexConfig: {{`vector.dimensions`: {get_embedder().dimensions}, `vector.similarity_function`: 'cosine'}}}}
+                """)
+            except Exception as e:
+                logger.info(f"Vector index not created, semantic search will scan embeddings: {e}")
+
 
     def _pre_scan_for_imports(self, files: list[Path]) -> dict:
         """Dispatches pre-scan to the correct language-specific implementation."""
@@ -108,10 +201,14 @@ class GraphBuilder:
         elif '.js' in files_by_lang:
             from .languages import javascript as js_lang_module
             imports_map.update(js_lang_module.pre_scan_javascript(files_by_lang['.js'], self.parsers['.js']))
+        if '.rs' in files_by_lang:
+            from .languages import rust as rust_lang_module
+            imports_map.update(rust_lang_module.pre_scan_rust(files_by_lang['.rs'], self.parsers['.rs']))
             
-        return imports_map
+        return intern_tree(imports_map)
 
     # Language-agnostic method
+    @retried
     def add_repository_to_graph(self, repo_path: Path, is_dependency: bool = False):
         """Adds a repository node using its absolute path as the unique key."""
         repo_name = repo_path.name
@@ -127,7 +224,59 @@ class GraphBuilder:
                 is_dependency=is_dependency,
             )
 
+    def _link_to_directories(self, session, file_path_str: str, repo_root: str):
+        """Creates the Directory nodes between a repository and one of its files, linked by CONTAINS."""
+        file_path_obj = Path(file_path_str)
+        repo_path_obj = Path(repo_root)
+        
+        relative_path_to_file = file_path_obj.relative_to(repo_path_obj)
+        
+        parent_path = str(repo_path_obj)
+        parent_label = 'Repository'
+
+        for part in relative_path_to_file.parts[:-1]:
+            current_path = Path(parent_path) / part
+            current_path_str = str(current_path)
+            
+            session.run(f"""
+                MATCH (p:{parent_label} {{path: $parent_path}})
+                MERGE (d:Directory {{path: $current_path}})
+                SET d.name = $part
+                MERGE (p)-[:CONTAINS]->(d)
+            """, parent_path=parent_path, current_path=current_path_str, part=part)
+
+            parent_path = current_path_str
+            parent_label = 'Directory'
+
+        session.run(f"""
+            MATCH (p:{parent_label} {{path: $parent_path}})
+            MATCH (f:File {{path: $file_path}})
+            MERGE (p)-[:CONTAINS]->(f)
+        """, parent_path=parent_path, file_path=file_path_str)
+
+    @retried
+    def add_alias_file_to_graph(self, file_path: Path, canonical_path: str, repo_path: Path, is_dependency: bool = False):
+        """
+        Records a file byte-identical to the already indexed `canonical_path` (see `sources`): a
+        File node with `alias_of` and File-[:ALIAS_OF]->File, in place of the file's own items.
+        """
+        file_path_str = str(Path(file_path).resolve())
+        repo_root = str(repo_path.resolve())
+        relative_path = str(Path(file_path_str).relative_to(repo_root))
+        with self.driver.session() as session:
+            session.run("""
+                MERGE (f:File {path: $path})
+                SET f.name = $name, f.relative_path = $relative_path, f.is_dependency = $is_dependency,
+                    f.uid = $uid, f.content_hash = $content_hash, f.alias_of = $canonical
+                MERGE (c:File {path: $canonical})
+                MERGE (f)-[:ALIAS_OF]->(c)
+            """, path=file_path_str, name=Path(file_path_str).name, relative_path=relative_path,
+                is_dependency=is_dependency, uid=stable_id(f"{repo_root}:File:{relative_path}"),
+                content_hash=file_hash(file_path_str), canonical=canonical_path)
+            self._link_to_directories(session, file_path_str, repo_root)
+
     # First pass to add file and its contents
+    @retried
     def add_file_to_graph(self, file_data: Dict, repo_name: str, imports_map: dict):
         logger.info("Executing add_file_to_graph with my change!")
         """Adds a file and its contents within a single, unified session."""
@@ -143,140 +292,452 @@ class GraphBuilder:
             except ValueError:
                 relative_path = file_name
 
+            repo_root = repo_result['path'] if repo_result else str(Path(file_data['repo_path']).resolve())
             session.run("""
                 MERGE (f:File {path: $path})
-                SET f.name = $name, f.relative_path = $relative_path, f.is_dependency = $is_dependency
-            """, path=file_path_str, name=file_name, relative_path=relative_path, is_dependency=is_dependency)
+                SET f.name = $name, f.relative_path = $relative_path, f.is_dependency = $is_dependency,
+                    f.docstring = $docstring, f.uid = $uid, f.content_hash = $content_hash,
+                    f.crate_summary = $crate_summary
+                REMOVE f.alias_of
+                WITH f
+                OPTIONAL MATCH (f)-[alias:ALIAS_OF]->()
+                DELETE alias
+            """, path=file_path_str, name=file_name, relative_path=relative_path, is_dependency=is_dependency,
+                docstring=file_data.get('docstring'), uid=stable_id(f"{repo_root}:File:{relative_path}"),
+                content_hash=file_hash(file_path_str),
+                crate_summary=crate_summary(file_data) if file_data.get('lang') == 'rust' else None)
 
-            file_path_obj = Path(file_path_str)
-            repo_path_obj = Path(repo_result['path'])
-            
-            relative_path_to_file = file_path_obj.relative_to(repo_path_obj)
-            
-            parent_path = str(repo_path_obj)
-            parent_label = 'Repository'
-
-            for part in relative_path_to_file.parts[:-1]:
-                current_path = Path(parent_path) / part
-                current_path_str = str(current_path)
-                
-                session.run(f"""
-                    MATCH (p:{parent_label} {{path: $parent_path}})
-                    MERGE (d:Directory {{path: $current_path}})
-                    SET d.name = $part
-                    MERGE (p)-[:CONTAINS]->(d)
-                """, parent_path=parent_path, current_path=current_path_str, part=part)
-
-                parent_path = current_path_str
-                parent_label = 'Directory'
+            parsed = {}
+            for item_data, label in [(file_data['functions'], 'Function'), (file_data['classes'], 'Class'), (file_data['variables'], 'Variable')]:
+                assign_ids(repo_root, label, relative_path, item_data)
+                for item in item_data:
+                    if label == 'Function':
+                        item.setdefault('cyclomatic_complexity', 1)
+                    item['fingerprint'] = fingerprint(item)
+                    parsed[item['uid']] = item['fingerprint']
+            stored = {record['uid']: record['fingerprint'] for record in session.run("""
+                MATCH (f:File {path: $path})-[:CONTAINS]->(e) WHERE e.uid IS NOT NULL
+                RETURN e.uid as uid, e.fingerprint as fingerprint
+            """, path=file_path_str)}
+            changes = diff_items(stored, parsed)
+            file_data['item_changes'] = {key: len(uids) for key, uids in changes.items()}
+            unchanged = set(changes['unchanged'])
+            self._prune_stale_elements(session, file_path_str, list(parsed), changes['unchanged'])
 
-            session.run(f"""
-                MATCH (p:{parent_label} {{path: $parent_path}})
-                MATCH (f:File {{path: $file_path}})
-                MERGE (p)-[:CONTAINS]->(f)
-            """, parent_path=parent_path, file_path=file_path_str)
+            self._link_to_directories(session, file_path_str, repo_result['path'])
 
             # CONTAINS relationships for functions, classes, and variables
+            file_data['nodes_written'] = 1
             for item_data, label in [(file_data['functions'], 'Function'), (file_data['classes'], 'Class'), (file_data['variables'], 'Variable')]:
-                for item in item_data:
-                    # Ensure cyclomatic_complexity is set for functions
-                    if label == 'Function' and 'cyclomatic_complexity' not in item:
-                        item['cyclomatic_complexity'] = 1 # Default value
-
-                    query = f"""
-                        MATCH (f:File {{path: $file_path}})
-                        MERGE (n:{label} {{name: $name, file_path: $file_path, line_number: $line_number}})
-                        SET n += $props
-                        MERGE (f)-[:CONTAINS]->(n)
-                    """
-                    session.run(query, file_path=file_path_str, name=item['name'], line_number=item['line_number'], props=item)
-                    
-                    if label == 'Function':
-                        for arg_name in item.get('args', []):
-                            session.run("""
-                                MATCH (fn:Function {name: $func_name, file_path: $file_path, line_number: $line_number})
-                                MERGE (p:Parameter {name: $arg_name, file_path: $file_path, function_line_number: $line_number})
-                                MERGE (fn)-[:HAS_PARAMETER]->(p)
-                            """, func_name=item['name'], file_path=file_path_str, line_number=item['line_number'], arg_name=arg_name)
+                # Unchanged items (same properties and position) keep their node and relationships as they are.
+                rows = [{"uid": item['uid'], "name": item['name'], "line_number": item['line_number'],
+                         "props": stored_properties(item, self.snippet_codec)}
+                        for item in item_data if item['uid'] not in unchanged]
+                file_data['nodes_written'] += len(rows)
+                self._write_batches(session, f"""
+                    UNWIND $rows AS row
+                    MATCH (f:File {{path: $file_path}})
+                    MERGE (n:{label} {{uid: row.uid}})
+                    SET n += row.props, n.name = row.name, n.file_path = $file_path, n.line_number = row.line_number
+                    MERGE (f)-[:CONTAINS]->(n)
+                """, rows, file_path=file_path_str)
+
+            self._write_batches(session, """
+                UNWIND $rows AS row
+                MATCH (fn:Function {name: row.func_name, file_path: $file_path, line_number: row.line_number})
+                MERGE (p:Parameter {name: row.arg_name, file_path: $file_path, function_line_number: row.line_number})
+                MERGE (fn)-[:HAS_PARAMETER]->(p)
+            """, [{"func_name": item['name'], "line_number": item['line_number'], "arg_name": arg_name}
+                  for item in file_data['functions'] if item['uid'] not in unchanged for arg_name in item.get('args', [])],
+                file_path=file_path_str)
 
             # Create CONTAINS relationships for nested functions
-            for item in file_data.get('functions', []):
-                if item.get("context_type") == "function_definition":
-                    session.run("""
-                        MATCH (outer:Function {name: $context, file_path: $file_path})
-                        MATCH (inner:Function {name: $name, file_path: $file_path, line_number: $line_number})
-                        MERGE (outer)-[:CONTAINS]->(inner)
-                    """, context=item["context"], file_path=file_path_str, name=item["name"], line_number=item["line_number"])
+            self._write_batches(session, """
+                UNWIND $rows AS row
+                MATCH (outer:Function {name: row.context, file_path: $file_path})
+                MATCH (inner:Function {name: row.name, file_path: $file_path, line_number: row.line_number})
+                MERGE (outer)-[:CONTAINS]->(inner)
+            """, [{"context": item["context"], "name": item["name"], "line_number": item["line_number"]}
+                  for item in file_data.get('functions', []) if item.get("context_type") in ("function_definition", "function_item")],
+                file_path=file_path_str)
 
             # Handle imports and create IMPORTS relationships
-            for imp in file_data.get('imports', []):
-                logger.info(f"Processing import: {imp}")
-                lang = file_data.get('lang')
-                if lang == 'javascript':
-                    # New, correct logic for JS
-                    module_name = imp.get('source')
-                    if not module_name: continue
-
-                    # Use a map for relationship properties to handle optional alias
-                    rel_props = {'imported_name': imp.get('name', '*')}
-                    if imp.get('alias'):
-                        rel_props['alias'] = imp.get('alias')
-
-                    session.run("""
-                        MATCH (f:File {path: $file_path})
-                        MERGE (m:Module {name: $module_name})
-                        MERGE (f)-[r:IMPORTS]->(m)
-                        SET r += $props
-                    """, file_path=file_path_str, module_name=module_name, props=rel_props)
-                else:
-                    # Existing logic for Python (and other languages)
-                    set_clauses = ["m.alias = $alias"]
-                    if 'full_import_name' in imp:
-                        set_clauses.append("m.full_import_name = $full_import_name")
-                    set_clause_str = ", ".join(set_clauses)
-
-                    session.run(f"""
-                        MATCH (f:File {{path: $file_path}})
-                        MERGE (m:Module {{name: $name}})
-                        SET {set_clause_str}
-                        MERGE (f)-[:IMPORTS]->(m)
-                    """, file_path=file_path_str, **imp)
+            if file_data.get('lang') == 'javascript':
+                # Relationship properties hold the imported name and its optional alias
+                rows = [{"module_name": imp['source'],
+                         "props": {'imported_name': imp.get('name', '*'), **({'alias': imp['alias']} if imp.get('alias') else {})}}
+                        for imp in file_data.get('imports', []) if imp.get('source')]
+                self._write_batches(session, """
+                    UNWIND $rows AS row
+                    MATCH (f:File {path: $file_path})
+                    MERGE (m:Module {name: row.module_name})
+                    MERGE (f)-[r:IMPORTS]->(m)
+                    SET r += row.props
+                """, rows, file_path=file_path_str)
+            else:
+                # Keep where and how each file imports the module, e.g. Rust `pub use` re-exports.
+                rows = [{"name": imp['name'], "alias": imp.get('alias'), "full_import_name": imp.get('full_import_name'),
+                         "rel_props": {key: imp[key] for key in ('line_number', 'full_import_name', 'is_reexport', 'alias') if key in imp}}
+                        for imp in file_data.get('imports', [])]
+                self._write_batches(session, """
+                    UNWIND $rows AS row
+                    MATCH (f:File {path: $file_path})
+                    MERGE (m:Module {name: row.name})
+                    SET m.alias = row.alias, m.full_import_name = coalesce(row.full_import_name, m.full_import_name)
+                    MERGE (f)-[r:IMPORTS]->(m)
+                    SET r += row.rel_props
+                """, rows, file_path=file_path_str)
 
             # Handle CONTAINS relationship between class to their children like variables
-            for func in file_data.get('functions', []):
-                if func.get('class_context'):
-                    session.run("""
-                        MATCH (c:Class {name: $class_name, file_path: $file_path})
-                        MATCH (fn:Function {name: $func_name, file_path: $file_path, line_number: $func_line})
-                        MERGE (c)-[:CONTAINS]->(fn)
-                    """, 
-                    class_name=func['class_context'],
-                    file_path=file_path_str,
-                    func_name=func['name'],
-                    func_line=func['line_number'])
+            # (Rust methods belong to their impl block, see _create_impl_nodes.)
+            self._write_batches(session, """
+                UNWIND $rows AS row
+                MATCH (c:Class {name: row.class_name, file_path: $file_path})
+                MATCH (fn:Function {name: row.func_name, file_path: $file_path, line_number: row.func_line})
+                MERGE (c)-[:CONTAINS]->(fn)
+            """, [{"class_name": func['class_context'], "func_name": func['name'], "func_line": func['line_number']}
+                  for func in file_data.get('functions', []) if func.get('class_context') and not func.get('impl_line')],
+                file_path=file_path_str)
+
+            self._create_file_extras(session, file_data, file_path_str)
 
             # Class inheritance is handled in a separate pass after all files are processed.
             # Function calls are also handled in a separate pass after all files are processed.
 
+    def _create_file_extras(self, session, file_data: Dict, file_path_str: str):
+        """Creates the language-specific nodes and links of a file whose items are already in the graph."""
+        self._create_channel_links(session, file_data, file_path_str)
+        self._create_shared_state_links(session, file_data, file_path_str)
+        self._create_await_lock_links(session, file_data, file_path_str)
+        self._create_lock_order_links(session, file_data, file_path_str)
+        self._create_blocking_call_links(session, file_data, file_path_str)
+        self._create_enum_variant_nodes(session, file_data, file_path_str)
+        self._create_field_nodes(session, file_data, file_path_str)
+        self._create_interior_mutability_links(session, file_data, file_path_str)
+        self._create_closure_nodes(session, file_data, file_path_str)
+        self._create_impl_nodes(session, file_data, file_path_str)
+        self._create_macro_nodes(session, file_data, file_path_str)
+        self._create_task_nodes(session, file_data, file_path_str)
+        self._create_literal_links(session, file_data, file_path_str)
+
+    def _create_channel_links(self, session, file_data: Dict, file_path_str: str):
+        """Creates Channel nodes and the SENDS_TO/RECEIVES_FROM edges of their producers and consumers."""
+        for channel in file_data.get('channels', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (ch:Channel {name: $name, file_path: $file_path, line_number: $line_number})
+                SET ch.constructor = $constructor, ch.sender = $sender, ch.receiver = $receiver,
+                    ch.created_in = $created_in, ch.lang = $lang
+                MERGE (f)-[:CONTAINS]->(ch)
+            """, file_path=file_path_str, name=channel['name'], line_number=channel['line_number'],
+                constructor=channel['constructor'], sender=channel['sender'], receiver=channel['receiver'],
+                created_in=channel['created_in'], lang=channel['lang'])
+
+            for rel_type, usages in (('SENDS_TO', channel['senders']), ('RECEIVES_FROM', channel['receivers'])):
+                for usage in usages:
+                    session.run(f"""
+                        MATCH (fn:Function {{name: $func_name, file_path: $file_path, line_number: $func_line}})
+                        MATCH (ch:Channel {{name: $name, file_path: $file_path, line_number: $line_number}})
+                        MERGE (fn)-[:{rel_type} {{line_number: $usage_line}}]->(ch)
+                    """, func_name=usage['function'], func_line=usage['function_line'], file_path=file_path_str,
+                        name=channel['name'], line_number=channel['line_number'], usage_line=usage['line_number'])
+
+    def _create_shared_state_links(self, session, file_data: Dict, file_path_str: str):
+        """Creates Lock nodes for Mutex/RwLock/atomic fields and statics, with GUARDS and LOCKS edges."""
+        for state in file_data.get('shared_state', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (l:Lock {name: $name, file_path: $file_path, line_number: $line_number})
+                SET l.kind = $kind, l.type = $type, l.guarded_type = $guarded_type,
+                    l.field = $field, l.owner = $owner, l.owner_kind = $owner_kind, l.lang = $lang
+                MERGE (f)-[:CONTAINS]->(l)
+            """, file_path=file_path_str, name=state['name'], line_number=state['line_number'],
+                kind=state['kind'], type=state['type'], guarded_type=state['guarded_type'],
+                field=state['field'], owner=state['owner'], owner_kind=state['owner_kind'], lang=state['lang'])
+
+            if state['owner_kind'] == 'field':
+                session.run("""
+                    MATCH (l:Lock {name: $name, file_path: $file_path, line_number: $line_number})
+                    MATCH (c:Class {name: $owner, file_path: $file_path})
+                    MERGE (l)-[:GUARDS {field: $field}]->(c)
+                """, name=state['name'], file_path=file_path_str, line_number=state['line_number'],
+                    owner=state['owner'], field=state['field'])
+            else:
+                session.run("""
+                    MATCH (l:Lock {name: $name, file_path: $file_path, line_number: $line_number})
+                    MATCH (v:Variable {name: $field, file_path: $file_path, line_number: $line_number})
+                    MERGE (l)-[:GUARDS]->(v)
+                """, name=state['name'], file_path=file_path_str, line_number=state['line_number'],
+                    field=state['field'])
+
+            for access in state['accesses']:
+                session.run("""
+                    MATCH (fn:Function {name: $func_name, file_path: $file_path, line_number: $func_line})
+                    MATCH (l:Lock {name: $name, file_path: $file_path, line_number: $line_number})
+                    MERGE (fn)-[:LOCKS {line_number: $access_line, mode: $mode}]->(l)
+                """, func_name=access['function'], func_line=access['function_line'], file_path=file_path_str,
+                    name=state['name'], line_number=state['line_number'],
+                    access_line=access['line_number'], mode=access['mode'])
+
+    def _create_await_lock_links(self, session, file_data: Dict, file_path_str: str):
+        """Records lock guards held across `.await` on the async function and as HOLDS_LOCK_ACROSS_AWAIT edges to known Locks."""
+        findings_by_function = {}
+        for finding in file_data.get('await_holding_locks', []):
+            if finding['function'] is None:
+                continue
+            guard = f" (guard `{finding['guard']}`)" if finding['guard'] else ""
+            findings_by_function.setdefault((finding['function'], finding['function_line']), []).append(
+                f"`{finding['lock']}` locked at line {finding['lock_line']}{guard} is held across "
+                f"`{finding['await_expression']}` at line {finding['await_line']}")
+            if finding['lock_name']:
+                session.run("""
+                    MATCH (fn:Function {name: $func_name, file_path: $file_path, line_number: $func_line})
+                    MATCH (l:Lock {name: $lock_name, file_path: $file_path})
+                    MERGE (fn)-[r:HOLDS_LOCK_ACROSS_AWAIT {lock_line: $lock_line, await_line: $await_line}]->(l)
+                    SET r.guard = $guard, r.await_span = $await_span
+                """, func_name=finding['function'], func_line=finding['function_line'], file_path=file_path_str,
+                    lock_name=finding['lock_name'], lock_line=finding['lock_line'], await_line=finding['await_line'],
+                    guard=finding['guard'], await_span=finding['await_span'])
+
+        for (func_name, func_line), findings in findings_by_function.items():
+            session.run("""
+                MATCH (fn:Function {name: $func_name, file_path: $file_path, line_number: $func_line})
+                SET fn.await_holding_lock = $findings
+            """, func_name=func_name, func_line=func_line, file_path=file_path_str, findings=findings)
+
+    def _create_lock_order_links(self, session, file_data: Dict, file_path_str: str):
+        """Creates Lock-[:ACQUIRED_BEFORE]->Lock edges for locks taken while another guard of the same function is held."""
+        for region in file_data.get('lock_regions', []):
+            for nested in region['nested']:
+                session.run("""
+                    MATCH (a:Lock {name: $lock, file_path: $file_path})
+                    MATCH (b:Lock {name: $nested_lock, file_path: $file_path})
+                    MERGE (a)-[r:ACQUIRED_BEFORE {function: $function, file_path: $file_path, line_number: $line_number}]->(b)
+                    SET r.held_since = $held_since, r.via = [$function]
+                """, lock=region['lock'], nested_lock=nested['lock'], file_path=file_path_str, function=region['function'],
+                    line_number=nested['line_number'], held_since=region['line_number'])
+
+    def _create_blocking_call_links(self, session, file_data: Dict, file_path_str: str):
+        """Creates one BlockingCall node per blocking API and BLOCKS_ON edges from the functions calling it."""
+        for call in file_data.get('blocking_calls', []):
+            session.run("""
+                MATCH (fn:Function {name: $func_name, file_path: $file_path, line_number: $func_line})
+                MERGE (b:BlockingCall {name: $call})
+                SET b.category = $category
+                MERGE (fn)-[r:BLOCKS_ON {line_number: $line_number}]->(b)
+                SET r.in_async = $in_async
+            """, func_name=call['function'], func_line=call['function_line'], file_path=file_path_str,
+                call=call['call'], category=call['category'], line_number=call['line_number'], in_async=call['in_async'])
+
+    def _create_enum_variant_nodes(self, session, file_data: Dict, file_path_str: str):
+        """Creates a Variant node for every enum variant and links it to its enum with VARIANT_OF."""
+        for variant in file_data.get('enum_variants', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (v:Variant {name: $name, file_path: $file_path, line_number: $line_number})
+                SET v += $props
+                MERGE (f)-[:CONTAINS]->(v)
+                WITH v
+                MATCH (e:Class {name: $enum, file_path: $file_path})
+                WHERE e.kind = 'enum'
+                MERGE (v)-[:VARIANT_OF]->(e)
+            """, file_path=file_path_str, name=variant['name'], line_number=variant['line_number'],
+                enum=variant['enum'], props=variant)
+
+    def _create_field_nodes(self, session, file_data: Dict, file_path_str: str):
+        """Creates a Field node for every struct field and links it to its owner with HAS_FIELD."""
+        for field in file_data.get('fields', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (fd:Field {name: $name, owner: $owner, file_path: $file_path, line_number: $line_number})
+                SET fd += $props
+                MERGE (f)-[:CONTAINS]->(fd)
+                WITH fd
+                MATCH (c:Class {name: $owner, file_path: $file_path})
+                MERGE (c)-[:HAS_FIELD]->(fd)
+            """, file_path=file_path_str, name=field['name'], owner=field['owner'],
+                line_number=field['line_number'], props=field)
+
+    def _create_interior_mutability_links(self, session, file_data: Dict, file_path_str: str):
+        """Marks Cell/RefCell/atomic fields and statics with their cell kind and links the functions accessing them with BORROWS."""
+        for cell in file_data.get('interior_mutability', []):
+            if cell['owner_kind'] == 'field':
+                target = "(t:Field {name: $field, owner: $owner, file_path: $file_path, line_number: $line_number})"
+            else:
+                target = "(t:Variable {name: $field, file_path: $file_path, line_number: $line_number})"
+            session.run(f"""
+                MATCH {target}
+                SET t.interior_mutability = $kind
+            """, field=cell['field'], owner=cell['owner'], file_path=file_path_str,
+                line_number=cell['line_number'], kind=cell['kind'])
+            for access in cell['accesses']:
+                session.run(f"""
+                    MATCH (fn:Function {{name: $func_name, file_path: $file_path, line_number: $func_line}})
+                    MATCH {target}
+                    MERGE (fn)-[r:BORROWS {{line_number: $access_line}}]->(t)
+                    SET r.method = $method, r.mode = $mode, r.mutable = $mutable
+                """, func_name=access['function'], func_line=access['function_line'], field=cell['field'],
+                    owner=cell['owner'], file_path=file_path_str, line_number=cell['line_number'],
+                    access_line=access['line_number'], method=access['method'], mode=access['mode'],
+                    mutable=access['mutable'])
+
+    def _create_closure_nodes(self, session, file_data: Dict, file_path_str: str):
+        """Creates a Closure node for every closure expression, contained by its enclosing function."""
+        for closure in file_data.get('closures', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (cl:Closure {name: $name, file_path: $file_path, line_number: $line_number})
+                SET cl += $props
+                MERGE (f)-[:CONTAINS]->(cl)
+            """, file_path=file_path_str, name=closure['name'], line_number=closure['line_number'], props=closure)
+            if closure['context']:
+                session.run("""
+                    MATCH (fn:Function {name: $context, file_path: $file_path, line_number: $context_line})
+                    MATCH (cl:Closure {name: $name, file_path: $file_path, line_number: $line_number})
+                    MERGE (fn)-[:CONTAINS]->(cl)
+                """, context=closure['context'], context_line=closure['context_line'], file_path=file_path_str,
+                    name=closure['name'], line_number=closure['line_number'])
+
+            for place, mode, reason, binding_line in zip(closure['captured_places'], closure['capture_modes'],
+                                                         closure['capture_reasons'], closure['capture_binding_lines']):
+                root, _, field = place.partition('.')
+                if root == 'self':
+                    if not field or not closure['owner']:
+                        continue
+                    target = "(t:Field {name: $target, owner: $owner})"
+                    target_name = field.split('.')[0]
+                else:
+                    target = "(t:Variable {name: $target, file_path: $file_path, line_number: $binding_line})"
+                    target_name = root
+                session.run(f"""
+                    MATCH (cl:Closure {{name: $name, file_path: $file_path, line_number: $line_number}})
+                    MATCH {target}
+                    MERGE (cl)-[r:CAPTURES {{place: $place}}]->(t)
+                    SET r.mode = $mode, r.reason = $reason
+                """, name=closure['name'], file_path=file_path_str, line_number=closure['line_number'], target=target_name,
+                    owner=closure['owner'], binding_line=binding_line, place=place, mode=mode, reason=reason)
+
+    def _create_impl_nodes(self, session, file_data: Dict, file_path_str: str):
+        """Creates an Impl node per `impl` block with its methods (CONTAINS) and associated type bindings (BINDS_TYPE)."""
+        for impl in file_data.get('impls', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (i:Impl {name: $name, file_path: $file_path, line_number: $line_number})
+                SET i += $props
+                MERGE (f)-[:CONTAINS]->(i)
+            """, file_path=file_path_str, name=impl['name'], line_number=impl['line_number'], props=impl)
+
+        for associated_type in file_data.get('associated_types', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (at:AssociatedType {name: $name, owner: $owner, file_path: $file_path, line_number: $line_number})
+                SET at += $props
+                MERGE (f)-[:CONTAINS]->(at)
+            """, file_path=file_path_str, name=associated_type['name'], owner=associated_type['owner'],
+                line_number=associated_type['line_number'], props=associated_type)
+            if associated_type['owner_kind'] == 'trait':
+                session.run("""
+                    MATCH (t:Class {name: $owner, file_path: $file_path})
+                    MATCH (at:AssociatedType {name: $name, owner: $owner, file_path: $file_path, line_number: $line_number})
+                    MERGE (t)-[:DECLARES_TYPE]->(at)
+                """, owner=associated_type['owner'], file_path=file_path_str, name=associated_type['name'],
+                    line_number=associated_type['line_number'])
+            else:
+                session.run("""
+                    MATCH (i:Impl {file_path: $file_path, line_number: $impl_line})
+                    MATCH (at:AssociatedType {name: $name, owner: $owner, file_path: $file_path, line_number: $line_number})
+                    MERGE (i)-[:BINDS_TYPE]->(at)
+                """, impl_line=associated_type['impl_line'], owner=associated_type['owner'], file_path=file_path_str,
+                    name=associated_type['name'], line_number=associated_type['line_number'])
+
+        for func in file_data.get('functions', []):
+            if not func.get('impl_line'):
+                continue
+            session.run("""
+                MATCH (i:Impl {file_path: $file_path, line_number: $impl_line})
+                MATCH (fn:Function {name: $func_name, file_path: $file_path, line_number: $func_line})
+                MERGE (i)-[:CONTAINS]->(fn)
+            """, file_path=file_path_str, impl_line=func['impl_line'], func_name=func['name'], func_line=func['line_number'])
+
     # Second pass to create relationships that depend on all files being present like call functions and class inheritance
+    def _create_macro_nodes(self, session, file_data: Dict, file_path_str: str):
+        """Creates Macro nodes for `macro_rules!` definitions and GENERATED_BY edges from the items they expand to."""
+        for macro in file_data.get('macros', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (mc:Macro {name: $name, file_path: $file_path, line_number: $line_number})
+                SET mc += $props
+                MERGE (f)-[:CONTAINS]->(mc)
+            """, file_path=file_path_str, name=macro['name'], line_number=macro['line_number'], props=macro)
+
+        for items, label in ((file_data.get('functions', []), 'Function'), (file_data.get('classes', []), 'Class')):
+            for item in items:
+                if not item.get('generated_by'):
+                    continue
+                session.run(f"""
+                    MATCH (n:{label} {{name: $name, file_path: $file_path, line_number: $line_number}})
+                    MATCH (mc:Macro {{name: $macro, file_path: $file_path}})
+                    MERGE (n)-[:GENERATED_BY]->(mc)
+                """, name=item['name'], file_path=file_path_str, line_number=item['line_number'], macro=item['generated_by'])
+
+    def _create_task_nodes(self, session, file_data: Dict, file_path_str: str):
+        """Creates Task nodes for TODO/FIXME/HACK comments and links the functions they sit in with HAS_TASK."""
+        for task in file_data.get('tasks', []):
+            session.run("""
+                MATCH (f:File {path: $file_path})
+                MERGE (t:Task {file_path: $file_path, line_number: $line_number})
+                SET t += $props
+                MERGE (f)-[:CONTAINS]->(t)
+                WITH t
+                MATCH (fn:Function {name: $function, file_path: $file_path, line_number: $function_line})
+                MERGE (fn)-[:HAS_TASK]->(t)
+            """, file_path=file_path_str, line_number=task['line_number'], function=task['function'],
+                function_line=task['function_line'], props=task)
+
+    def _create_literal_links(self, session, file_data: Dict, file_path_str: str):
+        """
+        Links functions to the notable literals in their bodies with CONTAINS_LITERAL; literals of
+        `const` and `static` values hang off the file, with the constant's name on the edge.
+        """
+        session.run("""
+            UNWIND $literals as literal
+            MERGE (l:Literal {value: literal.value, kind: literal.kind})
+            WITH l, literal
+            MATCH (f:File {path: $file_path})
+            OPTIONAL MATCH (fn:Function {name: literal.function, file_path: $file_path, line_number: literal.function_line})
+            WITH l, literal, coalesce(fn, f) as holder
+            MERGE (holder)-[r:CONTAINS_LITERAL {line_number: literal.line_number}]->(l)
+            SET r.in_macro = literal.in_macro, r.constant = literal.constant
+        """, literals=file_data.get('literals', []), file_path=file_path_str)
+
     def _create_function_calls(self, session, file_data: Dict, imports_map: dict):
-        """Create CALLS relationships with a unified, prioritized logic flow for all call types."""
+        """
+        Create CALLS relationships with a unified, prioritized logic flow for all call types. Calls
+        that resolve to no indexed function get a REFERENCES_UNRESOLVED edge to a placeholder
+        (see `_resolve_placeholders`) instead.
+        """
         caller_file_path = str(Path(file_data['file_path']).resolve())
         local_function_names = {func['name'] for func in file_data.get('functions', [])}
         local_imports = {imp.get('alias') or imp['name'].split('.')[-1]: imp['name'] 
                         for imp in file_data.get('imports', [])}
-        
+        closure_calls, function_calls, file_calls = [], [], []
+        # Python's builtins are not indexed; a Rust method may share a name with one (`len`, `map`, `sum`).
+        skipped_names = __builtins__ if file_data.get('lang') == 'python' else ()
+
         for call in file_data.get('function_calls', []):
             called_name = call['name']
-            if called_name in __builtins__: continue
+            if called_name in skipped_names: continue
 
-            resolved_path = None
+            resolved_path, external = None, False
             
             if call.get('inferred_obj_type'):
                 obj_type = call['inferred_obj_type']
                 possible_paths = imports_map.get(obj_type, [])
                 if len(possible_paths) > 0:
                     resolved_path = possible_paths[0]
+                else:
+                    # The receiver type is not part of the indexed code (e.g. `Arc::new`), maybe of a crate indexed later.
+                    external = True
             
             else:
                 lookup_name = call['full_name'].split('.')[0] if '.' in call['full_name'] else called_name
@@ -293,46 +754,237 @@ class GraphBuilder:
                             resolved_path = path
                             break
             
-            if not resolved_path:
+            if not resolved_path and not external:
                 if called_name in imports_map and imports_map[called_name]:
                     resolved_path = imports_map[called_name][0]
                 else:
                     resolved_path = caller_file_path
 
+            row = {
+                "called_name": called_name,
+                "called_file_path": resolved_path,
+                "line_number": call['line_number'],
+                "column": call.get('column', 0),
+                "span": {key: call.get(key) for key in CALL_SPAN_KEYS},
+                "args": call.get('args', []),
+                "full_call_name": call.get('full_name', called_name),
+                "obj_type": call.get('inferred_obj_type'),
+                "kind": 'method_call' if '.' in call.get('full_name', called_name) else 'call',
+                "owner": (call.get('inferred_obj_type') or '').split('::')[-1],
+            }
+            if call.get('closure_context'):
+                closure_calls.append({**row, "closure_name": call['closure_context']})
+
             caller_context = call.get('context')
             if caller_context and len(caller_context) == 3 and caller_context[0] is not None:
-                caller_name, _, caller_line_number = caller_context
-                session.run("""
-                    MATCH (caller:Function {name: $caller_name, file_path: $caller_file_path, line_number: $caller_line_number})
-                    MATCH (called:Function {name: $called_name, file_path: $called_file_path})
-                    MERGE (caller)-[:CALLS {line_number: $line_number, args: $args, full_call_name: $full_call_name}]->(called)
-                """,
-                caller_name=caller_name,
-                caller_file_path=caller_file_path,
-                caller_line_number=caller_line_number,
-                called_name=called_name,
-                called_file_path=resolved_path,
-                line_number=call['line_number'],
-                args=call.get('args', []),
-                full_call_name=call.get('full_name', called_name))
+                function_calls.append({**row, "caller_name": caller_context[0], "caller_line_number": caller_context[2]})
             else:
-                session.run("""
-                    MATCH (caller:File {path: $caller_file_path})
-                    MATCH (called:Function {name: $called_name, file_path: $called_file_path})
-                    MERGE (caller)-[:CALLS {line_number: $line_number, args: $args, full_call_name: $full_call_name}]->(called)
-                """,
-                caller_file_path=caller_file_path,
-                called_name=called_name,
-                called_file_path=resolved_path,
-                line_number=call['line_number'],
-                args=call.get('args', []),
-                full_call_name=call.get('full_name', called_name))
+                file_calls.append(row)
+
+        callee = """
+            OPTIONAL MATCH (called:Function {name: row.called_name, file_path: row.called_file_path})
+            WHERE row.obj_type IS NULL OR called.class_context = row.obj_type
+               OR NOT EXISTS { MATCH (m:Function {name: row.called_name, file_path: row.called_file_path}) WHERE m.class_context = row.obj_type }
+            WITH caller, row, collect(called) as targets
+            FOREACH (called IN targets |
+                MERGE (caller)-[r:CALLS {line_number: row.line_number, column: row.column, args: row.args, full_call_name: row.full_call_name}]->(called)
+                SET r += row.span)
+            FOREACH (_ IN CASE WHEN size(targets) = 0 THEN [1] ELSE [] END |
+                MERGE (u:UnresolvedReference {kind: row.kind, name: row.called_name, owner: row.owner})
+                MERGE (caller)-[r:REFERENCES_UNRESOLVED {line_number: row.line_number, column: row.column, full_call_name: row.full_call_name}]->(u)
+                SET r += row.span, r.args = row.args)
+            WITH caller, row, targets WHERE size(targets) > 0
+            OPTIONAL MATCH (caller)-[stale:REFERENCES_UNRESOLVED {line_number: row.line_number, column: row.column}]->()
+            DELETE stale
+        """
+        self._write_batches(session, """
+            UNWIND $rows AS row
+            MATCH (caller:Closure {name: row.closure_name, file_path: $caller_file_path})
+        """ + callee, closure_calls, caller_file_path=caller_file_path)
+        self._write_batches(session, """
+            UNWIND $rows AS row
+            MATCH (caller:Function {name: row.caller_name, file_path: $caller_file_path, line_number: row.caller_line_number})
+        """ + callee, function_calls, caller_file_path=caller_file_path)
+        self._write_batches(session, """
+            UNWIND $rows AS row
+            MATCH (caller:File {path: $caller_file_path})
+        """ + callee, file_calls, caller_file_path=caller_file_path)
+
+    def _record_call_sites(self, session, file_data: Dict):
+        """
+        Lists every call a function makes in `call_names`, with `call_lines`, including calls to code
+        outside the graph; method calls on a known receiver type are named `Type::method`.
+        """
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        sites = {}
+        for call in file_data.get('function_calls', []):
+            context = call.get('context')
+            if not context or context[0] is None or len(context) != 3:
+                continue
+            name = f"{call['inferred_obj_type']}::{call['name']}" if call.get('inferred_obj_type') else call.get('full_name', call['name'])
+            site = sites.setdefault((context[0], context[2]), {"names": [], "lines": []})
+            site["names"].append(name)
+            site["lines"].append(call['line_number'])
+        self._write_batches(session, """
+            UNWIND $rows AS row
+            MATCH (f:Function {name: row.name, file_path: $file_path, line_number: row.line_number})
+            SET f.call_names = row.names, f.call_lines = row.lines
+        """, [{"name": name, "line_number": line_number, **site} for (name, line_number), site in sites.items()],
+            file_path=file_path_str)
+
+    def _weigh_calls(self, session, file_data: Dict):
+        """
+        Sets on the CALLS edges of a file's callers how often each caller calls the same callee:
+        `call_count` and `call_sites`, the spans of all those calls as `line:column-line:column`
+        (lines 1-based, columns 0-based) in file order. Every edge of a pair carries the pair's totals.
+        """
+        session.run("""
+            MATCH (caller)-[r:CALLS]->(callee)
+            WHERE coalesce(caller.file_path, caller.path) = $file_path
+            WITH caller, callee, r ORDER BY coalesce(r.start_byte, 0), r.line_number, r.column
+            WITH caller, callee, collect(r) as calls
+            WITH calls, [c IN calls | coalesce(
+                     toString(c.start_line) + ':' + toString(c.start_column) + '-' + toString(c.end_line) + ':' + toString(c.end_column),
+                     toString(c.line_number))] as sites
+            FOREACH (c IN calls | SET c.call_count = size(calls), c.call_sites = sites)
+        """, file_path=str(Path(file_data['file_path']).resolve()))
+
+    def _resolve_placeholders(self) -> set:
+        """
+        Patches placeholders left by calls that did not resolve when their file was linked, e.g.
+        into a crate indexed later: a placeholder (`UnresolvedReference {kind, name, owner}`, where
+        `kind` is `call` or `method_call` and `owner` the receiver type or '') whose name and owner
+        now match exactly one function has its REFERENCES_UNRESOLVED edges turned into CALLS edges
+        marked `deferred`. Method calls on receivers of unknown type stay unresolved, and
+        placeholders nothing references any more are deleted. Returns the files of the patched callers.
+        """
+        with self.driver.session() as session:
+            # A revision snapshot being indexed (see `index_revision`) neither resolves nor is resolved to.
+            patched = {record['path'] for record in session.run("""
+                MATCH (u:UnresolvedReference)
+                WHERE u.kind = 'call' OR u.owner <> ''
+                MATCH (f:Function {name: u.name})
+                WHERE ((u.owner = '' AND f.class_context IS NULL) OR f.class_context = u.owner)
+                  AND NOT f.file_path STARTS WITH $snapshots
+                WITH u, collect(f) as candidates
+                WHERE size(candidates) = 1
+                MATCH (caller)-[p:REFERENCES_UNRESOLVED]->(u)
+                WHERE NOT coalesce(caller.file_path, caller.path) STARTS WITH $snapshots
+                WITH candidates[0] as target, caller, p
+                MERGE (caller)-[r:CALLS {line_number: p.line_number, column: p.column, args: coalesce(p.args, []),
+                                         full_call_name: p.full_call_name}]->(target)
+                SET r += properties(p), r.deferred = true
+                DELETE p
+                RETURN DISTINCT coalesce(caller.file_path, caller.path) as path
+            """, snapshots=str(REVISIONS_DIR.resolve()) + os.sep)}
+            session.run("""
+                MATCH (u:UnresolvedReference)
+                WHERE NOT EXISTS { MATCH ()-[:REFERENCES_UNRESOLVED]->(u) }
+                DELETE u
+            """)
+        return patched
+
+    def _weigh_all_calls(self, all_file_data: list[Dict]):
+        """Weigh CALLS edges once call sites are final (after method shadowing is resolved)."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._weigh_calls(session, file_data)
 
     def _create_all_function_calls(self, all_file_data: list[Dict], imports_map: dict):
         """Create CALLS relationships for all functions after all files have been processed."""
         with self.driver.session() as session:
             for file_data in all_file_data:
                 self._create_function_calls(session, file_data, imports_map)
+                self._record_call_sites(session, file_data)
+
+    def _mark_discarded_calls(self, session, file_data: Dict):
+        """Records on CALLS edges how the returned value is thrown away (`discarded`: statement, let_underscore or ok_discarded)."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        rows = [{"caller_name": call['context'][0], "caller_line": call['context'][2], "line_number": call['line_number'],
+                 "called_name": call['name'], "discarded": call['discarded']}
+                for call in file_data.get('function_calls', [])
+                if call.get('discarded') and call.get('context') and call['context'][0] is not None]
+        self._write_batches(session, """
+            UNWIND $rows AS row
+            MATCH (caller:Function {name: row.caller_name, file_path: $file_path, line_number: row.caller_line})
+                  -[c:CALLS {line_number: row.line_number}]->(called:Function {name: row.called_name})
+            SET c.discarded = row.discarded
+        """, rows, file_path=file_path_str)
+
+    def _mark_all_discarded_calls(self, all_file_data: list[Dict]):
+        """Mark discarded call results after all CALLS relationships have been created."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._mark_discarded_calls(session, file_data)
+
+    def _mark_method_shadowing(self):
+        """
+        Finds Rust types with an inherent method named like a method of a trait they implement
+        (their own `add` next to `impl Add`). The inherent method wins for `value.add(..)` and
+        `Type::add(..)`, the trait one only when the call names the trait (`Add::add(..)`,
+        `<Type as Add>::add(..)`): call sites resolved to both keep only the winning CALLS edge,
+        marked with `shadowing` (`inherent` or `trait`) and the losing candidate in `ambiguous_with`.
+        The inherent method lists the traits it shadows in `shadows_traits`.
+        """
+        with self.driver.session() as session:
+            pairs = [dict(record) for record in session.run("""
+                MATCH (c:Class)-[:HAS_IMPL]->(ii:Impl)-[:CONTAINS]->(inherent:Function)
+                WHERE ii.trait IS NULL
+                MATCH (c)-[:HAS_IMPL]->(ti:Impl)
+                WHERE ti.trait IS NOT NULL
+                OPTIONAL MATCH (ti)-[:CONTAINS]->(own:Function {name: inherent.name})
+                OPTIONAL MATCH (ti)-[:IMPLEMENTS]->(:Class)-[:CONTAINS]->(provided:Function {name: inherent.name})
+                WITH c, inherent, ti, own, provided
+                WHERE own IS NOT NULL OR provided IS NOT NULL
+                RETURN c.name as type_name, inherent.name as method, elementId(inherent) as inherent_id,
+                       ti.trait as trait, elementId(own) as trait_method_id
+            """)]
+
+            shadowed = {}
+            for pair in pairs:
+                shadowed.setdefault(pair['inherent_id'], set()).add(pair['trait'])
+                trait_name = pair['trait'].split('::')[-1]
+                ids = [pair['inherent_id']] + ([pair['trait_method_id']] if pair['trait_method_id'] else [])
+                sites = {}
+                for record in session.run("""
+                    MATCH (caller)-[r:CALLS]->(m:Function)
+                    WHERE elementId(m) IN $ids
+                    RETURN elementId(r) as id, elementId(caller) as caller, elementId(m) as target,
+                           r.line_number as line_number, r.column as column, coalesce(r.full_call_name, '') as call
+                """, ids=ids):
+                    sites.setdefault((record['caller'], record['line_number'], record['column'], record['call']), []).append(dict(record))
+
+                for (caller, _, column, call), edges in sites.items():
+                    segments = re.sub(r'<[^<>]*>', '', call).split('::')
+                    names_trait = call.startswith('<') or trait_name in segments[:-1]
+                    if names_trait and not pair['trait_method_id']:
+                        continue  # resolved to the trait's own default method, not one of these
+                    winner = pair['trait_method_id'] if names_trait else pair['inherent_id']
+                    loser_name = f"{pair['type_name']}::{pair['method']}" if names_trait else f"{pair['trait']}::{pair['method']}"
+                    losing = [edge for edge in edges if edge['target'] != winner]
+                    if losing and not any(edge['target'] == winner for edge in edges):
+                        session.run("""
+                            MATCH (caller), (winner:Function), ()-[r:CALLS]->()
+                            WHERE elementId(caller) = $caller AND elementId(winner) = $winner AND elementId(r) = $edge
+                            CREATE (caller)-[n:CALLS]->(winner)
+                            SET n = properties(r)
+                        """, caller=caller, winner=winner, edge=losing[0]['id'])
+                    session.run("""
+                        MATCH ()-[r:CALLS]->() WHERE elementId(r) IN $ids DELETE r
+                    """, ids=[edge['id'] for edge in losing])
+                    session.run("""
+                        MATCH (caller)-[r:CALLS]->(winner:Function)
+                        WHERE elementId(caller) = $caller AND elementId(winner) = $winner
+                          AND r.line_number = $line_number AND r.column = $column AND coalesce(r.full_call_name, '') = $call
+                        SET r.shadowing = $shadowing, r.ambiguous_with = $loser
+                    """, caller=caller, winner=winner, line_number=edges[0]['line_number'], column=column, call=call,
+                        shadowing='trait' if names_trait else 'inherent', loser=loser_name)
+
+            for inherent_id, traits in shadowed.items():
+                session.run("""
+                    MATCH (f:Function) WHERE elementId(f) = $id
+                    SET f.shadows_traits = $traits
+                """, id=inherent_id, traits=sorted(traits))
 
     def _create_inheritance_links(self, session, file_data: Dict, imports_map: dict):
         """Create INHERITS relationships with a more robust resolution logic."""
@@ -403,7 +1055,586 @@ class GraphBuilder:
         with self.driver.session() as session:
             for file_data in all_file_data:
                 self._create_inheritance_links(session, file_data, imports_map)
+
+    def _resolve_symbol_path(self, name: str, file_data: Dict, imports_map: dict) -> Optional[str]:
+        """Resolves the file defining a type referenced from `file_data`: local definitions, then imports, then a unique global match."""
+        caller_file_path = str(Path(file_data['file_path']).resolve())
+        if not name:
+            return None
+        if name in {c['name'] for c in file_data.get('classes', [])}:
+            return caller_file_path
+
+        possible_paths = imports_map.get(name, [])
+        for imp in file_data.get('imports', []):
+            if name not in (imp.get('alias'), imp['name']):
+                continue
+            full_import_name = imp.get('full_import_name', imp['name'])
+            separator = '::' if '::' in full_import_name else '.'
+            module_parts = [part for part in full_import_name.split(separator)[:-1] if part not in ('crate', 'self', 'super')]
+            for path in possible_paths:
+                if not module_parts or '/'.join(module_parts) in path:
+                    return path
+
+        if len(possible_paths) == 1:
+            return possible_paths[0]
+        return None
+
+    def _create_pointer_wrapping_links(self, session, file_data: Dict, imports_map: dict):
+        """Records smart pointer fields on their owning types and links wrapped types with WRAPPED_IN."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        wrappings = file_data.get('pointer_wrappings', [])
+
+        owners = {}
+        for wrapping in wrappings:
+            owner = owners.setdefault(wrapping['owner'], {'fields': set(), 'wrappers': set(), 'shared': False, 'interior': False})
+            owner['fields'].add(wrapping['field'])
+            owner['wrappers'].update(wrapping['wrappers'])
+            owner['shared'] = owner['shared'] or wrapping['shared_ownership']
+            owner['interior'] = owner['interior'] or wrapping['interior_mutability']
+
+        for owner_name, owner in owners.items():
+            session.run("""
+                MATCH (c:Class {name: $owner, file_path: $file_path})
+                SET c.smart_pointer_fields = $fields, c.pointer_wrappers = $wrappers,
+                    c.shared_ownership = $shared, c.interior_mutability = $interior
+            """, owner=owner_name, file_path=file_path_str, fields=sorted(owner['fields']),
+                wrappers=sorted(owner['wrappers']), shared=owner['shared'], interior=owner['interior'])
+
+        for wrapping in wrappings:
+            resolved_path = self._resolve_symbol_path(wrapping['inner_base'], file_data, imports_map)
+            if not resolved_path:
+                continue
+            session.run("""
+                MATCH (owner:Class {name: $owner, file_path: $file_path})
+                MATCH (inner:Class {name: $inner, file_path: $inner_file_path})
+                MERGE (inner)-[r:WRAPPED_IN {owner: $owner, field: $field}]->(owner)
+                SET r.wrappers = $wrappers, r.type = $type, r.line_number = $line_number
+            """, owner=wrapping['owner'], file_path=file_path_str, inner=wrapping['inner_base'],
+                inner_file_path=resolved_path, field=wrapping['field'], wrappers=wrapping['wrappers'],
+                type=wrapping['type'], line_number=wrapping['line_number'])
+
+    def _create_variant_usage_links(self, session, file_data: Dict, imports_map: dict):
+        """Links functions to the enum variants they construct (CONSTRUCTS) or match on (MATCHES)."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        for usage in file_data.get('variant_usages', []):
+            resolved_path = self._resolve_symbol_path(usage['enum'], file_data, imports_map)
+            if not resolved_path:
+                continue
+            rel_type = 'MATCHES' if usage['usage'] == 'match' else 'CONSTRUCTS'
+            session.run(f"""
+                MATCH (fn:Function {{name: $func_name, file_path: $file_path, line_number: $func_line}})
+                MATCH (v:Variant {{name: $variant, enum: $enum, file_path: $enum_file_path}})
+                MERGE (fn)-[:{rel_type} {{line_number: $line_number}}]->(v)
+            """, func_name=usage['function'], file_path=file_path_str, func_line=usage['function_line'],
+                variant=usage['variant'], enum=usage['enum'], enum_file_path=resolved_path,
+                line_number=usage['line_number'])
+
+    def _create_all_variant_usage_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create CONSTRUCTS/MATCHES relationships for all enum variant usages after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_variant_usage_links(session, file_data, imports_map)
+
+    def _create_field_access_links(self, session, file_data: Dict, imports_map: dict):
+        """Links methods to the struct fields they read (READS_FIELD) or write (WRITES_FIELD)."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        for access in file_data.get('field_accesses', []):
+            resolved_path = self._resolve_symbol_path(access['owner'], file_data, imports_map)
+            if not resolved_path:
+                continue
+            rel_type = 'WRITES_FIELD' if access['mode'] == 'write' else 'READS_FIELD'
+            session.run(f"""
+                MATCH (fn:Function {{name: $func_name, file_path: $file_path, line_number: $func_line}})
+                MATCH (fd:Field {{name: $field, owner: $owner, file_path: $owner_file_path}})
+                MERGE (fn)-[:{rel_type} {{line_number: $line_number}}]->(fd)
+            """, func_name=access['function'], file_path=file_path_str, func_line=access['function_line'],
+                field=access['field'], owner=access['owner'], owner_file_path=resolved_path,
+                line_number=access['line_number'])
+
+    def _create_all_field_access_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create READS_FIELD/WRITES_FIELD relationships for all field accesses after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_field_access_links(session, file_data, imports_map)
+
+    def _create_type_reference_links(self, session, file_data: Dict, imports_map: dict):
+        """
+        Links functions, types (for their fields) or files to the project types they mention with
+        REFERENCES_TYPE, keeping the role of the mention (parameter, return, field, construction, ..).
+        """
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        for reference in file_data.get('type_references', []):
+            resolved_path = self._resolve_symbol_path(reference['type'], file_data, imports_map)
+            if not resolved_path:
+                continue
+            if reference['function']:
+                source = "MATCH (src:Function {name: $function, file_path: $file_path, line_number: $function_line})"
+            elif reference['owner']:
+                source = "MATCH (src:Class {name: $owner, file_path: $file_path})"
+            else:
+                source = "MATCH (src:File {path: $file_path})"
+            session.run(source + """
+                MATCH (t:Class {name: $type, file_path: $type_file_path})
+                MERGE (src)-[r:REFERENCES_TYPE {role: $role, line_number: $line_number}]->(t)
+                SET r.type_text = $type_text, r.generic_in = $generic_in
+            """, function=reference['function'], function_line=reference['function_line'], owner=reference['owner'],
+                file_path=file_path_str, type=reference['type'], type_file_path=resolved_path, role=reference['role'],
+                line_number=reference['line_number'], type_text=reference['type_text'], generic_in=reference['generic_in'])
+
+    def _create_all_type_reference_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create REFERENCES_TYPE relationships for all type mentions after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_type_reference_links(session, file_data, imports_map)
+
+    def _bind_instantiation(self, session, site: Dict, resolved_path: str):
+        """
+        Returns `(label, line_number, type_arguments)` of the generic item instantiated at `site`,
+        binding inferred sites against the item's declaration (unbound parameters become `_`).
+        """
+        from .languages import rust as rust_lang_module
+
+        if site['kind'] == 'explicit':
+            record = session.run("""
+                MATCH (n {name: $item, file_path: $file_path})
+                WHERE (n:Class OR n:Function) AND size(coalesce(n.generic_params, [])) > 0
+                RETURN CASE WHEN n:Class THEN 'Class' ELSE 'Function' END as label, n.line_number as line_number,
+                       n.generic_params as params
+                LIMIT 1
+            """, item=site['item'], file_path=resolved_path).single()
+            if record is None:
+                return None
+            return record['label'], record['line_number'], site['type_arguments'][:len(record['params'])]
+
+        if site['kind'] == 'call' and site['method'] is None:
+            record = session.run("""
+                MATCH (fn:Function {name: $item, file_path: $file_path})
+                WHERE size(coalesce(fn.generic_params, [])) > 0
+                RETURN fn.line_number as line_number, fn.generic_params as params, coalesce(fn.param_types, []) as param_types
+                LIMIT 1
+            """, item=site['item'], file_path=resolved_path).single()
+            if record is None:
+                return None
+            bindings = {}
+            for declared, concrete in zip(record['param_types'], site['arg_types']):
+                if concrete:
+                    rust_lang_module.bind_type_parameters(declared, concrete, record['params'], bindings)
+            return 'Function', record['line_number'], [bindings.get(param, '_') for param in record['params']]
+
+        record = session.run("""
+            MATCH (c:Class {name: $item, file_path: $file_path})
+            WHERE size(coalesce(c.generic_params, [])) > 0
+            OPTIONAL MATCH (c)-[:HAS_IMPL]->(i:Impl)-[:CONTAINS]->(fn:Function {name: $method})
+            OPTIONAL MATCH (c)-[:HAS_FIELD]->(fd:Field)
+            RETURN c.line_number as line_number, c.generic_params as params, i.self_type as self_type,
+                   coalesce(i.generic_params, []) as impl_params, coalesce(fn.param_types, []) as param_types,
+                   collect([fd.name, fd.type]) as fields
+            LIMIT 1
+        """, item=site['item'], file_path=resolved_path, method=site['method'] or '').single()
+        if record is None:
+            return None
+        if site['kind'] == 'literal':
+            bindings = {}
+            field_types = {name: field_type for name, field_type in record['fields'] if name}
+            for name, concrete in site['field_types'].items():
+                if name in field_types:
+                    rust_lang_module.bind_type_parameters(field_types[name], concrete, record['params'], bindings)
+            return 'Class', record['line_number'], [bindings.get(param, '_') for param in record['params']]
+        if not record['self_type']:
+            return None
+        # `impl<U> Point<U>` names the type's parameters after its own; `impl Point<f32>` fixes them.
+        impl_arguments = [arg[2] for arg in rust_lang_module.type_tree(record['self_type'])[1]]
+        bindings = {}
+        for declared, concrete in zip(record['param_types'], site['arg_types']):
+            if concrete:
+                rust_lang_module.bind_type_parameters(declared, concrete, record['impl_params'], bindings)
+        type_arguments = [bindings.get(arg, '_') if arg in record['impl_params'] else arg for arg in impl_arguments]
+        return 'Class', record['line_number'], type_arguments
+
+    def _create_instantiation_links(self, session, file_data: Dict, imports_map: dict):
+        """
+        Links functions (or files) to the generic types and functions they instantiate with
+        INSTANTIATES, keeping the concrete instantiation, e.g. `Point<i32>` for `Point::new(1, 2)`.
+        """
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        local_functions = {func['name'] for func in file_data.get('functions', []) if not func.get('class_context')}
+        for site in file_data.get('instantiation_sites', []):
+            if site['kind'] == 'call' and site['method'] is None and site['item'] in local_functions:
+                resolved_path = file_path_str
+            else:
+                resolved_path = self._resolve_symbol_path(site['item'], file_data, imports_map)
+            if not resolved_path:
+                continue
+            bound = self._bind_instantiation(session, site, resolved_path)
+            if bound is None or all(arg == '_' for arg in bound[2]):
+                continue
+            label, line_number, type_arguments = bound
+            if site['function']:
+                source = "MATCH (src:Function {name: $function, file_path: $file_path, line_number: $function_line})"
+            else:
+                source = "MATCH (src:File {path: $file_path})"
+            session.run(source + f"""
+                MATCH (n:{label} {{name: $item, file_path: $item_file_path, line_number: $item_line}})
+                MERGE (src)-[r:INSTANTIATES {{line_number: $line_number, instantiation: $instantiation}}]->(n)
+                SET r.type_arguments = $type_arguments, r.site_kind = $kind, r.inferred = $inferred
+            """, function=site['function'], function_line=site['function_line'], file_path=file_path_str,
+                item=site['item'], item_file_path=resolved_path, item_line=line_number, line_number=site['line_number'],
+                instantiation=f"{site['item']}<{', '.join(type_arguments)}>", type_arguments=type_arguments,
+                kind=site['kind'], inferred=site['kind'] != 'explicit')
+
+    def _create_all_instantiation_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create INSTANTIATES relationships for all generic instantiation sites after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_instantiation_links(session, file_data, imports_map)
+
+    def _create_higher_order_links(self, session, file_data: Dict, imports_map: dict):
+        """Creates MAY_CALL edges from higher-order functions to the closures and functions passed to their callable parameters."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        local_function_names = {func['name'] for func in file_data.get('functions', [])}
+
+        def resolve_function(name, obj_type=None):
+            if obj_type:
+                return self._resolve_symbol_path(obj_type, file_data, imports_map)
+            if name in local_function_names:
+                return file_path_str
+            possible_paths = imports_map.get(name, [])
+            return possible_paths[0] if len(possible_paths) == 1 else None
+
+        for call in file_data.get('higher_order_calls', []):
+            callee_path = resolve_function(call['callee'], call['inferred_obj_type'])
+            if not callee_path:
+                continue
+            if call['target_kind'] == 'closure':
+                target_label, target_path = 'Closure', file_path_str
+            else:
+                target_label, target_path = 'Function', resolve_function(call['target'])
+                if not target_path:
+                    continue
+
+            # Arguments are positional over the non-self parameters of the callee.
+            session.run(f"""
+                MATCH (hof:Function {{name: $callee, file_path: $callee_path}})
+                WITH hof, [a IN hof.args WHERE a <> 'self'] as params
+                WHERE $arg_index < size(params) AND params[$arg_index] IN coalesce(hof.callable_params, [])
+                MATCH (target:{target_label} {{name: $target, file_path: $target_path}})
+                MERGE (hof)-[r:MAY_CALL {{via_param: params[$arg_index], call_site_line: $line_number}}]->(target)
+                SET r.call_site_file = $file_path, r.caller = $caller
+            """, callee=call['callee'], callee_path=callee_path, arg_index=call['arg_index'],
+                target=call['target'], target_path=target_path, line_number=call['line_number'],
+                file_path=file_path_str, caller=call['caller'])
+
+    def _create_all_higher_order_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create MAY_CALL relationships for all higher-order calls after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_higher_order_links(session, file_data, imports_map)
+
+    def _create_dispatch_links(self, session, file_data: Dict):
+        """
+        Creates MAY_CALL edges from functions making trait-dispatched method calls to every
+        implementation of the method in impls of those traits, and to the traits' default methods.
+        """
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        for call in file_data.get('dispatch_calls', []):
+            session.run("""
+                MATCH (caller:Function {name: $caller, file_path: $file_path, line_number: $caller_line})
+                MATCH (m:Function {name: $method})
+                WHERE EXISTS { MATCH (i:Impl)-[:CONTAINS]->(m) WHERE i.trait IN $traits }
+                   OR EXISTS { MATCH (t:Class)-[:CONTAINS]->(m) WHERE t.kind = 'trait' AND t.name IN $traits }
+                MERGE (caller)-[r:MAY_CALL {call_site_line: $line_number, dispatch: $dispatch}]->(m)
+                SET r.via_traits = $traits
+            """, caller=call['caller'], caller_line=call['caller_line'], file_path=file_path_str,
+                method=call['method'], traits=call['traits'], line_number=call['line_number'], dispatch=call['dispatch'])
+
+    def _create_all_dispatch_links(self, all_file_data: list[Dict]):
+        """Create trait-dispatch MAY_CALL relationships after all impls have been linked."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_dispatch_links(session, file_data)
+
+    def _create_impl_links(self, session, file_data: Dict, imports_map: dict):
+        """Links Impl nodes to their type (HAS_IMPL) and trait (IMPLEMENTS), and associated type bindings to their declaration (BINDS)."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        for impl in file_data.get('impls', []):
+            type_path = self._resolve_symbol_path(impl['type'], file_data, imports_map)
+            if type_path:
+                session.run("""
+                    MATCH (i:Impl {name: $name, file_path: $file_path, line_number: $line_number})
+                    MATCH (c:Class {name: $type, file_path: $type_path})
+                    MERGE (c)-[:HAS_IMPL]->(i)
+                """, name=impl['name'], file_path=file_path_str, line_number=impl['line_number'],
+                    type=impl['type'], type_path=type_path)
+
+            trait_path = self._resolve_symbol_path(impl['trait'], file_data, imports_map)
+            if trait_path:
+                session.run("""
+                    MATCH (i:Impl {name: $name, file_path: $file_path, line_number: $line_number})
+                    MATCH (t:Class {name: $trait, file_path: $trait_path})
+                    MERGE (i)-[:IMPLEMENTS]->(t)
+                """, name=impl['name'], file_path=file_path_str, line_number=impl['line_number'],
+                    trait=impl['trait'], trait_path=trait_path)
+
+        for binding in file_data.get('associated_types', []):
+            if binding['owner_kind'] != 'impl' or not binding['trait']:
+                continue
+            trait_path = self._resolve_symbol_path(binding['trait'], file_data, imports_map)
+            if not trait_path:
+                continue
+            session.run("""
+                MATCH (b:AssociatedType {name: $name, owner: $owner, file_path: $file_path, line_number: $line_number})
+                MATCH (decl:AssociatedType {name: $name, owner: $trait, owner_kind: 'trait', file_path: $trait_path})
+                MERGE (b)-[:BINDS]->(decl)
+            """, name=binding['name'], owner=binding['owner'], file_path=file_path_str,
+                line_number=binding['line_number'], trait=binding['trait'], trait_path=trait_path)
+
+    def _create_all_impl_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create HAS_IMPL/IMPLEMENTS relationships for all impl blocks after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_impl_links(session, file_data, imports_map)
+
+    def _create_builder_links(self, session, file_data: Dict, imports_map: dict):
+        """Tags builder types and links them to the type their build method produces with BUILDS."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        for builder in file_data.get('builders', []):
+            entry_points = [f"{e['owner']}::{e['name']}" if e['owner'] else e['name'] for e in builder['entry_points']]
+            session.run("""
+                MATCH (b:Class {name: $builder, file_path: $file_path})
+                SET b.is_builder = true, b.builder_setters = $setters, b.build_method = $build_method,
+                    b.builder_entry_points = $entry_points
+            """, builder=builder['builder'], file_path=file_path_str, setters=builder['setters'],
+                build_method=builder['build_method'], entry_points=entry_points)
+
+            target_path = self._resolve_symbol_path(builder['target'], file_data, imports_map)
+            if not target_path:
+                continue
+            session.run("""
+                MATCH (b:Class {name: $builder, file_path: $file_path})
+                MATCH (t:Class {name: $target, file_path: $target_path})
+                MERGE (b)-[r:BUILDS]->(t)
+                SET r.build_method = $build_method, r.setters = $setters, r.entry_points = $entry_points
+            """, builder=builder['builder'], file_path=file_path_str, target=builder['target'],
+                target_path=target_path, build_method=builder['build_method'], setters=builder['setters'],
+                entry_points=entry_points)
+
+    def _create_all_builder_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create BUILDS relationships for all detected builders after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_builder_links(session, file_data, imports_map)
+
+    def _create_newtype_links(self, session, file_data: Dict, imports_map: dict):
+        """Tags newtypes and generic wrappers, linking newtypes over project types to their inner type with WRAPS."""
+        file_path_str = str(Path(file_data['file_path']).resolve())
+        for newtype in file_data.get('newtypes', []):
+            session.run("""
+                MATCH (c:Class {name: $name, file_path: $file_path, line_number: $line_number})
+                SET c.newtype_kind = $kind, c.wraps_type = $inner_type
+            """, name=newtype['name'], file_path=file_path_str, line_number=newtype['line_number'],
+                kind=newtype['kind'], inner_type=newtype['inner_type'])
+
+            inner_path = self._resolve_symbol_path(newtype['inner_base'], file_data, imports_map)
+            if not inner_path:
+                continue
+            session.run("""
+                MATCH (c:Class {name: $name, file_path: $file_path, line_number: $line_number})
+                MATCH (inner:Class {name: $inner, file_path: $inner_path})
+                MERGE (c)-[r:WRAPS]->(inner)
+                SET r.field = $field, r.type = $inner_type
+            """, name=newtype['name'], file_path=file_path_str, line_number=newtype['line_number'],
+                inner=newtype['inner_base'], inner_path=inner_path, field=newtype['field'],
+                inner_type=newtype['inner_type'])
+
+    def _create_all_newtype_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create WRAPS relationships for all newtypes after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_newtype_links(session, file_data, imports_map)
+
+    def _create_lock_order_call_links(self, session, file_data: Dict, imports_map: dict):
+        """
+        Creates ACQUIRED_BEFORE edges for locks acquired by functions called (up to three calls
+        deep) while a guard is held, keeping the call path in `via`.
+        """
+        file_path = str(Path(file_data['file_path']).resolve())
+        for region in file_data.get('lock_regions', []):
+            session.run("""
+                MATCH (fn:Function {name: $function, file_path: $file_path, line_number: $function_line})-[c:CALLS]->(callee:Function)
+                WHERE c.line_number >= $start_line AND c.line_number <= $end_line
+                MATCH path = (callee)-[:CALLS*0..3]->(g:Function)-[acc:LOCKS]->(b:Lock)
+                WHERE acc.mode <> 'atomic'
+                MATCH (a:Lock {name: $lock, file_path: $file_path})
+                WITH a, b, c, [$function] + [n IN nodes(path) | n.name] as via
+                MERGE (a)-[r:ACQUIRED_BEFORE {function: $function, file_path: $file_path, line_number: c.line_number}]->(b)
+                SET r.held_since = $start_line, r.via = via
+            """, function=region['function'], function_line=region['function_line'], file_path=file_path,
+                lock=region['lock'], start_line=region['line_number'], end_line=region['end_line'])
+
+    def _create_all_lock_order_links(self, all_file_data: list[Dict], imports_map: dict):
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_lock_order_call_links(session, file_data, imports_map)
+
+    def _compute_thread_safety(self, all_file_data: list[Dict]) -> Dict[str, tuple]:
+        """
+        Computes `(is_send, is_sync, reason)` for every Rust struct, enum and union from its field
+        types, honouring manual `unsafe impl Send/Sync` and negative impls. Types start out
+        thread-safe and are downgraded until nothing changes, so recursive types are handled.
+        """
+        from .languages import rust as rust_lang_module
+
+        field_types, overrides = {}, {}
+        for file_data in all_file_data:
+            if file_data.get('lang') != 'rust':
+                continue
+            for class_data in file_data.get('classes', []):
+                if class_data.get('kind') in ('struct', 'enum', 'union'):
+                    field_types.setdefault(class_data['name'], [])
+            for field in file_data.get('fields', []):
+                field_types.setdefault(field['owner'], []).append((field['name'], field['type']))
+            for variant in file_data.get('enum_variants', []):
+                body = (variant.get('fields') or '').strip()[1:-1]
+                for index, part in enumerate(rust_lang_module.split_type_arguments(body)):
+                    name, _, type_text = part.rpartition(':') if variant['kind'] == 'struct' else (str(index), '', part)
+                    if type_text.strip():
+                        field_types.setdefault(variant['enum'], []).append((f"{variant['name']}.{name.strip()}", type_text.strip()))
+            for impl in file_data.get('impls', []):
+                if impl.get('trait') in ('Send', 'Sync') and (impl.get('is_unsafe') or impl.get('is_negative')):
+                    overrides.setdefault(impl['type'], {})[impl['trait']] = not impl.get('is_negative')
+
+        safety = {name: (True, True, None) for name in field_types}
+        for _ in range(len(safety) + 1):
+            changed = False
+            for name, fields in field_types.items():
+                is_send, is_sync, reason = True, True, None
+                for field_name, type_text in fields:
+                    field_send, field_sync, field_reason = rust_lang_module.thread_safety(type_text, safety)
+                    if reason is None and not (field_send and field_sync):
+                        reason = f"field `{field_name}`: {field_reason}"
+                    is_send, is_sync = is_send and field_send, is_sync and field_sync
+                for trait, implemented in overrides.get(name, {}).items():
+                    if trait == 'Send':
+                        is_send = implemented
+                    else:
+                        is_sync = implemented
+                    reason = f"{'unsafe impl ' if implemented else 'impl !'}{trait}" + (f"; {reason}" if reason else "")
+                if (is_send, is_sync, reason) != safety[name]:
+                    safety[name] = (is_send, is_sync, reason)
+                    changed = True
+            if not changed:
+                break
+        return safety
+
+    def _create_thread_safety_links(self, session, file_data: Dict, imports_map: dict, safety: Dict[str, tuple]):
+        """Stores Send/Sync on Class nodes and links thread-spawned closures to the types they move (MOVES_INTO_THREAD)."""
+        from .languages import rust as rust_lang_module
+
+        file_path = str(Path(file_data['file_path']).resolve())
+        for class_data in file_data.get('classes', []):
+            if class_data['name'] not in safety:
+                continue
+            is_send, is_sync, reason = safety[class_data['name']]
+            session.run("""
+                MATCH (c:Class {name: $name, file_path: $file_path, line_number: $line_number})
+                SET c.is_send = $is_send, c.is_sync = $is_sync, c.thread_safety_reason = $reason
+            """, name=class_data['name'], file_path=file_path, line_number=class_data['line_number'],
+                is_send=is_send, is_sync=is_sync, reason=reason)
+
+        for spawn in file_data.get('thread_spawns', []):
+            captures, non_send = [], []
+            for capture in spawn['captures']:
+                captures.append(f"{capture['name']}: {capture['type'] or '?'}")
+                if not capture['type']:
+                    continue
+                is_send, _, reason = rust_lang_module.thread_safety(capture['type'], safety)
+                if not is_send:
+                    non_send.append(f"{capture['name']}: {capture['type']} ({reason})")
+
+                for type_name in dict.fromkeys(re.findall(r'\b[A-Z]\w*', capture['type'])):
+                    if type_name not in safety:
+                        continue
+                    resolved_path = self._resolve_symbol_path(type_name, file_data, imports_map)
+                    if not resolved_path:
+                        continue
+                    session.run("""
+                        MATCH (cl:Closure {name: $closure, file_path: $file_path})
+                        MATCH (c:Class {name: $type_name, file_path: $resolved_path})
+                        MERGE (cl)-[r:MOVES_INTO_THREAD {variable: $variable}]->(c)
+                        SET r.type = $type, r.is_send = $is_send, r.reason = $reason
+                    """, closure=spawn['closure'], file_path=file_path, type_name=type_name, resolved_path=resolved_path,
+                        variable=capture['name'], type=capture['type'], is_send=is_send, reason=reason)
+
+            session.run("""
+                MATCH (cl:Closure {name: $closure, file_path: $file_path})
+                SET cl.spawned_by = $spawner, cl.captures = $captures, cl.non_send_captures = $non_send
+            """, closure=spawn['closure'], file_path=file_path, spawner=spawn['spawner'], captures=captures, non_send=non_send)
+
+    def _create_all_thread_safety_links(self, all_file_data: list[Dict], imports_map: dict):
+        safety = self._compute_thread_safety(all_file_data)
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                if file_data.get('lang') == 'rust':
+                    self._create_thread_safety_links(session, file_data, imports_map, safety)
+
+    def _create_all_pointer_wrapping_links(self, all_file_data: list[Dict], imports_map: dict):
+        """Create WRAPPED_IN relationships for all smart pointer fields after all files have been processed."""
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                self._create_pointer_wrapping_links(session, file_data, imports_map)
                 
+    def mark_graph_changed(self):
+        """Moves the graph on to a new generation, invalidating cached query results."""
+        with self.generation_lock:
+            self.generation += 1
+
+    def _write_batches(self, session, query: str, rows: list, **params):
+        """
+        Runs a write `query` reading its input from `UNWIND $rows AS row` over `rows`, `batch_size`
+        rows per statement, each batch in its own transaction.
+        """
+        for start in range(0, len(rows), self.batch_size):
+            batch = rows[start:start + self.batch_size]
+            session.execute_write(lambda tx: tx.run(query, rows=batch, **params).consume())
+            self.mark_graph_changed()
+
+    def _prune_stale_elements(self, session, file_path: str, uids: list, unchanged: list = ()):
+        """
+        Prepares a file's nodes for being upserted by `add_file_to_graph`: deletes the elements that
+        are gone (no longer among `uids`) or have no stable identity, and the relationships leaving
+        the file and the kept elements, which indexing recreates. Relationships into kept elements,
+        such as calls from other files, and annotations (ANNOTATION_RELATIONSHIPS) survive, and so
+        does everything about the `unchanged` elements (same fingerprint, see `incremental`).
+        """
+        session.run("""
+            MATCH (f:File {path: $path})-[:CONTAINS]->(e)
+            WHERE e.uid IS NULL OR NOT e.uid IN $uids
+            DETACH DELETE e
+        """, path=file_path, uids=uids)
+        session.run("""
+            MATCH (p:Parameter {file_path: $path})
+            WHERE NOT EXISTS { MATCH (fn:Function)-[:HAS_PARAMETER]->(p) WHERE fn.uid IN $unchanged }
+            DETACH DELETE p
+        """, path=file_path, unchanged=list(unchanged))
+        session.run("""
+            MATCH (f:File {path: $path})-[:CONTAINS]->(e)-[r]->()
+            WHERE NOT type(r) IN $kept AND NOT e.uid IN $unchanged
+            DELETE r
+        """, path=file_path, kept=ANNOTATION_RELATIONSHIPS, unchanged=list(unchanged))
+        session.run("""
+            MATCH (f:File {path: $path})-[r]->()
+            WHERE NOT type(r) IN $kept
+            DELETE r
+        """, path=file_path, kept=ANNOTATION_RELATIONSHIPS + ['CONTAINS'])
+        # Kept elements get their new line numbers on upsert; clearing the old ones first keeps the
+        # (name, file_path, line_number) constraints from tripping when two same-named items swap places.
+        session.run("""
+            MATCH (f:File {path: $path})-[:CONTAINS]->(e)
+            WHERE NOT e.uid IN $unchanged
+            REMOVE e.line_number
+        """, path=file_path, unchanged=list(unchanged))
+
+    @retried
     def delete_file_from_graph(self, file_path: str):
         """Deletes a file and all its contained elements and relationships."""
         file_path_str = str(Path(file_path).resolve())
@@ -430,38 +1661,799 @@ class GraphBuilder:
                     WHERE NOT (d)-[:CONTAINS]->()
                     DETACH DELETE d
                 """, path=path)
+        self.mark_graph_changed()
 
     def delete_repository_from_graph(self, repo_path: str):
         """Deletes a repository and all its contents from the graph."""
         repo_path_str = str(Path(repo_path).resolve())
         with self.driver.session() as session:
+            session.run("""MATCH (r:Repository {path: $path})
+                          OPTIONAL MATCH (r)-[:HAS_SHARD]->(s:Shard)
+                          DETACH DELETE s""", path=repo_path_str)
             session.run("""MATCH (r:Repository {path: $path})
                           OPTIONAL MATCH (r)-[:CONTAINS*]->(e)
                           DETACH DELETE r, e""", path=repo_path_str)
             logger.info(f"Deleted repository and its contents from graph: {repo_path_str}")
+        self.mark_graph_changed()
+
+    def sync_to_remote(self, repo_path: Path, full: bool = False, dry_run: bool = False) -> Dict[str, int]:
+        """
+        Mirrors an indexed repository to the remote database (see `delta_sync`), shipping only the
+        nodes and relationships changed since its last sync. Returns the counts of the delta.
+        """
+        repo_path_str = str(Path(repo_path).resolve())
+        remote = self.db_manager.get_remote_driver()
+        return self.db_manager.run_with_retry(
+            lambda: delta_sync.sync(self.driver, remote, repo_path_str, self.db_manager.remote_uri, full, dry_run),
+            f"Syncing {repo_path_str} to {self.db_manager.remote_uri}")
+
+    def _compute_centrality(self):
+        """
+        Stores PageRank (`pagerank`) and betweenness (`betweenness`) scores of the call graph on
+        every Function node, counting direct CALLS and trait-dispatched MAY_CALL edges. A revision
+        snapshot being indexed is left out.
+        """
+        snapshots = str(REVISIONS_DIR.resolve()) + os.sep
+        with self.driver.session() as session:
+            nodes = [record['id'] for record in session.run("""
+                MATCH (f:Function) WHERE NOT f.file_path STARTS WITH $snapshots RETURN elementId(f) as id
+            """, snapshots=snapshots)]
+            edges = [(record['caller'], record['callee']) for record in session.run("""
+                MATCH (caller:Function)-[r:CALLS|MAY_CALL]->(callee:Function)
+                WHERE NOT caller.file_path STARTS WITH $snapshots AND NOT callee.file_path STARTS WITH $snapshots
+                RETURN DISTINCT elementId(caller) as caller, elementId(callee) as callee
+            """, snapshots=snapshots)]
+            ranks = pagerank(nodes, edges)
+            between = betweenness(nodes, edges, max_sources=BETWEENNESS_MAX_SOURCES)
+            session.run("""
+                UNWIND $scores as row
+                MATCH (f:Function) WHERE elementId(f) = row.id
+                SET f.pagerank = row.pagerank, f.betweenness = row.betweenness
+            """, scores=[{"id": node, "pagerank": ranks[node], "betweenness": between[node]} for node in nodes])
+
+    def _create_all_embeddings(self, all_file_data: list[Dict]):
+        """Stores an embedding of every function (name, doc comment, signature and body) for semantic search."""
+        embedder = get_embedder()
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                functions = [
+                    {"name": func['name'], "line_number": func['line_number'], "embedding": embedder.embed_function(func)}
+                    for func in file_data.get('functions', [])
+                ]
+                session.run("""
+                    UNWIND $functions as row
+                    MATCH (fn:Function {name: row.name, file_path: $path, line_number: row.line_number})
+                    SET fn.embedding = row.embedding, fn.embedding_model = $model
+                """, functions=functions, path=str(Path(file_data['file_path']).resolve()), model=embedder.name)
+
+    @retried
+    def _annotate_git_history(self, all_file_data: list[Dict], repo_path: Path):
+        """
+        Stores blame metadata (last commit, author, modification time, age and owners) on the File,
+        Function and Task nodes of files committed to the git repository containing `repo_path`.
+        """
+        repository = open_repository(repo_path)
+        if repository is None:
+            return
+        now = time.time()
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                if file_data.get('is_dependency'):
+                    continue
+                file_path_str = str(Path(file_data['file_path']).resolve())
+                hunks = blame_file(repository, Path(file_path_str))
+                if not hunks:
+                    continue
+                file_history = summarize_blame(hunks, 1, max(hunk['end'] for hunk in hunks), now)
+                session.run("MATCH (f:File {path: $path}) SET f += $history", path=file_path_str, history=file_history)
+
+                functions = []
+                for func in file_data.get('functions', []):
+                    history = summarize_blame(hunks, func['line_number'], func.get('end_line') or func['line_number'], now)
+                    if history:
+                        functions.append({"name": func['name'], "line_number": func['line_number'], "history": history})
+                session.run("""
+                    UNWIND $functions as row
+                    MATCH (fn:Function {name: row.name, file_path: $path, line_number: row.line_number})
+                    SET fn += row.history
+                """, functions=functions, path=file_path_str)
+
+                tasks = []
+                for task in file_data.get('tasks', []):
+                    history = summarize_blame(hunks, task['line_number'], task['line_number'], now)
+                    if history:
+                        tasks.append({"line_number": task['line_number'], "history": history})
+                session.run("""
+                    UNWIND $tasks as row
+                    MATCH (t:Task {file_path: $path, line_number: row.line_number})
+                    SET t += row.history
+                """, tasks=tasks, path=file_path_str)
+
+    def annotate_clippy(self, crate_path: Path) -> Dict[str, Any]:
+        """
+        Runs `cargo clippy` on an indexed crate and attaches its diagnostics to the graph, replacing
+        those of a previous run: a Diagnostic node per finding, linked with HAS_DIAGNOSTIC from the
+        innermost function containing it (or from its file), and the lints found in each function in
+        `clippy_lints`.
+        """
+        crate_path = Path(crate_path).resolve()
+        diagnostics = run_clippy(crate_path)
+        root = str(crate_path) + os.sep
+        with self.driver.session() as session:
+            session.run("""
+                MATCH (d:Diagnostic) WHERE d.file_path STARTS WITH $root
+                DETACH DELETE d
+            """, root=root)
+            session.run("""
+                MATCH (fn:Function) WHERE fn.file_path STARTS WITH $root AND fn.clippy_lints IS NOT NULL
+                REMOVE fn.clippy_lints
+            """, root=root)
+            attached = [dict(record) for record in session.run("""
+                UNWIND $diagnostics as diagnostic
+                MATCH (f:File {path: diagnostic.file_path})
+                CREATE (d:Diagnostic)
+                SET d = diagnostic
+                WITH f, d, diagnostic
+                OPTIONAL MATCH (f)-[:CONTAINS]->(fn:Function)
+                WHERE fn.line_number <= diagnostic.line_number AND fn.end_line >= diagnostic.line_number
+                WITH f, d, fn ORDER BY fn.line_number DESC
+                WITH f, d, head(collect(fn)) as fn
+                WITH d, fn, coalesce(fn, f) as holder
+                MERGE (holder)-[:HAS_DIAGNOSTIC]->(d)
+                RETURN elementId(fn) as function_id, d.lint as lint
+            """, diagnostics=diagnostics)]
+
+            lints = {}
+            for record in attached:
+                if record['function_id'] is not None:
+                    lints.setdefault(record['function_id'], []).append(record['lint'])
+            session.run("""
+                UNWIND $functions as row
+                MATCH (fn:Function) WHERE elementId(fn) = row.id
+                SET fn.clippy_lints = row.lints
+            """, functions=[{"id": function_id, "lints": sorted(set(found))} for function_id, found in lints.items()])
+
+        by_lint = {}
+        for diagnostic in diagnostics:
+            by_lint[diagnostic['lint']] = by_lint.get(diagnostic['lint'], 0) + 1
+        return {
+            "crate_path": str(crate_path),
+            "diagnostics": len(diagnostics),
+            "attached": len(attached),
+            "in_functions": sum(len(found) for found in lints.values()),
+            "by_lint": dict(sorted(by_lint.items(), key=lambda item: -item[1])),
+        }
+
+    def import_coverage(self, report_path: Path, source_root: Path = None, test_name: str = None) -> Dict[str, Any]:
+        """
+        Imports an LCOV or llvm-cov JSON coverage report (see `coverage`) onto the Function and File
+        nodes of the files it covers: `covered_lines` and `coverable_lines` out of the instrumented
+        lines in their span, `line_coverage`, `uncovered_lines` and `covered_by_tests`. A report
+        from running a single test is imported with `test_name` instead, which links that test
+        function to every function it executed with COVERS.
+        """
+        files = load_report(report_path, source_root)
+        functions, file_rows = [], []
+        with self.driver.session() as session:
+            for file_path, hits in files.items():
+                if not hits:
+                    continue
+                file_rows.append({"path": file_path, "coverable": len(hits),
+                                  "covered": sum(1 for count in hits.values() if count > 0)})
+                for record in session.run("""
+                    MATCH (fn:Function {file_path: $file_path})
+                    RETURN elementId(fn) as id, fn.line_number as line_number, coalesce(fn.end_line, fn.line_number) as end_line
+                """, file_path=file_path):
+                    lines = {line: count for line, count in hits.items()
+                             if record['line_number'] <= line <= record['end_line']}
+                    if not lines:
+                        continue
+                    covered = [line for line, count in lines.items() if count > 0]
+                    functions.append({
+                        "id": record['id'],
+                        "covered_lines": len(covered),
+                        "coverable_lines": len(lines),
+                        "line_coverage": round(len(covered) / len(lines), 3),
+                        "uncovered_lines": sorted(line for line, count in lines.items() if count == 0),
+                    })
+
+            if not file_rows:
+                raise ValueError(f"{report_path} has no line coverage data")
+            linked = 0
+            if test_name:
+                # A single test's run says nothing about the coverage of the whole suite, only what it touched.
+                linked = session.run("""
+                    MATCH (t:Function {name: $test_name})
+                    WHERE any(attribute IN coalesce(t.decorators, []) WHERE attribute =~ $test_attribute)
+                    UNWIND $functions as row
+                    MATCH (fn:Function) WHERE elementId(fn) = row.id AND row.covered_lines > 0 AND fn <> t
+                    SET fn.covered_by_tests = true
+                    MERGE (t)-[r:COVERS]->(fn)
+                    SET r.covered_lines = row.covered_lines, r.line_coverage = row.line_coverage
+                    RETURN count(r) as linked
+                """, test_name=test_name, functions=functions, test_attribute=r'#\[\s*([\w:]*::)?test\b.*').single()['linked']
+            else:
+                session.run("""
+                    UNWIND $files as row
+                    MATCH (f:File {path: row.path})
+                    SET f.covered_lines = row.covered, f.coverable_lines = row.coverable,
+                        f.line_coverage = round(toFloat(row.covered) / row.coverable, 3)
+                """, files=file_rows)
+                session.run("""
+                    UNWIND $functions as row
+                    MATCH (fn:Function) WHERE elementId(fn) = row.id
+                    SET fn.covered_lines = row.covered_lines, fn.coverable_lines = row.coverable_lines,
+                        fn.line_coverage = row.line_coverage, fn.uncovered_lines = row.uncovered_lines,
+                        fn.covered_by_tests = row.covered_lines > 0
+                """, functions=functions)
+
+        return {
+            "report": str(report_path),
+            "files": len(file_rows),
+            "functions": len(functions),
+            "covered_functions": sum(1 for func in functions if func['covered_lines'] > 0),
+            "test": test_name,
+            "covers_edges": linked,
+        }
+
+    def import_benchmarks(self, results_path: Path) -> Dict[str, Any]:
+        """
+        Imports Criterion results (see `criterion`) as Benchmark nodes with their latest timings and
+        change against the previous run, replacing earlier imports of the same benchmarks, and links
+        each to the functions it measures with BENCHMARKS. Those are the functions named after a word
+        of the benchmark id, preferring the ones called by the function registering the benchmark
+        (the one holding its id as a string literal).
+        """
+        benchmarks = load_benchmarks(results_path)
+        links = []
+        with self.driver.session() as session:
+            for benchmark in benchmarks:
+                ids = [value for value in (benchmark['id'], benchmark['group'], benchmark['function']) if value]
+                called = set()
+                for record in session.run("""
+                    MATCH (r:Function)-[:CONTAINS_LITERAL]->(l:Literal {kind: 'string'})
+                    WHERE l.value IN $ids
+                    RETURN r.call_names as call_names
+                """, ids=ids):
+                    called.update(name.split("::")[-1].split(".")[-1] for name in record['call_names'] or [])
+                candidates = session.run("""
+                    MATCH (fn:Function)
+                    WHERE NOT coalesce(fn.is_dependency, false) AND NOT fn.file_path CONTAINS '/benches/'
+                      AND NOT any(attribute IN coalesce(fn.decorators, []) WHERE attribute =~ $test_attribute)
+                      AND toLower(fn.name) IN $names
+                    RETURN elementId(fn) as id, fn.name as name
+                """, names=sorted(candidate_names(benchmark)), test_attribute=r'#\[\s*([\w:]*::)?test\b.*')
+                targets = pick_targets(benchmark, [{**dict(record), "called": record['name'] in called} for record in candidates])
+                links.extend({"benchmark": benchmark['id'], "function_id": target['id']} for target in targets)
+
+            session.run("""
+                UNWIND $benchmarks as benchmark
+                MERGE (b:Benchmark {id: benchmark.id})
+                SET b = benchmark
+                WITH b
+                OPTIONAL MATCH (b)-[r:BENCHMARKS]->()
+                DELETE r
+            """, benchmarks=benchmarks)
+            session.run("""
+                UNWIND $links as link
+                MATCH (b:Benchmark {id: link.benchmark}), (fn:Function) WHERE elementId(fn) = link.function_id
+                MERGE (b)-[:BENCHMARKS]->(fn)
+            """, links=links)
+
+        verdicts = {}
+        for benchmark in benchmarks:
+            if benchmark['verdict']:
+                verdicts[benchmark['verdict']] = verdicts.get(benchmark['verdict'], 0) + 1
+        linked = {link['benchmark'] for link in links}
+        return {
+            "results_path": str(results_path),
+            "benchmarks": len(benchmarks),
+            "linked": len(linked),
+            "unlinked": sorted(benchmark['id'] for benchmark in benchmarks if benchmark['id'] not in linked),
+            "verdicts": verdicts,
+        }
+
+    def _resolve_frames(self, session, frames) -> Dict[str, str]:
+        """
+        Maps profile frames to indexed functions by name, narrowed down by the owning type (the
+        segment before the name) and then by the module path segments naming the file. Frames still
+        matching several functions are left unresolved.
+        """
+        segments = {frame: normalize_frame(frame) for frame in frames}
+        names = sorted({path[-1] for path in segments.values() if path})
+        by_name = {}
+        for record in session.run("""
+            MATCH (fn:Function) WHERE fn.name IN $names AND NOT coalesce(fn.is_dependency, false)
+            RETURN elementId(fn) as id, fn.name as name, fn.class_context as owner, fn.file_path as file_path
+        """, names=names):
+            by_name.setdefault(record['name'], []).append(dict(record))
+
+        resolved = {}
+        for frame, path in segments.items():
+            candidates = by_name.get(path[-1], []) if path else []
+            owner = path[-2] if len(path) > 1 else None
+            owned = [func for func in candidates if func['owner'] == owner]
+            candidates = owned or [func for func in candidates if func['owner'] is None]
+            if len(candidates) > 1:
+                modules = set(path[:-1])
+                candidates = [func for func in candidates
+                              if Path(func['file_path']).stem in modules
+                              or (Path(func['file_path']).stem == 'mod' and Path(func['file_path']).parent.name in modules)] \
+                    or candidates
+            if len(candidates) == 1:
+                resolved[frame] = candidates[0]['id']
+        return resolved
+
+    def import_profile(self, profile_path: Path) -> Dict[str, Any]:
+        """
+        Imports a CPU profile in collapsed stack format (see `profile`), replacing the previous one:
+        frames are resolved to indexed functions, which get their `total_samples`, `self_samples`
+        and `sample_share` of the whole profile, and the CALLS or MAY_CALL edges between consecutive
+        resolved frames get `samples`. Consecutive frames without such an edge (calls through code
+        outside the graph) are counted as unmatched.
+        """
+        stacks = load_profile(profile_path)
+        with self.driver.session() as session:
+            resolved = self._resolve_frames(session, {frame for frames, _ in stacks for frame in frames})
+            profile = aggregate(stacks, resolved.get)
+
+            session.run("""
+                MATCH (fn:Function) WHERE fn.total_samples IS NOT NULL
+                REMOVE fn.total_samples, fn.self_samples, fn.sample_share
+            """)
+            session.run("""
+                MATCH ()-[r:CALLS|MAY_CALL]->() WHERE r.samples IS NOT NULL
+                REMOVE r.samples
+            """)
+            session.run("""
+                UNWIND $functions as row
+                MATCH (fn:Function) WHERE elementId(fn) = row.id
+                SET fn.total_samples = row.total, fn.self_samples = row.self,
+                    fn.sample_share = round(toFloat(row.total) / $samples, 4)
+            """, samples=profile['samples'], functions=[
+                {"id": function_id, "total": total, "self": profile['self'].get(function_id, 0)}
+                for function_id, total in profile['total'].items()])
+            annotated = session.run("""
+                UNWIND $edges as edge
+                MATCH (a)-[r:CALLS|MAY_CALL]->(b)
+                WHERE elementId(a) = edge.caller AND elementId(b) = edge.callee
+                SET r.samples = edge.samples
+                RETURN count(DISTINCT [edge.caller, edge.callee]) as annotated
+            """, edges=[{"caller": caller, "callee": callee, "samples": samples}
+                        for (caller, callee), samples in profile['edges'].items()]).single()['annotated']
+
+        unresolved = sorted(profile['unresolved'].items(), key=lambda item: -item[1])
+        return {
+            "profile": str(profile_path),
+            "samples": profile['samples'],
+            "functions": len(profile['total']),
+            "annotated_edges": annotated,
+            "unmatched_edges": len(profile['edges']) - annotated,
+            "top_unresolved_frames": [{"frame": frame, "samples": samples} for frame, samples in unresolved[:10]],
+        }
+
+    @retried
+    def _check_layering(self, repo_path: Path):
+        """Evaluates the repository's layering rules, if it declares any, and logs the violations."""
+        if not repo_path.is_dir() or not (repo_path / LAYERS_CONFIG_FILE).is_file():
+            return
+        from .code_finder import CodeFinder
+        try:
+            report = CodeFinder(self.db_manager).check_layering(str(repo_path))
+        except ValueError as e:
+            logger.warning(f"Could not check layering rules of {repo_path}: {e}")
+            return
+        for violation in report['violations']:
+            logger.warning(f"Layering violation ({violation['rule']}): {violation['source_module']} -> {violation['target_module']}")
+
+    def index_revision(self, repo_path: Path, revision: str) -> Path:
+        """
+        Indexes `revision` of the git repository at `repo_path` as a repository of its own, rooted at
+        an exported snapshot of that commit, and returns the snapshot path. Already indexed revisions
+        are reused. Runs synchronously, so it must not be called from the event loop's thread.
+
+        Snapshots live in a namespace of their own: once indexed, their nodes are moved to labels
+        prefixed with `SNAPSHOT_LABEL_PREFIX` (`SnapshotFunction`, `SnapshotFile`, ...) and tagged
+        with the `revision` commit, the SnapshotRepository also with `is_snapshot`, so that the
+        analyses and tools matching `Function` or `Class` never see them. Only the revision diffs
+        (`CodeFinder.diff_indexed_trees`) and `record_history` read them.
+        """
+        snapshot = export_revision(repo_path, revision)
+        with self.driver.session() as session:
+            indexed = session.run(f"MATCH (r:{SNAPSHOT_LABEL_PREFIX}Repository {{path: $path}}) RETURN count(r) as count",
+                                  path=str(snapshot.resolve())).single()['count']
+        if not indexed:
+            # Leftovers of an interrupted build are indexed again from scratch, as is a failed build next time.
+            self._delete_snapshot(snapshot)
+            try:
+                asyncio.run(self.build_graph_from_path_async(snapshot))
+                self._move_to_snapshot_namespace(snapshot, resolve_revision(repo_path, revision))
+            except Exception:
+                self._delete_snapshot(snapshot)
+                raise
+        return snapshot
+
+    def _delete_snapshot(self, snapshot: Path):
+        """Deletes every node of a revision snapshot, whether or not it was moved to the snapshot labels yet."""
+        root = str(snapshot.resolve())
+        with self.driver.session() as session:
+            session.run("""
+                MATCH (n) WHERE NOT n:Revision AND (n.path = $root OR coalesce(n.file_path, n.path) STARTS WITH $prefix)
+                DETACH DELETE n
+            """, root=root, prefix=root + os.sep)
+        self.mark_graph_changed()
+
+    def _move_to_snapshot_namespace(self, snapshot: Path, commit: str):
+        """Moves the nodes of an indexed revision snapshot to the prefixed labels (see `index_revision`)."""
+        root = str(snapshot.resolve())
+        with self.driver.session() as session:
+            labels = [record['label'] for record in session.run("CALL db.labels() YIELD label RETURN label")]
+            for label in labels:
+                if label.startswith(SNAPSHOT_LABEL_PREFIX) or label == 'Revision':
+                    continue  # Revisions of a recorded history point at their snapshot by `path` too.
+                # Nodes shared between repositories (modules, literals, placeholders) have no path and stay.
+                session.run(f"""
+                    MATCH (n:`{label}`)
+                    WHERE n.path = $root OR n.path STARTS WITH $prefix OR n.file_path STARTS WITH $prefix
+                    SET n:`{SNAPSHOT_LABEL_PREFIX}{label}`, n.revision = $commit
+                    REMOVE n:`{label}`
+                """, root=root, prefix=root + os.sep, commit=commit)
+            session.run(f"MATCH (r:{SNAPSHOT_LABEL_PREFIX}Repository {{path: $path}}) SET r.is_snapshot = true", path=root)
+            # Edges linking passes drew to other repositories (e.g. trait-dispatched calls) are dropped.
+            session.run("""
+                MATCH (n)-[r]-(m) WHERE n.revision = $commit AND (n.path = $root OR coalesce(n.file_path, n.path) STARTS WITH $prefix)
+                  AND coalesce(m.file_path, m.path) IS NOT NULL
+                  AND NOT (m.path = $root OR coalesce(m.file_path, m.path) STARTS WITH $prefix)
+                DELETE r
+            """, root=root, prefix=root + os.sep, commit=commit)
+        self.mark_graph_changed()
+
+    def _revision_snapshot(self, root: Path) -> Dict[str, Any]:
+        """The functions, types and internal calls of an indexed revision, keyed as in `temporal`."""
+        root = str(root.resolve()) + os.sep
+        with self.driver.session() as session:
+            items = {}
+            for record in session.run("""
+                MATCH (n) WHERE (n:SnapshotFunction OR n:SnapshotClass) AND n.file_path STARTS WITH $root
+                RETURN CASE WHEN n:SnapshotFunction THEN 'function' ELSE coalesce(n.kind, 'class') END as kind,
+                       substring(n.file_path, size($root)) as file, n.class_context as owner, n.impl_trait as impl_trait,
+                       n.name as name, n.line_number as line_number,
+                       coalesce(n.source, n.source_code, n.source_compressed) as fingerprint
+            """, root=root):
+                item = dict(record)
+                item["fingerprint"] = source_text(item["fingerprint"])
+                item["key"] = f"{item['kind']}:{item['file']}:{item['owner'] or ''}:{item['impl_trait'] or ''}:{item['name']}"
+                items[item["key"]] = item
+            calls = set()
+            for record in session.run("""
+                MATCH (caller:SnapshotFunction)-[:CALLS]->(callee:SnapshotFunction)
+                WHERE caller.file_path STARTS WITH $root AND callee.file_path STARTS WITH $root
+                RETURN DISTINCT substring(caller.file_path, size($root)) as caller_file, caller.class_context as caller_owner,
+                       caller.impl_trait as caller_trait, caller.name as caller, substring(callee.file_path, size($root)) as callee_file,
+                       callee.class_context as callee_owner, callee.impl_trait as callee_trait, callee.name as callee
+            """, root=root):
+                calls.add((
+                    f"function:{record['caller_file']}:{record['caller_owner'] or ''}:{record['caller_trait'] or ''}:{record['caller']}",
+                    f"function:{record['callee_file']}:{record['callee_owner'] or ''}:{record['callee_trait'] or ''}:{record['callee']}",
+                ))
+        return {"items": items, "calls": calls}
+
+    def record_history(self, repo_path: Path, revisions: list) -> Dict[str, Any]:
+        """
+        Records the history of the functions, types and calls of a git repository over a sequence of
+        revisions (oldest first, or a single `base..head` range whose first-parent commits are used),
+        replacing the history recorded before. Each revision is indexed like in `index_revision`;
+        the history is a Revision node per revision and a VersionedItem per function or type, linked
+        by CALLED_DURING for calls, with validity ranges (see `temporal`). Runs synchronously.
+        """
+        repo_path = Path(repo_path).resolve()
+        if len(revisions) == 1 and ".." in revisions[0]:
+            labels = commits = list_revisions(repo_path, revisions[0])
+            if not commits:
+                raise ValueError(f"No commits in {revisions[0]}")
+        else:
+            labels, commits = list(revisions), [resolve_revision(repo_path, revision) for revision in revisions]
+        if not commits:
+            raise ValueError("No revisions to record")
+
+        snapshots, revision_rows = [], []
+        for ordinal, (label, commit) in enumerate(zip(labels, commits)):
+            root = self.index_revision(repo_path, commit)
+            snapshots.append(self._revision_snapshot(root))
+            revision_rows.append({"ordinal": ordinal, "label": label, "commit": commit,
+                                  "committed_at": commit_time(repo_path, commit), "path": str(root)})
+        history = build_history(snapshots)
+
+        repo = str(repo_path)
+        with self.driver.session() as session:
+            session.run("MATCH (n) WHERE (n:Revision OR n:VersionedItem) AND n.repo = $repo DETACH DELETE n", repo=repo)
+            session.run("""
+                UNWIND $revisions as revision
+                CREATE (rv:Revision {repo: $repo})
+                SET rv += revision
+            """, repo=repo, revisions=revision_rows)
+            session.run("""
+                UNWIND $items as item
+                CREATE (vi:VersionedItem {repo: $repo})
+                SET vi += item
+            """, repo=repo, items=[{key: value for key, value in item.items() if key != 'fingerprint'}
+                                   for item in history['items'].values()])
+            session.run("""
+                UNWIND $calls as call
+                MATCH (a:VersionedItem {repo: $repo, key: call.caller}), (b:VersionedItem {repo: $repo, key: call.callee})
+                CREATE (a)-[:CALLED_DURING {valid_from: call.valid_from, valid_to: call.valid_to}]->(b)
+            """, repo=repo, calls=[{"caller": caller, "callee": callee, **ranges}
+                                   for (caller, callee), ranges in history['calls'].items()])
+
+        return {
+            "repo_path": repo,
+            "revisions": [{key: row[key] for key in ("ordinal", "label", "commit")} for row in revision_rows],
+            "items": len(history['items']),
+            "calls": len(history['calls']),
+        }
+
+    def _add_included_files(self, all_file_data: list[Dict], repo_path: Path, repo_name: str, imports_map: dict, is_dependency: bool = False):
+        """
+        Indexes the files pulled in by Rust `include!` that the directory walk did not parse
+        (e.g. build script output or non-.rs files) and links them with File-[:INCLUDES]->File,
+        so the included items sit under the including module.
+        """
+        from .languages import rust as rust_lang_module
+
+        parsed = {str(Path(file_data['file_path']).resolve()) for file_data in all_file_data}
+        pending = [file_data for file_data in all_file_data if file_data.get('includes')]
+        while pending:
+            file_data = pending.pop()
+            for include in file_data.get('includes', []):
+                included = include.get('resolved_path')
+                if not included:
+                    continue
+                if included not in parsed:
+                    included_path = Path(included)
+                    try:
+                        included_path.relative_to(repo_path.resolve())
+                    except ValueError:
+                        logger.info(f"Skipping include! of {included}: outside of {repo_path}")
+                        continue
+                    for name, paths in rust_lang_module.pre_scan_rust([included_path], self.parsers['.rs']).items():
+                        imports_map.setdefault(name, []).extend(paths)
+                    try:
+                        included_data = self.parsers['.rs'].parse(included_path, is_dependency)
+                    except Exception as e:
+                        logger.warning(f"Failed to parse included file {included}: {e}")
+                        continue
+                    included_data['repo_path'] = str(repo_path.resolve())
+                    self.add_file_to_graph(included_data, repo_name, imports_map)
+                    parsed.add(included)
+                    all_file_data.append(included_data)
+                    pending.append(included_data)
+
+                with self.driver.session() as session:
+                    session.run("""
+                        MATCH (f:File {path: $file_path})
+                        MATCH (g:File {path: $included_path})
+                        MERGE (f)-[r:INCLUDES {line_number: $line_number}]->(g)
+                        SET r.module = $module, r.argument = $argument, g.included_by = $file_path
+                    """, file_path=str(Path(file_data['file_path']).resolve()), included_path=included,
+                        line_number=include['line_number'], module=include['module'], argument=include['argument'])
+
+    def _create_crate_nodes(self, all_file_data: list[Dict]):
+        """
+        Creates a Crate node per Cargo package, holding its manifest metadata and the minimum
+        supported Rust version estimated from the syntax features used by its files (PART_OF).
+        """
+        from .languages import rust as rust_lang_module
+
+        crates = {}
+        for file_data in all_file_data:
+            if file_data.get('lang') == 'rust' and file_data.get('crate_root'):
+                crates.setdefault(file_data['crate_root'], []).append(file_data)
+
+        with self.driver.session() as session:
+            for crate_root, files in crates.items():
+                manifest = rust_lang_module.read_cargo_manifest(Path(crate_root))
+                features = [
+                    {**feature, "file_path": str(Path(file_data['file_path']).resolve().relative_to(Path(crate_root)))}
+                    for file_data in files for feature in file_data.get('msrv_features', [])
+                ]
+                msrv, reasons = rust_lang_module.estimate_msrv(features, manifest['edition'])
+                session.run("""
+                    MERGE (cr:Crate {path: $path})
+                    SET cr.name = $name, cr.edition = $edition, cr.declared_rust_version = $rust_version,
+                        cr.estimated_msrv = $msrv, cr.msrv_reasons = $reasons,
+                        cr.syntax_features = $syntax_features, cr.lang = 'rust'
+                """, path=crate_root, name=manifest['name'] or Path(crate_root).name, edition=manifest['edition'],
+                    rust_version=manifest['rust_version'], msrv=msrv, reasons=reasons,
+                    syntax_features=sorted({feature['feature'] for feature in features}))
+                for file_data in files:
+                    session.run("""
+                        MATCH (f:File {path: $file_path})
+                        MATCH (cr:Crate {path: $path})
+                        MERGE (f)-[:PART_OF]->(cr)
+                    """, file_path=str(Path(file_data['file_path']).resolve()), path=crate_root)
+
+    def _create_module_use_links(self, all_file_data: list[Dict]):
+        """
+        Links Rust modules (files) to the modules of the same crate they import from with USES,
+        keeping the `use` statements behind each edge.
+        """
+        from .languages import rust as rust_lang_module
+
+        modules = {}
+        for file_data in all_file_data:
+            if file_data.get('lang') == 'rust' and file_data.get('module_path'):
+                modules[(file_data['crate_root'], file_data['module_path'])] = str(Path(file_data['file_path']).resolve())
+
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                if file_data.get('lang') != 'rust' or not file_data.get('module_path'):
+                    continue
+                file_path_str = str(Path(file_data['file_path']).resolve())
+                session.run("""
+                    MATCH (f:File {path: $file_path})
+                    SET f.module_path = $module_path
+                    WITH f
+                    OPTIONAL MATCH (f)-[r:USES]->()
+                    DELETE r
+                """, file_path=file_path_str, module_path=file_data['module_path'])
+
+                uses = {}
+                for imp in file_data.get('imports', []):
+                    current = file_data['module_path']
+                    if imp.get('inline_module'):
+                        current += '::' + imp['inline_module']
+                    segments = rust_lang_module.resolve_use_path(imp['full_import_name'], current)
+                    # The longest prefix naming a module of this crate is where the item comes from.
+                    target = next((modules[(file_data['crate_root'], '::'.join(segments[:end]))]
+                                   for end in range(len(segments or []), 0, -1)
+                                   if (file_data['crate_root'], '::'.join(segments[:end])) in modules), None)
+                    if target and target != file_path_str:
+                        uses.setdefault(target, []).append(f"use {imp['full_import_name']}; (line {imp['line_number']})")
+
+                for target, statements in uses.items():
+                    session.run("""
+                        MATCH (a:File {path: $file_path})
+                        MATCH (b:File {path: $target})
+                        MERGE (a)-[r:USES]->(b)
+                        SET r.imports = $statements
+                    """, file_path=file_path_str, target=target, statements=statements)
+
+    def _create_module_cfg(self, all_file_data: list[Dict]):
+        """
+        Marks the files of Rust modules declared under `#[cfg(...)]` (`#[cfg(feature = "x")] mod x;`)
+        with the conditions of every gated module above them (`module_cfg`) and the features they
+        require (`module_features`); item-level conditions are kept on the items themselves.
+        """
+        from .languages import rust as rust_lang_module
+
+        gated = {}
+        for file_data in all_file_data:
+            if file_data.get('lang') != 'rust' or not file_data.get('module_path'):
+                continue
+            for declaration in file_data.get('module_declarations', []):
+                if declaration.get('cfg') and not declaration['is_inline']:
+                    parent = file_data['module_path']
+                    if declaration.get('inline_module'):
+                        parent += '::' + declaration['inline_module']
+                    gated[(file_data['crate_root'], f"{parent}::{declaration['name']}")] = declaration['cfg']
+
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                if file_data.get('lang') != 'rust' or not file_data.get('module_path'):
+                    continue
+                segments = file_data['module_path'].split('::')
+                conditions = [condition for end in range(2, len(segments) + 1)
+                              for condition in gated.get((file_data['crate_root'], '::'.join(segments[:end])), [])]
+                features = {name for negated, name in rust_lang_module.CFG_FEATURE.findall(" ".join(conditions)) if not negated}
+                session.run("""
+                    MATCH (f:File {path: $file_path})
+                    SET f.module_cfg = $conditions, f.module_features = $features
+                """, file_path=str(Path(file_data['file_path']).resolve()), conditions=conditions, features=sorted(features))
+
+    def _create_module_docs(self, all_file_data: list[Dict]):
+        """
+        Documents the files of Rust modules declared with doc comments (`/// Parsing.` above
+        `mod parser;`): like rustdoc, their `docstring` is those docs followed by the file's own `//!` docs.
+        """
+        declared = {}
+        for file_data in all_file_data:
+            if file_data.get('lang') != 'rust' or not file_data.get('module_path'):
+                continue
+            for declaration in file_data.get('module_declarations', []):
+                if declaration.get('docstring') and not declaration['is_inline']:
+                    parent = file_data['module_path']
+                    if declaration.get('inline_module'):
+                        parent += '::' + declaration['inline_module']
+                    declared[(file_data['crate_root'], f"{parent}::{declaration['name']}")] = declaration['docstring']
+
+        with self.driver.session() as session:
+            for file_data in all_file_data:
+                outer = declared.get((file_data.get('crate_root'), file_data.get('module_path')))
+                if file_data.get('lang') != 'rust' or not outer:
+                    continue
+                session.run("MATCH (f:File {path: $file_path}) SET f.docstring = $docstring",
+                            file_path=str(Path(file_data['file_path']).resolve()),
+                            docstring="\n".join(filter(None, (outer, file_data.get('docstring')))))
+
+    def _record_glob_imports(self, all_file_data: list[Dict]):
+        """
+        Stores on the IMPORTS edge of each Rust glob import (`use foo::*`) the module it resolves
+        to (`glob_target`), the names it brings into scope (`glob_provided`) and those the importing
+        module actually uses (`glob_used`).
+        """
+        from .languages import rust as rust_lang_module
+
+        crates = {}
+        for file_data in all_file_data:
+            if file_data.get('lang') == 'rust' and file_data.get('module_path'):
+                crates.setdefault(file_data['crate_root'], []).append(file_data)
+
+        with self.driver.session() as session:
+            for files in crates.values():
+                for glob in rust_lang_module.glob_import_usage(files):
+                    session.run("""
+                        MATCH (f:File {path: $file_path})-[r:IMPORTS]->(:Module {name: $name})
+                        SET r.glob_module = $module, r.glob_target = $target,
+                            r.glob_provided = $provided, r.glob_used = $used
+                    """, file_path=str(Path(glob['file_path']).resolve()), name=glob['import_path'], module=glob['module'],
+                        target=glob['target_module'], provided=glob['provided'], used=glob['used'])
+
+    def _create_public_api_paths(self, all_file_data: list[Dict]):
+        """
+        Stores on Rust functions and types the paths under which their crate exposes them
+        (`public_paths`, e.g. `my_crate::Person` for a `pub use` in lib.rs), or an empty list.
+        """
+        from .languages import rust as rust_lang_module
+
+        crates = {}
+        for file_data in all_file_data:
+            if file_data.get('lang') == 'rust' and file_data.get('module_path'):
+                crates.setdefault(file_data['crate_root'], []).append(file_data)
+
+        with self.driver.session() as session:
+            for crate_root, files in crates.items():
+                manifest = rust_lang_module.read_cargo_manifest(Path(crate_root))
+                crate_name = (manifest['name'] or Path(crate_root).name).replace('-', '_')
+                paths = rust_lang_module.public_item_paths(files)
+                for file_data in files:
+                    file_path_str = str(Path(file_data['file_path']).resolve())
+                    for label, items in (('Function', file_data.get('functions', [])), ('Class', file_data.get('classes', []))):
+                        for item in items:
+                            public_paths = [crate_name + path[len('crate'):] for path in
+                                            paths.get((file_data['file_path'], item['name'], item['line_number']), [])]
+                            session.run(f"""
+                                MATCH (n:{label} {{name: $name, file_path: $file_path, line_number: $line_number}})
+                                SET n.public_paths = $public_paths
+                            """, name=item['name'], file_path=file_path_str, line_number=item['line_number'],
+                                public_paths=public_paths)
 
     def update_file_in_graph(self, file_path: Path, repo_path: Path, imports_map: dict):
-        """Updates a single file's nodes in the graph."""
+        """
+        Updates a single file's nodes in the graph. Existing nodes are upserted by their `uid`, so
+        they keep their identity and the relationships other files have to them; items whose
+        fingerprint did not change are left untouched along with their own relationships.
+        """
         file_path_str = str(file_path.resolve())
         repo_name = repo_path.name
-        
-        self.delete_file_from_graph(file_path_str)
 
-        if file_path.exists():
-            file_data = self.parse_file(repo_path, file_path)
-            
-            if "error" not in file_data:
-                self.add_file_to_graph(file_data, repo_name, imports_map)
-                return file_data
-            else:
-                logger.error(f"Skipping graph add for {file_path_str} due to parsing error: {file_data['error']}")
-                return None
-        else:
+        if not file_path.exists():
+            self.delete_file_from_graph(file_path_str)
             return {"deleted": True, "path": file_path_str}
 
+        file_data = self.parse_file(repo_path, file_path)
+        if "error" in file_data:
+            logger.error(f"Skipping graph add for {file_path_str} due to parsing error: {file_data['error']}")
+            return None
+        self.add_file_to_graph(file_data, repo_name, imports_map)
+        logger.info(f"Updated {file_path_str}: {file_data.get('item_changes')}")
+        return file_data
+
     def parse_file(self, repo_path: Path, file_path: Path, is_dependency: bool = False) -> Dict:
         """Parses a file with the appropriate language parser and extracts code elements."""
-        parser = self.parsers.get(file_path.suffix)
+        parsers = getattr(self.local, 'parsers', None)
+        if parsers is None:
+            parsers = self.local.parsers = create_parsers()
+        parser = parsers.get(file_path.suffix)
         if not parser:
             logger.warning(f"No parser found for file extension {file_path.suffix}. Skipping {file_path}")
             return {"file_path": str(file_path), "error": f"No parser for {file_path.suffix}"}
@@ -469,7 +2461,7 @@ class GraphBuilder:
         debug_log(f"[parse_file] Starting parsing for: {file_path} with {parser.language_name} parser")
         try:
             file_data = parser.parse(file_path, is_dependency)
-            file_data['repo_path'] = str(repo_path)
+            file_data['repo_path'] = intern_tree(str(repo_path))
             if debug_mode:
                 debug_log(f"[parse_file] Successfully parsed: {file_path}")
             return file_data
@@ -478,6 +2470,15 @@ class GraphBuilder:
             debug_log(f"[parse_file] Error parsing {file_path}: {e}")
             return {"file_path": str(file_path), "error": str(e)}
 
+    def source_files(self, path: Path) -> list[Path]:
+        """
+        The files indexing parses under a directory, leaving out those its ignore rules exclude
+        (see `ignore`), or the path itself when it is a supported file.
+        """
+        if path.is_file():
+            return [path] if path.suffix in self.parsers else []
+        return list(IgnoreRules.load(path).walk(self.parsers))
+
     def estimate_processing_time(self, path: Path) -> Optional[Tuple[int, float]]:
         """Estimate processing time and file count"""
         try:
@@ -488,8 +2489,7 @@ class GraphBuilder:
                 else:
                     return 0, 0.0 # Not a supported file type
             else:
-                all_files = path.rglob("*")
-                files = [f for f in all_files if f.is_file() and f.suffix in supported_extensions]
+                files = self.source_files(path)
             
             total_files = len(files)
             estimated_time = total_files * 0.05 # tree-sitter is faster
@@ -498,20 +2498,68 @@ class GraphBuilder:
             logger.error(f"Could not estimate processing time for {path}: {e}")
             return None
 
+    def _link_files(self, all_file_data: list[Dict], imports_map: dict, crate_data: list[Dict] = None):
+        """
+        Runs the passes linking parsed files to the rest of the graph, the second phase of indexing
+        once every file's definitions are in (so references resolve whatever the file order), then
+        patches the placeholders of earlier unresolved references. Crate-wide passes (crate and
+        module metadata, public paths, thread safety) see `crate_data`, the parsed files of every
+        crate involved, which defaults to `all_file_data`.
+        """
+        crate_data = all_file_data if crate_data is None else crate_data
+
+        def link(step, *args):
+            # The passes are idempotent, so one interrupted by a lost connection is run again as a whole.
+            return self.db_manager.run_with_retry(lambda: step(*args), step.__name__)
+
+        link(self._create_all_inheritance_links, all_file_data, imports_map)
+        link(self._create_all_impl_links, all_file_data, imports_map)
+        link(self._create_all_builder_links, all_file_data, imports_map)
+        link(self._create_all_newtype_links, all_file_data, imports_map)
+        link(self._create_all_function_calls, all_file_data, imports_map)
+        link(self._mark_all_discarded_calls, all_file_data)
+        link(self._mark_method_shadowing)
+        linked = {str(Path(file_data['file_path']).resolve()) for file_data in all_file_data}
+        patched = link(self._resolve_placeholders) - linked
+        link(self._weigh_all_calls, [{"file_path": path} for path in sorted(linked | patched)])
+        link(self._create_all_pointer_wrapping_links, all_file_data, imports_map)
+        link(self._create_all_variant_usage_links, all_file_data, imports_map)
+        link(self._create_all_field_access_links, all_file_data, imports_map)
+        link(self._create_all_type_reference_links, all_file_data, imports_map)
+        link(self._create_all_instantiation_links, all_file_data, imports_map)
+        link(self._create_all_higher_order_links, all_file_data, imports_map)
+        link(self._create_all_dispatch_links, all_file_data)
+        link(self._create_all_thread_safety_links, crate_data, imports_map)
+        link(self._create_all_lock_order_links, all_file_data, imports_map)
+        link(self._create_crate_nodes, crate_data)
+        link(self._create_module_use_links, crate_data)
+        link(self._create_module_cfg, crate_data)
+        link(self._create_module_docs, crate_data)
+        link(self._record_glob_imports, crate_data)
+        link(self._create_public_api_paths, crate_data)
+        link(self._compute_centrality)
+        link(self._create_all_embeddings, all_file_data)
+        self.mark_graph_changed()
+
     async def build_graph_from_path_async(
-        self, path: Path, is_dependency: bool = False, job_id: str = None
+        self, path: Path, is_dependency: bool = False, job_id: str = None, dedup: bool = True
     ):
-        """Builds graph from a directory or file path."""
+        """
+        Builds graph from a directory or file path in two phases: the definitions of every file
+        first, then the references between them (see `_link_files`). With `dedup`, files
+        byte-identical to one already indexed are recorded as its aliases (see `sources`).
+        A failure is reported on the job, or raised when there is none.
+        """
+        # Parsed files wait here for the linking passes, spilling to disk past CGC_MEMORY_LIMIT_MB.
+        all_file_data = SpillList(memory_limit_bytes())
         try:
             if job_id:
-                self.job_manager.update_job(job_id, status=JobStatus.RUNNING)
+                self.job_manager.report_progress(job_id, status=JobStatus.RUNNING, phase="scanning")
             
             self.add_repository_to_graph(path, is_dependency)
             repo_name = path.name
 
-            supported_extensions = self.parsers.keys()
-            all_files = path.rglob("*") if path.is_dir() else [path]
-            files = [f for f in all_files if f.is_file() and f.suffix in supported_extensions]
+            files = self.source_files(path)
             if job_id:
                 self.job_manager.update_job(job_id, total_files=len(files))
             
@@ -519,31 +2567,71 @@ class GraphBuilder:
             imports_map = self._pre_scan_for_imports(files)
             debug_log(f"Pre-scan complete. Found {len(imports_map)} definitions.")
 
-            all_file_data = []
-
             processed_count = 0
-            for file in files:
-                if file.is_file():
+            nodes_written = 0
+            contents = ContentIndex() if dedup and path.is_dir() else None
+
+            def claimed():
+                # On the feeder thread and in file order, so the first of identical files is the one parsed.
+                for file in files:
+                    if file.is_file():
+                        yield file, contents.claim(file.resolve()) if contents else None
+
+            def parse(item):
+                file, canonical = item
+                repo_path = path.resolve() if path.is_dir() else file.parent.resolve()
+                return file, repo_path, canonical, None if canonical else self.parse_file(repo_path, file, is_dependency)
+
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="indexing")
+            # Files are parsed on worker threads while this one writes; see `pipeline` for the backpressure.
+            workers = max(1, int(os.getenv("CGC_PARSE_WORKERS", DEFAULT_PARSE_WORKERS)))
+            capacity = max(1, int(os.getenv("CGC_PIPELINE_CAPACITY", DEFAULT_PIPELINE_CAPACITY)))
+            loop = asyncio.get_running_loop()
+            with BoundedPipeline(parse, workers, capacity).start(claimed()) as pipeline:
+                while True:
+                    parsed = await loop.run_in_executor(None, pipeline.next)
+                    if parsed is None:
+                        break
+                    file, repo_path, canonical, file_data = parsed
                     if job_id:
                         self.job_manager.update_job(job_id, current_file=str(file))
-                    repo_path = path.resolve() if path.is_dir() else file.parent.resolve()
-                    file_data = self.parse_file(repo_path, file, is_dependency)
-                    if "error" not in file_data:
+                    if canonical:
+                        self.add_alias_file_to_graph(file, canonical, repo_path, is_dependency)
+                        nodes_written += 1
+                    elif "error" not in file_data:
                         self.add_file_to_graph(file_data, repo_name, imports_map)
                         all_file_data.append(file_data)
+                        nodes_written += file_data.get('nodes_written', 0)
+                    elif contents:
+                        contents.forget(file.resolve())
                     processed_count += 1
                     if job_id:
-                        self.job_manager.update_job(job_id, processed_files=processed_count)
-                    await asyncio.sleep(0.01)
+                        self.job_manager.report_progress(job_id, processed_files=processed_count,
+                                                         nodes_written=nodes_written, queues=pipeline.depths())
+            if contents and contents.aliases:
+                logger.info(f"Recorded {contents.aliases} files of {path} as aliases of byte-identical files")
+
+            if path.is_dir():
+                self._add_included_files(all_file_data, path, repo_name, imports_map, is_dependency)
 
-            self._create_all_inheritance_links(all_file_data, imports_map)
-            self._create_all_function_calls(all_file_data, imports_map)
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="linking", current_file=None,
+                                                 nodes_written=self._nodes_written(all_file_data))
+            self._link_files(all_file_data, imports_map)
+            if not is_dependency:
+                if job_id:
+                    self.job_manager.report_progress(job_id, phase="annotating")
+                self._annotate_git_history(all_file_data, path)
+                self._check_layering(path)
             
+            self.mark_graph_changed()
             if job_id:
-                self.job_manager.update_job(job_id, status=JobStatus.COMPLETED, end_time=datetime.now())
+                self.job_manager.report_progress(job_id, status=JobStatus.COMPLETED, phase="completed", end_time=datetime.now())
         except Exception as e:
             error_message=str(e)
             logger.error(f"Failed to build graph for path {path}: {error_message}", exc_info=True)
+            self.mark_graph_changed()
             if job_id:
                 '''checking if the repo got deleted '''
                 if "no such file found" in error_message or "deleted" in error_message or "not found" in error_message:
@@ -552,6 +2640,418 @@ class GraphBuilder:
                 else:
                     status=JobStatus.FAILED
 
-                self.job_manager.update_job(
-                    job_id, status=JobStatus.FAILED, end_time=datetime.now(), errors=[str(e)]
-                )
\ No newline at end of file
+                self.job_manager.report_progress(
+                    job_id, status=JobStatus.FAILED, phase="failed", end_time=datetime.now(), errors=[str(e)]
+                )
+            else:
+                # Without a job nobody would hear of the failure; the caller must.
+                raise
+        finally:
+            all_file_data.close()
+
+    def _write_file_extras(self, file_data: Dict, file_path_str: str):
+        with self.driver.session() as session:
+            self._create_file_extras(session, file_data, file_path_str)
+
+    async def complete_bulk_import_async(self, path: Path, job_id: str = None) -> Optional[Dict[str, Any]]:
+        """
+        Finishes a repository loaded with `neo4j-admin` from a `bulk_import` export: creates the
+        language-specific nodes of each file and runs the linking passes, as indexing would have
+        after writing the definitions. Returns how many files were completed and the parse errors.
+        """
+        all_file_data, errors = SpillList(memory_limit_bytes()), []
+        try:
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.RUNNING, phase="scanning")
+            path = path.resolve()
+            files = self.source_files(path)
+            imports_map = self._pre_scan_for_imports(files)
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="indexing", total_files=len(files))
+            for processed, file in enumerate(files, 1):
+                file_data = self.parse_file(path, file)
+                if "error" in file_data:
+                    errors.append(f"{file}: {file_data['error']}")
+                else:
+                    self.db_manager.run_with_retry(lambda: self._write_file_extras(file_data, str(file.resolve())),
+                                                   f"Completing {file}")
+                    all_file_data.append(file_data)
+                if job_id:
+                    self.job_manager.report_progress(job_id, processed_files=processed, current_file=str(file))
+                await asyncio.sleep(0.01)
+
+            self._add_included_files(all_file_data, path, path.name, imports_map)
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="linking", current_file=None)
+            self._link_files(all_file_data, imports_map)
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="annotating")
+            self._annotate_git_history(all_file_data, path)
+            self._check_layering(path)
+            self.mark_graph_changed()
+            result = {"files": len(all_file_data), "errors": errors}
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.COMPLETED, phase="completed", end_time=datetime.now(), result=result)
+            return result
+        except Exception as e:
+            logger.error(f"Failed to complete the bulk import of {path}: {e}", exc_info=True)
+            self.mark_graph_changed()
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.FAILED, phase="failed", end_time=datetime.now(), errors=[str(e)])
+            return None
+        finally:
+            all_file_data.close()
+
+    def plan_shards(self, path: Path) -> list[Dict]:
+        """The shards of a repository (see `shards`), one per Cargo package plus the root shard."""
+        from .languages import rust as rust_lang_module
+
+        files = self.source_files(path)
+        return plan_shards(path, files, lambda root: rust_lang_module.read_cargo_manifest(root)['name'])
+
+    def _index_shard(self, shard: Dict, repo_path: Path, imports_map: dict, is_dependency: bool,
+                     shard_data: SpillList, contents: Optional[ContentIndex], on_file: Callable[[Dict], None]):
+        """
+        Parses a shard's files and writes their definitions, on a worker thread of a sharded build;
+        the parsed files go to `shard_data` and their contents to `contents`, shared by all shards.
+        """
+        shard.update(status="running", processed_files=0, nodes_written=0, errors=[])
+        started = time.time()
+        for file in shard['files']:
+            canonical = contents.claim(file) if contents else None
+            if canonical:
+                self.add_alias_file_to_graph(Path(file), canonical, repo_path, is_dependency)
+                shard['nodes_written'] += 1
+                shard['processed_files'] += 1
+                on_file(shard)
+                continue
+            file_data = self.parse_file(repo_path, Path(file), is_dependency)
+            if "error" in file_data:
+                shard['errors'].append(f"{file}: {file_data['error']}")
+                if contents:
+                    contents.forget(file)
+            else:
+                self.add_file_to_graph(file_data, repo_path.name, imports_map)
+                shard_data.append(file_data)
+                shard['nodes_written'] += file_data.get('nodes_written', 0)
+            shard['processed_files'] += 1
+            on_file(shard)
+        shard.update(status="indexed", duration_seconds=round(time.time() - started, 3))
+
+    def _record_shards(self, repo_path: Path, shards: list[Dict]):
+        """Stores the status of each shard on its Shard node (Repository-[:HAS_SHARD]->Shard)."""
+        with self.driver.session() as session:
+            self._write_batches(session, """
+                UNWIND $rows AS row
+                MATCH (r:Repository {path: $repo_path})
+                MERGE (s:Shard {path: row.path})
+                SET s.name = row.name, s.status = row.status, s.file_count = row.file_count,
+                    s.nodes_written = row.nodes_written, s.errors = row.errors,
+                    s.duration_seconds = row.duration_seconds, s.indexed_at = $indexed_at
+                MERGE (r)-[:HAS_SHARD]->(s)
+            """, [{"path": shard['path'], "name": shard['name'], "status": shard['status'],
+                   "file_count": len(shard['files']), "nodes_written": shard.get('nodes_written', 0),
+                   "errors": shard.get('errors', []), "duration_seconds": shard.get('duration_seconds')}
+                  for shard in shards],
+                repo_path=str(repo_path.resolve()), indexed_at=int(time.time()))
+
+    async def build_sharded_async(self, path: Path, is_dependency: bool = False, job_id: str = None,
+                                  max_workers: int = None, dedup: bool = True):
+        """
+        Builds the graph of a multi-crate repository shard by shard (see `shards`): the definitions
+        of up to `max_workers` shards are written in parallel, then every file is linked in a final
+        pass that stitches the references between shards. The job reports each shard's status.
+        With `dedup`, files byte-identical to one already indexed, in any shard, become its aliases.
+        """
+        shards = []
+        all_file_data = SpillList(memory_limit_bytes())
+        try:
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.RUNNING, phase="scanning")
+            path = path.resolve()
+            self.add_repository_to_graph(path, is_dependency)
+            shards = self.plan_shards(path)
+            for shard in shards:
+                shard.update(status="pending", processed_files=0, nodes_written=0, errors=[])
+            files = [Path(file) for shard in shards for file in shard['files']]
+            imports_map = self._pre_scan_for_imports(files)
+
+            lock = threading.Lock()
+            def on_file(shard):
+                if job_id:
+                    with lock:
+                        self.job_manager.report_progress(
+                            job_id, current_file=None, shards=[{key: value for key, value in s.items() if key != 'files'} for s in shards],
+                            processed_files=sum(s['processed_files'] for s in shards),
+                            nodes_written=sum(s['nodes_written'] for s in shards))
+
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="indexing", total_files=len(files))
+            workers = max_workers or max(1, int(os.getenv("CGC_SHARD_WORKERS", DEFAULT_SHARD_WORKERS)))
+            contents = ContentIndex() if dedup else None
+            loop = asyncio.get_running_loop()
+            with ThreadPoolExecutor(max_workers=workers, thread_name_prefix="cgc-shard") as pool:
+                outcomes = await asyncio.gather(*[
+                    loop.run_in_executor(pool, self._index_shard, shard, path, imports_map, is_dependency, all_file_data,
+                                         contents, on_file)
+                    for shard in shards
+                ], return_exceptions=True)
+            for shard, outcome in zip(shards, outcomes):
+                if isinstance(outcome, BaseException):
+                    shard.update(status="failed", errors=shard.get('errors', []) + [str(outcome)])
+
+            self._add_included_files(all_file_data, path, path.name, imports_map, is_dependency)
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="linking")
+            self._link_files(all_file_data, imports_map)
+            if not is_dependency:
+                if job_id:
+                    self.job_manager.report_progress(job_id, phase="annotating")
+                self._annotate_git_history(all_file_data, path)
+                self._check_layering(path)
+            for shard in shards:
+                if shard['status'] == "indexed":
+                    shard['status'] = "completed"
+            self._record_shards(path, shards)
+
+            self.mark_graph_changed()
+            summary = [{key: value for key, value in shard.items() if key != 'files'} for shard in shards]
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.COMPLETED, phase="completed", end_time=datetime.now(),
+                                                 shards=summary, result={"shards": summary})
+        except Exception as e:
+            logger.error(f"Failed to build sharded graph for path {path}: {e}", exc_info=True)
+            self.mark_graph_changed()
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.FAILED, phase="failed", end_time=datetime.now(), errors=[str(e)])
+        finally:
+            all_file_data.close()
+
+    async def reindex_shard_async(self, path: Path, shard_path: Path, job_id: str = None) -> Optional[Dict[str, Any]]:
+        """
+        Re-indexes one shard of a repository incrementally (see `reindex_path_async`), re-linking
+        the files of other shards that depend on it, and records its new status. The root shard
+        spans the whole repository.
+        """
+        path, shard_path = path.resolve(), shard_path.resolve()
+        shard = next((s for s in self.plan_shards(path) if Path(s['path']) == shard_path), None)
+        if shard is None:
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.FAILED, phase="failed", end_time=datetime.now(),
+                                                 errors=[f"{shard_path} is not a shard of {path}"])
+            return None
+        started = time.time()
+        result = await self.reindex_path_async(path, job_id, scope=None if shard_path == path else shard_path)
+        shard.update(status="completed" if result is not None else "failed",
+                     errors=result.get('errors', []) if result is not None else ["Re-index failed, see the server log"],
+                     duration_seconds=round(time.time() - started, 3))
+        self._record_shards(path, [shard])
+        return result
+
+    def _nodes_written(self, all_file_data: list[Dict]) -> int:
+        """How many File and item nodes `add_file_to_graph` upserted for the parsed files."""
+        return sum(file_data.get('nodes_written', 0) for file_data in all_file_data)
+
+    def _find_dependent_files(self, session, root: str, paths: list[str], names: set = None) -> set:
+        """
+        The indexed files under `root`, other than `paths`, with relationships into the elements of
+        `paths` or, given `names`, with functions calling or files importing one of those names.
+        """
+        dependents = {record['path'] for record in session.run("""
+            MATCH (src)-[r]->(dst)
+            WHERE coalesce(dst.file_path, dst.path) IN $paths AND type(r) <> 'CONTAINS'
+            WITH DISTINCT coalesce(src.file_path, src.path) as path
+            WHERE path STARTS WITH $root AND NOT path IN $paths
+            RETURN path
+        """, root=root, paths=paths)}
+        if names:
+            dependents |= {record['path'] for record in session.run("""
+                MATCH (fn:Function)
+                WHERE fn.file_path STARTS WITH $root AND NOT fn.file_path IN $paths
+                  AND ANY(call IN coalesce(fn.call_names, [])
+                          WHERE ANY(segment IN split(replace(call, '.', '::'), '::') WHERE segment IN $names))
+                RETURN DISTINCT fn.file_path as path
+                UNION
+                MATCH (f:File)-[r:IMPORTS]->(:Module)
+                WHERE f.path STARTS WITH $root AND NOT f.path IN $paths
+                  AND last(split(replace(coalesce(r.full_import_name, ''), '.', '::'), '::')) IN $names
+                RETURN DISTINCT f.path as path
+            """, root=root, paths=paths, names=sorted(names))}
+        return dependents
+
+    def _imports_map_from_graph(self, root: str, parsed_files: list[Path]) -> dict:
+        """
+        The imports map of a repository (see `_pre_scan_for_imports`) built from the functions and
+        types already in the graph, with `parsed_files` pre-scanned in place of their indexed state.
+        """
+        fresh = self._pre_scan_for_imports(parsed_files) if parsed_files else {}
+        skipped = [str(file.resolve()) for file in parsed_files]
+        imports_map = {}
+        with self.driver.session() as session:
+            for record in session.run("""
+                MATCH (n) WHERE (n:Function OR n:Class) AND n.file_path STARTS WITH $root
+                  AND NOT n.file_path IN $skipped AND n.generated_by IS NULL
+                RETURN n.name as name, n.file_path as file_path
+                ORDER BY file_path, n.line_number
+            """, root=root, skipped=skipped):
+                imports_map.setdefault(record['name'], []).append(record['file_path'])
+        for name, files in fresh.items():
+            imports_map.setdefault(name, []).extend(files)
+        return intern_tree(imports_map)
+
+    def _crate_summaries(self, repo_path: Path, parsed: Dict[str, Dict]) -> list[Dict]:
+        """
+        The `crate_summary` of every other Rust file of the crates the `parsed` files belong to, read
+        from the graph (see `incremental`); files indexed before summaries were stored are parsed.
+        """
+        crate_roots = sorted({file_data['crate_root'] for file_data in parsed.values()
+                              if file_data.get('lang') == 'rust' and file_data.get('crate_root')})
+        if not crate_roots:
+            return []
+        summaries, missing = [], []
+        with self.driver.session() as session:
+            for record in session.run("""
+                MATCH (f:File)-[:PART_OF]->(c:Crate) WHERE c.path IN $crate_roots AND NOT f.path IN $parsed
+                RETURN f.path as path, f.crate_summary as summary
+            """, crate_roots=crate_roots, parsed=list(parsed)):
+                if record['summary']:
+                    summaries.append(json.loads(record['summary']))
+                elif Path(record['path']).exists():
+                    missing.append(record['path'])
+        for file in missing:
+            file_data = self.parse_file(repo_path, Path(file))
+            if "error" not in file_data:
+                summaries.append(file_data)
+        return summaries
+
+    async def reindex_path_async(self, path: Path, job_id: str = None, scope: Path = None,
+                                 paths: list = None) -> Optional[Dict[str, Any]]:
+        """
+        Re-indexes an indexed repository incrementally (see `incremental`): only added and changed
+        files are parsed and their subgraphs upserted, and removed files are deleted. The files with
+        relationships into any of them, or calling or importing a name they define, are re-indexed
+        too and everything is re-linked, so no cross-file edge dangles or keeps a stale target.
+        Given a `scope` (a directory of the repository, such as a shard), only its files are compared,
+        though dependents anywhere in the repository are still re-linked. Given `paths` (files of the
+        repository known to have changed, e.g. by the watcher), only those are compared and the rest
+        of the repository is neither walked nor pre-scanned: names resolve against the graph.
+        Returns the plan with the `relinked` dependents and how many `items` of the parsed files were
+        added, removed, modified or left unchanged.
+        """
+        try:
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.RUNNING, phase="scanning")
+            path = path.resolve()
+            scope = scope.resolve() if scope else path
+            root = str(path) + os.sep
+            repo_name = path.name
+
+            rules = IgnoreRules.load(path)
+            if paths is not None:
+                paths = sorted({str(Path(file).resolve()) for file in paths})
+                repo_files = None
+                files = [Path(file) for file in paths if Path(file).suffix in self.parsers
+                         and Path(file).is_file() and not rules.is_ignored(Path(file))]
+            else:
+                repo_files = list(rules.walk(self.parsers))
+                files = repo_files if scope == path else [f for f in repo_files if scope in f.parents]
+            current = {str(f.resolve()): file_hash(f) for f in files}
+            current = {file: digest for file, digest in current.items() if digest is not None}
+            with self.driver.session() as session:
+                indexed = {record['path']: record['content_hash'] for record in session.run("""
+                    MATCH (f:File) WHERE f.path STARTS WITH $root AND ($paths IS NULL OR f.path IN $paths)
+                    RETURN f.path as path, f.content_hash as content_hash
+                """, root=str(scope) + os.sep, paths=paths)}
+            # Files an ignore rule now excludes are dropped like deleted ones.
+            plan = plan_reindex(current, indexed, lambda file: Path(file).exists() and not rules.is_ignored(Path(file)))
+            if plan['changed'] or plan['removed']:
+                # Aliases of changed or removed files (see `sources`) no longer share their items: parse them on their own.
+                with self.driver.session() as session:
+                    aliases = {record['path'] for record in session.run("""
+                        MATCH (a:File)-[:ALIAS_OF]->(c:File) WHERE c.path IN $paths RETURN a.path as path
+                    """, paths=plan['changed'] + plan['removed'])}
+                for alias in sorted(aliases - set(plan['changed']) - set(plan['removed'])):
+                    if Path(alias).exists() and not rules.is_ignored(Path(alias)):
+                        plan['changed'].append(alias)
+                        if alias in plan['unchanged']:
+                            plan['unchanged'].remove(alias)
+            touched = plan['added'] + plan['changed'] + plan['removed']
+            result = {key: plan[key] for key in ('added', 'changed', 'removed')}
+            result.update({"unchanged": len(plan['unchanged']), "relinked": []})
+            if job_id:
+                self.job_manager.report_progress(job_id, phase="indexing", total_files=len(plan['added']) + len(plan['changed']))
+
+            if not indexed and plan['added'] and scope == path:
+                # A repository first indexed this way (e.g. a watched directory) needs its node too.
+                self.add_repository_to_graph(path)
+            if touched:
+                # Edges into removed and changed files must be found before their elements are replaced.
+                with self.driver.session() as session:
+                    dependents = self._find_dependent_files(session, root, plan['changed'] + plan['removed'])
+                for removed in plan['removed']:
+                    self.delete_file_from_graph(removed)
+
+                # Calls out of the scope resolve against the definitions of the whole repository.
+                if repo_files is None:
+                    imports_map = self._imports_map_from_graph(root, [Path(file) for file in plan['added'] + plan['changed']])
+                else:
+                    imports_map = self._pre_scan_for_imports(repo_files)
+                parsed, errors = {}, []
+                for file in plan['added'] + plan['changed']:
+                    if job_id:
+                        self.job_manager.update_job(job_id, current_file=file)
+                    file_data = self.parse_file(path, Path(file))
+                    if "error" in file_data:
+                        errors.append(f"{file}: {file_data['error']}")
+                    else:
+                        self.add_file_to_graph(file_data, repo_name, imports_map)
+                        parsed[file] = file_data
+                    if job_id:
+                        self.job_manager.report_progress(job_id, processed_files=len(parsed) + len(errors),
+                                                         nodes_written=self._nodes_written(list(parsed.values())))
+                    await asyncio.sleep(0.01)
+
+                result["items"] = {key: sum(file_data['item_changes'][key] for file_data in parsed.values())
+                                   for key in ('added', 'removed', 'modified', 'unchanged')}
+                names = {item['name'] for file_data in parsed.values()
+                         for key in ('functions', 'classes') for item in file_data.get(key, [])}
+                with self.driver.session() as session:
+                    dependents |= self._find_dependent_files(session, root, touched, names)
+                if job_id:
+                    self.job_manager.report_progress(job_id, phase="linking", current_file=None)
+                for file in sorted(file for file in dependents - set(touched) if Path(file).exists()):
+                    file_data = self.parse_file(path, Path(file))
+                    if "error" not in file_data:
+                        self.add_file_to_graph(file_data, repo_name, imports_map)
+                        parsed[file] = file_data
+                        result['relinked'].append(file)
+
+                # Crate-wide passes need every module of the crates involved, not only the parsed ones.
+                crate_data = list(parsed.values()) + self._crate_summaries(path, parsed)
+                self._link_files(list(parsed.values()), imports_map, crate_data)
+                if job_id:
+                    self.job_manager.report_progress(job_id, phase="annotating",
+                                                     nodes_written=self._nodes_written(list(parsed.values())))
+                self._annotate_git_history(list(parsed.values()), path)
+                self._check_layering(path)
+                result["errors"] = errors
+
+                if self.db_manager.remote_configured():
+                    if job_id:
+                        self.job_manager.report_progress(job_id, phase="syncing")
+                    try:
+                        result["sync"] = self.sync_to_remote(path)
+                    except Exception as e:
+                        logger.error(f"Failed to sync {path} to the remote database: {e}")
+                        result["sync"] = {"error": str(e)}
+
+            self.mark_graph_changed()
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.COMPLETED, phase="completed", end_time=datetime.now(), result=result)
+            return result
+        except Exception as e:
+            logger.error(f"Failed to re-index {path}: {e}", exc_info=True)
+            self.mark_graph_changed()
+            if job_id:
+                self.job_manager.report_progress(job_id, status=JobStatus.FAILED, phase="failed", end_time=datetime.now(), errors=[str(e)])
+            return None
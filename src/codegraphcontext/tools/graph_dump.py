@@ -0,0 +1,132 @@
+# src/codegraphcontext/tools/graph_dump.py
+"""
+Dumps the graph (or one repository's part of it) to JSON Lines and loads such dumps back.
+
+A dump is a header line, then one line per node sorted by key, then one line per relationship
+sorted by key:
+
+    {"format": "cgc-graph", "version": 1, "repository": "/path" or null, "nodes": N, "relationships": M}
+    {"kind": "node", "key": "Function:uid:...", "labels": ["Function"], "owned": true, "properties": {...}}
+    {"kind": "relationship", "key": "CALLS:...#0", "type": "CALLS", "start": "<node key>", "end": "<node key>", "properties": {...}}
+
+Keys are those of `delta_sync` (a node's uid, else its path, else a digest of its properties),
+so they do not depend on the database a dump came from, and dumping the same graph twice gives
+the same file; keys, property names and lines are sorted and written compactly. Byte-array
+properties (compressed snippets, see `snippets`) are written as `{"$bytes": "<base64>"}`. Nodes a
+repository dump references outside the repository (modules, types of other repositories) are
+included with `"owned": false`. Paths ending in `.gz` are gzip-compressed.
+
+Loading writes the nodes and relationships like a first delta sync does: merged on their keys,
+kept on them as `sync_key`, so loading a dump again updates the graph in place.
+"""
+import base64
+import gzip
+import json
+from pathlib import Path
+from typing import Any, Dict, Iterator, Tuple
+
+from . import delta_sync
+
+DUMP_FORMAT = "cgc-graph"
+DUMP_VERSION = 1
+
+
+def _encode(value: Any) -> Any:
+    if isinstance(value, (bytes, bytearray)):
+        return {"$bytes": base64.b64encode(bytes(value)).decode("ascii")}
+    if isinstance(value, list):
+        return [_encode(item) for item in value]
+    if isinstance(value, dict):
+        return {key: _encode(item) for key, item in value.items()}
+    return value
+
+
+def _decode(value: Any) -> Any:
+    if isinstance(value, dict):
+        if set(value) == {"$bytes"}:
+            return base64.b64decode(value["$bytes"])
+        return {key: _decode(item) for key, item in value.items()}
+    if isinstance(value, list):
+        return [_decode(item) for item in value]
+    return value
+
+
+def _line(record: Dict[str, Any]) -> str:
+    return json.dumps(record, sort_keys=True, separators=(",", ":"), ensure_ascii=False, default=str)
+
+
+def dump_lines(session, repo_path: str = None) -> Iterator[str]:
+    """The lines of a dump of the graph, or of the part under `repo_path`; see the module docstring."""
+    graph = delta_sync.snapshot(session, repo_path)
+    yield _line({"format": DUMP_FORMAT, "version": DUMP_VERSION, "repository": repo_path,
+                 "nodes": len(graph["nodes"]), "relationships": len(graph["relationships"])})
+    for key in sorted(graph["nodes"]):
+        node = graph["nodes"][key]
+        yield _line({"kind": "node", "key": key, "labels": node["labels"], "owned": node["owned"],
+                     "properties": _encode(node["props"])})
+    for key in sorted(graph["relationships"]):
+        rel = graph["relationships"][key]
+        yield _line({"kind": "relationship", "key": key, "type": rel["type"], "start": rel["start"], "end": rel["end"],
+                     "properties": _encode(rel["props"])})
+
+
+def _open(path: Path, mode: str):
+    path = Path(path)
+    if path.suffix == ".gz":
+        return gzip.open(path, mode + "t", encoding="utf-8")
+    return open(path, mode, encoding="utf-8")
+
+
+def write_dump(session, path: Path, repo_path: str = None) -> Dict[str, Any]:
+    """Writes a dump to `path` and returns its header."""
+    with _open(path, "w") as f:
+        lines = dump_lines(session, repo_path)
+        header = next(lines)
+        f.write(header + "\n")
+        for line in lines:
+            f.write(line + "\n")
+    return json.loads(header)
+
+
+def read_dump(path: Path) -> Tuple[Dict[str, Any], Dict[str, Dict]]:
+    """
+    The header of a dump and its graph, shaped like `delta_sync.snapshot`'s; raises ValueError
+    when the file is not a dump, is of a newer version, or has relationships to missing nodes.
+    """
+    nodes, relationships = {}, {}
+    with _open(path, "r") as f:
+        try:
+            header = json.loads(f.readline() or "null")
+        except json.JSONDecodeError:
+            header = None
+        if not isinstance(header, dict) or header.get("format") != DUMP_FORMAT:
+            raise ValueError(f"{path} is not a graph dump")
+        if header.get("version", 0) > DUMP_VERSION:
+            raise ValueError(f"{path} is a version {header['version']} dump; this version reads up to {DUMP_VERSION}")
+        for number, line in enumerate(f, start=2):
+            if not line.strip():
+                continue
+            try:
+                record = json.loads(line)
+            except json.JSONDecodeError as e:
+                raise ValueError(f"{path}:{number}: {e}")
+            props = _decode(record.get("properties") or {})
+            if record.get("kind") == "node":
+                nodes[record["key"]] = {"labels": record["labels"], "props": props,
+                                        "hash": delta_sync.digest(props), "owned": record.get("owned", True)}
+            elif record.get("kind") == "relationship":
+                relationships[record["key"]] = {"type": record["type"], "start": record["start"], "end": record["end"],
+                                                "props": props}
+    for key, rel in relationships.items():
+        if rel["start"] not in nodes or rel["end"] not in nodes:
+            raise ValueError(f"Relationship {key} of {path} connects nodes missing from the dump")
+        rel["start_label"] = delta_sync.primary_label(nodes[rel["start"]]["labels"])
+        rel["end_label"] = delta_sync.primary_label(nodes[rel["end"]]["labels"])
+    return header, {"nodes": nodes, "relationships": relationships}
+
+
+def load_graph(session, graph: Dict[str, Dict], repo_path: str = None) -> Dict[str, int]:
+    """Writes a graph read from a dump; returns how many nodes and relationships were written."""
+    delta = delta_sync.diff({}, graph)
+    delta_sync.apply(session, delta, repo_path)
+    return {"nodes": len(delta["upsert_nodes"]), "relationships": len(delta["create_relationships"])}
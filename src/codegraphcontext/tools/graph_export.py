@@ -0,0 +1,251 @@
+# src/codegraphcontext/tools/graph_export.py
+"""
+Renders subgraphs exported by `CodeFinder.export_subgraph` (or whole graphs exported by
+`CodeFinder.export_graph`) for diagram and graph exploration tools.
+
+A subgraph is `{"nodes": [{id, label, name, owner, file_path, line_number}], "edges":
+[{source, target, type, line_number, samples, weight}]}`, where edge ends are node ids, `samples`
+comes from an imported profile and `weight` counts the relationships an edge stands for (e.g. the
+call sites of a caller calling the same function). Nodes and edges of whole-graph exports also
+carry their stored `properties`, less EXCLUDED_PROPERTIES.
+
+GraphML output (for Gephi, yEd or networkx) declares a typed attribute per field and property:
+`boolean`, `long` or `double` when every value of it is one, `string` otherwise, with lists
+written as JSON. Nodes get a display `label` (`Owner::name`), their `kind` (see `node_kind`)
+and their `neo4j_label`; edges get their type as `label`.
+
+DOT output (for Graphviz: `dot -Tsvg`) fills nodes by kind (functions, methods, structs, enums,
+traits, modules; see KIND_STYLES), draws edges by type, and can group the nodes of each source
+file in a cluster. `module_graph` collapses any subgraph to the files its nodes are in, giving
+module graphs in every format.
+"""
+import json
+import os
+import re
+import xml.etree.ElementTree as ET
+from typing import Any, Dict, List
+
+EXPORT_FORMATS = ("json", "mermaid", "graphml", "dot")
+# Properties too large or opaque to export: embedding vectors, source snippets and crate summaries.
+EXCLUDED_PROPERTIES = ("crate_summary", "embedding", "source", "source_code", "source_compressed")
+GRAPHML_NAMESPACE = "http://graphml.graphdrawing.org/xmlns"
+# Characters XML 1.0 cannot hold, even escaped.
+# Fill color and shape of DOT nodes by `node_kind`.
+KIND_STYLES = {
+    "fn": ("#cfe2ff", "box"),
+    "method": ("#e0ecff", "box"),
+    "struct": ("#ffe5b4", "box3d"),
+    "enum": ("#f3d9fa", "box3d"),
+    "union": ("#fde2e2", "box3d"),
+    "trait": ("#d3f9d8", "hexagon"),
+    "module": ("#eeeeee", "folder"),
+}
+# Line style of DOT edges by relationship type; CALLS and the others are solid.
+EDGE_STYLES = {"MAY_CALL": "dashed", "IMPLEMENTS": "dotted", "INHERITS": "dotted", "REFERENCES_TYPE": "dotted"}
+_INVALID_XML = re.compile("[\x00-\x08\x0b\x0c\x0e-\x1f\ufffe\uffff]")
+
+
+def node_title(node: Dict[str, Any]) -> str:
+    """`Owner::name` for methods and fields, the plain name otherwise."""
+    return f"{node['owner']}::{node['name']}" if node.get('owner') else str(node['name'])
+
+
+def node_kind(node: Dict[str, Any]) -> str:
+    """`fn`, `method`, `struct`, `enum`, `trait`, `union` or `module`, else the node's label in lower case."""
+    label = node.get('label') or ''
+    if label == 'Function':
+        return 'method' if node.get('owner') else 'fn'
+    if label == 'Class':
+        return node.get('kind') or 'struct'
+    if label in ('File', 'Module', 'Crate'):
+        return 'module'
+    return label.lower()
+
+
+def to_mermaid(subgraph: Dict[str, Any]) -> str:
+    """
+    A Mermaid flowchart; edges are labelled with their type unless they are all CALLS, with `xN`
+    when they stand for several relationships and with their profile samples when they have some.
+    """
+    ids = {node['id']: f"n{i}" for i, node in enumerate(subgraph['nodes'])}
+    label_edges = any(edge['type'] != 'CALLS' for edge in subgraph['edges'])
+    lines = ["flowchart LR"]
+    for node in subgraph['nodes']:
+        title = re.sub(r'["\n]', "'", node_title(node))
+        shape = ('(["', '"])') if node.get('root') else ('["', '"]')
+        lines.append(f"    {ids[node['id']]}{shape[0]}{title}{shape[1]}")
+    for edge in subgraph['edges']:
+        weight = f"x{edge['weight']}" if (edge.get('weight') or 1) > 1 else None
+        label = " ".join(str(part) for part in (edge['type'] if label_edges else None, weight, edge.get('samples')) if part)
+        arrow = f"-->|{label}|" if label else "-->"
+        lines.append(f"    {ids[edge['source']]} {arrow} {ids[edge['target']]}")
+    return "\n".join(lines) + "\n"
+
+
+def _graphml_type(values: List[Any]) -> str:
+    if all(isinstance(value, bool) for value in values):
+        return "boolean"
+    if all(isinstance(value, int) and not isinstance(value, bool) for value in values):
+        return "long"
+    if all(isinstance(value, (int, float)) and not isinstance(value, bool) for value in values):
+        return "double"
+    return "string"
+
+
+def _graphml_value(value: Any) -> str:
+    if isinstance(value, bool):
+        return "true" if value else "false"
+    if isinstance(value, (list, tuple, dict)):
+        value = json.dumps(value, default=str)
+    return _INVALID_XML.sub("", str(value))
+
+
+def _graphml_attributes(element: Dict[str, Any], fields: tuple) -> Dict[str, Any]:
+    attributes = {field: element.get(field) for field in fields}
+    for key, value in (element.get('properties') or {}).items():
+        attributes.setdefault(key, value)
+    return {key: value for key, value in attributes.items() if value is not None}
+
+
+def to_graphml(subgraph: Dict[str, Any]) -> str:
+    """A GraphML document of the subgraph with typed attributes; see the module docstring."""
+    nodes = [{"label": node_title(node), **_graphml_attributes(
+                 {**node, "kind": node_kind(node), "neo4j_label": node.get('label')},
+                 ('kind', 'neo4j_label', 'name', 'owner', 'file_path', 'line_number', 'root'))}
+             for node in subgraph['nodes']]
+    edges = [{"label": edge['type'], **_graphml_attributes(edge, ('type', 'line_number', 'samples', 'weight'))}
+             for edge in subgraph['edges']]
+
+    ET.register_namespace("", GRAPHML_NAMESPACE)
+    root = ET.Element(f"{{{GRAPHML_NAMESPACE}}}graphml")
+    keys = {}
+    for domain, prefix, elements in (("node", "n", nodes), ("edge", "e", edges)):
+        values = {}
+        for attributes in elements:
+            for name, value in attributes.items():
+                values.setdefault(name, []).append(value)
+        for index, (name, seen) in enumerate(values.items()):
+            keys[domain, name] = f"{prefix}d{index}"
+            ET.SubElement(root, f"{{{GRAPHML_NAMESPACE}}}key", {
+                "id": keys[domain, name], "for": domain, "attr.name": name, "attr.type": _graphml_type(seen)})
+
+    graph = ET.SubElement(root, f"{{{GRAPHML_NAMESPACE}}}graph", {"id": "G", "edgedefault": "directed"})
+    ids = {node['id']: f"n{i}" for i, node in enumerate(subgraph['nodes'])}
+    for node, attributes in zip(subgraph['nodes'], nodes):
+        element = ET.SubElement(graph, f"{{{GRAPHML_NAMESPACE}}}node", {"id": ids[node['id']]})
+        for name, value in attributes.items():
+            ET.SubElement(element, f"{{{GRAPHML_NAMESPACE}}}data", {"key": keys["node", name]}).text = _graphml_value(value)
+    for i, (edge, attributes) in enumerate(zip(subgraph['edges'], edges)):
+        element = ET.SubElement(graph, f"{{{GRAPHML_NAMESPACE}}}edge",
+                                {"id": f"e{i}", "source": ids[edge['source']], "target": ids[edge['target']]})
+        for name, value in attributes.items():
+            ET.SubElement(element, f"{{{GRAPHML_NAMESPACE}}}data", {"key": keys["edge", name]}).text = _graphml_value(value)
+    ET.indent(root)
+    return '<?xml version="1.0" encoding="UTF-8"?>\n' + ET.tostring(root, encoding="unicode") + "\n"
+
+
+def module_names(paths) -> Dict[str, str]:
+    """Each file path relative to the directory holding them all."""
+    paths = sorted(set(paths))
+    if not paths:
+        return {}
+    base = os.path.commonpath([os.path.dirname(path) for path in paths])
+    return {path: os.path.relpath(path, base) for path in paths}
+
+
+def module_graph(subgraph: Dict[str, Any]) -> Dict[str, Any]:
+    """
+    The subgraph with its nodes collapsed to the files they are in: one `Module` node per file and
+    one edge per relationship type between two files, weighing all the edges it stands for.
+    Nodes outside any file and edges within a file are dropped.
+    """
+    files = {node['id']: node['file_path'] for node in subgraph['nodes'] if node.get('file_path')}
+    names = module_names(files.values())
+    nodes = {}
+    for node in subgraph['nodes']:
+        if node['id'] in files:
+            module = nodes.setdefault(files[node['id']], {
+                "id": files[node['id']], "label": "Module", "name": names[files[node['id']]], "owner": None,
+                "file_path": files[node['id']], "line_number": None, "root": False})
+            module["root"] = module["root"] or bool(node.get('root'))
+    edges = {}
+    for edge in subgraph['edges']:
+        source, target = files.get(edge['source']), files.get(edge['target'])
+        if source is None or target is None or source == target:
+            continue
+        merged = edges.setdefault((source, target, edge['type']), {
+            "source": source, "target": target, "type": edge['type'], "line_number": None, "samples": None, "weight": 0})
+        merged["weight"] += edge.get('weight') or 1
+        if edge.get('samples'):
+            merged["samples"] = (merged["samples"] or 0) + edge['samples']
+    return {**subgraph, "nodes": list(nodes.values()), "edges": list(edges.values())}
+
+
+def _dot_string(value: Any) -> str:
+    return '"' + str(value).replace("\\", "\\\\").replace('"', '\\"').replace("\n", "\\n") + '"'
+
+
+def to_dot(subgraph: Dict[str, Any], cluster: bool = False) -> str:
+    """
+    A Graphviz digraph, styled by node kind and edge type (see the module docstring); edges are
+    labelled like in `to_mermaid`, and thicker the more relationships they stand for. With
+    `cluster`, the nodes of each file are drawn inside a box named after it.
+    """
+    ids = {node['id']: f"n{i}" for i, node in enumerate(subgraph['nodes'])}
+    label_edges = any(edge['type'] != 'CALLS' for edge in subgraph['edges'])
+    lines = ["digraph code {", "    rankdir=LR;", '    node [style="rounded,filled", fontname="Helvetica", fontsize=10];',
+             '    edge [fontname="Helvetica", fontsize=9];']
+
+    def node_line(node, indent):
+        kind = node_kind(node)
+        color, shape = KIND_STYLES.get(kind, ("#ffffff", "box"))
+        location = node.get('file_path') or ''
+        if node.get('line_number'):
+            location += f":{node['line_number']}"
+        attributes = [f"label={_dot_string(node_title(node))}", f"fillcolor={_dot_string(color)}", f"shape={shape}",
+                      f"tooltip={_dot_string(f'{kind} {location}')}"]
+        if node.get('root'):
+            attributes.append("penwidth=2")
+        return f"{indent}{ids[node['id']]} [{', '.join(attributes)}];"
+
+    if cluster:
+        names = module_names(node['file_path'] for node in subgraph['nodes'] if node.get('file_path'))
+        groups: Dict[Any, List[Dict[str, Any]]] = {}
+        for node in subgraph['nodes']:
+            groups.setdefault(node.get('file_path'), []).append(node)
+        for index, (path, members) in enumerate(sorted(groups.items(), key=lambda item: item[0] or '')):
+            if path is None:
+                lines.extend(node_line(node, "    ") for node in members)
+                continue
+            lines.append(f"    subgraph cluster_{index} {{")
+            lines.append(f'        label={_dot_string(names[path])}; style="rounded,dashed"; color="#999999";')
+            lines.extend(node_line(node, "        ") for node in members)
+            lines.append("    }")
+    else:
+        lines.extend(node_line(node, "    ") for node in subgraph['nodes'])
+
+    for edge in subgraph['edges']:
+        weight = edge.get('weight') or 1
+        label = " ".join(str(part) for part in (edge['type'] if label_edges else None,
+                                                 f"x{weight}" if weight > 1 else None, edge.get('samples')) if part)
+        attributes = [f"style={EDGE_STYLES.get(edge['type'], 'solid')}"]
+        if label:
+            attributes.append(f"label={_dot_string(label)}")
+        if weight > 1:
+            attributes.append(f"penwidth={min(1 + weight / 2, 5):g}")
+        lines.append(f"    {ids[edge['source']]} -> {ids[edge['target']]} [{', '.join(attributes)}];")
+    lines.append("}")
+    return "\n".join(lines) + "\n"
+
+
+def render(subgraph: Dict[str, Any], export_format: str, cluster: bool = False) -> str:
+    """Serializes a subgraph in one of `EXPORT_FORMATS`; `cluster` groups DOT nodes by file."""
+    if export_format == "json":
+        return json.dumps(subgraph, indent=2, default=str)
+    if export_format == "mermaid":
+        return to_mermaid(subgraph)
+    if export_format == "graphml":
+        return to_graphml(subgraph)
+    if export_format == "dot":
+        return to_dot(subgraph, cluster)
+    raise ValueError(f"Unknown export format '{export_format}'; expected one of {', '.join(EXPORT_FORMATS)}")
@@ -0,0 +1,177 @@
+# src/codegraphcontext/tools/ignore.py
+"""
+Decides which files of a repository are indexed, from gitignore-style rules.
+
+Rules come from three sources, later ones taking precedence: the built-in DEFAULT_RULES (build
+output, dependency and VCS directories), the comma-separated globs of the CGC_IGNORE environment
+variable, and the `.cgcignore` file at the repository root. As in `.gitignore`:
+
+- `#` starts a comment line, and `!pattern` includes again what an earlier rule excluded;
+- a trailing `/` only matches directories, and excluding a directory excludes everything below it;
+- a pattern holding a `/` elsewhere is anchored to the repository root (`/generated`, `src/gen/*.rs`),
+  while one without matches a file or directory name at any depth (`*.pb.rs`, `fixtures/`);
+- `*` and `?` do not cross `/`, `**` does (`tests/**/fixtures/`).
+
+The last rule matching a path decides; a path no rule matches is indexed. Files below an excluded
+directory cannot be included on their own: include the directory again (`!target/`) and exclude
+the rest of its entries instead.
+"""
+import os
+import re
+from pathlib import Path
+from typing import Dict, Iterable, Iterator, List, Optional, Tuple
+
+IGNORE_FILE = ".cgcignore"
+DEFAULT_RULES = [".git/", "target/", "node_modules/", "__pycache__/", ".venv/", "venv/"]
+
+
+class Rule:
+    """One pattern of a rule set, with where it came from."""
+
+    def __init__(self, pattern: str, source: str):
+        self.pattern = pattern
+        self.source = source
+        self.negated = pattern.startswith("!")
+        body = pattern[1:] if self.negated else pattern
+        self.directory_only = body.endswith("/")
+        body = body.rstrip("/")
+        self.anchored = "/" in body
+        self.regex = re.compile(glob_to_regex(body.lstrip("/")) + "$")
+
+    def matches(self, relative_path: str, is_dir: bool) -> bool:
+        """Whether the rule applies to a path relative to the repository root (posix separators)."""
+        if self.directory_only and not is_dir:
+            return False
+        if self.anchored:
+            return bool(self.regex.match(relative_path))
+        return bool(self.regex.match(relative_path.rsplit("/", 1)[-1]))
+
+    def describe(self) -> Dict[str, str]:
+        return {"pattern": self.pattern, "source": self.source}
+
+
+def glob_to_regex(glob: str) -> str:
+    """The regular expression of a gitignore glob: `**` crosses directories, `*` and `?` do not."""
+    regex, i = "", 0
+    while i < len(glob):
+        if glob.startswith("**/", i):
+            regex += "(?:.*/)?"
+            i += 3
+        elif glob.startswith("**", i):
+            regex += ".*"
+            i += 2
+        elif glob[i] == "*":
+            regex += "[^/]*"
+            i += 1
+        elif glob[i] == "?":
+            regex += "[^/]"
+            i += 1
+        elif glob[i] == "[" and "]" in glob[i + 1:]:
+            end = glob.index("]", i + 1)
+            body = glob[i + 1:end]
+            regex += "[" + ("^" + body[1:] if body.startswith("!") else body).replace("\\", "\\\\") + "]"
+            i = end + 1
+        else:
+            regex += re.escape(glob[i])
+            i += 1
+    return regex
+
+
+def parse_rules(lines: Iterable[str], source: str) -> List[Rule]:
+    """The rules of a `.cgcignore`-style text, skipping blank lines and comments."""
+    rules = []
+    for line in lines:
+        line = line.strip()
+        if line and not line.startswith("#"):
+            rules.append(Rule(line, source))
+    return rules
+
+
+class IgnoreRules:
+    """The effective rule set of a repository."""
+
+    def __init__(self, root: Path, rules: List[Rule]):
+        self.root = Path(root).resolve()
+        self.rules = rules
+
+    @classmethod
+    def load(cls, root: Path, env: Optional[str] = None) -> "IgnoreRules":
+        """The defaults, then CGC_IGNORE (or `env`), then the repository's `.cgcignore`."""
+        root = Path(root).resolve()
+        rules = parse_rules(DEFAULT_RULES, "default")
+        env = os.getenv("CGC_IGNORE", "") if env is None else env
+        rules += parse_rules(env.split(","), "CGC_IGNORE")
+        ignore_file = root / IGNORE_FILE
+        if ignore_file.is_file():
+            rules += parse_rules(ignore_file.read_text(encoding="utf-8", errors="replace").splitlines(), IGNORE_FILE)
+        return cls(root, rules)
+
+    def decide(self, relative_path: str, is_dir: bool) -> Optional[Rule]:
+        """The last rule matching the path, None when none does."""
+        for rule in reversed(self.rules):
+            if rule.matches(relative_path, is_dir):
+                return rule
+        return None
+
+    def excluded_by(self, path: Path) -> Optional[Rule]:
+        """
+        The rule excluding a path, looking at its directories first (an excluded directory hides
+        everything below it); None when the path is indexed or outside the repository.
+        """
+        try:
+            parts = Path(path).resolve().relative_to(self.root).parts
+        except ValueError:
+            return None
+        for depth in range(1, len(parts) + 1):
+            is_dir = depth < len(parts) or Path(path).is_dir()
+            rule = self.decide("/".join(parts[:depth]), is_dir)
+            if rule is not None and not rule.negated:
+                return rule
+        return None
+
+    def is_ignored(self, path: Path) -> bool:
+        return self.excluded_by(path) is not None
+
+    def walk(self, suffixes: Iterable[str]) -> Iterator[Path]:
+        """The indexed files of the repository with one of `suffixes`, in a stable order."""
+        suffixes = set(suffixes)
+        for directory, dirnames, filenames in os.walk(self.root):
+            relative = Path(directory).relative_to(self.root).as_posix()
+            prefix = "" if relative == "." else relative + "/"
+            kept = []
+            for name in sorted(dirnames):
+                rule = self.decide(prefix + name, True)
+                if rule is None or rule.negated:
+                    kept.append(name)
+            dirnames[:] = kept
+            for name in sorted(filenames):
+                if Path(name).suffix not in suffixes:
+                    continue
+                rule = self.decide(prefix + name, False)
+                if rule is None or rule.negated:
+                    yield Path(directory) / name
+
+    def dry_run(self, suffixes: Iterable[str], limit: int = 50) -> Dict:
+        """
+        The effective rules and what they do to the repository: how many files with `suffixes` are
+        indexed and excluded, with up to `limit` examples of each (excluded ones with their rule).
+        """
+        suffixes = set(suffixes)
+        included = [str(path) for path in self.walk(suffixes)]
+        kept = set(included)
+        excluded: List[Tuple[str, Rule]] = []
+        for directory, dirnames, filenames in os.walk(self.root):
+            for name in sorted(filenames):
+                path = Path(directory) / name
+                if path.suffix in suffixes and str(path) not in kept:
+                    rule = self.excluded_by(path)
+                    if rule is not None:
+                        excluded.append((str(path), rule))
+        return {
+            "root": str(self.root),
+            "rules": [rule.describe() for rule in self.rules],
+            "included_count": len(included),
+            "excluded_count": len(excluded),
+            "included": included[:limit],
+            "excluded": [{"path": path, **rule.describe()} for path, rule in excluded[:limit]],
+        }
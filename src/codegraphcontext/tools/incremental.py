@@ -0,0 +1,95 @@
+# src/codegraphcontext/tools/incremental.py
+"""
+Plans incremental re-indexing from file content hashes.
+
+Every indexed File node keeps the `content_hash` of the bytes it was parsed from. Re-indexing a
+repository hashes its files again: files that are new (added), whose hash differs or whose File
+node has no hash yet (changed) are parsed again, and files gone from disk are removed. Indexed
+files the directory walk does not see but that still exist (e.g. pulled in by `include!`) are left
+alone.
+
+Within a changed file, items are diffed by their stable `uid` (see `node_ids`) and a `fingerprint`
+of everything parsed about them, so only added, removed and modified items are rewritten and the
+relationships of untouched ones are kept.
+
+The crate-wide passes (crate and module metadata, glob imports, public paths, thread safety) see
+every module of a crate, not only the re-parsed ones. Rust File nodes therefore keep a
+`crate_summary`, the part of their parse those passes read, and re-indexing loads the summaries of
+the unchanged files of the crates it touches instead of parsing them again.
+"""
+import hashlib
+import json
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+
+from .sources import content_hash
+
+
+def file_hash(path: Path) -> Optional[str]:
+    """The `content_hash` of a file's bytes, None when it cannot be read."""
+    try:
+        return content_hash(path)
+    except OSError:
+        return None
+
+
+def plan_reindex(current: Dict[str, str], indexed: Dict[str, Optional[str]],
+                 exists: Callable[[str], bool] = lambda path: Path(path).exists()) -> Dict[str, List[str]]:
+    """
+    Sorts files into `added`, `changed`, `removed` and `unchanged`, from the hashes of the files on
+    disk (`current`) and those recorded on the indexed File nodes (`indexed`, None when missing).
+    """
+    plan = {"added": [], "changed": [], "removed": [], "unchanged": []}
+    for path, digest in current.items():
+        if path not in indexed:
+            plan["added"].append(path)
+        elif indexed[path] is None or indexed[path] != digest:
+            plan["changed"].append(path)
+        else:
+            plan["unchanged"].append(path)
+    plan["removed"] = [path for path in indexed if path not in current and not exists(path)]
+    return {key: sorted(paths) for key, paths in plan.items()}
+
+
+# What the crate-wide passes read of a parsed Rust file: whole lists, or only some keys of their entries.
+CRATE_SUMMARY_KEYS = {
+    "file_path": None, "lang": None, "crate_root": None, "module_path": None, "docstring": None,
+    "msrv_features": None, "imports": None, "module_declarations": None, "fields": None,
+    "enum_variants": None, "impls": None, "thread_spawns": None, "variant_usages": None,
+    "functions": ("name", "line_number", "context", "inline_module", "visibility"),
+    "classes": ("name", "line_number", "kind", "context", "inline_module", "visibility"),
+    "function_calls": ("context", "full_name"),
+    "type_references": ("function", "function_line", "owner", "type", "type_text"),
+}
+
+
+def crate_summary(file_data: Dict[str, Any]) -> str:
+    """The `crate_summary` of a parsed Rust file, as JSON; see the module docstring."""
+    summary = {}
+    for key, fields in CRATE_SUMMARY_KEYS.items():
+        if key not in file_data:
+            continue
+        value = file_data[key]
+        if fields and isinstance(value, list):
+            value = [{field: entry.get(field) for field in fields} for entry in value]
+        summary[key] = value
+    return json.dumps(summary, default=str)
+
+
+def fingerprint(item: Dict[str, Any]) -> str:
+    """A hash of a parsed item's properties; moving or editing an item (or its docs) changes it."""
+    state = {key: value for key, value in item.items() if key != "fingerprint"}
+    return hashlib.sha256(json.dumps(state, sort_keys=True, default=str).encode("utf-8")).hexdigest()[:32]
+
+
+def diff_items(stored: Dict[str, Optional[str]], parsed: Dict[str, str]) -> Dict[str, List[str]]:
+    """
+    Sorts the uids of a file's items into `added`, `removed`, `modified` and `unchanged`, from the
+    fingerprints stored on their nodes and those of the freshly parsed items.
+    """
+    return {
+        "added": sorted(uid for uid in parsed if uid not in stored),
+        "removed": sorted(uid for uid in stored if uid not in parsed),
+        "modified": sorted(uid for uid in parsed if uid in stored and stored[uid] != parsed[uid]),
+        "unchanged": sorted(uid for uid in parsed if uid in stored and stored[uid] == parsed[uid]),
+    }
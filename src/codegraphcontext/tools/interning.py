@@ -0,0 +1,70 @@
+# src/codegraphcontext/tools/interning.py
+"""
+Shares the strings of the parser's intermediate representation.
+
+A parsed file is a tree of dicts and lists whose strings repeat over and over: every item
+carries its file's path, every call the name of its caller and the path it resolves to, every
+field and parameter its type name, and the same attribute, derive and context strings recur in
+every module of a crate. Each occurrence is a separate string object as tree-sitter hands them
+out, and the parsed files of a whole repository are held until linking (see `spill`).
+`intern_tree` swaps every string of a parsed file for the interpreter's interned copy
+(`sys.intern`), so equal strings are stored once across all files held, and comparisons between
+them (the name lookups of the linking passes) short-circuit on identity.
+
+CPython allocates and frees objects itself, so there is no arena to manage from here; the tree
+keeps its shape, only its leaves are shared.
+"""
+import sys
+from typing import Any, Iterator
+
+
+def _interned(value: Any) -> Any:
+    if isinstance(value, str):
+        return sys.intern(value)
+    if isinstance(value, tuple):
+        return tuple(_interned(item) for item in value)
+    return value
+
+
+def intern_tree(tree: Any) -> Any:
+    """
+    Interns, in place, the strings (and tuples of strings) held by a tree of dicts and lists,
+    dict keys included, and returns it; a lone string is returned interned.
+    """
+    if not isinstance(tree, (dict, list)):
+        return _interned(tree)
+    stack = [tree]
+    while stack:
+        node = stack.pop()
+        if isinstance(node, dict):
+            if any(isinstance(key, str) and sys.intern(key) is not key for key in node):
+                # Rebuilt rather than re-keyed one by one, which would move the keys to the end.
+                items = list(node.items())
+                node.clear()
+                node.update((_interned(key), value) for key, value in items)
+            for key, value in list(node.items()):
+                if isinstance(value, (dict, list)):
+                    stack.append(value)
+                else:
+                    node[key] = _interned(value)
+        else:
+            for index, value in enumerate(node):
+                if isinstance(value, (dict, list)):
+                    stack.append(value)
+                else:
+                    node[index] = _interned(value)
+    return tree
+
+
+def strings(tree: Any) -> Iterator[str]:
+    """Every string object held by a tree of dicts, lists and tuples, keys included, with repeats."""
+    stack = [tree]
+    while stack:
+        node = stack.pop()
+        if isinstance(node, str):
+            yield node
+        elif isinstance(node, dict):
+            stack.extend(node.keys())
+            stack.extend(node.values())
+        elif isinstance(node, (list, tuple)):
+            stack.extend(node)
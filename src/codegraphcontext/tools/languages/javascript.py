@@ -3,6 +3,9 @@ from typing import Any, Dict, Optional, Tuple
 import logging
 import ast # Not strictly needed for JS, but kept for consistency if AST manipulation is added
 
+from .spans import node_span
+from ..sources import read_source
+
 logger = logging.getLogger(__name__)
 
 JS_QUERIES = {
@@ -131,10 +134,7 @@ class JavascriptTreeSitterParser:
 
     def parse(self, file_path: Path, is_dependency: bool = False) -> Dict:
         """Parses a file and returns its structure in a standardized dictionary format."""
-        with open(file_path, "r", encoding="utf-8") as f:
-            source_code = f.read()
-        
-        tree = self.parser.parse(bytes(source_code, "utf8"))
+        tree = self.parser.parse(read_source(file_path))
         root_node = tree.root_node
 
         functions = self._find_functions(root_node)
@@ -234,7 +234,7 @@ class JavascriptTreeSitterParser:
                 func_data = {
                     "name": name,
                     "line_number": func_node.start_point[0] + 1,
-                    "end_line": func_node.end_point[0] + 1,
+                    **node_span(func_node),
                     "args": args,
                     "source": self._get_node_text(func_node),
                     "source_code": self._get_node_text(func_node),
@@ -328,7 +328,7 @@ class JavascriptTreeSitterParser:
                 class_data = {
                     "name": name,
                     "line_number": class_node.start_point[0] + 1,
-                    "end_line": class_node.end_point[0] + 1,
+                    **node_span(class_node),
                     "bases": bases,
                     "source": self._get_node_text(class_node),
                     "docstring": self._get_docstring(class_node),
@@ -416,6 +416,7 @@ class JavascriptTreeSitterParser:
                     "inferred_obj_type": None,
                     "context": None, # Placeholder
                     "class_context": None, # Placeholder
+                    **node_span(call_node, node),
                     "lang": self.language_name,
                     "is_dependency": False,
                 }
@@ -475,8 +476,7 @@ def pre_scan_javascript(files: list[Path], parser_wrapper) -> dict:
     
     for file_path in files:
         try:
-            with open(file_path, "r", encoding="utf-8") as f:
-                tree = parser_wrapper.parser.parse(bytes(f.read(), "utf8"))
+            tree = parser_wrapper.parser.parse(read_source(file_path))
             
             for capture, _ in query.captures(tree.root_node):
                 name = capture.text.decode('utf-8')
@@ -3,6 +3,9 @@ from typing import Any, Dict, Optional, Tuple
 import logging
 import ast
 
+from .spans import node_span
+from ..sources import read_source
+
 logger = logging.getLogger(__name__)
 
 PY_QUERIES = {
@@ -99,10 +102,7 @@ class PythonTreeSitterParser:
 
     def parse(self, file_path: Path, is_dependency: bool = False) -> Dict:
         """Parses a file and returns its structure in a standardized dictionary format."""
-        with open(file_path, "r", encoding="utf-8") as f:
-            source_code = f.read()
-        
-        tree = self.parser.parse(bytes(source_code, "utf8"))
+        tree = self.parser.parse(read_source(file_path))
         root_node = tree.root_node
 
         functions = self._find_functions(root_node)
@@ -144,7 +144,7 @@ class PythonTreeSitterParser:
                 func_data = {
                     "name": name,
                     "line_number": node.start_point[0] + 1,
-                    "end_line": assignment_node.end_point[0] + 1,
+                    **node_span(assignment_node, node),
                     "args": [p for p in [self._get_node_text(p) for p in params_node.children if p.type == 'identifier'] if p] if params_node else [],
                     "source": self._get_node_text(assignment_node),
                     "source_code": self._get_node_text(assignment_node),
@@ -194,7 +194,7 @@ class PythonTreeSitterParser:
                 func_data = {
                     "name": name,
                     "line_number": node.start_point[0] + 1,
-                    "end_line": func_node.end_point[0] + 1,
+                    **node_span(func_node, node),
                     "args": args,
                     "source": self._get_node_text(func_node),
                     "source_code": self._get_node_text(func_node),
@@ -234,7 +234,7 @@ class PythonTreeSitterParser:
                 class_data = {
                     "name": name,
                     "line_number": node.start_point[0] + 1,
-                    "end_line": class_node.end_point[0] + 1,
+                    **node_span(class_node, node),
                     "bases": [b for b in bases if b],
                     "source": self._get_node_text(class_node),
                     "docstring": self._get_docstring(body_node),
@@ -339,6 +339,7 @@ class PythonTreeSitterParser:
                     "inferred_obj_type": None, # Type inference is a complex topic to be added
                     "context": self._get_parent_context(node),
                     "class_context": self._get_parent_context(node, types=('class_definition',))[:2],
+                    **node_span(call_node, node),
                     "lang": self.language_name,
                     "is_dependency": False,
                 }
@@ -393,8 +394,7 @@ def pre_scan_python(files: list[Path], parser_wrapper) -> dict:
     
     for file_path in files:
         try:
-            with open(file_path, "r", encoding="utf-8") as f:
-                tree = parser_wrapper.parser.parse(bytes(f.read(), "utf8"))
+            tree = parser_wrapper.parser.parse(read_source(file_path))
             
             for capture, _ in query.captures(tree.root_node):
                 name = capture.text.decode('utf-8')
@@ -0,0 +1,3494 @@
+from pathlib import Path
+from typing import Any, Dict, List, Optional, Tuple
+import hashlib
+import logging
+import re
+
+from . import rust_macros
+from .spans import node_span
+from ..similarity import shape_signature
+from ..sources import read_source
+
+logger = logging.getLogger(__name__)
+
+# Leaf nodes replaced by a placeholder when hashing function structure, so renamed
+# identifiers and changed constants still produce the same hash.
+NORMALIZED_IDENTIFIERS = {"identifier", "type_identifier", "field_identifier", "shorthand_field_identifier", "lifetime"}
+NORMALIZED_LITERALS = {"string_literal", "raw_string_literal", "char_literal", "integer_literal", "float_literal", "boolean_literal"}
+
+RUST_QUERIES = {
+    "functions": """
+        (function_item
+            name: (identifier) @name
+            parameters: (parameters) @parameters
+            body: (block) @body)
+    """,
+    "classes": """
+        (struct_item name: (type_identifier) @name)
+        (enum_item name: (type_identifier) @name)
+        (trait_item name: (type_identifier) @name)
+        (union_item name: (type_identifier) @name)
+    """,
+    "imports": """
+        (use_declaration) @import
+        (extern_crate_declaration) @import
+    """,
+    "calls": """
+        (call_expression function: (identifier) @name)
+        (call_expression function: (scoped_identifier name: (identifier) @name))
+        (call_expression function: (field_expression field: (field_identifier) @name))
+        (call_expression function: (generic_function function: (identifier) @name))
+        (call_expression function: (generic_function function: (scoped_identifier name: (identifier) @name)))
+        (call_expression function: (generic_function function: (field_expression field: (field_identifier) @name)))
+    """,
+    "variables": """
+        (let_declaration pattern: (identifier) @name)
+        (const_item name: (identifier) @name)
+        (static_item name: (identifier) @name)
+    """,
+}
+
+# Node types that open a new named scope for the items found inside them.
+CONTEXT_TYPES = ('function_item', 'impl_item', 'trait_item')
+
+# Constructors that create a (sender, receiver) pair.
+CHANNEL_CONSTRUCTORS = {'channel', 'sync_channel', 'unbounded', 'bounded'}
+CHANNEL_SEND_METHODS = {'send', 'try_send', 'send_timeout', 'blocking_send'}
+CHANNEL_RECV_METHODS = {'recv', 'try_recv', 'recv_timeout', 'iter', 'try_iter', 'blocking_recv'}
+
+# Synchronization primitives tracked as shared state, and how their methods access them.
+LOCK_TYPE_PATTERN = re.compile(r'\b(Mutex|RwLock|Atomic[A-Z][A-Za-z0-9]*)\b')
+LOCK_METHODS = {
+    'lock': 'lock', 'try_lock': 'lock',
+    'read': 'read', 'try_read': 'read',
+    'write': 'write', 'try_write': 'write',
+}
+# Pointer and cell types whose nesting is recorded as WRAPPED_IN.
+SMART_POINTER_TYPES = {'Box', 'Rc', 'Arc', 'Weak', 'RefCell', 'Cell', 'Mutex', 'RwLock', 'Cow'}
+SHARED_OWNERSHIP_TYPES = {'Rc', 'Arc'}
+INTERIOR_MUTABILITY_TYPES = {'RefCell', 'Cell', 'Mutex', 'RwLock'}
+
+# Pattern node types inside which a `Enum::Variant` path is a match rather than a construction.
+PATTERN_TYPES = {
+    'match_pattern', 'tuple_struct_pattern', 'struct_pattern', 'tuple_pattern', 'slice_pattern',
+    'or_pattern', 'ref_pattern', 'captured_pattern', 'field_pattern', 'reference_pattern',
+}
+PATTERN_PARENTS = {'let_declaration', 'let_condition', 'for_expression', 'parameter', 'closure_parameters'}
+
+# Collection and string methods that mutate the field they are called on.
+MUTATING_METHODS = {
+    'push', 'push_str', 'push_back', 'push_front', 'pop', 'pop_back', 'pop_front', 'insert', 'remove',
+    'clear', 'extend', 'truncate', 'retain', 'drain', 'sort', 'sort_by', 'sort_by_key', 'dedup',
+    'append', 'swap', 'reverse', 'entry', 'get_mut', 'iter_mut', 'take', 'replace', 'set',
+}
+
+# `F: Fn(..)`, `G: Send + FnMut()` in generic parameter lists and where clauses.
+FN_TRAIT_BOUND_PATTERN = re.compile(r"\b([A-Z]\w*)\s*:\s*(?:for\s*<[^>]*>\s*)?(?:[\w']+\s*\+\s*)*(?:FnOnce|FnMut|Fn)\s*\(")
+FN_TRAIT_TYPE_PATTERN = re.compile(r"\b(FnOnce|FnMut|Fn)\s*\(|^\s*fn\s*\(")
+
+# Associated functions assumed to return `Self` when their return type is not known.
+CONSTRUCTOR_NAMES = {'new', 'default', 'from', 'try_from', 'with_capacity', 'builder', 'build'}
+# Methods that pass the receiver's (inner) type through: `x.clone()`, `opt.unwrap()`, `cell.borrow()`.
+UNWRAP_METHODS = {'unwrap', 'expect', 'unwrap_or', 'unwrap_or_else', 'unwrap_or_default', 'unwrap_unchecked'}
+PASSTHROUGH_METHODS = {'clone', 'to_owned', 'as_ref', 'as_mut', 'borrow', 'borrow_mut', 'lock', 'read', 'write', 'get_mut'}
+
+# Consuming methods that finish a builder chain, and the wrappers stripped from their return type.
+BUILD_METHOD_PATTERN = re.compile(r'^(try_)?(build|finish)(_\w+)?$')
+FALLIBLE_WRAPPERS = {'Result', 'Option'}
+
+# Marker bounds that never select a method implementation for dynamic or generic dispatch.
+MARKER_TRAITS = {'Send', 'Sync', 'Sized', 'Unpin', 'Copy'}
+
+# Cell types and atomics that allow mutation through a shared reference, and how their methods access them.
+INTERIOR_MUTABILITY_PATTERN = re.compile(r'\b(RefCell|UnsafeCell|OnceCell|Cell|Atomic[A-Z][A-Za-z0-9]*)\b')
+CELL_METHODS = {
+    'borrow': 'borrow', 'try_borrow': 'borrow',
+    'borrow_mut': 'borrow_mut', 'try_borrow_mut': 'borrow_mut',
+    'get': 'read', 'get_or_init': 'read',
+    'set': 'write', 'replace': 'write', 'take': 'write', 'swap': 'write', 'update': 'write', 'get_mut': 'write',
+}
+
+ATOMIC_METHODS = {
+    'load', 'store', 'swap', 'compare_exchange', 'compare_exchange_weak', 'compare_and_swap',
+    'fetch_add', 'fetch_sub', 'fetch_and', 'fetch_or', 'fetch_xor', 'fetch_nand',
+    'fetch_max', 'fetch_min', 'fetch_update',
+}
+
+
+# Newer syntax that older tree-sitter-rust grammars reject, see `desugar_modern_syntax`.
+LET_ELSE_PATTERN = re.compile(rb'\blet\s[^;{}]*?=[^;{}]*?\b(else)\s*\{')
+LET_CHAIN_PATTERN = re.compile(rb'&&\s*(let\s[^=;{]*?=)(?!=)')
+LET_PATTERN_HEAD = re.compile(rb'\blet\s+([^=;{]*?)=(?!=)')
+GAT_GENERICS_PATTERN = re.compile(rb'\btype\s+(?:r#)?\w+\s*(<[^;={}]*?>)\s*(?=[:;=]|where\b)')
+GAT_WHERE_PATTERN = re.compile(rb'\btype\s+(?:r#)?\w+[^;={}]*?(\bwhere\b[^;={}]*)(?=[;=])')
+GAT_BINDING_WHERE_PATTERN = re.compile(rb'\btype\s+(?:r#)?\w+[^;={}]*=[^;{}]*?(\bwhere\b[^;{}]*)(?=;)')
+# Tokens of an `include!` argument: `env!("VAR")`, string literals and other macro names.
+INCLUDE_TOKEN_PATTERN = re.compile(r'env!\s*\(\s*"([^"]+)"\s*\)|"((?:[^"\\]|\\.)*)"|(\w+!)')
+# Language and standard library features that raise the minimum supported Rust version, with
+# the release that stabilized them. Patterns run on the source with comments and strings blanked.
+MSRV_FEATURES = [
+    ('raw_identifiers', (1, 30), re.compile(rb'(?<![\w#])r#[A-Za-z_]')),
+    ('async_await', (1, 39), re.compile(rb'\basync\s+(?:move\s*)?(?:fn\b|\{)|\.await\b')),
+    ('non_exhaustive', (1, 40), re.compile(rb'#\[non_exhaustive\]')),
+    ('const_generics', (1, 51), re.compile(rb'<(?:[^<>;{}]*,)?\s*const\s+\w+\s*:')),
+    ('scoped_threads', (1, 63), re.compile(rb'\bthread::scope\s*\(')),
+    ('let_else', (1, 65), LET_ELSE_PATTERN),
+    ('generic_associated_types', (1, 65), GAT_GENERICS_PATTERN),
+    ('once_lock', (1, 70), re.compile(rb'\bOnceLock\b')),
+    ('inline_const', (1, 79), re.compile(rb'(?<![\w:])const\s*\{')),
+    ('lazy_lock', (1, 80), re.compile(rb'\bLazyLock\b')),
+    ('let_chains', (1, 88), LET_CHAIN_PATTERN),
+]
+ASYNC_FN_IN_TRAIT_VERSION = (1, 75)
+EDITION_VERSIONS = {'2015': (1, 0), '2018': (1, 31), '2021': (1, 56), '2024': (1, 85)}
+MSRV_MASK_PATTERN = re.compile(rb'"(?:[^"\\]|\\.)*"|\'(?:[^\'\\\n]|\\.)\'|//[^\n]*|/\*.*?\*/', re.DOTALL)
+
+# Auto trait rules for thread-safety analysis: types that are never Send, types that are Send
+# but never Sync, and wrappers whose Send-ness depends on their contents being Sync as well.
+NON_SEND_TYPES = {'Rc', 'MutexGuard', 'RwLockReadGuard', 'RwLockWriteGuard', 'Ref', 'RefMut', 'NonNull'}
+NON_SYNC_TYPES = {'Cell', 'RefCell', 'UnsafeCell', 'OnceCell', 'Receiver'}
+THREAD_SPAWN_METHODS = {'spawn', 'spawn_scoped'}
+
+# Calls that block the executor thread when made from async code, by category. Paths are
+# matched on the call target; methods on any receiver. Awaited calls are async and never match.
+BLOCKING_CALL_PATTERNS = [
+    ('sleep', re.compile(r'^(std::)?thread::sleep$')),
+    ('io', re.compile(r'^(std::)?fs::\w+$|^(std::fs::)?File::(open|create)$|^(std::)?io::(stdin|copy|read_to_string)$')),
+    ('network', re.compile(r'^(std::net::)?(TcpStream::connect|TcpListener::bind|UdpSocket::bind)$')),
+]
+BLOCKING_METHODS = {
+    'lock': 'lock', 'blocking_lock': 'lock',
+    'blocking_recv': 'channel', 'blocking_send': 'channel',
+    'read_line': 'io', 'read_to_string': 'io', 'read_to_end': 'io', 'write_all': 'io', 'sync_all': 'io',
+}
+CFG_ATTRIBUTE = re.compile(r'^#!?\[\s*cfg\s*\((.*)\)\s*\]$', re.DOTALL)
+# `feature = "x"`, with the `not(` in front when the item is compiled only *without* the feature.
+CFG_FEATURE = re.compile(r'(not\s*\(\s*)?feature\s*=\s*"([^"]+)"')
+# `#[doc = "..."]` and `#![doc = "..."]`, the attribute form of doc comments.
+DOC_ATTRIBUTE = re.compile(r'^#!?\[\s*doc\s*=\s*"((?:[^"\\]|\\.)*)"\s*\]$', re.DOTALL)
+# A comment line opening with a task marker: `// TODO: x`, `// FIXME(alice) x`, ` * HACK - x`.
+TASK_PATTERN = re.compile(r'^\s*(?:[/*!]+\s*)?(TODO|FIXME|HACK)\b(?:\(([^)]*)\))?[\s:-]*(.*?)\s*(?:\*/)?$')
+# Literals indexed with CONTAINS_LITERAL: strings with a word in them, URLs and numbers other than
+# the ubiquitous ones below; longer strings are cut to LITERAL_MAX_LENGTH characters.
+TRIVIAL_NUMBERS = {0, 1, 2, 10, 100}
+LITERAL_MAX_LENGTH = 200
+URL_PATTERN = re.compile(r'^[a-z][a-z0-9+.-]*://\S+$', re.IGNORECASE)
+ASYNC_ENTRY_POINT_PATTERN = re.compile(r'#\[\s*(tokio|async_std|actix_web|actix_rt|smol_potat)::(main|test)\b')
+
+# Types of literals without a suffix; integer and float literals default to i32 and f64.
+LITERAL_TYPES = {'string_literal': '&str', 'raw_string_literal': '&str', 'char_literal': 'char', 'boolean_literal': 'bool'}
+GENERIC_SCOPE_TYPES = ('function_item', 'function_signature_item', 'impl_item', 'struct_item', 'enum_item',
+                       'union_item', 'trait_item', 'type_item')
+
+# Capture modes of closures, weakest first. Calling one of `MUTATING_METHODS` (`&mut self`) on a
+# captured place forces a mutable borrow, calling a method taking `self` a move (unless it is Copy);
+# `join` only consumes thread handles, slices and Vecs join by reference.
+CAPTURE_MODES = ('ref', 'mut_ref', 'move')
+CONSUMING_METHODS = {'unwrap', 'expect', 'unwrap_or', 'unwrap_or_default', 'unwrap_or_else'}
+JOIN_HANDLE_TYPES = {'JoinHandle', 'ScopedJoinHandle'}
+COPY_TYPES = {'bool', 'char', 'f32', 'f64', 'i8', 'i16', 'i32', 'i64', 'i128', 'isize',
+              'u8', 'u16', 'u32', 'u64', 'u128', 'usize'}
+
+# Nested macro invocations inside expanded code are followed at most this deep.
+MAX_MACRO_EXPANSION_DEPTH = 4
+
+# `type Item<'a>: Bound where Self: 'a = Binding;` split into its parts.
+ASSOCIATED_TYPE_PATTERN = re.compile(
+    r"^type\s+(?P<name>(?:r#)?\w+)\s*(?P<generics><.*?>)?\s*(?::(?P<bounds>[^=;]*?))?"
+    r"\s*(?P<where>\bwhere\b[^=;]*?)?\s*(?:=\s*(?P<binding>[^;]*?))?\s*;?$",
+    re.DOTALL,
+)
+
+# `r#` prefix of a raw identifier; raw strings (`r#"..."#`) are not followed by an identifier.
+RAW_IDENTIFIER_PATTERN = re.compile(r'(?<![\w#])r#(?=[A-Za-z_])')
+
+
+def normalize_identifier(text: str) -> str:
+    """Strips the `r#` prefix of raw identifiers, also inside paths: `r#mod::r#type` -> `mod::type`."""
+    return RAW_IDENTIFIER_PATTERN.sub('', text)
+
+
+def split_type_arguments(text: str, separator: str = ','):
+    """Splits `A, B<C, D>, (E, F)` on its top-level commas (or another separator, e.g. `+` in bounds)."""
+    parts, depth, current, previous = [], 0, "", ""
+    for char in text:
+        if char in '<([':
+            depth += 1
+        elif char in ')]' or (char == '>' and previous != '-'):
+            depth -= 1
+        if char == separator and depth == 0:
+            parts.append(current.strip())
+            current = ""
+        else:
+            current += char
+        previous = char
+    if current.strip():
+        parts.append(current.strip())
+    return parts
+
+
+def type_tree(text: str):
+    """
+    Parses a type into a `(base_name, type_arguments, text)` tree, ignoring references,
+    lifetimes and `dyn`/`impl` keywords: `&'a Rc<RefCell<Vec<i32>>>` becomes
+    `('Rc', [('RefCell', [('Vec', [('i32', [], 'i32')], 'Vec<i32>')], ...)], ...)`.
+    """
+    text = text.strip()
+    previous = None
+    while previous != text:
+        previous = text
+        text = re.sub(r"^(&\s*('\w+\s*)?(mut\s+)?|\*(const|mut)\s+|dyn\s+|impl\s+)", '', text).strip()
+
+    if text[:1] in ('(', '['):
+        inner = text[1:text.rfind(')' if text[0] == '(' else ']')]
+        if text[0] == '[':
+            inner = inner.split(';')[0]
+        return None, [type_tree(part) for part in split_type_arguments(inner)], text
+
+    if '<' in text:
+        base = text[:text.index('<')]
+        args_text = text[text.index('<') + 1:text.rfind('>')]
+        args = [type_tree(part) for part in split_type_arguments(args_text) if not part.startswith("'")]
+        return base.split('::')[-1].strip(), args, text
+
+    match = re.match(r"[\w:]+", text)
+    base = match.group(0).split('::')[-1] if match else None
+    return base, [], text
+
+
+def _type_argument_texts(text: str) -> List[str]:
+    """Returns the written arguments of a generic, slice, array or tuple type: `['K', 'Vec<V>']` for `HashMap<K, Vec<V>>`."""
+    if text[:1] in ('(', '['):
+        inner = text[1:text.rfind(')' if text[0] == '(' else ']')]
+        if text[0] == '[':
+            inner = inner.split(';')[0]
+    elif '<' in text:
+        inner = text[text.index('<') + 1:text.rfind('>')]
+    else:
+        return []
+    return [arg for arg in split_type_arguments(inner) if not arg.startswith("'")]
+
+
+def bind_type_parameters(declared: str, concrete: str, params, bindings: Optional[Dict[str, str]] = None) -> Dict[str, str]:
+    """
+    Matches a declared type such as `&[T]` or `Option<T>` against the concrete type of a value
+    (`&Vec<i32>`, `Option<&u8>`) and returns the bindings found for the type parameters in
+    `params`: `{'T': 'i32'}`, `{'T': '&u8'}`. Mismatching parts bind nothing; earlier bindings win.
+    """
+    bindings = {} if bindings is None else bindings
+    declared, concrete = declared.strip(), concrete.strip()
+    reference = re.compile(r"^&\s*('\w+\s*)?(mut\s+)?")
+    while reference.match(declared):
+        declared = declared[reference.match(declared).end():].strip()
+        if reference.match(concrete):
+            concrete = concrete[reference.match(concrete).end():].strip()
+    if declared in params:
+        bindings.setdefault(declared, re.sub(r"'\w+\s*", '', concrete))
+        return bindings
+
+    declared_base, concrete_base = type_tree(declared)[0], type_tree(concrete)[0]
+    if declared_base is None:
+        # Slices accept arrays and (deref'ed) Vecs; tuples only tuples.
+        is_slice = declared.startswith('[') and (concrete.startswith('[') or concrete_base == 'Vec')
+        is_tuple = declared.startswith('(') and concrete.startswith('(')
+        if not (is_slice or is_tuple):
+            return bindings
+    elif declared_base != concrete_base:
+        return bindings
+    for declared_arg, concrete_arg in zip(_type_argument_texts(declared), _type_argument_texts(concrete)):
+        bind_type_parameters(declared_arg, concrete_arg, params, bindings)
+    return bindings
+
+
+def thread_safety(type_text: str, user_types: Optional[Dict] = None):
+    """
+    Returns `(is_send, is_sync, reason)` for a type, following the auto trait rules of the
+    standard library (`Arc<T>` is Send only if T is Send + Sync, `&T` only if T is Sync, ...).
+    `user_types` maps the names of user-defined types to their own `(is_send, is_sync, reason)`;
+    unknown types are assumed to be thread-safe. `reason` explains the first violation found.
+    """
+    user_types = user_types or {}
+    text = type_text.strip()
+    if re.match(r'^\*\s*(const|mut)\b', text):
+        return False, False, f"raw pointer `{text}`"
+    reference = re.match(r"^&\s*(?:'\w+\s*)?(mut\s+)?", text)
+    if reference:
+        is_send, is_sync, reason = thread_safety(text[reference.end():], user_types)
+        if reference.group(1):
+            return is_send, is_sync, reason
+        return is_sync, is_sync, reason and f"shared reference to a non-Sync type: {reason}"
+    if re.match(r'^(dyn|impl)\s', text):
+        bounds = {bound.strip().split('<')[0].split('::')[-1] for bound in split_type_arguments(text.split(None, 1)[1], '+')}
+        is_send, is_sync = 'Send' in bounds, 'Sync' in bounds
+        return is_send, is_sync, None if is_send else f"`{text}` has no Send bound"
+
+    if text[:1] in ('(', '['):
+        inner = text[1:text.rfind(')' if text[0] == '(' else ']')]
+        base, args = None, split_type_arguments(inner.split(';')[0] if text[0] == '[' else inner)
+    elif '<' in text:
+        base = text[:text.index('<')].split('::')[-1].strip()
+        args = [arg for arg in split_type_arguments(text[text.index('<') + 1:text.rfind('>')]) if not arg.startswith("'")]
+    else:
+        match = re.match(r'[\w:]+', text)
+        base, args = (match.group(0).split('::')[-1] if match else None), []
+
+    results = [thread_safety(arg, user_types) for arg in args]
+    args_send = all(result[0] for result in results)
+    args_sync = all(result[1] for result in results)
+    send_reason = next((result[2] for result in results if not result[0]), None)
+    sync_reason = next((result[2] for result in results if not result[1]), None)
+
+    if base in NON_SEND_TYPES:
+        return False, False, f"`{base}` is not Send"
+    if base == 'Arc':
+        is_safe = args_send and args_sync
+        return is_safe, is_safe, None if is_safe else f"`{text}` needs Send + Sync contents: {sync_reason or send_reason}"
+    if base in NON_SYNC_TYPES:
+        return args_send, False, send_reason if not args_send else f"`{base}` is not Sync"
+    if base == 'Mutex':
+        return args_send, args_send, send_reason
+    if base == 'RwLock':
+        return args_send, args_send and args_sync, send_reason or sync_reason
+    if base in user_types:
+        is_send, is_sync, reason = user_types[base]
+        return is_send and args_send, is_sync and args_sync, reason or send_reason or sync_reason
+    return args_send, args_sync, send_reason or sync_reason
+
+
+def _blank(text: bytes) -> bytes:
+    """Replaces every byte except newlines with a space, so offsets and line numbers are preserved."""
+    return bytes(b if b == 0x0A else 0x20 for b in text)
+
+
+def parse_version(text: Optional[str]) -> Optional[Tuple[int, ...]]:
+    """Parses a Rust version such as `1.65` or `1.70.0` into a tuple, or returns None."""
+    match = re.match(r'^\s*(\d+)\.(\d+)(?:\.(\d+))?', text or '')
+    return tuple(int(part) for part in match.groups() if part is not None) if match else None
+
+
+def read_cargo_manifest(crate_root: Path) -> Dict[str, Optional[str]]:
+    """Reads `name`, `edition` and `rust-version` from the `[package]` table of a Cargo.toml."""
+    manifest = {"name": None, "edition": None, "rust_version": None}
+    try:
+        text = (crate_root / "Cargo.toml").read_text(encoding="utf-8")
+    except OSError:
+        return manifest
+    section = None
+    for line in text.splitlines():
+        line = line.split('#', 1)[0].strip()
+        header = re.match(r'^\[([^\]]+)\]$', line)
+        if header:
+            section = header.group(1).strip()
+            continue
+        entry = re.match(r'^(name|edition|rust-version)\s*=\s*"([^"]*)"', line)
+        if section == 'package' and entry:
+            manifest[entry.group(1).replace('-', '_')] = entry.group(2)
+    return manifest
+
+
+def module_path_for(file_path: Path, crate_root: Optional[Path]) -> Optional[str]:
+    """
+    Returns the module path of a file in its crate: `src/lib.rs` is `crate`, `src/a/b.rs` and
+    `src/a/b/mod.rs` are `crate::a::b`. Files outside `src/` or under `src/bin/` (separate crates) give None.
+    """
+    if crate_root is None:
+        return None
+    try:
+        parts = list(Path(file_path).resolve().relative_to(Path(crate_root).resolve() / "src").with_suffix('').parts)
+    except ValueError:
+        return None
+    if not parts or parts[0] == 'bin':
+        return None
+    if parts[-1] in ('lib', 'main', 'mod'):
+        parts = parts[:-1]
+    return '::'.join(['crate'] + parts)
+
+
+def resolve_use_path(import_path: str, module_path: str) -> Optional[List[str]]:
+    """
+    Turns a `use` path written in module `module_path` into absolute segments starting at
+    `crate`: `super::x` and `self::x` are resolved, and bare paths (`foo::Bar`) are taken as
+    relative to the current module. Returns None for paths that climb above the crate root.
+    """
+    current = module_path.split('::')
+    segments = import_path.split('::')
+    if segments[0] == 'crate':
+        return segments
+    if segments[0] == 'self':
+        return current + segments[1:]
+    while segments and segments[0] == 'super':
+        if len(current) == 1:
+            return None
+        current, segments = current[:-1], segments[1:]
+    return current + segments
+
+
+def public_item_paths(files: list) -> Dict[tuple, List[str]]:
+    """
+    Computes the paths under which the functions and types of one crate are reachable from
+    outside it: `pub` items of modules declared `pub mod` all the way from the crate root, plus
+    whatever `pub use` (including globs) re-exports into such modules. Paths start at `crate`;
+    returns `{(file_path, name, line_number): ["crate::a::Item", ..]}`.
+    """
+    members = {}
+    reexports = []
+    for file_data in files:
+        base = file_data.get('module_path')
+        if not base:
+            continue
+        scope = lambda inline: f"{base}::{inline}" if inline else base
+        for decl in file_data.get('module_declarations', []):
+            if decl['visibility'] == 'pub':
+                parent = scope(decl['inline_module'])
+                members.setdefault(parent, {})[decl['name']] = ('module', f"{parent}::{decl['name']}")
+        for item in file_data.get('functions', []) + file_data.get('classes', []):
+            # Methods are reported with their types, not as standalone paths.
+            if item.get('visibility') == 'pub' and item.get('context') is None:
+                key = (file_data['file_path'], item['name'], item['line_number'])
+                members.setdefault(scope(item.get('inline_module')), {})[item['name']] = ('item', key)
+        for imp in file_data.get('imports', []):
+            if imp.get('visibility') == 'pub' and imp.get('alias') != '_':
+                reexports.append((scope(imp.get('inline_module')), imp))
+
+    # Re-exports can re-export other re-exports, so resolve until nothing new appears.
+    changed = True
+    while changed:
+        changed = False
+        for module, imp in reexports:
+            path = imp['full_import_name']
+            if path.endswith('*'):
+                segments = resolve_use_path(path[:-1].rstrip(':'), module)
+                exported = dict(members.get('::'.join(segments), {})) if segments else {}
+            else:
+                segments = resolve_use_path(path, module)
+                target = members.get('::'.join(segments[:-1]), {}).get(segments[-1]) if segments else None
+                exported = {imp.get('alias') or segments[-1]: target} if target else {}
+            scope_members = members.setdefault(module, {})
+            for name, target in exported.items():
+                if name not in scope_members:
+                    scope_members[name] = target
+                    changed = True
+
+    paths = {}
+    queue, seen = [('crate', 'crate')], {'crate'}
+    while queue:
+        module, public = queue.pop(0)
+        for name, (kind, target) in sorted(members.get(module, {}).items()):
+            if kind == 'module':
+                if target not in seen:
+                    seen.add(target)
+                    queue.append((target, f"{public}::{name}"))
+            else:
+                paths.setdefault(target, set()).add(f"{public}::{name}")
+    return {key: sorted(value) for key, value in paths.items()}
+
+
+def glob_import_usage(files: list) -> List[Dict[str, Any]]:
+    """
+    Resolves every `use path::*` of one crate against its module tree and reports which names the
+    glob brings into scope (`provided`) and which of them the importing module actually refers to
+    (`used`): unqualified calls, types and enum paths in its functions, not shadowed by a local item
+    or an explicit import. Names a `pub use` glob re-exports also count as used when another module
+    imports them through it. Globs of modules outside the crate (`std::io::prelude::*`, enum
+    variants) come back with `target_module` None.
+    """
+    scope = lambda file_data, inline: f"{file_data['module_path']}::{inline}" if inline else file_data['module_path']
+    declared, explicit, globs = {}, [], []
+    for file_data in files:
+        if not file_data.get('module_path'):
+            continue
+        declared.setdefault(file_data['module_path'], {})
+        for decl in file_data.get('module_declarations', []):
+            declared.setdefault(scope(file_data, decl['inline_module']), {})[decl['name']] = decl['visibility']
+        for item in file_data.get('functions', []) + file_data.get('classes', []):
+            if item.get('context') is None:
+                declared.setdefault(scope(file_data, item.get('inline_module')), {})[item['name']] = item.get('visibility')
+        for imp in file_data.get('imports', []):
+            module = scope(file_data, imp.get('inline_module'))
+            if imp['full_import_name'].endswith('*'):
+                segments = resolve_use_path(imp['full_import_name'][:-1].rstrip(':'), module)
+                globs.append({"file_data": file_data, "imp": imp, "module": module,
+                              "target": '::'.join(segments) if segments else None})
+            elif imp.get('alias') != '_':
+                explicit.append((module, imp))
+
+    # What each module offers to a glob: its items, modules and imports, with their visibility.
+    members = {module: dict(names) for module, names in declared.items()}
+    for module, imp in explicit:
+        name = imp.get('alias') or imp['full_import_name'].split('::')[-1]
+        members.setdefault(module, {}).setdefault(name, imp.get('visibility'))
+
+    def visible(importer, target):
+        # Private items are visible to the module itself and its descendants.
+        if importer == target or importer.startswith(target + '::'):
+            return dict(members.get(target, {}))
+        return {name: vis for name, vis in members.get(target, {}).items() if vis}
+
+    # Globs re-export through other globs, so expand until nothing new appears.
+    changed = True
+    while changed:
+        changed = False
+        for glob in globs:
+            if glob['target'] not in members:
+                continue
+            glob['provided'] = visible(glob['module'], glob['target'])
+            for name, vis in glob['provided'].items():
+                if name not in members.setdefault(glob['module'], {}):
+                    members[glob['module']][name] = glob['imp'].get('visibility') and vis
+                    changed = True
+
+    # Names each module refers to without a path, from the code of its functions.
+    referenced = {}
+    for file_data in files:
+        if not file_data.get('module_path'):
+            continue
+        function_scopes = {(f['name'], f['line_number']): scope(file_data, f.get('inline_module'))
+                           for f in file_data.get('functions', [])}
+        class_scopes = {c['name']: scope(file_data, c.get('inline_module')) for c in file_data.get('classes', [])}
+        for call in file_data.get('function_calls', []):
+            context = call.get('context') or (None, None, None)
+            module = function_scopes.get((context[0], context[2]))
+            if module and call.get('full_name'):
+                referenced.setdefault(module, set()).add(re.split(r'::|\.|<', call['full_name'])[0])
+        for reference in file_data.get('type_references', []):
+            module = function_scopes.get((reference['function'], reference['function_line'])) or class_scopes.get(reference['owner'])
+            if module and not re.search(r'::\s*' + re.escape(reference['type']) + r'\b', reference['type_text'] or ''):
+                referenced.setdefault(module, set()).add(reference['type'])
+        for usage in file_data.get('variant_usages', []):
+            module = function_scopes.get((usage['function'], usage['function_line']))
+            if module:
+                referenced.setdefault(module, set()).add(usage['enum'].split('::')[0])
+
+    for glob in globs:
+        own = set(declared.get(glob['module'], {})) | {imp.get('alias') or imp['full_import_name'].split('::')[-1]
+                                                       for module, imp in explicit if module == glob['module']}
+        glob['used'] = {name for name in referenced.get(glob['module'], set())
+                        if name in glob.get('provided', {}) and name not in own}
+
+    # Re-exported names count as used when another module imports them through the glob's module.
+    changed = True
+    while changed:
+        changed = False
+        for glob in globs:
+            if not glob['imp'].get('is_reexport') or 'provided' not in glob:
+                continue
+            consumed = set()
+            for module, imp in explicit:
+                segments = resolve_use_path(imp['full_import_name'], module)
+                if segments and '::'.join(segments[:-1]) == glob['module']:
+                    consumed.add(segments[-1])
+            for other in globs:
+                if other['target'] == glob['module'] and other is not glob:
+                    consumed |= other['used']
+            new = (consumed & set(glob['provided'])) - glob['used']
+            if new:
+                glob['used'] |= new
+                changed = True
+
+    report = []
+    for glob in globs:
+        imp = glob['imp']
+        resolved = 'provided' in glob
+        report.append({
+            "file_path": glob['file_data']['file_path'],
+            "line_number": imp['line_number'],
+            "module": glob['module'],
+            "import_path": imp['full_import_name'],
+            "is_reexport": bool(imp.get('is_reexport')),
+            "target_module": glob['target'] if resolved else None,
+            "provided": sorted(glob.get('provided', {})),
+            "used": sorted(glob['used']),
+        })
+    return report
+
+
+def estimate_msrv(features: list, edition: Optional[str] = None):
+    """
+    Returns `(msrv, reasons)`: the highest version required by the detected `features` and the
+    crate `edition`, and the features that require it (as `feature (version) at file:line`).
+    """
+    required = [(EDITION_VERSIONS[edition], f"edition {edition}")] if edition in EDITION_VERSIONS else []
+    for feature in features:
+        location = f"{feature['file_path']}:{feature['line_number']}" if feature.get('file_path') else f"line {feature['line_number']}"
+        required.append((parse_version(feature['version']), f"{feature['feature']} ({feature['version']}) at {location}"))
+    if not required:
+        return None, []
+    msrv = max(version for version, _ in required)
+    return ".".join(str(part) for part in msrv), sorted(reason for version, reason in required if version == msrv)
+
+
+def desugar_modern_syntax(source: bytes) -> bytes:
+    """
+    Rewrites let-else, `if let` chains, top-level or-patterns in `let` and generic associated
+    types into syntax that older grammars accept, without moving any byte: `let Some(x) = y
+    else { .. }` becomes a `let` followed by a block, the `let P =` of a chained condition is
+    blanked so only its scrutinee remains, `let A | B = x` keeps its first alternative and the
+    generics and where clause of `type Item<'a> where Self: 'a;` are blanked. Used only to
+    recover from parse errors; node text is always read from the original source.
+    """
+    result = bytearray(source)
+    for match in LET_ELSE_PATTERN.finditer(source):
+        result[match.start(1):match.end(1)] = b';   '
+    for match in LET_CHAIN_PATTERN.finditer(source):
+        result[match.start(1):match.end(1)] = _blank(match.group(1))
+    for pattern in (GAT_GENERICS_PATTERN, GAT_WHERE_PATTERN, GAT_BINDING_WHERE_PATTERN):
+        for match in pattern.finditer(source):
+            result[match.start(1):match.end(1)] = _blank(match.group(1))
+    for match in LET_PATTERN_HEAD.finditer(source):
+        pattern, depth = match.group(1), 0
+        for index, char in enumerate(pattern):
+            if char in b'([':
+                depth += 1
+            elif char in b')]':
+                depth -= 1
+            elif char == ord('|') and depth == 0 and pattern[index:index + 2] != b'||':
+                start = match.start(1) + index
+                result[start:match.end(1)] = _blank(source[start:match.end(1)])
+                break
+    return bytes(result)
+
+
+class RustTreeSitterParser:
+    """A Rust-specific parser using tree-sitter, encapsulating language-specific logic."""
+
+    def __init__(self, generic_parser_wrapper):
+        self.generic_parser_wrapper = generic_parser_wrapper
+        self.language_name = generic_parser_wrapper.language_name
+        self.language = generic_parser_wrapper.language
+        self.parser = generic_parser_wrapper.parser
+
+        self.queries = {
+            name: self.language.query(query_str)
+            for name, query_str in RUST_QUERIES.items()
+        }
+        # Per-file state, rebuilt by every call to parse(): the original source bytes (node
+        # text is sliced from them, as the tree may come from a desugared copy) and the
+        # environment for local type inference.
+        self._source = None
+        self._type_env = None
+
+    def _get_node_text(self, node) -> str:
+        if self._source is not None:
+            return self._source[node.start_byte:node.end_byte].decode('utf-8')
+        return node.text.decode('utf-8')
+
+    def _get_identifier(self, node) -> str:
+        """Returns the text of a name or path node with raw identifiers normalized, so `r#type` and `type` are one symbol."""
+        return normalize_identifier(self._get_node_text(node))
+
+    def _count_errors(self, root_node) -> int:
+        return sum(1 for node in self._walk(root_node) if node.type == 'ERROR' or node.is_missing)
+
+    def _parse_source(self, source: bytes):
+        """
+        Parses `source`, retrying on a desugared copy when the grammar rejects newer syntax
+        (let-else, let chains, or-patterns) so that the surrounding function is not lost.
+        """
+        tree = self.parser.parse(source)
+        if not tree.root_node.has_error:
+            return tree
+        desugared = desugar_modern_syntax(source)
+        if desugared == source:
+            return tree
+        retry = self.parser.parse(desugared)
+        if self._count_errors(retry.root_node) < self._count_errors(tree.root_node):
+            return retry
+        return tree
+
+    def _walk(self, node):
+        """Yields every node of the subtree rooted at `node` in document order."""
+        stack = [node]
+        while stack:
+            current = stack.pop()
+            yield current
+            stack.extend(reversed(current.children))
+
+    def _strip_generics(self, type_text: str) -> str:
+        """Reduces a type such as `Container<T>` or `&mut fmt::Formatter` to its base name."""
+        type_text = type_text.lstrip('&').strip()
+        if type_text.startswith('mut '):
+            type_text = type_text[4:]
+        base = type_text.split('<', 1)[0].strip()
+        return normalize_identifier(base.split('::')[-1])
+
+    def _get_scope_name(self, node) -> Optional[str]:
+        if node.type == 'impl_item':
+            type_node = node.child_by_field_name('type')
+            return self._strip_generics(self._get_node_text(type_node)) if type_node else None
+        name_node = node.child_by_field_name('name')
+        return self._get_identifier(name_node) if name_node else None
+
+    def _get_parent_context(self, node, types=CONTEXT_TYPES):
+        curr = node.parent
+        while curr:
+            if curr.type in types:
+                name_node = curr.child_by_field_name('name')
+                line_node = name_node if name_node else curr
+                return self._get_scope_name(curr), curr.type, line_node.start_point[0] + 1
+            curr = curr.parent
+        return None, None, None
+
+    def _get_impl_trait(self, node) -> Optional[str]:
+        """Returns the trait name if `node` lives directly inside an `impl Trait for Type` block."""
+        curr = node.parent
+        while curr and curr.type not in CONTEXT_TYPES:
+            curr = curr.parent
+        if curr is None or curr.type != 'impl_item':
+            return None
+        trait_node = curr.child_by_field_name('trait')
+        return self._strip_generics(self._get_node_text(trait_node)) if trait_node else None
+
+    def _get_visibility(self, node) -> Optional[str]:
+        for child in node.children:
+            if child.type == 'visibility_modifier':
+                return self._get_node_text(child)
+        return None
+
+    def _get_attributes(self, node):
+        """Collects the outer attributes (`#[...]`) attached to an item."""
+        attributes = []
+        prev = node.prev_named_sibling
+        while prev and prev.type in ('attribute_item', 'line_comment', 'block_comment'):
+            if prev.type == 'attribute_item':
+                attributes.insert(0, self._get_node_text(prev))
+            prev = prev.prev_named_sibling
+        return attributes
+
+    def _get_repr(self, attributes):
+        """Extracts the layout hints of `#[repr(C, packed(2))]`-style attributes, e.g. `['C', 'packed(2)']`."""
+        repr_hints = []
+        for attribute in attributes:
+            match = re.match(r'#\[\s*repr\s*\((.*)\)\s*\]$', attribute, re.DOTALL)
+            if match:
+                repr_hints.extend(self._split_type_arguments(re.sub(r'\s+', '', match.group(1))))
+        return repr_hints
+
+    def _type_parameter_names(self, type_params_node):
+        """Returns the names of the type parameters (not lifetimes or const generics) declared in `<...>`, in order."""
+        names = []
+        if type_params_node is None:
+            return names
+        for child in type_params_node.named_children:
+            if child.type == 'type_identifier':
+                names.append(self._get_identifier(child))
+            elif child.type == 'constrained_type_parameter':
+                left = child.child_by_field_name('left')
+                if left is not None and left.type == 'type_identifier':
+                    names.append(self._get_identifier(left))
+            elif child.type in ('type_parameter', 'optional_type_parameter'):
+                name = child.child_by_field_name('name')
+                if name is not None:
+                    names.append(self._get_identifier(name))
+        return names
+
+    def _get_derives(self, attributes):
+        """Extracts the traits of `#[derive(Debug, serde::Serialize)]`-style attributes, e.g. `['Debug', 'Serialize']`."""
+        derives = []
+        for attribute in attributes:
+            match = re.match(r'#\[\s*derive\s*\((.*)\)\s*\]$', attribute, re.DOTALL)
+            if match:
+                derives.extend(part.strip().split('::')[-1] for part in self._split_type_arguments(match.group(1)) if part.strip())
+        return derives
+
+    def _calculate_complexity(self, node):
+        complexity_nodes = {
+            "if_expression", "while_expression", "for_expression", "loop_expression",
+            "match_arm", "try_expression", "if_let_expression", "while_let_expression",
+        }
+        count = 1
+
+        def traverse(n):
+            nonlocal count
+            if n.type in complexity_nodes:
+                count += 1
+            elif n.type == 'binary_expression':
+                operator = n.child_by_field_name('operator')
+                if operator and operator.type in ('&&', '||'):
+                    count += 1
+            for child in n.children:
+                traverse(child)
+
+        traverse(node)
+        return count
+
+    def _nesting_depth(self, func_node):
+        """Returns the deepest nesting of control flow (`if`, `match`, loops) in a function; `else if` stays at its `if`'s level."""
+        nesting_nodes = {"if_expression", "match_expression", "while_expression", "for_expression", "loop_expression"}
+
+        def depth(n, level):
+            deepest = level
+            for child in n.children:
+                if child.type == 'function_item':
+                    continue
+                nested = child.type in nesting_nodes and not (child.type == 'if_expression' and n.type == 'else_clause')
+                deepest = max(deepest, depth(child, level + 1 if nested else level))
+            return deepest
+
+        body = func_node.child_by_field_name('body')
+        return depth(body, 0) if body is not None else 0
+
+    def _structural_hash(self, func_node) -> Dict[str, Any]:
+        """
+        Hashes the shape of a function (signature and body) with identifiers, `Self` and literals
+        normalized away, so near-identical copies hash alike. `body_size` is the number of tokens hashed
+        and `shape_minhash` their MinHash signature, to also find functions of a similar shape.
+        """
+        tokens = []
+
+        def walk(n):
+            if n.type in ('line_comment', 'block_comment'):
+                return
+            if n.type in NORMALIZED_LITERALS:
+                tokens.append("LIT")
+            elif n.type in NORMALIZED_IDENTIFIERS or (n.child_count == 0 and self._get_node_text(n) == 'Self'):
+                tokens.append("ID")
+            elif n.child_count == 0:
+                tokens.append(n.type)
+            else:
+                tokens.append(f"({n.type}")
+                for child in n.children:
+                    walk(child)
+                tokens.append(")")
+
+        walk(func_node)
+        return {
+            "body_hash": hashlib.sha1(" ".join(tokens).encode("utf-8")).hexdigest(),
+            "body_size": sum(token != ")" and not token.startswith("(") for token in tokens),
+            "shape_minhash": shape_signature(tokens),
+        }
+
+    def _doc_text(self, node, inner: bool = False) -> Optional[str]:
+        """
+        The text of a doc comment (`///`, `/** */`, or `//!`, `/*! */` when `inner`) or `doc`
+        attribute, without its markers; None when `node` is not documentation.
+        """
+        text = self._get_node_text(node)
+        if node.type == 'line_comment':
+            if text.startswith('//!' if inner else '///') and not text.startswith('////'):
+                return text[3:].strip()
+        elif node.type == 'block_comment':
+            if text.startswith('/*!' if inner else '/**') and not text.startswith('/***') and len(text) > 4:
+                lines = [re.sub(r'^\s*\*?\s?', '', line).rstrip() for line in text[3:-2].splitlines()]
+                return "\n".join(lines).strip()
+        elif node.type == ('inner_attribute_item' if inner else 'attribute_item'):
+            match = DOC_ATTRIBUTE.match(text.strip())
+            if match:
+                return match.group(1).replace('\\"', '"').strip()
+        return None
+
+    def _get_docstring(self, item_node):
+        """Collects the doc comments (`///`, `/** */`) and `#[doc = "..."]` attributes directly preceding an item."""
+        lines = []
+        prev = item_node.prev_named_sibling
+        while prev and prev.type in ('line_comment', 'block_comment', 'attribute_item'):
+            text = self._doc_text(prev)
+            if text is not None:
+                lines.insert(0, text)
+            elif prev.type != 'attribute_item':
+                break
+            prev = prev.prev_named_sibling
+        return "\n".join(lines) if lines else None
+
+    def _get_inner_docstring(self, container):
+        """Collects the `//!` and `/*! */` doc comments and `#![doc = "..."]` attributes opening a file or inline module."""
+        lines = []
+        for child in container.named_children:
+            if child.type not in ('line_comment', 'block_comment', 'inner_attribute_item'):
+                break
+            text = self._doc_text(child, inner=True)
+            if text is not None:
+                lines.append(text)
+        return "\n".join(lines) if lines else None
+
+    def parse(self, file_path: Path, is_dependency: bool = False) -> Dict:
+        """Parses a file and returns its structure in a standardized dictionary format."""
+        self._source = read_source(file_path)
+        tree = self._parse_source(self._source)
+        root_node = tree.root_node
+        self._file_cfg = self._cfg_conditions(self._get_node_text(child) for child in root_node.children
+                                              if child.type == 'inner_attribute_item')
+
+        functions = self._find_functions(root_node)
+        classes = self._find_classes(root_node)
+        impls = self._find_impls(root_node)
+        associated_types = self._find_associated_types(root_node)
+        includes = self._find_includes(root_node, file_path)
+        macros, macro_rules = self._find_macros(root_node)
+        msrv_features = self._find_msrv_features(root_node)
+        generated_functions, generated_classes = self._expand_macro_items(root_node, macro_rules)
+        functions.extend(generated_functions)
+        classes.extend(generated_classes)
+        builders = self._find_builders(functions)
+        module_declarations = self._find_module_declarations(root_node)
+        imports = self._find_imports(root_node)
+        fields = self._find_fields(root_node)
+        newtypes = self._find_newtypes(root_node)
+        self._build_type_env(functions, fields, root_node)
+        function_calls = self._find_calls(root_node)
+        dispatch_calls = self._find_dispatch_calls(root_node)
+        variables = self._find_variables(root_node)
+        channels = self._find_channels(root_node)
+        shared_state = self._find_shared_state(root_node)
+        await_holding_locks = self._find_await_holding_locks(root_node, shared_state)
+        lock_regions = self._find_lock_regions(root_node, shared_state)
+        blocking_calls = self._find_blocking_calls(root_node)
+        interior_mutability = self._find_interior_mutability(root_node)
+        pointer_wrappings = self._find_pointer_wrappings(root_node)
+        enum_variants = self._find_enum_variants(root_node)
+        closures = self._find_closures(root_node)
+        higher_order_calls = self._find_higher_order_calls(root_node, closures)
+        thread_spawns = self._find_thread_spawns(root_node)
+        field_accesses = self._find_field_accesses(root_node)
+        variant_usages = self._find_variant_usages(root_node)
+        type_references = self._find_type_references(root_node)
+        instantiation_sites = self._find_instantiation_sites(root_node)
+        tasks = self._find_tasks(root_node)
+        literals = self._find_literals(root_node)
+
+        file_data = {
+            "file_path": str(file_path),
+            "functions": functions,
+            "classes": classes,
+            "impls": impls,
+            "builders": builders,
+            "associated_types": associated_types,
+            "includes": includes,
+            "macros": macros,
+            "msrv_features": msrv_features,
+            "crate_root": str(self._find_crate_root(file_path) or "") or None,
+            "module_path": module_path_for(file_path, self._find_crate_root(file_path)),
+            "module_declarations": module_declarations,
+            "docstring": self._get_inner_docstring(root_node),
+            "file_cfg": self._file_cfg,
+            "variables": variables,
+            "imports": imports,
+            "function_calls": function_calls,
+            "dispatch_calls": dispatch_calls,
+            "channels": channels,
+            "shared_state": shared_state,
+            "await_holding_locks": await_holding_locks,
+            "lock_regions": lock_regions,
+            "blocking_calls": blocking_calls,
+            "interior_mutability": interior_mutability,
+            "pointer_wrappings": pointer_wrappings,
+            "enum_variants": enum_variants,
+            "fields": fields,
+            "newtypes": newtypes,
+            "field_accesses": field_accesses,
+            "closures": closures,
+            "higher_order_calls": higher_order_calls,
+            "thread_spawns": thread_spawns,
+            "variant_usages": variant_usages,
+            "type_references": type_references,
+            "instantiation_sites": instantiation_sites,
+            "tasks": tasks,
+            "literals": literals,
+            "is_dependency": is_dependency,
+            "lang": self.language_name,
+        }
+        self._apply_import_aliases(imports, file_data)
+        return file_data
+
+    def _parameter_types(self, params_node):
+        """Returns the declared types of a function's parameters, `self` excluded."""
+        return [self._get_node_text(param.child_by_field_name('type')) for param in params_node.named_children
+                if param.type == 'parameter' and param.child_by_field_name('type') is not None
+                and param.child_by_field_name('pattern') is not None and param.child_by_field_name('pattern').type != 'self']
+
+    def _extract_parameters(self, params_node):
+        args = []
+        for param in params_node.named_children:
+            if param.type == 'self_parameter':
+                args.append('self')
+            elif param.type == 'parameter':
+                pattern = param.child_by_field_name('pattern')
+                if pattern is None:
+                    continue
+                if pattern.type == 'identifier':
+                    args.append(self._get_identifier(pattern))
+                else:
+                    args.extend(self._get_identifier(n) for n in self._walk(pattern) if n.type == 'identifier')
+        return args
+
+    def _get_receiver(self, params_node, is_method: bool):
+        """
+        Returns `(receiver, receiver_type)` where receiver is '&self', '&mut self', 'self' or
+        'associated' for methods, and None for free functions. Typed receivers such as
+        `self: Rc<Self>` are classified by their type, which is returned as `receiver_type`.
+        """
+        first = params_node.named_children[0] if params_node and params_node.named_children else None
+        if first is not None and first.type == 'self_parameter':
+            if not any(child.type == '&' for child in first.children):
+                return 'self', None
+            is_mut = any(child.type == 'mutable_specifier' for child in first.children)
+            return ('&mut self' if is_mut else '&self'), None
+        if first is not None and first.type == 'parameter':
+            pattern = first.child_by_field_name('pattern')
+            type_node = first.child_by_field_name('type')
+            if pattern is not None and pattern.type == 'self' and type_node is not None:
+                type_text = self._get_node_text(type_node)
+                if type_node.type == 'reference_type':
+                    is_mut = any(child.type == 'mutable_specifier' for child in type_node.children)
+                    return ('&mut self' if is_mut else '&self'), type_text
+                return 'self', type_text
+        return ('associated' if is_method else None), None
+
+    def _get_callable_params(self, func_node, params_node):
+        """Returns the names of the parameters typed as closures: `F` bound by `Fn*`, `impl Fn(..)`, `&dyn FnMut(..)` or `fn(..)`."""
+        bound_sources = [func_node.child_by_field_name('type_parameters')]
+        bound_sources += [child for child in func_node.children if child.type == 'where_clause']
+        callable_generics = set()
+        for source in bound_sources:
+            if source is not None:
+                callable_generics.update(FN_TRAIT_BOUND_PATTERN.findall(self._get_node_text(source)))
+
+        callable_params = []
+        for param in (params_node.named_children if params_node else []):
+            if param.type != 'parameter':
+                continue
+            pattern = param.child_by_field_name('pattern')
+            type_node = param.child_by_field_name('type')
+            if pattern is None or type_node is None:
+                continue
+            type_text = self._get_node_text(type_node)
+            if FN_TRAIT_TYPE_PATTERN.search(type_text) or self._strip_generics(type_text) in callable_generics:
+                callable_params.append(self._get_identifier(pattern).replace('mut ', ''))
+        return callable_params
+
+    def _get_closure_context(self, node) -> Optional[str]:
+        """Returns the name of the innermost closure enclosing `node` within its function, if any."""
+        curr = node.parent
+        while curr and curr.type != 'function_item':
+            if curr.type == 'closure_expression':
+                return self._closure_name(curr)
+            curr = curr.parent
+        return None
+
+    def _closure_name(self, node) -> str:
+        return f"<closure@{node.start_point[0] + 1}:{node.start_point[1]}>"
+
+    def _find_closures(self, root_node):
+        closures = []
+        for node in self._walk(root_node):
+            if node.type != 'closure_expression':
+                continue
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            owner, _, _ = self._get_parent_context(node, types=('impl_item', 'trait_item'))
+            params_node = node.child_by_field_name('parameters')
+            captures = self._closure_capture_modes(node)
+            bound_to = None
+            if node.parent and node.parent.type == 'let_declaration':
+                pattern = node.parent.child_by_field_name('pattern')
+                if pattern is not None and pattern.type == 'identifier':
+                    bound_to = self._get_identifier(pattern)
+
+            closures.append({
+                "name": self._closure_name(node),
+                "line_number": node.start_point[0] + 1,
+                **node_span(node),
+                "source": self._get_node_text(node),
+                "params": self._get_node_text(params_node) if params_node else "",
+                "is_move": any(child.type == 'move' for child in node.children),
+                "bound_to": bound_to,
+                "context": func_name,
+                "context_line": func_line,
+                "owner": owner,
+                "captured_places": [c['place'] for c in captures],
+                "capture_modes": [c['mode'] for c in captures],
+                "capture_reasons": [c['reason'] for c in captures],
+                "capture_binding_lines": [c['binding_line'] or 0 for c in captures],
+                "lang": self.language_name,
+            })
+        return closures
+
+    def _closure_capture_modes(self, closure_node):
+        """
+        Returns how a closure captures each place it uses from its environment: locals (and their
+        fields, captured precisely as in edition 2021) and `self.field` paths. The mode is the
+        strongest use: `mut_ref` for assignments, `&mut` borrows and mutating method calls, `move`
+        for by-value uses of non-Copy values (every capture of a `move` closure), `ref` otherwise.
+        """
+        locals_types = dict(self._closure_captures(closure_node))
+        is_move = any(child.type == 'move' for child in closure_node.children)
+        places = {}
+        for node in self._walk(closure_node.child_by_field_name('body') or closure_node):
+            if node.type == 'self':
+                root = 'self'
+            elif node.type == 'identifier' and self._get_identifier(node) in locals_types and node.parent.type != 'scoped_identifier':
+                root = self._get_identifier(node)
+            else:
+                continue
+
+            place, path = node, [root]
+            while place.parent is not None and place.parent.type == 'field_expression' \
+                    and self._same_node(place.parent.child_by_field_name('value'), place):
+                outer = place.parent
+                if outer.parent is not None and outer.parent.type == 'call_expression' \
+                        and self._same_node(outer.parent.child_by_field_name('function'), outer):
+                    break
+                path.append(self._get_node_text(outer.child_by_field_name('field')))
+                place = outer
+
+            place_type = locals_types.get(root) if len(path) == 1 else None
+            mode, reason = ('move', 'move closure') if is_move else self._capture_use(place, closure_node, place_type)
+            key = '.'.join(path)
+            if key not in places or CAPTURE_MODES.index(mode) > CAPTURE_MODES.index(places[key]['mode']):
+                places[key] = {"place": key, "mode": mode, "reason": reason, "binding_line": None}
+        for key, capture in places.items():
+            root = key.split('.')[0]
+            if root != 'self':
+                capture['binding_line'] = self._local_binding_line(closure_node, root)
+        return list(places.values())
+
+    def _capture_use(self, place, closure_node, place_type: Optional[str]):
+        """Classifies one use of a captured place as `(mode, reason)`."""
+        parent = place.parent
+        is_copy = place_type is not None and (place_type in COPY_TYPES or (place_type.startswith('&') and not place_type.startswith('&mut')))
+        by_value = ('ref', 'copied') if is_copy else ('move', 'used by value')
+
+        target = parent if parent.type == 'unary_expression' and self._get_node_text(parent).startswith('*') else place
+        assignment = target.parent
+        if assignment is not None and assignment.type in ('assignment_expression', 'compound_assignment_expr') \
+                and self._same_node(assignment.child_by_field_name('left'), target):
+            return 'mut_ref', 'assigned'
+        if parent.type == 'reference_expression':
+            return ('mut_ref', '&mut borrow') if any(c.type == 'mutable_specifier' for c in parent.children) else ('ref', 'borrowed')
+        if parent.type == 'field_expression' and parent.parent is not None and parent.parent.type == 'call_expression':
+            method = self._get_node_text(parent.child_by_field_name('field'))
+            if method in MUTATING_METHODS:
+                return 'mut_ref', f"calls {method}"
+            is_handle = place_type is not None and self._strip_generics(place_type) in JOIN_HANDLE_TYPES
+            if method.startswith('into_') or method in CONSUMING_METHODS or (method == 'join' and is_handle):
+                return by_value[0], f"calls {method}"
+            return 'ref', f"calls {method}"
+        if parent.type in ('arguments', 'return_expression', 'field_initializer', 'array_expression', 'tuple_expression') \
+                or (parent.type == 'let_declaration' and self._same_node(parent.child_by_field_name('value'), place)) \
+                or self._same_node(place, closure_node.child_by_field_name('body')) \
+                or (parent.type == 'block' and self._same_node(parent.named_children[-1], place)
+                    and self._same_node(parent, closure_node.child_by_field_name('body'))):
+            return by_value
+        return 'ref', 'read'
+
+    def _local_binding_line(self, node, name: str) -> Optional[int]:
+        """Returns the line of the latest binding of `name` before `node` in its enclosing function."""
+        func_node = node.parent
+        while func_node and func_node.type != 'function_item':
+            func_node = func_node.parent
+        if func_node is None:
+            return None
+        line = None
+        for candidate in self._walk(func_node):
+            if candidate.start_byte >= node.start_byte:
+                break
+            if candidate.type == 'identifier' and self._get_identifier(candidate) == name \
+                    and (candidate.parent.type == 'closure_parameters' or self._is_pattern(candidate)):
+                line = candidate.start_point[0] + 1
+        return line
+
+    def _closure_captures(self, closure_node):
+        """
+        Returns `[(name, type)]` for the variables of the enclosing function that a closure uses,
+        with their inferred type (None when unknown). Bindings made inside the closure are ignored.
+        """
+        func_node = closure_node.parent
+        while func_node and func_node.type != 'function_item':
+            func_node = func_node.parent
+        if func_node is None:
+            return []
+        is_binding = lambda node: node.type == 'identifier' and (node.parent.type == 'closure_parameters' or self._is_pattern(node))
+
+        declared_before = set()
+        for node in self._walk(func_node):
+            if node.start_byte >= closure_node.start_byte:
+                break
+            if is_binding(node):
+                declared_before.add(self._get_identifier(node))
+
+        declared_inside = set()
+        for node in self._walk(closure_node):
+            if is_binding(node):
+                declared_inside.add(self._get_identifier(node))
+
+        captures = []
+        for node in self._walk(closure_node.child_by_field_name('body') or closure_node):
+            if node.type != 'identifier':
+                continue
+            name = self._get_identifier(node)
+            if name in declared_inside or name not in declared_before or name in (c[0] for c in captures):
+                continue
+            captures.append((name, self._local_binding_type(closure_node, name)))
+        return captures
+
+    def _find_thread_spawns(self, root_node):
+        """
+        Finds closures handed to `thread::spawn`, `Builder::spawn` or a scope's `spawn`, with the
+        variables they move (or, without `move`, borrow) into the new thread.
+        """
+        spawns = []
+        for node in self._walk(root_node):
+            if node.type != 'call_expression':
+                continue
+            function_node = node.child_by_field_name('function')
+            arguments_node = node.child_by_field_name('arguments')
+            if function_node is None or arguments_node is None:
+                continue
+            full_name = self._get_identifier(function_node)
+            if re.split(r'::|\.', full_name)[-1] not in THREAD_SPAWN_METHODS:
+                continue
+            for closure_node in arguments_node.named_children:
+                if closure_node.type != 'closure_expression':
+                    continue
+                is_move = any(child.type == 'move' for child in closure_node.children)
+                func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+                spawns.append({
+                    "closure": self._closure_name(closure_node),
+                    "spawner": re.sub(r'\s+', '', full_name),
+                    "is_move": is_move,
+                    "captures": [
+                        {"name": name, "type": type_text if is_move or type_text is None else f"&{type_text}"}
+                        for name, type_text in self._closure_captures(closure_node)
+                    ],
+                    "context": func_name,
+                    "context_line": func_line,
+                    "line_number": node.start_point[0] + 1,
+                })
+        return spawns
+
+    def _find_higher_order_calls(self, root_node, closures):
+        """
+        Finds calls that pass a closure, or a function name, as an argument. The graph builder
+        turns them into MAY_CALL edges when the callee declares that parameter as callable.
+        """
+        bound_closures = {(c['context_line'], c['bound_to']): c['name'] for c in closures if c['bound_to']}
+        higher_order_calls = []
+        for node in self._walk(root_node):
+            if node.type != 'call_expression':
+                continue
+            arguments_node = node.child_by_field_name('arguments')
+            function_node = node.child_by_field_name('function')
+            if arguments_node is None or function_node is None:
+                continue
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+
+            for index, arg in enumerate(arguments_node.named_children):
+                if arg.type == 'closure_expression':
+                    target_kind, target = 'closure', self._closure_name(arg)
+                elif arg.type == 'identifier' and (func_line, self._get_identifier(arg)) in bound_closures:
+                    target_kind, target = 'closure', bound_closures[(func_line, self._get_identifier(arg))]
+                elif arg.type in ('identifier', 'scoped_identifier'):
+                    target_kind, target = 'function', self._get_identifier(arg).split('::')[-1]
+                else:
+                    continue
+
+                full_name, inferred_obj_type = self._resolve_call_target(function_node)
+                callee = function_node.child_by_field_name('function') if function_node.type == 'generic_function' else function_node
+                name_node = callee.child_by_field_name('field') or callee.child_by_field_name('name') or callee
+                higher_order_calls.append({
+                    "callee": self._get_identifier(name_node),
+                    "full_name": full_name,
+                    "inferred_obj_type": inferred_obj_type,
+                    "arg_index": index,
+                    "target_kind": target_kind,
+                    "target": target,
+                    "caller": func_name,
+                    "caller_line": func_line,
+                    "line_number": node.start_point[0] + 1,
+                })
+        return higher_order_calls
+
+    def _find_functions(self, root_node):
+        functions = []
+        query = self.queries['functions']
+        for node, capture_name in query.captures(root_node):
+            if capture_name != 'name':
+                continue
+
+            func_node = node.parent
+            name = self._get_identifier(node)
+            params_node = func_node.child_by_field_name('parameters')
+            return_type_node = func_node.child_by_field_name('return_type')
+
+            context, context_type, context_line = self._get_parent_context(func_node)
+            class_context, _, _ = self._get_parent_context(func_node, types=('impl_item', 'trait_item'))
+            receiver, receiver_type = self._get_receiver(params_node, context_type in ('impl_item', 'trait_item'))
+
+            func_data = {
+                "name": name,
+                "line_number": node.start_point[0] + 1,
+                **node_span(func_node, node),
+                "args": self._extract_parameters(params_node) if params_node else [],
+                "param_types": self._parameter_types(params_node) if params_node else [],
+                "generic_params": self._type_parameter_names(func_node.child_by_field_name('type_parameters')),
+                "source": self._get_node_text(func_node),
+                "source_code": self._get_node_text(func_node),
+                "docstring": self._get_docstring(func_node),
+                "cyclomatic_complexity": self._calculate_complexity(func_node),
+                "nesting_depth": self._nesting_depth(func_node),
+                **self._structural_hash(func_node),
+                "context": context,
+                "context_type": context_type,
+                "class_context": class_context,
+                "decorators": self._get_attributes(func_node),
+                "visibility": self._get_visibility(func_node),
+                "inline_module": self._inline_module_path(func_node),
+                "signature": self._item_signature(func_node),
+                "return_type": self._get_node_text(return_type_node) if return_type_node else None,
+                "impl_trait": self._get_impl_trait(func_node),
+                "impl_line": context_line if context_type == 'impl_item' else None,
+                "is_async": self._is_async_scope(func_node),
+                "is_async_entry_point": any(ASYNC_ENTRY_POINT_PATTERN.match(attr) for attr in self._get_attributes(func_node)),
+                **self._cfg_properties(func_node),
+                "receiver": receiver,
+                "receiver_type": receiver_type,
+                "callable_params": self._get_callable_params(func_node, params_node),
+                **self._constraint_properties(func_node),
+                "lang": self.language_name,
+                "is_dependency": False,
+            }
+            functions.append(func_data)
+        return functions
+
+    def _find_builders(self, functions):
+        """
+        Finds builder types: inherent impls with chainable `fn foo(mut self, ..) -> Self` setters
+        and a consuming `build()` (or `finish()`, `try_build()`) returning another type, possibly
+        wrapped in `Result`/`Option`. Associated functions returning the builder are its entry points.
+        """
+        methods_by_type = {}
+        for func in functions:
+            if func.get('impl_line') and not func.get('impl_trait'):
+                methods_by_type.setdefault(func['class_context'], []).append(func)
+
+        builders = []
+        for builder, methods in methods_by_type.items():
+            setters, build_methods = [], []
+            for method in methods:
+                return_type = method.get('return_type')
+                if not return_type:
+                    continue
+                returns_self = self._strip_generics(return_type) in ('Self', builder)
+                if method['receiver'] == 'self' and returns_self:
+                    setters.append(method['name'])
+                elif method['receiver'] in ('self', '&self', '&mut self') and BUILD_METHOD_PATTERN.match(method['name']):
+                    build_methods.append(method)
+            if not setters or not build_methods:
+                continue
+
+            build = build_methods[0]
+            target_tree = self._type_tree(build['return_type'])
+            while target_tree[0] in FALLIBLE_WRAPPERS and target_tree[1]:
+                target_tree = target_tree[1][0]
+            target = target_tree[0]
+            if not target or target in ('Self', builder):
+                continue
+
+            entry_points = [
+                {"name": func['name'], "owner": func.get('class_context'), "line_number": func['line_number']}
+                for func in functions
+                if func['receiver'] in ('associated', None) and func.get('return_type')
+                and self._strip_generics(func['return_type']) in (builder, 'Self' if func.get('class_context') == builder else None)
+            ]
+            builders.append({
+                "builder": builder,
+                "target": target,
+                "build_method": build['name'],
+                "setters": setters,
+                "entry_points": entry_points,
+                "line_number": build['impl_line'],
+            })
+        return builders
+
+    def _find_impls(self, root_node):
+        """Finds `impl` blocks, keeping their generic parameters and where clause so conditional APIs stay distinct."""
+        impls = []
+        for node in self._walk(root_node):
+            if node.type != 'impl_item':
+                continue
+            type_node = node.child_by_field_name('type')
+            trait_node = node.child_by_field_name('trait')
+            body_node = node.child_by_field_name('body')
+            type_params = node.child_by_field_name('type_parameters')
+            where_clause = next((child for child in node.children if child.type == 'where_clause'), None)
+            if type_node is None:
+                continue
+
+            header_end = body_node.start_byte if body_node else node.end_byte
+            header = self._source[node.start_byte:header_end].decode('utf-8')
+            methods, associated_consts = [], []
+            if body_node is not None:
+                methods = [self._get_identifier(item.child_by_field_name('name')) for item in body_node.named_children
+                           if item.type == 'function_item' and item.child_by_field_name('name')]
+                associated_consts = [self._get_identifier(item.child_by_field_name('name')) for item in body_node.named_children
+                                     if item.type == 'const_item' and item.child_by_field_name('name')]
+
+            impl_type = self._strip_generics(self._get_node_text(type_node))
+
+            impls.append({
+                "name": re.sub(r'\s+', ' ', header).strip(),
+                "line_number": node.start_point[0] + 1,
+                **node_span(node),
+                "type": impl_type,
+                "self_type": self._get_node_text(type_node),
+                "blanket_param": impl_type if trait_node is not None and impl_type in self._type_parameter_names(type_params) else None,
+                "trait": self._strip_generics(self._get_node_text(trait_node)) if trait_node else None,
+                "trait_type": self._get_node_text(trait_node) if trait_node else None,
+                "type_parameters": self._get_node_text(type_params) if type_params else None,
+                "generic_params": self._type_parameter_names(type_params),
+                "where_clause": self._get_node_text(where_clause) if where_clause else None,
+                "is_negative": any(child.type == '!' for child in node.children),
+                "is_unsafe": any(child.type == 'unsafe' for child in node.children),
+                "methods": methods,
+                "associated_consts": associated_consts,
+                "decorators": self._get_attributes(node),
+                "docstring": self._get_docstring(node),
+                **self._constraint_properties(node),
+                "lang": self.language_name,
+            })
+        return impls
+
+    def _find_associated_types(self, root_node):
+        """
+        Finds associated type declarations in traits and their bindings in impls, including
+        generic associated types such as `type Item<'a> where Self: 'a;`.
+        """
+        associated_types = []
+        for node in self._walk(root_node):
+            if node.type not in ('associated_type', 'type_item') or node.parent is None:
+                continue
+            container = node.parent.parent
+            if container is None or container.type not in ('trait_item', 'impl_item'):
+                continue
+
+            # Parsed from the original text, as the tree may come from a desugared copy.
+            match = ASSOCIATED_TYPE_PATTERN.match(re.sub(r'^(pub(\([^)]*\))?\s+)', '', self._get_node_text(node).strip()))
+            if not match:
+                continue
+            generics = match.group('generics')
+            bounds = match.group('bounds')
+            # Impl bindings put the where clause last: `type Item<'a> = &'a T where Self: 'a;`
+            binding, _, binding_where = (match.group('binding') or '').partition(' where ')
+            where_clause = match.group('where') or (f"where {binding_where}" if binding_where else None)
+            associated_types.append({
+                "name": normalize_identifier(match.group('name')),
+                "owner": self._get_scope_name(container),
+                "owner_kind": 'trait' if container.type == 'trait_item' else 'impl',
+                "trait": self._get_impl_trait(node) if container.type == 'impl_item' else self._get_scope_name(container),
+                "impl_line": container.start_point[0] + 1 if container.type == 'impl_item' else None,
+                "generics": generics,
+                "is_generic": generics is not None,
+                "bounds": self._split_type_arguments(bounds, '+') if bounds else [],
+                "where_clause": re.sub(r'\s+', ' ', where_clause).strip() if where_clause else None,
+                "binding": binding.strip() or None,
+                "line_number": node.start_point[0] + 1,
+                **node_span(node, node.child_by_field_name('name')),
+                "lang": self.language_name,
+            })
+        return associated_types
+
+    def _find_classes(self, root_node):
+        classes = []
+        query = self.queries['classes']
+        kinds = {'struct_item': 'struct', 'enum_item': 'enum', 'trait_item': 'trait', 'union_item': 'union'}
+        for node, capture_name in query.captures(root_node):
+            if capture_name != 'name':
+                continue
+
+            class_node = node.parent
+            bases = []
+            if class_node.type == 'trait_item':
+                bounds_node = class_node.child_by_field_name('bounds')
+                if bounds_node:
+                    bases = [self._get_identifier(b) for b in bounds_node.named_children
+                             if b.type in ('type_identifier', 'scoped_type_identifier', 'generic_type')]
+
+            context, _, _ = self._get_parent_context(class_node)
+            attributes = self._get_attributes(class_node)
+
+            class_data = {
+                "name": self._get_identifier(node),
+                "line_number": node.start_point[0] + 1,
+                **node_span(class_node, node),
+                "bases": bases,
+                "kind": kinds[class_node.type],
+                "source": self._get_node_text(class_node),
+                "docstring": self._get_docstring(class_node),
+                "context": context,
+                "decorators": attributes,
+                "repr": self._get_repr(attributes),
+                "derives": self._get_derives(attributes),
+                "visibility": self._get_visibility(class_node),
+                "inline_module": self._inline_module_path(class_node),
+                "signature": self._item_signature(class_node),
+                "generic_params": self._type_parameter_names(class_node.child_by_field_name('type_parameters')),
+                **self._cfg_properties(class_node),
+                **self._trait_item_properties(class_node),
+                **self._constraint_properties(class_node),
+                "lang": self.language_name,
+                "is_dependency": False,
+            }
+            classes.append(class_data)
+        return classes
+
+    def _trait_item_properties(self, class_node) -> Dict[str, List[str]]:
+        """
+        Splits a trait's methods and associated consts into required ones (declared without a body
+        or value) and provided ones (with a default). Empty for other items.
+        """
+        body = class_node.child_by_field_name('body')
+        if class_node.type != 'trait_item' or body is None:
+            return {}
+        items = {"required_methods": [], "provided_methods": [], "required_consts": [], "provided_consts": []}
+        for item in body.named_children:
+            name_node = item.child_by_field_name('name')
+            if name_node is None:
+                continue
+            if item.type == 'function_signature_item':
+                items["required_methods"].append(self._get_identifier(name_node))
+            elif item.type == 'function_item':
+                items["provided_methods"].append(self._get_identifier(name_node))
+            elif item.type == 'const_item':
+                kind = "provided_consts" if item.child_by_field_name('value') is not None else "required_consts"
+                items[kind].append(self._get_identifier(name_node))
+        return items
+
+    def _expand_use_tree(self, text: str, prefix: str = ""):
+        """
+        Expands a use tree such as `std::sync::{Arc, mpsc::{self, Sender as Tx}}` into
+        a flat list of `(path, alias)` pairs.
+        """
+        text = re.sub(r'\s+', '', re.sub(r'\s+as\s+', '@', text))
+        results = []
+        if '{' not in text:
+            path_text, _, alias = text.partition('@')
+            path = f"{prefix}::{path_text}" if prefix else path_text
+            if path.endswith('::self'):
+                path = path[:-len('::self')]
+            results.append((path, alias or None))
+            return results
+
+        head, _, rest = text.partition('{')
+        new_prefix = "::".join(p for p in (prefix, head.rstrip(':')) if p)
+        body = rest[:rest.rfind('}')]
+
+        depth, current = 0, ""
+        for char in body:
+            if char == '{':
+                depth += 1
+            elif char == '}':
+                depth -= 1
+            if char == ',' and depth == 0:
+                if current:
+                    results.extend(self._expand_use_tree(current, new_prefix))
+                current = ""
+            else:
+                current += char
+        if current:
+            results.extend(self._expand_use_tree(current, new_prefix))
+        return results
+
+    def _find_imports(self, root_node):
+        imports = []
+        seen = set()
+        query = self.queries['imports']
+        for node, capture_name in query.captures(root_node):
+            if capture_name != 'import':
+                continue
+
+            if node.type == 'extern_crate_declaration':
+                crate_node = node.child_by_field_name('name')
+                alias_node = node.child_by_field_name('alias')
+                if crate_node is None:
+                    continue
+                use_trees = [(self._get_identifier(crate_node), self._get_identifier(alias_node) if alias_node else None)]
+            else:
+                argument = node.child_by_field_name('argument')
+                if argument is None:
+                    continue
+                use_trees = self._expand_use_tree(self._get_identifier(argument))
+
+            is_reexport = self._get_visibility(node) is not None
+
+            for path, alias in use_trees:
+                if (path, alias) in seen:
+                    continue
+                seen.add((path, alias))
+
+                name = path if path.endswith('*') else path.split('::')[-1]
+                imports.append({
+                    "name": name,
+                    "full_import_name": path,
+                    "line_number": node.start_point[0] + 1,
+                    "alias": alias,
+                    "is_reexport": is_reexport,
+                    "visibility": self._get_visibility(node),
+                    "inline_module": self._inline_module_path(node),
+                    "context": self._get_parent_context(node)[:2],
+                    "lang": self.language_name,
+                    "is_dependency": False,
+                })
+        return imports
+
+    def _apply_import_aliases(self, imports, file_data):
+        """
+        Rewrites names introduced by `use a::Bar as Baz` or `extern crate x as y` back to the
+        original definition, so that `Baz::new()` or `y::f()` resolve like `Bar::new()`/`x::f()`.
+        Calls keep the name as written in their `alias` field.
+        """
+        aliases = {imp['alias']: imp['name'] for imp in imports
+                   if imp.get('alias') and imp['alias'] != '_' and not imp['name'].endswith('*')}
+        if not aliases:
+            return
+
+        for call in file_data['function_calls']:
+            if call['name'] in aliases and not call['inferred_obj_type']:
+                call['alias'], call['name'] = call['name'], aliases[call['name']]
+            if call['inferred_obj_type'] in aliases:
+                call['inferred_obj_type'] = aliases[call['inferred_obj_type']]
+        for call in file_data['higher_order_calls']:
+            if call['callee'] in aliases and not call['inferred_obj_type']:
+                call['callee'] = aliases[call['callee']]
+            if call['inferred_obj_type'] in aliases:
+                call['inferred_obj_type'] = aliases[call['inferred_obj_type']]
+            if call['target_kind'] == 'function' and call['target'] in aliases:
+                call['target'] = aliases[call['target']]
+        for usage in file_data['variant_usages']:
+            if usage['enum'] in aliases:
+                usage['enum'] = aliases[usage['enum']]
+        for reference in file_data['type_references']:
+            if reference['type'] in aliases:
+                reference['type'] = aliases[reference['type']]
+        for wrapping in file_data['pointer_wrappings']:
+            if wrapping['inner_base'] in aliases:
+                wrapping['inner_base'] = aliases[wrapping['inner_base']]
+
+    def _build_type_env(self, functions, fields, root_node):
+        """
+        Collects the return types of this file's functions and the types of struct and enum variant
+        fields (keyed like `Cons.1`, see `_iter_fields`) for local type inference.
+        """
+        return_types = {}
+        for func in functions:
+            if func.get('return_type'):
+                # Methods and associated functions are keyed by their impl type, free functions by None.
+                return_types[(func['class_context'] if func['receiver'] else None, func['name'])] = func['return_type']
+        field_types = {(field['owner'], field['name']): field['type'] for field in fields}
+        field_types.update({(owner, field): self._get_node_text(type_node)
+                            for owner_node, owner, field, type_node, _ in self._iter_fields(root_node)
+                            if owner_node.type == 'enum_item'})
+        self._type_env = {"return_types": return_types, "field_types": field_types, "locals": {}}
+
+    def _receiver_type(self, type_text: Optional[str]) -> Optional[str]:
+        """Strips references and auto-deref'ed `Box`/`Rc`/`Arc` layers: `&Rc<Person>` becomes `Person`."""
+        if not type_text:
+            return None
+        base, args, text = self._type_tree(type_text)
+        while base in ('Box', 'Rc', 'Arc') and args:
+            base, args, text = args[0]
+        return text if base else None
+
+    def _local_binding_type(self, node, name: str) -> Optional[str]:
+        """Returns the type of the latest binding of `name` declared before `node` in its function."""
+        func_node = node.parent
+        while func_node and func_node.type != 'function_item':
+            func_node = func_node.parent
+        if func_node is None:
+            return None
+        bindings = self._local_bindings(func_node)
+        binding_type = None
+        for byte, binding, type_text in bindings:
+            if byte < node.start_byte and binding == name:
+                binding_type = type_text
+        return binding_type
+
+    def _local_bindings(self, func_node):
+        """
+        Infers `[(byte, name, type)]` for the parameters, `let` bindings and tuple variant pattern
+        bindings (`List::Cons(_, tail)`) of a function, in order.
+        """
+        cache = self._type_env["locals"]
+        if func_node.start_byte in cache:
+            return cache[func_node.start_byte]
+        bindings = cache[func_node.start_byte] = []
+
+        params_node = func_node.child_by_field_name('parameters')
+        for param in (params_node.named_children if params_node else []):
+            pattern, type_node = param.child_by_field_name('pattern'), param.child_by_field_name('type')
+            if param.type == 'parameter' and pattern is not None and pattern.type == 'identifier' and type_node is not None:
+                bindings.append((param.start_byte, self._get_identifier(pattern), self._get_node_text(type_node)))
+
+        for node in self._walk(func_node.child_by_field_name('body')):
+            if node.type == 'tuple_struct_pattern':
+                bindings.extend(self._variant_pattern_bindings(node))
+                continue
+            if node.type != 'let_declaration':
+                continue
+            pattern = node.child_by_field_name('pattern')
+            if pattern is not None and pattern.type == 'mut_pattern':
+                pattern = pattern.named_children[-1] if pattern.named_children else None
+            if pattern is None or pattern.type != 'identifier':
+                continue
+            type_node, value_node = node.child_by_field_name('type'), node.child_by_field_name('value')
+            if type_node is not None:
+                type_text = self._get_node_text(type_node)
+            else:
+                type_text = self._infer_expression_type(value_node) if value_node is not None else None
+            if type_text:
+                bindings.append((node.end_byte, self._get_identifier(pattern), type_text))
+        return bindings
+
+    def _variant_pattern_bindings(self, pattern):
+        """The `(byte, name, type)` of the identifiers a path-qualified tuple variant pattern binds."""
+        path_node = pattern.child_by_field_name('type')
+        if path_node is None or path_node.type != 'scoped_identifier' or path_node.child_by_field_name('path') is None:
+            return []
+        enum = self._strip_generics(self._get_node_text(path_node.child_by_field_name('path')))
+        if enum == 'Self':
+            enum = self._get_parent_context(pattern, types=('impl_item', 'trait_item'))[0]
+        variant = self._get_identifier(path_node.child_by_field_name('name'))
+        bindings = []
+        for position, child in enumerate(c for c in pattern.named_children if c != path_node):
+            if child.type == 'mut_pattern':
+                child = child.named_children[-1] if child.named_children else child
+            type_text = self._type_env["field_types"].get((enum, f"{variant}.{position}"))
+            if child.type == 'identifier' and type_text:
+                bindings.append((pattern.end_byte, self._get_identifier(child), type_text))
+        return bindings
+
+    def _infer_expression_type(self, node) -> Optional[str]:
+        """
+        Lightweight, best-effort type inference for an expression: constructors (`Foo::new()`,
+        `Foo { .. }`), typed bindings and parameters, `self` and its fields, and method chains
+        whose return types are declared in this file (builders returning `Self`).
+        """
+        if node is None or self._type_env is None:
+            return None
+        impl_type = lambda: self._get_parent_context(node, types=('impl_item', 'trait_item'))[0]
+
+        if node.type in ('parenthesized_expression', 'reference_expression'):
+            return self._infer_expression_type(node.named_children[-1] if node.named_children else None)
+        if node.type == 'self':
+            return impl_type()
+        if node.type == 'identifier':
+            return self._local_binding_type(node, self._get_identifier(node))
+        if node.type == 'struct_expression':
+            name_node = node.child_by_field_name('name')
+            name = self._strip_generics(self._get_node_text(name_node)) if name_node else None
+            return impl_type() if name == 'Self' else name
+        if node.type == 'field_expression':
+            owner = self._strip_generics(self._receiver_type(self._infer_expression_type(node.child_by_field_name('value'))) or '')
+            return self._type_env["field_types"].get((owner, self._get_identifier(node.child_by_field_name('field'))))
+        if node.type == 'try_expression':
+            inner = self._infer_expression_type(node.named_children[0] if node.named_children else None)
+            return self._unwrap_type(inner)
+        if node.type != 'call_expression':
+            return None
+
+        function_node = node.child_by_field_name('function')
+        if function_node is not None and function_node.type == 'generic_function':
+            function_node = function_node.child_by_field_name('function')
+        if function_node is None:
+            return None
+
+        if function_node.type == 'scoped_identifier':
+            _, owner = self._resolve_call_target(function_node)
+            name = self._get_identifier(function_node.child_by_field_name('name'))
+            if owner in SMART_POINTER_TYPES and name in ('new', 'clone'):
+                # `Arc::new(Mutex::new(x))` keeps its contents; `Arc::clone(&a)` has the type of `a`.
+                arguments = node.child_by_field_name('arguments')
+                inner = self._infer_expression_type(arguments.named_children[0]) if arguments and arguments.named_children else None
+                if name == 'clone':
+                    return inner or owner
+                return f"{owner}<{inner}>" if inner else owner
+            if owner is None:
+                return self._type_env["return_types"].get((None, name))
+            return_type = self._type_env["return_types"].get((owner, name))
+            if return_type:
+                return owner if self._strip_generics(return_type) == 'Self' else return_type
+            return owner if name in CONSTRUCTOR_NAMES or name.startswith(('new_', 'from_')) else None
+
+        if function_node.type == 'identifier':
+            return self._type_env["return_types"].get((None, self._get_identifier(function_node)))
+
+        if function_node.type == 'field_expression':
+            method = self._get_identifier(function_node.child_by_field_name('field'))
+            receiver = self._infer_expression_type(function_node.child_by_field_name('value'))
+            if receiver is None:
+                return None
+            if method in UNWRAP_METHODS:
+                return self._unwrap_type(receiver)
+            if method in PASSTHROUGH_METHODS:
+                base, args, text = self._type_tree(receiver)
+                return args[0][2] if base in SMART_POINTER_TYPES and args else text
+            owner = self._strip_generics(self._receiver_type(receiver) or '')
+            return_type = self._type_env["return_types"].get((owner, method))
+            if return_type:
+                return owner if self._strip_generics(return_type) == 'Self' else return_type
+        return None
+
+    def _unwrap_type(self, type_text: Optional[str]) -> Optional[str]:
+        """`Option<T>` and `Result<T, E>` become `T`; other types are returned unchanged."""
+        if not type_text:
+            return None
+        base, args, text = self._type_tree(type_text)
+        if base in ('Option', 'Result') and args:
+            return args[0][2]
+        return text
+
+    def _resolve_call_target(self, function_node):
+        """Returns `(full_name, inferred_obj_type)` for the `function` child of a call expression."""
+        if function_node.type == 'generic_function':
+            function_node = function_node.child_by_field_name('function')
+
+        full_name = self._get_identifier(function_node)
+        inferred_obj_type = None
+
+        if function_node.type == 'scoped_identifier':
+            path_node = function_node.child_by_field_name('path')
+            if path_node is not None:
+                path_text = self._get_node_text(path_node)
+                if path_text == 'Self':
+                    inferred_obj_type = self._get_parent_context(function_node, types=('impl_item', 'trait_item'))[0]
+                elif self._strip_generics(path_text)[:1].isupper():
+                    # Lowercase segments are modules (`shapes::area()`), resolved by the function name.
+                    inferred_obj_type = self._strip_generics(path_text)
+        elif function_node.type == 'field_expression':
+            value_node = function_node.child_by_field_name('value')
+            if value_node is not None and value_node.type == 'self':
+                inferred_obj_type = self._get_parent_context(function_node, types=('impl_item', 'trait_item'))[0]
+            elif value_node is not None:
+                receiver_type = self._receiver_type(self._infer_expression_type(value_node))
+                if receiver_type:
+                    inferred_obj_type = self._strip_generics(receiver_type)
+
+        return full_name, inferred_obj_type
+
+    def _dispatch_traits(self, type_text: str, node):
+        """
+        Returns `(dispatch, traits)` when a method called on a value of `type_text` is picked at
+        runtime (`dyn Trait`) or per instantiation (`impl Trait`, a generic `T: Trait` of the
+        enclosing function or impl), and `(None, [])` for concrete types.
+        """
+        text, previous = type_text.strip(), None
+        while previous != text:
+            previous = text
+            text = re.sub(r"^&\s*('\w+\s*)?(mut\s+)?", '', text).strip()
+            match = re.match(r"^(?:Box|Rc|Arc)\s*<(.*)>$", text, re.DOTALL)
+            if match:
+                text = match.group(1).strip()
+
+        match = re.match(r"^(dyn|impl)\s+(.*)$", text, re.DOTALL)
+        if match:
+            dispatch = match.group(1)
+            bounds = [self._strip_generics(b.strip()) for b in self._split_type_arguments(match.group(2), '+')]
+        else:
+            dispatch, param, bounds = 'generic', self._strip_generics(text), []
+            scope = node.parent
+            while scope is not None:
+                if scope.type in ('function_item', 'impl_item', 'trait_item'):
+                    bounds.extend(c['bound_base'] for c in self._get_constraints(scope) if c['param'] == param)
+                scope = scope.parent
+        traits = [b for b in bounds if b and not b.startswith(("'", '?')) and b not in MARKER_TRAITS]
+        return (dispatch, traits) if traits else (None, [])
+
+    def _find_dispatch_calls(self, root_node):
+        """
+        Finds method calls dispatched through a trait: on `dyn Trait` and `impl Trait` values, on
+        generic parameters bounded by traits, and on `self` inside trait default methods. Each
+        call may reach the method of any implementation of those traits.
+        """
+        dispatch_calls = []
+        for node in self._walk(root_node):
+            if node.type != 'call_expression':
+                continue
+            function_node = node.child_by_field_name('function')
+            if function_node is not None and function_node.type == 'generic_function':
+                function_node = function_node.child_by_field_name('function')
+            if function_node is None or function_node.type != 'field_expression':
+                continue
+            value_node = function_node.child_by_field_name('value')
+
+            if value_node.type == 'self':
+                trait, context_type, _ = self._get_parent_context(node, types=('impl_item', 'trait_item'))
+                if context_type != 'trait_item':
+                    continue
+                dispatch, traits = 'self', [trait]
+            else:
+                type_text = self._infer_expression_type(value_node)
+                if not type_text:
+                    continue
+                dispatch, traits = self._dispatch_traits(type_text, node)
+                if not dispatch:
+                    continue
+
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            if func_name is None:
+                continue
+            dispatch_calls.append({
+                "method": self._get_identifier(function_node.child_by_field_name('field')),
+                "traits": traits,
+                "dispatch": dispatch,
+                "caller": func_name,
+                "caller_line": func_line,
+                "line_number": node.start_point[0] + 1,
+            })
+        return dispatch_calls
+
+    def _discarded_value(self, call_node) -> Optional[str]:
+        """
+        Tells how the value of a call is thrown away: 'statement' (`f();`), 'let_underscore'
+        (`let _ = f();` or `_ = f();`) or 'ok_discarded' (`f().ok();`). None if it is used.
+        """
+        node, via_ok = call_node, False
+        while True:
+            parent = node.parent
+            while parent is not None and parent.type in ('await_expression', 'parenthesized_expression'):
+                node, parent = parent, parent.parent
+            if parent is None:
+                return None
+            if (not via_ok and parent.type == 'field_expression' and parent.parent is not None
+                    and parent.parent.type == 'call_expression'
+                    and self._get_identifier(parent.child_by_field_name('field')) == 'ok'):
+                node, via_ok = parent.parent, True
+                continue
+            break
+        if parent.type == 'expression_statement':
+            pattern = 'statement'
+        elif parent.type == 'let_declaration' and self._get_node_text(parent.child_by_field_name('pattern')) == '_':
+            pattern = 'let_underscore'
+        elif parent.type == 'assignment_expression' and self._get_node_text(parent.child_by_field_name('left')) == '_':
+            pattern = 'let_underscore'
+        else:
+            return None
+        return 'ok_discarded' if via_ok else pattern
+
+    def _find_calls(self, root_node):
+        calls = []
+        query = self.queries['calls']
+        for node, capture_name in query.captures(root_node):
+            if capture_name != 'name':
+                continue
+
+            call_node = node.parent
+            while call_node and call_node.type != 'call_expression':
+                call_node = call_node.parent
+            if call_node is None:
+                continue
+
+            full_name, inferred_obj_type = self._resolve_call_target(call_node.child_by_field_name('function'))
+
+            args = []
+            arguments_node = call_node.child_by_field_name('arguments')
+            if arguments_node:
+                args = [self._get_node_text(arg) for arg in arguments_node.named_children]
+
+            call_data = {
+                "name": self._get_identifier(node),
+                "full_name": full_name,
+                "line_number": node.start_point[0] + 1,
+                "args": args,
+                "inferred_obj_type": inferred_obj_type,
+                "context": self._get_parent_context(node, types=('function_item',)),
+                "class_context": self._get_parent_context(node, types=('impl_item', 'trait_item'))[:2],
+                "closure_context": self._get_closure_context(node),
+                "discarded": self._discarded_value(call_node),
+                **node_span(call_node, node),
+                "lang": self.language_name,
+                "is_dependency": False,
+            }
+            calls.append(call_data)
+        return calls
+
+    def _find_variables(self, root_node):
+        variables = []
+        query = self.queries['variables']
+        kinds = {'let_declaration': 'let', 'const_item': 'const', 'static_item': 'static'}
+        for node, capture_name in query.captures(root_node):
+            if capture_name != 'name':
+                continue
+
+            decl_node = node.parent
+            value_node = decl_node.child_by_field_name('value')
+            type_node = decl_node.child_by_field_name('type')
+            is_mutable = any(child.type == 'mutable_specifier' for child in decl_node.children)
+
+            context, _, _ = self._get_parent_context(node, types=('function_item',))
+            class_context, _, _ = self._get_parent_context(node, types=('impl_item', 'trait_item'))
+
+            variable_data = {
+                "name": self._get_identifier(node),
+                "line_number": node.start_point[0] + 1,
+                **node_span(decl_node, node),
+                "value": self._get_node_text(value_node) if value_node else None,
+                "type": self._get_node_text(type_node) if type_node else None,
+                "kind": kinds[decl_node.type],
+                "is_mutable": is_mutable,
+                "context": context,
+                "class_context": class_context,
+                "docstring": self._get_docstring(decl_node) if decl_node.type != 'let_declaration' else None,
+                "lang": self.language_name,
+                "is_dependency": False,
+            }
+            variables.append(variable_data)
+        return variables
+
+    def _root_name(self, node) -> Optional[str]:
+        """
+        Finds the binding an expression is rooted at, looking through method chains,
+        references and `self.` field accesses (e.g. `self.rx.lock().unwrap()` -> `rx`).
+        """
+        while node is not None:
+            if node.type == 'identifier':
+                return self._get_identifier(node)
+            if node.type == 'field_expression':
+                value = node.child_by_field_name('value')
+                if value is not None and value.type == 'self':
+                    field = node.child_by_field_name('field')
+                    return self._get_identifier(field) if field else None
+                node = value
+            elif node.type == 'call_expression':
+                node = node.child_by_field_name('function')
+            elif node.type in ('reference_expression', 'unary_expression', 'try_expression',
+                               'parenthesized_expression', 'await_expression'):
+                node = node.named_children[0] if node.named_children else None
+            else:
+                return None
+        return None
+
+    def _get_module_path(self, node) -> Optional[str]:
+        """Returns the inline `mod` path enclosing `node` within its file, e.g. `geometry::shapes`."""
+        modules = []
+        curr = node.parent
+        while curr:
+            if curr.type == 'mod_item' and curr.child_by_field_name('name'):
+                modules.insert(0, self._get_identifier(curr.child_by_field_name('name')))
+            curr = curr.parent
+        return "::".join(modules) or None
+
+    def _find_module_declarations(self, root_node):
+        """Finds `mod` declarations, both `mod a;` and inline `mod a { .. }`, with their visibility."""
+        declarations = []
+        for node in self._walk(root_node):
+            if node.type != 'mod_item' or node.child_by_field_name('name') is None:
+                continue
+            declarations.append({
+                "name": self._get_identifier(node.child_by_field_name('name')),
+                "visibility": self._get_visibility(node),
+                "inline_module": self._inline_module_path(node),
+                "is_inline": node.child_by_field_name('body') is not None,
+                "cfg": self._cfg_conditions(self._get_attributes(node)),
+                "docstring": "\n".join(filter(None, (
+                    self._get_docstring(node),
+                    self._get_inner_docstring(node.child_by_field_name('body')) if node.child_by_field_name('body') else None,
+                ))) or None,
+                "line_number": node.start_point[0] + 1,
+            })
+        return declarations
+
+    def _cfg_conditions(self, attributes) -> List[str]:
+        """The predicates of `#[cfg(...)]` (and `#![cfg(...)]`) attributes, whitespace-normalized."""
+        conditions = []
+        for attribute in attributes:
+            match = CFG_ATTRIBUTE.match(attribute.strip())
+            if match:
+                conditions.append(re.sub(r'\s+', ' ', match.group(1)).strip())
+        return conditions
+
+    def _cfg_properties(self, node) -> Dict[str, List[str]]:
+        """
+        The `cfg` conditions an item is compiled under: its own, those of the impls, traits and
+        inline modules around it and the file's `#![cfg(...)]`; plus the `features` they require.
+        """
+        conditions = []
+        curr = node
+        while curr is not None:
+            if curr is node or curr.type in ('impl_item', 'trait_item', 'mod_item'):
+                conditions = self._cfg_conditions(self._get_attributes(curr)) + conditions
+            curr = curr.parent
+        conditions = list(getattr(self, '_file_cfg', [])) + conditions
+        features = {name for negated, name in CFG_FEATURE.findall(" ".join(conditions)) if not negated}
+        return {"cfg": conditions, "features": sorted(features)}
+
+    def _item_signature(self, node) -> str:
+        """Returns an item's declaration without its body, whitespace-normalized: `pub fn area(&self) -> f64`."""
+        body = node.child_by_field_name('body')
+        # Tuple struct fields are part of the signature; blocks, field lists and variants are not.
+        end = body.start_byte if body is not None and body.type != 'ordered_field_declaration_list' else node.end_byte
+        return re.sub(r'\s+', ' ', self._source[node.start_byte:end].decode('utf-8')).strip().rstrip(';').strip()
+
+    def _inline_module_path(self, node) -> Optional[str]:
+        """Returns the path of the inline `mod a { mod b { .. } }` blocks around `node` (`a::b`), or None at file level."""
+        names = []
+        curr = node.parent
+        while curr is not None:
+            if curr.type == 'mod_item' and curr.child_by_field_name('name') is not None:
+                names.insert(0, self._get_identifier(curr.child_by_field_name('name')))
+            curr = curr.parent
+        return '::'.join(names) or None
+
+    def _find_tasks(self, root_node):
+        """Finds `TODO`, `FIXME` and `HACK` comments with the function, type and inline module around them."""
+        tasks = []
+        for node in self._walk(root_node):
+            if node.type not in ('line_comment', 'block_comment'):
+                continue
+            function, _, function_line = self._get_parent_context(node, types=('function_item',))
+            owner, _, _ = self._get_parent_context(node, types=('impl_item', 'trait_item'))
+            for offset, line in enumerate(self._get_node_text(node).splitlines()):
+                match = TASK_PATTERN.match(line)
+                if not match:
+                    continue
+                tasks.append({
+                    "kind": match.group(1),
+                    "text": match.group(3),
+                    "assignee": (match.group(2) or '').strip() or None,
+                    "line_number": node.start_point[0] + offset + 1,
+                    "function": function,
+                    "function_line": function_line,
+                    "owner": owner,
+                    "inline_module": self._inline_module_path(node),
+                    "lang": self.language_name,
+                })
+        return tasks
+
+    def _literal_value(self, node):
+        """The `(kind, value)` of a notable string or number literal (see TRIVIAL_NUMBERS), None otherwise."""
+        text = self._get_node_text(node)
+        if node.type in ('string_literal', 'raw_string_literal'):
+            value = re.sub(r'^b?r?(#*)"(.*)"\1$', r'\2', text, flags=re.DOTALL)
+            if URL_PATTERN.match(value):
+                return 'url', value
+            if len(value) < 3 or not re.search(r'[A-Za-z]{2}', value):
+                return None
+            return 'string', value[:LITERAL_MAX_LENGTH]
+        if node.type == 'integer_literal':
+            digits = re.sub(r'[iu](8|16|32|64|128|size)$', '', text.replace('_', ''))
+            try:
+                number = int(digits, 0) if re.match(r'^0[xob]', digits) else int(digits)
+            except ValueError:
+                return None
+            return None if number in TRIVIAL_NUMBERS else ('number', text)
+        if node.type == 'float_literal':
+            try:
+                number = float(re.sub(r'_?f(32|64)$', '', text.replace('_', '')))
+            except ValueError:
+                return None
+            return None if number in TRIVIAL_NUMBERS else ('number', text)
+        return None
+
+    def _find_literals(self, root_node):
+        """
+        Finds the notable string, URL and number literals in function bodies and `const`/`static`
+        values, including macro arguments (`panic!("...")`); attributes and test code are skipped.
+        """
+        literals = []
+        for node in self._walk(root_node):
+            if node.type not in ('string_literal', 'raw_string_literal', 'integer_literal', 'float_literal'):
+                continue
+            literal = self._literal_value(node)
+            if literal is None:
+                continue
+            curr, in_macro, skip = node.parent, False, False
+            while curr is not None:
+                if curr.type in ('attribute_item', 'inner_attribute_item'):
+                    skip = True
+                    break
+                if curr.type == 'token_tree':
+                    in_macro = True
+                if curr.type in ('function_item', 'mod_item') and any(
+                        re.match(r'#\[\s*(test|cfg\s*\(\s*test\s*\))', attribute) for attribute in self._get_attributes(curr)):
+                    skip = True
+                    break
+                curr = curr.parent
+            function, _, function_line = self._get_parent_context(node, types=('function_item',))
+            constant, _, _ = self._get_parent_context(node, types=('const_item', 'static_item'))
+            if skip or (function is None and constant is None):
+                continue
+            literals.append({
+                "kind": literal[0],
+                "value": literal[1],
+                "line_number": node.start_point[0] + 1,
+                "function": function,
+                "function_line": function_line,
+                "constant": constant if function is None else None,
+                "in_macro": in_macro,
+            })
+        return literals
+
+    def _find_crate_root(self, file_path: Path) -> Optional[Path]:
+        """Returns the nearest directory above `file_path` holding a Cargo.toml."""
+        for directory in file_path.resolve().parents:
+            if (directory / "Cargo.toml").is_file():
+                return directory
+        return None
+
+    def _evaluate_include_path(self, text: str, file_path: Path) -> Optional[Path]:
+        """
+        Statically evaluates the argument of `include!`: string literals, `concat!(...)` and
+        `env!("CARGO_MANIFEST_DIR")`/`env!("OUT_DIR")`. `OUT_DIR` is looked up among the build
+        script outputs under `target/`. Returns None when the path cannot be determined.
+        """
+        crate_root = self._find_crate_root(file_path)
+        parts = []
+        for token in INCLUDE_TOKEN_PATTERN.finditer(text):
+            env_var, literal, macro = token.groups()
+            if literal is not None:
+                parts.append(literal)
+            elif env_var == 'CARGO_MANIFEST_DIR' and crate_root:
+                parts.append(str(crate_root))
+            elif env_var == 'OUT_DIR':
+                parts.append(None)  # Resolved below from the remaining suffix.
+            elif macro != 'concat!':
+                return None
+
+        if not parts:
+            return None
+        if None in parts[1:]:
+            return None
+        if parts[0] is None:
+            suffix = "".join(parts[1:]).lstrip('/')
+            if crate_root is None or not suffix:
+                return None
+            candidates = sorted(crate_root.glob(f"target/*/build/*/out/{suffix}"), key=lambda p: p.stat().st_mtime)
+            return candidates[-1].resolve() if candidates else None
+
+        path = Path("".join(parts))
+        if not path.is_absolute():
+            path = file_path.resolve().parent / path
+        return path.resolve()
+
+    def _find_includes(self, root_node, file_path: Path):
+        """Finds `include!` invocations and resolves the included file where its path is statically known."""
+        includes = []
+        for node in self._walk(root_node):
+            if node.type != 'macro_invocation':
+                continue
+            macro_node = node.child_by_field_name('macro')
+            if macro_node is None or self._get_identifier(macro_node) != 'include':
+                continue
+            argument = next((child for child in node.named_children if child.type == 'token_tree'), None)
+            if argument is None:
+                continue
+            argument_text = self._get_node_text(argument)[1:-1].strip()
+            resolved = self._evaluate_include_path(argument_text, file_path)
+            includes.append({
+                "argument": argument_text,
+                "resolved_path": str(resolved) if resolved and resolved.is_file() else None,
+                "module": self._get_module_path(node),
+                "context": self._get_parent_context(node, types=('function_item',))[0],
+                "line_number": node.start_point[0] + 1,
+            })
+        return includes
+
+    def _find_macros(self, root_node):
+        """
+        Finds `macro_rules!` definitions. Returns the macro dicts and a map from macro name to
+        its parsed rules, for the macros simple enough to be expanded by `rust_macros`.
+        """
+        macros, macro_rules = [], {}
+        for node in self._walk(root_node):
+            if node.type != 'macro_definition':
+                continue
+            name_node = node.child_by_field_name('name')
+            if name_node is None:
+                continue
+            name = self._get_identifier(name_node)
+            body_text = self._source[name_node.end_byte:node.end_byte].decode('utf-8').strip().rstrip(';')
+            try:
+                rules = rust_macros.parse_macro_rules(body_text)
+            except (ValueError, IndexError):
+                logger.debug(f"Cannot expand macro_rules! {name}, unsupported syntax")
+                rules = None
+            if rules:
+                macro_rules[name] = rules
+
+            macros.append({
+                "name": name,
+                "line_number": node.start_point[0] + 1,
+                **node_span(node, node.child_by_field_name('name')),
+                "source": self._get_node_text(node),
+                "exported": any(attr.startswith('#[macro_export') for attr in self._get_attributes(node)),
+                "rules": len(rules) if rules else 0,
+                "expandable": bool(rules),
+                "docstring": self._get_docstring(node),
+                "lang": self.language_name,
+            })
+        return macros, macro_rules
+
+    def _expand_macro_items(self, root_node, macro_rules, depth: int = 0):
+        """
+        Expands item-position invocations of the macros in `macro_rules` and returns the functions
+        and classes they generate. Generated items are placed on the lines of the invocation and
+        tagged with `generated_by`; expansions are parsed on their own, so their calls are not indexed.
+        """
+        functions, classes = [], []
+        if not macro_rules or depth >= MAX_MACRO_EXPANSION_DEPTH:
+            return functions, classes
+
+        for node in self._walk(root_node):
+            if node.type != 'macro_invocation' or node.parent is None:
+                continue
+            if node.parent.type not in ('source_file', 'declaration_list'):
+                continue
+            macro_node = node.child_by_field_name('macro')
+            if macro_node is None:
+                continue
+            name = self._get_identifier(macro_node).split('::')[-1]
+            argument = next((child for child in node.named_children if child.type == 'token_tree'), None)
+            if name not in macro_rules or argument is None:
+                continue
+
+            expansion = rust_macros.expand(macro_rules[name], self._get_node_text(argument)[1:-1])
+            if not expansion:
+                logger.debug(f"No rule of {name}! matched the invocation at line {node.start_point[0] + 1}")
+                continue
+
+            context, context_type, context_line = self._get_parent_context(node)
+            class_context, _, _ = self._get_parent_context(node, types=('impl_item', 'trait_item'))
+            original_source = self._source
+            self._source = expansion.encode('utf-8')
+            try:
+                expanded_root = self.parser.parse(self._source).root_node
+                items = (self._find_functions(expanded_root), self._find_classes(expanded_root))
+                nested = self._expand_macro_items(expanded_root, macro_rules, depth + 1)
+            finally:
+                self._source = original_source
+
+            for found, nested_found, target in zip(items, nested, (functions, classes)):
+                for item in found:
+                    item.update({
+                        "line_number": node.start_point[0] + 1,
+                        **node_span(node),
+                        "generated_by": name,
+                    })
+                    if item.get('context') is None:
+                        item['context'] = context
+                        if 'context_type' in item:
+                            item.update({"context_type": context_type, "class_context": class_context,
+                                         "impl_line": context_line if context_type == 'impl_item' else None})
+                    target.append(item)
+                for item in nested_found:
+                    item.update({"line_number": node.start_point[0] + 1, **node_span(node)})
+                    target.append(item)
+        return functions, classes
+
+    def _find_msrv_features(self, root_node):
+        """Finds the first use of every feature in MSRV_FEATURES, plus `async fn` declared in traits."""
+        source = MSRV_MASK_PATTERN.sub(lambda match: _blank(match.group(0)), self._source)
+        features = []
+        for feature, version, pattern in MSRV_FEATURES:
+            match = pattern.search(source)
+            if match:
+                features.append({
+                    "feature": feature,
+                    "version": ".".join(map(str, version)),
+                    "line_number": source.count(b'\n', 0, match.start()) + 1,
+                })
+
+        for node in self._walk(root_node):
+            if node.type != 'function_item' or self._get_parent_context(node)[1] != 'trait_item':
+                continue
+            modifiers = next((child for child in node.children if child.type == 'function_modifiers'), None)
+            if modifiers is not None and 'async' in self._get_node_text(modifiers).split():
+                features.append({
+                    "feature": 'async_fn_in_trait',
+                    "version": ".".join(map(str, ASYNC_FN_IN_TRAIT_VERSION)),
+                    "line_number": node.start_point[0] + 1,
+                })
+                break
+        return features
+
+    def _find_channels(self, root_node):
+        """
+        Finds channel creations (`let (tx, rx) = mpsc::channel();`) and the functions
+        that send to or receive from them. An endpoint is a binding of the function that
+        created it and is followed through `let` re-bindings in that function (such as
+        `tx.clone()` or `Arc::new(Mutex::new(rx))`), into the parameters of the functions
+        of the file it is passed to, and into the struct fields it is stored in (used as
+        `self.field`, or `value.field` when only one struct holds an endpoint in that field).
+        Two functions binding the same names therefore use their own channels.
+        """
+        channels = []
+        aliases = {}  # ('local', function scope, name) or ('field', owner, name) -> [(channel index, role)]
+
+        def scope_of(node):
+            name, _, line = self._get_parent_context(node, types=('function_item',))
+            return (name, line) if name is not None else None
+
+        def owner_of(node):
+            return self._get_parent_context(node, types=('impl_item', 'trait_item'))[0]
+
+        for node in self._walk(root_node):
+            if node.type != 'let_declaration':
+                continue
+            value = node.child_by_field_name('value')
+            pattern = node.child_by_field_name('pattern')
+            if value is None or pattern is None or value.type != 'call_expression':
+                continue
+            function_node = value.child_by_field_name('function')
+            if function_node.type == 'generic_function':
+                function_node = function_node.child_by_field_name('function')
+            constructor = self._get_identifier(function_node).split('::')[-1]
+            if constructor not in CHANNEL_CONSTRUCTORS or pattern.type != 'tuple_pattern':
+                continue
+
+            endpoints = [self._get_identifier(n) for n in pattern.named_children if n.type == 'identifier']
+            if len(endpoints) != 2:
+                continue
+
+            context, _, context_line = self._get_parent_context(node, types=('function_item',))
+            sender, receiver = endpoints
+            index = len(channels)
+            channels.append({
+                "name": f"{sender}/{receiver}",
+                "line_number": node.start_point[0] + 1,
+                "constructor": self._get_node_text(function_node),
+                "sender": sender,
+                "receiver": receiver,
+                "created_in": context,
+                "created_in_line": context_line,
+                "senders": [],
+                "receivers": [],
+                "lang": self.language_name,
+            })
+            scope = scope_of(node)
+            aliases.setdefault(('local', scope, sender), []).append((index, 'sender'))
+            aliases.setdefault(('local', scope, receiver), []).append((index, 'receiver'))
+
+        if not channels:
+            return channels
+
+        def sources(expr):
+            """The alias keys an expression evaluates to an endpoint through (method chains, wrappers, references)."""
+            if expr is None:
+                return []
+            if expr.type == 'identifier':
+                return [('local', scope_of(expr), self._get_identifier(expr))]
+            if expr.type in ('reference_expression', 'try_expression', 'parenthesized_expression', 'unary_expression'):
+                return [key for child in expr.named_children for key in sources(child)]
+            if expr.type == 'field_expression':
+                value, field = expr.child_by_field_name('value'), expr.child_by_field_name('field')
+                if field is None:
+                    return []
+                owner = owner_of(expr) if value is not None and value.type == 'self' else None
+                return [('field', owner, self._get_identifier(field))]
+            if expr.type == 'call_expression':
+                function_node = expr.child_by_field_name('function')
+                if function_node is not None and function_node.type == 'field_expression':
+                    return sources(function_node.child_by_field_name('value'))
+                arguments = expr.child_by_field_name('arguments')
+                # Wrapping constructors such as `Arc::new(rx)` or `Arc::clone(&rx)`.
+                return [key for arg in (arguments.named_children if arguments else []) for key in sources(arg)]
+            return []
+
+        def entries(keys):
+            found = []
+            for key in keys:
+                if key[0] == 'field' and key[1] is None:
+                    owners = {k[1] for k in aliases if k[0] == 'field' and k[2] == key[2]}
+                    key = ('field', owners.pop(), key[2]) if len(owners) == 1 else key
+                for entry in aliases.get(key, []):
+                    if entry not in found:
+                        found.append(entry)
+            return found
+
+        def alias(key, found):
+            known = aliases.setdefault(key, [])
+            added = [entry for entry in found if entry not in known]
+            known.extend(added)
+            return bool(added)
+
+        functions = {}  # name -> [(owner, scope, parameter names, has self)]
+        for node in self._walk(root_node):
+            if node.type != 'function_item':
+                continue
+            name_node, params_node = node.child_by_field_name('name'), node.child_by_field_name('parameters')
+            if name_node is None or params_node is None:
+                continue
+            params = self._extract_parameters(params_node)
+            has_self = bool(params) and params[0] == 'self'
+            functions.setdefault(self._get_identifier(name_node), []).append(
+                (owner_of(node), (self._get_identifier(name_node), name_node.start_point[0] + 1),
+                 params[1:] if has_self else params, has_self))
+
+        def callees(call):
+            """The functions of the file a call may reach, with the index of its first argument among their parameters."""
+            function_node = call.child_by_field_name('function')
+            if function_node is None:
+                return []
+            if function_node.type == 'generic_function':
+                function_node = function_node.child_by_field_name('function')
+            if function_node.type == 'identifier':
+                return [(f, 0) for f in functions.get(self._get_identifier(function_node), []) if f[0] is None]
+            if function_node.type == 'scoped_identifier':
+                path, name_node = function_node.child_by_field_name('path'), function_node.child_by_field_name('name')
+                if path is None or name_node is None:
+                    return []
+                owner = self._get_identifier(path).split('::')[-1]
+                owner = owner_of(call) if owner == 'Self' else owner
+                return [(f, -1 if f[3] else 0) for f in functions.get(self._get_identifier(name_node), []) if f[0] == owner]
+            if function_node.type == 'field_expression':
+                value, field = function_node.child_by_field_name('value'), function_node.child_by_field_name('field')
+                if field is None:
+                    return []
+                methods = [f for f in functions.get(self._get_identifier(field), []) if f[3]]
+                if value is not None and value.type == 'self':
+                    methods = [f for f in methods if f[0] == owner_of(call)]
+                return [(f, 0) for f in methods] if len(methods) == 1 else []
+            return []
+
+        # Follow re-bindings, arguments and struct fields until nothing new is reached.
+        changed = True
+        while changed:
+            changed = False
+            for node in self._walk(root_node):
+                if node.type == 'let_declaration':
+                    pattern = node.child_by_field_name('pattern')
+                    if pattern is not None and pattern.type == 'identifier':
+                        found = entries(sources(node.child_by_field_name('value')))
+                        if found:
+                            changed |= alias(('local', scope_of(node), self._get_identifier(pattern)), found)
+                elif node.type == 'call_expression':
+                    arguments = node.child_by_field_name('arguments')
+                    for position, argument in enumerate(arguments.named_children if arguments else []):
+                        found = entries(sources(argument))
+                        if not found:
+                            continue
+                        for (_, scope, params, _), offset in callees(node):
+                            if 0 <= position + offset < len(params):
+                                changed |= alias(('local', scope, params[position + offset]), found)
+                elif node.type == 'struct_expression':
+                    name_node, body = node.child_by_field_name('name'), node.child_by_field_name('body')
+                    if name_node is None or body is None:
+                        continue
+                    owner = self._strip_generics(self._get_node_text(name_node)).split('::')[-1]
+                    owner = owner_of(node) if owner == 'Self' else owner
+                    for initializer in body.named_children:
+                        if initializer.type == 'shorthand_field_initializer' and initializer.named_children:
+                            field_node = value = initializer.named_children[0]
+                        elif initializer.type == 'field_initializer':
+                            field_node, value = initializer.child_by_field_name('field'), initializer.child_by_field_name('value')
+                        else:
+                            continue
+                        found = entries(sources(value))
+                        if field_node is not None and found:
+                            changed |= alias(('field', owner, self._get_identifier(field_node)), found)
+
+        def record(endpoint_node, role, usage_node):
+            found = entries(sources(endpoint_node))
+            if not found:
+                return
+            func_name, _, func_line = self._get_parent_context(usage_node, types=('function_item',))
+            if func_name is None:
+                return
+            usage = {"function": func_name, "function_line": func_line, "line_number": usage_node.start_point[0] + 1}
+            for index, endpoint_role in found:
+                if endpoint_role == role:
+                    bucket = channels[index]["senders" if role == 'sender' else "receivers"]
+                    if usage not in bucket:
+                        bucket.append(usage)
+
+        for node in self._walk(root_node):
+            if node.type == 'call_expression':
+                function_node = node.child_by_field_name('function')
+                if function_node is None or function_node.type != 'field_expression':
+                    continue
+                method = self._get_identifier(function_node.child_by_field_name('field'))
+                if method in CHANNEL_SEND_METHODS:
+                    record(function_node.child_by_field_name('value'), 'sender', node)
+                elif method in CHANNEL_RECV_METHODS:
+                    record(function_node.child_by_field_name('value'), 'receiver', node)
+            elif node.type == 'for_expression':
+                record(node.child_by_field_name('value'), 'receiver', node)
+
+        return channels
+
+    def _iter_fields(self, root_node):
+        """
+        Yields `(owner_node, owner_name, field_name, type_node, declaration_node)` for every
+        struct, union and enum variant field. Tuple fields are named by position, and
+        variant fields are prefixed with the variant name (e.g. `Cons.1`, `Move.x`).
+        """
+        for node in self._walk(root_node):
+            if node.type not in ('field_declaration_list', 'ordered_field_declaration_list'):
+                continue
+            parent = node.parent
+            if parent is None:
+                continue
+            prefix = ""
+            owner_node = parent
+            if parent.type == 'enum_variant':
+                prefix = self._get_identifier(parent.child_by_field_name('name')) + "."
+                owner_node = parent.parent.parent if parent.parent else None
+            if owner_node is None or owner_node.type not in ('struct_item', 'union_item', 'enum_item'):
+                continue
+            owner_name = self._get_identifier(owner_node.child_by_field_name('name'))
+
+            if node.type == 'field_declaration_list':
+                for field in node.named_children:
+                    if field.type != 'field_declaration':
+                        continue
+                    name_node = field.child_by_field_name('name')
+                    type_node = field.child_by_field_name('type')
+                    if name_node is not None and type_node is not None:
+                        yield owner_node, owner_name, prefix + self._get_identifier(name_node), type_node, field
+            else:
+                position = 0
+                for child in node.named_children:
+                    if child.type in ('visibility_modifier', 'attribute_item', 'line_comment', 'block_comment'):
+                        continue
+                    yield owner_node, owner_name, f"{prefix}{position}", child, child
+                    position += 1
+
+    def _split_type_arguments(self, text: str, separator: str = ','):
+        """Splits `A, B<C, D>, (E, F)` on its top-level commas (or another separator, e.g. `+` in bounds)."""
+        return split_type_arguments(text, separator)
+
+    def _get_constraints(self, node):
+        """
+        Collects the trait and lifetime bounds of an item's generic parameters, whether written
+        inline (`<T: Display>`) or in a where clause, as `{param, bound, bound_base, source}`
+        records with one record per bound.
+        """
+        sources = []
+        type_params = node.child_by_field_name('type_parameters')
+        if type_params is not None:
+            sources.append(('inline', self._get_node_text(type_params).strip()[1:-1]))
+        for child in node.children:
+            if child.type == 'where_clause':
+                sources.append(('where', re.sub(r'^where\b', '', self._get_node_text(child)).strip()))
+
+        constraints = []
+        for source, text in sources:
+            for predicate in self._split_type_arguments(text):
+                # `const N: usize` is a const generic, not a bound.
+                if predicate.startswith('const '):
+                    continue
+                param, colon, bounds = predicate.partition(':')
+                param = param.strip()
+                if not colon:
+                    continue
+                # Drop a default (`T: Default = i32`) without touching `Add<Output = T>`.
+                bounds = (self._split_type_arguments(bounds, '=') or [''])[0]
+                for bound in self._split_type_arguments(bounds, '+'):
+                    bound = re.sub(r'\s+', ' ', bound).strip()
+                    if not bound:
+                        continue
+                    base = re.sub(r"^for\s*<[^>]*>\s*", '', bound)
+                    constraints.append({
+                        "param": param,
+                        "bound": bound,
+                        # Lifetimes and `?Sized` relaxations are kept verbatim.
+                        "bound_base": base if base.startswith(("'", '?')) else self._strip_generics(base.split('(')[0]),
+                        "source": source,
+                    })
+        return constraints
+
+    def _constraint_properties(self, node):
+        """Flattens `_get_constraints` into list properties that can be stored on a graph node."""
+        constraints = self._get_constraints(node)
+        return {
+            "constraints": [f"{c['param']}: {c['bound']}" for c in constraints],
+            "bound_traits": sorted({c['bound_base'] for c in constraints if not c['bound_base'].startswith(("'", '?'))}),
+        }
+
+    def _type_tree(self, text: str):
+        """Parses a type into a `(base_name, type_arguments, text)` tree, see `type_tree`."""
+        return type_tree(text)
+
+    def _wrapper_chains(self, tree, chain=()):
+        """Yields `(wrappers, inner_tree)` for every run of nested smart pointers in a type tree."""
+        base, args, _ = tree
+        if base in SMART_POINTER_TYPES:
+            chain = chain + (base,)
+            if not args:
+                yield list(chain), None
+            for arg in args:
+                yield from self._wrapper_chains(arg, chain)
+            return
+        if chain:
+            yield list(chain), tree
+        for arg in args:
+            yield from self._wrapper_chains(arg)
+
+    def _find_pointer_wrappings(self, root_node):
+        """Finds fields whose type stores a value behind `Box`, `Rc`, `Arc`, `RefCell` and friends."""
+        wrappings = []
+        for owner_node, owner, field, type_node, decl_node in self._iter_fields(root_node):
+            type_text = self._get_node_text(type_node)
+            for wrappers, inner in self._wrapper_chains(self._type_tree(type_text)):
+                wrappings.append({
+                    "owner": owner,
+                    "field": field,
+                    "type": type_text,
+                    "wrappers": wrappers,
+                    "inner_type": inner[2] if inner else None,
+                    "inner_base": inner[0] if inner else None,
+                    "shared_ownership": any(w in SHARED_OWNERSHIP_TYPES for w in wrappers),
+                    "interior_mutability": any(w in INTERIOR_MUTABILITY_TYPES for w in wrappers),
+                    "line_number": decl_node.start_point[0] + 1,
+                })
+        return wrappings
+
+    def _find_fields(self, root_node):
+        fields = []
+        for owner_node, owner, field, type_node, decl_node in self._iter_fields(root_node):
+            if owner_node.type not in ('struct_item', 'union_item'):
+                continue
+            fields.append({
+                "name": field,
+                "owner": owner,
+                "type": self._get_node_text(type_node),
+                "visibility": self._get_visibility(decl_node),
+                "line_number": decl_node.start_point[0] + 1,
+                **node_span(decl_node, decl_node.child_by_field_name('name')),
+                "docstring": self._get_docstring(decl_node),
+                "lang": self.language_name,
+            })
+        return fields
+
+    def _find_newtypes(self, root_node):
+        """
+        Finds newtypes (single-field tuple structs such as `Meters(f64)`) and generic wrappers
+        (single-field structs whose field is a type parameter, such as `Wrapper<T> { value: T }`).
+        """
+        fields_by_owner = {}
+        for owner_node, owner, field, type_node, decl_node in self._iter_fields(root_node):
+            if owner_node.type == 'struct_item':
+                fields_by_owner.setdefault((owner, owner_node.start_byte), (owner_node, []))[1].append((field, type_node))
+
+        newtypes = []
+        for (owner, _), (owner_node, owner_fields) in fields_by_owner.items():
+            if len(owner_fields) != 1:
+                continue
+            field, type_node = owner_fields[0]
+            type_params_node = owner_node.child_by_field_name('type_parameters')
+            type_params = set()
+            if type_params_node is not None:
+                for part in self._split_type_arguments(self._get_node_text(type_params_node)[1:-1]):
+                    part = part.strip()
+                    if part.startswith('const '):
+                        part = part[len('const '):]
+                    match = re.match(r"[A-Za-z_]\w*", part)
+                    if match:
+                        type_params.add(match.group(0))
+
+            inner_type = self._get_node_text(type_node)
+            inner_base = self._type_tree(inner_type)[0]
+            is_generic = inner_base in type_params and inner_type.strip() == inner_base
+            is_tuple = field == '0'
+            if not is_tuple and not is_generic:
+                continue
+            newtypes.append({
+                "name": owner,
+                "field": field,
+                "kind": 'wrapper' if is_generic else 'newtype',
+                "inner_type": inner_type,
+                "inner_base": None if is_generic else inner_base,
+                "line_number": owner_node.child_by_field_name('name').start_point[0] + 1,
+            })
+        return newtypes
+
+    def _field_access_mode(self, node) -> str:
+        """Classifies a `self.<field>` expression as a 'write' (assignment, `&mut`, mutating method) or a 'read'."""
+        curr = node
+        while curr.parent is not None and curr.parent.type in ('field_expression', 'index_expression'):
+            # Only climb while `curr` is the receiver (`curr.x`, `curr[i]`), not the index.
+            if curr.parent.named_children[0].start_byte != curr.start_byte:
+                break
+            curr = curr.parent
+
+        parent = curr.parent
+        if parent is None:
+            return 'read'
+        if parent.type in ('assignment_expression', 'compound_assignment_expr'):
+            left = parent.child_by_field_name('left')
+            if left is not None and left.start_byte == curr.start_byte and left.end_byte == curr.end_byte:
+                return 'write'
+        if parent.type == 'reference_expression' and any(c.type == 'mutable_specifier' for c in parent.children):
+            return 'write'
+        if parent.type == 'call_expression' and curr.type == 'field_expression' and curr.end_byte != node.end_byte:
+            method = self._get_identifier(curr.child_by_field_name('field'))
+            if method in MUTATING_METHODS:
+                return 'write'
+        return 'read'
+
+    def _find_field_accesses(self, root_node):
+        """Finds every `self.<field>` access inside impl methods, with the method and access mode."""
+        accesses = []
+        for node in self._walk(root_node):
+            if node.type != 'field_expression':
+                continue
+            value_node = node.child_by_field_name('value')
+            field_node = node.child_by_field_name('field')
+            if value_node is None or value_node.type != 'self' or field_node is None:
+                continue
+            # `self.method(...)` is a call, not a field access.
+            if node.parent and node.parent.type == 'call_expression' and \
+                    node.parent.child_by_field_name('function').start_byte == node.start_byte:
+                continue
+
+            owner = self._get_parent_context(node, types=('impl_item',))[0]
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            if owner is None or func_name is None:
+                continue
+            accesses.append({
+                "owner": owner,
+                "field": self._get_identifier(field_node),
+                "mode": self._field_access_mode(node),
+                "function": func_name,
+                "function_line": func_line,
+                "line_number": node.start_point[0] + 1,
+            })
+        return accesses
+
+    def _find_enum_variants(self, root_node):
+        variants = []
+        for node in self._walk(root_node):
+            if node.type != 'enum_variant':
+                continue
+            enum_node = node.parent.parent if node.parent else None
+            if enum_node is None or enum_node.type != 'enum_item':
+                continue
+            enum_name = self._get_identifier(enum_node.child_by_field_name('name'))
+            name = self._get_identifier(node.child_by_field_name('name'))
+
+            body = node.child_by_field_name('body')
+            kind = {'field_declaration_list': 'struct', 'ordered_field_declaration_list': 'tuple'}.get(
+                body.type if body else None, 'unit')
+            value = node.child_by_field_name('value')
+
+            variants.append({
+                "name": name,
+                "enum": enum_name,
+                "qualified_name": f"{enum_name}::{name}",
+                "line_number": node.start_point[0] + 1,
+                **node_span(node, node.child_by_field_name('name')),
+                "kind": kind,
+                "fields": self._get_node_text(body) if body else None,
+                "discriminant": self._get_node_text(value) if value else None,
+                "docstring": self._get_docstring(node),
+                "lang": self.language_name,
+            })
+        return variants
+
+    def _is_pattern(self, node) -> bool:
+        """Checks whether `node` sits in a pattern (match arm, `if let`, `let`, parameter) rather than an expression."""
+        curr = node
+        while curr.parent is not None:
+            parent = curr.parent
+            if curr.type in PATTERN_TYPES:
+                return True
+            if parent.type in PATTERN_PARENTS:
+                pattern = parent.child_by_field_name('pattern')
+                return pattern is not None and pattern.start_byte == curr.start_byte and pattern.end_byte == curr.end_byte
+            if curr.type.endswith('_expression') or curr.type in ('block', 'match_arm'):
+                return False
+            curr = parent
+        return False
+
+    def _find_variant_usages(self, root_node):
+        """Finds `Enum::Variant` paths in functions and classifies them as constructions or matches."""
+        usages = []
+        for node in self._walk(root_node):
+            if node.type not in ('scoped_identifier', 'scoped_type_identifier'):
+                continue
+            if node.parent and node.parent.type in ('use_declaration', 'scoped_use_list', 'use_as_clause', 'use_list'):
+                continue
+            path_node = node.child_by_field_name('path')
+            name_node = node.child_by_field_name('name')
+            if path_node is None or name_node is None:
+                continue
+            enum_name = self._strip_generics(self._get_node_text(path_node))
+            variant = self._get_identifier(name_node)
+            # Variants are CamelCase; SCREAMING_CASE names are associated constants.
+            if not (enum_name[:1].isupper() and variant[:1].isupper()) or (len(variant) > 1 and variant.isupper()):
+                continue
+            if enum_name == 'Self':
+                enum_name = self._get_parent_context(node, types=('impl_item', 'trait_item'))[0]
+
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            if enum_name is None or func_name is None:
+                continue
+
+            usages.append({
+                "enum": enum_name,
+                "variant": variant,
+                "usage": 'match' if self._is_pattern(node) else 'construct',
+                "function": func_name,
+                "function_line": func_line,
+                "line_number": node.start_point[0] + 1,
+            })
+        return usages
+
+    def _find_type_references(self, root_node):
+        """
+        Finds where named types are mentioned: parameters, return types, fields, `let` and const
+        annotations, struct literals and other expressions (turbofish, casts). Mentions inside type
+        arguments keep the generic they instantiate (`Vec` for `Vec<Person>`) in `generic_in`.
+        Impl headers, bounds and `use` paths are left to their own relationships.
+        """
+        references, seen = [], set()
+        for node in self._walk(root_node):
+            if node.type != 'type_identifier':
+                continue
+            role = self._type_reference_role(node)
+            if role is None:
+                continue
+            role, type_node, generic_in = role
+            type_name = self._get_identifier(node)
+            if type_name == 'Self':
+                # `Self { .. }` constructs the impl type; other `Self` mentions are the impl's own business.
+                if role != 'construction':
+                    continue
+                type_name = self._get_parent_context(node, types=('impl_item',))[0]
+                if type_name is None:
+                    continue
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            owner = None if func_name else self._get_parent_context(node, types=('struct_item', 'enum_item', 'union_item', 'trait_item'))[0]
+            key = (type_name, role, node.start_point[0], func_name, func_line)
+            if key in seen:
+                continue
+            seen.add(key)
+            references.append({
+                "type": type_name,
+                "role": role,
+                "type_text": self._get_node_text(type_node),
+                "generic_in": generic_in,
+                "function": func_name,
+                "function_line": func_line,
+                "owner": owner,
+                "line_number": node.start_point[0] + 1,
+            })
+        return references
+
+    def _type_reference_role(self, node):
+        """Returns `(role, type_node, generic_in)` for a type mention, where `type_node` is the whole type written at that position."""
+        generic_in, child, curr = None, node, node.parent
+        while curr is not None:
+            if curr.type == 'type_arguments' and generic_in is None and curr.parent is not None and curr.parent.type == 'generic_type':
+                generic_in = self._strip_generics(self._get_node_text(curr.parent))
+            elif curr.type in ('parameter', 'closure_parameters'):
+                return 'parameter', child, generic_in
+            elif curr.type in ('function_item', 'function_signature_item', 'closure_expression'):
+                return ('return', child, generic_in) if child == curr.child_by_field_name('return_type') else None
+            elif curr.type in ('field_declaration', 'ordered_field_declaration_list'):
+                return 'field', child, generic_in
+            elif curr.type in ('let_declaration', 'const_item', 'static_item'):
+                return 'variable', child, generic_in
+            elif curr.type == 'struct_expression':
+                return 'construction', child, generic_in
+            elif curr.type.endswith('_expression'):
+                return 'expression', child, generic_in
+            elif curr.type in ('impl_item', 'type_parameters', 'where_clause', 'trait_bounds', 'use_declaration',
+                               'type_item', 'macro_invocation', 'attribute_item', 'struct_pattern', 'tuple_struct_pattern'):
+                return None
+            child, curr = curr, curr.parent
+        return None
+
+    def _find_instantiation_sites(self, root_node):
+        """
+        Finds where generic items may be instantiated with concrete types: written type arguments
+        (`Point<f64>`, `largest::<i32>(..)`), and calls and struct literals whose argument types are
+        known (`Point::new(1, 2)`, `Point { x: 1u8, y: 2 }`). The type arguments of the latter are
+        bound later, against the declaration of the called item.
+        """
+        sites = []
+        for node in self._walk(root_node):
+            site = None
+            if node.type in ('generic_type', 'generic_function'):
+                item_node = node.child_by_field_name('type' if node.type == 'generic_type' else 'function')
+                args_node = node.child_by_field_name('type_arguments')
+                if item_node is None or args_node is None:
+                    continue
+                type_arguments = [re.sub(r"'\w+\s*", '', arg).strip() for arg in
+                                  self._split_type_arguments(self._get_node_text(args_node).strip()[1:-1]) if not arg.startswith("'")]
+                scope = self._type_parameters_in_scope(node)
+                if not type_arguments or any(set(re.findall(r'[A-Za-z_]\w*', arg)) & scope for arg in type_arguments):
+                    continue
+                site = {"kind": 'explicit', "item": self._strip_generics(self._get_node_text(item_node)),
+                        "method": None, "type_arguments": type_arguments}
+            elif node.type == 'call_expression':
+                function_node = node.child_by_field_name('function')
+                arguments = node.child_by_field_name('arguments')
+                if function_node is None or arguments is None or function_node.type not in ('identifier', 'scoped_identifier'):
+                    continue
+                name = self._get_identifier(function_node.child_by_field_name('name') if function_node.type == 'scoped_identifier' else function_node)
+                path_node = function_node.child_by_field_name('path') if function_node.type == 'scoped_identifier' else None
+                owner = self._strip_generics(self._get_node_text(path_node)) if path_node is not None else None
+                if owner == 'Self':
+                    continue
+                arg_types = [self._value_type(arg) for arg in arguments.named_children if arg.type != 'attribute_item']
+                if not any(arg_types):
+                    continue
+                is_associated = bool(owner) and owner[:1].isupper()
+                site = {"kind": 'call', "item": owner if is_associated else name,
+                        "method": name if is_associated else None, "arg_types": arg_types}
+            elif node.type == 'struct_expression':
+                name_node = node.child_by_field_name('name')
+                body = node.child_by_field_name('body')
+                if name_node is None or body is None or self._get_node_text(name_node) == 'Self':
+                    continue
+                field_types = {}
+                for initializer in body.named_children:
+                    if initializer.type == 'field_initializer' and initializer.child_by_field_name('field') is not None:
+                        value_type = self._value_type(initializer.child_by_field_name('value'))
+                        field_name = self._get_identifier(initializer.child_by_field_name('field'))
+                    elif initializer.type == 'shorthand_field_initializer':
+                        value_type = self._value_type(initializer.named_children[0]) if initializer.named_children else None
+                        field_name = self._get_node_text(initializer)
+                    else:
+                        continue
+                    if value_type:
+                        field_types[field_name] = value_type
+                if not field_types:
+                    continue
+                site = {"kind": 'literal', "item": self._strip_generics(self._get_node_text(name_node)),
+                        "method": None, "field_types": field_types}
+            if site is None:
+                continue
+            function, _, function_line = self._get_parent_context(node, types=('function_item',))
+            sites.append({**site, "function": function, "function_line": function_line,
+                          "line_number": node.start_point[0] + 1})
+        return sites
+
+    def _type_parameters_in_scope(self, node):
+        """Returns the type parameters declared by the items enclosing `node`, plus `Self`."""
+        names = {'Self'}
+        curr = node.parent
+        while curr is not None:
+            if curr.type in GENERIC_SCOPE_TYPES:
+                names.update(self._type_parameter_names(curr.child_by_field_name('type_parameters')))
+            curr = curr.parent
+        return names
+
+    def _value_type(self, node) -> Optional[str]:
+        """Returns the type of a value: literals (honouring suffixes such as `1u8`), references and negations of them, or the inferred type."""
+        if node is None:
+            return None
+        if node.type == 'integer_literal':
+            suffix = re.search(r'[iu](8|16|32|64|128|size)$', self._get_node_text(node))
+            return suffix.group(0) if suffix else 'i32'
+        if node.type == 'float_literal':
+            suffix = re.search(r'f(32|64)$', self._get_node_text(node))
+            return suffix.group(0) if suffix else 'f64'
+        if node.type in LITERAL_TYPES:
+            return LITERAL_TYPES[node.type]
+        if node.type == 'unary_expression' and self._get_node_text(node).startswith('-') and node.named_children:
+            return self._value_type(node.named_children[-1])
+        if node.type == 'reference_expression' and node.named_children:
+            inner = self._value_type(node.named_children[-1])
+            return f"&{inner}" if inner else None
+        return self._infer_expression_type(node)
+
+    def _generic_argument(self, type_text: str, wrapper: str) -> Optional[str]:
+        """Returns the type argument of `wrapper` in `type_text`, e.g. `i32` for `Arc<Mutex<i32>>` and `Mutex`."""
+        start = type_text.find(f"{wrapper}<")
+        if start == -1:
+            return None
+        start += len(wrapper) + 1
+        depth = 1
+        for i in range(start, len(type_text)):
+            if type_text[i] == '<':
+                depth += 1
+            elif type_text[i] == '>':
+                depth -= 1
+                if depth == 0:
+                    return type_text[start:i].strip()
+        return None
+
+    def _find_shared_state(self, root_node):
+        """
+        Finds struct fields and statics whose type involves a `Mutex`, `RwLock` or atomic,
+        along with every function that locks or atomically accesses them through
+        `self.<field>` or the static's name.
+        """
+        shared_state = []
+        by_key = {}  # (owner, field) for fields, (None, name) for statics
+
+        candidates = [
+            (owner, 'field', field, type_node, decl_node)
+            for owner_node, owner, field, type_node, decl_node in self._iter_fields(root_node)
+            if owner_node.type == 'struct_item'
+        ]
+        for node in self._walk(root_node):
+            if node.type == 'static_item' and node.child_by_field_name('name') and node.child_by_field_name('type'):
+                name = self._get_identifier(node.child_by_field_name('name'))
+                candidates.append((None, 'static', name, node.child_by_field_name('type'), node))
+
+        for owner, owner_kind, field, type_node, node in candidates:
+            type_text = self._get_node_text(type_node)
+            match = LOCK_TYPE_PATTERN.search(type_text)
+            if not match:
+                continue
+
+            kind = match.group(1)
+            by_key[(owner, field)] = len(shared_state)
+            shared_state.append({
+                "name": f"{owner}.{field}" if owner else field,
+                "owner": owner,
+                "owner_kind": owner_kind,
+                "field": field,
+                "kind": kind,
+                "type": type_text,
+                "guarded_type": self._generic_argument(type_text, kind),
+                "line_number": node.start_point[0] + 1,
+                "accesses": [],
+                "lang": self.language_name,
+            })
+
+        if not shared_state:
+            return shared_state
+
+        for node in self._walk(root_node):
+            if node.type != 'call_expression':
+                continue
+            function_node = node.child_by_field_name('function')
+            if function_node is None or function_node.type != 'field_expression':
+                continue
+            method = self._get_identifier(function_node.child_by_field_name('field'))
+            if method in LOCK_METHODS:
+                mode = LOCK_METHODS[method]
+            elif method in ATOMIC_METHODS:
+                mode = 'atomic'
+            else:
+                continue
+
+            target = function_node.child_by_field_name('value')
+            key = None
+            if target.type == 'field_expression':
+                base = target.child_by_field_name('value')
+                if base is not None and base.type == 'self':
+                    owner = self._get_parent_context(node, types=('impl_item',))[0]
+                    key = (owner, self._get_identifier(target.child_by_field_name('field')))
+            elif target.type == 'identifier':
+                key = (None, self._get_identifier(target))
+            if key not in by_key:
+                continue
+
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            if func_name is None:
+                continue
+            shared_state[by_key[key]]["accesses"].append({
+                "function": func_name,
+                "function_line": func_line,
+                "line_number": node.start_point[0] + 1,
+                "mode": mode,
+            })
+
+        return shared_state
+
+    def _find_interior_mutability(self, root_node):
+        """
+        Finds struct fields and statics holding a `Cell`, `RefCell`, `UnsafeCell`, `OnceCell` or
+        atomic, with every function that borrows or mutates them through `self.<field>`,
+        `<value>.<field>` (when the field name is unambiguous in the file) or the static's name.
+        """
+        cells = []
+        by_key = {}  # (owner, field) for fields, (None, name) for statics
+
+        candidates = [
+            (owner, 'field', field, type_node, decl_node)
+            for owner_node, owner, field, type_node, decl_node in self._iter_fields(root_node)
+            if owner_node.type in ('struct_item', 'union_item')
+        ]
+        for node in self._walk(root_node):
+            if node.type == 'static_item' and node.child_by_field_name('name') and node.child_by_field_name('type'):
+                name = self._get_identifier(node.child_by_field_name('name'))
+                candidates.append((None, 'static', name, node.child_by_field_name('type'), node))
+
+        for owner, owner_kind, field, type_node, node in candidates:
+            type_text = self._get_node_text(type_node)
+            match = INTERIOR_MUTABILITY_PATTERN.search(type_text)
+            if not match:
+                continue
+            by_key[(owner, field)] = len(cells)
+            cells.append({
+                "name": f"{owner}.{field}" if owner else field,
+                "owner": owner,
+                "owner_kind": owner_kind,
+                "field": field,
+                "kind": 'Atomic' if match.group(1).startswith('Atomic') else match.group(1),
+                "type": type_text,
+                "line_number": node.start_point[0] + 1,
+                "accesses": [],
+            })
+
+        if not cells:
+            return cells
+
+        owners_by_field = {}
+        for owner, field in by_key:
+            if owner is not None:
+                owners_by_field.setdefault(field, []).append(owner)
+
+        for node in self._walk(root_node):
+            if node.type != 'call_expression':
+                continue
+            function_node = node.child_by_field_name('function')
+            if function_node is None or function_node.type != 'field_expression':
+                continue
+            target = function_node.child_by_field_name('value')
+            key = None
+            if target.type == 'field_expression':
+                base = target.child_by_field_name('value')
+                field = self._get_identifier(target.child_by_field_name('field'))
+                if base is not None and base.type == 'self':
+                    key = (self._get_parent_context(node, types=('impl_item',))[0], field)
+                elif len(owners_by_field.get(field, [])) == 1:
+                    key = (owners_by_field[field][0], field)
+            elif target.type == 'identifier':
+                key = (None, self._get_identifier(target))
+            if key not in by_key:
+                continue
+
+            cell = cells[by_key[key]]
+            method = self._get_identifier(function_node.child_by_field_name('field'))
+            if cell['kind'] == 'Atomic':
+                if method not in ATOMIC_METHODS:
+                    continue
+                mode = 'read' if method == 'load' else 'write'
+            elif cell['kind'] == 'UnsafeCell' and method == 'get':
+                mode = 'raw'
+            elif method in CELL_METHODS:
+                mode = CELL_METHODS[method]
+            else:
+                continue
+
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            if func_name is None:
+                continue
+            cell["accesses"].append({
+                "function": func_name,
+                "function_line": func_line,
+                "method": method,
+                "mode": mode,
+                "mutable": mode in ('borrow_mut', 'write', 'raw'),
+                "line_number": node.start_point[0] + 1,
+            })
+
+        return cells
+
+    def _is_async_scope(self, node) -> bool:
+        """Checks whether a function, closure or block runs asynchronously (`async fn`, `async move { .. }`, `async ||`)."""
+        if node.type == 'async_block':
+            return True
+        if node.type == 'function_item':
+            modifiers = next((child for child in node.children if child.type == 'function_modifiers'), None)
+            return modifiers is not None and 'async' in self._get_node_text(modifiers).split()
+        return node.type == 'closure_expression' and any(child.type == 'async' for child in node.children)
+
+    def _lock_name(self, receiver, shared_state_names) -> Optional[str]:
+        """Resolves the receiver of a `.lock()`/`.read()`/`.write()` to a Lock name (`Owner.field` or a static)."""
+        if receiver.type == 'identifier':
+            name = self._get_identifier(receiver)
+            return name if name in shared_state_names else None
+        if receiver.type != 'field_expression':
+            return None
+        base = receiver.child_by_field_name('value')
+        if base.type == 'self':
+            owner = self._get_parent_context(receiver, types=('impl_item',))[0]
+        else:
+            owner = self._strip_generics(self._receiver_type(self._infer_expression_type(base)) or '')
+        name = f"{owner}.{self._get_identifier(receiver.child_by_field_name('field'))}"
+        return name if name in shared_state_names else None
+
+    def _same_node(self, a, b) -> bool:
+        return a is not None and b is not None and (a.start_byte, a.end_byte) == (b.start_byte, b.end_byte)
+
+    def _iter_lock_guards(self, root_node):
+        """
+        Yields `(lock_call, scope, guard, region_end)` for every `.lock()`/`.read()`/`.write()` call:
+        the enclosing function, closure or async block, the `let` binding holding the guard (None
+        for temporaries) and the byte offset where the guard is dropped. A bound guard lives until
+        the end of its block or an explicit `drop(guard)`; a temporary until the end of its statement.
+        """
+        for node in self._walk(root_node):
+            if node.type != 'call_expression':
+                continue
+            function_node = node.child_by_field_name('function')
+            if function_node is None or function_node.type != 'field_expression':
+                continue
+            if self._get_identifier(function_node.child_by_field_name('field')) not in LOCK_METHODS:
+                continue
+            scope = node.parent
+            while scope is not None and scope.type not in ('function_item', 'closure_expression', 'async_block'):
+                scope = scope.parent
+            if scope is None:
+                continue
+
+            # Follow `.await`, `.unwrap()`, `.expect(..)` and `?` to the expression that yields the guard.
+            guard_expr = node
+            while guard_expr.parent is not None:
+                parent = guard_expr.parent
+                if parent.type in ('try_expression', 'await_expression'):
+                    guard_expr = parent
+                elif (parent.type == 'field_expression' and self._same_node(parent.child_by_field_name('value'), guard_expr)
+                      and self._get_identifier(parent.child_by_field_name('field')) in UNWRAP_METHODS
+                      and parent.parent is not None and parent.parent.type == 'call_expression'):
+                    guard_expr = parent.parent
+                else:
+                    break
+
+            statement = guard_expr
+            while statement.parent is not None and statement.parent.type != 'block' and not self._same_node(statement, scope):
+                statement = statement.parent
+            guard = None
+            region_end = statement.end_byte
+            if statement.type == 'let_declaration' and self._same_node(statement.child_by_field_name('value'), guard_expr):
+                pattern = statement.child_by_field_name('pattern')
+                if pattern is not None and pattern.type == 'mut_pattern':
+                    pattern = pattern.named_children[-1] if pattern.named_children else None
+                if pattern is not None and pattern.type == 'identifier':
+                    guard = self._get_identifier(pattern)
+                    block = statement.parent
+                    region_end = block.end_byte if block is not None else statement.end_byte
+                    for call in self._walk(block) if block is not None else []:
+                        if (call.type == 'call_expression' and call.start_byte > statement.end_byte
+                                and self._get_node_text(call.child_by_field_name('function')) in ('drop', 'std::mem::drop', 'mem::drop')
+                                and [self._get_node_text(a) for a in call.child_by_field_name('arguments').named_children] == [guard]):
+                            region_end = min(region_end, call.start_byte)
+            yield node, scope, guard, region_end
+
+    def _find_await_holding_locks(self, root_node, shared_state):
+        """
+        Finds synchronous lock guards (`Mutex::lock`, `RwLock::read/write`) that are still alive
+        when an async function or block reaches an `.await`. Locks that are themselves awaited
+        (async mutexes) are not reported.
+        """
+        findings = []
+        shared_state_names = {state['name'] for state in shared_state}
+        for node, scope, guard, region_end in self._iter_lock_guards(root_node):
+            if not self._is_async_scope(scope) or node.parent.type == 'await_expression':
+                continue
+
+            await_node = None
+            for candidate in self._walk(scope):
+                if candidate.type != 'await_expression' or not node.end_byte <= candidate.start_byte < region_end:
+                    continue
+                owner = candidate.parent
+                while owner is not None and owner.type not in ('function_item', 'closure_expression', 'async_block'):
+                    owner = owner.parent
+                if self._same_node(owner, scope):
+                    await_node = candidate
+                    break
+            if await_node is None:
+                continue
+
+            receiver = node.child_by_field_name('function').child_by_field_name('value')
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            findings.append({
+                "function": func_name,
+                "function_line": func_line,
+                "lock": re.sub(r'\s+', '', self._get_node_text(receiver)),
+                "lock_name": self._lock_name(receiver, shared_state_names),
+                "guard": guard,
+                "lock_line": node.start_point[0] + 1,
+                "await_line": await_node.start_point[0] + 1,
+                "await_span": f"{node.start_point[0] + 1}-{await_node.end_point[0] + 1}",
+                "await_expression": self._get_node_text(await_node),
+            })
+        return findings
+
+    def _find_blocking_calls(self, root_node):
+        """
+        Finds calls that block the current thread (sleeps, std IO and networking, std mutex locks),
+        noting whether they run directly in async code. Calls in sync functions are kept too, as
+        they block when those functions are called from async code.
+        """
+        blocking_calls = []
+        for node in self._walk(root_node):
+            if node.type != 'call_expression' or node.parent.type == 'await_expression':
+                continue
+            function_node = node.child_by_field_name('function')
+            if function_node is None:
+                continue
+            if function_node.type == 'generic_function':
+                function_node = function_node.child_by_field_name('function')
+
+            category, call_name = None, None
+            if function_node.type == 'field_expression':
+                method = self._get_identifier(function_node.child_by_field_name('field'))
+                category, call_name = BLOCKING_METHODS.get(method), f".{method}()"
+            elif function_node.type in ('identifier', 'scoped_identifier'):
+                path = re.sub(r'\s+', '', self._get_identifier(function_node))
+                category = next((name for name, pattern in BLOCKING_CALL_PATTERNS if pattern.match(path)), None)
+                call_name = path
+            if category is None:
+                continue
+
+            scope = node.parent
+            while scope is not None and scope.type not in ('function_item', 'closure_expression', 'async_block'):
+                scope = scope.parent
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            if func_name is None:
+                continue
+            blocking_calls.append({
+                "call": call_name,
+                "category": category,
+                "function": func_name,
+                "function_line": func_line,
+                "in_async": scope is not None and self._is_async_scope(scope),
+                "line_number": node.start_point[0] + 1,
+            })
+        return blocking_calls
+
+    def _find_lock_regions(self, root_node, shared_state):
+        """
+        Finds the regions where a guard of a known Lock (field or static) is held, with the locks
+        acquired directly inside them (`nested`). The graph builder follows the calls made within a
+        region to find locks acquired further down the call graph.
+        """
+        shared_state_names = {state['name'] for state in shared_state}
+        acquisitions = []
+        for node, scope, guard, region_end in self._iter_lock_guards(root_node):
+            lock_name = self._lock_name(node.child_by_field_name('function').child_by_field_name('value'), shared_state_names)
+            func_name, _, func_line = self._get_parent_context(node, types=('function_item',))
+            if lock_name and func_name:
+                acquisitions.append((node, scope, guard, region_end, lock_name, func_name, func_line))
+
+        regions = []
+        for node, scope, guard, region_end, lock_name, func_name, func_line in acquisitions:
+            nested = [
+                {"lock": other[4], "line_number": other[0].start_point[0] + 1}
+                for other in acquisitions
+                if node.end_byte <= other[0].start_byte < region_end and self._same_node(other[1], scope)
+            ]
+            end_line = self._source.count(b'\n', 0, region_end) + 1
+            regions.append({
+                "lock": lock_name,
+                "function": func_name,
+                "function_line": func_line,
+                "guard": guard,
+                "line_number": node.start_point[0] + 1,
+                "end_line": end_line,
+                "nested": nested,
+            })
+        return regions
+
+def pre_scan_rust(files: list[Path], parser_wrapper) -> dict:
+    """Scans Rust files to create a map of type/function names to their file paths."""
+    imports_map = {}
+    query_str = """
+        (struct_item name: (type_identifier) @name)
+        (enum_item name: (type_identifier) @name)
+        (trait_item name: (type_identifier) @name)
+        (union_item name: (type_identifier) @name)
+        (function_item name: (identifier) @name)
+    """
+    query = parser_wrapper.language.query(query_str)
+
+    for file_path in files:
+        try:
+            source = read_source(file_path)
+            tree = parser_wrapper.parser.parse(source)
+            if tree.root_node.has_error:
+                tree = parser_wrapper.parser.parse(desugar_modern_syntax(source))
+
+            for capture, _ in query.captures(tree.root_node):
+                name = normalize_identifier(source[capture.start_byte:capture.end_byte].decode('utf-8'))
+                if name not in imports_map:
+                    imports_map[name] = []
+                imports_map[name].append(str(file_path.resolve()))
+        except Exception as e:
+            logger.warning(f"Tree-sitter pre-scan failed for {file_path}: {e}")
+    return imports_map
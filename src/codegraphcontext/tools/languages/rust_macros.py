@@ -0,0 +1,278 @@
+"""
+Best-effort expansion of simple `macro_rules!` macros, used by the Rust parser to index
+items that only exist inside macro invocations.
+
+Only declarative macros are supported. Matchers understand `$name:fragment` variables,
+literal tokens, delimited groups and `$( ... ) sep op` repetitions; fragments that span
+several tokens (`expr`, `ty`, `path`, ...) are matched by backtracking over their length.
+Anything else (procedural macros, `$crate`-relative helper macros, hygiene) is out of scope:
+when a macro cannot be expanded the invocation is simply skipped.
+"""
+import re
+from typing import Dict, Iterator, List, Optional, Tuple
+
+TOKEN_PATTERN = re.compile(r"""
+    (?P<skip>\s+|//[^\n]*|/\*.*?\*/)
+  | (?P<string>b?"(?:[^"\\]|\\.)*"|b?r(?P<hashes>\#*)".*?"(?P=hashes))
+  | (?P<char>b?'(?:[^'\\]|\\.)')
+  | (?P<lifetime>'[A-Za-z_]\w*)
+  | (?P<number>\d+(?:\.\d+)?(?:[eE][+-]?\d+)?\w*)
+  | (?P<ident>(?:r\#)?[A-Za-z_]\w*)
+  | (?P<punct>::|->|=>|==|!=|<=|>=|&&|\|\||\.\.=|\.\.\.|\.\.|\+=|-=|\*=|/=|%=|\^=|&=|\|=|<<=|>>=|<<|>>|[^\s\w])
+""", re.VERBOSE | re.DOTALL)
+
+OPEN_DELIMITERS = {'(': ')', '[': ']', '{': '}'}
+REPETITION_OPERATORS = {'*', '+', '?'}
+SINGLE_TOKEN_FRAGMENTS = {'ident', 'lifetime', 'tt', 'literal', 'block'}
+# Guards against pathological backtracking on large invocations.
+MAX_TOKENS = 4000
+MAX_STEPS = 200000
+
+
+class Group:
+    """A delimited token tree: `( ... )`, `[ ... ]` or `{ ... }`."""
+
+    def __init__(self, delimiter: str, tokens: list):
+        self.delimiter = delimiter
+        self.tokens = tokens
+
+    def __eq__(self, other):
+        return isinstance(other, Group) and other.delimiter == self.delimiter and other.tokens == self.tokens
+
+    def __repr__(self):
+        return f"Group({self.delimiter!r}, {self.tokens!r})"
+
+
+def tokenize(text: str) -> list:
+    """Splits source text into tokens, nesting delimited groups. Raises ValueError on unbalanced delimiters."""
+    stack: List[Tuple[Optional[str], list]] = [(None, [])]
+    for match in TOKEN_PATTERN.finditer(text):
+        kind = match.lastgroup
+        if kind == 'skip' or kind == 'hashes':
+            continue
+        token = match.group(0)
+        if token in OPEN_DELIMITERS:
+            stack.append((token, []))
+        elif token in (')', ']', '}'):
+            delimiter, tokens = stack.pop()
+            if delimiter is None or OPEN_DELIMITERS[delimiter] != token:
+                raise ValueError(f"Unbalanced delimiter {token!r}")
+            stack[-1][1].append(Group(delimiter, tokens))
+        else:
+            stack[-1][1].append(token)
+    if len(stack) != 1:
+        raise ValueError("Unclosed delimiter")
+    return stack[0][1]
+
+
+def render(tokens: list) -> str:
+    """Turns tokens back into source text, separated by spaces."""
+    parts = []
+    for token in tokens:
+        if isinstance(token, Group):
+            parts.append(f"{token.delimiter} {render(token.tokens)} {OPEN_DELIMITERS[token.delimiter]}")
+        else:
+            parts.append(token)
+    return " ".join(parts)
+
+
+def _is_ident(token) -> bool:
+    return isinstance(token, str) and re.fullmatch(r'(?:r#)?[A-Za-z_]\w*', token) is not None
+
+
+def _is_literal(token) -> bool:
+    return isinstance(token, str) and (token[:1] in '"\'' or token[:2] in ('b"', "b'", 'r"', 'r#')
+                                       or token[:1].isdigit() or token in ('true', 'false'))
+
+
+def parse_matcher(tokens: list) -> list:
+    """Compiles matcher tokens into `('var', name, fragment)`, `('rep', sub, sep, op)`, `('group', delim, sub)` and `('lit', token)` elements."""
+    pattern, i = [], 0
+    while i < len(tokens):
+        token = tokens[i]
+        if token == '$' and i + 1 < len(tokens):
+            following = tokens[i + 1]
+            if isinstance(following, Group) and following.delimiter == '(':
+                sub = parse_matcher(following.tokens)
+                sep, op = None, None
+                if i + 2 < len(tokens) and tokens[i + 2] in REPETITION_OPERATORS:
+                    op, i = tokens[i + 2], i + 3
+                elif i + 3 < len(tokens) and tokens[i + 3] in REPETITION_OPERATORS:
+                    sep, op, i = tokens[i + 2], tokens[i + 3], i + 4
+                else:
+                    raise ValueError("Repetition without an operator")
+                pattern.append(('rep', sub, sep, op))
+                continue
+            if _is_ident(following) and i + 3 < len(tokens) and tokens[i + 2] == ':':
+                pattern.append(('var', following, tokens[i + 3]))
+                i += 4
+                continue
+        if isinstance(token, Group):
+            pattern.append(('group', token.delimiter, parse_matcher(token.tokens)))
+        else:
+            pattern.append(('lit', token))
+        i += 1
+    return pattern
+
+
+def _pattern_vars(pattern: list) -> List[str]:
+    names = []
+    for element in pattern:
+        if element[0] == 'var':
+            names.append(element[1])
+        elif element[0] == 'rep':
+            names.extend(_pattern_vars(element[1]))
+        elif element[0] == 'group':
+            names.extend(_pattern_vars(element[2]))
+    return names
+
+
+class _Matcher:
+    def __init__(self):
+        self.steps = 0
+
+    def match_sequence(self, pattern: list, tokens: list, i: int, bindings: Dict) -> Iterator[Tuple[int, Dict]]:
+        self.steps += 1
+        if self.steps > MAX_STEPS:
+            return
+        if not pattern:
+            yield i, bindings
+            return
+        element, rest = pattern[0], pattern[1:]
+        kind = element[0]
+
+        if kind == 'lit':
+            if i < len(tokens) and tokens[i] == element[1]:
+                yield from self.match_sequence(rest, tokens, i + 1, bindings)
+        elif kind == 'group':
+            if i < len(tokens) and isinstance(tokens[i], Group) and tokens[i].delimiter == element[1]:
+                for end, inner in self.match_sequence(element[2], tokens[i].tokens, 0, bindings):
+                    if end == len(tokens[i].tokens):
+                        yield from self.match_sequence(rest, tokens, i + 1, inner)
+        elif kind == 'var':
+            for end in self._fragment_ends(element[2], tokens, i):
+                yield from self.match_sequence(rest, tokens, end, {**bindings, element[1]: tokens[i:end]})
+        elif kind == 'rep':
+            for end, matches in self._match_repetition(element, tokens, i, []):
+                merged = dict(bindings)
+                for name in _pattern_vars(element[1]):
+                    merged[name] = [match.get(name, []) for match in matches]
+                yield from self.match_sequence(rest, tokens, end, merged)
+
+    def _match_repetition(self, element, tokens, i, matches) -> Iterator[Tuple[int, list]]:
+        _, sub, sep, op = element
+        if not (op == '?' and len(matches) == 1):
+            start = i
+            if matches and sep is not None:
+                start = i + 1 if i < len(tokens) and tokens[i] == sep else None
+            if start is not None:
+                for end, match in self.match_sequence(sub, tokens, start, {}):
+                    if end > i:
+                        yield from self._match_repetition(element, tokens, end, matches + [match])
+        if op != '+' or matches:
+            yield i, matches
+
+    def _fragment_ends(self, fragment: str, tokens: list, i: int) -> Iterator[int]:
+        if fragment == 'vis':
+            if i < len(tokens) and tokens[i] == 'pub':
+                has_scope = i + 1 < len(tokens) and isinstance(tokens[i + 1], Group) and tokens[i + 1].delimiter == '('
+                yield i + 2 if has_scope else i + 1
+            yield i
+            return
+        if i >= len(tokens):
+            return
+        token = tokens[i]
+        if fragment in SINGLE_TOKEN_FRAGMENTS:
+            if (fragment == 'tt'
+                    or (fragment == 'ident' and _is_ident(token))
+                    or (fragment == 'lifetime' and isinstance(token, str) and token.startswith("'"))
+                    or (fragment == 'literal' and _is_literal(token))
+                    or (fragment == 'block' and isinstance(token, Group) and token.delimiter == '{')):
+                yield i + 1
+            return
+        # expr, ty, path, pat, pat_param, stmt, item, meta: shortest match first.
+        for end in range(i + 1, len(tokens) + 1):
+            if fragment in ('expr', 'ty', 'path', 'pat', 'pat_param') and tokens[end - 1] in (';', '=>'):
+                return
+            yield end
+
+
+def _transcribe(tokens: list, bindings: Dict) -> list:
+    output, i = [], 0
+    while i < len(tokens):
+        token = tokens[i]
+        if token == '$' and i + 1 < len(tokens):
+            following = tokens[i + 1]
+            if isinstance(following, Group) and following.delimiter == '(':
+                sep, op, skip = None, None, 2
+                if i + 2 < len(tokens) and tokens[i + 2] in REPETITION_OPERATORS:
+                    op, skip = tokens[i + 2], 3
+                elif i + 3 < len(tokens) and tokens[i + 3] in REPETITION_OPERATORS:
+                    sep, op, skip = tokens[i + 2], tokens[i + 3], 4
+                repeated = [name for name in _transcriber_vars(following.tokens) if isinstance(bindings.get(name), list)
+                            and bindings[name] and isinstance(bindings[name][0], list)]
+                count = min((len(bindings[name]) for name in repeated), default=0)
+                for k in range(count):
+                    if k and sep is not None:
+                        output.append(sep)
+                    inner = {**bindings, **{name: bindings[name][k] for name in repeated}}
+                    output.extend(_transcribe(following.tokens, inner))
+                i += skip
+                continue
+            if following == 'crate':
+                output.append('crate')
+                i += 2
+                continue
+            if _is_ident(following) and following in bindings:
+                output.extend(bindings[following])
+                i += 2
+                continue
+        if isinstance(token, Group):
+            output.append(Group(token.delimiter, _transcribe(token.tokens, bindings)))
+        else:
+            output.append(token)
+        i += 1
+    return output
+
+
+def _transcriber_vars(tokens: list) -> List[str]:
+    names = []
+    for i, token in enumerate(tokens):
+        if token == '$' and i + 1 < len(tokens) and _is_ident(tokens[i + 1]):
+            names.append(tokens[i + 1])
+        elif isinstance(token, Group):
+            names.extend(_transcriber_vars(token.tokens))
+    return names
+
+
+def parse_macro_rules(body_text: str) -> List[Tuple[list, list]]:
+    """Parses the `{ (matcher) => { transcriber }; ... }` body of a `macro_rules!` into `(matcher, transcriber)` pairs."""
+    tokens = tokenize(body_text)
+    if len(tokens) == 1 and isinstance(tokens[0], Group):
+        tokens = tokens[0].tokens
+    rules, i = [], 0
+    while i + 2 < len(tokens):
+        matcher, arrow, transcriber = tokens[i], tokens[i + 1], tokens[i + 2]
+        if not (isinstance(matcher, Group) and arrow == '=>' and isinstance(transcriber, Group)):
+            raise ValueError("Unsupported macro_rules! syntax")
+        rules.append((parse_matcher(matcher.tokens), transcriber.tokens))
+        i += 3
+        if i < len(tokens) and tokens[i] == ';':
+            i += 1
+    return rules
+
+
+def expand(rules: List[Tuple[list, list]], arguments_text: str) -> Optional[str]:
+    """Expands an invocation whose arguments (without the outer delimiters) are `arguments_text`, or returns None."""
+    try:
+        tokens = tokenize(arguments_text)
+    except ValueError:
+        return None
+    if len(tokens) > MAX_TOKENS:
+        return None
+    matcher = _Matcher()
+    for pattern, transcriber in rules:
+        for end, bindings in matcher.match_sequence(pattern, tokens, 0, {}):
+            if end == len(tokens):
+                return render(_transcribe(transcriber, bindings))
+    return None
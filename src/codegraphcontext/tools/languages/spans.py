@@ -0,0 +1,19 @@
+# src/codegraphcontext/tools/languages/spans.py
+from typing import Dict
+
+
+def node_span(node, name_node=None) -> Dict[str, int]:
+    """
+    The extent of a parsed item, for exact snippets and editor jumps: 1-based `start_line` and
+    `end_line`, 0-based byte `start_column` and `end_column`, `start_byte`/`end_byte` offsets into
+    the file and the `column` of the item's name (on its `line_number`).
+    """
+    return {
+        "start_line": node.start_point[0] + 1,
+        "start_column": node.start_point[1],
+        "end_line": node.end_point[0] + 1,
+        "end_column": node.end_point[1],
+        "start_byte": node.start_byte,
+        "end_byte": node.end_byte,
+        "column": (name_node or node).start_point[1],
+    }
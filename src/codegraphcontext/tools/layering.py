@@ -0,0 +1,72 @@
+# src/codegraphcontext/tools/layering.py
+"""
+Architecture layering rules, declared per repository in `.cgc-layers.json`:
+
+    {
+      "rules": [
+        {"name": "domain is pure", "from": "domain", "forbid": ["io", "net::*"]},
+        {"from": "ui", "allow": ["domain", "ui_kit"]}
+      ]
+    }
+
+Modules are Rust module paths (`crate::` may be left out) or, for other languages, file paths
+relative to the repository; patterns are globs and also cover submodules. A `forbid` rule is
+broken by any dependency of a `from` module on a forbidden one. An `allow` rule only lets `from`
+modules depend on the listed modules (and on each other).
+"""
+import json
+from fnmatch import fnmatchcase
+from pathlib import Path
+from typing import Any, Dict, List, Optional
+
+LAYERS_CONFIG_FILE = ".cgc-layers.json"
+
+
+def load_rules(repo_path: Path) -> Optional[List[Dict[str, Any]]]:
+    """Reads and validates the rules of `repo_path`, or returns None when it has no config file."""
+    config_path = Path(repo_path) / LAYERS_CONFIG_FILE
+    if not config_path.is_file():
+        return None
+    try:
+        config = json.loads(config_path.read_text(encoding="utf-8"))
+    except json.JSONDecodeError as e:
+        raise ValueError(f"Invalid {LAYERS_CONFIG_FILE}: {e}")
+
+    rules = config.get("rules") if isinstance(config, dict) else None
+    if not isinstance(rules, list):
+        raise ValueError(f"{LAYERS_CONFIG_FILE} must contain a 'rules' list")
+    for i, rule in enumerate(rules):
+        if not isinstance(rule, dict) or not isinstance(rule.get("from"), str) \
+                or not any(isinstance(rule.get(key), list) for key in ("forbid", "allow")):
+            raise ValueError(f"Rule {i + 1} of {LAYERS_CONFIG_FILE} needs a 'from' glob and a 'forbid' or 'allow' list")
+        rule.setdefault("name", f"{rule['from']} " + (
+            f"must not depend on {', '.join(rule['forbid'])}" if "forbid" in rule else f"may only depend on {', '.join(rule['allow'])}"
+        ))
+    return rules
+
+
+def module_matches(pattern: str, module: str) -> bool:
+    """Whether `module` (or one of its parents) matches the glob `pattern`."""
+    candidates = [pattern] if pattern.startswith("crate") or "/" in pattern else [pattern, f"crate::{pattern}"]
+    return any(fnmatchcase(module, candidate) or fnmatchcase(module, f"{candidate}::*") or fnmatchcase(module, f"{candidate}/*")
+               for candidate in candidates)
+
+
+def evaluate_rules(rules: List[Dict[str, Any]], dependencies: Dict[tuple, List[Dict]]) -> List[Dict[str, Any]]:
+    """
+    Checks module dependencies, `{(source_module, target_module): [edges]}`, against the rules and
+    returns one violation per broken rule and module pair, with the edges behind it.
+    """
+    violations = []
+    for (source, target), edges in sorted(dependencies.items()):
+        if source == target:
+            continue
+        for rule in rules:
+            if not module_matches(rule["from"], source):
+                continue
+            forbidden = any(module_matches(p, target) for p in rule.get("forbid", []))
+            not_allowed = "allow" in rule and not module_matches(rule["from"], target) \
+                and not any(module_matches(p, target) for p in rule["allow"])
+            if forbidden or not_allowed:
+                violations.append({"rule": rule["name"], "source_module": source, "target_module": target, "edges": edges})
+    return violations
@@ -0,0 +1,166 @@
+# src/codegraphcontext/tools/lsif.py
+"""
+Exports an LSIF dump (https://microsoft.github.io/language-server-protocol/specifications/lsif/0.5.0/specification/)
+of a repository, for editors and code hosts that read LSIF rather than SCIP.
+
+It is written from the same documents as the SCIP index (`scip.build_documents`): each document
+becomes a `document` vertex containing a `range` vertex per occurrence, and each symbol a
+`resultSet` the ranges point to (`next`), with
+- a `definitionResult` (`textDocument/definition`) holding the ranges defining it;
+- a `referenceResult` (`textDocument/references`) holding those ranges as `definitions` and the
+  ranges referencing it as `references`;
+- a `hoverResult` (`textDocument/hover`) with its signature and doc comment as Markdown, for
+  symbols that have either;
+- an `implementationResult` (`textDocument/implementation`) holding the definitions of the types
+  and methods implementing it, for traits, trait methods and base classes.
+
+The dump is JSON Lines, every vertex before the edges using it. LSIF positions count UTF-16 code
+units while the graph's columns count UTF-8 bytes, so columns are converted on the lines of the
+files on disk; a file that cannot be read keeps its byte columns. Document-local symbols
+(variables in function bodies) get one result set per document.
+"""
+import json
+from pathlib import Path
+from typing import Any, Callable, Dict, Iterator, List, Optional
+
+from .scip import TOOL_NAME, read_file_lines
+
+LSIF_VERSION = "0.5.0"
+
+
+def utf16_column(line_text: Optional[str], byte_column: int) -> int:
+    """The UTF-16 offset of a UTF-8 byte offset into a line; the byte offset when the line is unknown."""
+    if line_text is None:
+        return byte_column
+    prefix = line_text.encode("utf-8")[:byte_column].decode("utf-8", errors="ignore")
+    return len(prefix.encode("utf-16-le")) // 2
+
+
+def lsp_range(range_: List[int], lines: Optional[List[str]]) -> Dict[str, Dict[str, int]]:
+    """An LSP range from a 0-based `[start line, start column, end line, end column]` byte range."""
+    def position(line, column):
+        text = lines[line] if lines is not None and line < len(lines) else None
+        return {"line": line, "character": utf16_column(text, column)}
+
+    return {"start": position(range_[0], range_[1]), "end": position(range_[2], range_[3])}
+
+
+def hover_contents(documentation: List[str]) -> Dict[str, str]:
+    return {"kind": "markdown", "value": "\n\n---\n\n".join(documentation)}
+
+
+def dump_lines(documents: List[Dict[str, Any]], project_root: str, tool_version: str = "",
+               read_lines: Callable[[str], Optional[List[str]]] = read_file_lines) -> Iterator[str]:
+    """The lines of the LSIF dump of the documents of a SCIP index of `project_root`; see the module docstring."""
+    root = Path(project_root).resolve()
+    next_id = 0
+
+    def vertex(label, **fields):
+        nonlocal next_id
+        next_id += 1
+        return next_id, {"id": next_id, "type": "vertex", "label": label, **fields}
+
+    def edge(label, out_v, **fields):
+        nonlocal next_id
+        next_id += 1
+        return {"id": next_id, "type": "edge", "label": label, "outV": out_v, **fields}
+
+    def line(record):
+        return json.dumps(record, separators=(",", ":"), ensure_ascii=False)
+
+    _, metadata = vertex("metaData", version=LSIF_VERSION, projectRoot=root.as_uri(), positionEncoding="utf-16",
+                         toolInfo={"name": TOOL_NAME, "version": tool_version})
+    yield line(metadata)
+    project_id, project = vertex("project", kind="code")
+    yield line(project)
+
+    # Per symbol (local symbols per document): its ranges by role and document, docs and implementors.
+    symbols: Dict[Any, Dict[str, Any]] = {}
+    implementations: Dict[str, List[str]] = {}
+    document_ids = []
+    for document in documents:
+        path = root / document["relative_path"]
+        lines = read_lines(str(path))
+        document_id, document_vertex = vertex("document", uri=path.as_uri(),
+                                              languageId=(document.get("language") or "").lower())
+        yield line(document_vertex)
+        document_ids.append(document_id)
+
+        def key(symbol):
+            return (document_id, symbol) if symbol.startswith("local ") else symbol
+
+        for info in document["symbols"]:
+            entry = symbols.setdefault(key(info["symbol"]), {"ranges": [], "documentation": []})
+            entry["documentation"] = entry["documentation"] or info.get("documentation", [])
+            for relationship in info.get("relationships", []):
+                if relationship.get("is_implementation"):
+                    implementations.setdefault(relationship["symbol"], []).append(info["symbol"])
+
+        range_ids, seen = [], set()
+        for occurrence in document["occurrences"]:
+            position = tuple(occurrence["range"])
+            if position in seen:
+                continue  # one range, one result set
+            seen.add(position)
+            range_id, range_vertex = vertex("range", **lsp_range(occurrence["range"], lines))
+            yield line(range_vertex)
+            range_ids.append(range_id)
+            is_definition = bool(occurrence.get("symbol_roles", 0) & 1)
+            symbols.setdefault(key(occurrence["symbol"]), {"ranges": [], "documentation": []})["ranges"].append(
+                (document_id, range_id, is_definition))
+        if range_ids:
+            yield line(edge("contains", document_id, inVs=range_ids))
+    if document_ids:
+        yield line(edge("contains", project_id, inVs=document_ids))
+
+    def items(result_id, ranges, **fields):
+        by_document = {}
+        for document_id, range_id, _ in ranges:
+            by_document.setdefault(document_id, []).append(range_id)
+        for document_id, range_ids in by_document.items():
+            yield line(edge("item", result_id, inVs=range_ids, document=document_id, **fields))
+
+    for symbol, entry in symbols.items():
+        if not entry["ranges"]:
+            continue
+        result_set_id, result_set = vertex("resultSet")
+        yield line(result_set)
+        for _, range_id, _ in entry["ranges"]:
+            yield line(edge("next", range_id, inV=result_set_id))
+        definitions = [r for r in entry["ranges"] if r[2]]
+        references = [r for r in entry["ranges"] if not r[2]]
+
+        if definitions:
+            result_id, result = vertex("definitionResult")
+            yield line(result)
+            yield line(edge("textDocument/definition", result_set_id, inV=result_id))
+            yield from items(result_id, definitions)
+
+        result_id, result = vertex("referenceResult")
+        yield line(result)
+        yield line(edge("textDocument/references", result_set_id, inV=result_id))
+        yield from items(result_id, definitions, property="definitions")
+        yield from items(result_id, references, property="references")
+
+        if entry["documentation"]:
+            result_id, result = vertex("hoverResult", result={"contents": hover_contents(entry["documentation"])})
+            yield line(result)
+            yield line(edge("textDocument/hover", result_set_id, inV=result_id))
+
+        implementors = [r for implementor in sorted(set(implementations.get(symbol, [])))
+                        for r in symbols.get(implementor, {}).get("ranges", []) if r[2]]
+        if implementors:
+            result_id, result = vertex("implementationResult")
+            yield line(result)
+            yield line(edge("textDocument/implementation", result_set_id, inV=result_id))
+            yield from items(result_id, implementors)
+
+
+def write_dump(documents: List[Dict[str, Any]], project_root: str, path: Path, tool_version: str = "") -> int:
+    """Writes the LSIF dump of the documents to `path`; returns the number of lines written."""
+    count = 0
+    with open(path, "w", encoding="utf-8") as f:
+        for record in dump_lines(documents, project_root, tool_version):
+            f.write(record + "\n")
+            count += 1
+    return count
@@ -0,0 +1,76 @@
+# src/codegraphcontext/tools/monomorphization.py
+"""
+Estimates how many monomorphized copies the compiler makes of each generic function, from the
+INSTANTIATES edges recorded at indexing time.
+
+A generic function gets one copy per distinct set of concrete type arguments it is instantiated
+with. When the arguments mention the generic parameters of the instantiating function (`pair(x, x)`
+inside `fn twin<T>`), each copy of that function brings its own copy, so instantiations are
+substituted transitively. Methods of a generic type are counted once per instantiation of the
+type, an upper bound since unused methods are not compiled. Arguments that could not be inferred
+(`_`) still count as one copy each.
+"""
+import itertools
+import re
+from typing import Any, Dict, List, Set, Tuple
+
+
+def _substitute(type_argument: str, bindings: Dict[str, str]) -> str:
+    if not bindings:
+        return type_argument
+    pattern = r"\b(?:" + "|".join(re.escape(param) for param in bindings) + r")\b"
+    return re.sub(pattern, lambda match: bindings[match.group(0)], type_argument)
+
+
+def estimate_copies(functions: Dict[str, Dict[str, Any]], class_params: Dict[str, List[str]],
+                    instantiations: List[Tuple[str, str, List[str]]]) -> Dict[str, Set[Tuple[str, ...]]]:
+    """
+    Returns, per generic function id, the set of concrete bindings of its parameters (its type's
+    parameters first, then its own). `functions` maps ids to `{generic_params, class_id}`,
+    `class_params` maps generic type ids to their parameters and `instantiations` holds
+    `(source_id, target_id, type_arguments)` with targets being functions or types.
+    """
+    by_target = {}
+    for source, target, type_arguments in instantiations:
+        by_target.setdefault(target, []).append((source, type_arguments))
+
+    memo, in_progress = {}, set()
+
+    def bindings_of(fid) -> List[Dict[str, str]]:
+        """The concrete parameter bindings of function `fid`, as dicts; one empty dict when not generic."""
+        func = functions.get(fid)
+        if func is None:
+            return [{}]
+        params = class_params.get(func.get('class_id'), []) + list(func.get('generic_params') or [])
+        if not params:
+            return [{}]
+        return [dict(zip(params, copy)) for copy in copies(fid)]
+
+    def concrete(target, params) -> Set[Tuple[str, ...]]:
+        found = set()
+        for source, type_arguments in by_target.get(target, []):
+            type_arguments = list(type_arguments)[:len(params)]
+            if len(type_arguments) < len(params):
+                type_arguments += ['_'] * (len(params) - len(type_arguments))
+            for bindings in bindings_of(source):
+                found.add(tuple(_substitute(arg, bindings) for arg in type_arguments))
+        return found
+
+    def copies(fid) -> Set[Tuple[str, ...]]:
+        if fid in memo:
+            return memo[fid]
+        if fid in in_progress:  # recursive generic functions: stop at the copies found so far
+            return set()
+        in_progress.add(fid)
+        func = functions[fid]
+        type_params = class_params.get(func.get('class_id'), [])
+        own_params = list(func.get('generic_params') or [])
+        type_copies = concrete(func.get('class_id'), type_params) if type_params else {()}
+        own_copies = concrete(fid, own_params) if own_params else {()}
+        result = {a + b for a, b in itertools.product(type_copies, own_copies)}
+        in_progress.discard(fid)
+        memo[fid] = result
+        return result
+
+    return {fid: copies(fid) for fid, func in functions.items()
+            if func.get('generic_params') or class_params.get(func.get('class_id'))}
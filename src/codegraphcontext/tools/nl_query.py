@@ -0,0 +1,189 @@
+# src/codegraphcontext/tools/nl_query.py
+"""
+Translates natural-language questions about the code graph into parameterized Cypher.
+
+Each template pairs question patterns with a read-only query written against the published
+schema. Names in questions may be qualified as `Type::method` (or `Type.method`); the owner
+part is matched against `class_context`. Phrases like "outside tests" and "in <file>" narrow
+the results of any template that supports them.
+"""
+import re
+from dataclasses import dataclass
+from typing import Any, Dict, List, Optional, Tuple
+
+NAME = r"`?(?P<target>[A-Za-z_][\w:.]*?)`?"
+
+# Nodes whose attributes, inline module or file mark them as test code.
+NOT_TEST_CODE = """
+    NOT any(d IN coalesce({var}.decorators, []) WHERE d CONTAINS 'test')
+    AND NOT {var}.file_path =~ '.*/(tests?|benches)/.*'
+    AND NOT coalesce({var}.inline_module, '') =~ '(.*::)?tests?(::.*)?'
+"""
+
+MATCHES_TARGET = "{var}.name = $name AND ($owner IS NULL OR {var}.class_context = $owner OR {var}.owner = $owner)"
+
+
+@dataclass
+class QuestionTemplate:
+    """Question patterns and their query; `{exclude_tests}` and `{in_file}` filter `filter_var`, or become `true`."""
+    name: str
+    patterns: List[str]
+    cypher: str
+    filter_var: Optional[str] = None
+
+
+TEMPLATES = [
+    QuestionTemplate("callers", [
+        rf"^(?:who|what|which functions?) (?:calls|uses|invokes) {NAME}\??$",
+        rf"^(?:find |list |show )?(?:the )?callers of {NAME}\??$",
+    ], """
+        MATCH (caller:Function)-[call:CALLS]->(target:Function)
+        WHERE """ + MATCHES_TARGET.format(var="target") + """ AND {exclude_tests} AND {in_file}
+        RETURN DISTINCT caller.name as caller, caller.class_context as caller_owner, caller.file_path as file_path,
+               call.line_number as line_number, target.name as callee, target.class_context as callee_owner
+        ORDER BY file_path, line_number
+        LIMIT $limit
+    """, filter_var="caller"),
+    QuestionTemplate("callees", [
+        rf"^what (?:does|do) {NAME} call\??$",
+        rf"^(?:find |list |show )?(?:the )?(?:callees|calls made by) (?:of )?{NAME}\??$",
+    ], """
+        MATCH (source:Function)-[call:CALLS]->(callee:Function)
+        WHERE """ + MATCHES_TARGET.format(var="source") + """ AND {exclude_tests} AND {in_file}
+        RETURN DISTINCT callee.name as callee, callee.class_context as callee_owner, callee.file_path as file_path,
+               call.line_number as line_number
+        ORDER BY line_number
+        LIMIT $limit
+    """, filter_var="source"),
+    QuestionTemplate("definition", [
+        rf"^where is {NAME} (?:defined|declared)\??$",
+        rf"^(?:find|show|locate) (?:the )?(?:definition of )?{NAME}\??$",
+    ], """
+        MATCH (n)
+        WHERE (n:Function OR n:Class OR n:Variable OR n:Field) AND """ + MATCHES_TARGET.format(var="n") + """ AND {in_file}
+        RETURN labels(n)[0] as label, n.name as name, n.class_context as owner, n.file_path as file_path,
+               n.line_number as line_number, n.signature as signature
+        ORDER BY file_path, line_number
+        LIMIT $limit
+    """, filter_var="n"),
+    QuestionTemplate("implementations", [
+        rf"^(?:who|what|which types?) implements? {NAME}\??$",
+        rf"^(?:find |list |show )?(?:the )?implementations of {NAME}\??$",
+    ], """
+        MATCH (type:Class)-[:HAS_IMPL]->(impl:Impl)-[:IMPLEMENTS]->(trait:Class {name: $name})
+        WHERE {in_file}
+        RETURN DISTINCT type.name as type, type.file_path as file_path, impl.line_number as line_number, trait.name as trait
+        ORDER BY file_path, line_number
+        LIMIT $limit
+    """, filter_var="impl"),
+    QuestionTemplate("methods", [
+        rf"^what methods does {NAME} have\??$",
+        rf"^(?:find |list |show )?(?:the )?methods (?:of|on) {NAME}\??$",
+    ], """
+        MATCH (type:Class {name: $name})-[:HAS_IMPL]->(impl:Impl)-[:CONTAINS]->(m:Function)
+        WHERE {in_file}
+        RETURN m.name as method, impl.trait as trait, m.signature as signature, m.file_path as file_path,
+               m.line_number as line_number
+        ORDER BY file_path, line_number
+        LIMIT $limit
+    """, filter_var="m"),
+    QuestionTemplate("fields", [
+        rf"^what fields does {NAME} have\??$",
+        rf"^(?:find |list |show )?(?:the )?fields (?:of|on) {NAME}\??$",
+    ], """
+        MATCH (type:Class {name: $name})-[:HAS_FIELD]->(field:Field)
+        WHERE {in_file}
+        RETURN field.name as field, field.type as type, field.file_path as file_path, field.line_number as line_number
+        ORDER BY file_path, line_number
+        LIMIT $limit
+    """, filter_var="field"),
+    QuestionTemplate("type_usages", [
+        rf"^(?:who|what|which functions?) uses? (?:the )?type {NAME}\??$",
+        rf"^where is (?:the )?type {NAME} used\??$",
+    ], """
+        MATCH (user)-[r:REFERENCES_TYPE]->(type:Class {name: $name})
+        WHERE {exclude_tests} AND {in_file}
+        RETURN DISTINCT user.name as user, r.role as role, r.type_text as type_text,
+               coalesce(user.file_path, user.path) as file_path, r.line_number as line_number
+        ORDER BY file_path, line_number
+        LIMIT $limit
+    """, filter_var="user"),
+    QuestionTemplate("complex_functions", [
+        r"^(?:which |what |list |show |find )?(?:the )?functions? (?:with|having) (?:a )?(?:cyclomatic )?complexity (?:over|above|greater than|more than|>) (?P<number>\d+)\??$",
+        r"^(?:which |what |list |show |find )?(?:are )?(?:the )?most complex functions\??$",
+    ], """
+        MATCH (f:Function)
+        WHERE coalesce(f.cyclomatic_complexity, 1) > $number AND coalesce(f.is_dependency, false) = false
+          AND {exclude_tests} AND {in_file}
+        RETURN f.name as function, f.class_context as owner, f.cyclomatic_complexity as complexity,
+               f.file_path as file_path, f.line_number as line_number
+        ORDER BY complexity DESC
+        LIMIT $limit
+    """, filter_var="f"),
+    QuestionTemplate("file_contents", [
+        r"^(?:what is|what's|which functions are|list (?:the )?functions) (?:defined )?in (?:the )?file `?(?P<file>[\w./-]+)`?\??$",
+    ], """
+        MATCH (file:File)-[:CONTAINS]->(n)
+        WHERE file.path ENDS WITH $file AND (n:Function OR n:Class)
+        RETURN labels(n)[0] as label, n.name as name, n.class_context as owner, n.line_number as line_number
+        ORDER BY line_number
+        LIMIT $limit
+    """),
+]
+
+MODIFIERS = [
+    ("exclude_tests", re.compile(r"\s*,?\s*\b(?:outside|excluding|not in|ignoring) (?:of )?(?:the )?tests?\b", re.IGNORECASE)),
+    ("in_file", re.compile(r"\s*,?\s*\bin (?:the )?file `?(?P<file>[\w./-]+)`?", re.IGNORECASE)),
+]
+
+
+def supported_questions() -> List[str]:
+    """One example phrasing per template, for error messages and the tool description."""
+    return [
+        "who calls BankAccount::withdraw outside tests?", "what does main call?", "where is Person defined?",
+        "who implements Greetable?", "what methods does Person have?", "what fields does Person have?",
+        "who uses type Person?", "functions with complexity over 5", "what is in file structs_enums.rs?",
+    ]
+
+
+def _render(template: QuestionTemplate, match: re.Match, modifiers: Dict[str, Any], limit: int) -> Tuple[str, Dict[str, Any]]:
+    groups = match.groupdict()
+    owner, name = None, groups.get("target")
+    if name and re.search(r"::|\.", name):
+        parts = re.split(r"::|\.", name)
+        owner, name = parts[-2], parts[-1]
+    var = template.filter_var
+    cypher = template.cypher.replace(
+        "{exclude_tests}", NOT_TEST_CODE.format(var=var).strip() if var and "exclude_tests" in modifiers else "true"
+    ).replace(
+        "{in_file}", f"{var}.file_path ENDS WITH $in_file" if var and "in_file" in modifiers else "true"
+    )
+    parameters = {"name": name, "owner": owner, "limit": limit,
+                  "number": int(groups["number"]) if groups.get("number") else 10,
+                  "file": groups.get("file"), "in_file": modifiers.get("in_file")}
+    return re.sub(r"\n\s+", "\n", cypher).strip(), parameters
+
+
+def translate_question(question: str, limit: int = 100) -> Tuple[str, Dict[str, Any], str]:
+    """
+    Returns `(cypher, parameters, template_name)` for a question, or raises ValueError when no
+    template understands it. The question is tried as is first, then with its modifiers removed.
+    """
+    text = re.sub(r"\s+", " ", question.strip())
+    modifiers = {}
+    stripped = text
+    for name, pattern in MODIFIERS:
+        match = pattern.search(stripped)
+        if match:
+            modifiers[name] = match.groupdict().get("file", True)
+            stripped = (stripped[:match.start()] + stripped[match.end():]).strip()
+
+    for candidate, applied in ((text, {}), (stripped, modifiers)):
+        for template in TEMPLATES:
+            for pattern in template.patterns:
+                match = re.match(pattern, candidate, re.IGNORECASE)
+                if match:
+                    cypher, parameters = _render(template, match, applied, limit)
+                    return cypher, parameters, template.name
+
+    raise ValueError(f"Could not translate the question. Try phrasings like: {'; '.join(supported_questions())}")
@@ -0,0 +1,51 @@
+# src/codegraphcontext/tools/node_ids.py
+"""
+Stable node identities that survive re-indexing.
+
+A function, type or variable is identified by its qualified path: its label, its file relative to
+the repository, its owner (the type, trait or function an item belongs to), the trait it
+implements and its name, so `Function:src/geo.rs:Circle:Shape:area` keeps its `uid` when code
+around it moves. Paths are namespaced by the repository root, so the same code indexed twice (e.g.
+two revisions) still gets distinct nodes.
+Items sharing a qualified path (e.g. `#[cfg]`-gated alternatives) are told apart by their order in
+the file. `content_hash` fingerprints an item's source, so changed items can be told apart from
+moved ones.
+"""
+import hashlib
+from typing import Dict, List, Optional
+
+
+def _context_name(context) -> str:
+    """Parsers give contexts as names or as `(name, type, line)` tuples."""
+    if isinstance(context, (list, tuple)):
+        context = context[0] if context else None
+    return str(context) if context else ""
+
+
+def qualified_path(label: str, relative_file: str, item: Dict) -> str:
+    """The path identifying `item` (a parsed function, class or variable) within its repository."""
+    owner = _context_name(item.get("class_context")) or _context_name(item.get("context"))
+    return ":".join([label, relative_file, owner, item.get("impl_trait") or "", item["name"]])
+
+
+def stable_id(path: str) -> str:
+    """The `uid` of a qualified path."""
+    return hashlib.sha256(path.encode("utf-8")).hexdigest()[:32]
+
+
+def content_hash(source: Optional[str]) -> Optional[str]:
+    """A fingerprint of an item's source, ignoring leading and trailing whitespace."""
+    if source is None:
+        return None
+    return hashlib.sha256(source.strip().encode("utf-8")).hexdigest()[:32]
+
+
+def assign_ids(root: str, label: str, relative_file: str, items: List[Dict]) -> None:
+    """Sets `uid` and `content_hash` on the parsed items of one label in one file of repository `root`."""
+    seen = {}
+    for item in sorted(items, key=lambda item: item.get("line_number") or 0):
+        path = qualified_path(label, relative_file, item)
+        ordinal = seen.get(path, 0)
+        seen[path] = ordinal + 1
+        item["uid"] = stable_id(f"{root}:{path}" if ordinal == 0 else f"{root}:{path}#{ordinal}")
+        item["content_hash"] = content_hash(item.get("source") or item.get("source_code") or item.get("value"))
@@ -0,0 +1,136 @@
+# src/codegraphcontext/tools/pipeline.py
+"""
+Connects the parsing of files to the writing of their graph with bounded queues.
+
+A feeder thread hands the files to parse to a pool of parse workers through the parse queue;
+the workers put what they parsed on the write queue, which the single graph writer drains with
+`next`. Both queues hold at most `capacity` entries, so when the database is slow the full write
+queue blocks the workers, the full parse queue blocks the feeder, and parsing waits for writing
+instead of buffering parsed files without limit. `depths` reports how full the queues are and how
+long the workers were held back, for progress events.
+"""
+import queue
+import threading
+import time
+from typing import Any, Callable, Dict, Iterable, Optional
+
+# Parse workers of a build; override with CGC_PARSE_WORKERS.
+DEFAULT_PARSE_WORKERS = 2
+# Entries each queue of a build holds; override with CGC_PIPELINE_CAPACITY.
+DEFAULT_PIPELINE_CAPACITY = 32
+
+_DONE = object()
+
+
+class _Failure:
+    """An exception raised by a worker, re-raised to the writer."""
+
+    def __init__(self, error: BaseException):
+        self.error = error
+
+
+class BoundedPipeline:
+    """Runs `stage` over items on `workers` threads, handing the results to one consumer with backpressure."""
+
+    def __init__(self, stage: Callable[[Any], Any], workers: int = DEFAULT_PARSE_WORKERS,
+                 capacity: int = DEFAULT_PIPELINE_CAPACITY):
+        self.stage = stage
+        self.workers = max(1, workers)
+        self.capacity = max(1, capacity)
+        self.parse_queue: queue.Queue = queue.Queue(self.capacity)
+        self.write_queue: queue.Queue = queue.Queue(self.capacity)
+        self.stopped = threading.Event()
+        self.threads = []
+        self.finished = 0
+        self.blocked_seconds = 0.0
+        self.lock = threading.Lock()
+
+    def start(self, items: Iterable[Any]) -> "BoundedPipeline":
+        """Starts feeding `items` (iterated on the feeder thread) to the workers."""
+        self.threads = [threading.Thread(target=self._feed, args=(items,), name="cgc-feed", daemon=True)]
+        self.threads += [threading.Thread(target=self._work, name=f"cgc-parse-{i}", daemon=True)
+                         for i in range(self.workers)]
+        for thread in self.threads:
+            thread.start()
+        return self
+
+    def _put(self, target: queue.Queue, entry: Any) -> bool:
+        """Puts `entry` on a queue, waiting while it is full; False once the pipeline is closed."""
+        started = None
+        while not self.stopped.is_set():
+            try:
+                target.put(entry, timeout=0.1)
+                break
+            except queue.Full:
+                started = started or time.monotonic()
+        else:
+            return False
+        if started is not None and target is self.write_queue:
+            with self.lock:
+                self.blocked_seconds += time.monotonic() - started
+        return True
+
+    def _feed(self, items: Iterable[Any]):
+        try:
+            for item in items:
+                if not self._put(self.parse_queue, item):
+                    return
+        except Exception as e:
+            self._put(self.write_queue, _Failure(e))
+        for _ in range(self.workers):
+            self._put(self.parse_queue, _DONE)
+
+    def _work(self):
+        while not self.stopped.is_set():
+            try:
+                item = self.parse_queue.get(timeout=0.1)
+            except queue.Empty:
+                continue
+            if item is _DONE:
+                self._put(self.write_queue, _DONE)
+                return
+            try:
+                result = self.stage(item)
+            except Exception as e:
+                result = _Failure(e)
+            if not self._put(self.write_queue, result):
+                return
+
+    def next(self, default: Any = None) -> Any:
+        """
+        The next result, in the order the workers finish, waiting while they are busy; `default`
+        once every item is done. Re-raises what the stage or the item iterable raised.
+        """
+        while self.finished < self.workers:
+            entry = self.write_queue.get()
+            if entry is _DONE:
+                self.finished += 1
+            elif isinstance(entry, _Failure):
+                raise entry.error
+            else:
+                return entry
+        return default
+
+    def depths(self) -> Dict[str, Any]:
+        """How full the queues are, and how long the workers waited on the full write queue."""
+        with self.lock:
+            blocked = self.blocked_seconds
+        return {
+            "parse_queue": self.parse_queue.qsize(),
+            "write_queue": self.write_queue.qsize(),
+            "capacity": self.capacity,
+            "workers": self.workers,
+            "backpressure_seconds": round(blocked, 3),
+        }
+
+    def close(self, timeout: Optional[float] = 1.0):
+        """Stops the feeder and workers, e.g. when the writer failed, and waits for them briefly."""
+        self.stopped.set()
+        for thread in self.threads:
+            thread.join(timeout)
+
+    def __enter__(self) -> "BoundedPipeline":
+        return self
+
+    def __exit__(self, *exc_info):
+        self.close()
@@ -0,0 +1,91 @@
+# src/codegraphcontext/tools/profile.py
+"""
+Reads CPU profiles in the collapsed stack format of `inferno-collapse-perf`, `stackcollapse-perf.pl`
+and `cargo flamegraph` (the input of flame graphs): one line per distinct stack, root frame first,
+frames separated by `;` and followed by the number of samples.
+
+    app::main;app::run;app::parser::Parser::parse;alloc::vec::Vec<T>::push 42
+
+Rust frames are demangled symbol paths. `normalize_frame` turns them into path segments without
+the symbol hash, generic arguments, closure markers or perf annotations, so `<app::Circle as
+app::Shape>::area::h3f2a...` becomes `(app, Circle, area)` and closures count as their function.
+"""
+import re
+from pathlib import Path
+from typing import Callable, Dict, Iterable, List, Optional, Tuple
+
+STACK_LINE = re.compile(r"^(?P<stack>.+?)\s+(?P<count>\d+)$")
+TRAIT_IMPL_FRAME = re.compile(r"^<(?P<type>.+?)(?: as (?P<trait>.+))?>::(?P<rest>.+)$")
+
+
+def normalize_frame(frame: str) -> Tuple[str, ...]:
+    """The path segments of a demangled frame, `()` for frames that are not symbol paths."""
+    frame = frame.strip()
+    frame = frame.split("`", 1)[-1]
+    frame = re.sub(r"_\[[kjiw]\]$", "", frame)
+    frame = re.sub(r"\s*\(inlined\)$", "", frame)
+    frame = re.sub(r"::h[0-9a-f]{16}$", "", frame)
+    frame = re.sub(r"\{\{[^}]*\}\}|\{[\w#]+\}", "", frame)
+    if not frame or frame.startswith(("[", "0x", "_ZN", "_R")):
+        return ()
+    impl = TRAIT_IMPL_FRAME.match(frame)
+    if impl:
+        frame = f"{impl.group('type')}::{impl.group('rest')}"
+    while True:
+        stripped = re.sub(r"<[^<>]*>", "", frame)
+        if stripped == frame:
+            break
+        frame = stripped
+    segments = [segment.lstrip("&*") for segment in frame.split("::")]
+    return tuple(segment for segment in segments if re.match(r"^[\w$]+$", segment))
+
+
+def parse_collapsed(lines: Iterable[str]) -> List[Tuple[List[str], int]]:
+    """The stacks of a collapsed profile, as `(frames, samples)` with the root frame first."""
+    stacks = []
+    for line in lines:
+        match = STACK_LINE.match(line.strip())
+        if match:
+            stacks.append((match.group("stack").split(";"), int(match.group("count"))))
+    return stacks
+
+
+def load_profile(profile_path: Path) -> List[Tuple[List[str], int]]:
+    """Reads a collapsed stack file; raises ValueError when it is unreadable or holds no stacks."""
+    try:
+        text = Path(profile_path).read_text(encoding="utf-8", errors="replace")
+    except OSError as e:
+        raise ValueError(f"Cannot read profile {profile_path}: {e}")
+    stacks = parse_collapsed(text.splitlines())
+    if not stacks:
+        raise ValueError(f"{profile_path} is not a collapsed stack profile (`frame;frame;... count` lines)")
+    return stacks
+
+
+def aggregate(stacks: List[Tuple[List[str], int]], resolve: Callable[[str], Optional[str]]) -> Dict[str, Dict]:
+    """
+    Attributes samples to the functions frames resolve to (`resolve` returns a function id or None
+    for frames outside the graph, which are skipped over): `total` samples of stacks a function is
+    on, `self` samples of stacks it is the innermost resolved frame of (its time in library code
+    included), and the samples of each `edges` pair of consecutive resolved frames. Recursion
+    counts a stack once per function and per pair.
+    """
+    total, self_samples, edges, unresolved = {}, {}, {}, {}
+    samples = 0
+    for frames, count in stacks:
+        samples += count
+        resolved = []
+        for frame in frames:
+            function_id = resolve(frame)
+            if function_id is None:
+                if frame.strip():
+                    unresolved[frame] = unresolved.get(frame, 0) + count
+            elif not resolved or resolved[-1] != function_id:
+                resolved.append(function_id)
+        for function_id in set(resolved):
+            total[function_id] = total.get(function_id, 0) + count
+        if resolved:
+            self_samples[resolved[-1]] = self_samples.get(resolved[-1], 0) + count
+        for pair in set(zip(resolved, resolved[1:])):
+            edges[pair] = edges.get(pair, 0) + count
+    return {"samples": samples, "total": total, "self": self_samples, "edges": edges, "unresolved": unresolved}
@@ -0,0 +1,66 @@
+# src/codegraphcontext/tools/query_cache.py
+"""
+Caches the results of read-only graph queries for the length of a conversation.
+
+Results are keyed by the query (a tool name and its arguments) and the graph generation, a counter
+bumped whenever the graph is written (indexing, incremental updates, imported reports), so a cached
+result is only served while the graph it was computed from is unchanged. The generation is read
+before a result is computed: a result whose graph changed while it was computed is stored under the
+stale generation and never served.
+"""
+import copy
+import json
+import threading
+from collections import OrderedDict
+from typing import Any, Callable, Dict
+
+# Entries kept before the least recently used ones are evicted; override with CGC_QUERY_CACHE_SIZE (0 disables).
+DEFAULT_QUERY_CACHE_SIZE = 256
+
+
+def cache_key(name: str, args: Dict[str, Any]) -> str:
+    """The key of a query: its name and its arguments in a canonical order."""
+    return json.dumps([name, args], sort_keys=True, default=str)
+
+
+class QueryCache:
+    """A thread-safe LRU cache of query results, invalidated when the graph generation moves on."""
+
+    def __init__(self, generation: Callable[[], int], max_entries: int = DEFAULT_QUERY_CACHE_SIZE):
+        self.generation = generation
+        self.max_entries = max_entries
+        self.entries: "OrderedDict[str, Any]" = OrderedDict()
+        self.cached_generation = None
+        self.hits = 0
+        self.misses = 0
+        self.lock = threading.Lock()
+
+    def get_or_compute(self, key: str, compute: Callable[[], Dict[str, Any]]) -> Dict[str, Any]:
+        """The cached result of `key` for the current generation, computing (and caching) it if missing."""
+        if self.max_entries <= 0:
+            return compute()
+        generation = self.generation()
+        with self.lock:
+            if generation != self.cached_generation:
+                self.entries.clear()
+                self.cached_generation = generation
+            if key in self.entries:
+                self.entries.move_to_end(key)
+                self.hits += 1
+                return copy.deepcopy(self.entries[key])
+            self.misses += 1
+        result = compute()
+        # Failed queries are retried rather than cached.
+        if isinstance(result, dict) and "error" not in result:
+            with self.lock:
+                if generation == self.cached_generation:
+                    self.entries[key] = copy.deepcopy(result)
+                    while len(self.entries) > self.max_entries:
+                        self.entries.popitem(last=False)
+        return result
+
+    def stats(self) -> Dict[str, Any]:
+        """Hit and miss counts, the number of cached results and the generation they belong to."""
+        with self.lock:
+            return {"hits": self.hits, "misses": self.misses, "entries": len(self.entries),
+                    "generation": self.cached_generation, "max_entries": self.max_entries}
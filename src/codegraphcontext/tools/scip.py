@@ -0,0 +1,344 @@
+# src/codegraphcontext/tools/scip.py
+"""
+Emits a SCIP index (https://github.com/sourcegraph/scip) of a repository from its resolved
+symbol graph, for Sourcegraph-style code navigation.
+
+`build_documents` maps the graph read by `CodeFinder.symbol_graph` onto one document per file:
+- every Function, Class, Variable, Field, Variant and Macro node is a definition occurrence on its
+  name, enclosing its whole span, with a `SymbolInformation` holding its kind, display name and
+  documentation (the signature as a code block, then the doc comment);
+- CALLS, REFERENCES_TYPE and READS_FIELD/WRITES_FIELD edges are reference occurrences in the file
+  of their source, field accesses with the read or write role;
+- a type implementing a trait (HAS_IMPL/IMPLEMENTS) or inheriting a class (INHERITS), and a method
+  of a trait impl, get an `is_implementation` relationship to the trait, class or trait method.
+
+Symbols are `cgc . <package> <version> <descriptors>`: the file's path as namespaces (`src/`,
+`parser/` for src/parser.rs), then any inline modules, then `Type#`, `Type#method().`, `function().`,
+`Type#field.`, `CONSTANT.` or `macro!`. Methods defined twice under one name (e.g. `fmt` in a
+Display and a Debug impl) are told apart with SCIP disambiguators, `fmt(+1).`. Variables inside
+functions are document-local (`local 0`). Only what the graph locates is emitted: an edge without
+a column is placed by finding the target's name on its line in the file on disk, and dropped when
+it is not there. Symbols of other repositories are not referenced.
+
+Ranges are 0-based `[start line, start column, end line, end column]`, with columns in UTF-8
+bytes from the start of the line as tree-sitter reports them. `encode_index` writes the documents
+as the `scip.Index` protobuf message, single-line ranges as three numbers as SCIP does.
+"""
+import re
+from pathlib import Path
+from typing import Any, Callable, Dict, List, Optional
+
+SCHEME = "cgc"
+TOOL_NAME = "codegraphcontext"
+
+ROLE_DEFINITION = 1
+ROLE_IMPORT = 2
+ROLE_WRITE_ACCESS = 4
+ROLE_READ_ACCESS = 8
+
+# scip.SymbolInformation.Kind
+KIND_CLASS = 7
+KIND_CONSTANT = 8
+KIND_ENUM = 11
+KIND_ENUM_MEMBER = 12
+KIND_FIELD = 15
+KIND_FUNCTION = 17
+KIND_MACRO = 25
+KIND_METHOD = 26
+KIND_STRUCT = 49
+KIND_TRAIT = 53
+KIND_UNION = 59
+KIND_VARIABLE = 61
+CLASS_KINDS = {"struct": KIND_STRUCT, "enum": KIND_ENUM, "trait": KIND_TRAIT, "union": KIND_UNION}
+
+TEXT_ENCODING_UTF8 = 1
+POSITION_ENCODING_UTF8 = 1
+# Names of the scip.Language enum.
+LANGUAGES = {".rs": "Rust", ".py": "Python", ".js": "JavaScript", ".jsx": "JavaScript", ".mjs": "JavaScript"}
+
+_SIMPLE_IDENTIFIER = re.compile(r"^[A-Za-z0-9_+$-]+$")
+
+
+def escape_identifier(name: str) -> str:
+    """A descriptor name as SCIP writes it: backquoted (backquotes doubled) unless it is a simple identifier."""
+    if _SIMPLE_IDENTIFIER.match(name):
+        return name
+    return "`" + name.replace("`", "``") + "`"
+
+
+def _package_part(value: Optional[str]) -> str:
+    return value.replace(" ", "  ") if value else "."
+
+
+def symbol_prefix(package: str, version: str = None) -> str:
+    """The scheme and package every global symbol of the index starts with."""
+    return f"{SCHEME} . {_package_part(package)} {_package_part(version)} "
+
+
+def _namespaces(relative_path: str, inline_module: Optional[str]) -> str:
+    parts = list(Path(relative_path).with_suffix("").parts)
+    parts += inline_module.split("::") if inline_module else []
+    return "".join(escape_identifier(part) + "/" for part in parts)
+
+
+def descriptor(definition: Dict[str, Any], disambiguator: str = "") -> str:
+    """The descriptors of a definition below its file's namespaces; see the module docstring."""
+    name = escape_identifier(definition["name"])
+    owner = definition.get("owner")
+    owner = escape_identifier(owner) + "#" if owner else ""
+    label = definition["label"]
+    if label == "Class":
+        return f"{name}#"
+    if label == "Function":
+        return f"{owner}{name}({disambiguator})."
+    if label == "Macro":
+        return f"{name}!"
+    return f"{owner}{name}."
+
+
+def is_local(definition: Dict[str, Any]) -> bool:
+    """Variables inside a function body are only visible in their document."""
+    return definition["label"] == "Variable" and bool(definition.get("context"))
+
+
+def symbol_kind(definition: Dict[str, Any]) -> int:
+    label = definition["label"]
+    if label == "Class":
+        return CLASS_KINDS.get(definition.get("kind"), KIND_CLASS)
+    if label == "Function":
+        return KIND_METHOD if definition.get("owner") else KIND_FUNCTION
+    if label == "Variable":
+        return KIND_CONSTANT if definition.get("kind") in ("const", "static") else KIND_VARIABLE
+    return {"Field": KIND_FIELD, "Variant": KIND_ENUM_MEMBER, "Macro": KIND_MACRO}.get(label, 0)
+
+
+def documentation(definition: Dict[str, Any], language: Optional[str]) -> List[str]:
+    """The signature of a definition as a Markdown code block, then its doc comment."""
+    docs = []
+    if definition.get("signature"):
+        docs.append(f"```{(language or '').lower()}\n{definition['signature']}\n```")
+    if definition.get("docstring"):
+        docs.append(definition["docstring"])
+    return docs
+
+
+def assign_symbols(definitions: List[Dict[str, Any]], relative_paths: Dict[str, str],
+                   package: str, version: str = None) -> Dict[str, str]:
+    """
+    The symbol of every definition by id. Functions sharing a symbol get disambiguators in the
+    order of their lines; other definitions sharing one (e.g. a type under two `#[cfg]`s) share it.
+    """
+    prefix = symbol_prefix(package, version)
+    symbols, taken, locals_by_file = {}, set(), {}
+    for definition in sorted(definitions, key=lambda d: (d["file_path"], d.get("line_number") or 0, d["id"])):
+        relative_path = relative_paths.get(definition["file_path"])
+        if relative_path is None:
+            continue
+        if is_local(definition):
+            number = locals_by_file.get(relative_path, 0)
+            locals_by_file[relative_path] = number + 1
+            symbols[definition["id"]] = f"local {number}"
+            continue
+        namespaces = prefix + _namespaces(relative_path, definition.get("inline_module"))
+        symbol = namespaces + descriptor(definition)
+        if definition["label"] == "Function":
+            count = 0
+            while symbol in taken:
+                count += 1
+                symbol = namespaces + descriptor(definition, f"+{count}")
+        taken.add(symbol)
+        symbols[definition["id"]] = symbol
+    return symbols
+
+
+def name_range(lines: Optional[List[str]], line_number: Optional[int], column: Optional[int],
+               name: str) -> Optional[List[int]]:
+    """
+    The 0-based `[line, start, line, end]` range of `name` on a 1-based line: found on the line's text
+    from `column` on when the text is known, else at `column`; None when neither places it.
+    """
+    if not line_number or not name:
+        return None
+    line = line_number - 1
+    target = name.encode("utf-8")
+    if lines is not None and line < len(lines):
+        text = lines[line].encode("utf-8")
+        start = text.find(target, column or 0)
+        if start < 0 and column:
+            start = text.find(target)
+        if start >= 0:
+            return [line, start, line, start + len(target)]
+    if column is None:
+        return None
+    return [line, column, line, column + len(target)]
+
+
+def span_range(definition: Dict[str, Any]) -> Optional[List[int]]:
+    """The 0-based range of a definition's whole span, if the graph has it."""
+    if definition.get("start_line") is None or definition.get("end_line") is None:
+        return None
+    return [definition["start_line"] - 1, definition.get("start_column") or 0,
+            definition["end_line"] - 1, definition.get("end_column") or 0]
+
+
+def _compact(range_: List[int]) -> List[int]:
+    """SCIP writes single-line ranges as three numbers."""
+    if len(range_) == 4 and range_[0] == range_[2]:
+        return [range_[0], range_[1], range_[3]]
+    return range_
+
+
+def read_file_lines(path: str) -> Optional[List[str]]:
+    try:
+        return Path(path).read_text(encoding="utf-8", errors="replace").splitlines()
+    except OSError:
+        return None
+
+
+def build_documents(graph: Dict[str, Any], package: str, version: str = None,
+                    read_lines: Callable[[str], Optional[List[str]]] = read_file_lines) -> List[Dict[str, Any]]:
+    """
+    The documents of the index of a symbol graph `{files, definitions, references, implementations}`
+    (see `CodeFinder.symbol_graph`): per file, its `relative_path`, `language`, `occurrences`
+    (`range`, `symbol`, `symbol_roles`, `enclosing_range`) and `symbols` (`symbol`, `kind`,
+    `display_name`, `documentation`, `enclosing_symbol`, `relationships`), sorted.
+    """
+    relative_paths = {file["path"]: file["relative_path"] for file in graph["files"]}
+    definitions = {definition["id"]: definition for definition in graph["definitions"]}
+    symbols = assign_symbols(graph["definitions"], relative_paths, package, version)
+    documents = {path: {"relative_path": relative, "language": LANGUAGES.get(Path(path).suffix),
+                        "occurrences": [], "symbols": {}} for path, relative in relative_paths.items()}
+    cache = {}
+
+    def lines_of(path):
+        if path not in cache:
+            cache[path] = read_lines(path)
+        return cache[path]
+
+    enclosing = {}
+    for id_, definition in definitions.items():
+        if id_ in symbols and definition["label"] == "Function":
+            enclosing.setdefault((definition["file_path"], definition.get("owner"), definition["name"]), symbols[id_])
+
+    relationships = {}
+    for implementation in graph["implementations"]:
+        source, target = implementation["source"], implementation["target"]
+        if source in symbols and target in symbols:
+            relationships.setdefault(source, {})[symbols[target]] = {
+                "symbol": symbols[target], "is_implementation": True,
+                "is_reference": definitions[source]["label"] == "Function",
+            }
+
+    for id_, symbol in symbols.items():
+        definition = definitions[id_]
+        document = documents[definition["file_path"]]
+        range_ = name_range(lines_of(definition["file_path"]), definition.get("line_number"),
+                            definition.get("column"), definition["name"])
+        if range_ is None:
+            line = max((definition.get("line_number") or 1) - 1, 0)
+            range_ = [line, 0, line, 0]
+        document["occurrences"].append({"range": range_, "symbol": symbol, "symbol_roles": ROLE_DEFINITION,
+                                        "enclosing_range": span_range(definition) or []})
+        if symbol in document["symbols"]:
+            continue
+        document["symbols"][symbol] = {
+            "symbol": symbol,
+            "kind": symbol_kind(definition),
+            "display_name": definition["name"],
+            "documentation": documentation(definition, document["language"]),
+            "enclosing_symbol": enclosing.get((definition["file_path"], definition.get("owner"), definition.get("context")), "")
+            if is_local(definition) else "",
+            "relationships": sorted(relationships.get(id_, {}).values(), key=lambda r: r["symbol"]),
+        }
+
+    for reference in graph["references"]:
+        target = definitions.get(reference["target"])
+        document = documents.get(reference["file_path"])
+        symbol = symbols.get(reference["target"])
+        if target is None or document is None or symbol is None:
+            continue
+        if symbol.startswith("local ") and reference["file_path"] != target["file_path"]:
+            continue
+        range_ = name_range(lines_of(reference["file_path"]), reference.get("line_number"),
+                            reference.get("column"), target["name"])
+        if range_ is None:
+            continue
+        roles = {"write": ROLE_WRITE_ACCESS, "read": ROLE_READ_ACCESS}.get(reference.get("access"), 0)
+        document["occurrences"].append({"range": range_, "symbol": symbol, "symbol_roles": roles, "enclosing_range": []})
+
+    result = []
+    for document in sorted(documents.values(), key=lambda d: d["relative_path"]):
+        unique = {(tuple(o["range"]), o["symbol"], o["symbol_roles"]): o for o in document["occurrences"]}
+        document["occurrences"] = [unique[key] for key in sorted(unique)]
+        document["symbols"] = [document["symbols"][symbol] for symbol in sorted(document["symbols"])]
+        result.append(document)
+    return result
+
+
+# Protobuf wire format: just what the messages of scip.proto written below need.
+
+def _varint(value: int) -> bytes:
+    out = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def _tag(field: int, wire_type: int) -> bytes:
+    return _varint(field << 3 | wire_type)
+
+
+def _uint(field: int, value: int) -> bytes:
+    return _tag(field, 0) + _varint(value) if value else b""
+
+
+def _bytes(field: int, value: bytes) -> bytes:
+    return _tag(field, 2) + _varint(len(value)) + value
+
+
+def _string(field: int, value: Optional[str]) -> bytes:
+    return _bytes(field, value.encode("utf-8")) if value else b""
+
+
+def _packed(field: int, values: List[int]) -> bytes:
+    return _bytes(field, b"".join(_varint(value) for value in values)) if values else b""
+
+
+def encode_relationship(relationship: Dict[str, Any]) -> bytes:
+    return (_string(1, relationship["symbol"]) + _uint(2, relationship.get("is_reference", False))
+            + _uint(3, relationship.get("is_implementation", False))
+            + _uint(4, relationship.get("is_type_definition", False))
+            + _uint(5, relationship.get("is_definition", False)))
+
+
+def encode_symbol(info: Dict[str, Any]) -> bytes:
+    return (_string(1, info["symbol"])
+            + b"".join(_string(3, doc) for doc in info.get("documentation", []))
+            + b"".join(_bytes(4, encode_relationship(r)) for r in info.get("relationships", []))
+            + _uint(5, info.get("kind", 0)) + _string(6, info.get("display_name"))
+            + _string(8, info.get("enclosing_symbol")))
+
+
+def encode_occurrence(occurrence: Dict[str, Any]) -> bytes:
+    return (_packed(1, _compact(occurrence["range"])) + _string(2, occurrence["symbol"])
+            + _uint(3, occurrence.get("symbol_roles", 0)) + _packed(7, _compact(occurrence.get("enclosing_range", []))))
+
+
+def encode_document(document: Dict[str, Any]) -> bytes:
+    return (_string(1, document["relative_path"])
+            + b"".join(_bytes(2, encode_occurrence(o)) for o in document["occurrences"])
+            + b"".join(_bytes(3, encode_symbol(s)) for s in document["symbols"])
+            + _string(4, document.get("language")) + _uint(6, POSITION_ENCODING_UTF8))
+
+
+def encode_index(documents: List[Dict[str, Any]], project_root: str, tool_version: str = "") -> bytes:
+    """The `scip.Index` message of the documents, with metadata for `project_root` (a directory)."""
+    tool_info = _string(1, TOOL_NAME) + _string(2, tool_version)
+    metadata = (_bytes(2, tool_info) + _string(3, Path(project_root).resolve().as_uri())
+                + _uint(4, TEXT_ENCODING_UTF8))
+    return _bytes(1, metadata) + b"".join(_bytes(2, encode_document(document)) for document in documents)
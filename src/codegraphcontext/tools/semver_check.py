@@ -0,0 +1,113 @@
+# src/codegraphcontext/tools/semver_check.py
+"""
+Classifies the differences between two snapshots of a crate's public API by the semver level
+they require, following the Cargo SemVer compatibility guidelines:
+
+- major: a public item, method, trait impl, enum variant or `pub` field is removed or changes
+  signature or kind; a trait gains a required method; an exhaustive enum gains a variant; a
+  struct whose fields are all `pub` gains a field.
+- minor: a public item, method, trait impl, provided trait method, variant of a
+  `#[non_exhaustive]` enum or field of a struct with private fields is added.
+- patch: the implementation of a function changes without changing its signature.
+
+A snapshot maps each public path to `{kind, signature, methods, trait_impls, required_methods,
+provided_methods, variants, fields, has_private_fields, non_exhaustive}`, where `methods` and
+`fields` map names to signatures and types.
+"""
+import re
+from typing import Any, Dict, List
+
+LEVELS = ("none", "patch", "minor", "major")
+
+
+def _normalize(signature) -> str:
+    return re.sub(r"\s+", " ", signature or "").strip()
+
+
+def _change(level: str, change: str, path: str, detail: str, **extra) -> Dict[str, Any]:
+    return {"level": level, "change": change, "path": path, "detail": detail, **extra}
+
+
+def _compare_item(path: str, old: Dict, new: Dict) -> List[Dict[str, Any]]:
+    """The changes between two versions of the same public item."""
+    if old["kind"] != new["kind"]:
+        return [_change("major", "kind_changed", path, f"{old['kind']} became {new['kind']}")]
+
+    changes = []
+    if _normalize(old["signature"]) != _normalize(new["signature"]):
+        changes.append(_change("major", "signature_changed", path, "signature changed",
+                               old_signature=old["signature"], signature=new["signature"]))
+
+    old_methods, new_methods = old.get("methods") or {}, new.get("methods") or {}
+    for name in sorted(old_methods.keys() - new_methods.keys()):
+        changes.append(_change("major", "method_removed", f"{path}::{name}", "public method removed"))
+    for name in sorted(old_methods.keys() & new_methods.keys()):
+        if _normalize(old_methods[name]) != _normalize(new_methods[name]):
+            changes.append(_change("major", "signature_changed", f"{path}::{name}", "method signature changed",
+                                   old_signature=old_methods[name], signature=new_methods[name]))
+    for name in sorted(new_methods.keys() - old_methods.keys()):
+        changes.append(_change("minor", "method_added", f"{path}::{name}", "public method added"))
+
+    old_impls, new_impls = set(old.get("trait_impls") or []), set(new.get("trait_impls") or [])
+    for trait in sorted(old_impls - new_impls):
+        changes.append(_change("major", "trait_impl_removed", path, f"no longer implements {trait}"))
+    for trait in sorted(new_impls - old_impls):
+        changes.append(_change("minor", "trait_impl_added", path, f"now implements {trait}"))
+
+    old_required, new_required = set(old.get("required_methods") or []), set(new.get("required_methods") or [])
+    old_provided, new_provided = set(old.get("provided_methods") or []), set(new.get("provided_methods") or [])
+    for name in sorted(new_required - old_required):
+        detail = "provided method lost its default" if name in old_provided else "new required trait method"
+        changes.append(_change("major", "required_method_added", f"{path}::{name}", detail))
+    for name in sorted((old_required | old_provided) - (new_required | new_provided)):
+        changes.append(_change("major", "trait_item_removed", f"{path}::{name}", "trait method removed"))
+    for name in sorted(new_provided - old_required - old_provided):
+        changes.append(_change("minor", "provided_method_added", f"{path}::{name}", "new trait method with a default"))
+
+    old_variants, new_variants = set(old.get("variants") or []), set(new.get("variants") or [])
+    for name in sorted(old_variants - new_variants):
+        changes.append(_change("major", "variant_removed", f"{path}::{name}", "enum variant removed"))
+    for name in sorted(new_variants - old_variants):
+        if old.get("non_exhaustive"):
+            changes.append(_change("minor", "variant_added", f"{path}::{name}", "variant added to a #[non_exhaustive] enum"))
+        else:
+            changes.append(_change("major", "variant_added", f"{path}::{name}", "variant added to an exhaustive enum"))
+
+    old_fields, new_fields = old.get("fields") or {}, new.get("fields") or {}
+    for name in sorted(old_fields.keys() - new_fields.keys()):
+        changes.append(_change("major", "field_removed", f"{path}::{name}", "public field removed"))
+    for name in sorted(old_fields.keys() & new_fields.keys()):
+        if _normalize(old_fields[name]) != _normalize(new_fields[name]):
+            changes.append(_change("major", "field_type_changed", f"{path}::{name}",
+                                   f"field type changed from {old_fields[name]} to {new_fields[name]}"))
+    for name in sorted(new_fields.keys() - old_fields.keys()):
+        if old.get("has_private_fields") or old.get("non_exhaustive"):
+            changes.append(_change("minor", "field_added", f"{path}::{name}", "public field added"))
+        else:
+            changes.append(_change("major", "field_added", f"{path}::{name}",
+                                   "field added to a struct constructible with a literal"))
+    return changes
+
+
+def classify_changes(base_api: Dict[str, Dict], head_api: Dict[str, Dict],
+                     changed_functions: List[Dict]) -> Dict[str, Any]:
+    """
+    Compares two public API snapshots and returns every change with its semver level, the overall
+    `level` (the highest one, `none` without changes) and the number of changes per level.
+    `changed_functions` are the functions whose body changed (from the graph diff); those whose
+    signature stayed the same count as patch-level changes.
+    """
+    changes = []
+    for path in sorted(base_api.keys() - head_api.keys()):
+        changes.append(_change("major", "removed", path, f"public {base_api[path]['kind']} removed"))
+    for path in sorted(base_api.keys() & head_api.keys()):
+        changes.extend(_compare_item(path, base_api[path], head_api[path]))
+    for path in sorted(head_api.keys() - base_api.keys()):
+        changes.append(_change("minor", "added", path, f"public {head_api[path]['kind']} added"))
+    for func in changed_functions:
+        if not func.get("signature_changed"):
+            changes.append(_change("patch", "implementation_changed", func["name"], f"body changed in {func['file']}"))
+
+    summary = {level: sum(1 for c in changes if c["level"] == level) for level in LEVELS[1:]}
+    level = max((c["level"] for c in changes), key=LEVELS.index, default="none")
+    return {"level": level, "summary": summary, "changes": changes}
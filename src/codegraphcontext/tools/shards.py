@@ -0,0 +1,38 @@
+# src/codegraphcontext/tools/shards.py
+"""
+Splits a repository into shards indexed independently, one per Cargo package.
+
+Every file belongs to the package of the nearest directory above it (up to the repository root)
+holding a Cargo.toml, so the members of a workspace are separate shards. Files outside any package
+(scripts, the workspace's own Cargo-less tooling) form the root shard, keyed by the repository root.
+Shards write their definitions in parallel; references between them are stitched afterwards by
+linking every file once all definitions are in.
+"""
+from pathlib import Path
+from typing import Callable, Dict, List, Optional
+
+
+def shard_root(file_path: Path, repo_root: Path, is_package: Callable[[Path], bool]) -> Path:
+    """The root of the shard holding `file_path`: its nearest package directory, else `repo_root`."""
+    for directory in Path(file_path).parents:
+        if directory != repo_root and repo_root not in directory.parents:
+            break
+        if is_package(directory):
+            return directory
+    return repo_root
+
+
+def plan_shards(repo_root: Path, files: List[Path], package_name: Callable[[Path], Optional[str]],
+                is_package: Callable[[Path], bool] = lambda directory: (directory / "Cargo.toml").is_file()) -> List[Dict]:
+    """
+    The shards of a repository as `{name, path, files}`, largest first so parallel workers start
+    on the slowest ones. Shards are named after their package (`package_name`), or their directory.
+    """
+    repo_root = Path(repo_root).resolve()
+    grouped: Dict[Path, List[str]] = {}
+    for file in files:
+        file = Path(file).resolve()
+        grouped.setdefault(shard_root(file, repo_root, is_package), []).append(str(file))
+    shards = [{"name": package_name(root) or root.name, "path": str(root), "files": sorted(paths)}
+              for root, paths in grouped.items()]
+    return sorted(shards, key=lambda shard: (-len(shard["files"]), shard["path"]))
@@ -0,0 +1,190 @@
+# src/codegraphcontext/tools/signature_search.py
+"""
+Hoogle-style matching of Rust function signatures against patterns such as
+`fn(&[i32]) -> Option<i32>` or `Fn(i32) -> i32`.
+
+Types are parsed into small terms and unified with a cost: an exact match costs nothing, while
+each tolerated difference (a reference added or dropped, `&mut` for `&`, a slice for a `Vec`,
+a generic parameter standing in for a concrete type, parameters in another order) costs one.
+Single capital letters and `_` in a pattern are wildcards, bound consistently across it.
+"""
+import itertools
+import re
+from typing import Dict, List, Optional, Tuple
+
+CALLABLE_TRAITS = ('fn', 'Fn', 'FnMut', 'FnOnce')
+UNIT = ('()', ())
+
+
+def _split_top_level(text: str, separator: str = ',') -> List[str]:
+    parts, depth, current = [], 0, ""
+    for i, char in enumerate(text):
+        if char in '<([':
+            depth += 1
+        elif char in ')]' or (char == '>' and text[i - 1:i] != '-'):
+            depth -= 1
+        if char == separator and depth == 0:
+            parts.append(current.strip())
+            current = ""
+        else:
+            current += char
+    if current.strip():
+        parts.append(current.strip())
+    return parts
+
+
+def _split_arrow(text: str) -> Tuple[str, Optional[str]]:
+    """Splits `(A, B) -> R` at its top-level `->`."""
+    depth = 0
+    for i, char in enumerate(text):
+        if char in '<([':
+            depth += 1
+        elif char in ')]' or (char == '>' and text[i - 1:i] != '-'):
+            depth -= 1
+        elif char == '-' and text[i + 1:i + 2] == '>' and depth == 0:
+            return text[:i].strip(), text[i + 2:].strip()
+    return text.strip(), None
+
+
+def parse_type(text: str):
+    """
+    Parses a type into a term: `('&', (mutable, inner))`, `('[]', (element,))`, `('()', items)`,
+    `('fn', (params, return))` for fn pointers and `Fn*` bounds, or `(name, type_arguments)`.
+    Lifetimes, `dyn`/`impl`, `+ Send`-style extra bounds and path prefixes are dropped.
+    """
+    text = re.sub(r"'\w+\s*", '', text or '').strip().rstrip(',').strip()
+    text = re.sub(r"^(dyn|impl)\s+", '', text)
+    bounds = _split_top_level(text, '+')
+    text = bounds[0] if bounds else ''
+    if not text:
+        return UNIT
+
+    if text.startswith('&'):
+        rest = text[1:].strip()
+        mutable = rest.startswith('mut ')
+        return '&', (mutable, parse_type(rest[4:] if mutable else rest))
+    if text.startswith('*'):
+        return parse_type(re.sub(r"^\*\s*(const|mut)\s+", '', text))
+
+    head = re.match(r"^(?:[\w]+::)*(fn|Fn|FnMut|FnOnce)\s*\(", text)
+    if head:
+        params_text, return_text = _split_arrow(text[head.end() - 1:])
+        params = tuple(parse_type(p) for p in _split_top_level(params_text.strip()[1:-1]))
+        return 'fn', (params, parse_type(return_text) if return_text else UNIT)
+
+    if text[0] == '[':
+        return '[]', (parse_type(text[1:text.rfind(']')].split(';')[0]),)
+    if text[0] == '(':
+        items = tuple(parse_type(p) for p in _split_top_level(text[1:text.rfind(')')]))
+        return ('()', items) if len(items) != 1 or text.rstrip(')').rstrip().endswith(',') else items[0]
+
+    if '<' in text:
+        base = text[:text.index('<')].split('::')[-1].strip()
+        args = tuple(parse_type(a) for a in _split_top_level(text[text.index('<') + 1:text.rfind('>')]))
+        return base, args
+    return text.split('::')[-1].strip(), ()
+
+
+def parse_signature(pattern: str):
+    """Parses a search pattern into a `('fn', (params, return))` term; a bare type matches return types."""
+    term = parse_type(pattern)
+    if term[0] == 'fn':
+        return term
+    params_text, return_text = _split_arrow(pattern)
+    if return_text is not None:
+        params = params_text[1:-1] if params_text.startswith('(') else params_text
+        return 'fn', (tuple(parse_type(p) for p in _split_top_level(params)), parse_type(return_text))
+    return 'fn', (None, term)
+
+
+def _is_wildcard(term) -> bool:
+    return not term[1] and (term[0] == '_' or re.fullmatch(r"[A-Z]", term[0] or '') is not None)
+
+
+def unify(pattern, actual, generics, bindings: Dict[str, object]) -> Optional[int]:
+    """Returns the cost of matching `actual` against `pattern`, or None when they cannot match."""
+    if _is_wildcard(pattern):
+        bound = bindings.setdefault(pattern[0], actual)
+        return 0 if pattern[0] == '_' or bound == actual else None
+    if not actual[1] and actual[0] in generics:
+        bound = bindings.setdefault(f"generic:{actual[0]}", pattern)
+        return 1 if bound == pattern else None
+
+    if pattern[0] == '&' and actual[0] == '&':
+        cost = unify(pattern[1][1], actual[1][1], generics, bindings)
+        return None if cost is None else cost + (pattern[1][0] != actual[1][0])
+    if pattern[0] == '&':
+        cost = unify(pattern[1][1], actual, generics, bindings)
+        return None if cost is None else cost + 1
+    if actual[0] == '&':
+        cost = unify(pattern, actual[1][1], generics, bindings)
+        return None if cost is None else cost + 1
+
+    if pattern[0] == 'fn' and actual[0] == 'fn':
+        return unify_signature(pattern, actual, generics, bindings)
+    if {pattern[0], actual[0]} == {'[]', 'Vec'} and len(pattern[1]) == len(actual[1]) == 1:
+        cost = unify(pattern[1][0], actual[1][0], generics, bindings)
+        return None if cost is None else cost + 1
+    if pattern[0] != actual[0] or len(pattern[1]) != len(actual[1]):
+        return None
+
+    total = 0
+    for p, a in zip(pattern[1], actual[1]):
+        cost = unify(p, a, generics, bindings)
+        if cost is None:
+            return None
+        total += cost
+    return total
+
+
+def unify_signature(pattern, actual, generics, bindings: Dict[str, object]) -> Optional[int]:
+    """Matches two `fn` terms; parameters may come in another order at a cost of one."""
+    pattern_params, pattern_return = pattern[1]
+    actual_params, actual_return = actual[1]
+    return_cost = unify(pattern_return, actual_return, generics, bindings)
+    if return_cost is None:
+        return None
+    if pattern_params is None:
+        return return_cost
+    if len(pattern_params) != len(actual_params):
+        return None
+
+    best = None
+    orders = itertools.permutations(actual_params) if len(actual_params) <= 4 else [tuple(actual_params)]
+    for order in orders:
+        attempt = dict(bindings)
+        cost = 0 if tuple(order) == tuple(actual_params) else 1
+        for p, a in zip(pattern_params, order):
+            param_cost = unify(p, a, generics, attempt)
+            if param_cost is None:
+                cost = None
+                break
+            cost += param_cost
+        if cost is not None and (best is None or cost < best[0]):
+            best = (cost, attempt)
+    if best is None:
+        return None
+    bindings.update(best[1])
+    return return_cost + best[0]
+
+
+def match_function(pattern, param_types: List[str], return_type: Optional[str], generics) -> Optional[Tuple[int, str]]:
+    """
+    Matches a parsed pattern against a function. Returns `(cost, how)` where `how` is `signature`
+    when the function itself matches, or `returns` when it returns a callable matching the pattern
+    (e.g. `make_adder` for `Fn(i32) -> i32`); None when neither does.
+    """
+    actual = ('fn', (tuple(parse_type(t) for t in param_types), parse_type(return_type) if return_type else UNIT))
+    generics = set(generics or ())
+    cost = unify_signature(pattern, actual, generics, {})
+    if cost is not None:
+        return cost, 'signature'
+
+    returned = actual[1][1]
+    while returned[0] in ('Box', 'Rc', 'Arc') and len(returned[1]) == 1:
+        returned = returned[1][0]
+    if returned[0] == 'fn' and pattern[1][0] is not None:
+        cost = unify_signature(pattern, returned, generics, {})
+        if cost is not None:
+            return cost, 'returns'
+    return None
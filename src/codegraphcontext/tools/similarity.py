@@ -0,0 +1,39 @@
+# src/codegraphcontext/tools/similarity.py
+"""
+Structural similarity of functions, for finding helpers that already exist.
+
+The normalized token stream of a function (identifiers and literals replaced, see the parsers'
+`_structural_hash`) is cut into overlapping shingles of `SHINGLE_SIZE` tokens, and a MinHash
+signature of `SIGNATURE_SIZE` values is kept per function (`shape_minhash`). The share of equal
+values between two signatures estimates the Jaccard similarity of their shingle sets, so
+functions with the same shape up to a few inserted or changed statements score close to 1.
+"""
+import hashlib
+from typing import List, Optional, Sequence
+
+SHINGLE_SIZE = 4
+SIGNATURE_SIZE = 32
+_PRIME = (1 << 61) - 1
+# Fixed coefficients of the hash permutations, so signatures stay comparable between runs.
+_PERMUTATIONS = [
+    (int.from_bytes(hashlib.sha1(f"a{i}".encode()).digest()[:8], "big") % (_PRIME - 1) + 1,
+     int.from_bytes(hashlib.sha1(f"b{i}".encode()).digest()[:8], "big") % _PRIME)
+    for i in range(SIGNATURE_SIZE)
+]
+
+
+def shape_signature(tokens: Sequence[str]) -> List[int]:
+    """The MinHash signature of the token shingles of a function."""
+    if not tokens:
+        return []
+    shingles = {" ".join(tokens[i:i + SHINGLE_SIZE]) for i in range(max(1, len(tokens) - SHINGLE_SIZE + 1))}
+    hashes = [int.from_bytes(hashlib.blake2b(shingle.encode("utf-8"), digest_size=8).digest(), "big") % _PRIME
+              for shingle in shingles]
+    return [min((a * h + b) % _PRIME for h in hashes) for a, b in _PERMUTATIONS]
+
+
+def shape_similarity(a: Optional[Sequence[int]], b: Optional[Sequence[int]]) -> Optional[float]:
+    """Estimated Jaccard similarity of two signatures, or None when either is missing."""
+    if not a or not b or len(a) != len(b):
+        return None
+    return sum(x == y for x, y in zip(a, b)) / len(a)
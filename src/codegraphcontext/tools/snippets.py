@@ -0,0 +1,98 @@
+# src/codegraphcontext/tools/snippets.py
+"""
+Optional compression of the source snippets stored on Function, Class and Variable nodes.
+
+Every item keeps its full source text on its node (`source`, and `source_code` for the older
+readers), which makes up most of the database on large repositories. With
+`CGC_COMPRESS_SNIPPETS=zstd` (or `zlib`, or `on` for zstd) the items written from then on store
+it compressed instead, as the bytes of `source_compressed`; snippets shorter than
+COMPRESS_MIN_BYTES stay plain text, as compressing them saves nothing. zstd needs the `zstandard`
+package (`pip install codegraphcontext[compression]`); without it, zlib is used.
+
+Readers go through `source_text` (or `expand_tree` for whole result rows), which decompress whatever
+they are given, telling the codec from the frame's magic bytes, and pass plain text through, so a
+graph can hold both. Compressed snippets are not in the full-text index: content search matches
+their names and doc comments only.
+"""
+import logging
+import os
+import zlib
+from typing import Any, Dict, Optional
+
+logger = logging.getLogger(__name__)
+
+COMPRESSED_KEY = "source_compressed"
+COMPRESS_MIN_BYTES = 256
+ZSTD_MAGIC = b"\x28\xb5\x2f\xfd"
+ZLIB_LEVEL = 6
+ZSTD_LEVEL = 3
+
+try:
+    import zstandard
+except ImportError:
+    zstandard = None
+
+
+def codec_from_env() -> Optional[str]:
+    """The codec set by CGC_COMPRESS_SNIPPETS (`zstd` or `zlib`), or None when snippets are stored plain."""
+    value = os.getenv("CGC_COMPRESS_SNIPPETS", "").strip().lower()
+    if value in ("", "0", "off", "false", "no", "none"):
+        return None
+    codec = "zstd" if value in ("1", "on", "true", "yes") else value
+    if codec not in ("zstd", "zlib"):
+        raise ValueError(f"Unknown snippet compression {value!r}: use zstd, zlib or off")
+    if codec == "zstd" and zstandard is None:
+        logger.warning("zstandard is not installed, compressing snippets with zlib")
+        return "zlib"
+    return codec
+
+
+def compress(text: str, codec: str) -> bytes:
+    data = text.encode("utf-8")
+    if codec == "zstd":
+        return zstandard.ZstdCompressor(level=ZSTD_LEVEL).compress(data)
+    return zlib.compress(data, ZLIB_LEVEL)
+
+
+def source_text(value: Any) -> Any:
+    """The source text of a stored snippet: compressed bytes are decompressed, anything else is returned as is."""
+    if not isinstance(value, (bytes, bytearray)):
+        return value
+    data = bytes(value)
+    if data.startswith(ZSTD_MAGIC):
+        if zstandard is None:
+            raise RuntimeError("This snippet is zstd-compressed: install zstandard to read it")
+        return zstandard.ZstdDecompressor().decompress(data).decode("utf-8")
+    return zlib.decompress(data).decode("utf-8")
+
+
+def stored_properties(item: Dict[str, Any], codec: Optional[str]) -> Dict[str, Any]:
+    """
+    The properties to write for a parsed item: with a codec and a long enough source, a copy
+    whose source is compressed (the plain properties set to None, so writing them removes them);
+    otherwise a copy removing any compressed source of an earlier write.
+    """
+    source = item.get("source") or item.get("source_code")
+    if not codec or not isinstance(source, str) or len(source.encode("utf-8")) < COMPRESS_MIN_BYTES:
+        return {**item, COMPRESSED_KEY: None}
+    return {**item, "source": None, "source_code": None, COMPRESSED_KEY: compress(source, codec)}
+
+
+def expand_tree(value: Any) -> Any:
+    """
+    Decompresses the snippets in query results (rows, node property maps, lists): a map holding
+    `source_compressed` gets its text back as `source`, and bare compressed bytes become text.
+    """
+    if isinstance(value, dict):
+        expanded = {key: expand_tree(item) for key, item in value.items() if key != COMPRESSED_KEY}
+        if value.get(COMPRESSED_KEY) is not None:
+            expanded["source"] = source_text(value[COMPRESSED_KEY])
+        return expanded
+    if isinstance(value, list):
+        return [expand_tree(item) for item in value]
+    if isinstance(value, (bytes, bytearray)):
+        try:
+            return source_text(value)
+        except Exception:
+            return value  # bytes that are not a snippet
+    return value
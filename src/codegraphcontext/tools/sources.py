@@ -0,0 +1,85 @@
+# src/codegraphcontext/tools/sources.py
+"""
+Reads source files for parsing and hashing, and spots byte-identical ones.
+
+Files are memory-mapped rather than read: hashing a file (`incremental.file_hash`) feeds the
+mapping to the hash without copying it, and parsing copies it once into the bytes tree-sitter
+takes, instead of decoding it to text and encoding it back. Line endings are normalized to `\\n`
+as reading in text mode did, so parse results are unchanged.
+
+Large repositories hold many byte-identical files: vendored copies of a crate, generated code
+checked in twice, the same fixture in several test directories. `ContentIndex` remembers the
+first file indexed with each content hash; the next ones are recorded as aliases of its File
+node (File-[:ALIAS_OF]->File) instead of being parsed and written again. Files smaller than
+DEDUP_MIN_BYTES are always indexed on their own, since a `lib.rs` or `mod.rs` made of the same
+few `mod` lines declares different modules in every crate.
+"""
+import hashlib
+import mmap
+import threading
+from contextlib import contextmanager
+from pathlib import Path
+from typing import Dict, Iterator, Optional, Union
+
+DEDUP_MIN_BYTES = 1024
+
+
+@contextmanager
+def mapped(path: Union[str, Path]) -> Iterator[Union[mmap.mmap, bytes]]:
+    """A read-only mapping of a file's bytes (empty files, which cannot be mapped, as b"")."""
+    with open(path, "rb") as f:
+        if f.seek(0, 2) == 0:
+            yield b""
+            return
+        with mmap.mmap(f.fileno(), 0, access=mmap.ACCESS_READ) as mapping:
+            yield mapping
+
+
+def read_source(path: Union[str, Path]) -> bytes:
+    """The bytes of a source file to parse, with `\\r\\n` and `\\r` line endings as `\\n`."""
+    with mapped(path) as mapping:
+        has_cr = mapping.find(b"\r") != -1
+        data = mapping[:]
+    if has_cr:
+        data = data.replace(b"\r\n", b"\n").replace(b"\r", b"\n")
+    return data
+
+
+def content_hash(path: Union[str, Path]) -> str:
+    """The hex SHA-256 of a file's bytes, truncated to 32 characters; raises OSError when unreadable."""
+    with mapped(path) as mapping:
+        return hashlib.sha256(mapping).hexdigest()[:32]
+
+
+class ContentIndex:
+    """The first file seen with each content, to alias byte-identical files to; thread-safe."""
+
+    def __init__(self, min_bytes: int = DEDUP_MIN_BYTES):
+        self.min_bytes = min_bytes
+        self.first: Dict[str, str] = {}
+        self.aliases = 0
+        self.lock = threading.Lock()
+
+    def claim(self, path: Union[str, Path]) -> Optional[str]:
+        """
+        The path of an earlier file with the same bytes as `path`, which `path` is an alias of;
+        None when `path` is the first with its content, too small to dedup or unreadable.
+        """
+        path = Path(path)
+        try:
+            if path.stat().st_size < self.min_bytes:
+                return None
+            digest = content_hash(path)
+        except OSError:
+            return None
+        with self.lock:
+            first = self.first.setdefault(digest, str(path))
+            if first == str(path):
+                return None
+            self.aliases += 1
+            return first
+
+    def forget(self, path: Union[str, Path]):
+        """Stops aliasing files to `path`, e.g. when it failed to parse."""
+        with self.lock:
+            self.first = {digest: first for digest, first in self.first.items() if first != str(path)}
@@ -0,0 +1,129 @@
+# src/codegraphcontext/tools/spill.py
+"""
+Bounds the memory indexing holds on to between its two phases.
+
+Every parsed file is kept from the moment its definitions are written until the linking passes
+have run over all of them, which for a giant repository is more than a laptop's memory.
+`SpillList` is the list they are kept in: it holds parsed files in memory up to a byte budget
+(estimated from the sizes of their objects, see `estimate_size`) and pickles the rest to an
+anonymous temporary file, reading them back one at a time whenever the list is iterated. Only
+spilled files are ever pickled. The linking passes only read parsed files, so the copies read back
+(their strings interned again, see `interning`) behave like the originals.
+"""
+import logging
+import os
+import pickle
+import sys
+import tempfile
+import threading
+from typing import Any, Iterator, List, Optional, Tuple
+
+from .interning import intern_tree
+
+logger = logging.getLogger(__name__)
+
+# Budget for parsed files kept in memory during a build; override with CGC_MEMORY_LIMIT_MB (0 disables spilling).
+DEFAULT_MEMORY_LIMIT_MB = 512
+
+
+def memory_limit_bytes() -> Optional[int]:
+    """The budget of CGC_MEMORY_LIMIT_MB in bytes, None when spilling is disabled."""
+    megabytes = float(os.getenv("CGC_MEMORY_LIMIT_MB", DEFAULT_MEMORY_LIMIT_MB))
+    return int(megabytes * 1024 * 1024) if megabytes > 0 else None
+
+
+def estimate_size(tree: Any) -> int:
+    """
+    The bytes held by a tree of dicts, lists and tuples: the `sys.getsizeof` of each of its objects,
+    counted once however often they recur in it. Strings shared with other trees count in each.
+    """
+    seen, total, stack = set(), 0, [tree]
+    while stack:
+        node = stack.pop()
+        if id(node) in seen:
+            continue
+        seen.add(id(node))
+        total += sys.getsizeof(node)
+        if isinstance(node, dict):
+            stack.extend(node.keys())
+            stack.extend(node.values())
+        elif isinstance(node, (list, tuple)):
+            stack.extend(node)
+    return total
+
+
+class SpillList:
+    """
+    An append-only list that keeps its first items in memory up to `limit` bytes and spills the
+    rest to a temporary file in `directory` (the system's temporary directory by default).
+    Appending is thread-safe; call `close` (or use it as a context manager) to delete the file.
+    """
+
+    def __init__(self, limit: Optional[int] = None, directory: Optional[str] = None):
+        self.limit = limit
+        self.directory = directory
+        # Per item, either (item, None) or (None, (offset, size)) into the spill file.
+        self.entries: List[Tuple[Any, Optional[Tuple[int, int]]]] = []
+        self.memory_bytes = 0
+        self.spilled_bytes = 0
+        self.file = None
+        self.lock = threading.Lock()
+
+    def append(self, item: Any):
+        size = estimate_size(item) if self.limit is not None else 0
+        with self.lock:
+            if self.limit is None or self.memory_bytes + size <= self.limit:
+                self.memory_bytes += size
+                self.entries.append((item, None))
+                return
+            data = pickle.dumps(item, protocol=pickle.HIGHEST_PROTOCOL)
+            if self.file is None:
+                self.file = tempfile.TemporaryFile(prefix="cgc-spill-", dir=self.directory)
+                logger.info(f"Memory limit of {self.limit // (1024 * 1024)} MB reached after {len(self.entries)} files, spilling the rest to disk")
+            self.file.seek(0, os.SEEK_END)
+            offset = self.file.tell()
+            self.file.write(data)
+            self.spilled_bytes += len(data)
+            self.entries.append((None, (offset, len(data))))
+
+    def extend(self, items):
+        for item in items:
+            self.append(item)
+
+    def _load(self, entry: Tuple[Any, Optional[Tuple[int, int]]]) -> Any:
+        item, location = entry
+        if location is None:
+            return item
+        with self.lock:
+            self.file.seek(location[0])
+            data = self.file.read(location[1])
+        return intern_tree(pickle.loads(data))
+
+    def __len__(self) -> int:
+        return len(self.entries)
+
+    def __getitem__(self, index: int) -> Any:
+        return self._load(self.entries[index])
+
+    def __iter__(self) -> Iterator[Any]:
+        # By index, so items appended while iterating are visited too, as with a list.
+        index = 0
+        while index < len(self.entries):
+            yield self._load(self.entries[index])
+            index += 1
+
+    @property
+    def spilled(self) -> int:
+        """How many items live on disk."""
+        return sum(1 for _, location in self.entries if location is not None)
+
+    def close(self):
+        if self.file is not None:
+            self.file.close()
+            self.file = None
+
+    def __enter__(self) -> "SpillList":
+        return self
+
+    def __exit__(self, *exc_info):
+        self.close()
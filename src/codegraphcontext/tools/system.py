@@ -9,6 +9,7 @@ from neo4j.exceptions import CypherSyntaxError
 from ..core.database import DatabaseManager
 from ..core.jobs import JobManager, JobStatus
 from ..utils.debug_log import debug_log
+from .snippets import expand_tree
 
 logger = logging.getLogger(__name__)
 
@@ -88,7 +89,7 @@ class SystemTools:
         try:
             with self.db_manager.get_driver().session() as session:
                 result = session.run(cypher_query)
-                records = [record.data() for record in result]
+                records = [expand_tree(record.data()) for record in result]
                 return {
                     "success": True,
                     "query": cypher_query,
@@ -0,0 +1,110 @@
+# src/codegraphcontext/tools/taint.py
+"""
+Source-to-sink taint analysis over the call graph.
+
+A source is a call that brings outside data in (`std::env::args`, `File::open`, or an indexed
+function such as `read_file_contents`) and a sink is a call that must not receive it
+(`Command::new`, SQL execution). Patterns match the calls recorded on each function
+(`call_names`) by their last path segments, so `Command::new` matches
+`std::process::Command::new` and `read_to_string` matches `File::read_to_string`.
+
+Data read by a source call in function F can travel up to F's callers through return values and
+down from any of them to their callees through arguments. A finding is a chain
+`F <- ... <- C -> ... -> G` where G makes a sink call. This works at the level of functions, not
+variables, so a finding means "the data could get there", to be confirmed by reading the chain.
+"""
+import re
+from collections import deque
+from typing import Any, Dict, Iterable, List, Optional
+
+DEFAULT_SOURCES = [
+    "std::env::args", "std::env::args_os", "std::env::var", "std::env::var_os", "std::io::stdin", "Stdin::read_line",
+    "std::fs::read", "std::fs::read_to_string", "File::open", "File::read_to_string", "TcpStream::read",
+    "UdpSocket::recv_from", "reqwest::get", "Request::body", "Query::into_inner", "Json::into_inner", "Form::into_inner",
+]
+DEFAULT_SINKS = [
+    "Command::new", "Command::arg", "Command::args", "std::fs::write", "std::fs::remove_file", "std::fs::remove_dir_all",
+    "File::create", "Connection::execute", "Client::execute", "Client::query", "sqlx::query", "diesel::sql_query",
+    "libc::system", "Library::new",
+]
+
+
+def _normalize_call(name: str) -> str:
+    return re.sub(r"::<.*?>", "", name or "").replace(".", "::")
+
+
+def call_matches(pattern: str, call_name: str) -> bool:
+    """Whether `call_name` ends with the path segments of `pattern`."""
+    call, pattern = _normalize_call(call_name), _normalize_call(pattern)
+    return call == pattern or call.endswith(f"::{pattern}")
+
+
+def _matching_calls(func: Dict[str, Any], patterns: Iterable[str]) -> List[Dict[str, Any]]:
+    calls = []
+    for name, line in zip(func.get("call_names") or [], func.get("call_lines") or []):
+        matched = next((p for p in patterns if call_matches(p, name)), None)
+        if matched:
+            calls.append({"call": name, "pattern": matched, "line_number": line})
+    return calls
+
+
+def _walk(start: str, neighbours: Dict[str, List[str]], max_depth: int) -> Dict[str, List[str]]:
+    """Shortest paths (as node lists, `start` first) to every node within `max_depth` hops."""
+    paths = {start: [start]}
+    queue = deque([start])
+    while queue:
+        node = queue.popleft()
+        if len(paths[node]) > max_depth:
+            continue
+        for neighbour in neighbours.get(node, []):
+            if neighbour not in paths:
+                paths[neighbour] = paths[node] + [neighbour]
+                queue.append(neighbour)
+    return paths
+
+
+def find_flows(functions: Dict[str, Dict[str, Any]], calls: List[tuple], sources: Optional[List[str]] = None,
+               sinks: Optional[List[str]] = None, max_depth: int = 4, limit: int = 50) -> List[Dict[str, Any]]:
+    """
+    Finds source-to-sink chains. `functions` maps ids to `{name, owner, file_path, line_number,
+    call_names, call_lines}` and `calls` holds `(caller_id, callee_id)` pairs. Returns one finding
+    per source call and sink call, through the shortest chain, shortest findings first.
+    """
+    sources, sinks = sources or DEFAULT_SOURCES, sinks or DEFAULT_SINKS
+    callers, callees = {}, {}
+    for caller, callee in calls:
+        callees.setdefault(caller, []).append(callee)
+        callers.setdefault(callee, []).append(caller)
+    sink_calls = {fid: _matching_calls(func, sinks) for fid, func in functions.items()}
+
+    def step(fid, role):
+        func = functions[fid]
+        return {"name": func["name"], "owner": func.get("owner"), "file_path": func["file_path"],
+                "line_number": func["line_number"], "role": role}
+
+    findings = {}
+    for fid, func in functions.items():
+        source_calls = _matching_calls(func, sources)
+        if not source_calls:
+            continue
+        for meeting, up in _walk(fid, callers, max_depth).items():
+            for reached, down in _walk(meeting, callees, max_depth).items():
+                if not sink_calls.get(reached):
+                    continue
+                chain = [step(node, "returns to") for node in up[:-1]] + [step(meeting, "passes to")] \
+                    + [step(node, "passes to") for node in down[1:]]
+                chain[0]["role"] = "reads source"
+                chain[-1]["role"] = "calls sink"
+                for source in source_calls:
+                    for sink in sink_calls[reached]:
+                        key = (fid, source["line_number"], source["call"], reached, sink["line_number"], sink["call"])
+                        if key not in findings or len(chain) < len(findings[key]["chain"]):
+                            findings[key] = {
+                                "source": {**source, "function": func["name"], "file_path": func["file_path"]},
+                                "sink": {**sink, "function": functions[reached]["name"],
+                                         "file_path": functions[reached]["file_path"]},
+                                "chain": chain,
+                                "length": len(chain),
+                            }
+    ordered = sorted(findings.values(), key=lambda f: (f["length"], f["source"]["file_path"], f["source"]["line_number"]))
+    return ordered[:limit]
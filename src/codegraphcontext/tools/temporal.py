@@ -0,0 +1,69 @@
+# src/codegraphcontext/tools/temporal.py
+"""
+Builds the history of a repository's code graph from snapshots of successive revisions.
+
+Revisions are numbered by their ordinal in the recorded sequence (oldest first). An item or call
+edge is valid over one or more ranges of ordinals, stored as two parallel lists: `valid_from` holds
+the first revision of each range and `valid_to` the first revision after it where the item is gone,
+or -1 when it is still there in the last recorded revision. A function removed in revision 2 and
+brought back in revision 4 of 5 has `valid_from = [0, 4]` and `valid_to = [2, -1]`.
+
+A snapshot is `{"items": {key: {..., "fingerprint"}}, "calls": {(caller_key, callee_key)}}`, where
+keys identify the same item across revisions (file relative to the snapshot root, owner,
+implemented trait and name) and `fingerprint` changes when its code does.
+"""
+from typing import Any, Dict, List, Optional, Tuple
+
+OPEN = -1
+
+
+def intervals(present: List[bool]) -> Tuple[List[int], List[int]]:
+    """The `(valid_from, valid_to)` ranges of an item present in the revisions flagged in `present`."""
+    valid_from, valid_to = [], []
+    for ordinal, here in enumerate(present):
+        before = ordinal > 0 and present[ordinal - 1]
+        if here and not before:
+            valid_from.append(ordinal)
+        elif before and not here:
+            valid_to.append(ordinal)
+    if len(valid_to) < len(valid_from):
+        valid_to.append(OPEN)
+    return valid_from, valid_to
+
+
+def valid_at(valid_from: List[int], valid_to: List[int], ordinal: int) -> bool:
+    """Whether an item with these ranges exists in revision `ordinal`."""
+    return any(start <= ordinal and (end == OPEN or ordinal < end) for start, end in zip(valid_from, valid_to))
+
+
+def build_history(snapshots: List[Dict[str, Any]]) -> Dict[str, Any]:
+    """
+    Merges snapshots into `{"items": {key: {...latest attributes, valid_from, valid_to, changed_in}},
+    "calls": {(caller_key, callee_key): {valid_from, valid_to}}}`; `changed_in` lists the
+    revisions where an item existed before and its fingerprint changed.
+    """
+    items, calls = {}, {}
+    for snapshot in snapshots:
+        for key, item in snapshot["items"].items():
+            items[key] = {**item, "changed_in": items.get(key, {}).get("changed_in", [])}
+        for edge in snapshot["calls"]:
+            calls[edge] = {}
+
+    for key, item in items.items():
+        present = [key in snapshot["items"] for snapshot in snapshots]
+        item["valid_from"], item["valid_to"] = intervals(present)
+        previous = None
+        for ordinal, snapshot in enumerate(snapshots):
+            current = snapshot["items"].get(key)
+            if current is not None and previous is not None and current.get("fingerprint") != previous.get("fingerprint"):
+                item["changed_in"].append(ordinal)
+            previous = current if current is not None else previous
+    for edge in calls:
+        calls[edge]["valid_from"], calls[edge]["valid_to"] = intervals([edge in snapshot["calls"] for snapshot in snapshots])
+    return {"items": items, "calls": calls}
+
+
+def describe_ranges(valid_from: List[int], valid_to: List[int], revisions: Dict[int, Dict]) -> List[Dict[str, Optional[Dict]]]:
+    """The ranges as `{from, until}` revisions (`until` is the first one without the item, None if still there)."""
+    return [{"from": revisions.get(start), "until": None if end == OPEN else revisions.get(end)}
+            for start, end in zip(valid_from, valid_to)]
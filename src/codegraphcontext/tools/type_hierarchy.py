@@ -0,0 +1,40 @@
+# src/codegraphcontext/tools/type_hierarchy.py
+"""
+Renders the trees returned by `CodeFinder.type_hierarchy` as indented text.
+
+A tree node is `{name, kind, relation, file_path, line_number, children}`, where `relation`
+tells how it relates to its parent: `supertrait`, `implements` and `derives` upward,
+`implementor`, `derived`, `blanket` and `subtrait` downward. Traits outside the graph
+(`fmt::Display`) have no file.
+"""
+from typing import Any, Dict, List
+
+UPWARD_RELATIONS = ("supertrait", "implements", "derives")
+DOWNWARD_RELATIONS = ("implementor", "derived", "blanket", "subtrait")
+
+
+def _label(node: Dict[str, Any]) -> str:
+    label = node['name']
+    if node.get('kind'):
+        label += f" ({node['kind']})"
+    if node.get('relation'):
+        label += f" [{node['relation']}]"
+    if node.get('constraints'):
+        label += f" where {', '.join(node['constraints'])}"
+    if node.get('repeated'):
+        label += " ..."
+    return label
+
+
+def render_tree(tree: Dict[str, Any]) -> str:
+    """`Shape (trait)` followed by its supertraits and implementors as a box-drawn tree."""
+    lines = [_label(tree)]
+
+    def walk(children: List[Dict[str, Any]], prefix: str):
+        for i, child in enumerate(children):
+            last = i == len(children) - 1
+            lines.append(f"{prefix}{'└── ' if last else '├── '}{_label(child)}")
+            walk(child.get('children', []), prefix + ('    ' if last else '│   '))
+
+    walk(tree.get('supertypes', []) + tree.get('subtypes', []), "")
+    return "\n".join(lines) + "\n"
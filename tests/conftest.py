@@ -6,6 +6,7 @@ import pytest
 
 # Path to the sample project used in tests
 SAMPLE_PROJECT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), "sample_project"))
+RUST_SAMPLE_PROJECT_PATH = os.path.abspath(os.path.join(os.path.dirname(__file__), "sample_project_rust"))
 
 # Helper function to call a tool, now shared across all tests
 def call_tool(server, name, args):
@@ -97,17 +98,16 @@ def pytest_addoption(parser):
         "--no-reindex", action="store_true", default=False, help="Skip re-indexing the project for tests"
     )
 
-@pytest.fixture(scope="module")
-def indexed_project(server, request):
+def _ensure_indexed(server, request, project_path):
     """
-    Ensures the sample project is indexed before running tests.
+    Re-indexes the given project unless --no-reindex was passed.
     """
     if not request.config.getoption("--no-reindex"):
         print("\n--- Ensuring project is indexed ---")
-        delete_result = call_tool(server, "delete_repository", {"repo_path": SAMPLE_PROJECT_PATH})
+        delete_result = call_tool(server, "delete_repository", {"repo_path": project_path})
         print(f"Delete result: {delete_result}")
 
-        add_result = call_tool(server, "add_code_to_graph", {"path": SAMPLE_PROJECT_PATH})
+        add_result = call_tool(server, "add_code_to_graph", {"path": project_path})
         assert add_result.get("success") is True, f"add_code_to_graph failed: {add_result.get('error')}"
         job_id = add_result.get("job_id")
         assert job_id is not None, "add_code_to_graph did not return a job_id"
@@ -128,7 +128,21 @@ def indexed_project(server, request):
             time.sleep(2)
     else:
         print("\n--- Skipping re-indexing as per --no-reindex flag ---")
-    
+
+@pytest.fixture(scope="module")
+def indexed_project(server, request):
+    """
+    Ensures the sample project is indexed before running tests.
+    """
+    _ensure_indexed(server, request, SAMPLE_PROJECT_PATH)
+    return server
+
+@pytest.fixture(scope="module")
+def indexed_rust_project(server, request):
+    """
+    Ensures the Rust sample project is indexed before running tests.
+    """
+    _ensure_indexed(server, request, RUST_SAMPLE_PROJECT_PATH)
     return server
 
 class CodeGraph:
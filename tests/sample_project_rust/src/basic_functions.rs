@@ -1,7 +1,7 @@
 // basic_functions.rs - Demonstrates basic Rust function patterns and ownership
 use std::fmt;
 
-/// Simple function with single return value
+/** Simple function with single return value */
 pub fn simple_function(x: i32) -> i32 {
     x * 2
 }
@@ -145,6 +145,38 @@ pub fn from_string(s: String) -> Result<i32, std::num::ParseIntError> {
     s.parse()
 }
 
+/// Parses a leading number using let-else and or-patterns
+pub fn parse_leading_number(input: &str) -> Option<u32> {
+    let Some(first) = input.split_whitespace().next() else {
+        return None;
+    };
+    if let Some('+' | '-') = first.chars().next() {
+        return None;
+    }
+    first.parse().ok().map(|n: u32| factorial(n.min(5)))
+}
+
+/// Generates functions returning a constant
+macro_rules! make_constant_fns {
+    ($($name:ident => $value:expr),* $(,)?) => {
+        $(
+            pub fn $name() -> i32 {
+                $value
+            }
+        )*
+    };
+}
+
+make_constant_fns! {
+    answer => 42,
+    zero => 0,
+}
+
+/// Private helper that nothing calls
+fn unused_helper() -> i32 {
+    answer() + 1
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
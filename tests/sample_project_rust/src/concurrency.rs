@@ -324,6 +324,76 @@ pub fn scoped_threads() {
     println!("Final data: {:?}", data);
 }
 
+/// Buffer behind a raw pointer, declared sendable by hand
+pub struct RawBuffer {
+    ptr: *mut u8,
+    len: usize,
+}
+
+unsafe impl Send for RawBuffer {}
+
+/// Cache that can only be used from the thread that created it
+pub struct LocalCache {
+    entries: std::rc::Rc<std::cell::RefCell<Vec<i32>>>,
+}
+
+pub fn send_raw_buffer(buffer: RawBuffer) -> usize {
+    let handle = thread::spawn(move || buffer.len);
+    handle.join().unwrap()
+}
+
+/// Async update that keeps a std MutexGuard alive across an await point
+pub async fn record_async(counter: &SafeCounter, delta: i32) {
+    let mut count = counter.count.lock().unwrap();
+    *count += delta;
+    flush_async().await;
+}
+
+/// Async update that releases the guard before awaiting
+pub async fn record_async_scoped(counter: &SafeCounter, delta: i32) {
+    {
+        let mut count = counter.count.lock().unwrap();
+        *count += delta;
+    }
+    flush_async().await;
+}
+
+async fn flush_async() {}
+
+/// Ledger whose two locks are taken in opposite orders by different methods
+pub struct Ledger {
+    accounts: Mutex<Vec<i32>>,
+    audit_log: Mutex<Vec<String>>,
+}
+
+impl Ledger {
+    pub fn transfer(&self, amount: i32) {
+        let mut accounts = self.accounts.lock().unwrap();
+        let mut log = self.audit_log.lock().unwrap();
+        accounts.push(amount);
+        log.push(format!("transfer {}", amount));
+    }
+
+    pub fn audit(&self) -> usize {
+        let log = self.audit_log.lock().unwrap();
+        log.len() + self.account_count()
+    }
+
+    fn account_count(&self) -> usize {
+        self.accounts.lock().unwrap().len()
+    }
+}
+
+// Blocking calls inside async code
+pub async fn poll_status_async(path: &str) -> usize {
+    thread::sleep(Duration::from_millis(5));
+    read_status_file(path)
+}
+
+fn read_status_file(path: &str) -> usize {
+    std::fs::read_to_string(path).map(|s| s.len()).unwrap_or(0)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
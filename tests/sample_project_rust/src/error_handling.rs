@@ -4,6 +4,8 @@ use std::fmt;
 use std::fs::File;
 use std::io::{self, Read};
 
+use crate::structs_enums::BankAccount;
+
 /// Custom error type
 #[derive(Debug, Clone)]
 pub struct CustomError {
@@ -85,7 +87,7 @@ impl From<CustomError> for AppError {
     }
 }
 
-// Basic Result usage
+// FIXME: Basic Result usage, move next to the custom error examples
 
 /// Simple function returning Result
 pub fn divide(a: i32, b: i32) -> Result<i32, String> {
@@ -107,6 +109,14 @@ pub fn validate_age(age: i32) -> Result<i32, String> {
     Ok(age)
 }
 
+/// Withdraws from an account, refusing amounts above a fixed limit
+pub fn checked_withdraw(account: &mut BankAccount, amount: f64) -> Result<(), String> {
+    if amount > 10_000.0 {
+        return Err("Amount above withdrawal limit".to_string());
+    }
+    account.withdraw(amount)
+}
+
 /// Using custom error type
 pub fn validate_username(username: &str) -> Result<String, CustomError> {
     if username.is_empty() {
@@ -294,6 +304,26 @@ pub fn flexible_error_handling(input: &str) -> Result<i32, Box<dyn Error>> {
     Ok(num)
 }
 
+/// Discards the errors of fallible calls in several ways
+pub fn ignore_errors(input: &str) {
+    validate_username(input);
+    let _ = validate_age(42);
+    divide(1, 0).ok();
+    let handled = parse_and_validate(input).unwrap_or(0);
+    println!("{}", handled);
+}
+
+/// Runs the shell command stored in a file
+pub fn run_script(filename: &str) -> io::Result<std::process::ExitStatus> {
+    let script = read_file_contents(filename)?;
+    execute_command(script.trim())
+}
+
+/// Hands its input to the shell
+pub fn execute_command(command: &str) -> io::Result<std::process::ExitStatus> {
+    std::process::Command::new("sh").arg("-c").arg(command).status()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
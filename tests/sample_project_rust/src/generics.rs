@@ -28,6 +28,16 @@ pub fn pair<T, U>(first: T, second: U) -> (T, U) {
     (first, second)
 }
 
+/// Pairs a value with itself; every instantiation also instantiates `pair`
+pub fn twin<T: Copy>(value: T) -> (T, T) {
+    pair(value, value)
+}
+
+/// Instantiates `twin` for two types
+pub fn twins() -> ((i32, i32), (f64, f64)) {
+    (twin(1), twin(2.5))
+}
+
 /// Generic function with multiple trait bounds
 pub fn print_pair<T: Display, U: Display>(first: T, second: U) {
     println!("First: {}, Second: {}", first, second);
@@ -104,6 +114,13 @@ impl<T: Clone, U: Clone> Pair<T, U> {
     }
 }
 
+/// Points and pairs instantiated with concrete types
+pub fn labeled_origin() -> Pair<Point<f64>, &'static str> {
+    let origin: Point<f64> = Point::new(0.0, 0.0);
+    let _pixel = Point { x: 1u8, y: 2u8 };
+    Pair::new(origin, "origin")
+}
+
 // Generic collections
 
 /// Generic stack
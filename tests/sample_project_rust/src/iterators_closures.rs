@@ -11,6 +11,21 @@ where
     f(x)
 }
 
+/// Passing closures and functions to a higher-order function
+pub fn apply_examples(offset: i32) -> i32 {
+    let shifted = apply(|x| add_offset(x, offset), 5);
+    let triple = |x| x * 3;
+    apply(triple, shifted) + apply(negate, shifted)
+}
+
+fn add_offset(x: i32, offset: i32) -> i32 {
+    x + offset
+}
+
+fn negate(x: i32) -> i32 {
+    -x
+}
+
 /// Function returning closure
 pub fn make_adder(n: i32) -> impl Fn(i32) -> i32 {
     move |x| x + n
@@ -29,6 +44,20 @@ pub fn closure_move() -> Box<dyn Fn(i32) -> i32> {
     Box::new(move |x| x * factor)
 }
 
+/// Closures capturing by mutable reference, by reference and by value
+pub fn capture_modes() -> usize {
+    let mut total = 0;
+    let mut names = Vec::new();
+    let label = String::from("total");
+    let mut add = |n: usize| total += n;
+    add(2);
+    let mut record = || names.push(label.len());
+    record();
+    let consume = || drop(label);
+    consume();
+    total + names.len()
+}
+
 /// FnOnce closure (consumes captured values)
 pub fn call_once<F>(f: F)
 where
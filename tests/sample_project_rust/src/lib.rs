@@ -1,4 +1,4 @@
-// lib.rs - Main library file for Rust sample project
+//! Main library file for Rust sample project
 
 pub mod basic_functions;
 pub mod structs_enums;
@@ -16,6 +16,23 @@ pub use basic_functions::*;
 pub use structs_enums::{Person, Status};
 pub use traits::{Describable, Area};
 
+use structs_enums::BankAccount as Account;
+use basic_functions::factorial as fact;
+
+/// Opens an account through renamed imports
+pub fn open_account_with_bonus(n: u32) -> Account {
+    let mut account = Account::new("ACC-1".to_string(), 0.0);
+    let _ = account.deposit(fact(n) as f64);
+    account
+}
+
+/// Greets a freshly created person through inferred local types
+pub fn greet_new_person(name: &str) -> String {
+    let person = Person::new(name.to_string(), 30);
+    let greeting = person.greet();
+    format!("{} {}", greeting, Person::default_person().get_name())
+}
+
 /// Library-level documentation
 /// 
 /// This is a comprehensive Rust sample project demonstrating:
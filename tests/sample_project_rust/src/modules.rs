@@ -99,6 +99,31 @@ pub mod prelude {
     pub use super::data::*;
 }
 
+/// Lookup helpers kept in a separate, included source file
+pub mod lookup {
+    include!("lookup_table.in");
+}
+
+/// Timing helpers, only built with the `metrics` feature
+#[cfg(feature = "metrics")]
+pub mod metrics {
+    pub fn record_timing(label: &str, millis: u64) -> String {
+        format!("{}: {}ms", label, millis)
+    }
+}
+
+/// Reports a timing when metrics are enabled
+#[cfg(feature = "metrics")]
+pub fn report_timing(millis: u64) -> String {
+    metrics::record_timing("report", millis)
+}
+
+/// Always compiled, yet calls into code gated behind `metrics`
+pub fn timed_square(n: u64) -> u64 {
+    let _ = report_timing(n);
+    n * n
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -201,6 +201,24 @@ impl CellExample {
     }
 }
 
+// UnsafeCell - the primitive underneath Cell and RefCell
+
+pub struct RawSlot {
+    slot: std::cell::UnsafeCell<u64>,
+}
+
+impl RawSlot {
+    pub fn new(value: u64) -> Self {
+        Self {
+            slot: std::cell::UnsafeCell::new(value),
+        }
+    }
+
+    pub fn overwrite(&self, value: u64) {
+        unsafe { *self.slot.get() = value }
+    }
+}
+
 // Weak references
 
 pub fn weak_reference_example() {
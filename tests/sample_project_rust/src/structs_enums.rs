@@ -1,6 +1,8 @@
 // structs_enums.rs - Demonstrates Rust structs and enums
 use std::fmt;
 
+use crate::error_handling::validate_age;
+
 /// Basic struct with public fields
 #[derive(Debug, Clone, PartialEq)]
 pub struct Person {
@@ -74,6 +76,11 @@ impl Person {
     pub fn is_older_than(&self, other: &Person) -> bool {
         self.age > other.age
     }
+
+    /// Constructor validating the age first
+    pub fn with_checked_age(name: String, age: i32) -> Result<Self, String> {
+        validate_age(age).map(|age| Self::new(name, age as u32))
+    }
 }
 
 impl BankAccount {
@@ -255,6 +262,110 @@ impl fmt::Display for Status {
     }
 }
 
+/// C-compatible layout for FFI
+#[repr(C)]
+pub struct FfiPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+/// Untagged union sharing storage between an integer and a float
+#[repr(C)]
+pub union IntOrFloat {
+    pub i: u32,
+    pub f: f32,
+}
+
+/// Newtype with the same ABI as its field
+#[repr(transparent)]
+pub struct Meters(f64);
+
+/// Newtype restricting a person to staff members
+pub struct Staff(pub Person);
+
+/// Newtype over another newtype
+pub struct Manager(pub Staff);
+
+/// Packed wire header
+#[repr(C, packed)]
+pub struct PackedHeader {
+    tag: u8,
+    len: u32,
+}
+
+/// Struct using raw identifiers for keyword names
+pub struct Token {
+    pub r#type: String,
+}
+
+impl Token {
+    pub fn r#type(&self) -> &str {
+        &self.r#type
+    }
+}
+
+pub fn describe_token(token: &Token) -> String {
+    token.r#type().to_string()
+}
+
+/// Connection settings assembled through `ConnectionConfigBuilder`
+#[derive(Debug)]
+pub struct ConnectionConfig {
+    host: String,
+    port: u16,
+    retries: u32,
+}
+
+impl ConnectionConfig {
+    pub fn builder() -> ConnectionConfigBuilder {
+        ConnectionConfigBuilder::default()
+    }
+}
+
+/// Builder for `ConnectionConfig`
+#[derive(Debug, Default)]
+pub struct ConnectionConfigBuilder {
+    host: Option<String>,
+    port: Option<u16>,
+    retries: u32,
+}
+
+impl ConnectionConfigBuilder {
+    pub fn host(mut self, host: &str) -> Self {
+        self.host = Some(host.to_string());
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    pub fn build(self) -> Result<ConnectionConfig, String> {
+        Ok(ConnectionConfig {
+            host: self.host.ok_or("host is required")?,
+            port: self.port.unwrap_or(80),
+            retries: self.retries,
+        })
+    }
+}
+
+/// Collection of people, kept sorted by age
+pub struct Roster {
+    members: Vec<Person>,
+}
+
+impl Roster {
+    pub fn oldest(&self) -> Option<&Person> {
+        self.members.last()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -132,7 +132,7 @@ impl Area for Circle {
 
 impl Area for Triangle {
     fn area(&self) -> f64 {
-        // Heron's formula
+        // TODO(geometry): Heron's formula loses precision for needle-like triangles
         let s = (self.a + self.b + self.c) / 2.0;
         (s * (s - self.a) * (s - self.b) * (s - self.c)).sqrt()
     }
@@ -277,6 +277,32 @@ impl Add for Point {
     }
 }
 
+/// Amount in cents with its own `add` next to `impl Add`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Money {
+    pub cents: u64,
+}
+
+impl Money {
+    /// Saturating addition; `a.add(b)` calls this one, not `Add::add`
+    pub fn add(self, other: Money) -> Money {
+        Money { cents: self.cents.saturating_add(other.cents) }
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+
+    fn add(self, other: Money) -> Money {
+        Money { cents: self.cents + other.cents }
+    }
+}
+
+/// Adds with the inherent method, then through the trait
+pub fn add_money(a: Money, b: Money) -> (Money, Money) {
+    (a.add(b), Add::add(a, b))
+}
+
 // Trait for custom equality
 pub trait CustomEq {
     fn custom_eq(&self, other: &Self) -> bool;
@@ -299,6 +325,31 @@ impl<T: Describable> Summary for T {
     }
 }
 
+/// Lending iterator using a generic associated type
+pub trait LendingIterator {
+    type Item<'a>
+    where
+        Self: 'a;
+
+    fn next<'a>(&'a mut self) -> Option<Self::Item<'a>>;
+}
+
+/// Yields overlapping windows borrowed from its own buffer
+pub struct WindowsMut {
+    buffer: Vec<i32>,
+    position: usize,
+}
+
+impl LendingIterator for WindowsMut {
+    type Item<'a> = &'a mut [i32] where Self: 'a;
+
+    fn next<'a>(&'a mut self) -> Option<Self::Item<'a>> {
+        let start = self.position;
+        self.position += 1;
+        self.buffer.get_mut(start..start + 2)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,2141 @@
+import csv
+import json
+import os
+import subprocess
+import sys
+import time
+import tracemalloc
+import pytest
+from pathlib import Path
+from neo4j.exceptions import CypherSyntaxError, ServiceUnavailable
+from codegraphcontext.core.database import DatabaseManager, backoff_delays
+from codegraphcontext.tools import delta_sync, incremental, lsif, scip, snippets
+from codegraphcontext.tools.interning import strings
+from codegraphcontext.tools.pipeline import BoundedPipeline
+from codegraphcontext.tools.sources import read_source
+from codegraphcontext.tools.spill import SpillList, estimate_size
+from .conftest import RUST_SAMPLE_PROJECT_PATH, call_tool
+
+CONCURRENCY_RS = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "concurrency.rs")
+
+
+def run_query(server, query):
+    result = call_tool(server, "execute_cypher_query", {"cypher_query": query})
+    assert result.get("success") is True, f"Query failed: {result}"
+    return result.get("results", [])
+
+
+def test_rust_functions_indexed(indexed_rust_project):
+    query = "MATCH (f:Function)-[:CONTAINS]-(file:File) WHERE file.path ENDS WITH 'basic_functions.rs' RETURN f.name as name"
+    functions = [item['name'] for item in run_query(indexed_rust_project, query)]
+    assert "simple_function" in functions
+    assert "factorial" in functions
+
+
+def test_simple_channel_producer_and_consumer(indexed_rust_project):
+    query = f"""
+        MATCH (producer:Function)-[:SENDS_TO]->(ch:Channel {{file_path: '{CONCURRENCY_RS}'}})<-[:RECEIVES_FROM]-(consumer:Function)
+        RETURN producer.name as producer, consumer.name as consumer, ch.name as channel
+    """
+    links = {(r['producer'], r['consumer']) for r in run_query(indexed_rust_project, query)}
+    assert ("simple_channel", "simple_channel") in links
+    assert ("multiple_producers", "multiple_producers") in links
+
+    # Both functions bind `(tx, rx)`: neither uses the other's channel.
+    query = f"""
+        MATCH (fn:Function)-[:SENDS_TO|RECEIVES_FROM]->(ch:Channel {{file_path: '{CONCURRENCY_RS}'}})
+        WHERE ch.created_in IN ['simple_channel', 'multiple_producers'] AND fn.name <> ch.created_in
+        RETURN fn.name as function, ch.created_in as created_in
+    """
+    assert run_query(indexed_rust_project, query) == []
+
+
+def test_thread_pool_channel_crosses_functions(indexed_rust_project):
+    query = f"""
+        MATCH (ch:Channel {{file_path: '{CONCURRENCY_RS}', name: 'sender/receiver'}})
+        OPTIONAL MATCH (producer:Function)-[:SENDS_TO]->(ch)
+        OPTIONAL MATCH (consumer:Function)-[:RECEIVES_FROM]->(ch)
+        RETURN collect(DISTINCT producer.name) as producers, collect(DISTINCT consumer.name) as consumers
+    """
+    result = run_query(indexed_rust_project, query)[0]
+    assert "execute" in result['producers']
+    assert "new" in result['consumers']
+
+
+def test_safe_counter_lock_users(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "analyze_code_relationships", {
+        "query_type": "find_lock_users",
+        "target": "SafeCounter.count"
+    })
+    assert result.get("success") is True, f"find_lock_users failed: {result.get('error')}"
+    rows = result.get("results", {}).get("results", [])
+    assert {r['function_name'] for r in rows} == {"increment", "get"}
+    assert all(r['lock_kind'] == "Mutex" for r in rows)
+
+
+def test_smart_pointer_wrapping(indexed_rust_project):
+    query = """
+        MATCH (inner:Class)-[r:WRAPPED_IN]->(owner:Class)
+        WHERE owner.file_path ENDS WITH 'smart_pointers.rs'
+        RETURN inner.name as inner, owner.name as owner, r.field as field, r.wrappers as wrappers
+    """
+    links = {(r['inner'], r['owner'], r['field']): r['wrappers'] for r in run_query(indexed_rust_project, query)}
+    assert links[("List", "List", "Cons.1")] == ["Box"]
+    assert links[("Node", "Node", "children")] == ["Rc"]
+
+    query = "MATCH (c:Class {name: 'SharedData'}) RETURN c.pointer_wrappers as wrappers, c.shared_ownership as shared"
+    shared_data = run_query(indexed_rust_project, query)[0]
+    assert shared_data['wrappers'] == ["Rc", "RefCell"]
+    assert shared_data['shared'] is True
+
+
+def test_enum_variant_usages(indexed_rust_project):
+    query = """
+        MATCH (v:Variant)-[:VARIANT_OF]->(e:Class {name: 'Message'})
+        RETURN v.name as name
+    """
+    variants = {r['name'] for r in run_query(indexed_rust_project, query)}
+    assert {"Quit", "Move", "Write", "ChangeColor"} <= variants
+
+    result = call_tool(indexed_rust_project, "analyze_code_relationships", {
+        "query_type": "find_variant_usages",
+        "target": "Message::Move"
+    })
+    assert result.get("success") is True, f"find_variant_usages failed: {result.get('error')}"
+    rows = result.get("results", {}).get("results", [])
+    assert ("MATCHES", "process_message") in {(r['usage'], r['function_name']) for r in rows}
+    assert "CONSTRUCTS" in {r['usage'] for r in rows}
+
+
+def test_bank_account_field_accesses(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "analyze_code_relationships", {
+        "query_type": "find_field_accesses",
+        "target": "BankAccount.balance"
+    })
+    assert result.get("success") is True, f"find_field_accesses failed: {result.get('error')}"
+    rows = {(r['function_name'], r['access_mode']) for r in result.get("results", {}).get("results", [])}
+    assert ("withdraw", "write") in rows
+    assert ("withdraw", "read") in rows
+    assert ("deposit", "write") in rows
+    assert ("get_balance", "read") in rows
+
+
+def test_person_method_receivers(indexed_rust_project):
+    query = """
+        MATCH (c:Class {name: 'Person'})-[:HAS_IMPL]->(:Impl)-[:CONTAINS]->(f:Function)
+        RETURN f.name as name, f.receiver as receiver
+    """
+    receivers = {r['name']: r['receiver'] for r in run_query(indexed_rust_project, query)}
+    assert receivers["greet"] == "&self"
+    assert receivers["have_birthday"] == "&mut self"
+    assert receivers["into_name"] == "self"
+    assert receivers["new"] == "associated"
+
+
+def test_direct_recursion_detected(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_recursive_functions", {})
+    assert result.get("success") is True, f"find_recursive_functions failed: {result.get('error')}"
+    direct = {r['function_name']: r for r in result["results"]["direct_recursion"]}
+    assert direct["factorial"]["depth_bound"] == "O(n)"
+    assert direct["fibonacci"]["branching"] is True
+    # A method named like a Python builtin still calls itself.
+    assert ("len", "List") in {(r['function_name'], r['owner']) for r in result["results"]["direct_recursion"]}
+
+
+def test_higher_order_may_call(indexed_rust_project):
+    query = """
+        MATCH (hof:Function {name: 'apply'})-[r:MAY_CALL]->(target)
+        WHERE hof.file_path ENDS WITH 'iterators_closures.rs'
+        RETURN labels(target)[0] as label, target.name as name, r.via_param as param
+    """
+    targets = [(r['label'], r['name'], r['param']) for r in run_query(indexed_rust_project, query)]
+    assert ("Function", "negate", "f") in targets
+    assert len([t for t in targets if t[0] == "Closure"]) == 2
+
+    query = """
+        MATCH (hof:Function {name: 'apply'})-[:MAY_CALL]->(:Closure)-[:CALLS]->(callee:Function)
+        RETURN callee.name as name
+    """
+    assert "add_offset" in [r['name'] for r in run_query(indexed_rust_project, query)]
+
+
+def test_renamed_imports_resolve_to_original(indexed_rust_project):
+    query = """
+        MATCH (caller:Function {name: 'open_account_with_bonus'})-[:CALLS]->(callee:Function)
+        RETURN callee.name as name, callee.file_path as file_path
+    """
+    callees = {(r['name'], os.path.basename(r['file_path'])) for r in run_query(indexed_rust_project, query)}
+    assert ("factorial", "basic_functions.rs") in callees
+    assert ("new", "structs_enums.rs") in callees
+
+
+def test_let_binding_types_resolve_method_calls(indexed_rust_project):
+    query = """
+        MATCH (caller:Function {name: 'greet_new_person'})-[:CALLS]->(callee:Function)
+        RETURN callee.name as name, callee.class_context as owner
+    """
+    callees = {(r['name'], r['owner']) for r in run_query(indexed_rust_project, query)}
+    assert ("greet", "Person") in callees
+    assert ("get_name", "Person") in callees
+    assert {owner for name, owner in callees if name == "new"} == {"Person"}
+
+
+def test_impl_blocks_are_nodes(indexed_rust_project):
+    query = """
+        MATCH (c:Class {name: 'Point'})-[:HAS_IMPL]->(i:Impl)-[:CONTAINS]->(f:Function)
+        WHERE c.file_path ENDS WITH 'generics.rs'
+        RETURN i.name as impl, i.type_parameters as bounds, collect(f.name) as methods
+    """
+    impls = {r['bounds']: r for r in run_query(indexed_rust_project, query)}
+    assert set(impls) == {"<T>", "<T: Add<Output = T> + Copy>"}
+    assert impls["<T: Add<Output = T> + Copy>"]['impl'] == "impl<T: Add<Output = T> + Copy> Point<T>"
+
+    query = """
+        MATCH (c:Class)-[:HAS_IMPL]->(i:Impl)-[:IMPLEMENTS]->(t:Class {name: 'Describable'})
+        RETURN c.name as name
+    """
+    assert {"Rectangle", "Circle", "Triangle"} <= {r['name'] for r in run_query(indexed_rust_project, query)}
+
+
+def test_where_clause_and_inline_bounds_match(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "analyze_code_relationships", {
+        "query_type": "find_by_trait_bound",
+        "target": "U: Display + Debug"
+    })
+    assert result.get("success") is True, f"find_by_trait_bound failed: {result.get('error')}"
+    names = {r['name'] for r in result.get("results", {}).get("results", [])}
+    assert "complex_function" in names
+
+    result = call_tool(indexed_rust_project, "analyze_code_relationships", {
+        "query_type": "find_by_trait_bound",
+        "target": "Add + Copy"
+    })
+    rows = result.get("results", {}).get("results", [])
+    assert "impl<T: Add<Output = T> + Copy> Point<T>" in {r['name'] for r in rows if r['kind'] == "Impl"}
+
+
+def test_unions_and_repr_attributes(indexed_rust_project):
+    query = """
+        MATCH (c:Class)-[:HAS_FIELD]->(fd:Field)
+        WHERE c.name = 'IntOrFloat'
+        RETURN c.kind as kind, c.repr as repr, collect(fd.name) as fields
+    """
+    union = run_query(indexed_rust_project, query)[0]
+    assert union['kind'] == "union"
+    assert union['repr'] == ["C"]
+    assert set(union['fields']) == {"i", "f"}
+
+    query = "MATCH (c:Class) WHERE c.name IN ['Meters', 'PackedHeader'] RETURN c.name as name, c.repr as repr"
+    reprs = {r['name']: r['repr'] for r in run_query(indexed_rust_project, query)}
+    assert reprs == {"Meters": ["transparent"], "PackedHeader": ["C", "packed"]}
+
+
+def test_let_else_function_body_indexed(indexed_rust_project):
+    query = """
+        MATCH (f:Function {name: 'parse_leading_number'})-[:CONTAINS]->(:Closure)-[:CALLS]->(callee:Function)
+        RETURN callee.name as name
+    """
+    assert [r['name'] for r in run_query(indexed_rust_project, query)] == ["factorial"]
+
+
+def test_generic_associated_types(indexed_rust_project):
+    query = """
+        MATCH (t:Class {name: 'LendingIterator'})-[:DECLARES_TYPE]->(decl:AssociatedType)
+        OPTIONAL MATCH (binding:AssociatedType)-[:BINDS]->(decl)
+        RETURN decl.name as name, decl.generics as generics, decl.where_clause as where_clause,
+               binding.owner as implementor, binding.binding as binding
+    """
+    row = run_query(indexed_rust_project, query)[0]
+    assert row['name'] == "Item"
+    assert row['generics'] == "<'a>"
+    assert row['where_clause'] == "where Self: 'a"
+    assert row['implementor'] == "WindowsMut"
+    assert row['binding'] == "&'a mut [i32]"
+
+
+def test_include_macro_indexes_included_file(indexed_rust_project):
+    query = """
+        MATCH (f:File)-[r:INCLUDES]->(g:File)-[:CONTAINS]->(fn:Function)
+        WHERE f.path ENDS WITH 'modules.rs'
+        RETURN g.name as included, r.module as module, fn.name as function
+    """
+    rows = run_query(indexed_rust_project, query)
+    assert [(r['included'], r['module'], r['function']) for r in rows] == [("lookup_table.in", "lookup", "lookup_square")]
+
+
+def test_macro_generated_functions(indexed_rust_project):
+    query = """
+        MATCH (f:Function)-[:GENERATED_BY]->(m:Macro {name: 'make_constant_fns'})
+        WHERE m.file_path ENDS WITH 'basic_functions.rs'
+        RETURN f.name as name, f.line_number as line, m.expandable as expandable
+    """
+    rows = run_query(indexed_rust_project, query)
+    assert {r['name'] for r in rows} == {"answer", "zero"}
+    assert len({r['line'] for r in rows}) == 1
+    assert all(r['expandable'] for r in rows)
+
+
+def test_raw_identifiers_are_normalized(indexed_rust_project):
+    query = """
+        MATCH (c:Class {name: 'Token'})-[:HAS_FIELD]->(fd:Field)
+        OPTIONAL MATCH (reader:Function)-[:READS_FIELD]->(fd)
+        RETURN fd.name as field, collect(reader.name) as readers
+    """
+    row = run_query(indexed_rust_project, query)[0]
+    assert row['field'] == "type"
+    assert row['readers'] == ["type"]
+
+    query = """
+        MATCH (caller:Function {name: 'describe_token'})-[:CALLS]->(callee:Function)
+        RETURN callee.name as name, callee.class_context as owner
+    """
+    assert [(r['name'], r['owner']) for r in run_query(indexed_rust_project, query)] == [("type", "Token")]
+
+    query = "MATCH (n) WHERE n.name STARTS WITH 'r#' RETURN count(n) as count"
+    assert run_query(indexed_rust_project, query)[0]['count'] == 0
+
+
+def test_crate_msrv_estimate(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "get_crate_info", {"crate_name": "sample_project_rust"})
+    assert result.get("success") is True, f"get_crate_info failed: {result.get('error')}"
+    crate = result["results"][0]
+    assert crate['edition'] == "2021"
+    assert crate['estimated_msrv'] == "1.65"
+    assert {"let_else", "generic_associated_types", "const_generics", "scoped_threads"} <= set(crate['syntax_features'])
+    assert any(reason.startswith("let_else (1.65) at src/basic_functions.rs") for reason in crate['msrv_reasons'])
+    assert crate['declared_below_estimate'] is False
+
+
+def test_thread_crossing_types(indexed_rust_project):
+    query = "MATCH (c:Class) WHERE c.name IN ['RawBuffer', 'LocalCache'] RETURN c.name as name, c.is_send as is_send, c.thread_safety_reason as reason"
+    classes = {r['name']: r for r in run_query(indexed_rust_project, query)}
+    assert classes["LocalCache"]['is_send'] is False
+    assert classes["LocalCache"]['reason'] == "field `entries`: `Rc` is not Send"
+    assert classes["RawBuffer"]['is_send'] is True
+    assert classes["RawBuffer"]['reason'].startswith("unsafe impl Send")
+
+    result = call_tool(indexed_rust_project, "find_thread_crossing_types", {})
+    assert result.get("success") is True, f"find_thread_crossing_types failed: {result.get('error')}"
+    crossing = {r['type_name']: r for r in result["results"]["thread_crossing_types"]}
+    assert [(c['function_name'], c['variable']) for c in crossing["RawBuffer"]['crossings']] == [("send_raw_buffer", "buffer")]
+    assert result["results"]["non_send_captures"] == []
+
+    query = """
+        MATCH (fn:Function {name: 'shared_counter'})-[:CONTAINS]->(cl:Closure)
+        WHERE cl.spawned_by = 'thread::spawn'
+        RETURN cl.captures as captures
+    """
+    assert run_query(indexed_rust_project, query)[0]['captures'] == ["counter: Arc<Mutex>"]
+
+
+def test_await_holding_lock(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_await_holding_lock", {"file_path": CONCURRENCY_RS})
+    assert result.get("success") is True, f"find_await_holding_lock failed: {result.get('error')}"
+    rows = {r['function_name']: r for r in result["results"]}
+    assert set(rows) == {"record_async"}
+    lock = rows["record_async"]['locks'][0]
+    assert (lock['lock_name'], lock['guard']) == ("SafeCounter.count", "count")
+    assert lock['await_line'] - lock['lock_line'] == 2
+
+
+def test_lock_order_cycle_reported(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_deadlock_candidates", {})
+    assert result.get("success") is True, f"find_deadlock_candidates failed: {result.get('error')}"
+    cycles = result["results"]["cycles"]
+    assert [c['locks'] for c in cycles] == [["Ledger.accounts", "Ledger.audit_log"]]
+
+    forward, backward = cycles[0]['orderings']
+    assert [(o['function_name'], o['via']) for o in forward] == [("transfer", ["transfer"])]
+    assert [(o['function_name'], o['via']) for o in backward] == [("audit", ["audit", "account_count"])]
+
+
+def test_blocking_calls_in_async(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_blocking_in_async", {"file_path": CONCURRENCY_RS})
+    assert result.get("success") is True, f"find_blocking_in_async failed: {result.get('error')}"
+    findings = {(r['async_function'], r['function_name'], r['category']): r for r in result["results"]}
+
+    direct = findings[("poll_status_async", "poll_status_async", "sleep")]
+    assert direct['call_path'] == ["poll_status_async"]
+
+    indirect = findings[("poll_status_async", "read_status_file", "io")]
+    assert indirect['call_path'] == ["poll_status_async", "read_status_file"]
+    assert indirect['entry_point'] is None
+
+
+def test_interior_mutability_report(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_interior_mutability", {})
+    assert result.get("success") is True, f"find_interior_mutability failed: {result.get('error')}"
+    groups = {group['kind']: {cell['name']: cell for cell in group['cells']} for group in result["results"]}
+    assert {"Cell", "RefCell", "UnsafeCell", "Atomic"} <= set(groups)
+
+    assert groups["Cell"]["CellExample.value"]['mutable_borrowers'] == ["increment", "set"]
+    assert groups["RefCell"]["SharedData.data"]['mutable_borrowers'] == ["add"]
+    assert groups["UnsafeCell"]["RawSlot.slot"]['mutable_borrowers'] == ["overwrite"]
+    assert groups["Atomic"]["AtomicCounter.count"]['mutable_borrowers'] == ["increment"]
+
+
+def test_builder_detection(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_builders", {"type_name": "ConnectionConfig"})
+    assert result.get("success") is True, f"find_builders failed: {result.get('error')}"
+    assert len(result["results"]) == 1
+    builder = result["results"][0]
+    assert (builder['builder'], builder['target'], builder['build_method']) == ("ConnectionConfigBuilder", "ConnectionConfig", "build")
+    assert builder['setters'] == ["host", "port", "retries"]
+    assert builder['entry_points'] == ["ConnectionConfig::builder"]
+
+
+def test_newtypes_and_wrappers(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_newtypes", {})
+    assert result.get("success") is True, f"find_newtypes failed: {result.get('error')}"
+    kinds = {r['name']: (r['kind'], r['inner_type']) for r in result["results"]}
+    assert kinds["Meters"] == ("newtype", "f64")
+    assert kinds["Wrapper"] == ("wrapper", "T")
+    assert kinds["MyBox"] == ("wrapper", "T")
+    assert "Point" not in kinds
+
+    result = call_tool(indexed_rust_project, "find_newtypes", {"inner_type": "Person"})
+    assert [r['wrap_chain'] for r in result["results"]] == [["Staff", "Person"], ["Manager", "Staff", "Person"]]
+
+
+def test_types_satisfying_bounds(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_types_satisfying", {"bounds": "Summary + Clone"})
+    assert result.get("success") is True, f"find_types_satisfying failed: {result.get('error')}"
+    satisfying = {r['type_name']: r['satisfied_by'] for r in result["results"]}
+    assert set(satisfying) == {"Circle", "Rectangle", "Triangle"}
+    assert satisfying["Rectangle"] == {"Summary": "blanket", "Clone": "derive"}
+
+    result = call_tool(indexed_rust_project, "find_types_satisfying", {"bounds": "T: Display + Clone"})
+    satisfying = {r['type_name']: r['satisfied_by'] for r in result["results"]}
+    assert satisfying["Rectangle"] == {"Display": "impl", "Clone": "derive"}
+    assert "Circle" not in satisfying
+
+
+def test_find_implementations(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_implementations", {"trait_path": "traits::Area"})
+    assert result.get("success") is True, f"find_implementations failed: {result.get('error')}"
+    assert {(r['type_name'], r['impl_kind']) for r in result["results"]} == {
+        ("Rectangle", "handwritten"), ("Circle", "handwritten"), ("Triangle", "handwritten"),
+    }
+    assert all(r['end_line'] > r['line_number'] for r in result["results"])
+
+    result = call_tool(indexed_rust_project, "find_implementations", {"trait_path": "Summary"})
+    [blanket] = result["results"]
+    assert (blanket['type_name'], blanket['impl_kind'], blanket['constraints']) == ("T", "blanket", ["T: Describable"])
+
+    result = call_tool(indexed_rust_project, "find_implementations", {"trait_path": "Clone"})
+    derived = {r['type_name'] for r in result["results"] if r['impl_kind'] == 'derived'}
+    assert {"Rectangle", "Person", "Point"} <= derived
+
+
+def test_transitive_callees_expand_trait_dispatch(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_transitive_callees", {"function_name": "compare_areas"})
+    assert result.get("success") is True, f"find_transitive_callees failed: {result.get('error')}"
+    area_impls = [r for r in result["results"] if r['callee_name'] == "area"]
+    assert len(area_impls) == 3
+    assert all(r['confidence'] == round(1 / 3, 3) and not r['certain'] for r in area_impls)
+    assert all(r['edge_kinds'] == ["generic dispatch"] for r in area_impls)
+
+    result = call_tool(indexed_rust_project, "find_transitive_callees",
+                       {"function_name": "compare_areas", "expand_dispatch": False})
+    assert all(r['certain'] for r in result["results"])
+
+
+def test_unreachable_code(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_unreachable_code", {})
+    assert result.get("success") is True, f"find_unreachable_code failed: {result.get('error')}"
+    report = result["results"]
+    functions = {f['name'] for f in report['unreachable_functions']}
+    assert "unused_helper" in functions
+    assert "answer" not in functions
+
+    variants = {(v['enum'], v['name']) for v in report['unconstructed_variants']}
+    assert ("Status", "Pending") in variants
+    assert ("TrafficLight", "Red") not in variants
+
+
+def test_signature_change_impact(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_signature_impact", {"function_name": "factorial"})
+    assert result.get("success") is True, f"find_signature_impact failed: {result.get('error')}"
+    files = {os.path.basename(entry['file_path']): entry for entry in result["results"]["files"]}
+    assert "open_account_with_bonus" in {c['name'] for c in files["lib.rs"]['callers']}
+    assert [r['import_path'] for r in files["lib.rs"]['reexports']] == ["basic_functions::*"]
+    assert "test_factorial" in {t['name'] for t in files["basic_functions.rs"]['tests']}
+
+    result = call_tool(indexed_rust_project, "find_signature_impact", {"function_name": "area", "owner": "Rectangle"})
+    traits_rs = {os.path.basename(e['file_path']): e for e in result["results"]["files"]}["traits.rs"]
+    assert {(i['owner'], i['name']) for i in traits_rs['implementations']} >= {
+        ("Circle", "area"), ("Triangle", "area"), ("Area", "Area::area"),
+    }
+    assert ("compare_areas", "generic dispatch") in {(c['name'], c['call_kind']) for c in traits_rs['callers']}
+
+
+def test_circular_module_dependencies(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_circular_module_dependencies", {})
+    assert result.get("success") is True, f"find_circular_module_dependencies failed: {result.get('error')}"
+    [component] = result["results"]
+    assert component['modules'] == ["crate::error_handling", "crate::structs_enums"]
+    assert component['cycle'] == ["crate::error_handling", "crate::structs_enums", "crate::error_handling"]
+    imports = {edge['from']: edge['imports'] for edge in component['edges']}
+    assert imports["crate::structs_enums"] == ["use crate::error_handling::validate_age; (line 4)"]
+    assert imports["crate::error_handling"] == ["use crate::structs_enums::BankAccount; (line 7)"]
+
+
+def test_public_api(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "get_public_api", {"crate_name": "sample_project_rust"})
+    assert result.get("success") is True, f"get_public_api failed: {result.get('error')}"
+    api = {item['path']: item for item in result["results"]}
+    assert list(api) == sorted(api)
+    # lib.rs re-exports, including the `basic_functions::*` glob.
+    assert api["sample_project_rust::factorial"]['signature'] == "pub fn factorial(n: u32) -> u32"
+    assert "sample_project_rust::basic_functions::factorial" in api
+    person = api["sample_project_rust::Person"]
+    assert person['kind'] == 'struct' and person['signature'] == "pub struct Person"
+    assert "pub fn greet(&self) -> String" in person['methods']
+    assert "impl fmt::Display for Person" in person['trait_impls']
+    # Nested inline modules and their `pub use` re-exports.
+    for path in ("modules::geometry::shapes::Circle", "modules::utils::Circle", "modules::prelude::Circle"):
+        assert f"sample_project_rust::{path}" in api
+    assert "sample_project_rust::modules::data::public_function" in api
+    assert not any(path.endswith("::private_function") for path in api)
+
+
+def test_type_usages(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "analyze_code_relationships", {
+        "query_type": "find_type_usages",
+        "target": "Person"
+    })
+    assert result.get("success") is True, f"find_type_usages failed: {result.get('error')}"
+    rows = result.get("results", {}).get("results", [])
+    usages = {(r['usage'], r['user']) for r in rows}
+    assert ("construction", "greet_new_person") in usages  # Person::new
+    assert ("construction", "new") in usages  # Self { name, age }
+    assert ("parameter", "is_older_than") in usages
+    assert ("field_read", "get_name") in usages
+    assert ("field_write", "have_birthday") in usages
+    assert ("trait_impl", "Display") in usages
+    assert ("field", "Staff") in usages
+    assert ("generic_instantiation", "Roster") in usages  # members: Vec<Person>
+    assert ("generic_instantiation", "oldest") in usages  # -> Option<&Person>
+
+
+def test_trait_impl_coverage(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "get_trait_impl_coverage", {"trait_name": "Greetable"})
+    assert result.get("success") is True, f"get_trait_impl_coverage failed: {result.get('error')}"
+    [greetable] = result["results"]
+    assert greetable['required_methods'] == ["formal_greet"]
+    assert greetable['provided_methods'] == ["greet"]
+    impls = {impl['type_name']: impl for impl in greetable['impls']}
+    assert impls["Student"]['inherited_defaults'] == ["greet"]
+    assert impls["Student"]['implemented_methods'] == ["formal_greet"]
+    assert impls["Teacher"]['overridden_defaults'] == ["greet"]
+    assert impls["Teacher"]['missing_methods'] == []
+
+    result = call_tool(indexed_rust_project, "get_trait_impl_coverage", {"trait_name": "Container"})
+    [container] = result["results"]
+    [impl] = container['impls']
+    assert impl['associated_types'] == {"Item": "T"}
+    assert impl['inherited_defaults'] == ["is_empty"]
+
+
+def test_generic_instantiations(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_generic_instantiations", {"item_name": "Point"})
+    assert result.get("success") is True, f"find_generic_instantiations failed: {result.get('error')}"
+    [point] = result["results"]
+    assert point['generic_params'] == ["T"]
+    sites = {i['instantiation']: {(s['user'], s['site_kind']) for s in i['sites']} for i in point['instantiations']}
+    assert ("test_point_add", "call") in sites["Point<i32>"]  # Point::new(1, 2)
+    assert {("labeled_origin", "explicit"), ("labeled_origin", "call")} <= sites["Point<f64>"]
+    assert ("labeled_origin", "literal") in sites["Point<u8>"]
+
+    result = call_tool(indexed_rust_project, "find_generic_instantiations", {"item_name": "Pair"})
+    [pair] = result["results"]
+    assert "Pair<Point<f64>, &str>" in {i['instantiation'] for i in pair['instantiations']}
+
+
+def test_unhandled_results(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_unhandled_results", {})
+    assert result.get("success") is True, f"find_unhandled_results failed: {result.get('error')}"
+    findings = {(r['function'], r['callee'], r['pattern']) for r in result["results"]}
+    assert ("ignore_errors", "validate_username", "statement") in findings
+    assert ("ignore_errors", "validate_age", "let_underscore") in findings
+    assert ("ignore_errors", "divide", "ok_discarded") in findings
+    # Handled with unwrap_or, and functions returning other types, are not findings.
+    assert not any(callee == "parse_and_validate" for _, callee, _ in findings)
+    assert not any(pattern == "statement" and callee == "increment" for _, callee, pattern in findings)
+
+
+def test_unused_pub_items(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_unused_pub_items", {})
+    assert result.get("success") is True, f"find_unused_pub_items failed: {result.get('error')}"
+    findings = {f['name']: f for f in result["results"]}
+    assert findings["describe_token"]['suggested_visibility'] == "private"
+    assert findings["ignore_errors"]['file_path'].endswith("error_handling.rs")
+    # Used from other modules: structs_enums calls validate_age, lib.rs constructs Person.
+    assert "validate_age" not in findings
+    assert "Person" not in findings
+    assert "unused_helper" not in findings  # not pub
+
+    result = call_tool(indexed_rust_project, "find_unused_pub_items", {"scope": "crate"})
+    findings = {f['name']: f for f in result["results"]}
+    assert findings["validate_age"]['suggested_visibility'] == "pub(crate)"
+
+
+def test_code_health_report(indexed_rust_project):
+    limits = {"max_lines": 1000, "max_params": 10, "max_nesting": 1, "max_complexity": 100}
+    result = call_tool(indexed_rust_project, "get_code_health_report", limits)
+    assert result.get("success") is True, f"get_code_health_report failed: {result.get('error')}"
+    report = result["results"]
+    findings = {f['name']: f for f in report["functions"]}
+    # loop { match .. } inside the spawned closure, and for { if let .. } in drop.
+    assert findings["Worker::new"]['metrics']['nesting_depth'] == 2
+    assert findings["ThreadPool::drop"]['violations'] == ["nesting_depth"]
+    assert "SafeCounter::increment" not in findings
+    assert report["summary"]["nesting_depth"] == len(findings)
+
+    query = "MATCH (f:Function {name: 'drop'}) WHERE f.file_path ENDS WITH 'concurrency.rs' RETURN f.health_violations as violations"
+    assert run_query(indexed_rust_project, query)[0]['violations'] == ["nesting_depth"]
+
+
+def test_find_duplicates(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_duplicates", {"min_size": 10})
+    assert result.get("success") is True, f"find_duplicates failed: {result.get('error')}"
+    clusters = [{(f['owner'], f['name'], f['file_path'].rsplit('/', 1)[-1]) for f in c['functions']} for c in result["results"]]
+    # SharedData::clone_ref builds `Self { .. }` in one file and `SharedData { .. }` in the other.
+    assert any({("SharedData", "clone_ref", "lifetimes_references.rs"), ("SharedData", "clone_ref", "smart_pointers.rs")} <= c
+               for c in clusters)
+    # The constructors wrap different containers, so they are not duplicates.
+    assert not any(("SharedData", "new", "lifetimes_references.rs") in c for c in clusters)
+
+
+def test_diff_revisions(indexed_rust_project, tmp_path):
+    repo = tmp_path / "diffed"
+    (repo / "src").mkdir(parents=True)
+    (repo / "Cargo.toml").write_text('[package]\nname = "diffed"\nversion = "0.1.0"\n')
+
+    def commit(source, message):
+        (repo / "src" / "lib.rs").write_text(source)
+        subprocess.run(["git", "add", "-A"], cwd=repo, check=True)
+        subprocess.run(["git", "-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", message],
+                       cwd=repo, check=True)
+
+    subprocess.run(["git", "init", "-q"], cwd=repo, check=True)
+    commit("pub fn run() -> i32 { helper() }\nfn helper() -> i32 { 1 }\npub fn legacy() {}\n", "base")
+    commit("pub fn run(x: i32) -> i32 { helper() + extra(x) }\nfn helper() -> i32 { 1 }\nfn extra(x: i32) -> i32 { x }\n", "head")
+
+    result = call_tool(indexed_rust_project, "diff_revisions", {"repo_path": str(repo), "base": "HEAD~1"})
+    assert result.get("success") is True, f"diff_revisions failed: {result.get('error')}"
+    diff = result["results"]
+    assert [f['name'] for f in diff["functions"]["added"]] == ["extra"]
+    assert [f['name'] for f in diff["functions"]["removed"]] == ["legacy"]
+    [run] = diff["functions"]["changed"]
+    assert run['name'] == "run" and run['signature_changed'] is True
+    assert {(c['caller'], c['callee']) for c in diff["calls"]["added"]} == {("run", "extra")}
+    assert [a['path'] for a in diff["public_api"]["removed"]] == ["diffed::legacy"]
+    assert [a['path'] for a in diff["public_api"]["changed"]] == ["diffed::run"]
+
+    # The snapshots live apart from indexed repositories: no tool matching Function or Class sees them.
+    for side in ("base", "head"):
+        root = os.path.realpath(diff[side]["path"])
+        assert run_query(indexed_rust_project, f"""
+            MATCH (n) WHERE (n:Repository OR n:File OR n:Function OR n:Class)
+              AND coalesce(n.file_path, n.path) STARTS WITH '{root}'
+            RETURN count(n) as count
+        """) == [{"count": 0}]
+        [repository] = run_query(indexed_rust_project, f"""
+            MATCH (r:SnapshotRepository {{path: '{root}'}}) RETURN r.is_snapshot as is_snapshot, r.revision as revision
+        """)
+        assert repository["is_snapshot"] is True and len(repository["revision"]) == 40
+
+
+def test_check_semver(indexed_rust_project, tmp_path):
+    repo = tmp_path / "versioned"
+    (repo / "src").mkdir(parents=True)
+    (repo / "Cargo.toml").write_text('[package]\nname = "versioned"\nversion = "1.0.0"\n')
+
+    def commit(source, message):
+        (repo / "src" / "lib.rs").write_text(source)
+        subprocess.run(["git", "add", "-A"], cwd=repo, check=True)
+        subprocess.run(["git", "-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", message],
+                       cwd=repo, check=True)
+
+    subprocess.run(["git", "init", "-q"], cwd=repo, check=True)
+    commit("pub trait Store { fn get(&self) -> i32; }\npub enum Mode { Fast }\n"
+           "pub fn run() -> i32 { 1 }\npub fn legacy() {}\n", "1.0.0")
+    commit("pub trait Store { fn get(&self) -> i32; }\npub enum Mode { Fast }\n"
+           "pub fn run() -> i32 { 2 }\npub fn legacy() {}\n", "1.0.1")
+    commit("pub trait Store { fn get(&self) -> i32; fn put(&mut self, v: i32); fn len(&self) -> usize { 0 } }\n"
+           "pub enum Mode { Fast, Slow }\npub fn run() -> i32 { 2 }\npub fn fresh() {}\n", "2.0.0")
+
+    result = call_tool(indexed_rust_project, "check_semver", {"repo_path": str(repo), "base": "HEAD~2", "head": "HEAD~1"})
+    assert result.get("success") is True, f"check_semver failed: {result.get('error')}"
+    assert result["results"]["level"] == "patch"
+    assert [(c['change'], c['path']) for c in result["results"]["changes"]] == [("implementation_changed", "run")]
+
+    result = call_tool(indexed_rust_project, "check_semver", {"repo_path": str(repo), "base": "HEAD~1"})
+    report = result["results"]
+    assert report["level"] == "major"
+    changes = {(c['level'], c['change'], c['path']) for c in report["changes"]}
+    assert ("major", "removed", "legacy") in changes
+    assert ("major", "required_method_added", "Store::put") in changes
+    assert ("minor", "provided_method_added", "Store::len") in changes
+    assert ("major", "variant_added", "Mode::Slow") in changes
+    assert ("minor", "added", "fresh") in changes
+    assert report["summary"]["major"] == 3
+
+
+def test_git_blame_metadata(indexed_rust_project):
+    query = f"MATCH (f:Function {{name: 'shared_counter'}}) WHERE f.file_path = '{CONCURRENCY_RS}' RETURN f.last_commit as commit, f.last_author as author, f.owners as owners, f.age_days as age"
+    [func] = run_query(indexed_rust_project, query)
+    assert len(func['commit']) == 40
+    assert func['author'] in func['owners']
+    assert func['age'] >= 0
+
+    result = call_tool(indexed_rust_project, "find_recently_changed_functions", {"days": 100000, "calls": "new"})
+    assert result.get("success") is True, f"find_recently_changed_functions failed: {result.get('error')}"
+    callers = {(r['owner'], r['name']) for r in result["results"]}
+    assert ("ThreadPool", "new") in callers  # calls Worker::new
+    assert all(r['last_modified'] for r in result["results"])
+
+
+def test_hotspots(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_hotspots", {"since_days": 100000, "level": "file", "limit": 1000})
+    assert result.get("success") is True, f"find_hotspots failed: {result.get('error')}"
+    files = {h['file_path']: h for h in result["results"]}
+    concurrency = files[os.path.realpath(CONCURRENCY_RS)]
+    assert concurrency['commits'] >= 1
+    assert concurrency['score'] == concurrency['commits'] * concurrency['complexity']
+    scores = [h['score'] for h in result["results"]]
+    assert scores == sorted(scores, reverse=True)
+
+    result = call_tool(indexed_rust_project, "find_hotspots", {"since_days": 100000, "limit": 5})
+    assert len(result["results"]) <= 5
+    assert all(h['commits'] >= 1 for h in result["results"])
+
+
+def test_closure_captures(indexed_rust_project):
+    def captures(name):
+        result = call_tool(indexed_rust_project, "analyze_code_relationships",
+                           {"query_type": "find_closure_captures", "target": name, "context": "iterators_closures.rs"})
+        assert "error" not in result, result
+        return {(r['function_name'], r['mode'], r['reason']) for r in result["results"]}
+
+    assert captures("total") == {("capture_modes", "mut_ref", "assigned")}
+    assert captures("names") == {("capture_modes", "mut_ref", "calls push")}
+    assert captures("label") == {("capture_modes", "ref", "calls len"), ("capture_modes", "move", "used by value")}
+    assert captures("factor") >= {("closure_capture", "ref", "read"), ("closure_move", "move", "move closure")}
+
+    query = "MATCH (cl:Closure)-[r:CAPTURES]->(v:Variable {name: 'total'}) WHERE v.file_path ENDS WITH 'iterators_closures.rs' RETURN r.mode as mode"
+    assert [r['mode'] for r in run_query(indexed_rust_project, query)] == ["mut_ref"]
+
+
+def test_ask_code_graph(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "ask_code_graph", {"question": "who calls SafeCounter::increment?"})
+    assert result.get("success") is True, f"ask_code_graph failed: {result.get('error')}"
+    answer = result["results"]
+    assert answer["template"] == "callers"
+    assert answer["parameters"]["owner"] == "SafeCounter" and "$name" in answer["cypher"]
+    assert "test_safe_counter" in {r['caller'] for r in answer["results"]}
+
+    result = call_tool(indexed_rust_project, "ask_code_graph", {"question": "who calls SafeCounter::increment outside tests?"})
+    assert "test_safe_counter" not in {r['caller'] for r in result["results"]["results"]}
+
+    result = call_tool(indexed_rust_project, "ask_code_graph", {"question": "who implements Greetable?"})
+    assert {"Student", "Teacher"} <= {r['type'] for r in result["results"]["results"]}
+
+    result = call_tool(indexed_rust_project, "ask_code_graph", {"question": "delete everything"})
+    assert "Could not translate" in result["error"]
+
+
+def test_semantic_search(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "semantic_search", {"query": "first even number in a list of numbers", "limit": 5})
+    assert result.get("success") is True, f"semantic_search failed: {result.get('error')}"
+    names = [r['name'] for r in result["results"]]
+    assert "find_first_even" in names
+    scores = [r['score'] for r in result["results"]]
+    assert scores == sorted(scores, reverse=True)
+
+    result = call_tool(indexed_rust_project, "semantic_search",
+                       {"query": "counter shared between threads behind a mutex", "limit": 3, "file_path": "concurrency.rs"})
+    assert "shared_counter" in [r['name'] for r in result["results"]]
+
+
+def test_search_by_signature(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "search_by_signature", {"pattern": "fn(&[i32]) -> Option<i32>"})
+    assert result.get("success") is True, f"search_by_signature failed: {result.get('error')}"
+    matches = {m['name']: m for m in result["results"]}
+    assert matches["find_first_even"]['cost'] == 0
+    assert result["results"][0]['cost'] == 0
+
+    result = call_tool(indexed_rust_project, "search_by_signature", {"pattern": "Fn(i32) -> i32"})
+    matches = {m['name']: m for m in result["results"]}
+    assert matches["make_adder"]['match'] == "returns"
+    assert matches["negate"]['match'] == "signature"
+
+
+def test_why_depends(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "why_depends", {"module_a": "error_handling", "module_b": "structs_enums"})
+    assert result.get("success") is True, f"why_depends failed: {result.get('error')}"
+    why = result["results"]
+    assert why["depends"] is True
+    assert any("structs_enums::BankAccount" in statement for statement in why["evidence"]["imports"])
+    assert ("checked_withdraw", "BankAccount", "parameter") in {
+        (u['source'], u['target'], u.get('role')) for u in why["evidence"]["type_usages"]}
+
+    result = call_tool(indexed_rust_project, "why_depends", {"module_a": "crate::structs_enums", "module_b": "error_handling"})
+    assert "validate_age" in {c['target'] for c in result["results"]["evidence"]["calls"]}
+
+    result = call_tool(indexed_rust_project, "why_depends", {"module_a": "concurrency", "module_b": "smart_pointers"})
+    assert result["results"]["depends"] is False
+
+
+def test_check_layering(indexed_rust_project):
+    config_path = os.path.join(RUST_SAMPLE_PROJECT_PATH, ".cgc-layers.json")
+    result = call_tool(indexed_rust_project, "check_layering", {"repo_path": RUST_SAMPLE_PROJECT_PATH})
+    assert "error" in result and ".cgc-layers.json" in result["error"]
+
+    with open(config_path, "w") as f:
+        f.write('{"rules": [{"name": "errors stay low", "from": "error_handling", "forbid": ["structs_enums"]},'
+                ' {"from": "concurrency", "forbid": ["smart_pointers"]}]}')
+    try:
+        result = call_tool(indexed_rust_project, "check_layering", {"repo_path": RUST_SAMPLE_PROJECT_PATH})
+    finally:
+        os.remove(config_path)
+    assert result.get("success") is True, f"check_layering failed: {result.get('error')}"
+    violations = result["results"]["violations"]
+    assert [(v['rule'], v['source_module'], v['target_module']) for v in violations] == [
+        ("errors stay low", "crate::error_handling", "crate::structs_enums")]
+    assert "checked_withdraw" in {edge['source'] for edge in violations[0]['edges']}
+
+    stored = run_query(indexed_rust_project, f"MATCH (r:Repository {{path: '{RUST_SAMPLE_PROJECT_PATH}'}}) RETURN r.layering_violations as v")
+    assert stored[0]['v'] == ["crate::error_handling -> crate::structs_enums: errors stay low"]
+
+
+def test_export_subgraph(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "export_subgraph", {"root": "ignore_errors", "depth": 1})
+    assert result.get("success") is True, f"export_subgraph failed: {result.get('error')}"
+    subgraph = result["results"]
+    names = {node['id']: node['name'] for node in subgraph["nodes"]}
+    assert [node['name'] for node in subgraph["nodes"] if node['root']] == ["ignore_errors"]
+    assert {"validate_username", "validate_age", "divide", "parse_and_validate"} <= set(names.values())
+    assert {(names[e['source']], e['type']) for e in subgraph["edges"]} == {("ignore_errors", "CALLS")}
+
+    result = call_tool(indexed_rust_project, "export_subgraph", {"root": "structs_enums", "root_kind": "module", "depth": 1})
+    assert "validate_age" not in {node['name'] for node in result["results"]["nodes"]}
+    result = call_tool(indexed_rust_project, "export_subgraph",
+                       {"root": "structs_enums", "root_kind": "module", "depth": 1, "include_external": True, "format": "mermaid"})
+    assert result["results"].startswith("flowchart LR")
+    assert '["validate_age"]' in result["results"]
+
+
+def test_taint_flows(indexed_rust_project):
+    [func] = run_query(indexed_rust_project, "MATCH (f:Function {name: 'execute_command'}) RETURN f.call_names as calls")
+    assert "std::process::Command::new" in func['calls']
+
+    result = call_tool(indexed_rust_project, "find_taint_flows", {"sources": ["read_file_contents"], "sinks": ["Command::new"]})
+    assert result.get("success") is True, f"find_taint_flows failed: {result.get('error')}"
+    [finding] = result["results"]["findings"]
+    assert finding["source"]["function"] == "run_script"
+    assert finding["sink"]["call"] == "std::process::Command::new"
+    assert [(step['name'], step['role']) for step in finding["chain"]] == [
+        ("run_script", "reads source"), ("execute_command", "calls sink")]
+
+    result = call_tool(indexed_rust_project, "find_taint_flows", {})
+    chains = {tuple(step['name'] for step in f["chain"]) for f in result["results"]["findings"]
+              if f["source"]["call"] == "File::open"}
+    assert ("read_file_contents", "run_script", "execute_command") in chains
+
+
+def test_god_objects(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_god_objects", {"max_methods": 7, "max_trait_impls": 100, "max_fields": 100})
+    assert result.get("success") is True, f"find_god_objects failed: {result.get('error')}"
+    types = {(t['name'], os.path.basename(t['file_path'])): t for t in result["results"]}
+    person = types[("Person", "structs_enums.rs")]
+    assert person['methods'] == 8 and person['violations'] == ["methods"]
+    assert person['fan_in'] > 0
+    assert ("BankAccount", "structs_enums.rs") not in types
+    assert all(t['methods'] > 7 for t in result["results"])
+
+
+def test_monomorphization_estimate(indexed_rust_project):
+    generics_rs = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "generics.rs")
+    result = call_tool(indexed_rust_project, "estimate_monomorphization", {"limit": 1000, "file_path": generics_rs})
+    assert result.get("success") is True, f"estimate_monomorphization failed: {result.get('error')}"
+    ranking = {item['name']: item for item in result["results"]}
+    assert {"T=i32", "T=f64", "T=u8"} <= set(ranking["Point::new"]['instantiations'])
+    assert ranking["Point::new"]['estimated_lines'] == ranking["Point::new"]['copies'] * ranking["Point::new"]['lines']
+    assert set(ranking["twin"]['instantiations']) == {"T=i32", "T=f64"}
+    # twin<T> calls pair(value, value), so each copy of twin instantiates pair<T, T>.
+    assert {"T=i32, U=i32", "T=f64, U=f64"} <= set(ranking["pair"]['instantiations'])
+    lines = [item['estimated_lines'] for item in result["results"]]
+    assert lines == sorted(lines, reverse=True)
+
+
+def test_items_under_feature(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "items_under_feature", {"feature": "metrics"})
+    assert result.get("success") is True, f"items_under_feature failed: {result.get('error')}"
+    report = result["results"]
+    assert {item['name'] for item in report["items"]} == {"record_timing", "report_timing"}
+    assert all('feature = "metrics"' in item['cfg'] for item in report["items"])
+    # record_timing is only called from gated code, report_timing also from always-compiled code.
+    assert [(u['user'], u['target']) for u in report["ungated_users"]] == [("timed_square", "report_timing")]
+
+    [row] = run_query(indexed_rust_project, "MATCH (f:Function {name: 'test_circle_area'}) WHERE f.file_path ENDS WITH 'modules.rs' RETURN f.cfg as cfg, f.features as features")
+    assert row['cfg'] == ["test"] and row['features'] == []
+
+
+def test_canonical_paths(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "get_canonical_paths", {"symbol": "structs_enums::Person"})
+    assert result.get("success") is True, f"get_canonical_paths failed: {result.get('error')}"
+    [person] = result["results"]
+    assert person['definition_path'] == "sample_project_rust::structs_enums::Person"
+    assert {"sample_project_rust::Person", "sample_project_rust::structs_enums::Person"} <= set(person['public_paths'])
+    assert person['recommended_import'] == "use sample_project_rust::Person;"
+    assert person['internal_import'] == "use crate::Person;"
+
+    result = call_tool(indexed_rust_project, "get_canonical_paths", {"symbol": "Person::new"})
+    paths = {item['definition_path']: item for item in result["results"]}
+    assert "sample_project_rust::Person::new" in paths["sample_project_rust::structs_enums::Person::new"]['public_paths']
+
+
+def test_glob_imports(indexed_rust_project):
+    modules_rs = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "modules.rs")
+    result = call_tool(indexed_rust_project, "find_glob_imports", {"file_path": modules_rs})
+    assert result.get("success") is True, f"find_glob_imports failed: {result.get('error')}"
+    globs = {glob['line_number']: glob for glob in result["results"]}
+    shapes = globs[98]
+    assert shapes['is_reexport'] and {"Circle", "Rectangle"} <= set(shapes['provided'])
+    tests_glob = globs[129]
+    assert tests_glob['target_module'] == "crate::modules"
+    assert tests_glob['used'] == ["data", "geometry"]
+    assert tests_glob['suggestion'] == "use super::{data, geometry};"
+
+
+def test_method_shadowing(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_method_shadowing", {"type_name": "Money"})
+    assert result.get("success") is True, f"find_method_shadowing failed: {result.get('error')}"
+    [shadowing] = result["results"]
+    assert (shadowing['method'], shadowing['traits']) == ("add", ["Add"])
+    assert len(shadowing['trait_methods']) == 1
+    sites = {(site['caller'], site['resolves_to'], site['ambiguous_with']) for site in shadowing['call_sites']}
+    assert ("add_money", "inherent", "Add::add") in sites
+
+    # `a.add(b)` keeps only the edge to the inherent method.
+    rows = run_query(indexed_rust_project, """
+        MATCH (:Function {name: 'add_money'})-[r:CALLS]->(m:Function {name: 'add'})
+        RETURN m.impl_trait as impl_trait, r.shadowing as shadowing
+    """)
+    assert rows == [{"impl_trait": None, "shadowing": "inherent"}]
+
+
+def test_central_functions(indexed_rust_project):
+    [row] = run_query(indexed_rust_project, "MATCH (f:Function) RETURN sum(f.pagerank) as total, count(f.betweenness) as scored, count(f) as functions")
+    assert abs(row['total'] - 1.0) < 1e-6 and row['scored'] == row['functions']
+
+    for metric in ("pagerank", "betweenness"):
+        result = call_tool(indexed_rust_project, "find_central_functions", {"metric": metric, "limit": 10})
+        assert result.get("success") is True, f"find_central_functions failed: {result.get('error')}"
+        scores = [item[metric] for item in result["results"]]
+        assert len(scores) == 10 and scores == sorted(scores, reverse=True)
+        assert result["results"][0]['callers'] > 0
+
+    result = call_tool(indexed_rust_project, "find_central_functions", {"metric": "degree"})
+    assert "error" in result
+
+
+def test_module_boundaries(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "suggest_module_boundaries", {"min_weight": 1})
+    assert result.get("success") is True, f"suggest_module_boundaries failed: {result.get('error')}"
+    report = result["results"]
+    assert report["community_count"] > 1
+    assert -0.5 <= report["modularity"]["modules"] <= 1 and -0.5 <= report["modularity"]["communities"] <= 1
+    for suggestion in report["suggestions"]:
+        assert suggestion['suggested_module'] != suggestion['module']
+        assert suggestion['links_to_suggested_module'] > suggestion['links_to_own_module']
+    # Methods are clustered with their type rather than on their own.
+    assert "new" not in {s['name'] for s in report["suggestions"]}
+
+
+def test_similar_functions(indexed_rust_project):
+    lifetimes_rs = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "lifetimes_references.rs")
+    result = call_tool(indexed_rust_project, "find_similar_functions", {"function_name": "clone_ref", "file_path": lifetimes_rs})
+    assert result.get("success") is True, f"find_similar_functions failed: {result.get('error')}"
+    [entry] = result["results"]
+    top = entry["similar"][0]
+    assert (top['name'], os.path.basename(top['file_path'])) == ("clone_ref", "smart_pointers.rs")
+    assert top['same_body'] and top['structural'] == 1.0
+    scores = [match['score'] for match in entry["similar"]]
+    assert scores == sorted(scores, reverse=True) and all(score >= 0.5 for score in scores)
+
+    result = call_tool(indexed_rust_project, "find_similar_functions", {"function_name": "no_such_function"})
+    assert "error" in result
+
+
+def test_call_hierarchy(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "call_hierarchy", {"symbol": "greet_new_person", "direction": "outgoing", "depth": 2})
+    assert result.get("success") is True, f"call_hierarchy failed: {result.get('error')}"
+    [tree] = result["results"]
+    assert tree['item']['name'] == "greet_new_person" and tree['item']['kind'] == 12
+    assert tree['item']['uri'].startswith("file://") and tree['item']['uri'].endswith("lib.rs")
+    callees = {entry['to']['name']: entry for entry in tree['calls']}
+    assert {"Person::new", "Person::greet", "Person::get_name"} <= set(callees)
+    assert callees["Person::new"]['to']['kind'] == 9
+    # Call sites are zero-based lines in the caller.
+    assert callees["Person::new"]['fromRanges'] == [{"start": {"line": 30, "character": 0}, "end": {"line": 30, "character": 0}}]
+
+    result = call_tool(indexed_rust_project, "call_hierarchy", {"symbol": "Person::get_name", "direction": "incoming", "depth": 1})
+    callers = {entry['from']['name'] for tree in result["results"] for entry in tree['calls']}
+    assert "greet_new_person" in callers
+    assert all('calls' not in entry for tree in result["results"] for entry in tree['calls'])
+
+
+def test_type_hierarchy(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "type_hierarchy", {"name": "traits::Shape"})
+    assert result.get("success") is True, f"type_hierarchy failed: {result.get('error')}"
+    [shape] = result["results"]
+    assert [(t['name'], t['relation']) for t in shape['supertypes']] == [
+        ("Area", "supertrait"), ("Perimeter", "supertrait"), ("fmt::Display", "supertrait")]
+    assert ("Rectangle", "implementor") in {(t['name'], t['relation']) for t in shape['subtypes']}
+    assert shape['text'].startswith("Shape (trait)\n") and "Perimeter (trait) [supertrait]" in shape['text']
+
+    # Area's subtraits carry their own implementors.
+    [area] = call_tool(indexed_rust_project, "type_hierarchy", {"name": "Area"})["results"]
+    subtraits = {t['name']: t for t in area['subtypes'] if t['relation'] == "subtrait"}
+    assert "Rectangle" in {t['name'] for t in subtraits["Shape"]['children']}
+
+    [summary] = call_tool(indexed_rust_project, "type_hierarchy", {"name": "Summary"})["results"]
+    [blanket] = [t for t in summary['subtypes'] if t['relation'] == "blanket"]
+    assert blanket['constraints'] == ["T: Describable"]
+
+    [clone] = call_tool(indexed_rust_project, "type_hierarchy", {"name": "Clone"})["results"]
+    assert {"Rectangle", "Person"} <= {t['name'] for t in clone['subtypes'] if t['relation'] == "derived"}
+
+    [rectangle] = [t for t in call_tool(indexed_rust_project, "type_hierarchy", {"name": "Rectangle"})["results"]
+                   if t['file_path'].endswith("traits.rs")]
+    implemented = {t['name']: t for t in rectangle['supertypes']}
+    assert {"Area", "Perimeter", "Shape"} <= set(implemented)
+    assert {t['name'] for t in implemented["Shape"]['children']} == {"Area", "Perimeter", "fmt::Display"}
+
+
+def test_module_dependency_matrix(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "module_dependency_matrix", {"crate_name": "sample_project_rust"})
+    assert result.get("success") is True, f"module_dependency_matrix failed: {result.get('error')}"
+    dsm = result["results"]
+    modules = dsm['modules']
+    assert {"crate", "crate::structs_enums", "crate::error_handling"} <= set(modules)
+    assert len(dsm['matrix']) == len(modules) and all(len(row) == len(modules) for row in dsm['matrix'])
+    # `use crate::structs_enums::BankAccount;` in error_handling.rs
+    source, target = modules.index("crate::error_handling"), modules.index("crate::structs_enums")
+    [cell] = [c for c in dsm['cells'] if c['source'] == "crate::error_handling" and c['target'] == "crate::structs_enums"]
+    assert cell['kinds'].get('imports', 0) >= 1 and dsm['matrix'][source][target] == cell['count']
+    assert all(dsm['matrix'][i][i] == 0 for i in range(len(modules)))
+    in_cycle = {module for cycle in dsm['cycles'] for module in cycle}
+    assert target < source or {modules[source], modules[target]} <= in_cycle
+
+    result = call_tool(indexed_rust_project, "module_dependency_matrix", {"crate_name": "sample_project_rust", "format": "csv"})
+    header, *rows = result["results"].splitlines()
+    assert header.split(",")[1:] == modules and len(rows) == len(modules)
+
+    result = call_tool(indexed_rust_project, "module_dependency_matrix", {"crate_name": "no_such_crate"})
+    assert "error" in result
+
+
+def test_doc_comments(indexed_rust_project):
+    query = "MATCH (f:File) WHERE f.path ENDS WITH 'sample_project_rust/src/lib.rs' RETURN f.docstring as docstring"
+    assert [r['docstring'] for r in run_query(indexed_rust_project, query)] == ["Main library file for Rust sample project"]
+
+    query = """
+        MATCH (n) WHERE (n:Function AND n.name = 'simple_function') OR (n:Class AND n.name = 'Person' AND n.file_path ENDS WITH 'structs_enums.rs')
+           OR (n:Macro AND n.name = 'make_constant_fns')
+        RETURN n.name as name, n.docstring as docstring
+    """
+    docs = {r['name']: r['docstring'] for r in run_query(indexed_rust_project, query)}
+    assert docs == {
+        "simple_function": "Simple function with single return value",  # a `/** */` block
+        "Person": "Basic struct with public fields",  # above `#[derive(...)]`
+        "make_constant_fns": "Generates functions returning a constant",
+    }
+
+    result = call_tool(indexed_rust_project, "get_public_api", {"crate_name": "sample_project_rust"})
+    [entry] = [e for e in result["results"] if e['path'] == "sample_project_rust::basic_functions::simple_function"]
+    assert entry['docstring'] == "Simple function with single return value"
+
+
+def test_doc_coverage(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "get_doc_coverage", {"crate_name": "sample_project_rust", "min_coverage": 100})
+    assert result.get("success") is True, f"get_doc_coverage failed: {result.get('error')}"
+    report = result["results"]
+    assert 0 < report['coverage'] < 100 and report['passed'] is False
+    [crate] = report['crates']
+    assert crate['crate'] == "sample_project_rust" and crate['total'] == report['total']
+    traits = next(m for m in report['modules'] if m['module'] == "crate::traits")
+    assert traits['below_threshold'] and traits['total'] == traits['documented'] + len(traits['missing'])
+    missing = {(item['kind'], item['line_number']) for item in traits['missing']}
+    assert ("struct", 51) in missing  # Rectangle has no doc comment
+    assert ("function", 167) not in missing  # print_description does
+
+    result = call_tool(indexed_rust_project, "get_doc_coverage", {"crate_name": "sample_project_rust"})
+    assert result["results"]['passed'] is True
+
+    result = call_tool(indexed_rust_project, "get_doc_coverage", {"crate_name": "no_such_crate"})
+    assert "error" in result
+
+
+def test_tasks(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "list_tasks", {"subsystem": "traits"})
+    assert result.get("success") is True, f"list_tasks failed: {result.get('error')}"
+    [task] = result["results"]
+    assert (task['kind'], task['assignee'], task['line_number']) == ("TODO", "geometry", 135)
+    assert task['text'].startswith("Heron's formula") and task['function'] == "area" and task['owner'] == "Triangle"
+    assert task['module'] == "crate::traits"
+
+    # Module-level tasks have no function.
+    [fixme] = call_tool(indexed_rust_project, "list_tasks", {"subsystem": "error_handling", "kinds": ["FIXME"]})["results"]
+    assert fixme['line_number'] == 90 and fixme['function'] is None
+
+    assert call_tool(indexed_rust_project, "list_tasks", {"subsystem": "traits", "kinds": ["HACK"]})["results"] == []
+
+
+def test_literals(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_literal", {"text": "division by zero"})
+    assert result.get("success") is True, f"find_literal failed: {result.get('error')}"
+    producers = {(r['function'], os.path.basename(r['file_path'])) for r in result["results"]}
+    assert {("divide", "basic_functions.rs"), ("divide", "error_handling.rs")} <= producers
+    assert all(r['value'] == "Division by zero" and r['kind'] == "string" for r in result["results"])
+
+    [limit] = call_tool(indexed_rust_project, "find_literal", {"text": "10_000.0", "kind": "number", "exact": True})["results"]
+    assert limit['function'] == "checked_withdraw" and limit['line_number'] == 114
+
+    # Trivial numbers are not indexed.
+    assert call_tool(indexed_rust_project, "find_literal", {"text": "0", "kind": "number", "exact": True})["results"] == []
+
+
+def test_allocation_sites(indexed_rust_project):
+    result = call_tool(indexed_rust_project, "find_allocation_sites", {"function_name": "greet_new_person"})
+    assert result.get("success") is True, f"find_allocation_sites failed: {result.get('error')}"
+    report = result["results"]
+    sites = {(s['function'], s['call'], s['line_number']): s for s in report['sites']}
+    assert sites[("greet_new_person", "name.to_string", 31)]['depth'] == 0
+    default = sites[("default_person", "String::from", 70)]
+    assert default['allocation'] == "String" and default['call_path'] == ["greet_new_person", "default_person"]
+    assert report['summary']["String"] == sum(s['allocation'] == "String" for s in report['sites'])
+
+    result = call_tool(indexed_rust_project, "find_allocation_sites", {"function_name": "greet_new_person", "max_depth": 0})
+    assert {s['function'] for s in result["results"]['sites']} == {"greet_new_person"}
+
+
+def test_clippy_findings(indexed_rust_project, tmp_path):
+    # A directory without Cargo.toml cannot be linted, whether or not cargo is installed.
+    result = call_tool(indexed_rust_project, "run_clippy", {"path": str(tmp_path)})
+    assert "error" in result
+
+    result = call_tool(indexed_rust_project, "find_clippy_findings", {"lint": "needless_return", "min_complexity": 1})
+    assert result.get("success") is True, f"find_clippy_findings failed: {result.get('error')}"
+    assert all(d['lint'] == "clippy::needless_return" for f in result["results"] for d in f['diagnostics'])
+
+
+def test_coverage_import(indexed_rust_project, tmp_path):
+    sample_src = os.path.join(os.path.dirname(__file__), "sample_project_rust", "src")
+    report = tmp_path / "lcov.info"
+    report.write_text(f"SF:{os.path.join(sample_src, 'lib.rs')}\nDA:30,1\nDA:31,1\nDA:32,1\nDA:33,0\nend_of_record\n")
+    result = call_tool(indexed_rust_project, "import_coverage", {"report_path": str(report)})
+    assert result.get("success") is True, f"import_coverage failed: {result.get('error')}"
+    assert result["results"]["covered_functions"] >= 1
+
+    query = """
+        MATCH (f:Function {name: 'greet_new_person'}) WHERE f.file_path ENDS WITH 'lib.rs'
+        RETURN f.line_coverage as line_coverage, f.uncovered_lines as uncovered_lines, f.covered_by_tests as covered
+    """
+    greet = run_query(indexed_rust_project, query)[0]
+    assert greet['covered'] is True
+    assert greet['line_coverage'] == 0.75
+    assert greet['uncovered_lines'] == [33]
+
+    result = call_tool(indexed_rust_project, "find_untested_code", {"entry_point": "greet_new_person"})
+    assert result.get("success") is True, f"find_untested_code failed: {result.get('error')}"
+    assert result["results"]["tested"] >= 1
+    assert all(f['name'] != 'greet_new_person' for f in result["results"]["untested"])
+
+    test_report = tmp_path / "test_largest.info"
+    test_report.write_text(f"SF:{os.path.join(sample_src, 'generics.rs')}\nDA:13,1\nDA:17,1\nDA:409,1\nend_of_record\n")
+    result = call_tool(indexed_rust_project, "import_coverage", {"report_path": str(test_report), "test_name": "test_largest"})
+    assert result.get("success") is True, f"import_coverage failed: {result.get('error')}"
+    query = "MATCH (:Function {name: 'test_largest'})-[:COVERS]->(f:Function) RETURN f.name as name"
+    assert "largest" in [item['name'] for item in run_query(indexed_rust_project, query)]
+
+
+def test_benchmark_import(indexed_rust_project, tmp_path):
+    benchmark_dir = tmp_path / "target" / "criterion" / "largest"
+    (benchmark_dir / "new").mkdir(parents=True)
+    (benchmark_dir / "change").mkdir()
+    (benchmark_dir / "new" / "benchmark.json").write_text(
+        '{"group_id": "largest", "function_id": null, "value_str": null, "full_id": "largest", "title": "largest"}')
+    (benchmark_dir / "new" / "estimates.json").write_text(
+        '{"mean": {"point_estimate": 120.0}, "median": {"point_estimate": 118.0}, "std_dev": {"point_estimate": 4.0}}')
+    (benchmark_dir / "change" / "estimates.json").write_text(
+        '{"mean": {"point_estimate": 0.2, "confidence_interval": {"lower_bound": 0.15, "upper_bound": 0.25}}}')
+
+    result = call_tool(indexed_rust_project, "import_benchmarks", {"path": str(tmp_path)})
+    assert result.get("success") is True, f"import_benchmarks failed: {result.get('error')}"
+    assert result["results"]["linked"] == 1
+
+    result = call_tool(indexed_rust_project, "find_benchmarks", {"verdict": "regressed", "function_name": "largest"})
+    assert result.get("success") is True, f"find_benchmarks failed: {result.get('error')}"
+    benchmark = result["results"][0]
+    assert benchmark["mean_ns"] == 120.0
+    assert any(func["file_path"].endswith("generics.rs") for func in benchmark["functions"])
+
+
+def test_profile_import(indexed_rust_project, tmp_path):
+    profile = tmp_path / "out.folded"
+    profile.write_text(
+        "rust_sample::greet_new_person::h0123456789abcdef;rust_sample::structs_enums::Person::new 10\n"
+        "rust_sample::greet_new_person;rust_sample::structs_enums::Person::greet;alloc::fmt::format 5\n"
+        "[unknown];std::rt::lang_start 3\n"
+    )
+    result = call_tool(indexed_rust_project, "import_profile", {"profile_path": str(profile)})
+    assert result.get("success") is True, f"import_profile failed: {result.get('error')}"
+    assert result["results"]["samples"] == 18
+
+    query = "MATCH (f:Function {name: 'greet_new_person'}) RETURN f.total_samples as total, f.self_samples as self"
+    greet = run_query(indexed_rust_project, query)[0]
+    assert greet['total'] == 15 and greet['self'] == 0
+
+    result = call_tool(indexed_rust_project, "find_hot_functions", {"limit": 1})
+    assert result.get("success") is True, f"find_hot_functions failed: {result.get('error')}"
+    assert result["results"][0]["name"] == "greet_new_person"
+
+    result = call_tool(indexed_rust_project, "export_subgraph", {"root": "greet_new_person", "min_samples": 1})
+    assert result.get("success") is True, f"export_subgraph failed: {result.get('error')}"
+    assert all(edge["samples"] >= 1 for edge in result["results"]["edges"])
+
+
+def test_history(indexed_rust_project, tmp_path):
+    repo = tmp_path / "timeline"
+    (repo / "src").mkdir(parents=True)
+    (repo / "Cargo.toml").write_text('[package]\nname = "timeline"\nversion = "0.1.0"\n')
+
+    def commit(source, tag):
+        (repo / "src" / "lib.rs").write_text(source)
+        subprocess.run(["git", "add", "-A"], cwd=repo, check=True)
+        subprocess.run(["git", "-c", "user.name=test", "-c", "user.email=test@example.com", "commit", "-qm", tag],
+                       cwd=repo, check=True)
+        subprocess.run(["git", "tag", tag], cwd=repo, check=True)
+
+    subprocess.run(["git", "init", "-q"], cwd=repo, check=True)
+    commit("pub fn run() -> i32 { 1 }\nfn helper() -> i32 { 1 }\n", "v1.0")
+    commit("pub fn run() -> i32 { helper() }\nfn helper() -> i32 { 1 }\n", "v1.1")
+    commit("pub fn run() -> i32 { 2 }\nfn helper() -> i32 { 2 }\npub fn fresh() {}\n", "v1.2")
+
+    result = call_tool(indexed_rust_project, "record_history", {"repo_path": str(repo), "revisions": ["v1.0", "v1.1", "v1.2"]})
+    assert result.get("success") is True, f"record_history failed: {result.get('error')}"
+
+    result = call_tool(indexed_rust_project, "call_history", {"caller": "run", "callee": "helper", "repo_path": str(repo)})
+    assert result.get("success") is True, f"call_history failed: {result.get('error')}"
+    [edge] = result["results"]
+    assert edge["started_in"]["label"] == "v1.1"
+    assert edge["ranges"][0]["until"]["label"] == "v1.2"
+    assert edge["calls_in_latest"] is False
+
+    result = call_tool(indexed_rust_project, "function_history", {"name": "helper", "repo_path": str(repo)})
+    [helper] = result["results"]
+    assert [rev["label"] for rev in helper["changed_in"]] == ["v1.2"]
+
+    result = call_tool(indexed_rust_project, "module_as_of", {"repo_path": str(repo), "path": "src", "revision": "v1.1"})
+    assert result.get("success") is True, f"module_as_of failed: {result.get('error')}"
+    assert {item["name"] for item in result["results"]["items"]} == {"run", "helper"}
+    assert [(c["caller"], c["callee"]) for c in result["results"]["calls"]] == [("run", "helper")]
+
+
+def test_stable_node_ids(indexed_rust_project):
+    query = """
+        MATCH (f:File)-[:CONTAINS]->(fn:Function) WHERE f.path ENDS WITH 'basic_functions.rs'
+        RETURN f.uid as file_uid, fn.name as name, fn.uid as uid, fn.content_hash as content_hash
+    """
+    functions = run_query(indexed_rust_project, query)
+    assert functions and all(len(f['uid']) == 32 and f['content_hash'] for f in functions)
+    assert len({f['uid'] for f in functions}) == len(functions)
+    assert len({f['file_uid'] for f in functions}) == 1
+
+    query = "MATCH (fn:Function) WHERE fn.uid IS NOT NULL WITH fn.uid as uid, count(*) as nodes WHERE nodes > 1 RETURN uid"
+    assert run_query(indexed_rust_project, query) == []
+
+
+def test_symbol_spans(indexed_rust_project):
+    query = """
+        MATCH (f:File)-[:CONTAINS]->(fn:Function {name: 'greet_new_person'})
+        RETURN fn.start_line as start_line, fn.end_line as end_line, fn.column as column, fn.start_byte as start_byte
+    """
+    [span] = run_query(indexed_rust_project, query)
+    assert (span['start_line'], span['end_line'], span['column']) == (30, 34, 7)
+
+    result = call_tool(indexed_rust_project, "get_snippet", {"name": "greet_new_person"})
+    assert result.get("success") is True, f"get_snippet failed: {result.get('error')}"
+    [snippet] = result["results"]
+    assert snippet["snippet"].startswith("pub fn greet_new_person(name: &str) -> String {")
+    assert snippet["snippet"].endswith("}")
+    assert snippet["location"].endswith("lib.rs:30:8")
+    assert snippet["stale"] is False
+
+    result = call_tool(indexed_rust_project, "get_snippet", {"name": "Person::greet", "file_path": "structs_enums.rs", "context_lines": 1})
+    assert result["results"] and all(s["snippet"].count("\n") >= 2 for s in result["results"])
+
+
+def test_call_edge_weights(indexed_rust_project):
+    query = """
+        MATCH (caller:Function {name: 'test_point_add'})-[r:CALLS]->(callee:Function {name: 'new'})
+        RETURN r.line_number as line_number, r.call_count as call_count, r.call_sites as call_sites
+        ORDER BY line_number
+    """
+    calls = run_query(indexed_rust_project, query)
+    assert [call['line_number'] for call in calls] == [415, 416]
+    assert all(call['call_count'] == 2 for call in calls)
+    assert calls[0]['call_sites'] == ['415:17-415:33', '416:17-416:33']
+
+    result = call_tool(indexed_rust_project, "export_subgraph", {"root": "test_point_add", "depth": 1})
+    subgraph = result["results"]
+    names = {node["id"]: node["name"] for node in subgraph["nodes"]}
+    [edge] = [edge for edge in subgraph["edges"] if names[edge["target"]] == "new"]
+    assert edge["weight"] == 2 and edge["line_number"] == 415
+
+
+def _reindex_incrementally(server):
+    result = call_tool(server, "add_code_to_graph", {"path": RUST_SAMPLE_PROJECT_PATH, "incremental": True})
+    assert result.get("success") is True, f"incremental add_code_to_graph failed: {result}"
+    for _ in range(90):
+        job = call_tool(server, "check_job_status", {"job_id": result["job_id"]}).get("job", {})
+        if job.get("status") == "completed":
+            return job["result"]
+        assert job.get("status") not in ("failed", "cancelled"), f"Re-index job failed: {job}"
+        time.sleep(2)
+    raise AssertionError("Re-index job did not complete")
+
+
+def test_incremental_reindex(indexed_rust_project):
+    result = _reindex_incrementally(indexed_rust_project)
+    assert (result["added"], result["changed"], result["removed"]) == ([], [], [])
+    assert result["unchanged"] > 0
+
+    probe = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "incremental_probe.rs")
+    try:
+        with open(probe, "w") as f:
+            f.write("pub fn incremental_probe() -> i32 {\n    simple_function(1)\n}\n")
+        result = _reindex_incrementally(indexed_rust_project)
+        assert result["added"] == [os.path.realpath(probe)] and result["changed"] == []
+        query = """
+            MATCH (f:File)-[:CONTAINS]->(:Function {name: 'incremental_probe'})-[:CALLS]->(callee:Function)
+            RETURN f.content_hash as content_hash, callee.name as callee
+        """
+        [row] = run_query(indexed_rust_project, query)
+        assert row["callee"] == "simple_function" and row["content_hash"]
+        # The crate passes ran over the stored summaries of the unchanged modules.
+        query = f"MATCH (f:File {{path: '{os.path.realpath(probe)}'}})-[:PART_OF]->(:Crate) RETURN f.crate_summary as summary"
+        [row] = run_query(indexed_rust_project, query)
+        assert json.loads(row["summary"])["functions"] == [
+            {"name": "incremental_probe", "line_number": 1, "context": None, "inline_module": None, "visibility": "pub"}]
+    finally:
+        os.remove(probe)
+
+    result = _reindex_incrementally(indexed_rust_project)
+    assert result["removed"] == [os.path.realpath(probe)]
+    assert run_query(indexed_rust_project, "MATCH (fn:Function {name: 'incremental_probe'}) RETURN fn") == []
+
+
+def test_crate_summary():
+    """Summaries keep what the crate-wide passes read of a file and leave out its sources."""
+    file_data = {
+        "file_path": "/repo/src/lib.rs", "lang": "rust", "crate_root": "/repo", "module_path": "crate",
+        "functions": [{"name": "run", "line_number": 3, "context": None, "inline_module": None, "visibility": "pub",
+                       "source": "pub fn run() {}", "cyclomatic_complexity": 1}],
+        "imports": [{"name": "Config", "full_import_name": "crate::config::Config", "alias": None}],
+        "function_calls": [{"name": "load", "full_name": "Config::load", "context": ("run", "function_item", 3), "args": []}],
+    }
+    summary = json.loads(incremental.crate_summary(file_data))
+    assert set(summary) == {"file_path", "lang", "crate_root", "module_path", "functions", "imports", "function_calls"}
+    assert summary["functions"] == [{"name": "run", "line_number": 3, "context": None, "inline_module": None, "visibility": "pub"}]
+    assert summary["imports"] == file_data["imports"]
+    assert summary["function_calls"] == [{"context": ["run", "function_item", 3], "full_name": "Config::load"}]
+    assert summary["module_path"] == "crate"
+
+
+def test_function_level_update(indexed_rust_project):
+    probe = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "function_level_probe.rs")
+    kept = "pub fn kept_probe() -> i32 {\n    simple_function(1)\n}\n\n"
+    query = """
+        MATCH (fn:Function)-[r:CALLS]->(callee:Function)
+        WHERE fn.name IN ['kept_probe', 'edited_probe']
+        RETURN fn.name as name, elementId(fn) as node, elementId(r) as edge, callee.name as callee
+    """
+    try:
+        with open(probe, "w") as f:
+            f.write(kept + "pub fn edited_probe() -> i32 {\n    simple_function(2)\n}\n")
+        _reindex_incrementally(indexed_rust_project)
+        before = {row["name"]: row for row in run_query(indexed_rust_project, query)}
+
+        with open(probe, "w") as f:
+            f.write(kept + "pub fn edited_probe() -> u64 {\n    factorial(3)\n}\n")
+        result = _reindex_incrementally(indexed_rust_project)
+        assert result["changed"] == [os.path.realpath(probe)]
+        assert result["items"]["modified"] == 1 and result["items"]["unchanged"] >= 1
+        after = {row["name"]: row for row in run_query(indexed_rust_project, query)}
+        assert after["kept_probe"]["edge"] == before["kept_probe"]["edge"]
+        assert after["edited_probe"]["node"] == before["edited_probe"]["node"]
+        assert after["edited_probe"]["callee"] == "factorial"
+    finally:
+        os.remove(probe)
+    _reindex_incrementally(indexed_rust_project)
+
+
+def test_deferred_call_resolution(indexed_rust_project):
+    caller = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "deferred_caller_probe.rs")
+    target = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "deferred_target_probe.rs")
+    try:
+        with open(caller, "w") as f:
+            f.write("pub fn deferred_caller_probe() -> i32 {\n    deferred_target_probe(2)\n}\n")
+        _reindex_incrementally(indexed_rust_project)
+        result = call_tool(indexed_rust_project, "find_unresolved_references", {"name": "deferred_target_probe"})
+        assert result.get("success") is True, f"find_unresolved_references failed: {result.get('error')}"
+        [placeholder] = result["results"]
+        assert (placeholder["kind"], placeholder["owner"], placeholder["references"]) == ("call", None, 1)
+        assert placeholder["examples"][0]["caller"] == "deferred_caller_probe"
+
+        with open(target, "w") as f:
+            f.write("pub fn deferred_target_probe(x: i32) -> i32 {\n    x\n}\n")
+        _reindex_incrementally(indexed_rust_project)
+        query = """
+            MATCH (:Function {name: 'deferred_caller_probe'})-[r:CALLS]->(:Function {name: 'deferred_target_probe'})
+            RETURN r.line_number as line_number
+        """
+        assert run_query(indexed_rust_project, query) == [{"line_number": 2}]
+        result = call_tool(indexed_rust_project, "find_unresolved_references", {"name": "deferred_target_probe"})
+        assert result["results"] == []
+    finally:
+        for probe in (caller, target):
+            if os.path.exists(probe):
+                os.remove(probe)
+    _reindex_incrementally(indexed_rust_project)
+
+
+def _wait_for_watch_event(server, since, timeout=60):
+    deadline = time.time() + timeout
+    while time.time() < deadline:
+        result = call_tool(server, "get_watch_events", {"path": RUST_SAMPLE_PROJECT_PATH, "since": since})
+        assert result.get("success") is True, f"get_watch_events failed: {result.get('error')}"
+        if result["results"]:
+            return result["results"]
+        time.sleep(0.5)
+    raise AssertionError("No watch event before the timeout")
+
+
+def test_debounced_watcher(indexed_rust_project):
+    probes = [os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", f"watch_probe_{i}.rs") for i in range(3)]
+    result = call_tool(indexed_rust_project, "watch_directory", {"path": RUST_SAMPLE_PROJECT_PATH, "debounce_seconds": 1.0})
+    assert result.get("success") is True, f"watch_directory failed: {result.get('error')}"
+    try:
+        # A burst of writes is coalesced into a single re-index.
+        for i, probe in enumerate(probes):
+            with open(probe, "w") as f:
+                f.write(f"pub fn watch_probe_{i}() -> i32 {{\n    {i}\n}}\n")
+        [event] = _wait_for_watch_event(indexed_rust_project, 0)
+        assert sorted(event["added"]) == sorted(os.path.realpath(probe) for probe in probes)
+        assert set(event["paths"]) >= {os.path.realpath(probe) for probe in probes}
+        # Only the touched files are compared, not the rest of the repository.
+        assert event["unchanged"] == 0
+        names = run_query(indexed_rust_project, "MATCH (f:Function) WHERE f.name STARTS WITH 'watch_probe_' RETURN count(f) as count")
+        assert names == [{"count": 3}]
+
+        for probe in probes:
+            os.remove(probe)
+        [event] = _wait_for_watch_event(indexed_rust_project, event["sequence"])
+        assert sorted(event["removed"]) == sorted(os.path.realpath(probe) for probe in probes)
+    finally:
+        call_tool(indexed_rust_project, "unwatch_directory", {"path": RUST_SAMPLE_PROJECT_PATH})
+        for probe in probes:
+            if os.path.exists(probe):
+                os.remove(probe)
+    _reindex_incrementally(indexed_rust_project)
+
+
+def test_job_progress_events(indexed_rust_project):
+    probe = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "progress_probe.rs")
+    try:
+        with open(probe, "w") as f:
+            f.write("pub fn progress_probe() -> i32 {\n    1\n}\n")
+        result = call_tool(indexed_rust_project, "add_code_to_graph", {"path": RUST_SAMPLE_PROJECT_PATH, "incremental": True})
+        assert result.get("success") is True, f"incremental add_code_to_graph failed: {result}"
+        for _ in range(90):
+            job = call_tool(indexed_rust_project, "check_job_status", {"job_id": result["job_id"], "since_event": 0})["job"]
+            if job["status"] in ("completed", "failed"):
+                break
+            time.sleep(2)
+        assert job["status"] == "completed", f"Re-index job did not complete: {job}"
+        assert (job["progress"]["phase"], job["progress"]["processed_files"], job["progress"]["total_files"]) == ("completed", 1, 1)
+        assert job["progress"]["nodes_written"] >= 2
+        events = job["progress_events"]
+        assert [event["sequence"] for event in events] == list(range(1, len(events) + 1))
+        phases = [event["phase"] for event in events]
+        assert [phase for i, phase in enumerate(phases) if i == 0 or phases[i - 1] != phase] == \
+            ["scanning", "indexing", "linking", "annotating", "completed"]
+    finally:
+        if os.path.exists(probe):
+            os.remove(probe)
+    _reindex_incrementally(indexed_rust_project)
+
+
+def test_query_cache(indexed_rust_project):
+    def stats():
+        return call_tool(indexed_rust_project, "get_query_cache_stats", {})["results"]
+
+    args = {"cypher_query": "MATCH (f:Function) WHERE f.name STARTS WITH 'factorial' RETURN f.name as name ORDER BY name"}
+    first = call_tool(indexed_rust_project, "execute_cypher_query", args)
+    before = stats()
+    assert call_tool(indexed_rust_project, "execute_cypher_query", args) == first
+    after = stats()
+    assert (after["hits"], after["misses"]) == (before["hits"] + 1, before["misses"])
+
+    probe = os.path.join(RUST_SAMPLE_PROJECT_PATH, "src", "cache_probe.rs")
+    try:
+        with open(probe, "w") as f:
+            f.write("pub fn factorial_cache_probe() -> u64 {\n    1\n}\n")
+        _reindex_incrementally(indexed_rust_project)
+        fresh = call_tool(indexed_rust_project, "execute_cypher_query", args)
+        assert stats()["misses"] == after["misses"] + 1
+        assert {"name": "factorial_cache_probe"} in fresh["results"]
+    finally:
+        if os.path.exists(probe):
+            os.remove(probe)
+    _reindex_incrementally(indexed_rust_project)
+
+
+def _wait_for_job(server, job_id):
+    for _ in range(90):
+        job = call_tool(server, "check_job_status", {"job_id": job_id})["job"]
+        if job["status"] in ("completed", "failed", "cancelled"):
+            return job
+        time.sleep(2)
+    raise AssertionError(f"Job {job_id} did not finish")
+
+
+def test_sharded_workspace(indexed_rust_project, tmp_path):
+    workspace = tmp_path / "shard_workspace"
+    for crate, source in {
+        "shard_app": "use shard_util::shard_helper;\n\npub fn shard_run() -> i32 {\n    shard_helper(1)\n}\n",
+        "shard_util": "pub fn shard_helper(x: i32) -> i32 {\n    x + 1\n}\n",
+    }.items():
+        (workspace / crate / "src").mkdir(parents=True)
+        (workspace / crate / "Cargo.toml").write_text(f'[package]\nname = "{crate}"\nversion = "0.1.0"\nedition = "2021"\n')
+        (workspace / crate / "src" / "lib.rs").write_text(source)
+    (workspace / "Cargo.toml").write_text('[workspace]\nmembers = ["shard_app", "shard_util"]\n')
+    repo_path = str(workspace.resolve())
+
+    result = call_tool(indexed_rust_project, "add_code_to_graph", {"path": repo_path, "sharded": True, "max_workers": 2})
+    assert result.get("success") is True, f"sharded add_code_to_graph failed: {result}"
+    try:
+        job = _wait_for_job(indexed_rust_project, result["job_id"])
+        assert job["status"] == "completed", f"Sharded build failed: {job}"
+        assert {(shard["name"], shard["status"], shard["processed_files"]) for shard in job["result"]["shards"]} == \
+            {("shard_app", "completed", 1), ("shard_util", "completed", 1)}
+
+        shards = call_tool(indexed_rust_project, "list_shards", {"repo_path": repo_path})["results"]
+        assert {(shard["name"], shard["status"], shard["file_count"]) for shard in shards} == \
+            {("shard_app", "completed", 1), ("shard_util", "completed", 1)}
+        calls = """
+            MATCH (:Function {name: 'shard_run'})-[:CALLS]->(callee:Function {name: 'shard_helper'})
+            RETURN callee.file_path as file_path
+        """
+        assert run_query(indexed_rust_project, calls) == [{"file_path": str(workspace.resolve() / "shard_util" / "src" / "lib.rs")}]
+
+        (workspace / "shard_util" / "src" / "lib.rs").write_text("pub fn shard_helper(x: i32) -> i32 {\n    x + 2\n}\n")
+        result = call_tool(indexed_rust_project, "reindex_shard", {"repo_path": repo_path, "shard": "shard_util"})
+        assert result.get("success") is True, f"reindex_shard failed: {result}"
+        job = _wait_for_job(indexed_rust_project, result["job_id"])
+        assert job["status"] == "completed", f"Shard re-index failed: {job}"
+        assert job["result"]["changed"] == [str(workspace.resolve() / "shard_util" / "src" / "lib.rs")]
+        assert job["result"]["relinked"] == [str(workspace.resolve() / "shard_app" / "src" / "lib.rs")]
+        assert len(run_query(indexed_rust_project, calls)) == 1
+    finally:
+        call_tool(indexed_rust_project, "delete_repository", {"repo_path": repo_path})
+
+
+def test_bulk_export_matches_indexed_graph(indexed_rust_project, tmp_path):
+    output = tmp_path / "bulk"
+    subprocess.run(["cgc", "bulk-export", RUST_SAMPLE_PROJECT_PATH, "--output", str(output)], check=True,
+                   cwd=os.path.join(os.path.dirname(__file__), ".."))
+    assert os.access(output / "neo4j-admin-import.sh", os.X_OK)
+    script = (output / "neo4j-admin-import.sh").read_text()
+    assert "--nodes=Function=nodes_Function.csv" in script and "--relationships=CONTAINS=rels_CONTAINS.csv" in script
+
+    with open(output / "nodes_Function.csv", newline="", encoding="utf-8") as f:
+        rows = list(csv.reader(f))
+    header = rows[0]
+    assert header[0] == ":ID" and "uid:string" in header and "line_number:long" in header
+    exported = {row[0]: row[header.index("name:string")] for row in rows[1:]}
+    assert "factorial" in exported.values()
+    # The export's ids are the stable uids indexing gives the same functions.
+    query = f"MATCH (f:Function) WHERE f.file_path STARTS WITH '{RUST_SAMPLE_PROJECT_PATH}/' RETURN f.uid as uid"
+    assert set(exported) <= {record["uid"] for record in run_query(indexed_rust_project, query)}
+
+
+def test_ignore_rules(indexed_rust_project, tmp_path):
+    repo = tmp_path / "ignore_repo"
+    for relative in ["src/lib.rs", "src/generated/bindings.rs", "tests/fixtures/broken.rs", "target/debug/build.rs", "vendor/dep/lib.rs"]:
+        (repo / relative).parent.mkdir(parents=True, exist_ok=True)
+        (repo / relative).write_text("pub fn ignore_probe() {}\n")
+    (repo / ".cgcignore").write_text("# generated and fixture code\nsrc/generated/\nfixtures/\nvendor/*\n!vendor/dep/\n")
+    repo_path = str(repo.resolve())
+
+    preview = call_tool(indexed_rust_project, "preview_ignore_rules", {"path": repo_path})
+    assert preview.get("success") is True, f"preview_ignore_rules failed: {preview}"
+    report = preview["results"]
+    assert {"pattern": "target/", "source": "default"} in report["rules"]
+    assert {"pattern": "!vendor/dep/", "source": ".cgcignore"} in report["rules"]
+    assert sorted(report["included"]) == [f"{repo_path}/src/lib.rs", f"{repo_path}/vendor/dep/lib.rs"]
+    assert {(entry["path"], entry["pattern"]) for entry in report["excluded"]} == {
+        (f"{repo_path}/src/generated/bindings.rs", "src/generated/"),
+        (f"{repo_path}/tests/fixtures/broken.rs", "fixtures/"),
+        (f"{repo_path}/target/debug/build.rs", "target/"),
+    }
+
+    result = call_tool(indexed_rust_project, "add_code_to_graph", {"path": repo_path})
+    assert result.get("success") is True, f"add_code_to_graph failed: {result}"
+    try:
+        assert _wait_for_job(indexed_rust_project, result["job_id"])["status"] == "completed"
+        query = f"MATCH (f:File) WHERE f.path STARTS WITH '{repo_path}/' RETURN f.path as path ORDER BY path"
+        assert [record["path"] for record in run_query(indexed_rust_project, query)] == report["included"]
+
+        # Ignoring an indexed file removes it on the next re-index.
+        (repo / ".cgcignore").write_text("vendor/\n")
+        result = call_tool(indexed_rust_project, "add_code_to_graph", {"path": repo_path, "incremental": True})
+        job = _wait_for_job(indexed_rust_project, result["job_id"])
+        assert job["result"]["removed"] == [f"{repo_path}/vendor/dep/lib.rs"]
+        assert [record["path"] for record in run_query(indexed_rust_project, query)] == [
+            f"{repo_path}/src/generated/bindings.rs", f"{repo_path}/src/lib.rs", f"{repo_path}/tests/fixtures/broken.rs",
+        ]
+    finally:
+        call_tool(indexed_rust_project, "delete_repository", {"repo_path": repo_path})
+
+
+def test_database_retry():
+    delays = list(backoff_delays(6, 1.0, maximum=10.0))
+    assert len(delays) == 6
+    for delay, base in zip(delays, [1, 2, 4, 8, 10, 10]):
+        assert 0.75 * base <= delay <= base
+
+    db_manager = DatabaseManager()
+    retry_delay, retries = db_manager.retry_delay, db_manager.retries
+    db_manager.retry_delay = 0.01
+    attempts = []
+
+    def flaky():
+        attempts.append(len(attempts))
+        if len(attempts) < 3:
+            raise ServiceUnavailable("connection lost")
+        return 42
+
+    def broken():
+        attempts.append(len(attempts))
+        raise CypherSyntaxError("not transient")
+
+    try:
+        assert db_manager.run_with_retry(flaky, "probe") == 42
+        assert len(attempts) == 3
+        assert db_manager.retries == retries + 2
+        assert db_manager.pool_status()["last_transient_error"] == "ServiceUnavailable: connection lost"
+        with pytest.raises(CypherSyntaxError):
+            db_manager.run_with_retry(broken, "probe")
+        assert len(attempts) == 4
+    finally:
+        db_manager.retry_delay = retry_delay
+
+
+def test_spill_list(tmp_path):
+    files = [{"file_path": f"/repo/src/m{i}.rs", "functions": [{"name": f"f{i}_{j}", "line_number": j} for j in range(20)]} for i in range(10)]
+    limit = 3 * estimate_size(files[0])
+    with SpillList(limit=limit, directory=str(tmp_path)) as store:
+        for file_data in files[:5]:
+            store.append(file_data)
+        store.extend(files[5:])
+        assert store.spilled == 7
+        assert store.memory_bytes <= limit
+        assert len(store) == 10 and store[9] == files[9] and store[0] is files[0]
+        # Spilled files come back with their strings interned.
+        assert store[9]["functions"][0]["name"] is sys.intern("f9_0")
+        seen = []
+        for file_data in store:
+            seen.append(file_data)
+            if len(store) < 11:
+                store.append({"file_path": "/repo/src/included.rs"})
+        assert seen == files + [{"file_path": "/repo/src/included.rs"}]
+        # Passes iterate the store many times; spilled files read back the same every time.
+        assert list(store) == seen
+
+    with SpillList(limit=None) as unbounded:
+        unbounded.extend(files)
+        assert unbounded.spilled == 0 and unbounded.file is None
+
+
+def test_parsed_strings_are_interned():
+    from codegraphcontext.tools.graph_builder import TreeSitterParser
+
+    parser = TreeSitterParser('rust')
+    files = sorted(Path(RUST_SAMPLE_PROJECT_PATH).rglob("*.rs"))
+    raw = [parser.language_specific_parser.parse(file) for file in files]
+    parsed = [parser.parse(file) for file in files]
+    assert parsed == raw
+
+    raw_objects = {id(value) for file_data in raw for value in strings(file_data)}
+    objects = {id(value) for file_data in parsed for value in strings(file_data)}
+    values = {value for file_data in parsed for value in strings(file_data)}
+    # Every distinct string of the parsed files is stored once, across files too.
+    assert len(objects) == len(values) < len(raw_objects)
+
+    def peak_holding(parse):
+        tracemalloc.start()
+        try:
+            held = [parse(file) for file in files]
+            assert len(held) == len(files)
+            return tracemalloc.get_traced_memory()[1]
+        finally:
+            tracemalloc.stop()
+
+    # Sharing the strings lowers the memory peak of holding every parsed file until linking.
+    del raw, parsed, values
+    interned_peak = peak_holding(parser.parse)
+    raw_peak = peak_holding(parser.language_specific_parser.parse)
+    assert interned_peak < raw_peak
+
+
+def test_identical_files_are_aliased(indexed_rust_project, tmp_path):
+    repo = tmp_path / "dedup_repo"
+    generated = "".join(f"pub fn dedup_generated_{i}(x: u64) -> u64 {{\n    x.wrapping_mul({i}).wrapping_add({i})\n}}\n\n" for i in range(40))
+    for copy in ("one", "two"):
+        (repo / "vendor" / copy).mkdir(parents=True)
+        (repo / "vendor" / copy / "generated.rs").write_text(generated)
+    (repo / "src").mkdir()
+    (repo / "src" / "lib.rs").write_text("pub fn dedup_entry() -> u64 {\n    1\n}\n")
+    (repo / "src" / "crlf.rs").write_bytes(b"pub fn dedup_crlf() {}\r\n")
+    assert read_source(repo / "src" / "crlf.rs") == b"pub fn dedup_crlf() {}\n"
+    repo_path = str(repo.resolve())
+    one, two = f"{repo_path}/vendor/one/generated.rs", f"{repo_path}/vendor/two/generated.rs"
+
+    result = call_tool(indexed_rust_project, "add_code_to_graph", {"path": repo_path})
+    assert result.get("success") is True, f"add_code_to_graph failed: {result}"
+    try:
+        assert _wait_for_job(indexed_rust_project, result["job_id"])["status"] == "completed"
+        aliases = run_query(indexed_rust_project, f"""
+            MATCH (a:File)-[:ALIAS_OF]->(c:File) WHERE a.path STARTS WITH '{repo_path}/'
+            RETURN a.path as alias, c.path as canonical, a.alias_of as alias_of
+        """)
+        assert aliases == [{"alias": two, "canonical": one, "alias_of": one}]
+        defined_in = f"""
+            MATCH (f:File)-[:CONTAINS]->(fn:Function {{name: 'dedup_generated_7'}})
+            WHERE f.path STARTS WITH '{repo_path}/' RETURN f.path as path ORDER BY path
+        """
+        assert run_query(indexed_rust_project, defined_in) == [{"path": one}]
+
+        # Once the copies differ, the alias is indexed on its own.
+        (repo / "vendor" / "one" / "generated.rs").write_text(generated + "pub fn dedup_patched() {}\n")
+        result = call_tool(indexed_rust_project, "add_code_to_graph", {"path": repo_path, "incremental": True})
+        job = _wait_for_job(indexed_rust_project, result["job_id"])
+        assert job["status"] == "completed", f"Re-index failed: {job}"
+        assert sorted(job["result"]["changed"]) == [one, two]
+        assert run_query(indexed_rust_project, defined_in) == [{"path": one}, {"path": two}]
+        assert run_query(indexed_rust_project, f"MATCH (a:File {{path: '{two}'}}) RETURN a.alias_of as alias_of, "
+                                               f"size([(a)-[:ALIAS_OF]->() | 1]) as links") == [{"alias_of": None, "links": 0}]
+    finally:
+        call_tool(indexed_rust_project, "delete_repository", {"repo_path": repo_path})
+
+
+def test_bounded_pipeline_backpressure():
+    started = []
+
+    def stage(item):
+        started.append(item)
+        return item * 2
+
+    with BoundedPipeline(stage, workers=2, capacity=3).start(range(50)) as pipeline:
+        time.sleep(0.5)
+        # A writer that has not taken anything yet holds the workers back: a full write queue,
+        # plus one item per blocked worker, is all that gets parsed.
+        assert len(started) <= 3 + 2
+        assert pipeline.depths()["write_queue"] == 3
+        results = []
+        result = pipeline.next()
+        while result is not None:
+            results.append(result)
+            result = pipeline.next()
+    assert sorted(results) == [item * 2 for item in range(50)]
+    assert pipeline.depths()["backpressure_seconds"] > 0
+
+    def failing(item):
+        if item == 3:
+            raise ValueError("parse failed")
+        return item
+
+    with BoundedPipeline(failing, workers=2, capacity=2).start(range(10)) as pipeline:
+        with pytest.raises(ValueError):
+            while pipeline.next() is not None:
+                pass
+
+
+def test_build_reports_queue_depths(indexed_rust_project, tmp_path):
+    repo = tmp_path / "pipeline_repo"
+    (repo / "src").mkdir(parents=True)
+    for i in range(6):
+        (repo / "src" / f"m{i}.rs").write_text(f"pub fn pipeline_probe_{i}() -> u32 {{\n    {i}\n}}\n")
+    repo_path = str(repo.resolve())
+
+    result = call_tool(indexed_rust_project, "add_code_to_graph", {"path": repo_path})
+    assert result.get("success") is True, f"add_code_to_graph failed: {result}"
+    try:
+        _wait_for_job(indexed_rust_project, result["job_id"])
+        job = call_tool(indexed_rust_project, "check_job_status", {"job_id": result["job_id"], "since_event": 0})["job"]
+        assert job["status"] == "completed", f"Build failed: {job}"
+        indexing = [event for event in job["progress_events"] if event["phase"] == "indexing" and event["queues"]]
+        assert [event["processed_files"] for event in indexing] == list(range(1, 7))
+        for event in indexing:
+            queues = event["queues"]
+            assert 0 <= queues["parse_queue"] <= queues["capacity"] and 0 <= queues["write_queue"] <= queues["capacity"]
+        count = f"MATCH (f:Function) WHERE f.name STARTS WITH 'pipeline_probe_' AND f.file_path STARTS WITH '{repo_path}/' RETURN count(f) as n"
+        assert run_query(indexed_rust_project, count) == [{"n": 6}]
+    finally:
+        call_tool(indexed_rust_project, "delete_repository", {"repo_path": repo_path})
+
+
+def test_bench_detects_regressions(tmp_path):
+    baseline = tmp_path / "baseline.json"
+    command = ["cgc", "bench", "--project", RUST_SAMPLE_PROJECT_PATH, "--samples", "2", "--baseline", str(baseline), "--json"]
+    cwd = os.path.join(os.path.dirname(__file__), "..")
+    first = subprocess.run(command + ["--save-baseline"], check=True, capture_output=True, text=True, cwd=cwd)
+    results = json.loads(first.stdout)
+    assert [result["id"] for result in results] == ["parse/sample_project_rust", "resolve/sample_project_rust", "write/sample_project_rust"]
+    assert all(result["mean_ns"] > 0 and result["verdict"] is None for result in results)
+    saved = json.loads(baseline.read_text())["benchmarks"]
+    assert [result["id"] for result in saved] == [result["id"] for result in results]
+
+    # A baseline ten times faster than this machine makes every benchmark a regression.
+    for result in saved:
+        result["mean_ns"] /= 10
+    baseline.write_text(json.dumps({"benchmarks": saved}))
+    second = subprocess.run(command + ["--threshold", "50"], capture_output=True, text=True, cwd=cwd)
+    assert second.returncode == 1
+    assert {result["verdict"] for result in json.loads(second.stdout)} == {"regressed"}
+
+
+class _FakeSession:
+    """Answers the node and relationship reads of `delta_sync.snapshot` and records what else is run."""
+
+    def __init__(self, nodes=(), relationships=()):
+        self.nodes, self.relationships, self.queries = list(nodes), list(relationships), []
+
+    def run(self, query, **params):
+        if "properties(n) as props" in query and "MATCH (n)-[r]->(m)" not in query:
+            return self.nodes
+        if "MATCH (n)-[r]->(m)" in query:
+            return self.relationships
+        self.queries.append((query, params))
+        return []
+
+    def __enter__(self):
+        return self
+
+    def __exit__(self, *exc_info):
+        return False
+
+
+class _FakeDriver:
+    def __init__(self, session):
+        self.fake = session
+
+    def session(self):
+        return self.fake
+
+
+def test_delta_sync_ships_only_changes(tmp_path):
+    repo = "/repo"
+    file_node = {"id": "1", "labels": ["File"], "props": {"path": "/repo/src/lib.rs", "name": "lib.rs"}}
+    caller = {"id": "2", "labels": ["Function"], "props": {"uid": "u-caller", "name": "caller", "line_number": 1, "file_path": "/repo/src/lib.rs"}}
+    callee = {"id": "3", "labels": ["Function"], "props": {"uid": "u-callee", "name": "callee", "line_number": 5, "file_path": "/repo/src/lib.rs"}}
+    module = {"labels": ["Module"], "props": {"name": "std::sync"}}
+
+    def edge(start, rel_type, end, props=None):
+        return {"start": start["id"], "type": rel_type, "props": props or {}, "end": end.get("id", "external"),
+                "end_labels": end["labels"], "end_props": end["props"]}
+
+    local = _FakeSession([file_node, caller, callee], [
+        edge(file_node, "CONTAINS", caller), edge(file_node, "CONTAINS", callee),
+        edge(caller, "CALLS", callee, {"line_number": 2}), edge(caller, "CALLS", callee, {"line_number": 2}),
+        edge(file_node, "IMPORTS", module),
+    ])
+    remote = _FakeSession()
+    first = delta_sync.sync(_FakeDriver(local), _FakeDriver(remote), repo, "neo4j://remote", state_dir=tmp_path)
+    # Both call sites on the same line are kept; the imported module is shipped but not owned.
+    assert first == {"upsert_nodes": 4, "delete_nodes": 0, "create_relationships": 5, "delete_relationships": 0,
+                     "unchanged_nodes": 0, "unchanged_relationships": 0}
+    assert any("sync_repo = $repo" in query for query, _ in remote.queries)
+
+    # The callee moves and loses one call site, the imported module is dropped: only that is shipped.
+    moved = dict(callee, props=dict(callee["props"], line_number=6))
+    local.nodes = [file_node, caller, moved]
+    local.relationships = [edge(file_node, "CONTAINS", caller), edge(file_node, "CONTAINS", moved),
+                           edge(caller, "CALLS", moved, {"line_number": 2})]
+    remote.queries.clear()
+    dry = delta_sync.sync(_FakeDriver(local), _FakeDriver(remote), repo, "neo4j://remote", dry_run=True, state_dir=tmp_path)
+    assert dry == {"upsert_nodes": 1, "delete_nodes": 0, "create_relationships": 0, "delete_relationships": 2,
+                   "unchanged_nodes": 2, "unchanged_relationships": 3}
+    assert remote.queries == []
+
+    assert delta_sync.sync(_FakeDriver(local), _FakeDriver(remote), repo, "neo4j://remote", state_dir=tmp_path) == dry
+    deleted = [params["rows"] for query, params in remote.queries if "DELETE r" in query]
+    assert sorted(row["type"] for rows in deleted for row in rows) == ["CALLS", "IMPORTS"]
+    assert delta_sync.sync(_FakeDriver(local), _FakeDriver(remote), repo, "neo4j://remote", state_dir=tmp_path)["upsert_nodes"] == 0
+
+    removed = delta_sync.diff(delta_sync.manifest_of(delta_sync.snapshot(local, repo)), {"nodes": {}, "relationships": {}})
+    assert len(removed["delete_nodes"]) == 3
+
+
+def test_snippet_compression(monkeypatch):
+    source = "pub fn compressed_probe(values: &[u32]) -> u32 {\n" + "    values.iter().sum::<u32>() + 1;\n" * 20 + "}\n"
+    item = {"name": "compressed_probe", "source": source, "source_code": source, "line_number": 1}
+
+    monkeypatch.setenv("CGC_COMPRESS_SNIPPETS", "zlib")
+    codec = snippets.codec_from_env()
+    props = snippets.stored_properties(item, codec)
+    assert props["source"] is None and props["source_code"] is None
+    assert len(props["source_compressed"]) < len(source) // 2
+    assert item["source"] == source, "the parsed item is left as it was"
+    assert snippets.source_text(props["source_compressed"]) == source
+    # Query results get their text back under `source`, in rows and in nested node maps.
+    row = {"name": "compressed_probe", "node": {"source_compressed": bytearray(props["source_compressed"])}, "n": 1}
+    assert snippets.expand_tree([row]) == [{"name": "compressed_probe", "node": {"source": source}, "n": 1}]
+
+    short = {"name": "tiny", "source": "fn tiny() {}"}
+    assert snippets.stored_properties(short, codec) == {**short, "source_compressed": None}
+    monkeypatch.setenv("CGC_COMPRESS_SNIPPETS", "off")
+    assert snippets.codec_from_env() is None
+    assert snippets.stored_properties(item, None)["source"] == source
+    monkeypatch.setenv("CGC_COMPRESS_SNIPPETS", "lz4")
+    with pytest.raises(ValueError):
+        snippets.codec_from_env()
+
+
+def test_export_graphml(indexed_rust_project):
+    import xml.etree.ElementTree as ET
+    from codegraphcontext.tools.graph_export import to_graphml
+
+    ns = {"g": "http://graphml.graphdrawing.org/xmlns"}
+    result = call_tool(indexed_rust_project, "export_subgraph", {"root": "ignore_errors", "depth": 1, "format": "graphml"})
+    assert result.get("success") is True, f"export_subgraph failed: {result.get('error')}"
+    document = ET.fromstring(result["results"])
+    keys = {(key.get("for"), key.get("attr.name")): key for key in document.findall("g:key", ns)}
+    assert keys["node", "line_number"].get("attr.type") == "long"
+    assert keys["node", "root"].get("attr.type") == "boolean"
+    assert keys["edge", "weight"].get("attr.type") == "long"
+    label = keys["node", "label"].get("id")
+    titles = {data.text for data in document.iterfind(f"g:graph/g:node/g:data[@key='{label}']", ns)}
+    assert {"ignore_errors", "validate_age"} <= titles
+    assert document.findall("g:graph/g:edge", ns)
+
+    # Whole-graph exports carry the stored properties, typed from all their values.
+    graph = {"nodes": [
+        {"id": "a", "label": "Function", "name": "f", "owner": None, "file_path": "/r/a.rs", "line_number": 1,
+         "properties": {"name": "f", "cyclomatic_complexity": 2, "sample_share": 0.5, "decorators": ["#[inline]"], "doc": "a\x01b"}},
+        {"id": "b", "label": "Function", "name": "g", "owner": "T", "file_path": "/r/a.rs", "line_number": 9,
+         "properties": {"name": "g", "cyclomatic_complexity": 1, "sample_share": 1, "is_async": True}},
+    ], "edges": [{"source": "a", "target": "b", "type": "CALLS", "line_number": 3, "samples": None, "weight": 1,
+                  "properties": {"line_number": 3, "full_call_name": "T::g"}}]}
+    document = ET.fromstring(to_graphml(graph))
+    types = {key.get("attr.name"): key.get("attr.type") for key in document.findall("g:key[@for='node']", ns)}
+    assert types["cyclomatic_complexity"] == "long" and types["sample_share"] == "double"
+    assert types["is_async"] == "boolean" and types["decorators"] == "string"
+    values = [data.text for data in document.iterfind("g:graph/g:node/g:data", ns)]
+    assert '["#[inline]"]' in values and "ab" in values and "T::g" in values
+
+
+def test_export_dot(indexed_rust_project):
+    args = {"root": "structs_enums", "root_kind": "module", "depth": 1, "include_external": True, "format": "dot"}
+    result = call_tool(indexed_rust_project, "export_subgraph", {**args, "cluster_by_module": True})
+    assert result.get("success") is True, f"export_subgraph failed: {result.get('error')}"
+    dot = result["results"]
+    assert dot.startswith("digraph code {") and dot.rstrip().endswith("}")
+    assert 'subgraph cluster_' in dot and 'label="structs_enums.rs"' in dot
+    assert 'label="validate_age", fillcolor="#cfe2ff", shape=box' in dot
+
+    result = call_tool(indexed_rust_project, "export_subgraph", {**args, "format": "json", "modules": True})
+    modules = {node['id']: node['name'] for node in result["results"]["nodes"]}
+    assert "structs_enums.rs" in modules.values() and "error_handling.rs" in modules.values()
+    assert all(edge['source'] != edge['target'] for edge in result["results"]["edges"])
+    assert ("structs_enums.rs", "error_handling.rs") in {(modules[e['source']], modules[e['target']]) for e in result["results"]["edges"]}
+
+
+def test_graph_dump_round_trip(tmp_path):
+    from codegraphcontext.tools.graph_dump import load_graph, read_dump, write_dump
+
+    file_node = {"id": "1", "labels": ["File"], "props": {"path": "/repo/src/lib.rs", "name": "lib.rs"}}
+    func = {"id": "2", "labels": ["Function"], "props": {"uid": "u-f", "name": "f", "line_number": 3, "file_path": "/repo/src/lib.rs",
+                                                          "decorators": ["#[inline]"], "source_compressed": b"\x78\x9c\x00\xff"}}
+    module = {"labels": ["Module"], "props": {"name": "std::sync"}}
+    local = _FakeSession([func, file_node], [
+        {"start": "1", "type": "CONTAINS", "props": {}, "end": "2", "end_labels": func["labels"], "end_props": func["props"]},
+        {"start": "1", "type": "IMPORTS", "props": {"line_number": 1}, "end": "9", "end_labels": module["labels"], "end_props": module["props"]},
+    ])
+    first, second = tmp_path / "graph.jsonl", tmp_path / "again.jsonl"
+    header = write_dump(local, first, "/repo")
+    assert (header["nodes"], header["relationships"]) == (3, 2)
+    local.nodes.reverse()
+    write_dump(local, second, "/repo")
+    assert first.read_bytes() == second.read_bytes(), "dumps are stable"
+    lines = [json.loads(line) for line in first.read_text().splitlines()]
+    assert [line.get("kind") for line in lines] == [None, "node", "node", "node", "relationship", "relationship"]
+    assert {"$bytes": "eJwA/w=="} in [line["properties"].get("source_compressed") for line in lines[1:4]]
+
+    compressed = tmp_path / "graph.jsonl.gz"
+    write_dump(local, compressed, "/repo")
+    header, graph = read_dump(compressed)
+    assert header["repository"] == "/repo"
+    assert graph == delta_sync.snapshot(local, "/repo")
+    remote = _FakeSession()
+    assert load_graph(remote, graph, "/repo") == {"nodes": 3, "relationships": 2}
+    [written] = [params["rows"] for query, params in remote.queries if ":`Function`" in query and "MERGE (n" in query]
+    assert written[0]["props"]["source_compressed"] == b"\x78\x9c\x00\xff"
+
+    # A relationship to a node missing from the dump is refused.
+    kept = [line for line in first.read_text().splitlines() if '"key":"File:path:/repo/src/lib.rs"' not in line]
+    (tmp_path / "broken.jsonl").write_text("\n".join(kept) + "\n")
+    with pytest.raises(ValueError):
+        read_dump(tmp_path / "broken.jsonl")
+
+
+def test_scip_index():
+    """Definitions, references and implementations map onto SCIP symbols, occurrences and relationships."""
+    lines = {
+        "/repo/src/shape.rs": [
+            "pub trait Shape {",
+            "    fn area(&self) -> f64;",
+            "}",
+            "pub struct Square { side: f64 }",
+            "impl Shape for Square {",
+            "    fn area(&self) -> f64 { let s = self.side; s * s }",
+            "}",
+            "impl std::fmt::Debug for Square {",
+            "    fn area(&self) -> f64 { 0.0 }",
+            "}",
+            "fn total(shapes: &[Square]) -> f64 { shapes.iter().map(|s| s.area()).sum() }",
+        ],
+    }
+
+    def definition(id_, label, name, line, column, **extra):
+        return {"id": id_, "label": label, "name": name, "file_path": "/repo/src/shape.rs", "line_number": line,
+                "column": column, "kind": None, "owner": None, "context": None, "inline_module": None,
+                "start_line": line, "start_column": 0, "end_line": line, "end_column": len(lines["/repo/src/shape.rs"][line - 1]),
+                "signature": None, "docstring": None, **extra}
+
+    graph = {
+        "files": [{"path": "/repo/src/shape.rs", "relative_path": "src/shape.rs"}],
+        "definitions": [
+            definition("t", "Class", "Shape", 1, 10, kind="trait", docstring="A plane figure."),
+            definition("ta", "Function", "area", 2, 7, owner="Shape", signature="fn area(&self) -> f64"),
+            definition("s", "Class", "Square", 4, 11, kind="struct"),
+            definition("side", "Field", "side", 4, 20, owner="Square"),
+            definition("a1", "Function", "area", 6, 7, owner="Square"),
+            definition("v", "Variable", "s", 6, 32, kind="let", owner="Square", context="area"),
+            definition("a2", "Function", "area", 9, 7, owner="Square"),
+            definition("total", "Function", "total", 11, 3),
+        ],
+        "references": [
+            {"target": "a1", "file_path": "/repo/src/shape.rs", "line_number": 11, "column": 61, "access": None},
+            {"target": "s", "file_path": "/repo/src/shape.rs", "line_number": 11, "column": None, "access": None},
+            {"target": "side", "file_path": "/repo/src/shape.rs", "line_number": 6, "column": None, "access": "read"},
+            {"target": "t", "file_path": "/repo/src/shape.rs", "line_number": 3, "column": None, "access": None},
+        ],
+        "implementations": [{"source": "s", "target": "t"}, {"source": "a1", "target": "ta"}],
+    }
+    [document] = scip.build_documents(graph, "geometry", read_lines=lines.get)
+    assert document["relative_path"] == "src/shape.rs" and document["language"] == "Rust"
+    symbols = {info["symbol"]: info for info in document["symbols"]}
+    prefix = "cgc . geometry . src/shape/"
+    assert set(symbols) == {prefix + "Shape#", prefix + "Shape#area().", prefix + "Square#", prefix + "Square#side.",
+                            prefix + "Square#area().", prefix + "Square#area(+1).", prefix + "total().", "local 0"}
+    assert symbols[prefix + "Shape#"]["kind"] == scip.KIND_TRAIT
+    assert symbols[prefix + "Shape#"]["documentation"] == ["A plane figure."]
+    assert symbols[prefix + "Shape#area()."]["documentation"] == ["```rust\nfn area(&self) -> f64\n```"]
+    assert symbols[prefix + "Square#"]["relationships"] == [
+        {"symbol": prefix + "Shape#", "is_implementation": True, "is_reference": False}]
+    assert symbols[prefix + "Square#area()."]["relationships"][0]["symbol"] == prefix + "Shape#area()."
+    assert symbols["local 0"]["enclosing_symbol"] == prefix + "Square#area()."
+
+    occurrences = {(tuple(o["range"]), o["symbol"]): o for o in document["occurrences"]}
+    assert occurrences[((3, 11, 3, 17), prefix + "Square#")]["symbol_roles"] == scip.ROLE_DEFINITION
+    assert occurrences[((3, 11, 3, 17), prefix + "Square#")]["enclosing_range"] == [3, 0, 3, 31]
+    assert occurrences[((10, 61, 10, 65), prefix + "Square#area().")]["symbol_roles"] == 0
+    assert occurrences[((10, 19, 10, 25), prefix + "Square#")]["symbol_roles"] == 0
+    assert occurrences[((5, 41, 5, 45), prefix + "Square#side.")]["symbol_roles"] == scip.ROLE_READ_ACCESS
+    assert len(occurrences) == 11, "the reference to Shape on a line without it is dropped"
+
+    assert scip.escape_identifier("r#type") == "`r#type`"
+    assert scip.symbol_prefix("my crate", "1.0") == "cgc . my  crate 1.0 "
+    # Single-line ranges are written as three numbers, field 1 packed, then the symbol as field 2.
+    assert scip.encode_occurrence({"range": [3, 11, 3, 17], "symbol": "local 0", "symbol_roles": 1}) == \
+        b"\x0a\x03\x03\x0b\x11" + b"\x12\x07local 0" + b"\x18\x01"
+    encoded = scip.encode_index([document], "/repo", "1.0")
+    assert encoded.startswith(b"\x0a") and b"codegraphcontext" in encoded and b"file:///repo" in encoded
+    assert encoded.count(b"Square#area(+1).") == 2
+
+
+def test_lsif_dump():
+    """LSIF result sets carry the definitions, references, hovers and implementations of each symbol."""
+    lines = {"/repo/src/lib.rs": [
+        "/// Größe of a shape.",
+        "pub trait Size { fn size(&self) -> u32; }",
+        "struct Box; impl Size for Box { fn size(&self) -> u32 { 1 } }",
+        "fn größe(b: &Box) -> u32 { let n = b.size(); n }",
+    ]}
+    documents = [{
+        "relative_path": "src/lib.rs", "language": "Rust",
+        "occurrences": [
+            {"range": [1, 10, 1, 14], "symbol": "cgc . r . Size#", "symbol_roles": 1},
+            {"range": [2, 7, 2, 10], "symbol": "cgc . r . Box#", "symbol_roles": 1},
+            {"range": [2, 26, 2, 29], "symbol": "cgc . r . Box#", "symbol_roles": 0},
+            {"range": [3, 3, 3, 10], "symbol": "cgc . r . größe().", "symbol_roles": 1},
+            {"range": [3, 15, 3, 18], "symbol": "cgc . r . Box#", "symbol_roles": 0},
+            {"range": [3, 33, 3, 34], "symbol": "local 0", "symbol_roles": 1},
+        ],
+        "symbols": [
+            {"symbol": "cgc . r . Size#", "documentation": ["```rust\npub trait Size\n```", "Größe of a shape."]},
+            {"symbol": "cgc . r . Box#", "documentation": [],
+             "relationships": [{"symbol": "cgc . r . Size#", "is_implementation": True}]},
+            {"symbol": "cgc . r . größe().", "documentation": []},
+            {"symbol": "local 0", "documentation": []},
+        ],
+    }]
+    records = [json.loads(line) for line in lsif.dump_lines(documents, "/repo", "1.0", read_lines=lines.get)]
+    by_id = {record["id"]: record for record in records}
+    for record in records:
+        if record["type"] == "edge":
+            for target in [record["outV"], record.get("inV")] + record.get("inVs", []):
+                assert target is None or target < record["id"], "vertices come before the edges using them"
+    assert records[0]["label"] == "metaData" and records[0]["positionEncoding"] == "utf-16"
+    [document] = [r for r in records if r["label"] == "document"]
+    assert document["uri"] == "file:///repo/src/lib.rs" and document["languageId"] == "rust"
+    ranges = {(r["start"]["line"], r["start"]["character"]): r["id"] for r in records if r["label"] == "range"}
+    # `größe` is 7 bytes but 5 UTF-16 code units: what follows it on the line moves back by 2.
+    assert by_id[ranges[(3, 3)]]["end"] == {"line": 3, "character": 8}
+    assert (3, 31) in ranges
+
+    def result_of(range_key, method):
+        [result_set] = [e["inV"] for e in records if e["label"] == "next" and e["outV"] == ranges[range_key]]
+        found = [e["inV"] for e in records if e["label"] == method and e["outV"] == result_set]
+        return found[0] if found else None
+
+    def item_ranges(result, prop=None):
+        return sorted(v for e in records if e["label"] == "item" and e["outV"] == result and e.get("property") == prop
+                      for v in e["inVs"])
+
+    box_references = result_of((2, 26), "textDocument/references")
+    assert item_ranges(box_references, "definitions") == [ranges[(2, 7)]]
+    assert item_ranges(box_references, "references") == sorted([ranges[(2, 26)], ranges[(3, 13)]])
+    assert item_ranges(result_of((3, 13), "textDocument/definition")) == [ranges[(2, 7)]]
+    hover = by_id[result_of((1, 10), "textDocument/hover")]
+    assert hover["result"]["contents"]["value"] == "```rust\npub trait Size\n```\n\n---\n\nGröße of a shape."
+    assert result_of((2, 7), "textDocument/hover") is None
+    assert item_ranges(result_of((1, 10), "textDocument/implementation")) == [ranges[(2, 7)]]
+    assert lsif.utf16_column("a😀b", 5) == 3